@@ -0,0 +1,193 @@
+//! The agenda `agenda` prints or emails: what is overdue and what is due
+//! today, or with `--week` the rest of the week day by day, then what is due
+//! later and what has no due date, for triage.
+//!
+//! Meant to be run from cron, e.g. `0 7 * * * task agenda --email`. The
+//! recipient is read from `[agenda]` in the config file, and the mail goes
+//! out through the server under `[smtp]`, or the local `sendmail` if none is
+//! set:
+//!
+//! ```toml
+//! [agenda]
+//! to = "me@example.com"
+//!
+//! [smtp]
+//! host = "smtp.example.com"
+//! port = 587
+//! username = "me@example.com"
+//! ```
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::app::{Config, Task};
+
+/// Settings for `agenda --email`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgendaConfig {
+    /// Who the agenda is emailed to.
+    pub to: Option<String>,
+}
+
+/// How far ahead `agenda` looks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AgendaSpan {
+    /// What is overdue and due today.
+    #[default]
+    Day,
+    /// The rest of the week day by day, then everything due later or not at
+    /// all.
+    Week,
+}
+
+/// A section of the agenda, in the order they are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    /// A later day of the current week, which ends on Sunday.
+    Day(NaiveDate),
+    Later,
+    NoDate,
+}
+
+impl AgendaGroup {
+    fn heading(self, config: &Config) -> String {
+        match self {
+            AgendaGroup::Overdue => "Overdue".to_string(),
+            AgendaGroup::Today => "Due today".to_string(),
+            AgendaGroup::Tomorrow => "Tomorrow".to_string(),
+            AgendaGroup::Day(date) => format!("{} {}", date.format("%A"), config.format_date(date)),
+            AgendaGroup::Later => "Later".to_string(),
+            AgendaGroup::NoDate => "No due date".to_string(),
+        }
+    }
+}
+
+/// The pending tasks of the agenda, grouped by when they are due.
+#[derive(Debug)]
+pub struct Agenda<'a> {
+    pub date: NaiveDate,
+    pub span: AgendaSpan,
+    /// The groups with tasks in them, in order; tasks in the order they fall
+    /// due.
+    pub sections: Vec<(AgendaGroup, Vec<&'a Task>)>,
+}
+
+impl<'a> Agenda<'a> {
+    /// Collects the agenda of `tasks` at `now` over `span`. Tasks waiting for
+    /// their start date are left out.
+    pub fn build(tasks: &'a [Task], now: NaiveDateTime, span: AgendaSpan) -> Self {
+        let date = now.date();
+        let tomorrow = date + Duration::days(1);
+        let sunday = date + Duration::days(6 - i64::from(date.weekday().num_days_from_monday()));
+        let mut groups: BTreeMap<AgendaGroup, Vec<&Task>> = BTreeMap::new();
+        for task in tasks
+            .iter()
+            .filter(|t| t.is_pending() && !t.is_waiting(date))
+        {
+            let group = match task.due_date {
+                _ if task.is_overdue(now) => AgendaGroup::Overdue,
+                Some(due) if due == date => AgendaGroup::Today,
+                Some(due) if due == tomorrow => AgendaGroup::Tomorrow,
+                Some(due) if due <= sunday => AgendaGroup::Day(due),
+                Some(_) => AgendaGroup::Later,
+                None => AgendaGroup::NoDate,
+            };
+            if span == AgendaSpan::Week
+                || matches!(group, AgendaGroup::Overdue | AgendaGroup::Today)
+            {
+                groups.entry(group).or_default().push(task);
+            }
+        }
+        for tasks in groups.values_mut() {
+            tasks.sort_by_key(|t| (t.deadline(), t.id));
+        }
+        Agenda {
+            date,
+            span,
+            sections: groups.into_iter().collect(),
+        }
+    }
+
+    /// Returns `true` if the agenda lists no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    fn count(&self, wanted: impl Fn(AgendaGroup) -> bool) -> usize {
+        self.sections
+            .iter()
+            .filter(|(group, _)| wanted(*group))
+            .map(|(_, tasks)| tasks.len())
+            .sum()
+    }
+
+    /// Returns a one-line summary, used as the email subject.
+    pub fn subject(&self, config: &Config) -> String {
+        let overdue = self.count(|g| g == AgendaGroup::Overdue);
+        let due_today = self.count(|g| g == AgendaGroup::Today);
+        match self.span {
+            AgendaSpan::Day => format!(
+                "Agenda for {} {}: {} overdue, {} due today",
+                self.date.format("%a"),
+                config.format_date(self.date),
+                overdue,
+                due_today
+            ),
+            AgendaSpan::Week => format!(
+                "Agenda for the week of {} {}: {} overdue, {} due today, {} later this week",
+                self.date.format("%a"),
+                config.format_date(self.date),
+                overdue,
+                due_today,
+                self.count(|g| matches!(g, AgendaGroup::Tomorrow | AgendaGroup::Day(_)))
+            ),
+        }
+    }
+
+    /// Renders the agenda as plain text, a section per group of tasks.
+    pub fn body(&self, config: &Config) -> String {
+        let mut body = String::new();
+        for (group, tasks) in &self.sections {
+            if !body.is_empty() {
+                body.push('\n');
+            }
+            let _ = writeln!(body, "{} ({}):", group.heading(config), tasks.len());
+            for task in tasks {
+                let when = match group {
+                    AgendaGroup::Overdue | AgendaGroup::Later => {
+                        config.format_due(task).unwrap_or_default()
+                    }
+                    AgendaGroup::Today => task
+                        .due_time
+                        .map_or_else(|| "today".to_string(), |t| t.format("%H:%M").to_string()),
+                    _ => task
+                        .due_time
+                        .map(|t| t.format("%H:%M").to_string())
+                        .unwrap_or_default(),
+                };
+                let _ = write!(body, "  [{}] ", task.id);
+                if !when.is_empty() {
+                    let _ = write!(body, "{}  ", when);
+                }
+                body.push_str(&task.description);
+                let details: Vec<String> = task
+                    .priority
+                    .map(|p| p.to_string())
+                    .into_iter()
+                    .chain(task.project.as_ref().map(|p| format!("project {}", p)))
+                    .collect();
+                if !details.is_empty() {
+                    let _ = write!(body, " ({})", details.join(", "));
+                }
+                body.push('\n');
+            }
+        }
+        body
+    }
+}
@@ -0,0 +1,123 @@
+//! Shorthands for commands, defined under `[aliases]` in the config file.
+//!
+//! ```toml
+//! [aliases]
+//! t = "list due:today"
+//! wip = "list tag:wip --sort priority"
+//! ```
+//!
+//! `task wip --long` then runs `task list tag:wip --sort priority --long`:
+//! the alias is replaced by its words before the command line is parsed, and
+//! the words given after it follow. Quotes group words, as in
+//! `"list 'project:acme and tag:bug'"`. An alias can expand to another alias,
+//! but never to itself, and can't take the name of a command.
+
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+
+use crate::app::build_cli;
+
+/// Splits `s` into words at whitespace, keeping what is quoted with `"` or
+/// `'` together.
+pub fn split_words(s: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_default().push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_default().push(c),
+        }
+    }
+    if let Some(q) = quote {
+        return Err(format!("unclosed {} in '{}'", q, s));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Checks that every alias expands to some words and none is named like a
+/// command.
+pub fn validate_aliases(aliases: &BTreeMap<String, String>) -> Result<(), String> {
+    let cli = build_cli();
+    for (name, expansion) in aliases {
+        if cli
+            .get_subcommands()
+            .any(|c| c.get_name() == name || c.get_all_aliases().any(|a| a == name))
+        {
+            return Err(format!(
+                "the alias '{}' has the name of a command, which it would hide",
+                name
+            ));
+        }
+        let words = split_words(expansion).map_err(|e| format!("alias '{}': {}", name, e))?;
+        if words.is_empty() {
+            return Err(format!("the alias '{}' expands to nothing", name));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the alias in the command position of `args`, the whole command
+/// line including the program name, by its words.
+///
+/// # Returns
+///
+/// An error if an alias comes back to itself.
+pub fn expand_aliases(
+    mut args: Vec<OsString>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<OsString>, String> {
+    let Some(position) = command_position(&args) else {
+        return Ok(args);
+    };
+    let mut expanded = HashSet::new();
+    while let Some(name) = args[position].to_str().map(str::to_string)
+        && let Some(expansion) = aliases.get(&name)
+    {
+        if !expanded.insert(name.clone()) {
+            return Err(format!("the alias '{}' expands to itself", name));
+        }
+        let words = split_words(expansion).map_err(|e| format!("alias '{}': {}", name, e))?;
+        args.splice(position..=position, words.into_iter().map(OsString::from));
+    }
+    Ok(args)
+}
+
+/// Returns where the command is in `args`: the first word that isn't a
+/// global option or the value of one.
+fn command_position(args: &[OsString]) -> Option<usize> {
+    let cli = build_cli();
+    let takes_value = |arg: &clap::Arg| arg.get_action().takes_values();
+    let mut position = 1;
+    while let Some(word) = args.get(position).and_then(|a| a.to_str()) {
+        if word == "--" {
+            return None;
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            let needs_value = !long.contains('=')
+                && cli
+                    .get_arguments()
+                    .any(|a| a.get_long() == Some(long) && takes_value(a));
+            position += if needs_value { 2 } else { 1 };
+        } else if let Some(shorts) = word.strip_prefix('-').filter(|s| !s.is_empty()) {
+            // A flag taking a value takes the rest of the word, or the next
+            // word if it comes last.
+            let chars: Vec<char> = shorts.chars().collect();
+            let needs_value = chars.iter().position(|&c| {
+                cli.get_arguments()
+                    .any(|a| a.get_short() == Some(c) && takes_value(a))
+            }) == Some(chars.len() - 1);
+            position += if needs_value { 2 } else { 1 };
+        } else {
+            return Some(position);
+        }
+    }
+    None
+}
@@ -0,0 +1,125 @@
+//! Async loading and saving of tasks, for embedding in async servers and
+//! bots; only available with the `async` feature.
+//!
+//! Reading a data file can mean deriving a key from a passphrase, which
+//! takes a good fraction of a second, so the work runs on tokio's blocking
+//! pool rather than on the runtime's worker threads:
+//!
+//! ```ignore
+//! let mut tasks = load_tasks_from_async("tasks.toml").await?;
+//! TaskManager::new(&mut tasks, &config).complete(3)?;
+//! save_tasks_to_async(tasks, "tasks.toml").await?;
+//! ```
+
+use std::future::Future;
+use std::path::PathBuf;
+
+use crate::app::{
+    AppError, FileStore, MemoryStore, Result, StorageFormat, Task, TaskStore, default_format,
+    encode_tasks, load_tasks_from, parse_tasks, save_tasks_to, storage,
+};
+
+/// Runs `f` on tokio's blocking pool and waits for it.
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Unexpected(format!("storage task failed: {}", e)))?
+}
+
+/// Loads tasks from the default tasks file; see [`storage::load_tasks`].
+pub async fn load_tasks_async() -> Result<Vec<Task>> {
+    blocking(storage::load_tasks).await
+}
+
+/// Loads tasks from the tasks file at `path`; see [`load_tasks_from`].
+pub async fn load_tasks_from_async(path: impl Into<PathBuf>) -> Result<Vec<Task>> {
+    let path = path.into();
+    blocking(move || load_tasks_from(&path)).await
+}
+
+/// Saves `tasks` to the default tasks file; see [`storage::save_tasks`].
+pub async fn save_tasks_async(tasks: Vec<Task>) -> Result<()> {
+    blocking(move || storage::save_tasks(&tasks)).await
+}
+
+/// Saves `tasks` to the tasks file at `path`; see [`save_tasks_to`].
+pub async fn save_tasks_to_async(tasks: Vec<Task>, path: impl Into<PathBuf>) -> Result<()> {
+    let path = path.into();
+    blocking(move || save_tasks_to(&tasks, &path)).await
+}
+
+/// A [`TaskStore`] whose reads and writes are async, such as one backed by
+/// a database or a remote service.
+pub trait AsyncTaskStore {
+    /// Returns what was stored last, or `None` if nothing was stored yet.
+    fn read(&self) -> impl Future<Output = Result<Option<Vec<u8>>>> + Send;
+
+    /// Replaces what is stored with `contents`.
+    fn write(&mut self, contents: Vec<u8>) -> impl Future<Output = Result<()>> + Send;
+
+    /// Loads the stored tasks; none if nothing was stored yet.
+    fn load(&self) -> impl Future<Output = Result<Vec<Task>>> + Send
+    where
+        Self: Sync,
+    {
+        async {
+            match self.read().await? {
+                Some(contents) => parse_tasks(&contents),
+                None => Ok(Vec::new()),
+            }
+        }
+    }
+
+    /// Stores `tasks`, in the format of what was stored before if anything
+    /// was, and in the default format otherwise.
+    fn save(&mut self, tasks: &[Task]) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Send,
+    {
+        async move {
+            let format = match self.read().await? {
+                Some(contents) => StorageFormat::of(&contents),
+                None => default_format(),
+            };
+            let contents = encode_tasks(tasks, format)?;
+            self.write(contents).await
+        }
+    }
+}
+
+impl AsyncTaskStore for MemoryStore {
+    async fn read(&self) -> Result<Option<Vec<u8>>> {
+        TaskStore::read(self)
+    }
+
+    async fn write(&mut self, contents: Vec<u8>) -> Result<()> {
+        TaskStore::write(self, &contents)
+    }
+}
+
+impl AsyncTaskStore for FileStore {
+    async fn read(&self) -> Result<Option<Vec<u8>>> {
+        let store = self.clone();
+        blocking(move || TaskStore::read(&store)).await
+    }
+
+    async fn write(&mut self, contents: Vec<u8>) -> Result<()> {
+        let mut store = self.clone();
+        blocking(move || TaskStore::write(&mut store, &contents)).await
+    }
+
+    async fn load(&self) -> Result<Vec<Task>> {
+        let store = self.clone();
+        blocking(move || TaskStore::load(&store)).await
+    }
+
+    async fn save(&mut self, tasks: &[Task]) -> Result<()> {
+        let mut store = self.clone();
+        let tasks = tasks.to_vec();
+        blocking(move || TaskStore::save(&mut store, &tasks)).await
+    }
+}
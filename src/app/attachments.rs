@@ -0,0 +1,79 @@
+//! Files and links attached to tasks, and opening them with the platform's
+//! opener.
+//!
+//! A task can also have one URL of its own, the page it is about, which
+//! `open` prefers over its attachments.
+//!
+//! `attach` stores URLs as given and file paths made absolute, so a task's
+//! attachments still open from any working directory. `open` hands them to
+//! `xdg-open`, `open` on macOS, or `start` on Windows.
+
+use log::debug;
+use std::fs;
+use std::process::Command;
+
+use crate::app::{AppError, Result};
+
+/// Returns `true` if `target` is a URL rather than a file path, e.g.
+/// `https://example.com/pr/123` or `mailto:me@example.com`.
+pub fn is_url(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:")
+}
+
+/// Checks a URL given to `--url`.
+pub fn parse_url(s: &str) -> std::result::Result<String, String> {
+    let s = s.trim();
+    if is_url(s) {
+        Ok(s.to_string())
+    } else {
+        Err(format!(
+            "Expected a URL such as https://example.com/pr/123. Got: '{}'",
+            s
+        ))
+    }
+}
+
+/// Returns what to store for `target`: a URL as is, a path made absolute.
+///
+/// # Returns
+///
+/// An `AppError::InvalidArgument` if `target` is a path to nothing.
+pub fn resolve_attachment(target: &str) -> Result<String> {
+    let target = target.trim();
+    if is_url(target) {
+        return Ok(target.to_string());
+    }
+    let path = fs::canonicalize(target)
+        .map_err(|e| AppError::InvalidArgument(format!("Can't attach {}: {}", target, e)))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Opens `target` in the application the platform associates with it.
+///
+/// # Returns
+///
+/// An `AppError::Unexpected` if the opener can't be run or fails.
+pub fn open_target(target: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let program = command.get_program().to_string_lossy().into_owned();
+    debug!("Opening {} with {}.", target, program);
+    let status = command
+        .arg(target)
+        .status()
+        .map_err(|e| AppError::Unexpected(format!("could not run {}: {}", program, e)))?;
+    if !status.success() {
+        return Err(AppError::Unexpected(format!(
+            "{} could not open {} ({})",
+            program, target, status
+        )));
+    }
+    Ok(())
+}
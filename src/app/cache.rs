@@ -0,0 +1,217 @@
+//! A small index of the data file kept beside it, so cheap commands don't
+//! parse every task.
+//!
+//! With `index_cache = true`, the index is written to `{stem}.cache` next to
+//! the data file whenever the tasks are saved or read whole. It holds the
+//! SHA-256 of the data file, the IDs in use, and the few fields of each
+//! pending task that `count` and `status` look at, with each tag's tasks
+//! listed by position. `count`, `status`, and an `add` appending to the file
+//! (see `check_duplicates`) then only hash the data file; if the hash differs
+//! from the index's, the file was changed behind the index's back and is
+//! read in full as usual.
+//!
+//! Encrypted data files get no index, since it would hold their projects and
+//! tags in the clear.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{CountSelection, Result, StatusSummary, Task, encryption};
+
+/// Returns where the index of the data file at `tasks_path` is kept.
+pub fn index_cache_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.cache", stem))
+}
+
+/// What the index keeps of a pending task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedTask {
+    due_date: Option<NaiveDate>,
+    due_time: Option<NaiveTime>,
+    start_date: Option<NaiveDate>,
+    expires_on: Option<NaiveDate>,
+    project: Option<String>,
+}
+
+impl IndexedTask {
+    /// Returns `true` if `list` shows the task at `now`: it hasn't expired,
+    /// and its start date has come.
+    fn is_listed(&self, now: NaiveDateTime) -> bool {
+        let today = now.date();
+        self.expires_on.is_none_or(|expires| expires >= today)
+            && self.start_date.is_none_or(|start| start <= today)
+    }
+
+    /// Returns `true` if the task's deadline has passed at `now`, as
+    /// [`Task::is_overdue`] decides.
+    fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        let Some(date) = self.due_date else {
+            return false;
+        };
+        match self.due_time {
+            Some(time) => date.and_time(time) <= now,
+            None => date < now.date(),
+        }
+    }
+}
+
+/// The index of a data file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexCache {
+    /// The SHA-256 of the data file the index describes.
+    hash: String,
+    /// The IDs of every task, for `add` to pick the next free one.
+    pub ids: Vec<u32>,
+    /// The pending tasks.
+    pending: Vec<IndexedTask>,
+    /// For each tag, the positions in `pending` of the tasks with it.
+    tags: BTreeMap<String, Vec<usize>>,
+}
+
+impl IndexCache {
+    /// Indexes `tasks`, read from a data file holding `contents`.
+    pub fn build(tasks: &[Task], contents: &[u8]) -> Self {
+        let mut pending = Vec::new();
+        let mut tags: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for task in tasks.iter().filter(|t| t.is_pending()) {
+            for tag in &task.tags {
+                tags.entry(tag.clone()).or_default().push(pending.len());
+            }
+            pending.push(IndexedTask {
+                due_date: task.due_date,
+                due_time: task.due_time,
+                start_date: task.start_date,
+                expires_on: task.expires_on,
+                project: task.project.clone(),
+            });
+        }
+        IndexCache {
+            hash: file_hash(contents),
+            ids: tasks.iter().map(|t| t.id).collect(),
+            pending,
+            tags,
+        }
+    }
+
+    /// Loads the index of the data file at `tasks_path`, which holds
+    /// `contents`; `None` if there is none or it describes other contents.
+    pub fn load(tasks_path: &Path, contents: &[u8]) -> Option<Self> {
+        let path = index_cache_path(tasks_path);
+        let data = fs::read(&path).ok()?;
+        let index: IndexCache = match rmp_serde::from_slice(&data) {
+            Ok(index) => index,
+            Err(e) => {
+                debug!("Ignoring the unreadable index {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        if index.hash != file_hash(contents) {
+            debug!("The index {} is out of date.", path.display());
+            return None;
+        }
+        Some(index)
+    }
+
+    /// Records `added`, appended to the data file, which now holds
+    /// `contents`.
+    pub fn record_added(&mut self, added: &[Task], contents: &[u8]) {
+        let mut index = IndexCache::build(added, contents);
+        let offset = self.pending.len();
+        for (tag, positions) in index.tags {
+            self.tags
+                .entry(tag)
+                .or_default()
+                .extend(positions.into_iter().map(|i| i + offset));
+        }
+        self.ids.append(&mut index.ids);
+        self.pending.append(&mut index.pending);
+        self.hash = index.hash;
+    }
+
+    /// Writes the index next to the data file at `tasks_path`.
+    pub fn save(&self, tasks_path: &Path) -> Result<()> {
+        fs::write(index_cache_path(tasks_path), rmp_serde::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Counts the overdue, due, and pending tasks as of `now`, as
+    /// `TaskManager::status` does.
+    pub fn status(&self, now: NaiveDateTime) -> StatusSummary {
+        let listed = || self.pending.iter().filter(move |t| t.is_listed(now));
+        StatusSummary {
+            overdue: listed().filter(|t| t.is_overdue(now)).count(),
+            due_today: listed()
+                .filter(|t| t.due_date == Some(now.date()) && !t.is_overdue(now))
+                .count(),
+            pending: listed().count(),
+        }
+    }
+
+    /// Counts the tasks `args` select as of `now`, as `TaskManager::count`
+    /// does; `None` if that takes more than the index holds: a query, a due
+    /// date, or tasks that aren't pending.
+    pub fn count(&self, args: &CountSelection, now: NaiveDateTime) -> Option<usize> {
+        if args.query.is_some() || args.due.is_some() || args.all {
+            return None;
+        }
+        let positions: Vec<usize> = match args.tags.split_first() {
+            None => (0..self.pending.len()).collect(),
+            Some((first, rest)) => self
+                .tags
+                .get(first)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&i| {
+                    rest.iter()
+                        .all(|tag| self.tags.get(tag).is_some_and(|p| p.contains(&i)))
+                })
+                .collect(),
+        };
+        Some(
+            positions
+                .into_iter()
+                .map(|i| &self.pending[i])
+                .filter(|t| t.is_listed(now))
+                .filter(|t| args.project.is_none() || t.project == args.project)
+                .filter(|t| !args.overdue || t.is_overdue(now))
+                .count(),
+        )
+    }
+}
+
+/// Returns the hex SHA-256 of a data file's `contents`.
+pub fn file_hash(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
+}
+
+/// Returns `true` if the data file at `tasks_path` may have an index.
+pub fn can_index(tasks_path: &Path) -> bool {
+    tasks_path.exists() && !encryption::should_encrypt(tasks_path)
+}
+
+/// Rewrites the index of the data file at `tasks_path`, which holds `tasks`.
+///
+/// The index only saves time, so failing to write it is logged rather than
+/// reported.
+pub fn refresh_index_cache(tasks: &[Task], tasks_path: &Path) {
+    if !can_index(tasks_path) {
+        return;
+    }
+    let written = fs::read(tasks_path)
+        .map_err(Into::into)
+        .and_then(|contents| IndexCache::build(tasks, &contents).save(tasks_path));
+    match written {
+        Ok(()) => debug!("Updated the index of {}.", tasks_path.display()),
+        Err(e) => error!("Could not update the index cache: {}", e),
+    }
+}
@@ -0,0 +1,91 @@
+//! Due dates laid out over a month, for `calendar`.
+//!
+//! [`MonthAgenda`] collects the pending tasks due in a month by day, in the
+//! order they fall due, and [`MonthAgenda::weeks`] lays the month out as a
+//! grid of weeks starting on Monday.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+
+use crate::app::{Priority, Task};
+
+/// Reads a month given as `YYYY-MM`, returning its first day.
+pub fn parse_month(s: &str) -> std::result::Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", s.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("Month must be YYYY-MM, e.g. 2025-06: '{}'", s))
+}
+
+/// How the tasks due on a day stand, for coloring the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DayMark {
+    Due,
+    HighPriority,
+    Overdue,
+}
+
+/// The pending tasks due in one month, by day.
+#[derive(Debug)]
+pub struct MonthAgenda<'a> {
+    /// The first day of the month.
+    pub first: NaiveDate,
+    /// The tasks due on each day that has any, in the order they fall due.
+    pub days: BTreeMap<NaiveDate, Vec<&'a Task>>,
+}
+
+impl<'a> MonthAgenda<'a> {
+    /// Collects the pending tasks of `tasks` due in the month starting on
+    /// `first`.
+    pub fn build(tasks: &'a [Task], first: NaiveDate) -> Self {
+        let mut days: BTreeMap<NaiveDate, Vec<&Task>> = BTreeMap::new();
+        for task in tasks.iter().filter(|t| t.is_pending()) {
+            if let Some(due) = task.due_date
+                && (due.year(), due.month()) == (first.year(), first.month())
+            {
+                days.entry(due).or_default().push(task);
+            }
+        }
+        for tasks in days.values_mut() {
+            tasks.sort_by_key(|t| (t.due_time.is_none(), t.due_time, t.id));
+        }
+        MonthAgenda { first, days }
+    }
+
+    /// Returns the month as weeks from Monday to Sunday, with `None` for the
+    /// days of the neighbouring months.
+    pub fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let offset = self.first.weekday().num_days_from_monday() as i64;
+        let mut day = self.first - Duration::days(offset);
+        let mut weeks = Vec::new();
+        while weeks.is_empty() || day.month() == self.first.month() {
+            let mut week = [None; 7];
+            for slot in &mut week {
+                *slot = (day.month() == self.first.month()).then_some(day);
+                day += Duration::days(1);
+            }
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    /// Returns the tasks due on `date`.
+    pub fn due_on(&self, date: NaiveDate) -> &[&'a Task] {
+        self.days.get(&date).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns what stands out among the tasks due on `date` at `now`:
+    /// overdue ones before high-priority ones.
+    pub fn mark(&self, date: NaiveDate, now: NaiveDateTime) -> Option<DayMark> {
+        self.due_on(date)
+            .iter()
+            .map(|task| {
+                if task.is_overdue(now) {
+                    DayMark::Overdue
+                } else if task.priority == Some(Priority::High) {
+                    DayMark::HighPriority
+                } else {
+                    DayMark::Due
+                }
+            })
+            .max()
+    }
+}
@@ -0,0 +1,255 @@
+//! The history `log` prints: which task was added, completed, reopened,
+//! edited, or removed, and when.
+//!
+//! The history is read from the audit journal when one is kept, and
+//! otherwise from the data file itself when it is an event log, including
+//! the events compacted into its history file. Without either, only what the
+//! tasks record about themselves is known: when each was added and
+//! completed.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::slice;
+use uuid::Uuid;
+
+use crate::app::{
+    EventRecord, Result, Task, TaskDiff, TaskEvent, TaskStatus, encryption, history_file_path,
+    is_event_log, journal_file_path, load_journal, parse_events,
+};
+
+/// What happened to a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    Added,
+    Completed,
+    Reopened,
+    Edited,
+    Removed,
+}
+
+impl ChangeKind {
+    /// Returns the lowercase name shown in the log.
+    pub fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Completed => "completed",
+            ChangeKind::Reopened => "reopened",
+            ChangeKind::Edited => "edited",
+            ChangeKind::Removed => "removed",
+        }
+    }
+}
+
+/// One thing that happened to a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeEntry {
+    pub at: DateTime<Utc>,
+    pub kind: ChangeKind,
+    /// The task's ID at the time.
+    pub id: u32,
+    pub description: String,
+    /// The fields an edit changed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    /// The command line that made the change, when the journal recorded it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Who made the change, when the list is shared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+}
+
+/// Where a [`Changelog`] was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeSource {
+    Journal,
+    EventLog,
+    /// The creation and completion times of the tasks alone.
+    Timestamps,
+}
+
+/// The history of the task list, oldest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct Changelog {
+    pub source: ChangeSource,
+    pub entries: Vec<ChangeEntry>,
+}
+
+impl Changelog {
+    /// Reads the history of the data file at `tasks_path`, which holds
+    /// `tasks`.
+    pub fn read(tasks_path: &Path, tasks: &[Task]) -> Result<Self> {
+        let journal_path = journal_file_path(tasks_path);
+        let (source, mut entries) = if journal_path.exists() {
+            (ChangeSource::Journal, from_journal(&journal_path)?)
+        } else if is_event_log(tasks_path) && tasks_path.exists() {
+            (ChangeSource::EventLog, from_event_log(tasks_path)?)
+        } else {
+            (ChangeSource::Timestamps, from_timestamps(tasks))
+        };
+        entries.sort_by_key(|e| e.at);
+        Ok(Changelog { source, entries })
+    }
+
+    /// Keeps the entries from `since` up to, but not including, `until`.
+    pub fn within(mut self, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> Self {
+        self.entries.retain(|e| {
+            since.is_none_or(|since| e.at >= since) && until.is_none_or(|until| e.at < until)
+        });
+        self
+    }
+}
+
+/// Makes an entry for `task`, attributed to whoever the task records for
+/// that kind of change.
+fn entry(at: DateTime<Utc>, kind: ChangeKind, task: &Task) -> ChangeEntry {
+    let by = match kind {
+        ChangeKind::Added => &task.created_by,
+        ChangeKind::Completed => &task.completed_by,
+        ChangeKind::Reopened | ChangeKind::Edited => &task.updated_by,
+        ChangeKind::Removed => &None,
+    };
+    ChangeEntry {
+        at,
+        kind,
+        id: task.id,
+        description: task.description.clone(),
+        fields: Vec::new(),
+        command: None,
+        by: by.clone(),
+    }
+}
+
+/// Tells completions and reopenings from other edits by the status field.
+fn edit_kind(fields: &[(String, Value, Value)]) -> Result<ChangeKind> {
+    let done = serde_json::to_value(TaskStatus::Done)?;
+    Ok(match fields.iter().find(|(name, _, _)| name == "status") {
+        Some((_, _, after)) if *after == done => ChangeKind::Completed,
+        Some((_, before, _)) if *before == done => ChangeKind::Reopened,
+        _ => ChangeKind::Edited,
+    })
+}
+
+fn from_journal(path: &Path) -> Result<Vec<ChangeEntry>> {
+    let mut entries = Vec::new();
+    for record in load_journal(path)? {
+        let command = Some(record.command.clone());
+        let by = record.user.clone();
+        let changes = record.changes;
+        for (kind, tasks) in [
+            (ChangeKind::Added, &changes.added),
+            (ChangeKind::Removed, &changes.removed),
+        ] {
+            for task in tasks {
+                entries.push(ChangeEntry {
+                    command: command.clone(),
+                    by: by.clone(),
+                    ..entry(record.at, kind, task)
+                });
+            }
+        }
+        for change in changes.changed {
+            let fields: Vec<(String, Value, Value)> = change
+                .fields
+                .into_iter()
+                .map(|f| (f.field, f.before, f.after))
+                .collect();
+            entries.push(ChangeEntry {
+                at: record.at,
+                kind: edit_kind(&fields)?,
+                id: change.id,
+                description: change.description,
+                fields: fields.into_iter().map(|(name, _, _)| name).collect(),
+                command: command.clone(),
+                by: by.clone(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn from_event_log(path: &Path) -> Result<Vec<ChangeEntry>> {
+    let mut records: Vec<EventRecord> = Vec::new();
+    let history_path = history_file_path(path);
+    if history_path.exists() {
+        records.extend(parse_events(&encryption::read_file(&history_path)?)?);
+    }
+    records.extend(parse_events(&encryption::read_file(path)?)?);
+
+    let mut tasks: HashMap<Uuid, Task> = HashMap::new();
+    let mut entries = Vec::new();
+    for record in records {
+        match record.event {
+            TaskEvent::Snapshot { tasks: snapshot } => {
+                // Tasks first seen in a snapshot, e.g. when the log was
+                // started from a plain data file, carry their own history.
+                let unseen: Vec<Task> = snapshot
+                    .iter()
+                    .filter(|t| !tasks.contains_key(&t.uuid))
+                    .cloned()
+                    .collect();
+                entries.extend(from_timestamps(&unseen));
+                tasks = snapshot.into_iter().map(|t| (t.uuid, t)).collect();
+            }
+            TaskEvent::TaskAdded { task } => {
+                entries.push(entry(record.at, ChangeKind::Added, &task));
+                tasks.insert(task.uuid, task);
+            }
+            TaskEvent::TaskCompleted { uuid, completed_at } => {
+                if let Some(task) = tasks.get_mut(&uuid) {
+                    task.status = TaskStatus::Done;
+                    task.completed_at = completed_at;
+                    entries.push(entry(record.at, ChangeKind::Completed, task));
+                }
+            }
+            TaskEvent::TaskReopened { uuid } => {
+                if let Some(task) = tasks.get_mut(&uuid) {
+                    task.status = TaskStatus::Todo;
+                    task.completed_at = None;
+                    entries.push(entry(record.at, ChangeKind::Reopened, task));
+                }
+            }
+            TaskEvent::TaskEdited { task } => {
+                let fields = match tasks.get(&task.uuid) {
+                    Some(before) => {
+                        TaskDiff::between(slice::from_ref(before), slice::from_ref(&task))?
+                            .changed
+                            .into_iter()
+                            .flat_map(|c| c.fields)
+                            .map(|f| (f.field, f.before, f.after))
+                            .collect()
+                    }
+                    None => Vec::new(),
+                };
+                entries.push(ChangeEntry {
+                    kind: edit_kind(&fields)?,
+                    fields: fields.into_iter().map(|(name, _, _)| name).collect(),
+                    ..entry(record.at, ChangeKind::Edited, &task)
+                });
+                tasks.insert(task.uuid, task);
+            }
+            TaskEvent::TaskRemoved { uuid } => {
+                if let Some(task) = tasks.remove(&uuid) {
+                    entries.push(entry(record.at, ChangeKind::Removed, &task));
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn from_timestamps(tasks: &[Task]) -> Vec<ChangeEntry> {
+    let mut entries = Vec::new();
+    for task in tasks {
+        entries.push(entry(task.created_at, ChangeKind::Added, task));
+        if let Some(at) = task.completed_at.filter(|_| task.is_done()) {
+            entries.push(entry(at, ChangeKind::Completed, task));
+        }
+    }
+    entries
+}
@@ -0,0 +1,253 @@
+//! Renders report charts to image files.
+//!
+//! Report commands describe their data as a [`Chart`] and hand it to
+//! [`render_chart`], which picks SVG or PNG output from the file extension.
+//! Drawing is done with `plotters` and only available when the crate is built
+//! with the `charts` feature; otherwise rendering fails with a clear error.
+//!
+//! Text layout needs a TrueType font: the one named by `TASK_CHART_FONT`, or
+//! the first of a few common system fonts.
+
+use std::path::Path;
+
+use crate::app::{AppError, Result};
+
+/// The size of rendered charts in pixels.
+pub const CHART_WIDTH: u32 = 800;
+pub const CHART_HEIGHT: u32 = 400;
+
+/// How the series of a chart are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    /// One line per series, for values changing over time.
+    Line,
+    /// Grouped bars per label, for comparing categories.
+    Bar,
+}
+
+/// A named sequence of values, one per chart label.
+#[derive(Debug, Clone)]
+pub struct ChartSeries {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+/// A chart ready to be rendered: labels along the x axis and one or more series.
+#[derive(Debug, Clone)]
+pub struct Chart {
+    pub title: String,
+    pub kind: ChartKind,
+    pub labels: Vec<String>,
+    pub series: Vec<ChartSeries>,
+}
+
+/// The image formats a chart can be written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    /// Determines the image format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("svg") => Ok(ChartFormat::Svg),
+            Some("png") => Ok(ChartFormat::Png),
+            _ => Err(AppError::InvalidArgument(format!(
+                "Chart output must end in .svg or .png: '{}'",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Checks that a chart can be written to `path`, so commands fail before
+/// doing any work: the extension must name a format, and the build must
+/// include chart support.
+pub fn check_chart_path(path: &Path) -> Result<()> {
+    ChartFormat::from_path(path)?;
+    if cfg!(feature = "charts") {
+        Ok(())
+    } else {
+        Err(no_chart_support())
+    }
+}
+
+fn no_chart_support() -> AppError {
+    AppError::Chart(
+        "this build does not include chart support; rebuild with `--features charts`".to_string(),
+    )
+}
+
+/// Renders `chart` into the file at `path`.
+///
+/// The format is taken from the file extension (`.svg` or `.png`).
+pub fn render_chart(chart: &Chart, path: &Path) -> Result<()> {
+    let format = ChartFormat::from_path(path)?;
+    draw::render(chart, path, format)
+}
+
+#[cfg(feature = "charts")]
+mod draw {
+    use plotters::coord::Shift;
+    use plotters::prelude::*;
+    use plotters::style::{FontStyle, register_font};
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use std::{env, fs};
+
+    use super::{CHART_HEIGHT, CHART_WIDTH, Chart, ChartFormat, ChartKind};
+    use crate::app::{AppError, Result};
+
+    /// The colors assigned to series in order.
+    const SERIES_COLORS: [RGBColor; 4] = [
+        RGBColor(41, 128, 185),
+        RGBColor(39, 174, 96),
+        RGBColor(192, 57, 43),
+        RGBColor(142, 68, 173),
+    ];
+
+    /// The environment variable naming a TrueType font used for PNG charts.
+    const FONT_ENV_VAR: &str = "TASK_CHART_FONT";
+
+    /// Fonts tried, in order, when `TASK_CHART_FONT` is not set.
+    const FALLBACK_FONTS: [&str; 4] = [
+        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+        "/usr/share/fonts/TTF/DejaVuSans.ttf",
+        "/Library/Fonts/Arial.ttf",
+        "C:\\Windows\\Fonts\\arial.ttf",
+    ];
+
+    pub(super) fn render(chart: &Chart, path: &Path, format: ChartFormat) -> Result<()> {
+        ensure_font_registered()?;
+        let size = (CHART_WIDTH, CHART_HEIGHT);
+        let outcome = match format {
+            ChartFormat::Svg => draw(SVGBackend::new(path, size).into_drawing_area(), chart),
+            ChartFormat::Png => draw(BitMapBackend::new(path, size).into_drawing_area(), chart),
+        };
+        outcome.map_err(AppError::Chart)
+    }
+
+    /// Loads a TrueType font and registers it as "sans-serif" once per process.
+    fn ensure_font_registered() -> Result<()> {
+        static REGISTERED: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+        REGISTERED
+            .get_or_init(|| {
+                let candidates: Vec<PathBuf> = match env::var_os(FONT_ENV_VAR) {
+                    Some(path) => vec![PathBuf::from(path)],
+                    None => FALLBACK_FONTS.iter().map(PathBuf::from).collect(),
+                };
+                let bytes = candidates
+                    .iter()
+                    .find_map(|p| fs::read(p).ok())
+                    .ok_or_else(|| {
+                        format!(
+                            "Charts need a TrueType font; set {} to a .ttf file",
+                            FONT_ENV_VAR
+                        )
+                    })?;
+                // Fonts must outlive every chart, so the data is kept for the process lifetime.
+                let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+                register_font("sans-serif", FontStyle::Normal, bytes)
+                    .map_err(|_| "The configured chart font could not be parsed".to_string())
+            })
+            .clone()
+            .map_err(AppError::Chart)
+    }
+
+    fn draw<DB: DrawingBackend>(
+        root: DrawingArea<DB, Shift>,
+        chart: &Chart,
+    ) -> std::result::Result<(), String> {
+        let err = |e: DrawingAreaErrorKind<DB::ErrorType>| e.to_string();
+        root.fill(&WHITE).map_err(err)?;
+
+        let max_value = chart
+            .series
+            .iter()
+            .flat_map(|s| s.values.iter().copied())
+            .fold(0.0_f64, f64::max)
+            .max(1.0)
+            * 1.1;
+        let label_count = chart.labels.len().max(1);
+
+        let mut ctx = ChartBuilder::on(&root)
+            .caption(&chart.title, ("sans-serif", 24))
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(-0.5_f64..(label_count as f64 - 0.5), 0.0_f64..max_value)
+            .map_err(err)?;
+
+        let labels = &chart.labels;
+        ctx.configure_mesh()
+            .disable_x_mesh()
+            .x_labels(label_count.min(12))
+            .x_label_formatter(&|x| {
+                let index = x.round();
+                if (x - index).abs() > f64::EPSILON || index < 0.0 {
+                    return String::new();
+                }
+                labels.get(index as usize).cloned().unwrap_or_default()
+            })
+            .draw()
+            .map_err(err)?;
+
+        let series_count = chart.series.len().max(1) as f64;
+        for (i, series) in chart.series.iter().enumerate() {
+            let color = SERIES_COLORS[i % SERIES_COLORS.len()];
+            let drawn = match chart.kind {
+                ChartKind::Line => ctx
+                    .draw_series(LineSeries::new(
+                        series
+                            .values
+                            .iter()
+                            .enumerate()
+                            .map(|(x, y)| (x as f64, *y)),
+                        color.stroke_width(2),
+                    ))
+                    .map_err(err)?,
+                ChartKind::Bar => {
+                    let width = 0.8 / series_count;
+                    let offset = -0.4 + i as f64 * width;
+                    ctx.draw_series(series.values.iter().enumerate().map(|(x, y)| {
+                        let left = x as f64 + offset;
+                        Rectangle::new([(left, 0.0), (left + width, *y)], color.filled())
+                    }))
+                    .map_err(err)?
+                }
+            };
+            drawn.label(series.name.as_str()).legend(move |(x, y)| {
+                Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled())
+            });
+        }
+
+        if chart.series.len() > 1 {
+            ctx.configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()
+                .map_err(err)?;
+        }
+
+        root.present().map_err(err)
+    }
+}
+
+#[cfg(not(feature = "charts"))]
+mod draw {
+    use std::path::Path;
+
+    use super::{Chart, ChartFormat, no_chart_support};
+    use crate::app::Result;
+
+    pub(super) fn render(_chart: &Chart, _path: &Path, _format: ChartFormat) -> Result<()> {
+        Err(no_chart_support())
+    }
+}
@@ -3,8 +3,46 @@
 //! This module specifies the application's commands, subcommands, and arguments,
 //! allowing `clap` to parse user input from the terminal.
 
-use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::app::{
+    AlertSpec, ColorChoice, CompareFormat, CountSelection, DEFAULT_KEEP_BACKUPS,
+    DEFAULT_STALE_AFTER_DAYS, DESCRIPTION_WIDTH, DoneLogFormat, DueAt, DueSpec, Estimate,
+    ExportFormat, FILTER_ENV_VAR, FORECAST_HISTORY_WEEKS, GitHubRepo, GraphFormat, ImportSource,
+    MergeStrategy, NewTask, Priority, Query, Recurrence, RenderFormat, STATS_WEEKS, SnippetFormat,
+    SnoozeSpec, SortKey, StandupFormat, StorageFormat, Task, TaskChanges, TaskFilter,
+    TaskSelection, TaskStatus, clock, parse_github_repo, parse_list_name, parse_month,
+    parse_offset, parse_task_context, parse_url,
+};
+
+/// The name of the installed binary, used when generating shell completions.
+pub const BIN_NAME: &str = "tm";
+
+/// Subcommands whose first positional argument is a task ID.
+///
+/// Shell completion scripts use this to offer existing IDs dynamically.
+pub const ID_COMMANDS: &[&str] = &[
+    "complete",
+    "edit",
+    "undone",
+    "move-to",
+    "remove",
+    "note",
+    "annotate",
+    "duplicate",
+    "attach",
+    "open",
+    "show",
+    "share",
+    "skip",
+    "snooze",
+];
 
 /// This struct uses `clap`'s `Parser` trait to automatically parse command-line arguments.
 #[derive(Parser, Debug)]
@@ -12,9 +50,104 @@ use clap::{Parser, Subcommand};
     author = "0xgsvs",
     version = "0.1.0",
     about = "A simple command-line task manager written in Rust.",
-    long_about = "Organize your tasks efficiently from the terminal. Add, list, complete, and remove tasks with ease."
+    long_about = "Organize your tasks efficiently from the terminal. Add, list, complete, and remove tasks with ease.",
+    after_long_help = "Exit status:\n  \
+        0  success\n  \
+        1  the task wasn't found\n  \
+        2  invalid arguments\n  \
+        3  the data file or a file next to it couldn't be read or written\n  \
+        4  the config file is invalid\n  \
+        5  the data file was changed elsewhere in a conflicting way\n  \
+        6  a hook, sync, notification, or other service failed\n  \
+        7  anything else\n\n\
+        Any other command runs the executable task-<command> on PATH, which\n\
+        exits with a status of its own."
 )]
 pub struct Cli {
+    /// Pin the clock to this timestamp for date calculations (for tests and demos).
+    #[arg(long, global = true, hide = true, env = "TASK_NOW", value_parser = clock::parse_timestamp)]
+    pub now: Option<DateTime<Utc>>,
+
+    /// Use this task data file instead of the configured one, or read the
+    /// one published at an `https://` URL, for commands that change nothing.
+    #[arg(long, global = true, env = "TASK_FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Work on this named list, kept in its own file in the lists directory.
+    #[arg(long, global = true, env = "TASK_LIST", conflicts_with = "file", value_parser = parse_list_name)]
+    pub list: Option<String>,
+
+    /// Use the configured data file even inside a git repository that keeps
+    /// its own list in `.tasks.toml`.
+    #[arg(long, global = true, conflicts_with = "file")]
+    pub global: bool,
+
+    /// When to color the output; overrides the config file.
+    #[arg(long, global = true, value_enum)]
+    pub color: Option<ColorChoice>,
+
+    /// Show textual markers such as `[OVERDUE]` and `[P1]` instead of
+    /// colors and Unicode symbols, for screen readers and dumb terminals.
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// How to print lists of tasks and projects; overrides the config file.
+    ///
+    /// Not `--format`, which `export` and `compare` use for their own formats.
+    #[arg(long, global = true, value_enum)]
+    pub output_format: Option<RenderFormat>,
+
+    /// The strftime-style format for displaying dates, e.g. `%d.%m.%Y`, or
+    /// `relative` for dates like "in 3 days"; overrides the config file.
+    #[arg(long, global = true)]
+    pub date_format: Option<String>,
+
+    /// Show what the command would change without writing the data or trash file.
+    ///
+    /// Commands that reach other services or write files of their own refuse
+    /// to run with it.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Only read the data file: commands that would change tasks fail
+    /// instead of saving, and nothing else is written either.
+    ///
+    /// A data file that can't be written, e.g. on a read-only mount, is
+    /// treated this way for commands that change nothing; changes still go
+    /// to the fallback file.
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Load a damaged TOML data file anyway, skipping the tasks that can't be
+    /// read. The file is backed up first, since saving drops them.
+    #[arg(long, global = true)]
+    pub lenient: bool,
+
+    /// Fail instead of warning when a command takes the list past a limit
+    /// set under [limits] in the config file, saving nothing.
+    #[arg(long, global = true)]
+    pub strict: bool,
+
+    /// Print nothing but errors and warnings, for scripts going by the exit
+    /// status.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Print how long each phase of the command took to stderr.
+    #[arg(long, global = true)]
+    pub profile: bool,
+
+    /// Log what the command does to stderr: -v for the main steps and how
+    /// long loading and saving took, -vv for details, -vvv for everything.
+    /// Without it, RUST_LOG chooses what is logged.
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Write the log to FILE instead of stderr, appending to it. Logs
+    /// details unless -v says otherwise.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -27,28 +160,201 @@ pub enum Commands {
     /// Add a new task.
     ///
     /// The task description is required. An optional due date can be specified.
-    Add {
-        /// The description of the task to add.
-        description: String,
-        /// Optional due date for the task (format: YYYY-MM-DD).
-        #[arg(short, long, value_parser = parse_due_date)]
-        due: Option<NaiveDate>,
-    },
+    /// Fields can also be written inline, as in `add "pay rent tomorrow !high
+    /// #finance @home"`, unless --literal is given.
+    /// Tasks added without a project land in the inbox for later triage.
+    Add(AddArgs),
     /// List all tasks.
     ///
     /// By default, only incomplete tasks are shown. Use the --all flag to see all tasks.
-    List {
-        /// Show all tasks, including completed ones.
+    List(ListArgs),
+    /// Print how many tasks match a query.
+    ///
+    /// Counts the tasks `list` would show with the same query and `--all`,
+    /// printing nothing but the number, so it can go into shell prompts and
+    /// status bars. Never writes the data file.
+    Count(CountArgs),
+    /// Print how many tasks are overdue, due today, and pending.
+    ///
+    /// Meant for shell prompts and status bars, so it never writes the data
+    /// file. With `--output-format` json, csv, or plain the counts are
+    /// printed for programs instead.
+    Status {
+        /// Print a single line such as `3 overdue, 7 due today, 42 pending`.
         #[arg(short, long)]
-        all: bool,
+        short: bool,
+    },
+    /// Search descriptions, notes, projects, and tags of all tasks, and
+    /// annotations with `--annotations`.
+    ///
+    /// Lists the tasks containing every word of the query, completed ones
+    /// included.
+    Search {
+        /// The words to look for.
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Show creation and completion timestamps.
+        #[arg(short, long)]
+        long: bool,
+        /// Look in the annotations of tasks too.
+        #[arg(short, long)]
+        annotations: bool,
+    },
+    /// List all projects with their pending and total task counts.
+    Projects,
+    /// List the contexts such as `@office` that pending tasks can be done
+    /// in, with how many there are in each; `list @office` shows them.
+    Contexts,
+    /// Show the named lists with their pending and total task counts.
+    Lists,
+    /// Move a task to another named list.
+    Move {
+        /// The ID of the task to move.
+        id: u32,
+        /// The list to move it to; created if it doesn't exist yet.
+        #[arg(long, value_parser = parse_list_name)]
+        to: String,
+    },
+    /// Save, list, and remove the templates `add --template` fills tasks from.
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// Define, list, and remove goals, and see how far along they are.
+    Goal {
+        #[command(subcommand)]
+        command: GoalCommands,
+    },
+    /// Define, switch, and clear the context narrowing `list` and `count`.
+    Context {
+        #[command(subcommand)]
+        command: ContextCommands,
+    },
+    /// Inspect a single project.
+    Project {
+        #[command(subcommand)]
+        command: ProjectCommands,
     },
     /// Mark a task as complete.
     ///
-    /// Requires the ID of the task to mark.
+    /// Requires the ID of the task to mark, or part of its description, e.g.
+    /// `complete milk`. If several pending tasks match, you are asked which.
     Complete {
-        /// The ID of the task to mark as complete.
+        /// The ID of the task to mark as complete, or part of its description.
+        #[arg(value_name = "ID|TEXT")]
+        task: TaskRef,
+    },
+    /// Pick a pending task in a fuzzy finder over the descriptions, then
+    /// complete, remove, or edit it.
+    ///
+    /// Without an action, prints the ID of the picked task, e.g. for
+    /// `task show $(task pick)`.
+    Pick {
+        #[command(subcommand)]
+        action: Option<PickCommands>,
+    },
+    /// Change the description, due date, project, priority, or tags of a task.
+    ///
+    /// With `--editor`, the task opens in $VISUAL or $EDITOR instead, and
+    /// what is saved there is applied once it is valid.
+    Edit {
+        /// The ID of the task to edit, or part of its description.
+        #[arg(value_name = "ID|TEXT")]
+        task: TaskRef,
+        /// Edit the task's fields, and add a note, in $VISUAL or $EDITOR.
+        #[arg(long)]
+        editor: bool,
+        #[command(flatten)]
+        changes: EditArgs,
+    },
+    /// Change every task matching a query at once.
+    ///
+    /// Lists the matching tasks first, then asks for confirmation when run
+    /// from a terminal; otherwise requires --yes.
+    Modify {
+        /// Which tasks to change, in the query language of `list`, e.g. "tag:sprint1".
+        #[arg(long, value_name = "QUERY")]
+        filter: Query,
+        /// Set a field, e.g. `due=+1w`, `project=none`, or `priority=high`;
+        /// can be repeated.
+        #[arg(long = "set", value_name = "FIELD=VALUE")]
+        set: Vec<FieldAssignment>,
+        /// Add a tag; can be repeated.
+        #[arg(long)]
+        add_tag: Vec<String>,
+        /// Remove a tag; can be repeated.
+        #[arg(long)]
+        remove_tag: Vec<String>,
+        /// Also change completed, cancelled, and waiting tasks.
+        #[arg(short, long)]
+        all: bool,
+        /// Change the tasks without prompting.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Start a timer on a task, stopping any other running timer.
+    Start {
+        /// The ID of the task to work on.
         id: u32,
     },
+    /// Stop the running timer.
+    Stop,
+    /// Show time tracked per task and day for a week.
+    Timesheet {
+        /// Any day of the week to show, in the same date formats as `add --due`.
+        #[arg(long, default_value = "today")]
+        week: DueSpec,
+    },
+    /// Push the due date of a task back.
+    ///
+    /// Counts how often each task was snoozed; `list --snoozed` shows the
+    /// tasks you keep putting off.
+    Snooze {
+        /// The ID of the task to snooze.
+        id: u32,
+        /// How long to snooze, e.g. `3d` or `2w`, counted from the due date
+        /// or today if that is later; or the new due date, e.g. `2025-06-01`
+        /// or `next monday`.
+        until: SnoozeSpec,
+    },
+    /// Record that a task is blocked on something outside the list, such as
+    /// a reply, and when to follow up on it.
+    ///
+    /// With --until the task stays out of the default list until then;
+    /// `followups` shows everything waited on meanwhile.
+    Wait {
+        /// The ID of the waiting task.
+        id: u32,
+        /// What the task waits on, e.g. "reply from legal".
+        #[arg(long = "for", value_name = "WHAT", required_unless_present = "clear")]
+        waiting_on: Option<String>,
+        /// When to follow up, in the same formats as `add --due`.
+        #[arg(long, conflicts_with = "clear")]
+        until: Option<DueSpec>,
+        /// Stop waiting, showing the task again right away.
+        #[arg(long, conflicts_with = "waiting_on")]
+        clear: bool,
+    },
+    /// List the pending tasks waiting on something, soonest follow-up first.
+    Followups,
+    /// Skip the current occurrence of a recurring task.
+    ///
+    /// Moves the due date to the next occurrence without marking anything as done.
+    Skip {
+        /// The ID of the recurring task.
+        id: u32,
+    },
+    /// Record how far along a task is, e.g. `progress 3 60` for 60%.
+    ///
+    /// Tasks with subtasks get their progress from them instead: the share
+    /// of their subtasks that are done.
+    Progress {
+        /// The ID of the task.
+        id: u32,
+        /// How far along it is, from 0 to 100.
+        #[arg(value_parser = clap::value_parser!(u8).range(..=100))]
+        percent: u8,
+    },
     /// Mark a task as incomplete.
     ///
     /// Requires the ID of the task to mark.
@@ -56,28 +362,1382 @@ pub enum Commands {
         /// The ID of the task to mark as incomplete.
         id: u32,
     },
-    /// Remove a task.
+    /// Move a task to another column of the board, e.g. `move-to 3 in-progress`.
+    ///
+    /// Moving it to `done` completes it, as `complete` does.
+    MoveTo {
+        /// The ID of the task to move.
+        id: u32,
+        /// The status to give it.
+        #[arg(value_enum)]
+        status: TaskStatus,
+    },
+    /// Suggest the tasks to work on now, ranked by urgency.
+    ///
+    /// Urgency weighs due dates, priority, age, and status; the weights are
+    /// set under [urgency] in the config.
+    Next {
+        /// How many tasks to suggest.
+        #[arg(short = 'n', long, default_value_t = 3)]
+        limit: usize,
+    },
+    /// Pick a task to work on at random, for when nothing stands out.
+    ///
+    /// More urgent tasks are likelier to come up. Blocked tasks, tasks
+    /// waiting on something, and tasks that haven't started are left out.
+    Random {
+        /// Only pick from tasks with this tag.
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only pick from tasks that can be done in this context, e.g.
+        /// `@phone`.
+        #[arg(long, value_parser = parse_task_context)]
+        context: Option<String>,
+        /// Only pick from tasks estimated to take at most this long, e.g.
+        /// `30m`; tasks without an estimate are left out.
+        #[arg(long, value_name = "ESTIMATE")]
+        within: Option<Estimate>,
+    },
+    /// Show pending tasks grouped by status, one column after another.
+    Board {
+        /// Also show the done and cancelled columns.
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Remove a task, moving it to the trash.
     ///
-    /// Requires the ID of the task to remove.
+    /// Requires the ID of the task to remove. Asks for confirmation when run
+    /// from a terminal.
     Remove {
-        /// The ID of the task to remove.
+        /// The ID of the task to remove, or part of its description.
+        #[arg(value_name = "ID|TEXT")]
+        task: TaskRef,
+        /// Skip the confirmation prompt.
+        #[arg(short, long, visible_alias = "force")]
+        yes: bool,
+    },
+    /// Append a note to a task.
+    ///
+    /// Notes are timestamped and can span multiple lines.
+    Note {
+        /// The ID of the task to annotate.
+        id: u32,
+        /// The text of the note.
+        text: String,
+    },
+    /// Record a timestamped entry in the history of a task.
+    ///
+    /// Annotations log what happened with a task, like "called vendor", and
+    /// are shown in order by `show`.
+    Annotate {
+        /// The ID of the task to annotate.
+        id: u32,
+        /// What happened.
+        text: String,
+    },
+    /// Copy a task's description, tags, project, and priority into new tasks.
+    Duplicate {
+        /// The ID of the task to copy.
+        id: u32,
+        /// When the copies are due, in the same formats as `add --due`;
+        /// defaults to the original's due date.
+        #[arg(short, long)]
+        due: Option<DueAt>,
+        /// How many copies to make.
+        #[arg(short = 'n', long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+    },
+    /// Fold a task into another and remove it, e.g. to clean up duplicates.
+    ///
+    /// The remaining task gets the tags, notes, annotations, attachments,
+    /// and tracked time of both, the earlier due date, and the higher
+    /// priority; fields only the merged task had set carry over, and its
+    /// subtasks move along.
+    Merge {
+        /// The ID of the task to fold in and remove.
+        source: u32,
+        /// The ID of the task to keep.
+        target: u32,
+    },
+    /// Attach a file or link to a task.
+    ///
+    /// Paths are stored made absolute; `show` lists what a task has attached.
+    Attach {
+        /// The ID of the task.
         id: u32,
+        /// The file path or URL to attach.
+        target: String,
+    },
+    /// Keep a checklist of small steps on a task, lighter than subtasks.
+    Check {
+        #[command(subcommand)]
+        command: CheckCommands,
+    },
+    /// Open a task's URL, or else its first attachment, in the application
+    /// the system picks for it.
+    Open {
+        /// The ID of the task.
+        id: u32,
+        /// Open this attachment instead, as numbered by `show`.
+        number: Option<usize>,
+    },
+    /// Copy a task's description, or its URL, to the system clipboard.
+    Yank {
+        /// The ID of the task.
+        id: u32,
+        /// Copy the task's URL instead of its description.
+        #[arg(long)]
+        url: bool,
+    },
+    /// Show every detail of a single task, including its notes.
+    Show {
+        /// The ID of the task to show.
+        id: u32,
+        /// Print the task as JSON for scripting.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a task as a short block to paste into a chat or an email:
+    /// its description, due date, notes, and link.
+    Share {
+        /// The ID of the task to share.
+        id: u32,
+        /// How to format the block.
+        #[arg(long, value_enum, default_value_t = SnippetFormat::Markdown)]
+        format: SnippetFormat,
+    },
+    /// Add a task from a block printed by `share`, read from stdin, e.g. on
+    /// another machine.
+    Paste {
+        /// Refuse to add the task if a pending one reads the same.
+        #[arg(long)]
+        no_duplicates: bool,
+    },
+    /// Show a productivity report: counts, completion throughput, and breakdowns.
+    Stats {
+        /// Print the report as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Also render daily completions as a chart (`.svg` or `.png`); needs
+        /// a build with the `charts` feature.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Also show completion streaks and points earned, weighted by
+        /// priority.
+        #[arg(long)]
+        fun: bool,
+    },
+    /// List what happened to tasks in a time window: which were added,
+    /// completed, reopened, edited, or removed.
+    ///
+    /// Read from the audit journal when one is kept, or else from the data
+    /// file when it is an event log (`.jsonl`); otherwise only when tasks
+    /// were added and completed is known.
+    Log {
+        /// The first day to show, in the same date formats as `add --due`.
+        #[arg(long)]
+        since: Option<DueSpec>,
+        /// The last day to show.
+        #[arg(long)]
+        until: Option<DueSpec>,
+        /// Print the entries as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a month with the number of tasks due on each day.
+    ///
+    /// Days with overdue tasks are red, days with high-priority tasks yellow.
+    Calendar {
+        /// The month to show as YYYY-MM; defaults to the current one.
+        #[arg(long, value_parser = parse_month)]
+        month: Option<NaiveDate>,
+        /// List each day's tasks in the order they fall due instead.
+        #[arg(long)]
+        agenda: bool,
+    },
+    /// Show how tasks break down into subtasks, as a tree or as Graphviz
+    /// DOT to render with e.g. `dot -Tsvg`.
+    Graph {
+        /// How to print the graph.
+        #[arg(long, value_enum, default_value_t = GraphFormat::Tree)]
+        format: GraphFormat,
+        /// Only show the tasks of this project.
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Include completed and cancelled tasks.
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show today's agenda: the tasks overdue and due today; with `--week`,
+    /// also the rest of the week day by day, then what is due later and what
+    /// has no due date.
+    ///
+    /// Meant for cron: `--email` sends it to `to` under `[agenda]` in the
+    /// config file, through the `[smtp]` server or the local `sendmail`.
+    Agenda {
+        /// Group every pending task: overdue, today, tomorrow, each later
+        /// day of this week, later, and no due date.
+        #[arg(long, conflicts_with = "day")]
+        week: bool,
+        /// Show only what is overdue and due today, the default.
+        #[arg(long)]
+        day: bool,
+        /// Email the agenda instead of printing it; nothing is sent if
+        /// nothing is due.
+        #[arg(long, conflicts_with = "stdout")]
+        email: bool,
+        /// Who to email it to, instead of `to` under `[agenda]`.
+        #[arg(long, requires = "email")]
+        to: Option<String>,
+        /// Print only the agenda's text, and nothing if nothing is due, for
+        /// piping to `mail`.
+        #[arg(long)]
+        stdout: bool,
+    },
+    /// Print a standup report: what was completed on the last working day,
+    /// what is in progress or due today, and what is blocked.
+    Standup {
+        /// How to format the report.
+        #[arg(long, value_enum, default_value_t = StandupFormat::Text)]
+        format: StandupFormat,
+    },
+    /// List the tasks completed on a day with the time each was completed,
+    /// e.g. for a work log or an invoice.
+    Done {
+        /// The day, in the same date formats as `add --due`.
+        #[arg(long, default_value = "today")]
+        on: DueSpec,
+        /// How to format the list.
+        #[arg(long, value_enum, default_value_t = DoneLogFormat::Text)]
+        format: DoneLogFormat,
+    },
+    /// Show the tasks due on a day with their estimates, warning when they
+    /// add up to more than `capacity_hours` under `[plan]` in the config
+    /// file.
+    Plan {
+        /// The day to plan, in the same date formats as `add --due`.
+        #[arg(long, default_value = "today")]
+        date: DueSpec,
+    },
+    /// Show open against completed tasks week by week, to see whether the
+    /// backlog is shrinking.
+    Burndown {
+        /// How many weeks to show, ending with this one.
+        #[arg(long, default_value_t = STATS_WEEKS as u32)]
+        weeks: u32,
+        /// Print the weeks as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Also render the weeks as a chart (`.svg` or `.png`); needs a build
+        /// with the `charts` feature.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Project the open tasks over the coming weeks, at the pace tasks were
+    /// added and completed lately, and when the backlog will be cleared.
+    Forecast {
+        /// How many past weeks, ending with this one, set the pace.
+        #[arg(long, default_value_t = FORECAST_HISTORY_WEEKS)]
+        weeks: u32,
+        /// How many weeks to project, starting with this one.
+        #[arg(long, default_value_t = STATS_WEEKS as u32)]
+        ahead: u32,
+        /// Print the forecast as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Also render the projection as a chart (`.svg` or `.png`); needs a
+        /// build with the `charts` feature.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Keep a Markdown TODO file and the task store in sync.
+    ///
+    /// New checkbox lines become tasks and checking a box completes its task;
+    /// changes made with other commands are written back to the file.
+    WatchFile {
+        /// The TODO file; created if it doesn't exist.
+        path: PathBuf,
+        /// Sync once and exit instead of watching.
+        #[arg(long)]
+        once: bool,
+        /// Seconds between checks for changes.
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
     },
-    /// Remove all tasks.
+    /// Send notifications for tasks due today, overdue, or within their
+    /// reminder offset: desktop notifications, or whatever `[[reminders]]` in
+    /// the config file lists, e.g. ntfy or Gotify pushes.
+    ///
+    /// Meant to be run periodically from cron or a systemd timer; each task is
+    /// announced at most once a day. With `--dry-run`, the reminders are
+    /// listed without showing notifications.
+    ///
+    /// Given a task and `--at`, sets reminders for it instead, e.g.
+    /// `remind 7 --at "2025-06-01 09:00" --at "1h before due"`.
+    #[command(group = ArgGroup::new("alert").args(["at", "clear"]))]
+    Remind {
+        /// The task to set reminders for.
+        #[arg(requires = "alert")]
+        id: Option<u32>,
+        /// When to remind about the task: a date and time in the formats of
+        /// `add --due`, or an offset before the due date such as
+        /// `1h before due`. Repeat for several reminders.
+        #[arg(long = "at", value_name = "WHEN", requires = "id")]
+        at: Vec<AlertSpec>,
+        /// Remove the reminders set for the task.
+        #[arg(long, requires = "id", conflicts_with = "at")]
+        clear: bool,
+    },
+    /// List the reminders still to come, soonest first.
+    Reminders,
+    /// Stay running and send reminders as tasks become due, without cron.
+    ///
+    /// Reloads the data file whenever it changes. Stop with Ctrl-C or SIGTERM.
+    Daemon {
+        /// Seconds between checks of the data file for changes.
+        #[arg(long, default_value_t = 5)]
+        poll: u64,
+    },
+    /// Send escalation notifications for overdue tasks, as configured in `config.toml`.
+    ///
+    /// Meant to be run periodically, e.g. from cron. With `--dry-run`, only
+    /// shows which escalations would fire.
+    Escalate,
+    /// Reassign sequential IDs to all tasks, keeping their order.
+    ///
+    /// Prints the old to new ID mapping.
+    Renumber,
+    /// Triage the inbox.
+    ///
+    /// Walks through every pending task without a project, prompting for a project,
+    /// due date, and priority (or deletion), moving organized tasks out of the inbox.
+    /// Edits made before a crash or Ctrl-C are applied the next time the tasks load.
+    Triage,
+    /// Go through overdue, stale, and undated tasks, as in a weekly review.
     ///
-    /// Requires confirmation to prevent accidental data loss.
+    /// Asks for each whether to complete, reschedule, delete, or keep it.
+    /// Edits made before a crash or Ctrl-C are applied the next time the tasks
+    /// load.
+    Review {
+        /// How many days a pending task may sit before it counts as stale.
+        #[arg(long, default_value_t = DEFAULT_STALE_AFTER_DAYS)]
+        stale_after: u32,
+    },
+    /// List the pending tasks no command has changed for a while, then
+    /// reschedule, archive (cancel), or delete them all at once or one by
+    /// one.
+    ///
+    /// Only lists them when stdin isn't a terminal.
+    Stale {
+        /// How long a task may go unchanged, e.g. `60d` or `8w`.
+        #[arg(long, default_value = "60d", value_parser = parse_offset)]
+        older_than: u32,
+    },
+    /// Export tasks to another format.
+    ///
+    /// Writes to standard output unless an output file is given. With
+    /// `--format todoist`, the tasks are created or updated in Todoist instead,
+    /// and with `--format readme-section`, the task sections marked in the
+    /// output file are updated in place.
+    Export {
+        /// The format to export to.
+        #[arg(short, long, value_enum, default_value = "toml")]
+        format: ExportFormat,
+        /// Scramble descriptions, notes, and projects so the export can be shared in bug reports.
+        #[arg(long)]
+        anonymize: bool,
+        /// Only export tasks that are not completed yet.
+        #[arg(long)]
+        only_pending: bool,
+        /// Only export tasks belonging to this project.
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Only export tasks due on or after this date (format: YYYY-MM-DD).
+        #[arg(long, value_parser = parse_due_date)]
+        from: Option<NaiveDate>,
+        /// Only export tasks due on or before this date (format: YYYY-MM-DD).
+        #[arg(long, value_parser = parse_due_date)]
+        to: Option<NaiveDate>,
+        /// Write the export to this file instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import tasks from another task manager, or from an export of this
+    /// one.
+    ///
+    /// Importing again updates the tasks imported before instead of adding
+    /// them twice.
+    Import {
+        /// Where to import from.
+        #[arg(long, visible_alias = "format", value_enum)]
+        from: ImportSource,
+        /// The file to import, for sources read from a file.
+        path: Option<PathBuf>,
+        /// Which side wins for tasks in both the list and a JSON or CSV
+        /// file, matched by UUID. [default: newest]
+        #[arg(long, value_enum, value_name = "STRATEGY")]
+        merge: Option<MergeStrategy>,
+    },
+    /// Work on GitHub issues as tasks; needs a token in `GITHUB_TOKEN` or
+    /// under `[github]` in the config file.
+    Github {
+        #[command(subcommand)]
+        command: GithubCommands,
+    },
+    /// Mirror the Jira issues assigned to you into the task list; needs
+    /// `url` and `token` under `[jira]` in the config file.
+    Jira {
+        #[command(subcommand)]
+        command: JiraCommands,
+    },
+    /// Add a task for each `TODO` and `FIXME` comment in a codebase, tagged
+    /// with the kind of comment and its `file:line`.
+    ///
+    /// Comments scanned before are recognized by their file and text and
+    /// not added again, even after they move to another line.
+    Scan {
+        /// The directory to scan; only the files git tracks when it is in a
+        /// repository. [default: the current directory]
+        path: Option<PathBuf>,
+    },
+    /// Generate a static dashboard snapshot.
+    ///
+    /// Writes `index.html`, `dashboard.json`, and `burndown.svg` into the output directory.
+    Dashboard {
+        /// The directory to write the dashboard files into.
+        #[arg(short, long, default_value = "site")]
+        out: PathBuf,
+    },
+    /// Print a shell completion script.
+    ///
+    /// For example: `tm completions bash > ~/.local/share/bash-completion/completions/tm`.
+    Completions {
+        /// The shell to generate completions for.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Serve JSON-RPC requests on standard input, one per line, for editor
+    /// plugins.
+    ///
+    /// Each request runs a command, e.g. `{"jsonrpc": "2.0", "id": 1,
+    /// "method": "list", "params": ["--all"]}`, and is answered with its
+    /// output on one line of standard output. Global options given to `rpc`
+    /// apply to every request.
+    Rpc,
+    /// Serve the task list to AI assistants as a Model Context Protocol
+    /// server on standard input and output.
+    ///
+    /// Offers tools for listing, adding, completing, and searching tasks.
+    /// Global options given to `mcp` apply to every tool call.
+    Mcp,
+    /// Print pending task IDs and descriptions, one per line.
+    ///
+    /// Used by the generated completion scripts to complete task IDs.
+    #[command(name = "__complete-ids", hide = true)]
+    CompleteIds,
+    /// Inspect, restore, or empty removed tasks.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+    /// Remove all tasks, moving them to the trash.
+    ///
+    /// Asks for confirmation when run from a terminal; otherwise requires --yes
+    /// to prevent accidental data loss.
     Clear {
-        /// Confirm removal of all tasks.
+        /// Confirm removal of all tasks without prompting.
+        #[arg(short, long, visible_alias = "force")]
+        yes: bool,
+    },
+    /// Commit the data file to git and sync it with the configured remote.
+    ///
+    /// With [sync.storage] configured, fetch the data file from S3 or WebDAV
+    /// instead, even if it looks current, and upload local changes.
+    ///
+    /// Concurrent edits from other machines are merged by UUID; a field
+    /// changed on both sides keeps the most recent edit unless
+    /// `--interactive` is given.
+    Sync {
+        /// Sync with the CalDAV task list under [sync.caldav] instead of git.
+        #[arg(long)]
+        caldav: bool,
+        /// Ask which version to keep for each field changed on both sides.
         #[arg(short, long)]
+        interactive: bool,
+    },
+    /// Compare two task files, matching tasks by UUID.
+    ///
+    /// Lists tasks added in, removed from, and changed between the first and
+    /// the second file, e.g. before restoring a backup.
+    Compare {
+        /// The file to compare from, e.g. the backup.
+        before: PathBuf,
+        /// The file to compare to.
+        after: PathBuf,
+        /// How to print the differences.
+        #[arg(long, value_enum, default_value_t = CompareFormat::Text)]
+        format: CompareFormat,
+    },
+    /// Inspect and verify the audit journal.
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+    /// Encrypt the data and trash files with the passphrase in `TASK_PASSPHRASE`.
+    ///
+    /// Later commands decrypt and re-encrypt them transparently.
+    Encrypt,
+    /// Decrypt the data and trash files back to plain files.
+    Decrypt,
+    /// Check the data file for duplicate IDs, contradictory dates, unreadable
+    /// sections, and permission problems.
+    Doctor {
+        /// Repair the problems that are safe to repair, and report what changed.
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Report the size of the data file and what has piled up beside it:
+    /// trashed tasks, the audit journal, the event log, and backups.
+    Maintenance {
+        /// Also compact in one pass: squash an event log into a snapshot,
+        /// purge expired trash, and delete old backups.
+        #[arg(long)]
+        compact: bool,
+        /// How many of the newest backups `--compact` keeps.
+        #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_KEEP_BACKUPS)]
+        keep_backups: usize,
+    },
+    /// Rewrite the data file in another storage format.
+    ///
+    /// Later saves keep the file in that format.
+    ConvertStorage {
+        /// The format to switch to; defaults to `storage_format` from the config.
+        #[arg(value_enum)]
+        format: Option<StorageFormat>,
+    },
+    /// Any other command runs the executable `task-<command>` on `PATH`.
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
+}
+
+impl Commands {
+    /// Returns `true` if `--dry-run` can hold back every change the command
+    /// makes, i.e. it changes nothing besides the data and trash files.
+    /// Only these commands run with `--read-only`, too.
+    pub fn supports_dry_run(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Sync { .. }
+                | Commands::WatchFile { .. }
+                | Commands::Daemon { .. }
+                | Commands::Encrypt
+                | Commands::Decrypt
+                | Commands::ConvertStorage { .. }
+                | Commands::Doctor { .. }
+                | Commands::Maintenance { compact: true, .. }
+                | Commands::Move { .. }
+                | Commands::Context { .. }
+                | Commands::Template { .. }
+                | Commands::Goal { .. }
+                | Commands::Github { .. }
+                | Commands::Jira { .. }
+                | Commands::External(_)
+                | Commands::Agenda { email: true, .. }
+                | Commands::Audit {
+                    command: AuditCommands::Keygen { .. }
+                }
+                | Commands::Import {
+                    from: ImportSource::Todoist,
+                    ..
+                }
+                | Commands::Export {
+                    format: ExportFormat::Todoist,
+                    ..
+                }
+        )
+    }
+}
+
+/// Subcommands of the `trash` command.
+#[derive(Subcommand, Debug)]
+pub enum TrashCommands {
+    /// List removed tasks and when they will be purged.
+    List,
+    /// Move a removed task back into the task list.
+    Restore {
+        /// The ID the task had when it was removed.
+        id: u32,
+    },
+    /// Permanently delete everything in the trash.
+    Empty {
+        /// Skip the confirmation prompt.
+        #[arg(short, long, visible_alias = "force")]
         yes: bool,
     },
 }
 
+/// Subcommands of the `audit` command.
+#[derive(Subcommand, Debug)]
+pub enum AuditCommands {
+    /// Check that no journal entry was altered, removed, or forged.
+    Verify {
+        /// The hex public key to check signatures against, overriding the config.
+        #[arg(long)]
+        public_key: Option<String>,
+    },
+    /// Print the journal.
+    Log {
+        /// Print the entries as JSON lines, as stored.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create a signing key and print its public key.
+    Keygen {
+        /// Replace an existing key file.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Subcommands of the `template` command.
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommands {
+    /// Save the project, priority, tags, and due date offset of a task as a template.
+    Save {
+        /// The name of the template; replaces any template of that name.
+        #[arg(value_parser = parse_list_name)]
+        name: String,
+        /// The ID of the task to take them from.
+        id: u32,
+    },
+    /// Show the saved templates.
+    List,
+    /// Remove a template.
+    Remove {
+        /// The name of the template.
+        name: String,
+    },
+}
+
+/// Subcommands of the `goal` command.
+#[derive(Subcommand, Debug)]
+pub enum GoalCommands {
+    /// Define a goal, e.g. `goal add "Ship v1.0" --due 2025-09-01`.
+    Add {
+        /// What the goal is.
+        title: String,
+        /// When the goal should be reached, in the same date formats as
+        /// `add --due`.
+        #[arg(long)]
+        due: Option<DueSpec>,
+    },
+    /// Show each goal and the share of its linked tasks that are done.
+    List,
+    /// Remove a goal, unlinking its tasks.
+    Remove {
+        /// The ID of the goal.
+        id: u32,
+    },
+}
+
+/// Subcommands of the `check` command.
+#[derive(Subcommand, Debug)]
+pub enum CheckCommands {
+    /// Add an item to a task's checklist, e.g. `check add 5 "buy stamps"`.
+    Add {
+        /// The ID of the task.
+        id: u32,
+        /// What the item says.
+        text: String,
+    },
+    /// Check off an item, by the number `show` lists it under.
+    Done {
+        /// The ID of the task.
+        id: u32,
+        /// The number of the item.
+        number: usize,
+    },
+    /// Uncheck an item again.
+    Undo {
+        /// The ID of the task.
+        id: u32,
+        /// The number of the item.
+        number: usize,
+    },
+    /// Take an item off the checklist; the ones after it move up.
+    Remove {
+        /// The ID of the task.
+        id: u32,
+        /// The number of the item.
+        number: usize,
+    },
+}
+
+/// Subcommands of the `github` command.
+#[derive(Subcommand, Debug)]
+pub enum GithubCommands {
+    /// Add the open issues of a repository assigned to you as tasks, with
+    /// the issue's URL, and complete the tasks whose issue was closed.
+    ///
+    /// Pulling again updates the tasks pulled before instead of adding them
+    /// twice.
+    Pull {
+        /// The repository, as `owner/name`.
+        #[arg(long, value_parser = parse_github_repo)]
+        repo: GitHubRepo,
+    },
+    /// Complete a task and close the GitHub issue its URL points at.
+    Close {
+        /// The ID of the task.
+        id: u32,
+    },
+}
+
+/// Subcommands of the `jira` command.
+#[derive(Subcommand, Debug)]
+pub enum JiraCommands {
+    /// Add the unresolved issues assigned to you as tasks, and bring the
+    /// tasks pulled before up to date with their issues.
+    ///
+    /// Each task keeps its issue's URL, which holds the issue key, so
+    /// pulling again never adds an issue twice.
+    Pull,
+}
+
+/// What `pick` does with the picked task.
+#[derive(Subcommand, Debug)]
+pub enum PickCommands {
+    /// Mark it as complete.
+    Complete,
+    /// Move it to the trash.
+    Remove {
+        /// Skip the confirmation prompt.
+        #[arg(short, long, visible_alias = "force")]
+        yes: bool,
+    },
+    /// Change it, as with `edit`.
+    Edit {
+        #[command(flatten)]
+        changes: Box<EditArgs>,
+    },
+}
+
+/// Subcommands of the `context` command.
+#[derive(Subcommand, Debug)]
+pub enum ContextCommands {
+    /// Save a query under a name, e.g. `context define work "project:acme or tag:work"`.
+    Define {
+        /// The name of the context.
+        #[arg(value_parser = parse_list_name)]
+        name: String,
+        /// The query `list` and `count` are narrowed by while it is active.
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
+    /// Narrow `list` and `count` by the named context from now on.
+    Set {
+        /// The name of the context.
+        name: String,
+    },
+    /// Stop narrowing `list` and `count` by a context.
+    None,
+    /// Show the defined contexts, marking the active one.
+    List,
+    /// Remove a context.
+    Delete {
+        /// The name of the context.
+        name: String,
+    },
+}
+
+/// Subcommands of the `project` command.
+#[derive(Subcommand, Debug)]
+pub enum ProjectCommands {
+    /// Report effort-weighted completion, remaining estimated hours, and the nearest due date.
+    Status {
+        /// The name of the project.
+        name: String,
+    },
+}
+
+/// Arguments of the `add` command.
+#[derive(Args, Debug, Clone)]
+pub struct AddArgs {
+    /// The description of the task to add.
+    #[arg(
+        required_unless_present_any = ["editor", "from_file", "from_clipboard"],
+        default_value = "",
+        hide_default_value = true
+    )]
+    pub description: String,
+    /// Optional due date: YYYY-MM-DD, DD/MM/YYYY, `today`, `tomorrow`, a weekday, or `+3d`/`+2w`/`+1m`,
+    /// optionally followed by a time such as `17:00` or `5pm`; or `+2h`/`+30min` from now.
+    #[arg(short, long)]
+    pub due: Option<DueAt>,
+    /// Optional project the task belongs to.
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// Optional priority of the task.
+    #[arg(long, value_enum)]
+    pub priority: Option<Priority>,
+    /// Tag the task; repeat to add several tags.
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+    /// Where the task can be done, e.g. `phone` or `@office`; repeat for
+    /// several contexts.
+    #[arg(long = "context", value_name = "CONTEXT", value_parser = parse_task_context)]
+    pub contexts: Vec<String>,
+    /// Optional date after which the task is cancelled automatically, in the same date formats as --due.
+    #[arg(long)]
+    pub expires: Option<DueSpec>,
+    /// Hide the task from `list` until this date, in the same date formats as --due.
+    #[arg(long)]
+    pub start: Option<DueSpec>,
+    /// Estimated effort, e.g. `45m`, `2h`, `1h30m`.
+    #[arg(long)]
+    pub estimate: Option<Estimate>,
+    /// Start reminding this long before the due date, e.g. `2d`, `1w`. Requires --due.
+    #[arg(long, requires = "due", value_parser = parse_offset)]
+    pub remind_before: Option<u32>,
+    /// Repeat the task, e.g. `daily`, `weekly`, `2w`, `every 3 days`. Requires --due.
+    #[arg(long, requires = "due")]
+    pub recur: Option<Recurrence>,
+    /// End the recurrence after this date (format: YYYY-MM-DD).
+    #[arg(long, requires = "recur", value_parser = parse_due_date)]
+    pub recur_until: Option<NaiveDate>,
+    /// End the recurrence after this many occurrences.
+    #[arg(long, requires = "recur")]
+    pub recur_count: Option<u32>,
+    /// Skip this date when scheduling occurrences; can be repeated (format: YYYY-MM-DD).
+    #[arg(long, requires = "recur", value_parser = parse_due_date)]
+    pub recur_except: Vec<NaiveDate>,
+    /// Fill in the project, priority, tags, and due date left out from this
+    /// template, saved with `template save`.
+    #[arg(long)]
+    pub template: Option<String>,
+    /// Start the task's notes with this text.
+    #[arg(long)]
+    pub note: Option<String>,
+    /// The page the task is about, opened by `open`.
+    #[arg(long, value_parser = parse_url)]
+    pub url: Option<String>,
+    /// Who the task is for, when the list is shared.
+    #[arg(long = "assign", value_name = "NAME")]
+    pub assignee: Option<String>,
+    /// Make the task a subtask of the task with this ID.
+    #[arg(long, value_name = "ID")]
+    pub parent: Option<u32>,
+    /// Link the task to the goal with this ID, from `goal list`.
+    #[arg(long, value_name = "ID")]
+    pub goal: Option<u32>,
+    /// Fill in the task in $VISUAL or $EDITOR, starting from the other
+    /// options given.
+    #[arg(long)]
+    pub editor: bool,
+    /// Add a task for every non-empty line of this file, or of standard
+    /// input for `-`. The other options apply to each of them.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["description", "editor"])]
+    pub from_file: Option<PathBuf>,
+    /// Add a task for every non-empty line on the system clipboard, like
+    /// --from-file.
+    #[arg(long, conflicts_with_all = ["description", "editor", "from_file"])]
+    pub from_clipboard: bool,
+    /// Take the description as is, without reading a due date, `!priority`,
+    /// `#project`, or `@context` out of it.
+    #[arg(long)]
+    pub literal: bool,
+    /// Fail instead of asking when a pending task already reads like this
+    /// one, e.g. "Email Bob" for "email bob!".
+    #[arg(long)]
+    pub no_duplicates: bool,
+}
+
+impl AddArgs {
+    /// Returns `true` if the tasks are read one per line, from a file or the
+    /// clipboard, rather than given on the command line.
+    pub fn from_lines(&self) -> bool {
+        self.from_file.is_some() || self.from_clipboard
+    }
+}
+
+impl From<AddArgs> for NewTask {
+    fn from(args: AddArgs) -> Self {
+        let recurrence = args.recur.map(|mut recurrence| {
+            recurrence.until = args.recur_until;
+            recurrence.count = args.recur_count;
+            recurrence.except = args.recur_except;
+            recurrence
+        });
+        NewTask {
+            description: args.description,
+            due: args.due,
+            project: args.project,
+            priority: args.priority,
+            tags: args.tags,
+            contexts: args.contexts,
+            expires: args.expires,
+            start: args.start,
+            estimate: args.estimate,
+            remind_before: args.remind_before,
+            recurrence,
+            notes: args.note.into_iter().collect(),
+            url: args.url,
+            assignee: args.assignee,
+            parent: args.parent,
+            goal: args.goal,
+        }
+    }
+}
+
+/// Arguments of the `edit` command. Fields left out keep their value.
+#[derive(Args, Debug, Clone, Default)]
+pub struct EditArgs {
+    /// The new description.
+    #[arg(long)]
+    pub description: Option<String>,
+    /// The new due date, in the same formats as `add --due`.
+    #[arg(short, long, conflicts_with = "no_due")]
+    pub due: Option<DueAt>,
+    /// Remove the due date.
+    #[arg(long)]
+    pub no_due: bool,
+    /// The new start date, in the same date formats as `add --due`.
+    #[arg(long, conflicts_with = "no_start")]
+    pub start: Option<DueSpec>,
+    /// Remove the start date, so the task shows up right away.
+    #[arg(long)]
+    pub no_start: bool,
+    /// Move the task to this project.
+    #[arg(short, long, conflicts_with = "no_project")]
+    pub project: Option<String>,
+    /// Move the task back to the inbox.
+    #[arg(long)]
+    pub no_project: bool,
+    /// The new priority.
+    #[arg(long, value_enum, conflicts_with = "no_priority")]
+    pub priority: Option<Priority>,
+    /// Remove the priority.
+    #[arg(long)]
+    pub no_priority: bool,
+    /// Add a tag; can be repeated.
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+    /// Remove a tag; can be repeated.
+    #[arg(long)]
+    pub untag: Vec<String>,
+    /// Add a context such as `@office`; can be repeated.
+    #[arg(long = "context", value_name = "CONTEXT", value_parser = parse_task_context)]
+    pub contexts: Vec<String>,
+    /// Remove a context; can be repeated.
+    #[arg(long = "remove-context", value_name = "CONTEXT", value_parser = parse_task_context)]
+    pub remove_contexts: Vec<String>,
+    /// The new URL of the page the task is about.
+    #[arg(long, conflicts_with = "no_url", value_parser = parse_url)]
+    pub url: Option<String>,
+    /// Remove the URL.
+    #[arg(long)]
+    pub no_url: bool,
+    /// Hand the task to someone else.
+    #[arg(long = "assign", value_name = "NAME", conflicts_with = "unassign")]
+    pub assignee: Option<String>,
+    /// Take the task off whoever it is assigned to.
+    #[arg(long)]
+    pub unassign: bool,
+    /// Make the task a subtask of the task with this ID.
+    #[arg(long, value_name = "ID", conflicts_with = "no_parent")]
+    pub parent: Option<u32>,
+    /// Make the task a task of its own again, not a subtask.
+    #[arg(long)]
+    pub no_parent: bool,
+    /// Link the task to the goal with this ID, from `goal list`.
+    #[arg(long, value_name = "ID", conflicts_with = "no_goal")]
+    pub goal: Option<u32>,
+    /// Unlink the task from its goal.
+    #[arg(long)]
+    pub no_goal: bool,
+}
+
+impl EditArgs {
+    /// Returns `true` if no change was asked for.
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none()
+            && self.due.is_none()
+            && !self.no_due
+            && self.start.is_none()
+            && !self.no_start
+            && self.project.is_none()
+            && !self.no_project
+            && self.priority.is_none()
+            && !self.no_priority
+            && self.tags.is_empty()
+            && self.untag.is_empty()
+            && self.contexts.is_empty()
+            && self.remove_contexts.is_empty()
+            && self.url.is_none()
+            && !self.no_url
+            && self.assignee.is_none()
+            && !self.unassign
+            && self.parent.is_none()
+            && !self.no_parent
+            && self.goal.is_none()
+            && !self.no_goal
+    }
+}
+
+impl From<EditArgs> for TaskChanges {
+    fn from(args: EditArgs) -> Self {
+        TaskChanges {
+            description: args.description,
+            due: change(args.due, args.no_due),
+            start: change(args.start, args.no_start),
+            project: change(args.project, args.no_project),
+            priority: change(args.priority, args.no_priority),
+            add_tags: args.tags,
+            remove_tags: args.untag,
+            add_contexts: args.contexts,
+            remove_contexts: args.remove_contexts,
+            url: change(args.url, args.no_url),
+            assignee: change(args.assignee, args.unassign),
+            parent: change(args.parent, args.no_parent),
+            goal: change(args.goal, args.no_goal),
+        }
+    }
+}
+
+/// Reads an option and the flag clearing it as a change to a field.
+fn change<T>(value: Option<T>, clear: bool) -> Option<Option<T>> {
+    match value {
+        Some(value) => Some(Some(value)),
+        None if clear => Some(None),
+        None => None,
+    }
+}
+
+/// A field assignment given to `modify --set`, such as `due=+1w`.
+///
+/// `due`, `start`, and `project` can be set to `none` to clear them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldAssignment {
+    Description(String),
+    Due(Option<DueAt>),
+    Start(Option<DueSpec>),
+    Project(Option<String>),
+    Priority(Priority),
+}
+
+/// A task given on the command line: by ID, or by part of its description.
+///
+/// A number is always an ID. Anything else picks the pending tasks whose
+/// description contains it, ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskRef {
+    Id(u32),
+    Description(String),
+}
+
+impl TaskRef {
+    /// Returns the pending tasks whose description contains the text, or
+    /// nothing for an ID.
+    pub fn candidates<'a>(&self, tasks: &'a [Task]) -> Vec<&'a Task> {
+        let TaskRef::Description(text) = self else {
+            return Vec::new();
+        };
+        let text = text.to_lowercase();
+        tasks
+            .iter()
+            .filter(|t| t.is_pending() && t.description.to_lowercase().contains(&text))
+            .collect()
+    }
+}
+
+impl FromStr for TaskRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Expected a task ID or part of its description".to_string());
+        }
+        Ok(s.parse()
+            .map(TaskRef::Id)
+            .unwrap_or_else(|_| TaskRef::Description(s.to_string())))
+    }
+}
+
+impl FieldAssignment {
+    /// Records the change in `args`, as if given to `edit`.
+    pub fn apply_to(self, args: &mut EditArgs) {
+        match self {
+            FieldAssignment::Description(description) => args.description = Some(description),
+            FieldAssignment::Due(due) => {
+                args.no_due = due.is_none();
+                args.due = due;
+            }
+            FieldAssignment::Start(start) => {
+                args.no_start = start.is_none();
+                args.start = start;
+            }
+            FieldAssignment::Project(project) => {
+                args.no_project = project.is_none();
+                args.project = project;
+            }
+            FieldAssignment::Priority(priority) => args.priority = Some(priority),
+        }
+    }
+}
+
+impl FromStr for FieldAssignment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected FIELD=VALUE, such as due=+1w. Got: '{}'", s))?;
+        let value = value.trim();
+        let none = value.eq_ignore_ascii_case("none");
+        match field.trim() {
+            "description" => Ok(FieldAssignment::Description(value.to_string())),
+            "due" => Ok(FieldAssignment::Due(if none {
+                None
+            } else {
+                Some(value.parse()?)
+            })),
+            "start" => Ok(FieldAssignment::Start(if none {
+                None
+            } else {
+                Some(value.parse()?)
+            })),
+            "project" => Ok(FieldAssignment::Project((!none).then(|| value.to_string()))),
+            "priority" => Priority::from_str(value, true)
+                .map(FieldAssignment::Priority)
+                .map_err(|_| format!("Unknown priority '{}'", value)),
+            other => Err(format!(
+                "Unknown field '{}'; expected description, due, start, project, or priority",
+                other
+            )),
+        }
+    }
+}
+
+/// Arguments of the `count` command. All given conditions must hold.
+#[derive(Args, Debug, Clone, Default)]
+pub struct CountArgs {
+    /// Only count tasks matching this query, e.g. "due < +1w and not tag:waiting".
+    pub query: Option<Query>,
+    /// Count completed, cancelled, and waiting tasks too.
+    #[arg(short, long)]
+    pub all: bool,
+    /// Only count overdue tasks.
+    #[arg(long)]
+    pub overdue: bool,
+    /// Only count tasks due on this date, e.g. `today` or `tomorrow`.
+    #[arg(long)]
+    pub due: Option<DueSpec>,
+    /// Only count tasks with this tag; can be repeated.
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+    /// Only count tasks belonging to this project.
+    #[arg(short, long)]
+    pub project: Option<String>,
+}
+
+impl From<&CountArgs> for CountSelection {
+    fn from(args: &CountArgs) -> Self {
+        CountSelection {
+            query: args.query.clone(),
+            all: args.all,
+            overdue: args.overdue,
+            due: args.due,
+            tags: args.tags.clone(),
+            project: args.project.clone(),
+        }
+    }
+}
+
+/// Arguments of the `list` command.
+#[derive(Args, Debug, Default)]
+pub struct ListArgs {
+    /// Only show tasks matching this query, e.g.
+    /// "due < 2025-06-01 and priority >= high and not tag:waiting".
+    ///
+    /// Conditions can be combined with `and`, `or`, `not`, and parentheses;
+    /// fields are compared with `<`, `<=`, `>`, `>=`, `=`, and `!=`.
+    pub query: Option<Query>,
+    /// Show all tasks, including completed, cancelled, and waiting ones.
+    #[arg(short, long, overrides_with = "no_all")]
+    pub all: bool,
+    /// Only show pending tasks, even if the config file sets `all`.
+    #[arg(long, overrides_with = "all")]
+    pub no_all: bool,
+    /// Only show pending tasks whose start date hasn't come yet.
+    #[arg(short, long, conflicts_with = "all")]
+    pub waiting: bool,
+    /// Show additional columns such as creation and completion times.
+    #[arg(short, long, overrides_with = "short")]
+    pub long: bool,
+    /// Use the usual columns, even if the config file sets `long`.
+    #[arg(long, overrides_with = "long")]
+    pub short: bool,
+    /// Only show tasks belonging to this project.
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// Only show tasks assigned to this person.
+    #[arg(long, value_name = "NAME")]
+    pub assignee: Option<String>,
+    /// Only show tasks assigned to you, as named by `identity` in the config
+    /// file.
+    #[arg(long, conflicts_with = "assignee")]
+    pub mine: bool,
+    /// Group the output by the given field.
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+    /// Only show tasks matching this filter, e.g. `project:work +urgent`.
+    ///
+    /// Defaults to `TASK_FILTER`, so a filter can be set for a whole shell session.
+    #[arg(long, env = FILTER_ENV_VAR)]
+    pub filter: Option<TaskFilter>,
+    /// Ignore the filter from `TASK_FILTER` for this command.
+    #[arg(long)]
+    pub no_filter: bool,
+    /// Only show tasks that were snoozed, most often snoozed first unless
+    /// `--sort` is given.
+    #[arg(long)]
+    pub snoozed: bool,
+    /// Sort the output by the given field instead of insertion order.
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+    /// Reverse the sort order.
+    #[arg(long)]
+    pub reverse: bool,
+    /// Don't print the line summarizing active filters.
+    #[arg(long)]
+    pub no_header: bool,
+    /// Show at most this many tasks.
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Skip this many tasks before showing any.
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+    /// Show due dates as dates, e.g. `2025-06-01`, rather than "in 3 days".
+    #[arg(long, overrides_with = "relative")]
+    pub absolute: bool,
+    /// Show due dates relative to today, even if the config file sets
+    /// `absolute`.
+    #[arg(long, overrides_with = "absolute")]
+    pub relative: bool,
+    /// The columns to show, in order, e.g. `id,desc,due,tags`; overrides
+    /// `--long`.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub columns: Vec<ListColumn>,
+}
+
+impl ListArgs {
+    /// Returns the filter in effect, unless it's empty or `--no-filter` was given.
+    pub fn active_filter(&self) -> Option<&TaskFilter> {
+        self.filter
+            .as_ref()
+            .filter(|f| !self.no_filter && !f.is_empty())
+    }
+}
+
+impl From<&ListArgs> for TaskSelection {
+    fn from(args: &ListArgs) -> Self {
+        TaskSelection {
+            query: args.query.clone(),
+            all: args.all,
+            waiting: args.waiting,
+            project: args.project.clone(),
+            assignee: args.assignee.clone(),
+            filter: args.active_filter().cloned(),
+            snoozed: args.snoozed,
+            sort: args.sort,
+            reverse: args.reverse,
+            offset: args.offset,
+            limit: args.limit,
+        }
+    }
+}
+
+/// Fields the task list can be grouped by.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Project,
+}
+
+/// The columns the task list can show.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListColumn {
+    Id,
+    #[value(alias = "desc")]
+    #[serde(alias = "desc")]
+    Description,
+    Due,
+    Status,
+    Priority,
+    Project,
+    Tags,
+    Contexts,
+    Created,
+    Completed,
+    Assignee,
+    Url,
+    Progress,
+    Checklist,
+}
+
+impl ListColumn {
+    /// Returns the name of the column in JSON and CSV.
+    pub fn key(self) -> &'static str {
+        match self {
+            ListColumn::Id => "id",
+            ListColumn::Description => "description",
+            ListColumn::Due => "due",
+            ListColumn::Status => "status",
+            ListColumn::Priority => "priority",
+            ListColumn::Project => "project",
+            ListColumn::Tags => "tags",
+            ListColumn::Contexts => "contexts",
+            ListColumn::Created => "created",
+            ListColumn::Completed => "completed",
+            ListColumn::Assignee => "assignee",
+            ListColumn::Url => "url",
+            ListColumn::Progress => "progress",
+            ListColumn::Checklist => "checklist",
+        }
+    }
+
+    /// How wide the column is drawn unless the config file says otherwise.
+    pub fn default_width(self) -> usize {
+        match self {
+            ListColumn::Id => 4,
+            ListColumn::Description => DESCRIPTION_WIDTH,
+            ListColumn::Due | ListColumn::Status => 11,
+            ListColumn::Priority => 8,
+            ListColumn::Project | ListColumn::Assignee => 12,
+            ListColumn::Tags | ListColumn::Contexts => 20,
+            ListColumn::Created | ListColumn::Completed => 16,
+            ListColumn::Progress => 17,
+            ListColumn::Checklist => 9,
+            ListColumn::Url => 40,
+        }
+    }
+}
+
+/// Builds the `clap` command definition for the whole CLI.
+///
+/// Exposed so tooling such as completion generators can inspect the command tree
+/// without going through argument parsing.
+pub fn build_cli() -> clap::Command {
+    Cli::command()
+}
+
 /// Helper function to parse a string into a `NaiveDate`.
 ///
 /// Used by `clap`'s `value_parser` to validate and convert the `due` argument.
 /// Returns a `Result` indicating success or failure of parsing.
-fn parse_due_date(s: &str) -> Result<NaiveDate, String> {
+pub fn parse_due_date(s: &str) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d")
         .map_err(|_| format!("Date format must be YYYY-MM-DD. Failed to parse: '{}'", s))
 }
@@ -0,0 +1,133 @@
+//! Reading and writing the system clipboard, for `add --from-clipboard` and
+//! `yank`.
+//!
+//! The clipboard is reached through the platform's own tools: `pbpaste` and
+//! `pbcopy` on macOS, PowerShell's `Get-Clipboard` and `clip` on Windows,
+//! and elsewhere `wl-paste`/`wl-copy` under Wayland, then `xclip` or `xsel`.
+//! The first tool that is installed is used.
+
+use log::debug;
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::app::{AppError, Result};
+
+/// The commands that print the clipboard, in the order they are tried.
+fn paste_commands() -> Vec<Vec<&'static str>> {
+    if cfg!(target_os = "macos") {
+        vec![vec!["pbpaste"]]
+    } else if cfg!(windows) {
+        vec![vec![
+            "powershell",
+            "-NoProfile",
+            "-Command",
+            "Get-Clipboard",
+        ]]
+    } else {
+        let mut commands = Vec::new();
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(vec!["wl-paste", "--no-newline"]);
+        }
+        commands.push(vec!["xclip", "-selection", "clipboard", "-out"]);
+        commands.push(vec!["xsel", "--clipboard", "--output"]);
+        commands
+    }
+}
+
+/// The commands that set the clipboard to their input, in the order they
+/// are tried.
+fn copy_commands() -> Vec<Vec<&'static str>> {
+    if cfg!(target_os = "macos") {
+        vec![vec!["pbcopy"]]
+    } else if cfg!(windows) {
+        vec![vec!["clip"]]
+    } else {
+        let mut commands = Vec::new();
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(vec!["wl-copy"]);
+        }
+        commands.push(vec!["xclip", "-selection", "clipboard", "-in"]);
+        commands.push(vec!["xsel", "--clipboard", "--input"]);
+        commands
+    }
+}
+
+fn no_tool(commands: &[Vec<&str>]) -> AppError {
+    let tools: Vec<&str> = commands.iter().map(|c| c[0]).collect();
+    AppError::Clipboard(format!(
+        "no clipboard tool found; install one of {}",
+        tools.join(", ")
+    ))
+}
+
+/// Returns the text on the clipboard.
+///
+/// # Returns
+///
+/// An `AppError::Clipboard` if no clipboard tool is installed or it fails.
+pub fn read_clipboard() -> Result<String> {
+    let commands = paste_commands();
+    for args in &commands {
+        let output = match Command::new(args[0]).args(&args[1..]).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(AppError::Clipboard(format!(
+                    "could not run {}: {}",
+                    args[0], e
+                )));
+            }
+        };
+        if !output.status.success() {
+            return Err(AppError::Clipboard(format!(
+                "{} failed ({}): {}",
+                args[0],
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        debug!("Read the clipboard with {}.", args[0]);
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    Err(no_tool(&commands))
+}
+
+/// Puts `text` on the clipboard.
+///
+/// # Returns
+///
+/// An `AppError::Clipboard` if no clipboard tool is installed or it fails.
+pub fn write_clipboard(text: &str) -> Result<()> {
+    let commands = copy_commands();
+    for args in &commands {
+        let mut child = match Command::new(args[0])
+            .args(&args[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(AppError::Clipboard(format!(
+                    "could not run {}: {}",
+                    args[0], e
+                )));
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(AppError::Clipboard(format!(
+                "{} failed ({})",
+                args[0], status
+            )));
+        }
+        debug!("Wrote the clipboard with {}.", args[0]);
+        return Ok(());
+    }
+    Err(no_tool(&commands))
+}
@@ -0,0 +1,121 @@
+//! The application clock.
+//!
+//! All "current time" lookups go through this module so the clock can be pinned
+//! with `--now` or `TASK_NOW`, making date-dependent output reproducible in
+//! tests, demos, and bug reports.
+//!
+//! Instants are kept in UTC, while dates and times of day are wall-clock time
+//! in the zone set with the `timezone` config setting, or the system's zone
+//! when there is none. "Today", due dates, and due times are all read in that
+//! zone, so a task is overdue at the same moment whether the command runs on
+//! a laptop or on a server in UTC.
+
+use chrono::offset::LocalResult;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+/// The pinned time, if one was set for this process.
+static NOW_OVERRIDE: OnceLock<DateTime<Utc>> = OnceLock::new();
+
+/// The zone dates are read in, if one was configured.
+static TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Pins the clock to `now` for the rest of the process.
+///
+/// Only the first call has an effect; later calls are ignored.
+pub fn set_now_override(now: DateTime<Utc>) {
+    let _ = NOW_OVERRIDE.set(now);
+}
+
+/// Returns the current time, or the pinned time if an override is set.
+pub fn now() -> DateTime<Utc> {
+    NOW_OVERRIDE.get().copied().unwrap_or_else(Utc::now)
+}
+
+/// Reads dates and times of day in `zone` for the rest of the process,
+/// instead of the system's zone.
+///
+/// Only the first call has an effect; later calls are ignored.
+pub fn set_timezone(zone: Tz) {
+    let _ = TIMEZONE.set(zone);
+}
+
+/// Returns the current date, used for overdue and due-soon checks.
+pub fn today() -> NaiveDate {
+    local(now()).date()
+}
+
+/// Returns the current date and time of day, in the zone [`today`] uses.
+pub fn now_naive() -> NaiveDateTime {
+    local(now())
+}
+
+/// Returns the wall-clock time of `at` in the configured zone.
+pub fn local(at: DateTime<Utc>) -> NaiveDateTime {
+    match TIMEZONE.get() {
+        Some(zone) => at.with_timezone(zone).naive_local(),
+        None => at.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Returns the date of `at` in the configured zone.
+pub fn local_date(at: DateTime<Utc>) -> NaiveDate {
+    local(at).date()
+}
+
+/// Returns the instant at which the configured zone shows the wall-clock
+/// time `at`.
+///
+/// A time that occurs twice when clocks go back is the earlier of the two;
+/// one skipped when clocks go forward is read with the offset in effect
+/// before the change.
+pub fn from_local(at: NaiveDateTime) -> DateTime<Utc> {
+    match TIMEZONE.get() {
+        Some(zone) => resolve_local(zone, at),
+        None => resolve_local(&Local, at),
+    }
+}
+
+fn resolve_local<Z: TimeZone>(zone: &Z, at: NaiveDateTime) -> DateTime<Utc> {
+    match zone.from_local_datetime(&at) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+        LocalResult::None => {
+            let before = at - chrono::Duration::hours(1);
+            zone.from_local_datetime(&before)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc) + chrono::Duration::hours(1))
+                .unwrap_or_else(|| at.and_utc())
+        }
+    }
+}
+
+/// Parses a timestamp given to `--now` or `TASK_NOW`.
+///
+/// Accepts RFC 3339 (`2025-06-01T09:00:00Z`), `YYYY-MM-DD HH:MM[:SS]` and
+/// `YYYY-MM-DD` (both read as UTC), or Unix seconds.
+pub fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(Utc.from_utc_datetime(&naive));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default()));
+    }
+    if let Some(dt) = s
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+    {
+        return Ok(dt);
+    }
+    Err(format!(
+        "Timestamp must be RFC 3339, 'YYYY-MM-DD HH:MM', 'YYYY-MM-DD', or Unix seconds. Failed to parse: '{}'",
+        s
+    ))
+}
@@ -0,0 +1,969 @@
+//! Listing, searching, and showing tasks, and the task table they share.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tracing::info_span;
+
+use super::print_fields;
+use super::review::status_color;
+use crate::app::{
+    Annotation, AppError, Cell, Color, Column, Config, ContextCommands, DESCRIPTION_WIDTH,
+    Estimate, GoalProgress, GroupBy, INBOX_LABEL, ListArgs, ListColumn, ListPage, OutputSink, Past,
+    Priority, Query, Renderer, Result, SearchBackend, Table, Task, TaskManager, TaskSelection,
+    TaskStatus, Template, TemplateCommands, clock, context_summaries, define_context,
+    delete_context, display_width, format_elapsed, format_offset, group_by_project, list_names,
+    load_templates, paint, progress_bar, project_summaries, relative_date, save_templates,
+    set_context, storage, subtasks, tracked_time,
+};
+use crate::{outln, tr};
+
+/// Handles the 'list' command.
+/// Prints tasks to the console, optionally including completed ones,
+/// restricted to a project, or grouped by project.
+/// The long format adds creation and completion timestamps.
+pub(super) fn handle_list_tasks(
+    out: &mut dyn OutputSink,
+    manager: &TaskManager,
+    args: &ListArgs,
+    config: &Config,
+) -> Result<()> {
+    let selection = TaskSelection::from(args);
+    if !config.output_format.for_humans() {
+        print_task_table(
+            out,
+            &manager.list(&selection)?.tasks,
+            TableStyle::of(args),
+            config,
+        );
+        return Ok(());
+    }
+
+    let tasks = manager.tasks();
+    if tasks.is_empty() {
+        out.line(&tr!("no-tasks"));
+        return Ok(());
+    }
+
+    let filter_span = info_span!("filter").entered();
+    let filter = args.active_filter();
+    let ListPage {
+        tasks: selected,
+        matching: total,
+    } = manager.list(&selection)?;
+
+    if !args.no_header {
+        let mut parts = Vec::new();
+        if let Some(project) = &args.project {
+            parts.push(tr!("header-project", project = project.as_str()));
+        }
+        if let Some(assignee) = &args.assignee {
+            parts.push(tr!("header-assignee", assignee = assignee.as_str()));
+        }
+        if let Some(filter) = filter {
+            parts.push(tr!("header-filter", filter = filter.to_string()));
+        }
+        if let Some(query) = &args.query {
+            parts.push(tr!("header-query", query = query.to_string()));
+        }
+        if !parts.is_empty() {
+            parts.push(tr!("header-shown", shown = total, count = tasks.len()));
+        }
+        if let Some(goal) = config.weekly_goal {
+            let progress = GoalProgress::this_week(tasks, goal, clock::now());
+            parts.push(tr!("header-goal", progress = progress.to_string()));
+        }
+        if !parts.is_empty() {
+            outln!(out, "{}", parts.join(separator(config)));
+        }
+    }
+    if total == 0 {
+        if args.query.is_some() {
+            out.line(&tr!("no-tasks-match-query"));
+        } else if filter.is_some() {
+            out.line(&tr!("no-tasks-match-filter"));
+        } else if args.project.is_some() {
+            out.line(&tr!("no-tasks-in-project"));
+        } else if let Some(assignee) = &args.assignee {
+            out.line(&tr!("no-tasks-assigned", assignee = assignee.as_str()));
+        } else if args.waiting {
+            out.line(&tr!("no-tasks-waiting"));
+        } else if let waiting @ 1.. = tasks
+            .iter()
+            .filter(|t| t.is_waiting(clock::today()))
+            .count()
+        {
+            out.line(&tr!("nothing-to-do-yet", count = waiting));
+        } else if !args.all {
+            out.line(&tr!("all-tasks-completed"));
+        }
+        return Ok(());
+    }
+    if selected.is_empty() {
+        out.line(&tr!(
+            "no-tasks-at-offset",
+            offset = args.offset,
+            count = total
+        ));
+        return Ok(());
+    }
+    drop(filter_span);
+
+    let _render = info_span!("render").entered();
+    match args.group_by {
+        Some(GroupBy::Project) => {
+            for (project, group) in group_by_project(&selected) {
+                outln!(out, "{} ({})", project.unwrap_or(INBOX_LABEL), group.len());
+                print_task_table(out, &group, TableStyle::of(args), config);
+                outln!(out);
+            }
+        }
+        None => print_task_table(out, &selected, TableStyle::of(args), config),
+    }
+
+    if selected.len() < total {
+        out.line(&tr!(
+            "showing-range",
+            first = args.offset + 1,
+            last = args.offset + selected.len(),
+            count = total,
+            which = if args.all { "all" } else { "pending" }
+        ));
+    }
+    Ok(())
+}
+
+/// How [`task_table`] lays out tasks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableStyle<'a> {
+    /// The columns to show; empty means the usual ones.
+    pub columns: &'a [ListColumn],
+    /// Adds creation and completion timestamps to the usual columns.
+    pub long: bool,
+    /// Shows due dates as dates rather than relative to today; unset means
+    /// as the config file says.
+    pub absolute: Option<bool>,
+}
+
+impl<'a> TableStyle<'a> {
+    /// Returns the style `list` was asked for.
+    fn of(args: &'a ListArgs) -> Self {
+        TableStyle {
+            columns: &args.columns,
+            long: args.long,
+            absolute: Some(args.absolute),
+        }
+    }
+}
+
+/// Prints tasks as a table, with colored statuses and overdue due dates in red.
+pub(super) fn print_task_table(
+    out: &mut dyn OutputSink,
+    tasks: &[&Task],
+    style: TableStyle,
+    config: &Config,
+) {
+    let table = task_table(tasks, style, clock::now_naive(), config);
+    renderer(config).table(out, &table);
+}
+
+/// Returns the table `list` shows for `tasks` at `now`.
+pub fn task_table(
+    tasks: &[&Task],
+    style: TableStyle,
+    now: NaiveDateTime,
+    config: &Config,
+) -> Table {
+    let columns = if style.columns.is_empty() {
+        default_columns(tasks, style.long)
+    } else {
+        style.columns.to_vec()
+    };
+    let absolute = style.absolute.unwrap_or(config.list.absolute);
+    let dues: Vec<Cell> = tasks
+        .iter()
+        .map(|task| due_cell(task, now, absolute, config))
+        .collect();
+    let widest_due = dues
+        .iter()
+        .map(|d| display_width(&d.text))
+        .max()
+        .unwrap_or(0);
+
+    let mut table = Table::new(
+        columns
+            .iter()
+            .map(|&column| Column {
+                key: column.key(),
+                title: column_title(column),
+                width: match (column, config.list.widths.contains_key(&column)) {
+                    (ListColumn::Due, false) => widest_due.max(column.default_width()),
+                    _ => config.list.width(column),
+                },
+            })
+            .collect(),
+    );
+    if config.list.widths.contains_key(&ListColumn::Description) {
+        table.flexible = None;
+    }
+    for (task, due) in tasks.iter().zip(dues) {
+        let row = columns
+            .iter()
+            .map(|column| match column {
+                ListColumn::Id => Cell::new(task.id),
+                ListColumn::Description => Cell::new(task.description.as_str()),
+                ListColumn::Due => due.clone(),
+                ListColumn::Status => Cell::new(task.status_label())
+                    .shown_as(task.status.label())
+                    .colored(Some(status_color(task))),
+                ListColumn::Priority => task
+                    .priority
+                    .map_or(Cell::missing("N/A"), |p| priority_cell(p, config)),
+                ListColumn::Project => task
+                    .project
+                    .as_deref()
+                    .map_or(Cell::missing("N/A"), Cell::new),
+                ListColumn::Tags => Cell::new(task.tags.clone()).shown_as(task.tags.join(", ")),
+                ListColumn::Contexts => {
+                    Cell::new(task.contexts.clone()).shown_as(format_task_contexts(task))
+                }
+                ListColumn::Created => {
+                    Cell::new(task.created_at.to_rfc3339()).shown_as(local_stamp(task.created_at))
+                }
+                ListColumn::Completed => match task.completed_at {
+                    Some(at) => Cell::new(at.to_rfc3339()).shown_as(local_stamp(at)),
+                    None => Cell::missing("N/A"),
+                },
+                ListColumn::Assignee => task
+                    .assignee
+                    .as_deref()
+                    .map_or(Cell::missing(""), Cell::new),
+                ListColumn::Url => task.url.as_deref().map_or(Cell::missing(""), Cell::new),
+                ListColumn::Progress => task.progress.map_or(Cell::missing(""), |percent| {
+                    Cell::new(percent).shown_as(progress_bar(percent))
+                }),
+                ListColumn::Checklist => task
+                    .checklist_progress()
+                    .map_or(Cell::missing(""), |(done, total)| {
+                        Cell::new(format!("{}/{}", done, total))
+                    }),
+            })
+            .collect();
+        table.push(row);
+    }
+    table
+}
+
+/// Returns the columns `list` shows without `--columns`: the long format
+/// adds timestamps, and contexts, assignees, URLs, progress, and checklists
+/// when some task has one.
+pub(super) fn default_columns(tasks: &[&Task], long: bool) -> Vec<ListColumn> {
+    let mut columns = vec![
+        ListColumn::Id,
+        ListColumn::Description,
+        ListColumn::Due,
+        ListColumn::Status,
+    ];
+    if long {
+        columns.extend([ListColumn::Created, ListColumn::Completed]);
+        if tasks.iter().any(|t| !t.contexts.is_empty()) {
+            columns.push(ListColumn::Contexts);
+        }
+        if tasks.iter().any(|t| t.assignee.is_some()) {
+            columns.push(ListColumn::Assignee);
+        }
+        if tasks.iter().any(|t| t.url.is_some()) {
+            columns.push(ListColumn::Url);
+        }
+        if tasks.iter().any(|t| t.progress.is_some()) {
+            columns.push(ListColumn::Progress);
+        }
+        if tasks.iter().any(|t| !t.checklist.is_empty()) {
+            columns.push(ListColumn::Checklist);
+        }
+    }
+    columns
+}
+
+/// Returns the heading of `column`.
+pub(super) fn column_title(column: ListColumn) -> String {
+    match column {
+        ListColumn::Id => tr!("column-id"),
+        ListColumn::Description => tr!("column-description"),
+        ListColumn::Due => tr!("column-due-date"),
+        ListColumn::Status => tr!("column-status"),
+        ListColumn::Priority => tr!("column-priority"),
+        ListColumn::Project => tr!("column-project"),
+        ListColumn::Tags => tr!("column-tags"),
+        ListColumn::Contexts => tr!("column-contexts"),
+        ListColumn::Created => tr!("column-created"),
+        ListColumn::Completed => tr!("column-completed"),
+        ListColumn::Assignee => tr!("column-assignee"),
+        ListColumn::Url => tr!("column-url"),
+        ListColumn::Progress => tr!("column-progress"),
+        ListColumn::Checklist => tr!("column-checklist"),
+    }
+}
+
+/// Formats the contexts of `task` as written in descriptions, e.g.
+/// `@phone @office`.
+pub(super) fn format_task_contexts(task: &Task) -> String {
+    task.contexts
+        .iter()
+        .map(|c| format!("@{}", c))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats a recorded moment as wall-clock time in the configured time zone.
+pub(super) fn local_stamp(at: DateTime<Utc>) -> String {
+    clock::local(at).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// Returns the due date cell of `task`, in red if it's overdue at `now`.
+/// Overdue tasks with a due time also show how late they are; finished
+/// tasks show just the date.
+pub(super) fn due_cell(task: &Task, now: NaiveDateTime, absolute: bool, config: &Config) -> Cell {
+    let (Some(date), Some(absolute_due)) = (task.due_date, config.format_due(task)) else {
+        return Cell::missing("N/A");
+    };
+    let cell = match task.due_time {
+        Some(time) => Cell::new(date.and_time(time).format("%Y-%m-%dT%H:%M").to_string()),
+        None => Cell::new(date.to_string()),
+    };
+    if !task.is_pending() {
+        return cell.shown_as(match task.due_time {
+            Some(time) => format!("{} {}", date, time.format("%H:%M")),
+            None => date.to_string(),
+        });
+    }
+    let days = (date - now.date()).num_days();
+    let shown = match task.due_time {
+        _ if absolute => absolute_due,
+        Some(time) if days == 0 || days == 1 => {
+            format!(
+                "{} {}",
+                relative_date(date, now.date(), Past::Overdue),
+                time.format("%H:%M")
+            )
+        }
+        _ => relative_date(date, now.date(), Past::Overdue),
+    };
+    if !task.is_overdue(now) {
+        return cell.shown_as(shown);
+    }
+    let late_today = absolute || days == 0;
+    let shown = match task
+        .deadline()
+        .filter(|_| task.due_time.is_some() && late_today)
+    {
+        Some(deadline) => {
+            let late = now - deadline;
+            let late = if late.num_hours() < 1 {
+                tr!("late-minutes", minutes = late.num_minutes())
+            } else if late.num_hours() < 48 {
+                tr!("late-hours", hours = late.num_hours())
+            } else {
+                tr!("late-days", days = late.num_days())
+            };
+            format!("{} ({})", shown, late)
+        }
+        None => shown,
+    };
+    if config.ascii {
+        return cell.shown_as(format!("{} {}", tr!("marker-overdue"), shown));
+    }
+    cell.shown_as(shown).colored(Some(Color::Red))
+}
+
+/// Returns the cell of a priority, its marker in `--ascii` mode.
+pub(super) fn priority_cell(priority: Priority, config: &Config) -> Cell {
+    let cell = Cell::new(priority.to_string());
+    if config.ascii {
+        cell.shown_as(priority.marker())
+    } else {
+        cell
+    }
+}
+
+/// Returns what separates the parts of a line, e.g. of the list header.
+pub(super) fn separator(config: &Config) -> &'static str {
+    if config.ascii { " | " } else { " · " }
+}
+
+/// Returns the renderer for tables in the configured output format.
+pub(super) fn renderer(config: &Config) -> Box<dyn Renderer> {
+    config.output_format.renderer(config.color.enabled())
+}
+
+/// Handles the 'search' command.
+pub(super) fn handle_search(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    mut backend: Box<dyn SearchBackend>,
+    query: &str,
+    long: bool,
+    annotations: bool,
+    config: &Config,
+) -> Result<()> {
+    backend.update(tasks)?;
+    let matches: Vec<&Task> = backend
+        .search(tasks, query, annotations)?
+        .iter()
+        .filter_map(|uuid| tasks.iter().find(|t| t.uuid == *uuid))
+        .collect();
+    if matches.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-tasks-match-text", text = query));
+        return Ok(());
+    }
+    print_task_table(
+        out,
+        &matches,
+        TableStyle {
+            long,
+            ..TableStyle::default()
+        },
+        config,
+    );
+    Ok(())
+}
+
+/// Handles the 'projects' command.
+/// Prints every project with its pending and total task counts.
+pub(super) fn handle_list_projects(out: &mut dyn OutputSink, tasks: &[Task], config: &Config) {
+    let summaries = project_summaries(tasks);
+    if summaries.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-tasks"));
+        return;
+    }
+
+    let mut table = Table::new(vec![
+        Column {
+            key: "project",
+            title: tr!("column-project"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "pending",
+            title: tr!("column-pending"),
+            width: 8,
+        },
+        Column {
+            key: "total",
+            title: tr!("column-total"),
+            width: 8,
+        },
+    ]);
+    for summary in summaries {
+        let name = match summary.name {
+            Some(name) => Cell::new(name),
+            None => Cell::missing(INBOX_LABEL),
+        };
+        table.push(vec![
+            name,
+            Cell::new(summary.pending),
+            Cell::new(summary.total),
+        ]);
+    }
+    renderer(config).table(out, &table);
+}
+
+/// Handles the 'contexts' command.
+pub(super) fn handle_task_contexts(out: &mut dyn OutputSink, tasks: &[Task], config: &Config) {
+    let summaries = context_summaries(tasks, clock::now_naive());
+    if summaries.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-task-contexts"));
+        return;
+    }
+    let mut table = Table::new(vec![
+        Column {
+            key: "context",
+            title: tr!("column-context"),
+            width: 20,
+        },
+        Column {
+            key: "pending",
+            title: tr!("column-pending"),
+            width: 8,
+        },
+        Column {
+            key: "overdue",
+            title: tr!("column-overdue"),
+            width: 8,
+        },
+    ]);
+    for summary in summaries {
+        table.push(vec![
+            Cell::new(summary.name.as_str()).shown_as(format!("@{}", summary.name)),
+            Cell::new(summary.pending),
+            Cell::new(summary.overdue),
+        ]);
+    }
+    renderer(config).table(out, &table);
+}
+
+/// Handles the 'template' command.
+pub(super) fn handle_template(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    command: &TemplateCommands,
+    config: &Config,
+) -> Result<()> {
+    let mut templates = load_templates()?;
+    match command {
+        TemplateCommands::Save { name, id } => {
+            let task = tasks
+                .iter()
+                .find(|t| t.id == *id)
+                .ok_or_else(|| AppError::task_not_found(*id, tasks))?;
+            templates.insert(name.clone(), Template::from_task(task));
+            let path = save_templates(&templates)?;
+            out.line(&tr!(
+                "template-saved",
+                name = name.as_str(),
+                path = path.display().to_string()
+            ));
+        }
+        TemplateCommands::Remove { name } => {
+            if templates.remove(name).is_none() {
+                return Err(AppError::InvalidArgument(tr!(
+                    "error-no-template",
+                    name = name.as_str()
+                )));
+            }
+            save_templates(&templates)?;
+            out.line(&tr!("template-removed", name = name.as_str()));
+        }
+        TemplateCommands::List => {
+            if templates.is_empty() && config.output_format.for_humans() {
+                out.line(&tr!("no-templates"));
+                return Ok(());
+            }
+            let mut table = Table::new(vec![
+                Column {
+                    key: "template",
+                    title: tr!("column-template"),
+                    width: 16,
+                },
+                Column {
+                    key: "project",
+                    title: tr!("column-project"),
+                    width: 12,
+                },
+                Column {
+                    key: "priority",
+                    title: tr!("column-priority"),
+                    width: 8,
+                },
+                Column {
+                    key: "tags",
+                    title: tr!("column-tags"),
+                    width: 20,
+                },
+                Column {
+                    key: "due_after_days",
+                    title: tr!("column-due-after"),
+                    width: 9,
+                },
+            ]);
+            let optional = |value: Option<String>| value.map_or(Cell::missing(""), Cell::new);
+            for (name, template) in &templates {
+                table.push(vec![
+                    Cell::new(name.as_str()),
+                    optional(template.project.clone()),
+                    optional(template.priority.map(|p| p.to_string())),
+                    Cell::new(template.tags.join(", ")),
+                    template.due_after_days.map_or(Cell::missing(""), |days| {
+                        Cell::new(days).shown_as(format!("{}d", days))
+                    }),
+                ]);
+            }
+            renderer(config).table(out, &table);
+        }
+    }
+    Ok(())
+}
+
+/// Handles the 'lists' command.
+/// The current list is marked with `*`.
+pub(super) fn handle_context(
+    out: &mut dyn OutputSink,
+    command: &ContextCommands,
+    config: &Config,
+) -> Result<()> {
+    match command {
+        ContextCommands::Define { name, query } => {
+            let query: Query = query.join(" ").parse().map_err(AppError::InvalidArgument)?;
+            let path = define_context(name, &query)?;
+            out.line(&tr!(
+                "context-defined",
+                name = name.as_str(),
+                path = path.display().to_string()
+            ));
+        }
+        ContextCommands::Set { name } => {
+            if !config.contexts.contains_key(name) {
+                return Err(AppError::InvalidArgument(tr!(
+                    "error-no-context",
+                    name = name.as_str()
+                )));
+            }
+            set_context(Some(name))?;
+            out.line(&tr!("context-active", name = name.as_str()));
+        }
+        ContextCommands::None => {
+            set_context(None)?;
+            out.line(&tr!("no-context-active"));
+        }
+        ContextCommands::Delete { name } => {
+            delete_context(name)?;
+            out.line(&tr!("context-deleted", name = name.as_str()));
+        }
+        ContextCommands::List => {
+            if config.contexts.is_empty() && config.output_format.for_humans() {
+                out.line(&tr!("no-contexts"));
+                return Ok(());
+            }
+            let mut table = Table::new(vec![
+                Column {
+                    key: "context",
+                    title: tr!("column-context"),
+                    width: 16,
+                },
+                Column {
+                    key: "query",
+                    title: tr!("column-query"),
+                    width: DESCRIPTION_WIDTH,
+                },
+            ]);
+            for (name, query) in &config.contexts {
+                let label = if config.context.as_ref() == Some(name) {
+                    format!("{} *", name)
+                } else {
+                    name.clone()
+                };
+                table.push(vec![Cell::new(label), Cell::new(query.as_str())]);
+            }
+            renderer(config).table(out, &table);
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn handle_lists(out: &mut dyn OutputSink, config: &Config) -> Result<()> {
+    let dir = config.lists_dir();
+    let names = list_names(&dir)?;
+    if names.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-lists", dir = dir.display().to_string()));
+        return Ok(());
+    }
+
+    let mut table = Table::new(vec![
+        Column {
+            key: "list",
+            title: tr!("column-list"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "pending",
+            title: tr!("column-pending"),
+            width: 8,
+        },
+        Column {
+            key: "total",
+            title: tr!("column-total"),
+            width: 8,
+        },
+    ]);
+    for name in names {
+        let tasks = storage::load_tasks_from(&config.list_file_path(&name))?;
+        let label = if config.default_list.as_ref() == Some(&name) {
+            format!("{} *", name)
+        } else {
+            name
+        };
+        table.push(vec![
+            Cell::new(label),
+            Cell::new(tasks.iter().filter(|t| t.is_pending()).count()),
+            Cell::new(tasks.len()),
+        ]);
+    }
+    renderer(config).table(out, &table);
+    Ok(())
+}
+
+/// Handles the 'next' command.
+pub(super) fn handle_next(out: &mut dyn OutputSink, tasks: &[Task], limit: usize, config: &Config) {
+    let now = clock::now_naive();
+    let ranked = config.urgency.rank(tasks, now);
+    if ranked.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("nothing-to-do"));
+        return;
+    }
+
+    let mut table = Table::new(vec![
+        Column {
+            key: "id",
+            title: tr!("column-id"),
+            width: 4,
+        },
+        Column {
+            key: "urgency",
+            title: tr!("column-urgency"),
+            width: 8,
+        },
+        Column {
+            key: "description",
+            title: tr!("column-description"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "due",
+            title: tr!("column-due-date"),
+            width: 16,
+        },
+    ]);
+    for (task, score) in ranked.into_iter().take(limit) {
+        table.push(vec![
+            Cell::new(task.id),
+            Cell::new((score * 10.0).round() / 10.0).shown_as(format!("{:.1}", score)),
+            Cell::new(task.description.as_str()),
+            due_cell(task, now, config.list.absolute, config),
+        ]);
+    }
+    renderer(config).table(out, &table);
+}
+
+/// Handles the 'random' command.
+pub(super) fn handle_random(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    tag: Option<String>,
+    context: Option<String>,
+    within: Option<Estimate>,
+    config: &Config,
+) {
+    let candidates = tasks.iter().filter(|t| {
+        tag.as_ref().is_none_or(|tag| t.tags.contains(tag))
+            && context.as_ref().is_none_or(|c| t.contexts.contains(c))
+            && within.is_none_or(|within| t.estimate.is_some_and(|e| e <= within))
+    });
+    let Some(task) = config.urgency.pick(candidates, clock::now_naive()) else {
+        if config.output_format.for_humans() {
+            out.line(&tr!("nothing-to-do"));
+        }
+        return;
+    };
+    if config.output_format.for_humans() {
+        out.line(&tr!(
+            "random-pick",
+            id = task.id,
+            description = task.description.as_str()
+        ));
+    } else {
+        print_task_table(out, &[task], TableStyle::default(), config);
+    }
+}
+
+/// Handles the 'board' command, printing a table per status.
+pub(super) fn handle_board(out: &mut dyn OutputSink, tasks: &[Task], all: bool, config: &Config) {
+    for status in TaskStatus::ALL {
+        if !all && !status.is_pending() {
+            continue;
+        }
+        let column: Vec<&Task> = tasks.iter().filter(|t| t.status == status).collect();
+        outln!(out, "{} ({})", status.title(), column.len());
+        if !column.is_empty() {
+            print_task_table(out, &column, TableStyle::default(), config);
+        }
+        outln!(out);
+    }
+}
+
+/// Handles the 'show' command.
+/// Prints all fields of a single task followed by its history and notes, or the whole task as JSON.
+pub(super) fn handle_show_task(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    id: u32,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::task_not_found(id, tasks))?;
+
+    if json {
+        out.value(&serde_json::to_value(task)?);
+        return Ok(());
+    }
+
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+    let mut fields = vec![
+        (tr!("field-id"), task.id.to_string()),
+        (tr!("field-description"), task.description.clone()),
+        (
+            tr!("field-status"),
+            paint(
+                &task.status.label(),
+                status_color(task),
+                config.color.enabled(),
+            ),
+        ),
+        (tr!("field-project"), optional(task.project.clone())),
+    ];
+    if let Some(assignee) = &task.assignee {
+        fields.push((tr!("field-assignee"), assignee.clone()));
+    }
+    if let Some(url) = &task.url {
+        fields.push((tr!("field-url"), url.clone()));
+    }
+    if let Some(parent) = task
+        .parent
+        .and_then(|uuid| tasks.iter().find(|t| t.uuid == uuid))
+    {
+        fields.push((
+            tr!("field-parent"),
+            format!("[{}] {}", parent.id, parent.description),
+        ));
+    }
+    let children: Vec<&Task> = subtasks(task, tasks).collect();
+    if !children.is_empty() {
+        let ids: Vec<String> = children.iter().map(|t| t.id.to_string()).collect();
+        fields.push((tr!("field-subtasks"), ids.join(", ")));
+    }
+    if let Some(percent) = task.progress {
+        fields.push((tr!("field-progress"), progress_bar(percent)));
+    }
+    if let Some(goal) = task.goal {
+        fields.push((tr!("field-goal"), goal.to_string()));
+    }
+    if let Some(waiting_on) = &task.waiting_on {
+        fields.push((tr!("field-waiting-on"), waiting_on.clone()));
+    }
+    fields.push((
+        tr!("field-tags"),
+        if task.tags.is_empty() {
+            "N/A".to_string()
+        } else {
+            task.tags.join(", ")
+        },
+    ));
+    if !task.contexts.is_empty() {
+        fields.push((tr!("field-contexts"), format_task_contexts(task)));
+    }
+    fields.push((
+        tr!("field-priority"),
+        optional(task.priority.map(|p| p.to_string())),
+    ));
+    fields.push((tr!("field-due-date"), optional(config.format_due(task))));
+    if task.snooze_count > 0 {
+        fields.push((
+            tr!("field-snoozed"),
+            tr!("snoozed-times", count = task.snooze_count),
+        ));
+    }
+    fields.push((
+        tr!("field-estimate"),
+        optional(task.estimate.map(|e| e.to_string())),
+    ));
+    fields.push((
+        tr!("field-remind"),
+        optional(
+            task.remind_before_minutes
+                .map(|m| tr!("before-due", offset = format_offset(m))),
+        ),
+    ));
+    fields.push((
+        tr!("field-reminders"),
+        optional((!task.alerts.is_empty()).then(|| {
+            task.alerts
+                .iter()
+                .map(|alert| alert.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })),
+    ));
+    fields.push((
+        tr!("field-tracked"),
+        if task.intervals.is_empty() {
+            "N/A".to_string()
+        } else {
+            let total = format_elapsed(tracked_time(task, clock::now()));
+            match task.intervals.last() {
+                Some(interval) if interval.end.is_none() => {
+                    tr!("tracked-running", total = total)
+                }
+                _ => total,
+            }
+        },
+    ));
+    fields.push((
+        tr!("field-recurrence"),
+        optional(task.recurrence.as_ref().map(|r| r.to_string())),
+    ));
+    fields.push((
+        tr!("field-starts"),
+        optional(task.start_date.map(|d| config.format_date(d))),
+    ));
+    fields.push((
+        tr!("field-expires"),
+        optional(task.expires_on.map(|d| config.format_date(d))),
+    ));
+    let stamp_by = |at: DateTime<Utc>, by: &Option<String>| match by {
+        Some(user) => tr!("stamp-by", stamp = local_stamp(at), user = user.as_str()),
+        None => local_stamp(at),
+    };
+    fields.push((
+        tr!("field-created"),
+        stamp_by(task.created_at, &task.created_by),
+    ));
+    if let Some(at) = task.updated_at {
+        fields.push((tr!("field-changed"), stamp_by(at, &task.updated_by)));
+    }
+    fields.push((
+        tr!("field-completed"),
+        optional(task.completed_at.map(|at| stamp_by(at, &task.completed_by))),
+    ));
+    print_fields(out, &fields);
+
+    if let Some((done, total)) = task.checklist_progress() {
+        out.line(&tr!("heading-checklist", done = done, total = total));
+        for (i, item) in task.checklist.iter().enumerate() {
+            let mark = if item.done { "x" } else { " " };
+            outln!(out, "  {}) [{}] {}", i + 1, mark, item.text);
+        }
+    }
+    if !task.attachments.is_empty() {
+        out.line(&tr!("heading-attached"));
+        for (i, attachment) in task.attachments.iter().enumerate() {
+            outln!(out, "  {}) {}", i + 1, attachment.target);
+        }
+    }
+    if !task.annotations.is_empty() {
+        let mut annotations: Vec<&Annotation> = task.annotations.iter().collect();
+        annotations.sort_by_key(|a| a.created_at);
+        out.line(&tr!("heading-history"));
+        for annotation in annotations {
+            outln!(
+                out,
+                "  [{}] {}",
+                local_stamp(annotation.created_at),
+                annotation.text
+            );
+        }
+    }
+    if task.notes.is_empty() {
+        out.line(&tr!("notes-none"));
+    } else {
+        out.line(&tr!("heading-notes"));
+        for note in &task.notes {
+            let stamp = local_stamp(note.created_at);
+            let mut lines = note.text.lines();
+            outln!(out, "  [{}] {}", stamp, lines.next().unwrap_or(""));
+            for line in lines {
+                outln!(out, "  {:width$}  {}", "", line, width = stamp.len() + 1);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! Looking after the data file: the audit journal, encryption, checks,
+//! storage conversion, and renumbering.
+
+use log::info;
+use std::fs;
+use std::path::Path;
+
+use super::listing::local_stamp;
+use crate::app::{
+    AppError, AuditCommands, Compaction, Config, OutputSink, Result, StorageFormat, Task, clock,
+    encryption, examine, format_bytes, generate_signing_key, history_file_path, is_encrypted_file,
+    is_event_log, journal_file_path, load_journal, load_trash, parse_public_key,
+    passphrase_from_env, public_key_hex, renumber, storage, survey, verify_journal,
+    write_signing_key, write_trash,
+};
+use crate::{outln, tr};
+
+/// Handles the 'audit' command.
+pub(super) fn handle_audit(
+    out: &mut dyn OutputSink,
+    command: &AuditCommands,
+    tasks_path: &Path,
+    config: &Config,
+) -> Result<()> {
+    let journal_path = journal_file_path(tasks_path);
+    match command {
+        AuditCommands::Verify { public_key } => {
+            let key = match public_key {
+                Some(key) => Some(parse_public_key(key)?),
+                None if config.audit.sign || config.audit.public_key.is_some() => {
+                    Some(config.audit.verifying_key()?)
+                }
+                None => None,
+            };
+            let entries = load_journal(&journal_path)?;
+            let result = verify_journal(&entries, key.as_ref(), config.audit.sign)?;
+            if result.is_intact() {
+                out.line(&tr!(
+                    "journal-intact",
+                    entries = result.entries,
+                    signed = result.signed
+                ));
+                return Ok(());
+            }
+            for (seq, problem) in &result.problems {
+                out.line(&tr!(
+                    "journal-entry-problem",
+                    seq = *seq,
+                    problem = problem.to_string()
+                ));
+            }
+            Err(AppError::Audit(tr!(
+                "error-journal-tampered",
+                path = journal_path.display().to_string()
+            )))
+        }
+        AuditCommands::Log { json } => {
+            let entries = load_journal(&journal_path)?;
+            if entries.is_empty() {
+                out.line(&tr!("journal-empty"));
+            }
+            for entry in &entries {
+                if *json {
+                    outln!(out, "{}", serde_json::to_string(entry)?);
+                    continue;
+                }
+                let changes = &entry.changes;
+                outln!(
+                    out,
+                    "{:<4} {}  {}  (+{} -{} ~{}){}",
+                    entry.seq,
+                    local_stamp(entry.at),
+                    entry.command,
+                    changes.added.len(),
+                    changes.removed.len(),
+                    changes.changed.len(),
+                    if entry.signature.is_some() {
+                        format!("  {}", tr!("journal-signed"))
+                    } else {
+                        String::new()
+                    }
+                );
+            }
+            Ok(())
+        }
+        AuditCommands::Keygen { force } => {
+            let path = config
+                .audit
+                .key_file_path()
+                .ok_or_else(|| AppError::Audit(tr!("error-no-key-directory")))?;
+            if path.exists() && !force {
+                return Err(AppError::Audit(tr!(
+                    "error-key-exists",
+                    path = path.display().to_string()
+                )));
+            }
+            let key = generate_signing_key();
+            write_signing_key(&path, &key)?;
+            out.line(&tr!("key-written", path = path.display().to_string()));
+            out.line(&tr!("public-key", key = public_key_hex(&key)));
+            out.line(&tr!("key-hint"));
+            Ok(())
+        }
+    }
+}
+
+/// Handles the 'encrypt' and 'decrypt' commands.
+/// Rewrites the data file and the trash in the requested form; the final save
+/// of the run keeps it.
+pub(super) fn handle_set_encryption(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    tasks_path: &Path,
+    trash_path: &Path,
+    encrypted: bool,
+) -> Result<()> {
+    let action = if encrypted { "encrypted" } else { "decrypted" };
+    let path = tasks_path.display().to_string();
+    if tasks_path.exists() && is_encrypted_file(tasks_path) == encrypted {
+        out.line(&tr!("already-encrypted", path = path, action = action));
+        return Ok(());
+    }
+    if encrypted {
+        passphrase_from_env()?;
+    }
+
+    storage::write_tasks_to(tasks, tasks_path, encrypted)?;
+    let trash = load_trash(trash_path)?;
+    if !trash.is_empty() {
+        write_trash(&trash, trash_path, encrypted)?;
+    }
+    let history_path = history_file_path(tasks_path);
+    if history_path.exists() {
+        let history = encryption::read_file(&history_path)?;
+        encryption::write_file(&history_path, &history, encrypted)?;
+    }
+    info!("Data file {} is now {}.", tasks_path.display(), action);
+    out.line(&tr!("now-encrypted", path = path, action = action));
+    Ok(())
+}
+
+/// Handles the 'doctor' command.
+/// Lists the problems found in the data file, repairing the safe ones if `fix` is set.
+pub(super) fn handle_doctor(out: &mut dyn OutputSink, tasks_path: &Path, fix: bool) -> Result<()> {
+    if !tasks_path.exists() {
+        out.line(&tr!(
+            "nothing-to-check",
+            path = tasks_path.display().to_string()
+        ));
+        return Ok(());
+    }
+    let checkup = examine(tasks_path)?;
+    if checkup.findings.is_empty() {
+        out.line(&tr!("no-problems", path = tasks_path.display().to_string()));
+        return Ok(());
+    }
+
+    for finding in &checkup.findings {
+        match &finding.repair {
+            Some(repair) if fix => outln!(
+                out,
+                "- {} ({})",
+                finding.problem,
+                tr!("finding-fixed", repair = repair.to_string())
+            ),
+            Some(repair) => outln!(
+                out,
+                "- {} ({})",
+                finding.problem,
+                tr!("finding-fixable", repair = repair.to_string())
+            ),
+            None => outln!(out, "- {}", finding.problem),
+        }
+    }
+    let found = checkup.findings.len();
+    let repairable = checkup.repairable();
+    if fix && repairable > 0 {
+        checkup.repair(tasks_path)?;
+        out.line(&tr!("repaired", repaired = repairable, count = found));
+    }
+    let left = if fix { found - repairable } else { found };
+    if left == 0 {
+        return Ok(());
+    }
+    let hint = if !fix && repairable > 0 {
+        format!("; {}", tr!("doctor-fix-hint", count = repairable))
+    } else {
+        String::new()
+    };
+    Err(AppError::Doctor(format!(
+        "{}{}",
+        tr!(
+            "error-problems-left",
+            count = left,
+            path = tasks_path.display().to_string()
+        ),
+        hint
+    )))
+}
+
+/// Handles the 'maintenance' command.
+/// Reports what has piled up beside the data file, compacting it if `compact` is set.
+pub(super) fn handle_maintenance(
+    out: &mut dyn OutputSink,
+    tasks_path: &Path,
+    compact: bool,
+    keep_backups: usize,
+    config: &Config,
+) -> Result<()> {
+    let now = clock::now();
+    let report = survey(tasks_path, now, config.trash_retention_days)?;
+    out.line(&tr!(
+        "maintenance-data-file",
+        path = tasks_path.display().to_string(),
+        size = format_bytes(report.data_bytes)
+    ));
+    out.line(&tr!(
+        "maintenance-trash",
+        count = report.trashed,
+        expired = report.trash_expired,
+        size = format_bytes(report.trash_bytes)
+    ));
+    out.line(&tr!(
+        "maintenance-journal",
+        count = report.journal_entries,
+        size = format_bytes(report.journal_bytes)
+    ));
+    if let Some(events) = report.events {
+        out.line(&tr!(
+            "maintenance-events",
+            count = events,
+            size = format_bytes(report.history_bytes)
+        ));
+    }
+    out.line(&tr!(
+        "maintenance-backups",
+        count = report.backups.len(),
+        size = format_bytes(report.backup_bytes)
+    ));
+    out.line(&tr!(
+        "maintenance-total",
+        size = format_bytes(report.total_bytes())
+    ));
+
+    if !compact {
+        return Ok(());
+    }
+    let compaction = report.compact(tasks_path, now, config.trash_retention_days, keep_backups)?;
+    if compaction == Compaction::default() {
+        out.line(&tr!("maintenance-nothing"));
+        return Ok(());
+    }
+    let size = fs::metadata(tasks_path).map_or(0, |m| m.len());
+    out.line(&tr!(
+        "maintenance-compacted",
+        events = compaction.events_squashed,
+        trash = compaction.trash_purged,
+        backups = compaction.backups_pruned,
+        size = format_bytes(size),
+        before = format_bytes(report.data_bytes)
+    ));
+    Ok(())
+}
+
+/// Handles the 'convert-storage' command.
+/// Rewrites the data file in `format`, keeping whether it is encrypted.
+pub(super) fn handle_convert_storage(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    tasks_path: &Path,
+    format: StorageFormat,
+) -> Result<()> {
+    if is_event_log(tasks_path) {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-event-log-format",
+            path = tasks_path.display().to_string()
+        )));
+    }
+    if tasks_path.exists() && storage::stored_format(tasks_path)? == format {
+        out.line(&tr!(
+            "already-stored-as",
+            path = tasks_path.display().to_string(),
+            format = format.to_string()
+        ));
+        return Ok(());
+    }
+
+    let encrypted = encryption::should_encrypt(tasks_path);
+    storage::write_tasks_as(tasks, tasks_path, encrypted, format)?;
+    info!(
+        "Data file {} is now stored as {}.",
+        tasks_path.display(),
+        format
+    );
+    out.line(&tr!(
+        "now-stored-as",
+        path = tasks_path.display().to_string(),
+        format = format.to_string()
+    ));
+    Ok(())
+}
+
+/// Handles the 'renumber' command.
+/// Compacts IDs to 1..=n and prints the `old -> new` mapping.
+pub(super) fn handle_renumber(out: &mut dyn OutputSink, tasks: &mut [Task], dry_run: bool) {
+    let changes = renumber(tasks);
+
+    if changes.is_empty() {
+        out.line(&tr!("ids-sequential"));
+        return;
+    }
+
+    for (old, new) in &changes {
+        outln!(out, "{:>4} -> {}", old, new);
+    }
+    if dry_run {
+        out.line(&tr!("would-renumber", count = changes.len()));
+    } else {
+        info!("Renumbered {} tasks.", changes.len());
+        out.line(&tr!("renumbered", count = changes.len()));
+    }
+}
@@ -0,0 +1,1268 @@
+//! Runs the commands of the `tm` command line.
+//!
+//! [`run`] loads the configured task file, carries out the parsed command on
+//! it through [`TaskManager`] and the other parts of the library, writes what
+//! it has to say to an [`OutputSink`], and saves the file again. The binary
+//! only parses the arguments and reports errors.
+
+mod listing;
+mod maintenance;
+mod reports;
+mod review;
+mod sync;
+mod tasks;
+
+use chrono::Datelike;
+use clap::Parser;
+use log::{debug, error, info};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+use tracing::info_span;
+use uuid::Uuid;
+
+use crate::app::{
+    AgendaSpan, AppError, BIN_NAME, COMMAND_FAILED, Cli, ColorChoice, Commands, Config,
+    CountSelection, DESCRIPTION_WIDTH, Daemon, EXIT_EXTERNAL, EditArgs, ExportOptions, Hooks,
+    INVALID_PARAMS, JiraCommands, JsonSink, LimitBreach, LinearSearch, METHOD_NOT_FOUND,
+    NOT_OVER_RPC, OutputSink, Profiler, ProjectCommands, QuickAdd, Recovery, RejectConflicts,
+    RenderFormat, RequestGuard, Result, RolloverReport, RpcError, RpcRequest, SaveOutcome,
+    SearchBackend, SessionRecovery, Snippet, Standup, Task, TaskDiff, TaskDraft, TaskGraph,
+    TaskManager, TaskSelection, answer_mcp, append_journal, can_index, clear_session, clock,
+    fetch_remote, find_goal, find_template, goals_file_path, hooks_dir, install_interrupt_handler,
+    install_shutdown_handler, is_encrypted_file, is_remote, journal_file_path, load_goals,
+    merge_tasks, open_search_backend, pull_jira_issues, read_clipboard, recover_session,
+    refresh_index_cache, refresh_search_index, render_snippet, roll_up_progress, rollover,
+    run_plugin, search_index_path, serve_rpc, set_language, storage, take_back_from_trash,
+    trash_file_path, truncate, write_completions,
+};
+use crate::{outln, tr};
+pub use listing::{TableStyle, task_table};
+use listing::{
+    handle_board, handle_context, handle_list_projects, handle_list_tasks, handle_lists,
+    handle_next, handle_random, handle_search, handle_show_task, handle_task_contexts,
+    handle_template, local_stamp,
+};
+use maintenance::{
+    handle_audit, handle_convert_storage, handle_doctor, handle_maintenance, handle_renumber,
+    handle_set_encryption,
+};
+use reports::{
+    handle_agenda, handle_burndown, handle_calendar, handle_done, handle_escalate,
+    handle_followups, handle_forecast, handle_goal, handle_log, handle_plan, handle_remind,
+    handle_reminders, handle_start_timer, handle_stats, handle_stop_timer, handle_timesheet,
+};
+use review::{handle_review, handle_stale, handle_triage, interactive_session};
+use sync::{
+    handle_caldav_sync, handle_compare, handle_dashboard, handle_export, handle_github,
+    handle_import, handle_scan, handle_storage_sync, handle_sync, handle_watch_file,
+    print_task_diff, refresh_from_storage, upload_to_storage,
+};
+use tasks::{
+    answer_from_index, append_added_task, edit_until_valid, handle_add_lines, handle_add_note,
+    handle_add_task, handle_attach, handle_check, handle_clear_tasks, handle_complete_ids,
+    handle_count, handle_edit_in_editor, handle_edit_task, handle_mark_task_completion,
+    handle_modify_tasks, handle_move_task, handle_move_to, handle_open, handle_pick,
+    handle_project_status, handle_remove_task, handle_set_progress, handle_skip_occurrence,
+    handle_snooze_task, handle_status, handle_trash, handle_yank, purge_expired_trash,
+    resolve_task,
+};
+
+/// Runs the parsed command against the configured task file, writing its
+/// output to `out`.
+pub fn run(mut cli: Cli, out: &mut dyn OutputSink) -> Result<()> {
+    if let Some(now) = cli.now {
+        debug!("Clock pinned to {}.", now);
+        clock::set_now_override(now);
+    }
+
+    // Completion scripts don't depend on any task data.
+    if let Commands::Completions { shell } = cli.command {
+        write_completions(shell, &mut io::stdout())?;
+        return Ok(());
+    }
+
+    // Each request is run as a command of its own.
+    if let Commands::Rpc = cli.command {
+        install_interrupt_handler()?;
+        let stdin = io::stdin();
+        serve_rpc(stdin.lock(), io::stdout().lock(), |request| {
+            let _request = RequestGuard::new();
+            call_rpc(&cli, request)
+        })?;
+        return Ok(());
+    }
+
+    // Tool calls run commands the same way.
+    if let Commands::Mcp = cli.command {
+        install_interrupt_handler()?;
+        let stdin = io::stdin();
+        serve_rpc(stdin.lock(), io::stdout().lock(), |request| {
+            let _request = RequestGuard::new();
+            answer_mcp(request, |args| run_nested(&cli, args))
+        })?;
+        return Ok(());
+    }
+
+    // Comparing works on the given files, not the data file.
+    if let Commands::Compare {
+        before,
+        after,
+        format,
+    } = &cli.command
+    {
+        return handle_compare(out, before, after, *format);
+    }
+
+    let dry_run = cli.dry_run;
+    if dry_run && !cli.command.supports_dry_run() {
+        return Err(AppError::InvalidArgument(tr!("error-no-dry-run")));
+    }
+    if cli.read_only && !cli.command.supports_dry_run() {
+        return Err(AppError::InvalidArgument(tr!("error-no-read-only")));
+    }
+
+    let config = info_span!("config").in_scope(|| -> Result<Config> {
+        let mut config = Config::load()?;
+        config.apply_cli(&cli)?;
+        Ok(config)
+    })?;
+    if let Some(zone) = config.timezone {
+        debug!("Reading dates in {}.", zone);
+        clock::set_timezone(zone);
+    }
+    if let Some(language) = config.language {
+        set_language(language);
+    }
+    storage::set_default_format(config.storage_format);
+    let tasks_path = config.tasks_file_path();
+    // A data file published over HTTP(S) is read from its cached copy.
+    let remote_url = is_remote(&tasks_path).then(|| tasks_path.display().to_string());
+    if remote_url.is_some() && !cli.command.supports_dry_run() {
+        return Err(AppError::InvalidArgument(tr!("error-no-remote")));
+    }
+    let tasks_path = match &remote_url {
+        Some(url) => {
+            let copy = info_span!("fetch").in_scope(|| fetch_remote(url))?;
+            if let Some(reason) = copy.stale_because {
+                eprintln!(
+                    "{}",
+                    tr!("warning-remote-stale", url = url.as_str(), reason = reason)
+                );
+            }
+            copy.path
+        }
+        None => tasks_path,
+    };
+    let read_only = cli.read_only || remote_url.is_some() || storage::is_read_only(&tasks_path);
+    if read_only {
+        debug!("Not writing {} unless tasks change.", tasks_path.display());
+    }
+
+    // External commands work on the data file themselves.
+    if let Commands::External(args) = &cli.command {
+        let status = run_plugin(args, &tasks_path, &config)?;
+        process::exit(status.code().unwrap_or(i32::from(EXIT_EXTERNAL)));
+    }
+
+    // The watcher and the daemon load and save the store on their own, once per change.
+    if let Commands::WatchFile {
+        path,
+        once,
+        interval,
+    } = &cli.command
+    {
+        return handle_watch_file(out, &config, &tasks_path, path, *once, *interval);
+    }
+    if let Commands::Lists = cli.command {
+        return handle_lists(out, &config);
+    }
+    if let Commands::Context { command } = &cli.command {
+        return handle_context(out, command, &config);
+    }
+    if let Commands::Add(args) = &mut cli.command {
+        QuickAdd::read_into(args)?;
+    }
+    if let Commands::Add(args) = &mut cli.command
+        && !args.from_lines()
+        && let Some(name) = args.template.take()
+    {
+        find_template(&name)?.apply(args)?;
+    }
+    if let Commands::Add(args) = &mut cli.command
+        && args.editor
+    {
+        let draft = TaskDraft::from_add_args(args).render("New task");
+        let Some(edited) = edit_until_valid(out, &draft, |draft| {
+            let mut edited = args.clone();
+            draft.apply_to_add(&mut edited)?;
+            Ok(edited)
+        })?
+        else {
+            out.line(&tr!("nothing-added"));
+            return Ok(());
+        };
+        *args = edited;
+    }
+    let hooks = hooks_dir().and_then(|dir| Hooks::find(&dir));
+    let goals_path = goals_file_path(&tasks_path);
+
+    // Goals live in a file of their own, so links to them are checked here.
+    let linked_goal = match &cli.command {
+        Commands::Add(args) => args.goal,
+        Commands::Edit { changes, .. } => changes.goal,
+        _ => None,
+    };
+    if let Some(id) = linked_goal {
+        find_goal(&load_goals(&goals_path)?, id)?;
+    }
+
+    // The checks read the data file themselves, since it may not load.
+    if let Commands::Doctor { fix } = cli.command {
+        return handle_doctor(out, &tasks_path, fix);
+    }
+    if let Commands::Maintenance {
+        compact,
+        keep_backups,
+    } = cli.command
+    {
+        return handle_maintenance(out, &tasks_path, compact, keep_backups, &config);
+    }
+    if let Commands::Audit { command } = &cli.command {
+        return handle_audit(out, command, &tasks_path, &config);
+    }
+    // Remote storage holds the configured data file; --file, named lists,
+    // and repository lists stay local.
+    let remote_storage = config.sync.storage.as_ref().filter(|_| {
+        cli.file.is_none() && config.default_list.is_none() && config.repo_list.is_none()
+    });
+    if let Commands::Sync {
+        caldav: false,
+        interactive,
+    } = cli.command
+    {
+        return match remote_storage {
+            Some(storage) => handle_storage_sync(out, &tasks_path, storage, interactive),
+            None => handle_sync(out, &tasks_path, &config, interactive),
+        };
+    }
+    if let Commands::Daemon { poll } = cli.command {
+        let shutdown = install_shutdown_handler()?;
+        out.line(&tr!("daemon-running"));
+        return Daemon::new(config, tasks_path, Duration::from_secs(poll)).run(&shutdown);
+    }
+
+    if let Some(storage) = remote_storage {
+        refresh_from_storage(&tasks_path, storage);
+    }
+
+    let fallback_path = config.fallback_tasks_file_path();
+    let load_path = storage::resolve_load_path(&tasks_path, fallback_path.as_deref());
+    if load_path != tasks_path {
+        eprintln!(
+            "{}",
+            tr!(
+                "warning-loading-fallback",
+                fallback = load_path.display().to_string(),
+                path = tasks_path.display().to_string()
+            )
+        );
+    }
+    // A triage or review cut short left its edits next to the data file.
+    if !dry_run && !read_only && load_path == tasks_path {
+        report_session_recovery(out, &tasks_path)?;
+    }
+
+    // Adding only needs the IDs already in use, so a plain data file gets the
+    // new task appended instead of every task being loaded and rewritten.
+    // Subtasks need their parent loaded, and looking for duplicates all tasks.
+    if let Commands::Add(args) = &cli.command
+        && !args.from_lines()
+        && args.parent.is_none()
+        && !config.check_duplicates
+        && !args.no_duplicates
+        && !dry_run
+        && !read_only
+        && !config.audit.journal
+        && !config.limits.is_set()
+        && hooks.is_none()
+        && remote_storage.is_none()
+        && load_path == tasks_path
+        && tasks_path.exists()
+        && !is_encrypted_file(&tasks_path)
+        && !search_index_path(&tasks_path).exists()
+        && append_added_task(out, &config, &tasks_path, args.clone())?
+    {
+        return Ok(());
+    }
+
+    if config.index_cache
+        && load_path == tasks_path
+        && can_index(&tasks_path)
+        && answer_from_index(out, &cli.command, &tasks_path, &config)?
+    {
+        return Ok(());
+    }
+
+    let loaded_modified = storage::modified_time(&load_path);
+    let (mut stored, mut tasks) = info_span!("load").in_scope(|| -> Result<_> {
+        let stored = storage::read_tasks_file(&load_path)?;
+        let tasks = match &stored {
+            Some(contents) => match storage::parse_tasks(contents) {
+                Ok(tasks) => tasks,
+                Err(e) if cli.lenient => {
+                    let recovery = storage::recover_tasks(&load_path, contents, e)?;
+                    print_recovery(&load_path, &recovery);
+                    recovery.tasks
+                }
+                Err(e) => {
+                    if matches!(e, AppError::TomlDeserialize(_))
+                        && storage::parse_sections(contents).is_some()
+                    {
+                        eprintln!("{}", tr!("hint-lenient"));
+                    }
+                    return Err(e.in_file(&load_path));
+                }
+            },
+            None => Vec::new(),
+        };
+        Ok((stored, tasks))
+    })?;
+    info!("Loaded {} tasks from storage.", tasks.len());
+
+    // Counts run in shell prompts, so they skip everything that could write
+    // besides the index cache.
+    if config.index_cache
+        && matches!(cli.command, Commands::Count(_) | Commands::Status { .. })
+        && load_path == tasks_path
+        && !read_only
+    {
+        refresh_index_cache(&tasks, &tasks_path);
+    }
+    match &cli.command {
+        Commands::Count(args) => {
+            rollover(&mut tasks, clock::today());
+            let mut selection = CountSelection::from(args);
+            selection.query = config.in_context(selection.query);
+            return handle_count(out, &TaskManager::new(&mut tasks, &config), &selection);
+        }
+        Commands::Status { short } => {
+            rollover(&mut tasks, clock::today());
+            let status = TaskManager::new(&mut tasks, &config).status();
+            handle_status(out, status, *short, &config);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let report = info_span!("rollover").in_scope(|| rollover(&mut tasks, clock::today()));
+    print_rollover_banner(&tasks, &report);
+    roll_up_progress(&mut tasks);
+
+    let trash_path = trash_file_path(&tasks_path);
+    info_span!("trash")
+        .in_scope(|| purge_expired_trash(&trash_path, &config, dry_run || read_only))?;
+
+    // Reading a read-only file saves nothing, even what rollover changed; the
+    // tasks as rolled over are kept to tell whether the command changed any.
+    let rolled_over = read_only.then(|| tasks.clone());
+
+    let strict = cli.strict || config.limits.strict;
+    let unchanged = (((config.audit.journal || hooks.is_some()) && !dry_run)
+        || config.limits.is_set())
+    .then(|| tasks.clone());
+    // Interactive sessions keep their edits aside until they are saved.
+    let interactive = matches!(cli.command, Commands::Triage | Commands::Review { .. });
+    let command_span = info_span!("command").entered();
+    match cli.command {
+        Commands::Add(mut args) => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            let lines = match args.from_file.take() {
+                Some(source) if source.as_os_str() == "-" => Some(io::read_to_string(io::stdin())?),
+                Some(source) => Some(fs::read_to_string(source)?),
+                None if args.from_clipboard => Some(read_clipboard()?),
+                None => None,
+            };
+            match lines {
+                Some(text) => handle_add_lines(out, &mut manager, args, &text)?,
+                None => {
+                    let no_duplicates = args.no_duplicates;
+                    handle_add_task(out, &mut manager, args.into(), no_duplicates, &config)?
+                }
+            }
+        }
+        Commands::List(mut args) => {
+            config.list.apply(&mut args);
+            args.query = config.in_context(args.query);
+            if args.mine {
+                args.assignee = Some(config.me()?);
+            }
+            handle_list_tasks(out, &TaskManager::new(&mut tasks, &config), &args, &config)?;
+        }
+        Commands::Search {
+            query,
+            long,
+            annotations,
+        } => {
+            // The index lives next to the data file, so it can't be kept up
+            // to date either.
+            let backend: Box<dyn SearchBackend> = if read_only {
+                Box::new(LinearSearch)
+            } else {
+                open_search_backend(&tasks_path)?
+            };
+            handle_search(
+                out,
+                &tasks,
+                backend,
+                &query.join(" "),
+                long,
+                annotations,
+                &config,
+            )?;
+        }
+        Commands::Projects => {
+            handle_list_projects(out, &tasks, &config);
+        }
+        Commands::Contexts => handle_task_contexts(out, &tasks, &config),
+        Commands::Move { id, to } => {
+            handle_move_task(out, &mut tasks, id, &to, &tasks_path, &config)?;
+        }
+        Commands::Project {
+            command: ProjectCommands::Status { name },
+        } => {
+            handle_project_status(out, &tasks, &name, &config)?;
+        }
+        Commands::Complete { task } => {
+            let id = resolve_task(out, &tasks, &task)?;
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            handle_mark_task_completion(out, &mut manager, id, true, &config)?;
+        }
+        Commands::Pick { action } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            handle_pick(out, &mut manager, action, &trash_path, dry_run, &config)?;
+        }
+        Commands::Edit {
+            task,
+            editor,
+            changes,
+        } => {
+            let id = resolve_task(out, &tasks, &task)?;
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            if editor {
+                handle_edit_in_editor(out, &mut manager, id, &changes)?;
+            } else {
+                handle_edit_task(out, &mut manager, id, changes.into())?;
+            }
+        }
+        Commands::Modify {
+            filter,
+            set,
+            add_tag,
+            remove_tag,
+            all,
+            yes,
+        } => {
+            let mut changes = EditArgs {
+                tags: add_tag,
+                untag: remove_tag,
+                ..EditArgs::default()
+            };
+            for assignment in set {
+                assignment.apply_to(&mut changes);
+            }
+            let selection = TaskSelection {
+                query: Some(filter),
+                all,
+                ..TaskSelection::default()
+            };
+            handle_modify_tasks(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                &selection,
+                changes.into(),
+                yes,
+                &config,
+                dry_run,
+            )?;
+        }
+        Commands::Start { id } => {
+            handle_start_timer(out, &mut tasks, id)?;
+        }
+        Commands::Stop => {
+            handle_stop_timer(out, &mut tasks);
+        }
+        Commands::Timesheet { week } => {
+            handle_timesheet(out, &tasks, week, &config)?;
+        }
+        Commands::Snooze { id, until } => {
+            handle_snooze_task(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                id,
+                until,
+                &config,
+            )?;
+        }
+        Commands::Skip { id } => {
+            handle_skip_occurrence(out, &mut TaskManager::new(&mut tasks, &config), id, &config)?;
+        }
+        Commands::Progress { id, percent } => {
+            handle_set_progress(out, &mut TaskManager::new(&mut tasks, &config), id, percent)?;
+        }
+        Commands::Undone { id } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            handle_mark_task_completion(out, &mut manager, id, false, &config)?;
+        }
+        Commands::MoveTo { id, status } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            handle_move_to(out, &mut manager, id, status, &config)?;
+        }
+        Commands::Next { limit } => {
+            handle_next(out, &tasks, limit, &config);
+        }
+        Commands::Random {
+            tag,
+            context,
+            within,
+        } => {
+            handle_random(out, &tasks, tag, context, within, &config);
+        }
+        Commands::Template { command } => {
+            handle_template(out, &tasks, &command, &config)?;
+        }
+        Commands::Goal { command } => {
+            handle_goal(out, &mut tasks, command, &goals_path, &config)?;
+        }
+        Commands::Board { all } => {
+            handle_board(out, &tasks, all, &config);
+        }
+        Commands::Remove { task, yes } => {
+            let id = resolve_task(out, &tasks, &task)?;
+            handle_remove_task(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                id,
+                yes,
+                &trash_path,
+                dry_run,
+            )?;
+        }
+        Commands::Note { id, text } => {
+            handle_add_note(out, &mut TaskManager::new(&mut tasks, &config), id, text)?;
+        }
+        Commands::Annotate { id, text } => {
+            TaskManager::new(&mut tasks, &config).annotate(id, text)?;
+            out.line(&tr!("task-annotated", id = id));
+        }
+        Commands::Duplicate { id, due, count } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            let ids = manager.duplicate(id, due, count)?;
+            let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+            out.line(&tr!("task-duplicated", id = id, copies = ids.join(", ")));
+        }
+        Commands::Wait {
+            id,
+            waiting_on,
+            until,
+            clear,
+        } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            match waiting_on {
+                Some(what) if !clear => {
+                    manager.wait(id, what, until)?;
+                    let task = manager.get(id)?;
+                    match task.start_date.filter(|_| until.is_some()) {
+                        Some(date) => out.line(&tr!(
+                            "task-waiting-until",
+                            id = id,
+                            date = config.format_date(date)
+                        )),
+                        None => out.line(&tr!("task-waiting", id = id)),
+                    }
+                }
+                _ => {
+                    manager.stop_waiting(id)?;
+                    out.line(&tr!("task-not-waiting", id = id));
+                }
+            }
+        }
+        Commands::Followups => handle_followups(out, &tasks, &config),
+        Commands::Merge { source, target } => {
+            let merged = TaskManager::new(&mut tasks, &config).merge(source, target)?;
+            out.line(&tr!(
+                "task-merged",
+                id = merged.id,
+                description = truncate(&merged.description, DESCRIPTION_WIDTH),
+                target = target
+            ));
+        }
+        Commands::Check { command } => {
+            handle_check(out, &mut TaskManager::new(&mut tasks, &config), command)?;
+        }
+        Commands::Attach { id, target } => {
+            handle_attach(out, &mut TaskManager::new(&mut tasks, &config), id, &target)?;
+        }
+        Commands::Open { id, number } => {
+            handle_open(out, &tasks, id, number)?;
+        }
+        Commands::Yank { id, url } => {
+            handle_yank(out, &tasks, id, url)?;
+        }
+        Commands::Show { id, json } => {
+            handle_show_task(out, &tasks, id, json, &config)?;
+        }
+        Commands::Share { id, format } => {
+            let task = tasks
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| AppError::task_not_found(id, &tasks))?;
+            out.text(&render_snippet(task, format));
+        }
+        Commands::Paste { no_duplicates } => {
+            let snippet = Snippet::parse(&io::read_to_string(io::stdin())?)?;
+            handle_add_task(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                snippet.into(),
+                no_duplicates,
+                &config,
+            )?;
+        }
+        Commands::Stats {
+            json,
+            out: chart_path,
+            fun,
+        } => {
+            handle_stats(out, &tasks, json, chart_path.as_deref(), fun, &config)?;
+        }
+        Commands::Log { since, until, json } => {
+            handle_log(out, &tasks, &tasks_path, since, until, json, &config)?;
+        }
+        Commands::Calendar { month, agenda } => {
+            let first = month.unwrap_or_else(|| clock::today().with_day(1).expect("day 1 exists"));
+            handle_calendar(out, &tasks, first, agenda, &config);
+        }
+        Commands::Agenda {
+            week,
+            email,
+            to,
+            stdout,
+            ..
+        } => {
+            let span = if week {
+                AgendaSpan::Week
+            } else {
+                AgendaSpan::Day
+            };
+            handle_agenda(out, &tasks, span, email, to, stdout, &config)?;
+        }
+        Commands::Graph {
+            format,
+            project,
+            all,
+        } => {
+            out.text(
+                &TaskGraph::build(&tasks, all, project.as_deref()).render(format, config.ascii),
+            );
+        }
+        Commands::Standup { format } => {
+            out.text(&Standup::build(&tasks, clock::now_naive()).render(format));
+        }
+        Commands::Done { on, format } => {
+            handle_done(out, &tasks, on, format, &config)?;
+        }
+        Commands::Plan { date } => {
+            handle_plan(out, &tasks, date, &config)?;
+        }
+        Commands::Burndown {
+            weeks,
+            json,
+            out: chart_path,
+        } => {
+            handle_burndown(out, &tasks, weeks, json, chart_path.as_deref(), &config)?;
+        }
+        Commands::Forecast {
+            weeks,
+            ahead,
+            json,
+            out: chart_path,
+        } => {
+            handle_forecast(
+                out,
+                &tasks,
+                weeks,
+                ahead,
+                json,
+                chart_path.as_deref(),
+                &config,
+            )?;
+        }
+        Commands::Remind {
+            id: Some(id),
+            at,
+            clear,
+        } => {
+            let mut manager = TaskManager::new(&mut tasks, &config);
+            if clear {
+                manager.clear_alerts(id)?;
+                out.line(&tr!("reminders-cleared", id = id));
+            } else {
+                manager.remind_at(id, at)?;
+                let reminders = manager
+                    .get(id)?
+                    .alerts
+                    .iter()
+                    .map(|alert| alert.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                out.line(&tr!("reminders-set", id = id, reminders = reminders));
+            }
+        }
+        Commands::Remind { id: None, .. } => {
+            handle_remind(out, &mut tasks, &config, dry_run);
+        }
+        Commands::Reminders => handle_reminders(out, &tasks, &config),
+        Commands::Escalate => {
+            handle_escalate(out, &mut tasks, &config, dry_run);
+        }
+        Commands::Renumber => {
+            handle_renumber(out, &mut tasks, dry_run);
+        }
+        Commands::Triage => {
+            let session = interactive_session("triage", &tasks, &tasks_path, &config, dry_run)?;
+            handle_triage(
+                out,
+                &mut tasks,
+                config.date_order,
+                &trash_path,
+                dry_run,
+                session,
+            )?;
+        }
+        Commands::Review { stale_after } => {
+            let session = interactive_session("review", &tasks, &tasks_path, &config, dry_run)?;
+            handle_review(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                stale_after,
+                &trash_path,
+                dry_run,
+                session,
+            )?;
+        }
+        Commands::Stale { older_than } => {
+            handle_stale(
+                out,
+                &mut TaskManager::new(&mut tasks, &config),
+                older_than,
+                &trash_path,
+                dry_run,
+            )?;
+        }
+        Commands::Export {
+            format,
+            anonymize,
+            only_pending,
+            project,
+            from,
+            to,
+            output,
+        } => {
+            let options = ExportOptions {
+                only_pending,
+                from,
+                to,
+                project,
+                anonymize,
+            };
+            handle_export(out, &tasks, format, &options, output, &tasks_path, &config)?;
+        }
+        Commands::Import { from, path, merge } => {
+            handle_import(out, &mut tasks, &tasks_path, from, path, merge, &config)?;
+        }
+        Commands::Github { command } => {
+            handle_github(out, &mut tasks, command, &config)?;
+        }
+        Commands::Jira {
+            command: JiraCommands::Pull,
+        } => {
+            let report = pull_jira_issues(&mut tasks, &config.jira)?;
+            out.line(&tr!(
+                "imported",
+                source = "Jira",
+                added = report.added,
+                updated = report.updated
+            ));
+            if report.completed > 0 {
+                out.line(&tr!(
+                    "import-completed",
+                    count = report.completed,
+                    source = "Jira"
+                ));
+            }
+        }
+        Commands::Scan { path } => {
+            handle_scan(out, &mut tasks, &tasks_path, path, dry_run)?;
+        }
+        Commands::Dashboard { out: out_dir } => {
+            handle_dashboard(out, &tasks, &out_dir)?;
+        }
+        Commands::Completions { .. }
+        | Commands::Compare { .. }
+        | Commands::Sync { caldav: false, .. }
+        | Commands::Doctor { .. }
+        | Commands::Maintenance { .. }
+        | Commands::Lists
+        | Commands::Context { .. }
+        | Commands::Audit { .. }
+        | Commands::WatchFile { .. }
+        | Commands::Daemon { .. }
+        | Commands::Rpc
+        | Commands::Mcp
+        | Commands::External(_) => {
+            unreachable!("handled before loading tasks")
+        }
+        Commands::Count(_) | Commands::Status { .. } => {
+            unreachable!("handled right after loading tasks")
+        }
+        Commands::CompleteIds => {
+            handle_complete_ids(out, &tasks);
+        }
+        Commands::Trash { command } => {
+            let mut ids = config.id_strategy.generator();
+            handle_trash(
+                out,
+                &mut tasks,
+                ids.as_mut(),
+                command,
+                &trash_path,
+                &config,
+                dry_run,
+            )?;
+        }
+        Commands::Clear { yes } => {
+            handle_clear_tasks(out, &mut tasks, yes, &trash_path, dry_run)?;
+        }
+        Commands::Sync {
+            caldav: true,
+            interactive,
+        } => {
+            handle_caldav_sync(
+                out,
+                &mut tasks,
+                &tasks_path,
+                &trash_path,
+                &config,
+                interactive,
+            )?;
+        }
+        Commands::Encrypt => {
+            handle_set_encryption(out, &tasks, &tasks_path, &trash_path, true)?;
+        }
+        Commands::Decrypt => {
+            handle_set_encryption(out, &tasks, &tasks_path, &trash_path, false)?;
+        }
+        Commands::ConvertStorage { format } => {
+            let format = format.unwrap_or(config.storage_format);
+            handle_convert_storage(out, &tasks, &tasks_path, format)?;
+        }
+    }
+
+    drop(command_span);
+    roll_up_progress(&mut tasks);
+
+    if let Some(rolled_over) = rolled_over {
+        if storage::serialize_tasks(&rolled_over)? == storage::serialize_tasks(&tasks)? {
+            info!("Nothing changed in read-only {}.", tasks_path.display());
+            return Ok(());
+        }
+        if cli.read_only {
+            return Err(AppError::InvalidArgument(tr!("error-read-only-changed")));
+        }
+        if let Some(url) = remote_url {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-remote-changed",
+                url = url
+            )));
+        }
+    }
+
+    if let Some(before) = &unchanged {
+        check_limits(before, &tasks, strict, &config)?;
+    }
+    if let Some(before) = unchanged.filter(|_| !dry_run) {
+        if let Some(hooks) = &hooks
+            && let Err(e) = hooks.run(&before, &tasks)
+        {
+            // The removals are vetoed too, so their tasks leave the trash again.
+            let kept: HashSet<Uuid> = tasks.iter().map(|t| t.uuid).collect();
+            let removed: HashSet<Uuid> = before
+                .iter()
+                .map(|t| t.uuid)
+                .filter(|uuid| !kept.contains(uuid))
+                .collect();
+            take_back_from_trash(&trash_path, &removed)?;
+            return Err(e);
+        }
+        if config.audit.journal {
+            record_in_journal(&before, &tasks, &tasks_path, &config)?;
+        }
+    }
+
+    // Another process may have saved the file since it was loaded; merge its
+    // changes by UUID rather than overwriting them.
+    if !dry_run && load_path == tasks_path {
+        let current = info_span!("merge").in_scope(|| {
+            storage::changed_since_load(&tasks_path, stored.as_deref(), loaded_modified)
+        })?;
+        if let Some(current) = current {
+            let base = match &stored {
+                Some(contents) => storage::parse_tasks(contents)?,
+                None => Vec::new(),
+            };
+            let theirs = storage::parse_tasks(&current)?;
+            tasks =
+                merge_tasks(&base, &tasks, &theirs, &mut RejectConflicts).map_err(|e| match e {
+                    AppError::Conflict(reason) => AppError::Conflict(tr!(
+                        "error-saved-meanwhile",
+                        path = tasks_path.display().to_string(),
+                        reason = reason
+                    )),
+                    e => e,
+                })?;
+            eprintln!(
+                "{}",
+                tr!("note-merged", path = tasks_path.display().to_string())
+            );
+            stored = Some(current);
+        }
+    }
+
+    // Leave the file alone when nothing changed, so read-only commands stay
+    // cheap and don't touch its modification time. Tasks loaded from the
+    // fallback file are still written back to the data file.
+    let up_to_date = info_span!("compare").in_scope(|| -> Result<bool> {
+        Ok(load_path == tasks_path
+            && match &stored {
+                Some(contents) => storage::is_up_to_date(contents, &tasks)?,
+                None => tasks.is_empty(),
+            })
+    })?;
+    if dry_run {
+        return report_dry_run(out, stored.as_deref(), &tasks, &tasks_path, up_to_date);
+    }
+    if up_to_date {
+        if interactive {
+            clear_session(&tasks_path)?;
+        }
+        info!("No changes to save. Application finished.");
+        return Ok(());
+    }
+
+    // The first save to a named list creates the lists directory.
+    if config.default_list.is_some() {
+        fs::create_dir_all(config.lists_dir())?;
+    }
+    let outcome = info_span!("save").in_scope(|| {
+        storage::save_tasks_with_fallback(&tasks, &tasks_path, fallback_path.as_deref())
+    })?;
+    let saved = matches!(outcome, SaveOutcome::Saved);
+    if interactive && saved {
+        clear_session(&tasks_path)?;
+    }
+    if let SaveOutcome::FellBack { path, reason } = outcome {
+        eprintln!(
+            "{}",
+            tr!(
+                "warning-saved-to-fallback",
+                path = tasks_path.display().to_string(),
+                reason = reason.to_string(),
+                fallback = path.display().to_string()
+            )
+        );
+        eprintln!(
+            "{}",
+            tr!(
+                "warning-fallback-until-writable",
+                path = tasks_path.display().to_string()
+            )
+        );
+    }
+    if let Err(e) = refresh_search_index(&tasks, &tasks_path) {
+        error!("Could not update the search index: {}", e);
+    }
+    if config.index_cache && saved {
+        refresh_index_cache(&tasks, &tasks_path);
+    }
+    if let Some(storage) = remote_storage
+        && saved
+    {
+        upload_to_storage(&tasks_path, storage);
+    }
+    info!("Tasks saved to storage. Application finished.");
+
+    Ok(())
+}
+
+/// Replays the session file an interrupted `triage` or `review` left next
+/// to the data file at `tasks_path`, and says what became of it.
+fn report_session_recovery(out: &mut dyn OutputSink, tasks_path: &Path) -> Result<()> {
+    match recover_session(tasks_path)? {
+        None => {}
+        Some(SessionRecovery::Replayed(snapshot)) => out.line(&tr!(
+            "session-recovered",
+            command = snapshot.command.as_str(),
+            started = local_stamp(snapshot.started)
+        )),
+        Some(SessionRecovery::SetAside { snapshot, backup }) => eprintln!(
+            "{}",
+            tr!(
+                "warning-session-set-aside",
+                command = snapshot.command.as_str(),
+                path = tasks_path.display().to_string(),
+                backup = backup.display().to_string()
+            )
+        ),
+    }
+    Ok(())
+}
+
+/// Reports the tasks a lenient load of the data file at `path` left out.
+fn print_recovery(path: &Path, recovery: &Recovery) {
+    eprintln!(
+        "{}",
+        tr!(
+            "warning-skipped-unreadable",
+            count = recovery.skipped.len(),
+            path = path.display().to_string()
+        )
+    );
+    for section in &recovery.skipped {
+        eprintln!(
+            "  {}",
+            tr!(
+                "line-error",
+                line = section.line,
+                error = section.error.as_str()
+            )
+        );
+    }
+    eprintln!(
+        "{}",
+        tr!(
+            "original-backed-up",
+            path = recovery.backup.display().to_string()
+        )
+    );
+}
+
+/// Prints the phase timings collected for `--profile` to stderr.
+pub fn print_profile(profiler: &Profiler) {
+    eprintln!();
+    eprintln!("Profile:");
+    for timing in profiler.timings() {
+        let name = format!("{}{}", "  ".repeat(timing.depth), timing.name);
+        eprintln!(
+            "  {:<16} {:>10.3} ms",
+            name,
+            timing.elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    eprintln!(
+        "  {:<16} {:>10.3} ms",
+        "total",
+        profiler.total().as_secs_f64() * 1000.0
+    );
+}
+
+/// Prints what changed during the startup rollover, if anything.
+/// Goes to stderr so it never mixes into exported or JSON output.
+fn print_rollover_banner(tasks: &[Task], report: &RolloverReport) {
+    if report.is_empty() {
+        return;
+    }
+
+    eprintln!("{}", tr!("tasks-expired", count = report.expired.len()));
+    for task in tasks.iter().filter(|t| report.expired.contains(&t.id)) {
+        eprintln!(
+            "  {:<4} {}",
+            task.id,
+            truncate(&task.description, DESCRIPTION_WIDTH)
+        );
+    }
+    eprintln!();
+}
+
+/// Prints how the command would have changed the data file under `--dry-run`.
+fn report_dry_run(
+    out: &mut dyn OutputSink,
+    stored: Option<&[u8]>,
+    tasks: &[Task],
+    tasks_path: &Path,
+    up_to_date: bool,
+) -> Result<()> {
+    if up_to_date {
+        out.line(&tr!(
+            "dry-run-unchanged",
+            path = tasks_path.display().to_string()
+        ));
+        return Ok(());
+    }
+    let before = match stored {
+        Some(contents) => storage::parse_tasks(contents)?,
+        None => Vec::new(),
+    };
+    out.line(&tr!(
+        "dry-run-write",
+        path = tasks_path.display().to_string()
+    ));
+    print_task_diff(out, &TaskDiff::between(&before, tasks)?);
+    Ok(())
+}
+
+/// Warns about the limits under `[limits]` the command went past, or with
+/// `strict` fails before anything is saved.
+fn check_limits(before: &[Task], after: &[Task], strict: bool, config: &Config) -> Result<()> {
+    let breaches: Vec<String> = config
+        .limits
+        .breaches(before, after)
+        .into_iter()
+        .map(|breach| match breach {
+            LimitBreach::InProgress { count, limit } => {
+                tr!("limit-in-progress", count = count, limit = limit)
+            }
+            LimitBreach::DuePerDay { date, count, limit } => tr!(
+                "limit-due-per-day",
+                count = count,
+                date = config.format_date(date),
+                limit = limit
+            ),
+        })
+        .collect();
+    if strict && !breaches.is_empty() {
+        return Err(AppError::Limit(breaches.join("; ")));
+    }
+    for breach in breaches {
+        eprintln!("{}", tr!("warning-limit", breach = breach));
+    }
+    Ok(())
+}
+
+/// Appends the changes the command made to the audit journal, if any.
+fn record_in_journal(
+    before: &[Task],
+    after: &[Task],
+    tasks_path: &Path,
+    config: &Config,
+) -> Result<()> {
+    let changes = TaskDiff::between(before, after)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let command: Vec<String> = std::env::args().skip(1).collect();
+    let entry = append_journal(
+        &journal_file_path(tasks_path),
+        &command.join(" "),
+        config.actor(),
+        changes,
+        clock::now(),
+        &config.audit,
+    )?;
+    debug!("Recorded journal entry {}.", entry.seq);
+    Ok(())
+}
+
+/// Runs the command `request` names, collecting its output.
+fn call_rpc(rpc: &Cli, request: &RpcRequest) -> std::result::Result<JsonSink, RpcError> {
+    if NOT_OVER_RPC.contains(&request.method.as_str()) {
+        return Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("`{}` can't run over rpc", request.method),
+        ));
+    }
+    let mut args = vec![request.method.clone()];
+    args.extend(request.args()?);
+    run_nested(rpc, &args)
+}
+
+/// Runs the command line `args` with the global options of `server`, unless
+/// `args` gives its own, collecting its output. Tables come back as JSON
+/// unless `args` asks for another output format.
+fn run_nested(server: &Cli, args: &[String]) -> std::result::Result<JsonSink, RpcError> {
+    let args = std::iter::once(BIN_NAME).chain(args.iter().map(String::as_str));
+    let mut cli = Cli::try_parse_from(args).map_err(|e| {
+        let code = match e.kind() {
+            clap::error::ErrorKind::InvalidSubcommand => METHOD_NOT_FOUND,
+            _ => INVALID_PARAMS,
+        };
+        RpcError::new(code, e.render().to_string().trim_end())
+    })?;
+    if cli.file.is_none() && cli.list.is_none() {
+        cli.file = server.file.clone();
+        cli.list = server.list.clone();
+    }
+    cli.now = cli.now.or(server.now);
+    cli.color = cli.color.or(server.color).or(Some(ColorChoice::Never));
+    cli.output_format = cli
+        .output_format
+        .or(server.output_format)
+        .or(Some(RenderFormat::Json));
+    cli.date_format = cli.date_format.or_else(|| server.date_format.clone());
+    cli.dry_run |= server.dry_run;
+    cli.lenient |= server.lenient;
+    cli.read_only |= server.read_only;
+
+    let mut sink = JsonSink::default();
+    run(cli, &mut sink).map_err(|e| RpcError::new(COMMAND_FAILED, e.to_string()))?;
+    Ok(sink)
+}
+
+/// Prints `message` and reads one trimmed line of input.
+///
+/// Returns `None` when the input stream is closed.
+fn prompt(input: &mut impl BufRead, message: &str) -> Result<Option<String>> {
+    print!("{}", message);
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Asks a yes/no question on the terminal; anything but `y`, `yes`, or a
+/// yes in the active language means no.
+fn confirm(question: &str) -> Result<bool> {
+    let message = format!("{} {} ", question, tr!("confirm-choices"));
+    let answer = prompt(&mut io::stdin().lock(), &message)?;
+    let yes = tr!("confirm-yes");
+    Ok(answer.is_some_and(|a| {
+        yes.split_whitespace()
+            .chain(["y", "yes"])
+            .any(|word| word.eq_ignore_ascii_case(&a))
+    }))
+}
+
+/// Prints `label: value` lines with the values lined up after the widest
+/// label, which differs by language.
+fn print_fields(out: &mut dyn OutputSink, fields: &[(String, String)]) {
+    let width = fields
+        .iter()
+        .map(|(label, _)| label.chars().count() + 1)
+        .max()
+        .unwrap_or(0);
+    for (label, value) in fields {
+        outln!(
+            out,
+            "{:<width$} {}",
+            format!("{}:", label),
+            value,
+            width = width
+        );
+    }
+}
@@ -0,0 +1,986 @@
+//! Reports over the task list: stats, logs, agendas, plans, goals, time
+//! sheets, and reminders.
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc};
+use log::{error, info};
+use std::path::Path;
+
+use super::listing::{local_stamp, priority_cell, renderer};
+use crate::app::{
+    Agenda, AgendaSpan, AppError, Burndown, Cell, ChangeSource, Changelog, Color, Column, Config,
+    DESCRIPTION_WIDTH, DayMark, DayPlan, DoneLog, DoneLogFormat, DueSpec, Forecast, Goal,
+    GoalCommands, GoalStatus, ListColumn, MonthAgenda, OutputSink, PeriodCount, Priority, Result,
+    STATS_DAYS, STATS_WEEKS, Stats, Table, Task, Timesheet, build_reminders, check_chart_path,
+    clock, deliver_reminder, find_goal, format_elapsed, format_minutes, load_goals, next_goal_id,
+    pad, paint, pending_escalations, progress_bar, render_chart, save_goals, send_email,
+    sorted_levels, sparkline, start_timer, stop_timer, truncate, upcoming_reminders,
+};
+use crate::{outln, tr};
+
+/// Handles the 'start' command.
+pub(super) fn handle_start_timer(
+    out: &mut dyn OutputSink,
+    tasks: &mut [Task],
+    id: u32,
+) -> Result<()> {
+    let now = clock::now();
+    if let Some(stopped) = start_timer(tasks, id, now)? {
+        out.line(&tr!("timer-stopped", id = stopped));
+    }
+    info!("Started timer on task ID {}.", id);
+    out.line(&tr!("timer-started", id = id));
+    Ok(())
+}
+
+/// Handles the 'stop' command.
+pub(super) fn handle_stop_timer(out: &mut dyn OutputSink, tasks: &mut [Task]) {
+    match stop_timer(tasks, clock::now()) {
+        Some((id, elapsed)) => out.line(&tr!(
+            "timer-stopped-after",
+            id = id,
+            elapsed = format_elapsed(elapsed)
+        )),
+        None => out.line(&tr!("no-timer-running")),
+    }
+}
+
+/// Handles the 'timesheet' command.
+pub(super) fn handle_timesheet(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    week: DueSpec,
+    config: &Config,
+) -> Result<()> {
+    let now = clock::now();
+    let date = week
+        .with_order(config.date_order)
+        .map_err(AppError::InvalidArgument)?
+        .resolve(clock::local_date(now))
+        .unwrap_or(clock::local_date(now));
+    let sheet = Timesheet::for_week(tasks, date, now);
+    out.line(&tr!("week-of", date = config.format_date(sheet.monday)));
+    if sheet.rows.is_empty() {
+        out.line(&tr!("no-time-tracked"));
+        return Ok(());
+    }
+
+    let cell = |d: chrono::Duration| {
+        if d.is_zero() {
+            "-".to_string()
+        } else {
+            format_elapsed(d)
+        }
+    };
+    let weekdays: Vec<String> = [
+        tr!("weekday-mon"),
+        tr!("weekday-tue"),
+        tr!("weekday-wed"),
+        tr!("weekday-thu"),
+        tr!("weekday-fri"),
+        tr!("weekday-sat"),
+        tr!("weekday-sun"),
+    ]
+    .iter()
+    .map(|day| format!("{:>7}", day))
+    .collect();
+    outln!(
+        out,
+        "{:<4} {} {} {:>8}",
+        tr!("column-id"),
+        pad(&tr!("column-description"), DESCRIPTION_WIDTH),
+        weekdays.join(" "),
+        tr!("column-total")
+    );
+    for row in &sheet.rows {
+        let days: Vec<String> = row
+            .days
+            .iter()
+            .map(|&d| format!("{:>7}", cell(d)))
+            .collect();
+        outln!(
+            out,
+            "{:<4} {} {} {:>8}",
+            row.task_id,
+            pad(
+                &truncate(&row.description, DESCRIPTION_WIDTH),
+                DESCRIPTION_WIDTH
+            ),
+            days.join(" "),
+            format_elapsed(row.total())
+        );
+    }
+    let totals = sheet.day_totals();
+    let days: Vec<String> = totals.iter().map(|&d| format!("{:>7}", cell(d))).collect();
+    outln!(
+        out,
+        "{:<33} {} {:>8}",
+        tr!("column-total"),
+        days.join(" "),
+        format_elapsed(totals.iter().copied().sum())
+    );
+    Ok(())
+}
+
+/// Handles the 'stats' command.
+pub(super) fn handle_stats(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    json: bool,
+    chart_path: Option<&Path>,
+    fun: bool,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = chart_path {
+        check_chart_path(path)?;
+    }
+    let mut stats = Stats::build(tasks, clock::now()).with_weekly_goal(tasks, config.weekly_goal);
+    if fun {
+        stats = stats.with_streaks(tasks);
+    }
+    if let Some(path) = chart_path {
+        render_chart(&stats.chart(), path)?;
+        info!("Wrote stats chart to {}.", path.display());
+    }
+
+    if json {
+        out.value(&serde_json::to_value(&stats)?);
+        return Ok(());
+    }
+
+    out.line(&tr!(
+        "stats-tasks",
+        total = stats.total,
+        pending = stats.pending,
+        completed = stats.completed,
+        cancelled = stats.cancelled
+    ));
+    out.line(&tr!("stats-overdue", count = stats.overdue));
+    if let Some(goal) = &stats.weekly_goal {
+        out.line(&tr!(
+            "stats-weekly-goal",
+            progress = goal.to_string(),
+            met = if goal.is_met() { "yes" } else { "no" }
+        ));
+    }
+    out.line(&tr!(
+        "stats-average",
+        time = stats
+            .average_completion_hours
+            .map_or("N/A".to_string(), format_hours)
+    ));
+    if let Some(streaks) = &stats.streaks {
+        out.line(&tr!(
+            "stats-streak",
+            current = streaks.current,
+            longest = streaks.longest
+        ));
+        out.line(&tr!(
+            "stats-points",
+            today = streaks.points_today,
+            week = streaks.points_this_week,
+            total = streaks.points_total
+        ));
+    }
+
+    outln!(out);
+    out.line(&tr!("stats-per-day", days = STATS_DAYS));
+    print_period_counts(out, &stats.completed_per_day, config);
+
+    outln!(out);
+    out.line(&tr!("stats-per-week", weeks = STATS_WEEKS));
+    print_period_counts(out, &stats.completed_per_week, config);
+
+    for (title, breakdown) in [
+        (tr!("stats-by-project"), &stats.by_project),
+        (tr!("stats-by-tag"), &stats.by_tag),
+    ] {
+        outln!(out);
+        outln!(out, "{}", title);
+        if breakdown.is_empty() {
+            outln!(out, "  {}", tr!("none"));
+        }
+        for entry in breakdown {
+            outln!(
+                out,
+                "  {} {}",
+                pad(&truncate(&entry.name, 20), 20),
+                tr!(
+                    "stats-breakdown",
+                    pending = format!("{:>3}", entry.pending),
+                    completed = format!("{:>3}", entry.completed)
+                )
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handles the 'log' command.
+pub(super) fn handle_log(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    tasks_path: &Path,
+    since: Option<DueSpec>,
+    until: Option<DueSpec>,
+    json: bool,
+    config: &Config,
+) -> Result<()> {
+    // Days are local, so each bound is the local midnight starting it.
+    let today = clock::today();
+    let bound = |spec: Option<DueSpec>, days_after: u64| -> Result<Option<DateTime<Utc>>> {
+        let Some(spec) = spec else {
+            return Ok(None);
+        };
+        let date = spec
+            .with_order(config.date_order)
+            .map_err(AppError::InvalidArgument)?
+            .resolve(today)
+            .and_then(|d| d.checked_add_days(Days::new(days_after)))
+            .ok_or_else(|| AppError::InvalidArgument(spec.to_string()))?;
+        Ok(Some(clock::from_local(date.and_time(NaiveTime::MIN))))
+    };
+    let log = Changelog::read(tasks_path, tasks)?.within(bound(since, 0)?, bound(until, 1)?);
+    if json {
+        out.value(&serde_json::to_value(&log)?);
+        return Ok(());
+    }
+    if log.source == ChangeSource::Timestamps {
+        out.line(&tr!("log-from-timestamps"));
+    }
+    if log.entries.is_empty() {
+        out.line(&tr!("log-empty"));
+    }
+    for entry in &log.entries {
+        let mut line = format!(
+            "{}  {:<9}  [{}] {}",
+            local_stamp(entry.at),
+            entry.kind.label(),
+            entry.id,
+            entry.description
+        );
+        if !entry.fields.is_empty() {
+            line.push_str(&format!(" ({})", entry.fields.join(", ")));
+        }
+        if let Some(by) = &entry.by {
+            line.push_str(&format!(" {} {}", if config.ascii { "-" } else { "—" }, by));
+        }
+        outln!(out, "{}", line);
+    }
+    Ok(())
+}
+
+/// Handles the 'agenda' command.
+/// Prints the agenda over `span`, or emails it to `to`.
+pub(super) fn handle_agenda(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    span: AgendaSpan,
+    email: bool,
+    to: Option<String>,
+    stdout: bool,
+    config: &Config,
+) -> Result<()> {
+    let agenda = Agenda::build(tasks, clock::now_naive(), span);
+    if stdout {
+        if !agenda.is_empty() {
+            out.text(&agenda.body(config));
+        }
+        return Ok(());
+    }
+    if !email {
+        outln!(out, "{}", agenda.subject(config));
+        if !agenda.is_empty() {
+            outln!(out);
+            out.text(&agenda.body(config));
+        }
+        return Ok(());
+    }
+
+    let to = to
+        .or_else(|| config.agenda.to.clone())
+        .ok_or_else(|| AppError::InvalidArgument(tr!("error-no-agenda-recipient")))?;
+    if agenda.is_empty() {
+        out.line(&tr!("agenda-nothing-due"));
+        return Ok(());
+    }
+    send_email(
+        &config.smtp,
+        &to,
+        &agenda.subject(config),
+        &agenda.body(config),
+    )?;
+    out.line(&tr!("agenda-emailed", to = to.as_str()));
+    Ok(())
+}
+
+/// Handles the 'goal' command.
+pub(super) fn handle_goal(
+    out: &mut dyn OutputSink,
+    tasks: &mut [Task],
+    command: GoalCommands,
+    path: &Path,
+    config: &Config,
+) -> Result<()> {
+    let mut goals = load_goals(path)?;
+    match command {
+        GoalCommands::Add { title, due } => {
+            let due = due
+                .map(|d| d.with_order(config.date_order))
+                .transpose()
+                .map_err(AppError::InvalidArgument)?
+                .and_then(|d| d.resolve(clock::today()));
+            let id = next_goal_id(&goals);
+            goals.push(Goal { id, title, due });
+            save_goals(&goals, path)?;
+            out.line(&tr!("goal-added", id = id));
+        }
+        GoalCommands::Remove { id } => {
+            find_goal(&goals, id)?;
+            goals.retain(|g| g.id != id);
+            save_goals(&goals, path)?;
+            let mut unlinked = 0;
+            for task in tasks.iter_mut().filter(|t| t.goal == Some(id)) {
+                task.goal = None;
+                unlinked += 1;
+            }
+            out.line(&tr!("goal-removed", id = id, count = unlinked));
+        }
+        GoalCommands::List => {
+            if goals.is_empty() && config.output_format.for_humans() {
+                out.line(&tr!("no-goals"));
+                return Ok(());
+            }
+            let mut table = Table::new(vec![
+                Column {
+                    key: "id",
+                    title: tr!("column-id"),
+                    width: ListColumn::Id.default_width(),
+                },
+                Column {
+                    key: "goal",
+                    title: tr!("column-goal"),
+                    width: DESCRIPTION_WIDTH,
+                },
+                Column {
+                    key: "due",
+                    title: tr!("column-due-date"),
+                    width: ListColumn::Due.default_width(),
+                },
+                Column {
+                    key: "done",
+                    title: tr!("column-tasks-done"),
+                    width: 10,
+                },
+                Column {
+                    key: "progress",
+                    title: tr!("column-progress"),
+                    width: ListColumn::Progress.default_width(),
+                },
+            ]);
+            for goal in &goals {
+                let progress = GoalStatus::of(goal, tasks);
+                table.push(vec![
+                    Cell::new(goal.id),
+                    Cell::new(goal.title.as_str()),
+                    goal.due.map_or(Cell::missing("N/A"), |d| {
+                        Cell::new(d.to_string()).shown_as(config.format_date(d))
+                    }),
+                    Cell::new(progress.done)
+                        .shown_as(format!("{}/{}", progress.done, progress.total)),
+                    progress.percent().map_or(Cell::missing("N/A"), |percent| {
+                        Cell::new(percent).shown_as(progress_bar(percent))
+                    }),
+                ]);
+            }
+            renderer(config).table(out, &table);
+        }
+    }
+    Ok(())
+}
+
+/// Handles the 'done' command, printing the tasks completed on a day.
+pub(super) fn handle_done(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    on: DueSpec,
+    format: DoneLogFormat,
+    config: &Config,
+) -> Result<()> {
+    let today = clock::today();
+    let date = on
+        .with_order(config.date_order)
+        .map_err(AppError::InvalidArgument)?
+        .resolve(today)
+        .unwrap_or(today);
+    let log = DoneLog::build(tasks, date);
+    if log.tasks.is_empty() && format == DoneLogFormat::Text {
+        out.line(&tr!("done-log-empty", date = config.format_date(date)));
+        return Ok(());
+    }
+    out.text(&log.render(format, &config.format_date(date), clock::now()));
+    Ok(())
+}
+
+/// Handles the 'plan' command.
+pub(super) fn handle_plan(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    date: DueSpec,
+    config: &Config,
+) -> Result<()> {
+    let today = clock::today();
+    let date = date
+        .with_order(config.date_order)
+        .map_err(AppError::InvalidArgument)?
+        .resolve(today)
+        .unwrap_or(today);
+    let plan = DayPlan::build(tasks, date);
+    let capacity = config.plan.capacity_minutes();
+    let for_humans = config.output_format.for_humans();
+    if plan.tasks.is_empty() && for_humans {
+        out.line(&tr!("plan-nothing-due", date = config.format_date(date)));
+        return Ok(());
+    }
+
+    if for_humans {
+        out.line(&tr!(
+            "plan-heading",
+            date = config.format_date(date),
+            planned = format_minutes(plan.planned_minutes),
+            capacity = format_minutes(capacity)
+        ));
+    }
+    let mut table = Table::new(vec![
+        Column {
+            key: "id",
+            title: tr!("column-id"),
+            width: ListColumn::Id.default_width(),
+        },
+        Column {
+            key: "description",
+            title: tr!("column-description"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "time",
+            title: tr!("column-time"),
+            width: 5,
+        },
+        Column {
+            key: "estimate",
+            title: tr!("column-estimate"),
+            width: 8,
+        },
+    ]);
+    for task in &plan.tasks {
+        table.push(vec![
+            Cell::new(task.id),
+            Cell::new(task.description.as_str()),
+            task.due_time.map_or(Cell::missing(""), |t| {
+                Cell::new(t.format("%H:%M").to_string())
+            }),
+            task.estimate.map_or(Cell::missing("N/A"), |e| {
+                Cell::new(e.minutes()).shown_as(e.to_string())
+            }),
+        ]);
+    }
+    renderer(config).table(out, &table);
+    if !for_humans {
+        return Ok(());
+    }
+    if plan.unestimated > 0 {
+        out.line(&tr!("plan-unestimated", count = plan.unestimated));
+    }
+    if let Some(over) = plan.overload(capacity) {
+        out.line(&tr!(
+            "plan-overloaded",
+            over = format_minutes(over),
+            capacity = format_minutes(capacity)
+        ));
+    }
+    Ok(())
+}
+
+/// Handles the 'followups' command: lists the pending tasks waiting on
+/// something, those to follow up on soonest first.
+pub(super) fn handle_followups(out: &mut dyn OutputSink, tasks: &[Task], config: &Config) {
+    let mut waiting: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.is_pending() && t.waiting_on.is_some())
+        .collect();
+    waiting.sort_by_key(|t| (t.start_date.is_none(), t.start_date, t.id));
+    if waiting.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-followups"));
+        return;
+    }
+    let mut table = Table::new(vec![
+        Column {
+            key: "id",
+            title: tr!("column-id"),
+            width: ListColumn::Id.default_width(),
+        },
+        Column {
+            key: "description",
+            title: tr!("column-description"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "waiting_on",
+            title: tr!("column-waiting-on"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "until",
+            title: tr!("column-until"),
+            width: 11,
+        },
+    ]);
+    for task in waiting {
+        table.push(vec![
+            Cell::new(task.id),
+            Cell::new(task.description.as_str()),
+            Cell::new(task.waiting_on.as_deref().unwrap_or_default()),
+            task.start_date.map_or(Cell::missing("N/A"), |date| {
+                Cell::new(date.to_string()).shown_as(config.format_date(date))
+            }),
+        ]);
+    }
+    renderer(config).table(out, &table);
+}
+
+/// Handles the 'reminders' command.
+pub(super) fn handle_reminders(out: &mut dyn OutputSink, tasks: &[Task], config: &Config) {
+    let upcoming = upcoming_reminders(tasks, clock::now());
+    if upcoming.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!("no-upcoming-reminders"));
+        return;
+    }
+    let mut table = Table::new(vec![
+        Column {
+            key: "at",
+            title: tr!("column-reminder"),
+            width: 17,
+        },
+        Column {
+            key: "id",
+            title: tr!("column-id"),
+            width: ListColumn::Id.default_width(),
+        },
+        Column {
+            key: "description",
+            title: tr!("column-description"),
+            width: DESCRIPTION_WIDTH,
+        },
+    ]);
+    for (at, task) in upcoming {
+        let local = clock::local(at);
+        table.push(vec![
+            Cell::new(at.to_rfc3339()).shown_as(format!(
+                "{} {}",
+                config.format_date(local.date()),
+                local.format("%H:%M")
+            )),
+            Cell::new(task.id),
+            Cell::new(task.description.as_str()),
+        ]);
+    }
+    renderer(config).table(out, &table);
+}
+
+pub(super) fn handle_calendar(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    first: NaiveDate,
+    agenda: bool,
+    config: &Config,
+) {
+    let month = MonthAgenda::build(tasks, first);
+    if agenda {
+        print_agenda(out, &month, config);
+        return;
+    }
+
+    let now = clock::now_naive();
+    let color = config.color.enabled();
+    let title = format!("{:^40}", first.format("%B %Y").to_string());
+    outln!(out, "{}", title.trim_end());
+    out.line(&tr!("calendar-weekdays"));
+    for week in month.weeks() {
+        let cells: Vec<String> = week
+            .iter()
+            .map(|day| {
+                let Some(day) = *day else {
+                    return " ".repeat(6);
+                };
+                let count = month.due_on(day).len();
+                let cell = match count {
+                    0 => format!("{:<6}", day.day()),
+                    _ => format!("{:<6}", format!("{}({})", day.day(), count)),
+                };
+                match month.mark(day, now) {
+                    Some(DayMark::Overdue) => paint(&cell, Color::Red, color),
+                    Some(DayMark::HighPriority) => paint(&cell, Color::Yellow, color),
+                    Some(DayMark::Due) | None => cell,
+                }
+            })
+            .collect();
+        outln!(out, "{}", cells.concat().trim_end());
+    }
+    let due: usize = month.days.values().map(Vec::len).sum();
+    outln!(out);
+    // Without colors, the marked days are listed instead.
+    if config.ascii {
+        for (mark, marker) in [
+            (DayMark::Overdue, tr!("marker-overdue")),
+            (DayMark::HighPriority, Priority::High.marker().to_string()),
+        ] {
+            let days: Vec<String> = month
+                .days
+                .keys()
+                .filter(|day| month.mark(**day, now) == Some(mark))
+                .map(|day| day.day().to_string())
+                .collect();
+            if !days.is_empty() {
+                outln!(out, "{} {}", marker, days.join(", "));
+            }
+        }
+    }
+    out.line(&tr!("due-this-month", count = due));
+}
+
+/// Prints the tasks due in `month` day by day, as `calendar --agenda` does.
+pub(super) fn print_agenda(out: &mut dyn OutputSink, month: &MonthAgenda, config: &Config) {
+    if month.days.is_empty() && config.output_format.for_humans() {
+        out.line(&tr!(
+            "nothing-due-in-month",
+            month = month.first.format("%B %Y").to_string()
+        ));
+        return;
+    }
+
+    let now = clock::now_naive();
+    let mut table = Table::new(vec![
+        Column {
+            key: "date",
+            title: tr!("column-date"),
+            // Room for the overdue marker in `--ascii` mode.
+            width: if config.ascii { 30 } else { 16 },
+        },
+        Column {
+            key: "time",
+            title: tr!("column-time"),
+            width: 5,
+        },
+        Column {
+            key: "id",
+            title: tr!("column-id"),
+            width: 4,
+        },
+        Column {
+            key: "description",
+            title: tr!("column-description"),
+            width: DESCRIPTION_WIDTH,
+        },
+        Column {
+            key: "priority",
+            title: tr!("column-priority"),
+            width: 8,
+        },
+    ]);
+    for (date, tasks) in &month.days {
+        for (i, task) in tasks.iter().enumerate() {
+            let mut day = Cell::new(date.to_string()).shown_as(if i == 0 {
+                format!("{} {}", date.format("%a"), config.format_date(*date))
+            } else {
+                String::new()
+            });
+            if task.is_overdue(now) {
+                day = if config.ascii {
+                    let shown = format!("{} {}", tr!("marker-overdue"), day.text);
+                    day.shown_as(shown.trim_end())
+                } else {
+                    day.colored(Some(Color::Red))
+                };
+            }
+            table.push(vec![
+                day,
+                task.due_time.map_or(Cell::missing(""), |t| {
+                    Cell::new(t.format("%H:%M").to_string())
+                }),
+                Cell::new(task.id),
+                Cell::new(task.description.as_str()),
+                task.priority
+                    .map_or(Cell::missing(""), |p| priority_cell(p, config)),
+            ]);
+        }
+    }
+    renderer(config).table(out, &table);
+}
+
+/// Handles the 'burndown' command.
+pub(super) fn handle_burndown(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    weeks: u32,
+    json: bool,
+    chart_path: Option<&Path>,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = chart_path {
+        check_chart_path(path)?;
+    }
+    let burndown = Burndown::build(tasks, weeks, clock::now());
+    if let Some(path) = chart_path {
+        render_chart(&burndown.chart(), path)?;
+        info!("Wrote burndown chart to {}.", path.display());
+    }
+
+    if json {
+        out.value(&serde_json::to_value(&burndown)?);
+        return Ok(());
+    }
+
+    let open: Vec<usize> = burndown.weeks.iter().map(|w| w.open).collect();
+    let completed: Vec<usize> = burndown.weeks.iter().map(|w| w.completed).collect();
+    out.line(&tr!(
+        "burndown-open",
+        sparkline = sparkline(&open, config.ascii)
+    ));
+    out.line(&tr!(
+        "burndown-completed",
+        sparkline = sparkline(&completed, config.ascii)
+    ));
+    outln!(out);
+    outln!(
+        out,
+        "  {:<12} {:>5} {:>9}",
+        tr!("column-week-of"),
+        tr!("column-open"),
+        tr!("column-completed")
+    );
+    let widest = open.iter().copied().max().unwrap_or(0).max(1);
+    for week in &burndown.weeks {
+        // Bars are scaled so the busiest week fills 40 columns.
+        let bar = "#".repeat(week.open * 40 / widest);
+        let row = format!(
+            "  {:<12} {:>5} {:>9}  {}",
+            config.format_date(week.start),
+            week.open,
+            week.completed,
+            bar
+        );
+        outln!(out, "{}", row.trim_end_matches(' '));
+    }
+
+    outln!(out);
+    match burndown.change() {
+        0 => out.line(&tr!("backlog-steady")),
+        change if change < 0 => out.line(&tr!(
+            "backlog-shrank",
+            count = -change,
+            weeks = burndown.weeks.len()
+        )),
+        change => out.line(&tr!(
+            "backlog-grew",
+            count = change,
+            weeks = burndown.weeks.len()
+        )),
+    }
+    Ok(())
+}
+
+/// Handles the 'forecast' command.
+pub(super) fn handle_forecast(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    weeks: u32,
+    ahead: u32,
+    json: bool,
+    chart_path: Option<&Path>,
+    config: &Config,
+) -> Result<()> {
+    if let Some(path) = chart_path {
+        check_chart_path(path)?;
+    }
+    let forecast = Forecast::build(tasks, weeks, ahead, clock::now());
+    if let Some(path) = chart_path {
+        render_chart(&forecast.chart(), path)?;
+        info!("Wrote forecast chart to {}.", path.display());
+    }
+
+    if json {
+        out.value(&serde_json::to_value(&forecast)?);
+        return Ok(());
+    }
+
+    out.line(&tr!(
+        "forecast-pace",
+        open = forecast.open,
+        added = format!("{:.1}", forecast.added_per_week),
+        completed = format!("{:.1}", forecast.completed_per_week),
+        weeks = weeks.max(1)
+    ));
+    outln!(out);
+    outln!(
+        out,
+        "  {:<12} {:>5}",
+        tr!("column-week-of"),
+        tr!("column-open")
+    );
+    let widest = forecast
+        .weeks
+        .iter()
+        .map(|w| w.open)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for week in &forecast.weeks {
+        // Bars are scaled so the fullest week fills 40 columns.
+        let bar = "#".repeat(week.open * 40 / widest);
+        let row = format!(
+            "  {:<12} {:>5}  {}",
+            config.format_date(week.start),
+            week.open,
+            bar
+        );
+        outln!(out, "{}", row.trim_end_matches(' '));
+    }
+
+    outln!(out);
+    match forecast.clear_by {
+        _ if forecast.open == 0 => out.line(&tr!("forecast-empty")),
+        Some(week) => out.line(&tr!("forecast-clear-by", week = config.format_date(week))),
+        None => out.line(&tr!("forecast-not-shrinking")),
+    }
+    Ok(())
+}
+
+/// Prints one row per period with its count and a bar of `#` characters.
+pub(super) fn print_period_counts(
+    out: &mut dyn OutputSink,
+    periods: &[PeriodCount],
+    config: &Config,
+) {
+    for period in periods {
+        let row = format!(
+            "  {:<12} {:>3} {}",
+            config.format_date(period.start),
+            period.completed,
+            "#".repeat(period.completed)
+        );
+        outln!(out, "{}", row.trim_end_matches(' '));
+    }
+}
+
+/// Formats a duration in hours as `3.5h`, or in days once it exceeds two days.
+pub(super) fn format_hours(hours: f64) -> String {
+    if hours >= 48.0 {
+        format!("{:.1}d", hours / 24.0)
+    } else {
+        format!("{:.1}h", hours)
+    }
+}
+
+/// Handles the 'remind' command.
+///
+/// A task only counts as reminded once its notification was shown, so a
+/// failed notification is retried on the next run.
+pub(super) fn handle_remind(
+    out: &mut dyn OutputSink,
+    tasks: &mut [Task],
+    config: &Config,
+    dry_run: bool,
+) {
+    let now = clock::now();
+    let reminders = build_reminders(tasks, now, config);
+    if reminders.is_empty() {
+        out.line(&tr!("nothing-to-remind"));
+        return;
+    }
+
+    for reminder in reminders {
+        if dry_run {
+            out.line(&tr!(
+                "would-remind",
+                id = reminder.task_id,
+                body = reminder.body.as_str(),
+                when = reminder.when.to_string()
+            ));
+            continue;
+        }
+        match deliver_reminder(tasks, &reminder, &config.reminders, now) {
+            Ok(()) => out.line(&tr!(
+                "reminded",
+                id = reminder.task_id,
+                when = reminder.when.to_string()
+            )),
+            Err(e) => error!("Reminder for task {} failed: {}", reminder.task_id, e),
+        }
+    }
+}
+
+/// Handles the 'escalate' command.
+///
+/// A level only counts as fired once its notification was delivered. If it
+/// fails, later levels of the same task wait for the next run.
+pub(super) fn handle_escalate(
+    out: &mut dyn OutputSink,
+    tasks: &mut [Task],
+    config: &Config,
+    dry_run: bool,
+) {
+    let levels = sorted_levels(&config.escalation);
+    if levels.is_empty() {
+        out.line(&tr!("no-escalation-levels"));
+        return;
+    }
+
+    let mut failed: Vec<u32> = Vec::new();
+    for pending in pending_escalations(tasks, &levels, clock::now()) {
+        if failed.contains(&pending.task_id) {
+            continue;
+        }
+        let Some(task) = tasks.iter_mut().find(|t| t.id == pending.task_id) else {
+            continue;
+        };
+        let step = &levels[pending.level];
+        let title = tr!(
+            "escalation-title",
+            id = task.id,
+            description = task.description.as_str()
+        );
+        let body = tr!(
+            "escalation-body",
+            due = task
+                .due_date
+                .map_or_else(String::new, |d| config.format_date(d)),
+            hours = step.after_hours,
+            level = pending.level + 1
+        );
+
+        if dry_run {
+            out.line(&tr!(
+                "would-notify",
+                id = task.id,
+                channel = step.channel.name(),
+                level = pending.level + 1
+            ));
+            continue;
+        }
+        match step.channel.send(&title, &body) {
+            Ok(()) => {
+                task.escalations_sent = pending.level as u32 + 1;
+                out.line(&tr!(
+                    "notified",
+                    id = task.id,
+                    channel = step.channel.name(),
+                    level = pending.level + 1
+                ));
+            }
+            Err(e) => {
+                error!("Escalation for task {} failed: {}", task.id, e);
+                failed.push(task.id);
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! The interactive sessions: `triage`, `review`, and `stale`.
+
+use clap::ValueEnum;
+use log::info;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::Path;
+
+use super::prompt;
+use crate::app::{
+    Color, Config, DESCRIPTION_WIDTH, DateOrder, DueSpec, OutputSink, Priority, Result, Session,
+    Task, TaskManager, TaskStatus, clock, format_minutes, move_to_trash, review_queue, stale_tasks,
+    truncate,
+};
+use crate::{outln, tr};
+
+/// Begins the session of the interactive `command` on `tasks`, unless
+/// nothing will be saved.
+pub(super) fn interactive_session(
+    command: &str,
+    tasks: &[Task],
+    tasks_path: &Path,
+    config: &Config,
+    dry_run: bool,
+) -> Result<Option<Session>> {
+    if dry_run {
+        return Ok(None);
+    }
+    Session::begin(command, tasks, tasks_path, config.autosave_interval).map(Some)
+}
+
+/// Handles the 'triage' command.
+/// Prompts for each inbox task in turn, assigning a project, due date, and priority,
+/// or deleting it. Tasks left without a project stay in the inbox; deleted
+/// ones go to the trash.
+pub(super) fn handle_triage(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    date_order: Option<DateOrder>,
+    trash_path: &Path,
+    dry_run: bool,
+    mut session: Option<Session>,
+) -> Result<()> {
+    let inbox_ids: Vec<u32> = tasks
+        .iter()
+        .filter(|t| t.is_in_inbox())
+        .map(|t| t.id)
+        .collect();
+
+    if inbox_ids.is_empty() {
+        out.line(&tr!("inbox-empty"));
+        return Ok(());
+    }
+
+    out.line(&tr!("inbox-count", count = inbox_ids.len()));
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let (mut organized, mut removed) = (0, Vec::new());
+
+    for id in inbox_ids {
+        let Some(task) = tasks.iter().find(|t| t.id == id) else {
+            continue;
+        };
+        outln!(out);
+        outln!(out, "[{}] {}", task.id, task.description);
+
+        let question = format!("{} ", tr!("triage-project-prompt"));
+        let Some(answer) = prompt(&mut input, &question)? else {
+            break;
+        };
+        match answer.as_str() {
+            "" => continue,
+            "q" => break,
+            "d" => {
+                if let Some(index) = tasks.iter().position(|t| t.id == id) {
+                    removed.push(tasks.remove(index));
+                    info!("Deleted task ID {} during triage.", id);
+                    out.line(&tr!("task-trashed", id = id));
+                }
+                if let Some(session) = &mut session {
+                    session.record(tasks, &removed);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let due_date = loop {
+            let question = format!("{} ", tr!("triage-due-prompt"));
+            match prompt(&mut input, &question)? {
+                None => break None,
+                Some(s) if s.is_empty() => break None,
+                Some(s) => match s.parse::<DueSpec>().and_then(|d| d.with_order(date_order)) {
+                    Ok(spec) => break spec.resolve(clock::today()),
+                    Err(e) => outln!(out, "{}", e),
+                },
+            }
+        };
+
+        let priority = loop {
+            let question = format!("{} ", tr!("triage-priority-prompt"));
+            match prompt(&mut input, &question)? {
+                None => break None,
+                Some(s) if s.is_empty() => break None,
+                Some(s) => match Priority::from_str(&s, true) {
+                    Ok(priority) => break Some(priority),
+                    Err(_) => out.line(&tr!("priority-choices")),
+                },
+            }
+        };
+
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            task.project = Some(answer);
+            task.due_date = due_date.or(task.due_date);
+            task.priority = priority.or(task.priority);
+            info!("Triaged task: {:?}", task);
+        }
+        organized += 1;
+        if let Some(session) = &mut session {
+            session.record(tasks, &removed);
+        }
+    }
+
+    let deleted = removed.len();
+    if !removed.is_empty() && !dry_run {
+        move_to_trash(removed, trash_path, clock::now())?;
+    }
+    let remaining = tasks.iter().filter(|t| t.is_in_inbox()).count();
+    outln!(out);
+    out.line(&tr!(
+        "triage-finished",
+        organized = organized,
+        deleted = deleted,
+        remaining = remaining
+    ));
+    Ok(())
+}
+
+/// Returns the color of the task's status.
+pub(super) fn status_color(task: &Task) -> Color {
+    match task.status {
+        TaskStatus::Done => Color::Green,
+        TaskStatus::Cancelled | TaskStatus::Backlog => Color::Dim,
+        TaskStatus::Blocked => Color::Red,
+        TaskStatus::Todo | TaskStatus::InProgress => Color::Yellow,
+    }
+}
+
+/// Handles the 'review' command.
+/// Prompts for each task in the review queue in turn; removed tasks go to
+/// the trash.
+pub(super) fn handle_review(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    stale_after: u32,
+    trash_path: &Path,
+    dry_run: bool,
+    mut session: Option<Session>,
+) -> Result<()> {
+    let queue = review_queue(manager.tasks(), clock::now(), stale_after);
+    if queue.is_empty() {
+        out.line(&tr!("nothing-to-review"));
+        return Ok(());
+    }
+
+    out.line(&tr!("review-count", count = queue.len()));
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let (mut completed, mut rescheduled, mut removed) = (0, 0, Vec::new());
+
+    'review: for (id, reason) in queue {
+        let Ok(task) = manager.get(id) else {
+            continue;
+        };
+        outln!(out);
+        outln!(out, "[{}] {} ({})", task.id, task.description, reason);
+
+        loop {
+            let question = format!("{} ", tr!("review-prompt"));
+            let Some(answer) = prompt(&mut input, &question)? else {
+                break 'review;
+            };
+            match answer.to_ascii_lowercase().as_str() {
+                "c" | "complete" => {
+                    manager.complete(id)?;
+                    out.line(&tr!("task-completed", id = id));
+                    completed += 1;
+                }
+                "r" | "reschedule" => {
+                    let Some(due) = prompt_due_date(out, &mut input)? else {
+                        continue;
+                    };
+                    match manager.reschedule(id, due) {
+                        Ok(due) => out.line(&tr!("task-now-due", id = id, due = due.to_string())),
+                        Err(e) => {
+                            outln!(out, "{}", e);
+                            continue;
+                        }
+                    }
+                    rescheduled += 1;
+                }
+                "d" | "delete" => {
+                    removed.push(manager.remove(id)?);
+                    out.line(&tr!("task-removed", id = id));
+                }
+                "" | "k" | "keep" => {}
+                "q" | "quit" => break 'review,
+                _ => continue,
+            }
+            if let Some(session) = &mut session {
+                session.record(manager.tasks(), &removed);
+            }
+            break;
+        }
+    }
+
+    let deleted = removed.len();
+    if !removed.is_empty() && !dry_run {
+        move_to_trash(removed, trash_path, clock::now())?;
+    }
+    outln!(out);
+    out.line(&tr!(
+        "review-finished",
+        completed = completed,
+        rescheduled = rescheduled,
+        deleted = deleted
+    ));
+    Ok(())
+}
+
+/// Handles the 'stale' command.
+///
+/// Lists the tasks unchanged for longer than `older_than` minutes, then asks
+/// what to do with all of them, or goes through them one by one.
+pub(super) fn handle_stale(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    older_than: u32,
+    trash_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let now = clock::now();
+    let stale: Vec<(u32, i64)> = stale_tasks(
+        manager.tasks(),
+        now,
+        chrono::Duration::minutes(older_than.into()),
+    )
+    .iter()
+    .map(|t| (t.id, (now - t.last_modified()).num_days()))
+    .collect();
+    // Ages are mostly given in days or weeks, which read better in days.
+    let age = if older_than.is_multiple_of(24 * 60) {
+        format!("{}d", older_than / (24 * 60))
+    } else {
+        format_minutes(older_than)
+    };
+    if stale.is_empty() {
+        out.line(&tr!("no-stale-tasks", age = age.as_str()));
+        return Ok(());
+    }
+
+    out.line(&tr!("stale-count", count = stale.len(), age = age.as_str()));
+    for &(id, days) in &stale {
+        let task = manager.get(id)?;
+        outln!(
+            out,
+            "  {:<4} {} ({})",
+            id,
+            truncate(&task.description, DESCRIPTION_WIDTH),
+            tr!("stale-age", days = days)
+        );
+    }
+    if !io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let (mut rescheduled, mut archived, mut removed) = (0, 0, Vec::new());
+    outln!(out);
+    let bulk = loop {
+        let question = format!("{} ", tr!("stale-bulk-prompt"));
+        let Some(answer) = prompt(&mut input, &question)? else {
+            return Ok(());
+        };
+        match answer.to_ascii_lowercase().as_str() {
+            "r" | "reschedule" => {
+                let Some(due) = prompt_due_date(out, &mut input)? else {
+                    continue;
+                };
+                break Some(("r", Some(due)));
+            }
+            "a" | "archive" => break Some(("a", None)),
+            "d" | "delete" => break Some(("d", None)),
+            "o" | "one" => break None,
+            "" | "q" | "quit" => return Ok(()),
+            _ => continue,
+        }
+    };
+
+    'stale: for &(id, days) in &stale {
+        let (action, due) = match bulk {
+            Some(choice) => choice,
+            None => {
+                let task = manager.get(id)?;
+                outln!(out);
+                outln!(
+                    out,
+                    "[{}] {} ({})",
+                    id,
+                    task.description,
+                    tr!("stale-age", days = days)
+                );
+                loop {
+                    let question = format!("{} ", tr!("stale-prompt"));
+                    let Some(answer) = prompt(&mut input, &question)? else {
+                        break 'stale;
+                    };
+                    match answer.to_ascii_lowercase().as_str() {
+                        "r" | "reschedule" => match prompt_due_date(out, &mut input)? {
+                            Some(due) => break ("r", Some(due)),
+                            None => continue,
+                        },
+                        "a" | "archive" => break ("a", None),
+                        "d" | "delete" => break ("d", None),
+                        "" | "k" | "keep" => continue 'stale,
+                        "q" | "quit" => break 'stale,
+                        _ => continue,
+                    }
+                }
+            }
+        };
+        match (action, due) {
+            ("r", Some(due)) => match manager.reschedule(id, due) {
+                Ok(due) => {
+                    out.line(&tr!("task-now-due", id = id, due = due.to_string()));
+                    rescheduled += 1;
+                }
+                Err(e) => outln!(out, "{}", e),
+            },
+            ("a", _) => {
+                manager.move_to(id, TaskStatus::Cancelled)?;
+                out.line(&tr!("task-archived", id = id));
+                archived += 1;
+            }
+            ("d", _) => {
+                removed.push(manager.remove(id)?);
+                out.line(&tr!("task-removed", id = id));
+            }
+            _ => {}
+        }
+    }
+
+    let deleted = removed.len();
+    if !removed.is_empty() && !dry_run {
+        move_to_trash(removed, trash_path, now)?;
+    }
+    outln!(out);
+    out.line(&tr!(
+        "stale-finished",
+        rescheduled = rescheduled,
+        archived = archived,
+        deleted = deleted
+    ));
+    Ok(())
+}
+
+/// Asks for a due date until one reads, or returns `None` if the answer is
+/// empty or input ends.
+pub(super) fn prompt_due_date(
+    out: &mut dyn OutputSink,
+    input: &mut impl BufRead,
+) -> Result<Option<DueSpec>> {
+    loop {
+        let question = format!("{} ", tr!("due-date-prompt"));
+        match prompt(input, &question)? {
+            None => return Ok(None),
+            Some(s) if s.is_empty() => return Ok(None),
+            Some(s) => match s.parse::<DueSpec>() {
+                Ok(spec) => return Ok(Some(spec)),
+                Err(e) => outln!(out, "{}", e),
+            },
+        }
+    }
+}
@@ -0,0 +1,566 @@
+//! Keeping the task list in step with other files and services: watching,
+//! syncing, comparing, importing, and exporting.
+
+use log::{debug, info};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing::info_span;
+
+use super::tasks::handle_mark_task_completion;
+use super::{print_fields, prompt};
+use crate::app::{
+    AppError, CompareFormat, Config, ConflictResolver, ExportFormat, ExportOptions, GithubCommands,
+    ImportSource, MergeStrategy, OutputSink, RemoteStorageConfig, Resolution, Result, Task,
+    TaskConflict, TaskDiff, TaskManager, TodoSyncReport, apply_todo_lines, clock,
+    close_github_issue, export_tasks, export_to_todoist, import_from_todoist, import_taskwarrior,
+    merge_import, move_to_trash, parse_csv_export, parse_json_export, parse_todo_file,
+    pull_github_issues, pull_remote_storage, push_remote_storage, render_todo_file, scan_into,
+    snapshot_path, storage, sync_caldav, sync_remote_storage, sync_tasks, update_readme_sections,
+    write_dashboard,
+};
+use crate::{outln, tr};
+
+/// Handles the 'watch-file' command.
+///
+/// Polls both files for modification. Edits to the TODO file are applied to
+/// the store first; the file is then rewritten from the store.
+pub(super) fn handle_watch_file(
+    out: &mut dyn OutputSink,
+    config: &Config,
+    tasks_path: &Path,
+    todo_path: &Path,
+    once: bool,
+    interval: u64,
+) -> Result<()> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let snapshot = snapshot_path(todo_path);
+    let mut seen: Option<(Option<SystemTime>, Option<SystemTime>)> = None;
+    if !once {
+        out.line(&tr!("watching", path = todo_path.display().to_string()));
+    }
+
+    loop {
+        let current = (modified(todo_path), modified(tasks_path));
+        if seen != Some(current) {
+            let text = fs::read_to_string(todo_path).unwrap_or_default();
+            let previous = parse_todo_file(&fs::read_to_string(&snapshot).unwrap_or_default());
+            let mut lines = parse_todo_file(&text);
+            let mut tasks = storage::load_tasks_from(tasks_path)?;
+
+            let mut ids = config.id_strategy.generator();
+            let report = apply_todo_lines(&mut tasks, &mut lines, &previous, ids.as_mut());
+            if !report.is_empty() {
+                storage::save_tasks_to(&tasks, tasks_path)?;
+                print_todo_sync_report(out, &report);
+            }
+
+            let rendered = render_todo_file(&tasks, &lines);
+            if rendered != text {
+                fs::write(todo_path, &rendered)?;
+                debug!("Rewrote {}.", todo_path.display());
+            }
+            fs::write(&snapshot, &rendered)?;
+            seen = Some((modified(todo_path), modified(tasks_path)));
+        }
+
+        if once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// Prints one line per kind of change made by a TODO file sync.
+pub(super) fn print_todo_sync_report(out: &mut dyn OutputSink, report: &TodoSyncReport) {
+    for (kind, ids) in [
+        ("added", &report.added),
+        ("completed", &report.completed),
+        ("reopened", &report.reopened),
+        ("renamed", &report.renamed),
+    ] {
+        if !ids.is_empty() {
+            let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+            out.line(&tr!("todo-synced", kind = kind, ids = ids.join(", ")));
+        }
+    }
+}
+
+/// Handles the 'sync' command.
+pub(super) fn handle_sync(
+    out: &mut dyn OutputSink,
+    tasks_path: &Path,
+    config: &Config,
+    interactive: bool,
+) -> Result<()> {
+    let report = match terminal_resolver(out, interactive)? {
+        Some(mut resolver) => sync_tasks(tasks_path, &config.sync, Some(&mut resolver))?,
+        None => sync_tasks(tasks_path, &config.sync, None)?,
+    };
+    let branch = config.sync.branch.as_str();
+    if report.committed {
+        out.line(&tr!("sync-committed"));
+    }
+    if report.fast_forwarded {
+        out.line(&tr!("sync-pulled", branch = branch));
+    }
+    if report.merged {
+        out.line(&tr!("sync-merged"));
+    }
+    match (&config.sync.remote, report.pushed) {
+        (None, _) => out.line(&tr!("sync-no-remote")),
+        (Some(remote), true) => out.line(&tr!(
+            "sync-pushed",
+            remote = remote.as_str(),
+            branch = branch
+        )),
+        (Some(_), false) => out.line(&tr!("sync-nothing-to-push")),
+    }
+    Ok(())
+}
+
+/// Handles the 'sync' command when the data file is kept on remote storage.
+pub(super) fn handle_storage_sync(
+    out: &mut dyn OutputSink,
+    tasks_path: &Path,
+    storage: &RemoteStorageConfig,
+    interactive: bool,
+) -> Result<()> {
+    let report = match terminal_resolver(out, interactive)? {
+        Some(mut resolver) => sync_remote_storage(tasks_path, storage, Some(&mut resolver))?,
+        None => sync_remote_storage(tasks_path, storage, None)?,
+    };
+    out.line(&tr!(
+        "storage-synced",
+        url = storage.url.as_str(),
+        pulled = report.pulled
+    ));
+    if report.pushed {
+        out.line(&tr!(
+            "storage-uploaded",
+            path = tasks_path.display().to_string(),
+            url = storage.url.as_str()
+        ));
+    }
+    Ok(())
+}
+
+/// Fetches a newer version of the data file from remote storage, carrying
+/// on with the local copy if the server can't be reached.
+pub(super) fn refresh_from_storage(tasks_path: &Path, storage: &RemoteStorageConfig) {
+    let outcome = info_span!("fetch").in_scope(|| pull_remote_storage(tasks_path, storage));
+    match outcome {
+        Ok(outcome) => debug!("Remote storage: {:?}.", outcome),
+        Err(e) => eprintln!(
+            "{}",
+            tr!(
+                "warning-storage-not-fetched",
+                path = tasks_path.display().to_string(),
+                url = storage.url.as_str(),
+                reason = e.to_string()
+            )
+        ),
+    }
+}
+
+/// Uploads the saved data file to remote storage. Failing that, the changes
+/// stay local until the next command or `sync` uploads them.
+pub(super) fn upload_to_storage(tasks_path: &Path, storage: &RemoteStorageConfig) {
+    if let Err(e) = info_span!("upload").in_scope(|| push_remote_storage(tasks_path, storage)) {
+        eprintln!(
+            "{}",
+            tr!(
+                "warning-storage-not-uploaded",
+                path = tasks_path.display().to_string(),
+                url = storage.url.as_str(),
+                reason = e.to_string()
+            )
+        );
+    }
+}
+
+/// Handles the 'sync --caldav' command.
+/// Tasks deleted on the server are moved to the trash.
+pub(super) fn handle_caldav_sync(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    trash_path: &Path,
+    config: &Config,
+    interactive: bool,
+) -> Result<()> {
+    let Some(caldav) = &config.sync.caldav else {
+        return Err(AppError::InvalidArgument(tr!("error-no-caldav")));
+    };
+    let report = match terminal_resolver(out, interactive)? {
+        Some(mut resolver) => sync_caldav(tasks, tasks_path, caldav, Some(&mut resolver))?,
+        None => sync_caldav(tasks, tasks_path, caldav, None)?,
+    };
+    let removed = report.removed.len();
+    if removed > 0 {
+        move_to_trash(report.removed, trash_path, clock::now())?;
+    }
+    out.line(&tr!(
+        "caldav-synced",
+        url = caldav.url.as_str(),
+        pulled = report.pulled,
+        removed = removed,
+        pushed = report.pushed,
+        deleted = report.deleted
+    ));
+    Ok(())
+}
+
+/// Returns the resolver for `sync --interactive`, which needs a terminal.
+pub(super) fn terminal_resolver(
+    out: &mut dyn OutputSink,
+    interactive: bool,
+) -> Result<Option<TerminalResolver<'_>>> {
+    if !interactive {
+        return Ok(None);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-interactive-no-terminal"
+        )));
+    }
+    Ok(Some(TerminalResolver { out }))
+}
+
+/// Settles sync conflicts by showing both versions of each conflicting
+/// field and asking which to keep.
+pub(super) struct TerminalResolver<'a> {
+    out: &'a mut dyn OutputSink,
+}
+
+impl ConflictResolver for TerminalResolver<'_> {
+    fn resolve(&mut self, conflict: &TaskConflict) -> Result<Vec<Resolution>> {
+        let show = |value: &Option<serde_json::Value>| match value {
+            None => tr!("value-unset"),
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(value) => value.to_string(),
+        };
+        let out = &mut *self.out;
+        outln!(out);
+        out.line(&tr!(
+            "conflict-heading",
+            id = conflict.ours.id,
+            description = conflict.ours.description.as_str()
+        ));
+
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        let mut resolutions = Vec::new();
+        for field in &conflict.fields {
+            outln!(out, "  {}", field.field);
+            let sides = [
+                (tr!("conflict-was"), &field.base),
+                (tr!("conflict-local"), &field.ours),
+                (tr!("conflict-remote"), &field.theirs),
+            ]
+            .map(|(label, value)| (format!("    {}", label), show(value)));
+            print_fields(out, &sides);
+            let resolution = loop {
+                let question = format!("  {} ", tr!("conflict-prompt"));
+                let Some(answer) = prompt(&mut input, &question)? else {
+                    return Err(AppError::Sync("conflict resolution aborted".to_string()));
+                };
+                match answer.to_ascii_lowercase().as_str() {
+                    "l" | "local" => break Resolution::Ours,
+                    "r" | "remote" => break Resolution::Theirs,
+                    "e" | "edit" => {
+                        let question = format!("  {} ", tr!("conflict-new-value"));
+                        let Some(text) = prompt(&mut input, &question)? else {
+                            continue;
+                        };
+                        let value =
+                            serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+                        if conflict.accepts(&field.field, &value) {
+                            break Resolution::Value(value);
+                        }
+                        outln!(
+                            out,
+                            "  {}",
+                            tr!("conflict-invalid", field = field.field.as_str())
+                        );
+                    }
+                    _ => {}
+                }
+            };
+            resolutions.push(resolution);
+        }
+        Ok(resolutions)
+    }
+}
+
+/// Handles the 'compare' command.
+pub(super) fn handle_compare(
+    out: &mut dyn OutputSink,
+    before: &Path,
+    after: &Path,
+    format: CompareFormat,
+) -> Result<()> {
+    for path in [before, after] {
+        if !path.exists() {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-does-not-exist",
+                path = path.display().to_string()
+            )));
+        }
+    }
+    let diff = TaskDiff::between(
+        &storage::load_tasks_from(before)?,
+        &storage::load_tasks_from(after)?,
+    )?;
+
+    if format == CompareFormat::Json {
+        out.value(&serde_json::to_value(&diff)?);
+        return Ok(());
+    }
+    if diff.is_empty() {
+        out.line(&tr!("no-differences"));
+        return Ok(());
+    }
+    print_task_diff(out, &diff);
+    Ok(())
+}
+
+/// Prints the tasks added, removed, and changed in `diff`.
+pub(super) fn print_task_diff(out: &mut dyn OutputSink, diff: &TaskDiff) {
+    if !diff.added.is_empty() {
+        out.line(&tr!("diff-added", count = diff.added.len()));
+        for task in &diff.added {
+            outln!(out, "  + {:<4} {}", task.id, task.description);
+        }
+    }
+    if !diff.removed.is_empty() {
+        out.line(&tr!("diff-removed", count = diff.removed.len()));
+        for task in &diff.removed {
+            outln!(out, "  - {:<4} {}", task.id, task.description);
+        }
+    }
+    if !diff.changed.is_empty() {
+        out.line(&tr!("diff-changed", count = diff.changed.len()));
+        for change in &diff.changed {
+            outln!(out, "  ~ {:<4} {}", change.id, change.description);
+            for field in &change.fields {
+                outln!(
+                    out,
+                    "      {}: {} -> {}",
+                    field.field,
+                    field.before,
+                    field.after
+                );
+            }
+        }
+    }
+}
+
+/// Handles the 'export' command.
+/// Prints the exported document, or writes it to `output` when given.
+/// Todoist exports are uploaded instead.
+pub(super) fn handle_export(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    format: ExportFormat,
+    options: &ExportOptions,
+    output: Option<PathBuf>,
+    tasks_path: &Path,
+    config: &Config,
+) -> Result<()> {
+    if format == ExportFormat::Todoist {
+        if output.is_some() || options.anonymize {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-todoist-export-options"
+            )));
+        }
+        let selected: Vec<Task> = tasks
+            .iter()
+            .filter(|t| options.matches(t))
+            .cloned()
+            .collect();
+        let report = export_to_todoist(&selected, tasks_path, &config.todoist)?;
+        out.line(&tr!(
+            "todoist-exported",
+            added = report.added,
+            updated = report.updated,
+            completed = report.completed
+        ));
+        return Ok(());
+    }
+    if let (ExportFormat::ReadmeSection, Some(path)) = (format, &output) {
+        let document = fs::read_to_string(path)?;
+        let (updated, sections) = update_readme_sections(&document, tasks, options)?;
+        if updated != document {
+            fs::write(path, updated)?;
+        }
+        info!("Updated {} task sections in {}.", sections, path.display());
+        out.line(&tr!(
+            "sections-updated",
+            count = sections,
+            path = path.display().to_string()
+        ));
+        return Ok(());
+    }
+    let contents = export_tasks(tasks, format, options)?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, contents)?;
+            info!("Exported tasks as {:?} to {}.", format, path.display());
+            out.line(&tr!("tasks-exported", path = path.display().to_string()));
+        }
+        None => out.text(&contents),
+    }
+    Ok(())
+}
+
+/// Handles the 'import' command.
+pub(super) fn handle_import(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    from: ImportSource,
+    path: Option<PathBuf>,
+    merge: Option<MergeStrategy>,
+    config: &Config,
+) -> Result<()> {
+    if merge.is_some() && !matches!(from, ImportSource::Json | ImportSource::Csv) {
+        return Err(AppError::InvalidArgument(tr!("error-merge-source")));
+    }
+    let strategy = merge.unwrap_or_default();
+    let (source, report) = match (from, path) {
+        (ImportSource::Todoist, None) => (
+            "Todoist",
+            import_from_todoist(tasks, tasks_path, &config.todoist)?,
+        ),
+        (ImportSource::Todoist, Some(_)) => {
+            return Err(AppError::InvalidArgument(tr!("error-todoist-import-file")));
+        }
+        (ImportSource::Taskwarrior, Some(path)) => (
+            "Taskwarrior",
+            import_taskwarrior(tasks, &fs::read_to_string(&path)?)?,
+        ),
+        (ImportSource::Taskwarrior, None) => {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-taskwarrior-import-file"
+            )));
+        }
+        (ImportSource::Json, Some(path)) => (
+            "JSON",
+            merge_import(
+                tasks,
+                parse_json_export(&fs::read_to_string(&path)?)?,
+                strategy,
+            )?,
+        ),
+        (ImportSource::Csv, Some(path)) => (
+            "CSV",
+            merge_import(
+                tasks,
+                parse_csv_export(&fs::read_to_string(&path)?)?,
+                strategy,
+            )?,
+        ),
+        (ImportSource::Json | ImportSource::Csv, None) => {
+            return Err(AppError::InvalidArgument(tr!("error-export-import-file")));
+        }
+    };
+    out.line(&tr!(
+        "imported",
+        source = source,
+        added = report.added,
+        updated = report.updated
+    ));
+    if report.completed > 0 {
+        out.line(&tr!(
+            "import-completed",
+            count = report.completed,
+            source = source
+        ));
+    }
+    if !report.dropped.is_empty() {
+        let dropped: Vec<String> = report
+            .dropped
+            .iter()
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect();
+        out.line(&tr!("not-preserved", fields = dropped.join(", ")));
+    }
+    Ok(())
+}
+
+/// Handles the 'github' command.
+pub(super) fn handle_github(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    command: GithubCommands,
+    config: &Config,
+) -> Result<()> {
+    match command {
+        GithubCommands::Pull { repo } => {
+            let report = pull_github_issues(tasks, &repo, &config.github)?;
+            let source = repo.to_string();
+            out.line(&tr!(
+                "imported",
+                source = source.as_str(),
+                added = report.added,
+                updated = report.updated
+            ));
+            if report.completed > 0 {
+                out.line(&tr!(
+                    "import-completed",
+                    count = report.completed,
+                    source = source.as_str()
+                ));
+            }
+        }
+        GithubCommands::Close { id } => {
+            let mut manager = TaskManager::new(tasks, config);
+            let issue = close_github_issue(manager.get(id)?, &config.github)?;
+            out.line(&tr!("github-issue-closed", issue = issue.to_string()));
+            if manager.get(id)?.is_pending() {
+                handle_mark_task_completion(out, &mut manager, id, true, config)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handles the 'scan' command.
+pub(super) fn handle_scan(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    path: Option<PathBuf>,
+    dry_run: bool,
+) -> Result<()> {
+    let root = match path {
+        Some(path) => path,
+        None => env::current_dir()?,
+    };
+    let report = scan_into(tasks, tasks_path, &root, !dry_run)?;
+    out.line(&tr!(
+        "scanned",
+        files = report.files,
+        added = report.added,
+        moved = report.moved
+    ));
+    Ok(())
+}
+
+/// Handles the 'dashboard' command.
+pub(super) fn handle_dashboard(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    out_dir: &Path,
+) -> Result<()> {
+    let written = write_dashboard(tasks, out_dir, clock::now())?;
+    for path in written {
+        out.line(&tr!("wrote", path = path.display().to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,976 @@
+//! Adding, editing, completing, moving, and removing tasks.
+
+use log::{debug, error, info};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use tracing::info_span;
+
+use super::listing::{TableStyle, print_task_table, renderer};
+use super::{confirm, print_fields, prompt};
+use crate::app::{
+    AddArgs, AppError, Cell, CheckCommands, Column, Commands, Config, CountSelection,
+    DESCRIPTION_WIDTH, EditArgs, IdGenerator, IndexCache, MAX_SUGGESTIONS, NewTask, OutputSink,
+    PickCommands, QuickAdd, Result, Skipped, SnoozeSpec, StatusSummary, Streaks, Suggestion, Table,
+    Task, TaskChanges, TaskDraft, TaskField, TaskManager, TaskRef, TaskSelection, TaskStatus,
+    TrashCommands, clock, completion_points, did_you_mean, edit_in_editor, find_duplicates,
+    find_template, format_minutes, fuzzy_matches, load_trash, move_task, move_to_trash,
+    open_target, pick_task, project_status, purge_trash, resolve_attachment, save_trash, storage,
+    truncate, write_clipboard,
+};
+use crate::{outln, tr};
+
+/// Handles the 'add' and 'paste' commands.
+/// Generates a new unique ID for the task and adds it to the list.
+/// Recurring tasks start on the first due date that isn't an exception.
+/// A pending task reading like the new one is pointed out first; adding
+/// anyway takes a yes on a terminal, and `--no-duplicates` refuses.
+pub(super) fn handle_add_task(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    task: NewTask,
+    no_duplicates: bool,
+    config: &Config,
+) -> Result<()> {
+    if config.check_duplicates || no_duplicates {
+        let duplicates = find_duplicates(&task.description, manager.tasks());
+        if let Some(first) = duplicates.first() {
+            if no_duplicates {
+                return Err(AppError::InvalidArgument(tr!(
+                    "error-duplicate",
+                    id = first.id,
+                    description = truncate(&first.description, DESCRIPTION_WIDTH)
+                )));
+            }
+            eprintln!("{}", tr!("warning-duplicates", count = duplicates.len()));
+            for task in &duplicates {
+                eprintln!(
+                    "  {:<4} {}",
+                    task.id,
+                    truncate(&task.description, DESCRIPTION_WIDTH)
+                );
+            }
+            if io::stdin().is_terminal() && !confirm(&tr!("confirm-add-duplicate"))? {
+                out.line(&tr!("nothing-added"));
+                return Ok(());
+            }
+        }
+    }
+    let task = manager.add(task)?;
+    out.line(&tr!("task-added", id = task.id));
+    Ok(())
+}
+
+/// Handles the 'add --from-file' and 'add --from-clipboard' commands.
+/// Adds a task per non-empty line of `text`, reading each line's quick-add
+/// syntax before the template fills in the rest. A bad line adds nothing.
+pub(super) fn handle_add_lines(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    mut args: AddArgs,
+    text: &str,
+) -> Result<()> {
+    let template = args
+        .template
+        .take()
+        .map(|name| find_template(&name))
+        .transpose()?;
+    let mut ids = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let in_line = |e: AppError| {
+            let message = match e {
+                AppError::InvalidArgument(message) => message,
+                e => e.to_string(),
+            };
+            AppError::InvalidArgument(tr!("line-error", line = number + 1, error = message))
+        };
+        let mut line_args = args.clone();
+        line_args.description = line.to_string();
+        QuickAdd::read_into(&mut line_args).map_err(in_line)?;
+        if let Some(template) = &template {
+            template.apply(&mut line_args).map_err(in_line)?;
+        }
+        ids.push(
+            manager
+                .add(line_args.into())
+                .map_err(in_line)?
+                .id
+                .to_string(),
+        );
+    }
+    if ids.is_empty() {
+        out.line(&tr!("no-tasks-to-add"));
+    } else {
+        out.line(&tr!("tasks-added", count = ids.len(), ids = ids.join(", ")));
+    }
+    Ok(())
+}
+
+/// Handles the 'add' command by appending the task to the plain data file
+/// at `path`, reading only the IDs of the tasks already in it.
+///
+/// Returns `false`, having changed nothing, if the file can't take the task
+/// that way, so the command is to run as usual.
+pub(super) fn append_added_task(
+    out: &mut dyn OutputSink,
+    config: &Config,
+    path: &Path,
+    args: AddArgs,
+) -> Result<bool> {
+    let contents = fs::read(path)?;
+    let mut index = config
+        .index_cache
+        .then(|| IndexCache::load(path, &contents))
+        .flatten();
+    let ids = match &index {
+        Some(index) => index.ids.clone(),
+        None => info_span!("load").in_scope(|| storage::read_task_ids(&contents))?,
+    };
+    if !storage::can_append_to(path, &contents, &ids) {
+        return Ok(false);
+    }
+    let mut added = Vec::new();
+    let id = TaskManager::new(&mut added, config)
+        .reserving(ids)
+        .add(args.into())?
+        .id;
+    match info_span!("save").in_scope(|| storage::append_tasks_to(&added, path, &contents)) {
+        Ok(()) => {}
+        Err(AppError::Io(e)) if storage::is_unwritable(&e) => return Ok(false),
+        Err(e) => return Err(e),
+    }
+    if let Some(index) = &mut index {
+        index.record_added(&added, &fs::read(path)?);
+        if let Err(e) = index.save(path) {
+            error!("Could not update the index cache: {}", e);
+        }
+    }
+    out.line(&tr!("task-added", id = id));
+    Ok(true)
+}
+
+/// Answers `count` and `status` from the index cache of the data file at
+/// `tasks_path`, if it is up to date and holds what the command needs.
+pub(super) fn answer_from_index(
+    out: &mut dyn OutputSink,
+    command: &Commands,
+    tasks_path: &Path,
+    config: &Config,
+) -> Result<bool> {
+    if !matches!(command, Commands::Count(_) | Commands::Status { .. }) {
+        return Ok(false);
+    }
+    let Some(index) = IndexCache::load(tasks_path, &fs::read(tasks_path)?) else {
+        return Ok(false);
+    };
+    let now = clock::now_naive();
+    match command {
+        Commands::Count(args) => {
+            let mut selection = CountSelection::from(args);
+            selection.query = config.in_context(selection.query);
+            let Some(count) = index.count(&selection, now) else {
+                return Ok(false);
+            };
+            outln!(out, "{}", count);
+        }
+        Commands::Status { short } => handle_status(out, index.status(now), *short, config),
+        _ => return Ok(false),
+    }
+    debug!("Answered from the index cache.");
+    Ok(true)
+}
+
+/// Handles the 'pick' command: lets the user pick a pending task, then acts on
+/// it.
+pub(super) fn handle_pick(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    action: Option<PickCommands>,
+    trash_path: &Path,
+    dry_run: bool,
+    config: &Config,
+) -> Result<()> {
+    let pending: Vec<&Task> = manager.tasks().iter().filter(|t| t.is_pending()).collect();
+    if pending.is_empty() {
+        out.line(&tr!("no-pending-to-pick"));
+        return Ok(());
+    }
+    let Some(id) = pick_task(&pending, &format!("{} ", tr!("pick-prompt")))?.map(|t| t.id) else {
+        return Err(AppError::InvalidArgument(tr!("error-no-task-picked")));
+    };
+    match action {
+        None => outln!(out, "{}", id),
+        Some(PickCommands::Complete) => {
+            handle_mark_task_completion(out, manager, id, true, config)?;
+        }
+        Some(PickCommands::Remove { yes }) => {
+            handle_remove_task(out, manager, id, yes, trash_path, dry_run)?;
+        }
+        Some(PickCommands::Edit { changes }) => {
+            handle_edit_task(out, manager, id, (*changes).into())?
+        }
+    }
+    Ok(())
+}
+
+/// Returns the ID of the task `task` refers to. When part of a description
+/// matches several pending tasks, asks which one is meant, or fails if there
+/// is no terminal to ask on.
+pub(super) fn resolve_task(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    task: &TaskRef,
+) -> Result<u32> {
+    let text = match task {
+        TaskRef::Id(id) => return Ok(*id),
+        TaskRef::Description(text) => text,
+    };
+    let candidates = task.candidates(tasks);
+    match candidates.as_slice() {
+        [] => {
+            let pending: Vec<&Task> = tasks.iter().filter(|t| t.is_pending()).collect();
+            let near: Vec<Suggestion> = fuzzy_matches(text, &pending)
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(Suggestion::of)
+                .collect();
+            Err(AppError::InvalidArgument(format!(
+                "{}{}",
+                tr!("error-no-pending-match", text = text.as_str()),
+                did_you_mean(&near)
+            )))
+        }
+        [task] => Ok(task.id),
+        _ if !io::stdin().is_terminal() => {
+            let ids: Vec<String> = candidates.iter().map(|t| t.id.to_string()).collect();
+            Err(AppError::InvalidArgument(tr!(
+                "error-several-pending-match",
+                count = candidates.len(),
+                text = text.as_str(),
+                ids = ids.join(", ")
+            )))
+        }
+        _ => {
+            out.line(&tr!(
+                "several-pending-match",
+                count = candidates.len(),
+                text = text.as_str()
+            ));
+            for (i, task) in candidates.iter().enumerate() {
+                outln!(
+                    out,
+                    "  {}) [{}] {}",
+                    i + 1,
+                    task.id,
+                    truncate(&task.description, DESCRIPTION_WIDTH)
+                );
+            }
+            let stdin = io::stdin();
+            let mut input = stdin.lock();
+            loop {
+                let message = format!("{} ", tr!("which-one", count = candidates.len()));
+                let Some(answer) = prompt(&mut input, &message)?.filter(|a| !a.is_empty()) else {
+                    return Err(AppError::InvalidArgument(tr!("error-no-task-chosen")));
+                };
+                match answer.parse::<usize>() {
+                    Ok(n) if (1..=candidates.len()).contains(&n) => return Ok(candidates[n - 1].id),
+                    _ => out.line(&tr!("answer-with-number", count = candidates.len())),
+                }
+            }
+        }
+    }
+}
+
+/// Handles the 'edit' command.
+pub(super) fn handle_edit_task(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    changes: TaskChanges,
+) -> Result<()> {
+    let changed = manager.edit(id, changes)?;
+    if changed.is_empty() {
+        out.line(&tr!("task-unchanged", id = id));
+    } else {
+        out.line(&tr!(
+            "task-updated",
+            id = id,
+            fields = field_names(&changed)
+        ));
+    }
+    Ok(())
+}
+
+/// Handles the 'modify' command.
+/// Lists the tasks `selection` picks and applies `changes` to each of them.
+/// Asks for confirmation when stdin is a terminal; otherwise requires --yes.
+pub(super) fn handle_modify_tasks(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    selection: &TaskSelection,
+    changes: TaskChanges,
+    confirmed: bool,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    if changes.is_empty() {
+        return Err(AppError::InvalidArgument(tr!("error-nothing-to-modify")));
+    }
+    let matching = manager.list(selection)?.tasks;
+    if matching.is_empty() {
+        out.line(&tr!("no-tasks-match-query"));
+        return Ok(());
+    }
+    out.line(&tr!("tasks-match", count = matching.len()));
+    print_task_table(out, &matching, TableStyle::default(), config);
+    let ids: Vec<u32> = matching.iter().map(|t| t.id).collect();
+
+    if !confirmed && !dry_run {
+        if !io::stdin().is_terminal() {
+            out.line(&tr!("modify-needs-yes", count = ids.len()));
+            return Ok(());
+        }
+        if !confirm(&tr!("confirm-modify", count = ids.len()))? {
+            out.line(&tr!("nothing-changed"));
+            return Ok(());
+        }
+    }
+
+    let mut updated = 0;
+    for id in &ids {
+        let changed = manager.edit(*id, changes.clone())?;
+        if !changed.is_empty() {
+            out.line(&tr!(
+                "task-updated",
+                id = *id,
+                fields = field_names(&changed)
+            ));
+            updated += 1;
+        }
+    }
+    out.line(&tr!("tasks-updated", updated = updated, count = ids.len()));
+    Ok(())
+}
+
+/// Lists the fields an edit changed, for the messages reporting it.
+fn field_names(fields: &[TaskField]) -> String {
+    let names: Vec<&str> = fields.iter().map(|f| f.name()).collect();
+    names.join(", ")
+}
+
+/// Handles the 'status' command.
+/// Prints the overdue, due today, and pending counts on separate lines, on
+/// one line with `short`, or as a one-row table for programs.
+pub(super) fn handle_status(
+    out: &mut dyn OutputSink,
+    status: StatusSummary,
+    short: bool,
+    config: &Config,
+) {
+    if !config.output_format.for_humans() {
+        let mut table = Table::new(vec![
+            Column {
+                key: "overdue",
+                title: tr!("column-overdue"),
+                width: 7,
+            },
+            Column {
+                key: "due_today",
+                title: tr!("column-due-today"),
+                width: 9,
+            },
+            Column {
+                key: "pending",
+                title: tr!("column-pending"),
+                width: 7,
+            },
+        ]);
+        table.push(vec![
+            Cell::new(status.overdue),
+            Cell::new(status.due_today),
+            Cell::new(status.pending),
+        ]);
+        renderer(config).table(out, &table);
+    } else if short {
+        out.line(&tr!(
+            "status-short",
+            overdue = status.overdue,
+            due_today = status.due_today,
+            pending = status.pending
+        ));
+    } else {
+        print_fields(
+            out,
+            &[
+                (tr!("field-overdue"), status.overdue.to_string()),
+                (tr!("field-due-today"), status.due_today.to_string()),
+                (tr!("field-pending"), status.pending.to_string()),
+            ],
+        );
+    }
+}
+
+/// Handles the 'count' command.
+/// Prints only the number of matching tasks.
+pub(super) fn handle_count(
+    out: &mut dyn OutputSink,
+    manager: &TaskManager,
+    args: &CountSelection,
+) -> Result<()> {
+    outln!(out, "{}", manager.count(args)?);
+    Ok(())
+}
+
+/// Handles the 'move' command.
+pub(super) fn handle_move_task(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    id: u32,
+    list: &str,
+    tasks_path: &Path,
+    config: &Config,
+) -> Result<()> {
+    let target = config.list_file_path(list);
+    if target == tasks_path {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-already-in-list",
+            id = id,
+            list = list
+        )));
+    }
+    let new_id = move_task(tasks, id, &target, config.id_strategy.generator().as_mut())?;
+    out.line(&tr!(
+        "task-moved-to-list",
+        id = id,
+        list = list,
+        new_id = new_id
+    ));
+    Ok(())
+}
+
+/// Handles the 'project status' command.
+pub(super) fn handle_project_status(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    name: &str,
+    config: &Config,
+) -> Result<()> {
+    let status = project_status(tasks, name)
+        .ok_or_else(|| AppError::InvalidArgument(tr!("error-no-tasks-in-project", name = name)))?;
+
+    let completion = format!("{:.0}", status.weighted_completion * 100.0);
+    let unestimated = if status.unestimated_pending > 0 {
+        format!(
+            " {}",
+            tr!("project-unestimated", count = status.unestimated_pending)
+        )
+    } else {
+        String::new()
+    };
+    print_fields(
+        out,
+        &[
+            (tr!("field-project"), status.name.clone()),
+            (
+                tr!("field-completion"),
+                tr!("project-completion", percent = completion),
+            ),
+            (
+                tr!("field-tasks"),
+                tr!(
+                    "project-tasks-done",
+                    done = status.completed,
+                    total = status.total
+                ),
+            ),
+            (
+                tr!("field-remaining"),
+                format!(
+                    "{}{}",
+                    format_minutes(status.remaining_minutes),
+                    unestimated
+                ),
+            ),
+            (
+                tr!("field-nearest-due"),
+                status
+                    .nearest_due
+                    .map_or("N/A".to_string(), |d| config.format_date(d)),
+            ),
+        ],
+    );
+    Ok(())
+}
+
+/// Handles marking a task as complete or incomplete.
+/// Completing a recurring task schedules its next occurrence as a new task.
+pub(super) fn handle_mark_task_completion(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    status: bool,
+    config: &Config,
+) -> Result<()> {
+    if !status {
+        manager.reopen(id)?;
+        out.line(&tr!("task-reopened", id = id));
+        return Ok(());
+    }
+
+    let next = manager.complete(id)?;
+    out.line(&tr!("task-completed", id = id));
+    if let Some(next) = next {
+        let due = next
+            .due_date
+            .map_or("N/A".to_string(), |d| config.format_date(d));
+        out.line(&tr!("next-occurrence-added", id = next.id, due = due));
+    }
+    if config.completion_banner {
+        let points = manager.get(id).map_or(0, completion_points);
+        let streaks = Streaks::build(manager.tasks(), clock::now());
+        let banner = if config.ascii {
+            tr!(
+                "completion-banner-ascii",
+                streak = streaks.current,
+                points = points,
+                total = streaks.points_total
+            )
+        } else {
+            tr!(
+                "completion-banner",
+                streak = streaks.current,
+                points = points,
+                total = streaks.points_total
+            )
+        };
+        out.line(&banner);
+    }
+    Ok(())
+}
+
+/// Handles the 'move-to' command.
+pub(super) fn handle_move_to(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    status: TaskStatus,
+    config: &Config,
+) -> Result<()> {
+    if status == TaskStatus::Done {
+        return handle_mark_task_completion(out, manager, id, true, config);
+    }
+    manager.move_to(id, status)?;
+    out.line(&tr!(
+        "task-moved-to-status",
+        id = id,
+        status = status.title().to_lowercase()
+    ));
+    Ok(())
+}
+
+/// Handles the 'skip' command.
+/// Advances a recurring task to its next occurrence, cancelling it if the series has ended.
+pub(super) fn handle_skip_occurrence(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    config: &Config,
+) -> Result<()> {
+    match manager.skip(id)? {
+        Skipped::Next(next_due) => out.line(&tr!(
+            "occurrence-skipped",
+            id = id,
+            due = config.format_date(next_due)
+        )),
+        Skipped::Ended => out.line(&tr!("series-ended", id = id)),
+    }
+    Ok(())
+}
+
+/// Handles the 'snooze' command.
+pub(super) fn handle_snooze_task(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    until: SnoozeSpec,
+    config: &Config,
+) -> Result<()> {
+    let due = manager.snooze(id, until)?;
+    let count = manager.get(id)?.snooze_count;
+    out.line(&tr!(
+        "task-snoozed",
+        id = id,
+        due = config.format_date(due),
+        count = count
+    ));
+    Ok(())
+}
+
+/// Handles the 'progress' command.
+pub(super) fn handle_set_progress(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    percent: u8,
+) -> Result<()> {
+    manager.set_progress(id, percent)?;
+    out.line(&tr!("progress-set", id = id, percent = percent));
+    Ok(())
+}
+
+/// Handles the 'edit --editor' command.
+/// Applies the fields saved in the editor, then adds the note written there.
+pub(super) fn handle_edit_in_editor(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    changes: &EditArgs,
+) -> Result<()> {
+    if !changes.is_empty() {
+        return Err(AppError::InvalidArgument(tr!("error-editor-with-options")));
+    }
+    let task = manager.get(id)?.clone();
+    let draft = TaskDraft::from_task(&task).render(&tr!("task-heading", id = id));
+    let Some((changes, note)) = edit_until_valid(out, &draft, |draft| draft.edit_args(&task))?
+    else {
+        out.line(&tr!("task-unchanged", id = id));
+        return Ok(());
+    };
+    handle_edit_task(out, manager, id, changes.into())?;
+    if let Some(note) = note {
+        handle_add_note(out, manager, id, note)?;
+    }
+    Ok(())
+}
+
+/// Opens `text` in the editor until what is saved there passes `check`,
+/// offering to edit it again after each mistake. Returns `None` if the file
+/// was emptied.
+pub(super) fn edit_until_valid<T>(
+    out: &mut dyn OutputSink,
+    text: &str,
+    mut check: impl FnMut(&TaskDraft) -> Result<T>,
+) -> Result<Option<T>> {
+    let mut text = text.to_string();
+    loop {
+        text = edit_in_editor(&text)?;
+        let error = match TaskDraft::parse(&text)
+            .and_then(|draft| draft.map(|draft| check(&draft)).transpose())
+        {
+            Ok(checked) => return Ok(checked),
+            Err(e) => e,
+        };
+        if !io::stdin().is_terminal() {
+            return Err(error);
+        }
+        outln!(out, "{}", error);
+        if !confirm(&tr!("confirm-edit-again"))? {
+            return Err(error);
+        }
+    }
+}
+
+/// Handles the 'note' command.
+pub(super) fn handle_add_note(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    text: String,
+) -> Result<()> {
+    manager.note(id, text)?;
+    out.line(&tr!("note-added", id = id));
+    Ok(())
+}
+
+/// Handles the 'check' command.
+pub(super) fn handle_check(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    command: CheckCommands,
+) -> Result<()> {
+    match command {
+        CheckCommands::Add { id, text } => {
+            let number = manager.check_add(id, text)?;
+            out.line(&tr!("checklist-item-added", number = number, id = id));
+        }
+        CheckCommands::Done { id, number } => {
+            let item = manager.check_mark(id, number, true)?;
+            out.line(&tr!("checklist-item-checked", text = item.text.as_str()));
+        }
+        CheckCommands::Undo { id, number } => {
+            let item = manager.check_mark(id, number, false)?;
+            out.line(&tr!("checklist-item-unchecked", text = item.text.as_str()));
+        }
+        CheckCommands::Remove { id, number } => {
+            let item = manager.check_remove(id, number)?;
+            out.line(&tr!("checklist-item-removed", text = item.text.as_str()));
+        }
+    }
+    Ok(())
+}
+
+/// Handles the 'attach' command.
+pub(super) fn handle_attach(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    target: &str,
+) -> Result<()> {
+    let target = resolve_attachment(target)?;
+    let count = manager.attach(id, target.clone())?;
+    out.line(&tr!(
+        "attached",
+        target = target.as_str(),
+        id = id,
+        count = count
+    ));
+    Ok(())
+}
+
+/// Handles the 'open' command.
+/// Opens the task's URL, or the attachment numbered `number`, by default the
+/// first.
+pub(super) fn handle_open(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    id: u32,
+    number: Option<usize>,
+) -> Result<()> {
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::task_not_found(id, tasks))?;
+    let target = match (&task.url, number) {
+        (Some(url), None) => url,
+        (_, number) => {
+            let number = number.unwrap_or(1);
+            let attachment = number
+                .checked_sub(1)
+                .and_then(|i| task.attachments.get(i))
+                .ok_or_else(|| {
+                    AppError::InvalidArgument(match task.attachments.len() {
+                        0 => tr!("error-nothing-attached", id = id),
+                        n => tr!("error-no-attachment", id = id, count = n, number = number),
+                    })
+                })?;
+            &attachment.target
+        }
+    };
+    open_target(target)?;
+    out.line(&tr!("opened", target = target.as_str()));
+    Ok(())
+}
+
+/// Handles the 'yank' command.
+/// Copies the description, or with `url` the URL, of the task with ID `id`
+/// to the clipboard.
+pub(super) fn handle_yank(
+    out: &mut dyn OutputSink,
+    tasks: &[Task],
+    id: u32,
+    url: bool,
+) -> Result<()> {
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::task_not_found(id, tasks))?;
+    if url {
+        let url = task
+            .url
+            .as_deref()
+            .ok_or_else(|| AppError::InvalidArgument(tr!("error-no-url", id = id)))?;
+        write_clipboard(url)?;
+        out.line(&tr!("yanked-url", id = id));
+    } else {
+        write_clipboard(&task.description)?;
+        out.line(&tr!("yanked-description", id = id));
+    }
+    Ok(())
+}
+
+/// Handles the 'remove' command.
+/// Asks for confirmation when stdin is a terminal; scripts are never prompted.
+/// The task is moved to the trash rather than deleted.
+pub(super) fn handle_remove_task(
+    out: &mut dyn OutputSink,
+    manager: &mut TaskManager,
+    id: u32,
+    confirmed: bool,
+    trash_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        manager.remove(id)?;
+        out.line(&tr!("would-trash", id = id));
+        return Ok(());
+    }
+    let description = &manager.get(id)?.description;
+    if !confirmed
+        && io::stdin().is_terminal()
+        && !confirm(&tr!(
+            "confirm-remove",
+            id = id,
+            description = truncate(description, DESCRIPTION_WIDTH)
+        ))?
+    {
+        out.line(&tr!("nothing-removed"));
+        return Ok(());
+    }
+
+    let task = manager.remove(id)?;
+    move_to_trash(vec![task], trash_path, clock::now())?;
+    out.line(&tr!("task-trashed", id = id));
+    Ok(())
+}
+
+/// Purges trash entries older than the configured retention.
+pub(super) fn purge_expired_trash(trash_path: &Path, config: &Config, dry_run: bool) -> Result<()> {
+    if dry_run || !trash_path.exists() {
+        return Ok(());
+    }
+    let mut trash = load_trash(trash_path)?;
+    let purged = purge_trash(&mut trash, clock::now(), config.trash_retention_days);
+    if purged > 0 {
+        info!("Purged {} task(s) from the trash.", purged);
+        save_trash(&trash, trash_path)?;
+    }
+    Ok(())
+}
+
+/// Handles the 'trash' subcommands.
+pub(super) fn handle_trash(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    ids: &mut dyn IdGenerator,
+    command: TrashCommands,
+    trash_path: &Path,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let mut trash = load_trash(trash_path)?;
+    match command {
+        TrashCommands::List => {
+            if trash.is_empty() {
+                out.line(&tr!("trash-empty"));
+                return Ok(());
+            }
+            let mut table = Table::new(vec![
+                Column {
+                    key: "id",
+                    title: tr!("column-id"),
+                    width: 4,
+                },
+                Column {
+                    key: "description",
+                    title: tr!("column-description"),
+                    width: DESCRIPTION_WIDTH,
+                },
+                Column {
+                    key: "removed",
+                    title: tr!("column-removed"),
+                    width: 11,
+                },
+                Column {
+                    key: "purged_after",
+                    title: tr!("column-purged-after"),
+                    width: 12,
+                },
+            ]);
+            for entry in &trash {
+                let removed = clock::local_date(entry.deleted_at);
+                let purge_on = removed + chrono::Duration::days(config.trash_retention_days.into());
+                table.push(vec![
+                    Cell::new(entry.task.id),
+                    Cell::new(entry.task.description.as_str()),
+                    Cell::new(removed.to_string()).shown_as(config.format_date(removed)),
+                    Cell::new(purge_on.to_string()).shown_as(config.format_date(purge_on)),
+                ]);
+            }
+            renderer(config).table(out, &table);
+        }
+        TrashCommands::Restore { id } => {
+            let position = trash
+                .iter()
+                .rposition(|entry| entry.task.id == id)
+                .ok_or_else(|| AppError::task_not_found(id, trash.iter().map(|e| &e.task)))?;
+            let mut task = trash.remove(position).task;
+            if tasks.iter().any(|t| t.id == task.id) {
+                task.id = ids.next_id(tasks);
+                out.line(&tr!("task-restored-as", id = id, new_id = task.id));
+            } else {
+                out.line(&tr!("task-restored", id = id));
+            }
+            tasks.push(task);
+            if !dry_run {
+                save_trash(&trash, trash_path)?;
+            }
+        }
+        TrashCommands::Empty { yes } => {
+            if trash.is_empty() {
+                out.line(&tr!("trash-already-empty"));
+                return Ok(());
+            }
+            let count = trash.len();
+            if dry_run {
+                out.line(&tr!("would-delete-permanently", count = count));
+                return Ok(());
+            }
+            if !yes {
+                if !io::stdin().is_terminal() {
+                    out.line(&tr!("empty-trash-needs-yes", count = count));
+                    return Ok(());
+                }
+                if !confirm(&tr!("confirm-empty-trash", count = count))? {
+                    out.line(&tr!("nothing-deleted"));
+                    return Ok(());
+                }
+            }
+            save_trash(&[], trash_path)?;
+            out.line(&tr!("deleted-permanently", count = count));
+        }
+    }
+    Ok(())
+}
+
+/// Handles the hidden '__complete-ids' command used by completion scripts.
+/// Prints one pending task per line as `<id>\t<description>`.
+pub(super) fn handle_complete_ids(out: &mut dyn OutputSink, tasks: &[Task]) {
+    for task in tasks.iter().filter(|t| t.is_pending()) {
+        outln!(out, "{}\t{}", task.id, task.description);
+    }
+}
+
+/// Handles the 'clear' command.
+/// Moves all tasks to the trash after confirmation.
+pub(super) fn handle_clear_tasks(
+    out: &mut dyn OutputSink,
+    tasks: &mut Vec<Task>,
+    confirmed: bool,
+    trash_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        out.line(&tr!("would-clear", count = tasks.len()));
+        tasks.clear();
+        return Ok(());
+    }
+    if !confirmed {
+        if !io::stdin().is_terminal() {
+            out.line(&tr!("clear-needs-yes"));
+            return Ok(());
+        }
+        if !confirm(&tr!("confirm-clear", count = tasks.len()))? {
+            out.line(&tr!("nothing-removed"));
+            return Ok(());
+        }
+    }
+
+    let num_cleared = tasks.len();
+    move_to_trash(std::mem::take(tasks), trash_path, clock::now())?;
+    out.line(&tr!("cleared", count = num_cleared));
+    Ok(())
+}
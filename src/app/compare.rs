@@ -0,0 +1,119 @@
+//! Semantic comparison of two task files.
+//!
+//! Tasks are matched by UUID, so renumbered tasks still pair up. Fields are
+//! compared as they would be serialized, which covers every field without
+//! listing them here.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::app::{Result, Task};
+
+/// How `compare` prints its report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompareFormat {
+    /// A human-readable summary.
+    #[default]
+    Text,
+    /// A JSON document for tooling.
+    Json,
+}
+
+/// One field that differs between the two versions of a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    /// The value in the first file, or `null` if the field was unset.
+    pub before: Value,
+    /// The value in the second file, or `null` if the field was unset.
+    pub after: Value,
+}
+
+/// A task present in both files with different fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub uuid: Uuid,
+    /// The ID in the second file.
+    pub id: u32,
+    pub description: String,
+    pub fields: Vec<FieldChange>,
+}
+
+/// The differences between two task files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskDiff {
+    /// Tasks only in the second file.
+    pub added: Vec<Task>,
+    /// Tasks only in the first file.
+    pub removed: Vec<Task>,
+    pub changed: Vec<TaskChange>,
+}
+
+impl TaskDiff {
+    /// Compares `before` against `after`, keeping the order of each file.
+    pub fn between(before: &[Task], after: &[Task]) -> Result<Self> {
+        let old: HashMap<Uuid, &Task> = before.iter().map(|t| (t.uuid, t)).collect();
+        let new: HashMap<Uuid, &Task> = after.iter().map(|t| (t.uuid, t)).collect();
+
+        let mut diff = TaskDiff {
+            removed: before
+                .iter()
+                .filter(|t| !new.contains_key(&t.uuid))
+                .cloned()
+                .collect(),
+            ..TaskDiff::default()
+        };
+        for task in after {
+            match old.get(&task.uuid) {
+                None => diff.added.push(task.clone()),
+                Some(previous) => {
+                    let fields = changed_fields(previous, task)?;
+                    if !fields.is_empty() {
+                        diff.changed.push(TaskChange {
+                            uuid: task.uuid,
+                            id: task.id,
+                            description: task.description.clone(),
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Returns `true` if the files hold the same tasks.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn changed_fields(before: &Task, after: &Task) -> Result<Vec<FieldChange>> {
+    let (Value::Object(before), Value::Object(after)) =
+        (serde_json::to_value(before)?, serde_json::to_value(after)?)
+    else {
+        return Ok(Vec::new());
+    };
+
+    // Fields of `after` in declaration order, then any only `before` has.
+    // When and by whom the task was changed follows from the changes
+    // themselves.
+    let mut names: Vec<&String> = after.keys().collect();
+    names.extend(before.keys().filter(|k| !after.contains_key(*k)));
+    names.retain(|name| *name != "updated_at" && *name != "updated_by");
+
+    Ok(names
+        .into_iter()
+        .filter_map(|name| {
+            let old = before.get(name).cloned().unwrap_or(Value::Null);
+            let new = after.get(name).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| FieldChange {
+                field: name.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect())
+}
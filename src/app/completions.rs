@@ -0,0 +1,59 @@
+//! Generates shell completion scripts for the command-line interface.
+//!
+//! The static part of each script comes from `clap_complete`. For shells that
+//! support it, a small snippet is appended that completes task IDs by calling
+//! back into the binary's hidden `__complete-ids` subcommand.
+
+use clap_complete::{Shell, generate};
+use std::io::{self, Write};
+
+use crate::app::{BIN_NAME, ID_COMMANDS, build_cli};
+
+/// Writes the completion script for `shell` to `out`.
+///
+/// # Arguments
+///
+/// * `shell` - The shell to generate the script for.
+/// * `out` - Where the script is written, usually standard output.
+pub fn write_completions(shell: Shell, out: &mut impl Write) -> io::Result<()> {
+    let mut command = build_cli();
+    generate(shell, &mut command, BIN_NAME, out);
+
+    if let Some(snippet) = dynamic_id_snippet(shell) {
+        writeln!(out)?;
+        out.write_all(snippet.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Returns the shell code that completes task IDs, if the shell is supported.
+fn dynamic_id_snippet(shell: Shell) -> Option<String> {
+    let commands = ID_COMMANDS.join(" ");
+    match shell {
+        Shell::Bash => Some(format!(
+            r#"# Complete task IDs for commands that take one.
+_{bin}_with_ids() {{
+    if [[ ${{COMP_CWORD}} -eq 2 ]]; then
+        case " {commands} " in
+            *" ${{COMP_WORDS[1]}} "*)
+                COMPREPLY=( $(compgen -W "$({bin} __complete-ids 2>/dev/null | cut -f1)" -- "${{COMP_WORDS[COMP_CWORD]}}") )
+                return 0
+                ;;
+        esac
+    fi
+    _{bin} "$@"
+}}
+complete -F _{bin}_with_ids -o bashdefault -o default {bin}
+"#,
+            bin = BIN_NAME,
+            commands = commands
+        )),
+        Shell::Fish => Some(format!(
+            "# Complete task IDs for commands that take one.\n\
+             complete -c {bin} -n \"__fish_seen_subcommand_from {commands}\" -f -a \"({bin} __complete-ids 2>/dev/null)\"\n",
+            bin = BIN_NAME,
+            commands = commands
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,631 @@
+//! Loads user settings from `config.toml`.
+//!
+//! The file lives in the platform configuration directory
+//! (e.g. `~/.config/task_manager/config.toml` on Linux), or wherever the
+//! `TASK_CONFIG` environment variable points. A missing file means defaults.
+//!
+//! Settings are resolved in three layers: command-line flags override the
+//! config file, which overrides the built-in defaults.
+//!
+//! Inside a git repository with a `.tasks.toml` at its root, or in a
+//! directory between the root and the working directory, that file is the
+//! data file unless `--global`, `--file`, or `--list` is given, so each
+//! codebase can keep its own list. Create an empty `.tasks.toml` to start
+//! one.
+//!
+//! ```toml
+//! id_strategy = "sequential"
+//! date_format = "%d.%m.%Y"
+//! date_order = "DMY"
+//! color = "auto"
+//! ascii = false
+//! output_format = "table"
+//! timezone = "Europe/Berlin"
+//! data_file = "/home/me/tasks.toml"
+//! storage_format = "toml"
+//! default_list = "personal"
+//! lists_dir = "/home/me/lists"
+//! fallback_data_file = "/tmp/tasks.toml"
+//! default_priority = "medium"
+//! check_duplicates = true
+//! index_cache = true
+//! repo_lists = true
+//! trash_retention_days = 30
+//! autosave_interval = 30
+//! weekly_goal = 10
+//! completion_banner = true
+//! identity = "alice"
+//! shared = true
+//! language = "de"
+//! context = "work"
+//!
+//! [list]
+//! all = false
+//! long = true
+//! group_by = "project"
+//! sort = "due"
+//! absolute = false
+//! columns = ["id", "desc", "due", "tags"]
+//!
+//! [list.widths]
+//! description = 50
+//!
+//! [contexts]
+//! work = "project:acme or tag:work"
+//!
+//! [aliases]
+//! wip = "list tag:wip --sort priority"
+//!
+//! [urgency]
+//! due = 12.0
+//! blocked = -5.0
+//!
+//! [audit]
+//! journal = true
+//!
+//! [sync]
+//! remote = "git@example.com:me/tasks.git"
+//!
+//! [agenda]
+//! to = "me@example.com"
+//!
+//! [plan]
+//! capacity_hours = 6
+//!
+//! [limits]
+//! in_progress = 3
+//!
+//! [smtp]
+//! host = "smtp.example.com"
+//! username = "me@example.com"
+//!
+//! [[escalation]]
+//! after_hours = 24
+//! channel = "email"
+//! to = "me@example.com"
+//!
+//! [[reminders]]
+//! channel = "ntfy"
+//! topic = "my-tasks"
+//! ```
+
+use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    AgendaConfig, AppError, AuditConfig, Channel, Cli, DEFAULT_AUTOSAVE_INTERVAL,
+    DEFAULT_TRASH_RETENTION_DAYS, DateOrder, EscalationLevel, GitHubConfig, GroupBy, IdStrategy,
+    JiraConfig, Language, LimitsConfig, ListArgs, ListColumn, Past, PlanConfig, Priority, Query,
+    RenderFormat, Result, SmtpConfig, SortKey, StorageFormat, SyncConfig, Task, TodoistConfig,
+    UrgencyConfig, clock, parse_list_name, relative_date, storage, validate_aliases,
+};
+
+/// The directory name used inside the platform configuration directory.
+pub const CONFIG_DIR_NAME: &str = "task_manager";
+
+/// The name of the configuration file.
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The name of the task list a git repository keeps for itself.
+pub const REPO_LIST_FILE_NAME: &str = ".tasks.toml";
+
+/// The environment variable that overrides the config file location.
+const CONFIG_ENV_VAR: &str = "TASK_CONFIG";
+
+/// The built-in date display format.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The `date_format` that shows dates relative to today.
+pub const RELATIVE_DATE_FORMAT: &str = "relative";
+
+/// When to color terminal output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Decides whether standard output should be colored.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Defaults applied to the `list` command when its flags are not given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListDefaults {
+    /// Include completed and cancelled tasks.
+    pub all: bool,
+    /// Use the long table format.
+    pub long: bool,
+    /// Group the output by this field.
+    pub group_by: Option<GroupBy>,
+    /// Sort the output by this field.
+    pub sort: Option<SortKey>,
+    /// Show due dates as dates rather than relative to today.
+    pub absolute: bool,
+    /// The columns to show, in order; empty means the usual ones.
+    pub columns: Vec<ListColumn>,
+    /// How wide to draw columns, overriding their usual widths.
+    pub widths: BTreeMap<ListColumn, usize>,
+}
+
+impl ListDefaults {
+    /// Fills in settings the command line left unset.
+    ///
+    /// A boolean enabled here is switched off again by its negating flag:
+    /// `--no-all`, `--short`, or `--relative`.
+    pub fn apply(&self, args: &mut ListArgs) {
+        args.all = args.all || (self.all && !args.no_all);
+        args.long = args.long || (self.long && !args.short);
+        args.group_by = args.group_by.or(self.group_by);
+        args.sort = args.sort.or(self.sort);
+        args.absolute = args.absolute || (self.absolute && !args.relative);
+        if args.columns.is_empty() {
+            args.columns = self.columns.clone();
+        }
+    }
+
+    /// Returns how wide `column` is drawn.
+    pub fn width(&self, column: ListColumn) -> usize {
+        self.widths
+            .get(&column)
+            .copied()
+            .unwrap_or_else(|| column.default_width())
+    }
+}
+
+/// User settings read from the config file.
+///
+/// Every field has a default, so a partial file only overrides what it mentions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// How IDs are assigned to new tasks.
+    pub id_strategy: IdStrategy,
+    /// The `strftime`-style format used to display dates, or `relative` for
+    /// dates like "in 3 days".
+    pub date_format: String,
+    /// How to read numeric dates like `03/04/2025` that are valid either way;
+    /// unset means such dates are rejected.
+    pub date_order: Option<DateOrder>,
+    /// When to color terminal output.
+    pub color: ColorChoice,
+    /// Print textual markers such as `[OVERDUE]` and `[P1]` instead of
+    /// colors and Unicode symbols, for screen readers and dumb terminals.
+    pub ascii: bool,
+    /// How lists of tasks and projects are printed.
+    pub output_format: RenderFormat,
+    /// The IANA time zone due dates and "today" are read in; unset means the
+    /// system's zone.
+    pub timezone: Option<Tz>,
+    /// Where tasks are stored; defaults to `tasks.toml` in the working directory.
+    /// A file ending in `.jsonl` is kept as an append-only event log.
+    pub data_file: Option<PathBuf>,
+    /// The named list to work on when `--list` isn't given; overrides
+    /// `data_file`.
+    pub default_list: Option<String>,
+    /// Where named lists are kept; defaults to `lists` in the platform data
+    /// directory.
+    pub lists_dir: Option<PathBuf>,
+    /// The format new data files are written in; existing files keep theirs
+    /// until `convert-storage` switches them.
+    pub storage_format: StorageFormat,
+    /// Where tasks are saved when `data_file` can't be written, e.g. on a
+    /// read-only or full filesystem; defaults to `tasks.toml` in the platform
+    /// data directory.
+    pub fallback_data_file: Option<PathBuf>,
+    /// Whether a `.tasks.toml` in the current git repository is used instead
+    /// of the data file.
+    pub repo_lists: bool,
+    /// The repository's own task list, found when the command started.
+    #[serde(skip)]
+    pub repo_list: Option<PathBuf>,
+    /// The priority given to new tasks added without `--priority`.
+    pub default_priority: Option<Priority>,
+    /// Whether `add` looks for a pending task reading like the new one
+    /// first. Without the check, `add` appends to a plain data file without
+    /// reading it whole, which is faster for large ones.
+    pub check_duplicates: bool,
+    /// Whether to keep a small index next to the data file, so `count`,
+    /// `status`, and appending `add`s skip reading every task of a large one.
+    pub index_cache: bool,
+    /// How many days removed tasks stay in the trash before being purged.
+    pub trash_retention_days: u32,
+    /// How often `triage` and `review` write their edits so far next to the
+    /// data file, in seconds, so a crash doesn't lose them.
+    pub autosave_interval: u64,
+    /// How many tasks to complete each week, shown by `list` and `stats`.
+    pub weekly_goal: Option<u32>,
+    /// Whether `complete` prints the current streak and points earned.
+    pub completion_banner: bool,
+    /// Your name in the `assignee` field of shared lists, for `list --mine`;
+    /// unset means the login name.
+    pub identity: Option<String>,
+    /// Whether several people work on the data file, e.g. a household's
+    /// list in a synced folder; tasks and the audit journal then record who
+    /// added, completed, and changed them, under `identity`.
+    pub shared: bool,
+    /// The language messages are printed in; unset means the one the
+    /// environment names, e.g. with `LANG`.
+    pub language: Option<Language>,
+    /// Defaults for the `list` command.
+    pub list: ListDefaults,
+    /// Queries saved with `context define`, by name.
+    pub contexts: BTreeMap<String, String>,
+    /// The context narrowing `list` and `count`; set with `context set`.
+    pub context: Option<String>,
+    /// Shorthands for commands, by name; see
+    /// [`expand_aliases`](crate::app::expand_aliases).
+    pub aliases: BTreeMap<String, String>,
+    /// Notifications sent as a task stays pending past its due date.
+    pub escalation: Vec<EscalationLevel>,
+    /// Where `remind` and `daemon` send reminders; a desktop notification
+    /// by default.
+    pub reminders: Vec<Channel>,
+    /// Where `sync` pushes and pulls the data file.
+    pub sync: SyncConfig,
+    /// The journal of changes and how it is signed.
+    pub audit: AuditConfig,
+    /// The weights of the urgency score `next` ranks tasks by.
+    pub urgency: UrgencyConfig,
+    /// How `import --from todoist` and `export --format todoist` reach Todoist.
+    pub todoist: TodoistConfig,
+    /// How `github` reaches GitHub.
+    pub github: GitHubConfig,
+    /// Which Jira server `jira pull` mirrors issues from.
+    pub jira: JiraConfig,
+    /// Who `agenda --email` sends the agenda to.
+    pub agenda: AgendaConfig,
+    /// How much work `plan` fits in a day.
+    pub plan: PlanConfig,
+    /// How many tasks may be in progress, or due on a day, before commands
+    /// warn.
+    pub limits: LimitsConfig,
+    /// The server email is sent through; unset means `sendmail`.
+    pub smtp: SmtpConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            id_strategy: IdStrategy::default(),
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            date_order: None,
+            color: ColorChoice::default(),
+            ascii: false,
+            output_format: RenderFormat::default(),
+            timezone: None,
+            data_file: None,
+            default_list: None,
+            lists_dir: None,
+            storage_format: StorageFormat::default(),
+            fallback_data_file: None,
+            repo_lists: true,
+            repo_list: None,
+            default_priority: None,
+            check_duplicates: true,
+            index_cache: false,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            autosave_interval: DEFAULT_AUTOSAVE_INTERVAL,
+            weekly_goal: None,
+            completion_banner: false,
+            identity: None,
+            shared: false,
+            language: None,
+            list: ListDefaults::default(),
+            contexts: BTreeMap::new(),
+            context: None,
+            aliases: BTreeMap::new(),
+            escalation: Vec::new(),
+            reminders: vec![Channel::Desktop],
+            sync: SyncConfig::default(),
+            audit: AuditConfig::default(),
+            urgency: UrgencyConfig::default(),
+            todoist: TodoistConfig::default(),
+            github: GitHubConfig::default(),
+            jira: JiraConfig::default(),
+            agenda: AgendaConfig::default(),
+            plan: PlanConfig::default(),
+            limits: LimitsConfig::default(),
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, falling back to defaults if it doesn't exist.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Config`, or an `AppError` if the file
+    /// exists but can't be read or parsed, or contains invalid values.
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_file_path() else {
+            debug!("No config directory available. Using default settings.");
+            return Ok(Config::default());
+        };
+
+        if !path.exists() {
+            debug!(
+                "Config file not found at {}. Using defaults.",
+                path.display()
+            );
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let config: Config = toml::from_str(&contents)
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        config
+            .validate()
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        info!("Loaded config from {}.", path.display());
+        Ok(config)
+    }
+
+    /// Applies global command-line flags, which take precedence over the file.
+    pub fn apply_cli(&mut self, cli: &Cli) -> Result<()> {
+        if let Some(file) = &cli.file {
+            self.data_file = Some(file.clone());
+            self.default_list = None;
+        }
+        if let Some(list) = &cli.list {
+            self.default_list = Some(list.clone());
+        }
+        if cli.file.is_none() && cli.list.is_none() && !cli.global && self.repo_lists {
+            self.repo_list = env::current_dir().ok().and_then(|dir| find_repo_list(&dir));
+            if let Some(path) = &self.repo_list {
+                debug!("Using the repository's task list {}.", path.display());
+            }
+        }
+        if let Some(color) = cli.color {
+            self.color = color;
+        }
+        self.ascii |= cli.ascii;
+        if self.ascii {
+            self.color = ColorChoice::Never;
+        }
+        if let Some(format) = cli.output_format {
+            self.output_format = format;
+        }
+        if let Some(format) = &cli.date_format {
+            validate_date_format(format).map_err(AppError::InvalidArgument)?;
+            self.date_format = format.clone();
+        }
+        Ok(())
+    }
+
+    /// Returns the data file location: the repository's own list or the
+    /// current named list if there is one, otherwise `data_file` or the
+    /// built-in default.
+    pub fn tasks_file_path(&self) -> PathBuf {
+        if let Some(path) = &self.repo_list {
+            return path.clone();
+        }
+        match &self.default_list {
+            Some(name) => self.list_file_path(name),
+            None => self
+                .data_file
+                .clone()
+                .unwrap_or_else(storage::default_tasks_file_path),
+        }
+    }
+
+    /// Returns the directory named lists are kept in.
+    pub fn lists_dir(&self) -> PathBuf {
+        self.lists_dir.clone().unwrap_or_else(|| {
+            dirs::data_local_dir()
+                .map(|dir| dir.join(CONFIG_DIR_NAME))
+                .unwrap_or_default()
+                .join("lists")
+        })
+    }
+
+    /// Returns the data file of the named list `name`.
+    pub fn list_file_path(&self, name: &str) -> PathBuf {
+        self.lists_dir().join(format!("{}.toml", name))
+    }
+
+    /// Returns where to save when the data file can't be written, or `None` if
+    /// no fallback location distinct from the data file is available.
+    pub fn fallback_tasks_file_path(&self) -> Option<PathBuf> {
+        let fallback = self.fallback_data_file.clone().or_else(|| {
+            dirs::data_local_dir().map(|dir| dir.join(CONFIG_DIR_NAME).join("tasks.toml"))
+        })?;
+        (fallback != self.tasks_file_path()).then_some(fallback)
+    }
+
+    /// Checks values that can't be validated by deserialization alone.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        validate_date_format(&self.date_format)?;
+        if let Some((column, _)) = self.list.widths.iter().find(|(_, width)| **width == 0) {
+            return Err(format!(
+                "the width of the {} column must be at least 1",
+                column.key()
+            ));
+        }
+        if !(self.plan.capacity_hours.is_finite() && self.plan.capacity_hours > 0.0) {
+            return Err("plan.capacity_hours must be more than 0".to_string());
+        }
+        if let Some(name) = &self.default_list {
+            parse_list_name(name)?;
+        }
+        for (name, query) in &self.contexts {
+            query
+                .parse::<Query>()
+                .map_err(|e| format!("context '{}': {}", name, e))?;
+        }
+        if let Some(name) = &self.context
+            && !self.contexts.contains_key(name)
+        {
+            return Err(format!("the active context '{}' isn't defined", name));
+        }
+        validate_aliases(&self.aliases)?;
+        Ok(())
+    }
+
+    /// Returns the name `list --mine` looks for: `identity`, or the login
+    /// name from `USER` or `USERNAME`.
+    ///
+    /// # Returns
+    ///
+    /// An `AppError::Config` if neither is set.
+    pub fn me(&self) -> Result<String> {
+        self.identity
+            .clone()
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| {
+                AppError::Config("set `identity` in the config file to use --mine".to_string())
+            })
+    }
+
+    /// Returns who changes are attributed to: the same name as
+    /// [`me`](Self::me) when the data file is `shared`, and nobody otherwise.
+    pub fn actor(&self) -> Option<String> {
+        if self.shared { self.me().ok() } else { None }
+    }
+
+    /// Narrows `query` by the active context, if there is one.
+    pub fn in_context(&self, query: Option<Query>) -> Option<Query> {
+        let context = self
+            .context
+            .as_ref()
+            .and_then(|name| self.contexts.get(name))
+            .and_then(|query| query.parse::<Query>().ok());
+        match (context, query) {
+            (Some(context), Some(query)) => Some(context.and(query)),
+            (context, query) => context.or(query),
+        }
+    }
+
+    /// Formats `date` with the configured date format.
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        if self.date_format == RELATIVE_DATE_FORMAT {
+            return relative_date(date, clock::today(), Past::Ago);
+        }
+        date.format(&self.date_format).to_string()
+    }
+
+    /// Formats the due date of `task` followed by its time of day, if any.
+    pub fn format_due(&self, task: &Task) -> Option<String> {
+        let date = self.format_date(task.due_date?);
+        Some(match task.due_time {
+            Some(time) => format!("{} {}", date, time.format("%H:%M")),
+            None => date,
+        })
+    }
+}
+
+/// Rejects `strftime` patterns chrono can't render for a plain date,
+/// such as unknown specifiers or time-of-day fields. `relative` is allowed.
+pub fn validate_date_format(format: &str) -> std::result::Result<(), String> {
+    if format == RELATIVE_DATE_FORMAT {
+        return Ok(());
+    }
+    let mut rendered = String::new();
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+        || write!(rendered, "{}", NaiveDate::MIN.format(format)).is_err()
+    {
+        return Err(format!("invalid date format '{}'", format));
+    }
+    Ok(())
+}
+
+/// Returns the `.tasks.toml` nearest to `dir` within the git repository
+/// containing it, or `None` outside a repository or if it has none.
+pub fn find_repo_list(dir: &Path) -> Option<PathBuf> {
+    let mut nearest = None;
+    for ancestor in dir.ancestors() {
+        let candidate = ancestor.join(REPO_LIST_FILE_NAME);
+        if nearest.is_none() && candidate.is_file() {
+            nearest = Some(candidate);
+        }
+        if ancestor.join(".git").exists() {
+            return nearest;
+        }
+    }
+    None
+}
+
+/// Returns the directory holding the config file and other per-user files.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME))
+}
+
+/// Determines the path of the config file, honoring `TASK_CONFIG`.
+pub fn config_file_path() -> Option<PathBuf> {
+    match env::var_os(CONFIG_ENV_VAR) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => config_dir().map(|dir| dir.join(CONFIG_FILE_NAME)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn list_args(flags: &[&str]) -> ListArgs {
+        let cli = Cli::try_parse_from(["tm", "list"].iter().chain(flags)).unwrap();
+        match cli.command {
+            crate::app::Commands::List(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    fn enabled() -> ListDefaults {
+        ListDefaults {
+            all: true,
+            long: true,
+            absolute: true,
+            ..ListDefaults::default()
+        }
+    }
+
+    #[test]
+    fn config_fills_in_unset_flags() {
+        let mut args = list_args(&[]);
+        enabled().apply(&mut args);
+        assert!(args.all && args.long && args.absolute);
+    }
+
+    #[test]
+    fn negating_flags_override_config() {
+        let mut args = list_args(&["--no-all", "--short", "--relative"]);
+        enabled().apply(&mut args);
+        assert!(!args.all && !args.long && !args.absolute);
+    }
+
+    #[test]
+    fn last_of_a_flag_pair_wins() {
+        let mut args = list_args(&["--no-all", "--all", "--long", "--short"]);
+        ListDefaults::default().apply(&mut args);
+        assert!(args.all);
+        assert!(!args.long);
+    }
+}
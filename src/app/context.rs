@@ -0,0 +1,86 @@
+//! Named queries that narrow what `list` and `count` show, as Taskwarrior's
+//! contexts do.
+//!
+//! `context define work "project:acme or tag:work"` stores the query under
+//! `[contexts]` in the config file, and `context set work` makes it the
+//! active one until `context none`. The config file is edited in place, so
+//! its comments and layout are kept.
+//!
+//! ```toml
+//! context = "work"
+//!
+//! [contexts]
+//! work = "project:acme or tag:work"
+//! ```
+
+use log::info;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, value};
+
+use crate::app::{AppError, Query, Result, config_file_path};
+
+/// Stores `query` as the context `name`, replacing any context of that name.
+pub fn define_context(name: &str, query: &Query) -> Result<PathBuf> {
+    edit_config_file(|document| {
+        let contexts = document
+            .entry("contexts")
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_like_mut()
+            .ok_or_else(|| AppError::Config("`contexts` is not a table".to_string()))?;
+        contexts.insert(name, value(query.to_string()));
+        Ok(())
+    })
+}
+
+/// Removes the context `name`, and deactivates it if it is active.
+pub fn delete_context(name: &str) -> Result<PathBuf> {
+    edit_config_file(|document| {
+        let removed = document
+            .get_mut("contexts")
+            .and_then(Item::as_table_like_mut)
+            .and_then(|contexts| contexts.remove(name));
+        if removed.is_none() {
+            return Err(AppError::Config(format!("no context named '{}'", name)));
+        }
+        if document.get("context").and_then(Item::as_str) == Some(name) {
+            document.remove("context");
+        }
+        Ok(())
+    })
+}
+
+/// Makes `name` the active context, or deactivates the active one if `name`
+/// is `None`.
+pub fn set_context(name: Option<&str>) -> Result<PathBuf> {
+    edit_config_file(|document| {
+        match name {
+            Some(name) => document.insert("context", value(name)),
+            None => document.remove("context"),
+        };
+        Ok(())
+    })
+}
+
+/// Applies `edit` to the config file, creating the file if needed.
+fn edit_config_file(edit: impl FnOnce(&mut DocumentMut) -> Result<()>) -> Result<PathBuf> {
+    let path = config_file_path()
+        .ok_or_else(|| AppError::Config("no config directory available".to_string()))?;
+    let mut document = read_document(&path)?;
+    edit(&mut document)?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, document.to_string())?;
+    info!("Updated {}.", path.display());
+    Ok(path)
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    fs::read_to_string(path)?
+        .parse()
+        .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))
+}
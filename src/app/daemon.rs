@@ -0,0 +1,141 @@
+//! A long-running process that shows reminders on time without cron.
+//!
+//! The daemon polls the data file for changes and reloads it when it was
+//! modified, so tasks added with other commands are picked up right away.
+//! Between changes it wakes up when the next reminder becomes due. SIGINT
+//! and SIGTERM stop it after the current iteration; tasks are only ever
+//! written right after a reminder was shown, so stopping never loses state.
+
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::app::{
+    AppError, Channel, Config, Reminder, Result, Task, build_reminders, clock, mark_reminded,
+    next_reminder_check, storage,
+};
+
+/// How often the data file is checked for changes by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Installs SIGINT/SIGTERM handlers that set the returned flag.
+pub fn install_shutdown_handler() -> Result<Arc<AtomicBool>> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&shutdown);
+    ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst))
+        .map_err(|e| AppError::Unexpected(format!("failed to install signal handler: {}", e)))?;
+    Ok(shutdown)
+}
+
+/// Sends `reminder` through each of `channels` and records it on the task.
+///
+/// The reminder counts as delivered when any channel took it; the others'
+/// failures are logged. It fails only when every channel did.
+pub fn deliver_reminder(
+    tasks: &mut [Task],
+    reminder: &Reminder,
+    channels: &[Channel],
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let mut first_error = None;
+    let mut delivered = false;
+    for channel in channels {
+        match channel.send(&reminder.title, &reminder.body) {
+            Ok(()) => delivered = true,
+            Err(e) => {
+                warn!(
+                    "Reminder for task {} through {} failed: {}",
+                    reminder.task_id,
+                    channel.name(),
+                    e
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if !delivered && let Some(e) = first_error {
+        return Err(e);
+    }
+    if let Some(task) = tasks.iter_mut().find(|t| t.id == reminder.task_id) {
+        mark_reminded(task, now);
+    }
+    Ok(())
+}
+
+/// The reminder scheduler loop.
+#[derive(Debug, Clone)]
+pub struct Daemon {
+    config: Config,
+    tasks_path: PathBuf,
+    poll_interval: Duration,
+}
+
+impl Daemon {
+    /// Creates a daemon watching the data file at `tasks_path`.
+    pub fn new(config: Config, tasks_path: PathBuf, poll_interval: Duration) -> Self {
+        Daemon {
+            config,
+            tasks_path,
+            poll_interval,
+        }
+    }
+
+    /// Runs until `shutdown` is set.
+    ///
+    /// Failing to show a notification is logged and retried at the next
+    /// check; failing to read or write the data file stops the daemon.
+    pub fn run(&self, shutdown: &AtomicBool) -> Result<()> {
+        info!("Daemon watching {}.", self.tasks_path.display());
+        let mut seen = None;
+        let mut next_check = clock::now();
+
+        while !shutdown.load(Ordering::SeqCst) {
+            let modified = modified_time(&self.tasks_path);
+            let now = clock::now();
+            if modified != seen || now >= next_check {
+                if modified != seen {
+                    debug!("{} changed; rescheduling.", self.tasks_path.display());
+                }
+                let tasks = self.tick(now)?;
+                next_check = next_reminder_check(&tasks, now);
+                debug!("Next reminder check at {}.", next_check);
+                seen = modified_time(&self.tasks_path);
+            }
+            thread::sleep(self.poll_interval);
+        }
+
+        info!("Daemon stopped.");
+        Ok(())
+    }
+
+    /// Shows the reminders due at `now` and saves which were shown.
+    fn tick(&self, now: DateTime<Utc>) -> Result<Vec<Task>> {
+        let mut tasks = storage::load_tasks_from(&self.tasks_path)?;
+        let mut shown = 0;
+        for reminder in build_reminders(&tasks, now, &self.config) {
+            match deliver_reminder(&mut tasks, &reminder, &self.config.reminders, now) {
+                Ok(()) => {
+                    info!(
+                        "Reminded about task {} ({}).",
+                        reminder.task_id, reminder.when
+                    );
+                    shown += 1;
+                }
+                Err(e) => error!("Reminder for task {} failed: {}", reminder.task_id, e),
+            }
+        }
+        if shown > 0 {
+            storage::save_tasks_to(&tasks, &self.tasks_path)?;
+        }
+        Ok(tasks)
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,319 @@
+//! Generates a static, read-only dashboard snapshot of the task list.
+//!
+//! A snapshot consists of `index.html`, `dashboard.json`, and `burndown.svg`
+//! written into a single directory, so it can be regenerated from cron and
+//! served from any static host.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use log::info;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{Priority, Result, Task, clock};
+
+/// The number of days covered by the burndown chart.
+pub const BURNDOWN_DAYS: i64 = 30;
+
+/// The number of days ahead considered "upcoming".
+pub const UPCOMING_DAYS: i64 = 7;
+
+/// How often the generated HTML page reloads itself, in seconds.
+const HTML_REFRESH_SECS: u32 = 300;
+
+/// The dimensions of the burndown chart in pixels.
+const SVG_WIDTH: f64 = 600.0;
+const SVG_HEIGHT: f64 = 200.0;
+const SVG_PADDING: f64 = 30.0;
+
+/// Headline counts shown as summary cards.
+#[derive(Debug, Serialize)]
+pub struct DashboardSummary {
+    pub total: usize,
+    pub pending: usize,
+    pub completed: usize,
+    pub overdue: usize,
+    pub due_this_week: usize,
+}
+
+/// The subset of task fields shown in dashboard tables.
+#[derive(Debug, Serialize)]
+pub struct DashboardTask {
+    pub id: u32,
+    pub description: String,
+    pub due_date: Option<NaiveDate>,
+    pub project: Option<String>,
+    pub priority: Option<Priority>,
+}
+
+impl From<&Task> for DashboardTask {
+    fn from(task: &Task) -> Self {
+        DashboardTask {
+            id: task.id,
+            description: task.description.clone(),
+            due_date: task.due_date,
+            project: task.project.clone(),
+            priority: task.priority,
+        }
+    }
+}
+
+/// The number of open tasks at the end of a given day.
+#[derive(Debug, Serialize)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    pub open: usize,
+}
+
+/// A point-in-time snapshot of the task list, ready to be rendered.
+#[derive(Debug, Serialize)]
+pub struct Dashboard {
+    pub generated_at: DateTime<Utc>,
+    pub summary: DashboardSummary,
+    pub overdue: Vec<DashboardTask>,
+    pub upcoming: Vec<DashboardTask>,
+    pub burndown: Vec<BurndownPoint>,
+}
+
+impl Dashboard {
+    /// Builds a snapshot of `tasks` as seen at `now`.
+    pub fn build(tasks: &[Task], now: DateTime<Utc>) -> Self {
+        let today = clock::local_date(now);
+        let week_end = today + Duration::days(UPCOMING_DAYS);
+
+        let mut overdue: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.is_overdue(clock::local(now)))
+            .collect();
+        overdue.sort_by_key(|t| (t.deadline(), t.id));
+
+        let mut upcoming: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.is_pending() && t.due_date.is_some_and(|d| d >= today && d <= week_end))
+            .collect();
+        upcoming.sort_by_key(|t| (t.deadline(), t.id));
+
+        let completed = tasks.iter().filter(|t| t.is_done()).count();
+        let summary = DashboardSummary {
+            total: tasks.len(),
+            pending: tasks.iter().filter(|t| t.is_pending()).count(),
+            completed,
+            overdue: overdue.len(),
+            due_this_week: upcoming.len(),
+        };
+
+        let burndown = (0..BURNDOWN_DAYS)
+            .rev()
+            .map(|offset| {
+                let date = today - Duration::days(offset);
+                BurndownPoint {
+                    date,
+                    open: open_tasks_on(tasks, date),
+                }
+            })
+            .collect();
+
+        Dashboard {
+            generated_at: now,
+            summary,
+            overdue: overdue.into_iter().map(DashboardTask::from).collect(),
+            upcoming: upcoming.into_iter().map(DashboardTask::from).collect(),
+            burndown,
+        }
+    }
+
+    /// Serializes the snapshot as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Renders the snapshot as a self-contained HTML page.
+    ///
+    /// The page references `burndown.svg` from the same directory.
+    pub fn to_html(&self) -> String {
+        let s = &self.summary;
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str(&format!(
+            "<meta http-equiv=\"refresh\" content=\"{}\">\n",
+            HTML_REFRESH_SECS
+        ));
+        html.push_str("<title>Task Dashboard</title>\n<style>\n");
+        html.push_str(
+            "body{font-family:sans-serif;margin:2em;background:#fafafa;color:#222}\n\
+             .cards{display:flex;gap:1em;flex-wrap:wrap}\n\
+             .card{background:#fff;border-radius:6px;padding:1em 1.5em;box-shadow:0 1px 3px #0002}\n\
+             .card .value{font-size:2em;font-weight:bold}\n\
+             .overdue .value{color:#c0392b}\n\
+             table{border-collapse:collapse;background:#fff;margin-bottom:2em}\n\
+             th,td{padding:.4em .8em;border-bottom:1px solid #ddd;text-align:left}\n",
+        );
+        html.push_str("</style>\n</head>\n<body>\n<h1>Task Dashboard</h1>\n");
+        html.push_str(&format!(
+            "<p>Generated {}</p>\n",
+            self.generated_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+
+        html.push_str("<div class=\"cards\">\n");
+        for (class, label, value) in [
+            ("", "Total", s.total),
+            ("", "Pending", s.pending),
+            ("", "Completed", s.completed),
+            ("overdue", "Overdue", s.overdue),
+            ("", "Due this week", s.due_this_week),
+        ] {
+            html.push_str(&format!(
+                "<div class=\"card {}\"><div class=\"label\">{}</div><div class=\"value\">{}</div></div>\n",
+                class, label, value
+            ));
+        }
+        html.push_str("</div>\n");
+
+        html.push_str("<h2>Overdue</h2>\n");
+        html.push_str(&html_task_table(&self.overdue));
+        html.push_str(&format!("<h2>Next {} days</h2>\n", UPCOMING_DAYS));
+        html.push_str(&html_task_table(&self.upcoming));
+        html.push_str(&format!(
+            "<h2>Open tasks, last {} days</h2>\n",
+            BURNDOWN_DAYS
+        ));
+        html.push_str("<img src=\"burndown.svg\" alt=\"Burndown chart\">\n");
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Renders the burndown series as a simple SVG line chart.
+    pub fn burndown_svg(&self) -> String {
+        let max_open = self
+            .burndown
+            .iter()
+            .map(|p| p.open)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let plot_width = SVG_WIDTH - 2.0 * SVG_PADDING;
+        let plot_height = SVG_HEIGHT - 2.0 * SVG_PADDING;
+        let step = plot_width / (self.burndown.len().max(2) - 1) as f64;
+
+        let points: Vec<String> = self
+            .burndown
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let x = SVG_PADDING + i as f64 * step;
+                let y = SVG_HEIGHT - SVG_PADDING - (p.open as f64 / max_open as f64) * plot_height;
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = SVG_WIDTH,
+            h = SVG_HEIGHT
+        );
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n");
+        svg.push_str(&format!(
+            "<line x1=\"{p}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#999\"/>\n\
+             <line x1=\"{p}\" y1=\"{p}\" x2=\"{p}\" y2=\"{b}\" stroke=\"#999\"/>\n",
+            p = SVG_PADDING,
+            b = SVG_HEIGHT - SVG_PADDING,
+            r = SVG_WIDTH - SVG_PADDING
+        ));
+        svg.push_str(&format!(
+            "<polyline fill=\"none\" stroke=\"#2980b9\" stroke-width=\"2\" points=\"{}\"/>\n",
+            points.join(" ")
+        ));
+        svg.push_str(&format!(
+            "<text x=\"2\" y=\"{}\" font-size=\"10\">{}</text>\n",
+            SVG_PADDING + 4.0,
+            max_open
+        ));
+        if let (Some(first), Some(last)) = (self.burndown.first(), self.burndown.last()) {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\">{}</text>\n\
+                 <text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"end\">{}</text>\n",
+                SVG_PADDING,
+                SVG_HEIGHT - 10.0,
+                first.date,
+                SVG_WIDTH - SVG_PADDING,
+                SVG_HEIGHT - 10.0,
+                last.date
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Writes a dashboard snapshot into `out_dir`, creating the directory if needed.
+///
+/// # Returns
+///
+/// The paths of the files that were written.
+pub fn write_dashboard(tasks: &[Task], out_dir: &Path, now: DateTime<Utc>) -> Result<Vec<PathBuf>> {
+    let dashboard = Dashboard::build(tasks, now);
+    fs::create_dir_all(out_dir)?;
+
+    let files = [
+        ("index.html", dashboard.to_html()),
+        ("dashboard.json", dashboard.to_json()?),
+        ("burndown.svg", dashboard.burndown_svg()),
+    ];
+
+    let mut written = Vec::with_capacity(files.len());
+    for (name, contents) in files {
+        let path = out_dir.join(name);
+        fs::write(&path, contents)?;
+        written.push(path);
+    }
+    info!("Wrote dashboard snapshot to {}.", out_dir.display());
+    Ok(written)
+}
+
+/// Counts tasks that existed and were not yet completed at the end of `date`.
+///
+/// Cancelled tasks and completed tasks without a recorded completion time are
+/// left out, since there is no way to tell when they were closed.
+fn open_tasks_on(tasks: &[Task], date: NaiveDate) -> usize {
+    tasks
+        .iter()
+        .filter(|t| clock::local_date(t.created_at) <= date)
+        .filter(|t| match (t.is_pending(), t.completed_at) {
+            (true, _) => true,
+            (false, Some(done)) => clock::local_date(done) > date,
+            (false, None) => false,
+        })
+        .count()
+}
+
+/// Renders a list of tasks as an HTML table, or a placeholder when empty.
+fn html_task_table(tasks: &[DashboardTask]) -> String {
+    if tasks.is_empty() {
+        return "<p>Nothing here.</p>\n".to_string();
+    }
+
+    let mut html = String::from(
+        "<table>\n<tr><th>ID</th><th>Description</th><th>Due</th><th>Project</th><th>Priority</th></tr>\n",
+    );
+    for task in tasks {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            task.id,
+            escape_html(&task.description),
+            task.due_date.map_or("N/A".to_string(), |d| d.to_string()),
+            escape_html(task.project.as_deref().unwrap_or("")),
+            task.priority.map_or(String::new(), |p| p.to_string())
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Escapes characters that are significant in HTML text and attributes.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,298 @@
+//! Checks the data file for problems, and repairs the ones that are safe to.
+//!
+//! [`examine`] looks for tasks sharing an ID or UUID, contradictory dates,
+//! TOML sections that can't be read, and a file that can't be read or
+//! written. [`Checkup::repair`] then fixes what needs no judgement: clashing
+//! IDs and UUIDs get fresh ones, exact duplicates and leftover times are
+//! dropped, and unreadable sections are moved to `{stem}.unreadable.toml`
+//! next to the data file instead of being lost. Everything else is only
+//! reported.
+
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app::{BadSection, Result, Task, clock, encryption, storage};
+
+/// A problem found in the data file.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// What is wrong.
+    pub problem: String,
+    /// What `--fix` does about it; `None` if it needs a closer look.
+    pub repair: Option<String>,
+}
+
+impl Finding {
+    fn new(problem: String, repair: Option<String>) -> Self {
+        Finding { problem, repair }
+    }
+}
+
+/// The outcome of [`examine`].
+#[derive(Debug, Default)]
+pub struct Checkup {
+    pub findings: Vec<Finding>,
+    /// The tasks with the repairs applied.
+    tasks: Vec<Task>,
+    /// TOML sections to move out of the data file.
+    unreadable: Vec<BadSection>,
+    /// Whether `tasks` differ from what the file holds.
+    changed: bool,
+    /// Whether the owner lacks permission to write the file.
+    read_only: bool,
+}
+
+impl Checkup {
+    /// Returns how many findings `--fix` repairs.
+    pub fn repairable(&self) -> usize {
+        self.findings.iter().filter(|f| f.repair.is_some()).count()
+    }
+
+    /// Applies the repairs to the data file at `path`.
+    pub fn repair(self, path: &Path) -> Result<()> {
+        if self.read_only {
+            make_writable(path)?;
+        }
+        if !self.unreadable.is_empty() {
+            let aside = unreadable_file_path(path);
+            let mut contents = if aside.exists() {
+                encryption::read_file(&aside)?
+            } else {
+                String::new()
+            };
+            for section in &self.unreadable {
+                contents.push_str(section.text.trim_end());
+                contents.push_str("\n\n");
+            }
+            encryption::write_file(&aside, &contents, encryption::should_encrypt(path))?;
+            info!(
+                "Moved {} unreadable sections to {}.",
+                self.unreadable.len(),
+                aside.display()
+            );
+        }
+        if self.changed || !self.unreadable.is_empty() {
+            storage::save_tasks_to(&self.tasks, path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns where unreadable sections of the data file at `path` are moved.
+pub fn unreadable_file_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    path.with_file_name(format!("{}.unreadable.toml", stem))
+}
+
+/// Checks the data file at `path`.
+pub fn examine(path: &Path) -> Result<Checkup> {
+    let mut checkup = Checkup::default();
+    if let Err(e) = fs::File::open(path) {
+        checkup.findings.push(Finding::new(
+            format!("{} can't be read: {}", path.display(), e),
+            None,
+        ));
+        return Ok(checkup);
+    }
+    if let Err(e) = fs::OpenOptions::new().append(true).open(path) {
+        checkup.read_only = is_read_only(path);
+        let repair = checkup
+            .read_only
+            .then(|| "give the owner permission to write it".to_string());
+        checkup.findings.push(Finding::new(
+            format!("{} can't be written: {}", path.display(), e),
+            repair,
+        ));
+    }
+
+    let contents = encryption::read_bytes(path)?;
+    let mut tasks = match storage::parse_tasks(&contents) {
+        Ok(tasks) => tasks,
+        Err(e) => match storage::parse_sections(&contents) {
+            Some((tasks, unreadable)) if !tasks.is_empty() || !unreadable.is_empty() => {
+                for section in unreadable {
+                    checkup.findings.push(Finding::new(
+                        format!(
+                            "the section at line {} can't be read: {}",
+                            section.line, section.error
+                        ),
+                        Some(format!(
+                            "move it to {}",
+                            unreadable_file_path(path).display()
+                        )),
+                    ));
+                    checkup.unreadable.push(section);
+                }
+                tasks
+            }
+            _ => {
+                checkup.findings.push(Finding::new(
+                    format!("{} can't be read: {}", path.display(), e),
+                    None,
+                ));
+                return Ok(checkup);
+            }
+        },
+    };
+
+    checkup.changed |= check_uuids(&mut tasks, &mut checkup.findings);
+    checkup.changed |= check_ids(&mut tasks, &mut checkup.findings);
+    for task in &mut tasks {
+        checkup.changed |= check_dates(task, &mut checkup.findings);
+    }
+    checkup.tasks = tasks;
+    Ok(checkup)
+}
+
+/// Reports tasks sharing a UUID, dropping exact duplicates and giving the
+/// others a new UUID. Returns `true` if anything changed.
+fn check_uuids(tasks: &mut Vec<Task>, findings: &mut Vec<Finding>) -> bool {
+    let mut first: HashMap<Uuid, usize> = HashMap::new();
+    let mut copies = Vec::new();
+    let mut changed = false;
+    for i in 0..tasks.len() {
+        let Some(&original) = first.get(&tasks[i].uuid) else {
+            first.insert(tasks[i].uuid, i);
+            continue;
+        };
+        if serde_json::to_value(&tasks[i]).ok() == serde_json::to_value(&tasks[original]).ok() {
+            findings.push(Finding::new(
+                format!(
+                    "task {} \"{}\" is stored twice",
+                    tasks[i].id, tasks[i].description
+                ),
+                Some("remove the second copy".to_string()),
+            ));
+            copies.push(i);
+        } else {
+            findings.push(Finding::new(
+                format!(
+                    "tasks \"{}\" and \"{}\" share the UUID {}",
+                    tasks[original].description, tasks[i].description, tasks[i].uuid
+                ),
+                Some("give the second one a new UUID".to_string()),
+            ));
+            tasks[i].uuid = Uuid::new_v4();
+        }
+        changed = true;
+    }
+    for i in copies.into_iter().rev() {
+        tasks.remove(i);
+    }
+    changed
+}
+
+/// Reports tasks sharing an ID and gives all but the first a free one.
+/// Returns `true` if anything changed.
+fn check_ids(tasks: &mut [Task], findings: &mut Vec<Finding>) -> bool {
+    let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    let mut seen = HashSet::new();
+    let mut changed = false;
+    for task in tasks.iter_mut() {
+        if seen.insert(task.id) {
+            continue;
+        }
+        findings.push(Finding::new(
+            format!(
+                "task \"{}\" has the ID {} of another task",
+                task.description, task.id
+            ),
+            Some(format!("give it ID {}", next_id)),
+        ));
+        task.id = next_id;
+        next_id += 1;
+        changed = true;
+    }
+    changed
+}
+
+/// Reports dates of `task` that contradict each other, clearing leftovers
+/// that no longer mean anything. Returns `true` if anything changed.
+fn check_dates(task: &mut Task, findings: &mut Vec<Finding>) -> bool {
+    let name = format!("task {} \"{}\"", task.id, task.description);
+    let mut changed = false;
+    if !task.is_done() && task.completed_at.is_some() {
+        findings.push(Finding::new(
+            format!("{} is pending but has a completion time", name),
+            Some("clear the completion time".to_string()),
+        ));
+        task.completed_at = None;
+        changed = true;
+    }
+    if task.due_date.is_none() && task.due_time.is_some() {
+        findings.push(Finding::new(
+            format!("{} has a due time but no due date", name),
+            Some("clear the due time".to_string()),
+        ));
+        task.due_time = None;
+        changed = true;
+    }
+    if task.due_date.is_none() && task.recurrence.is_some() {
+        findings.push(Finding::new(
+            format!("{} recurs but has no due date", name),
+            None,
+        ));
+    }
+    if let (Some(start), Some(due)) = (task.start_date, task.due_date)
+        && start > due
+    {
+        findings.push(Finding::new(
+            format!("{} starts on {}, after it is due on {}", name, start, due),
+            None,
+        ));
+    }
+    if task.completed_at.is_some_and(|at| at < task.created_at) {
+        findings.push(Finding::new(
+            format!("{} was completed before it was created", name),
+            None,
+        ));
+    }
+    if task.created_at > clock::now() {
+        findings.push(Finding::new(
+            format!(
+                "{} was created in the future, at {}",
+                name,
+                task.created_at.format("%Y-%m-%d %H:%M")
+            ),
+            None,
+        ));
+    }
+    changed
+}
+
+/// Returns `true` if the owner of the file at `path` lacks write permission.
+#[cfg(unix)]
+fn is_read_only(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o200 == 0)
+}
+
+#[cfg(not(unix))]
+fn is_read_only(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.permissions().readonly())
+}
+
+/// Gives the owner of the file at `path` permission to write it.
+#[cfg(unix)]
+fn make_writable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o200);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_writable(path: &Path) -> Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
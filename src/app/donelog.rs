@@ -0,0 +1,90 @@
+//! The "done log" `done` prints: the tasks completed on one day, with the
+//! time each was completed, to paste into a work log or an invoice.
+//!
+//! Days are the configured time zone's, like everywhere else, so a task
+//! completed just after midnight counts for the day it was completed on
+//! there. Time tracked on a task with `start` and `stop` is shown next to
+//! it.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fmt::Write;
+
+use crate::app::{Task, clock, format_minutes, tracked_time};
+
+/// How `done` prints the log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DoneLogFormat {
+    /// Plain text for the terminal.
+    #[default]
+    Text,
+    /// A Markdown list under a heading, for a work log or an invoice.
+    Markdown,
+}
+
+/// The tasks completed on a day, in the order they were completed.
+#[derive(Debug)]
+pub struct DoneLog<'a> {
+    pub date: NaiveDate,
+    pub tasks: Vec<&'a Task>,
+}
+
+impl<'a> DoneLog<'a> {
+    /// Collects the tasks of `tasks` completed on `date`.
+    pub fn build(tasks: &'a [Task], date: NaiveDate) -> Self {
+        let mut completed: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter(|t| t.completed_at.map(clock::local_date) == Some(date))
+            .collect();
+        completed.sort_by_key(|t| (t.completed_at, t.id));
+        DoneLog {
+            date,
+            tasks: completed,
+        }
+    }
+
+    /// Returns the minutes tracked on the tasks in the log.
+    pub fn tracked_minutes(&self, now: DateTime<Utc>) -> u32 {
+        self.tasks
+            .iter()
+            .map(|t| tracked_time(t, now).num_minutes().max(0) as u32)
+            .sum()
+    }
+
+    /// Renders the log in `format`; `date` is the day as the config formats
+    /// it.
+    pub fn render(&self, format: DoneLogFormat, date: &str, now: DateTime<Utc>) -> String {
+        let mut log = String::new();
+        if format == DoneLogFormat::Markdown {
+            let _ = writeln!(log, "## Done on {}\n", date);
+        }
+        for task in &self.tasks {
+            let time = task
+                .completed_at
+                .map(|at| clock::local(at).format("%H:%M").to_string())
+                .unwrap_or_default();
+            let _ = match format {
+                DoneLogFormat::Text => write!(log, "{}  {} [{}]", time, task.description, task.id),
+                DoneLogFormat::Markdown => write!(log, "- {} {}", time, task.description),
+            };
+            if let Some(project) = &task.project {
+                let _ = write!(log, " ({})", project);
+            }
+            let minutes = tracked_time(task, now).num_minutes();
+            if minutes > 0 {
+                let _ = write!(log, " · {}", format_minutes(minutes as u32));
+            }
+            log.push('\n');
+        }
+        let total = self.tracked_minutes(now);
+        if total > 0 {
+            let _ = match format {
+                DoneLogFormat::Text => writeln!(log, "\nTracked: {}", format_minutes(total)),
+                DoneLogFormat::Markdown => {
+                    writeln!(log, "\n**Tracked:** {}", format_minutes(total))
+                }
+            };
+        }
+        log
+    }
+}
@@ -0,0 +1,412 @@
+//! Due date specifications as typed on the command line.
+//!
+//! A [`DueSpec`] is either an absolute date or one relative to today, and is
+//! resolved to a date when a command runs, so it honors `--now`.
+//!
+//! Numeric dates such as `03/04/2025` are read day-first or month-first. When
+//! only one reading is a valid date it is used; otherwise the configured
+//! [`DateOrder`] decides, and without one the date is rejected with both
+//! readings spelled out.
+
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app::RecurrenceUnit;
+
+/// How to read a numeric date whose day and month could be swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum DateOrder {
+    /// Day first, as in `31/12/2025`.
+    #[serde(rename = "DMY", alias = "dmy")]
+    #[value(name = "DMY", alias = "dmy")]
+    Dmy,
+    /// Month first, as in `12/31/2025`.
+    #[serde(rename = "MDY", alias = "mdy")]
+    #[value(name = "MDY", alias = "mdy")]
+    Mdy,
+}
+
+/// A date given as `YYYY-MM-DD`, `DD/MM/YYYY` or `MM/DD/YYYY`, `today`,
+/// `tomorrow`, a weekday name, optionally after `next`, or an offset such as
+/// `+3d`, `+2w`, `+1m`, or `+1y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueSpec {
+    On(NaiveDate),
+    /// A numeric date valid both day-first and month-first, e.g. `03/04/2025`.
+    /// Must be settled with [`DueSpec::with_order`] before it resolves.
+    Ambiguous {
+        first: u32,
+        second: u32,
+        year: i32,
+    },
+    Today,
+    Tomorrow,
+    /// The next such weekday after today.
+    Next(Weekday),
+    /// This many units after today.
+    In(u32, RecurrenceUnit),
+}
+
+impl DueSpec {
+    /// Settles an ambiguous numeric date using `order`; other specifications
+    /// are returned unchanged.
+    ///
+    /// # Returns
+    ///
+    /// An error naming both readings if the date is ambiguous and no order is set.
+    pub fn with_order(self, order: Option<DateOrder>) -> Result<Self, String> {
+        let DueSpec::Ambiguous {
+            first,
+            second,
+            year,
+        } = self
+        else {
+            return Ok(self);
+        };
+        let day_first = NaiveDate::from_ymd_opt(year, second, first);
+        let month_first = NaiveDate::from_ymd_opt(year, first, second);
+        match (order, day_first, month_first) {
+            (Some(DateOrder::Dmy), Some(date), _) | (Some(DateOrder::Mdy), _, Some(date)) => {
+                Ok(DueSpec::On(date))
+            }
+            (_, Some(dmy), Some(mdy)) => Err(format!(
+                "'{}' is ambiguous: it could be {} (day first) or {} (month first). Write it as YYYY-MM-DD or set date_order = \"DMY\" or \"MDY\" in the config file.",
+                self,
+                dmy.format("%Y-%m-%d"),
+                mdy.format("%Y-%m-%d")
+            )),
+            _ => Err(format!("'{}' is not a valid date.", self)),
+        }
+    }
+
+    /// Resolves the specification to a date, relative to `today`.
+    ///
+    /// Returns `None` if the result is out of chrono's date range, or for an
+    /// ambiguous date not yet settled with [`DueSpec::with_order`].
+    pub fn resolve(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            DueSpec::On(date) => Some(date),
+            DueSpec::Ambiguous { .. } => None,
+            DueSpec::Today => Some(today),
+            DueSpec::Tomorrow => today.checked_add_days(Days::new(1)),
+            DueSpec::Next(weekday) => {
+                let ahead = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                today.checked_add_days(Days::new(if ahead == 0 { 7 } else { ahead.into() }))
+            }
+            DueSpec::In(amount, unit) => add_span(today, amount, unit),
+        }
+    }
+}
+
+/// Returns `date` moved `amount` units ahead, or `None` past chrono's range.
+fn add_span(date: NaiveDate, amount: u32, unit: RecurrenceUnit) -> Option<NaiveDate> {
+    match unit {
+        RecurrenceUnit::Day => date.checked_add_days(Days::new(amount.into())),
+        RecurrenceUnit::Week => date.checked_add_days(Days::new(u64::from(amount) * 7)),
+        RecurrenceUnit::Month => date.checked_add_months(Months::new(amount)),
+        RecurrenceUnit::Year => date.checked_add_months(Months::new(amount.checked_mul(12)?)),
+    }
+}
+
+/// Parses a span such as `3d`, `2w`, `1m`, or `1y`.
+fn parse_span(input: &str) -> Option<(u32, RecurrenceUnit)> {
+    let digits_end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(digits_end);
+    let amount: u32 = number.parse().ok()?;
+    let unit = match unit {
+        "d" => RecurrenceUnit::Day,
+        "w" => RecurrenceUnit::Week,
+        "m" => RecurrenceUnit::Month,
+        "y" => RecurrenceUnit::Year,
+        _ => return None,
+    };
+    Some((amount, unit))
+}
+
+impl FromStr for DueSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Date must be YYYY-MM-DD, DD/MM/YYYY, 'today', 'tomorrow', a weekday, or an offset like '+3d'. Failed to parse: '{}'",
+                s
+            )
+        };
+        let input = s.trim().to_ascii_lowercase();
+        if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+            return Ok(DueSpec::On(date));
+        }
+        if let Some(spec) = parse_numeric_date(&input) {
+            return spec.ok_or_else(error);
+        }
+        match input.as_str() {
+            "today" => return Ok(DueSpec::Today),
+            "tomorrow" => return Ok(DueSpec::Tomorrow),
+            _ => {}
+        }
+        let weekday = input.strip_prefix("next ").unwrap_or(&input);
+        if let Ok(weekday) = weekday.trim_start().parse::<Weekday>() {
+            return Ok(DueSpec::Next(weekday));
+        }
+
+        let offset = input.strip_prefix('+').ok_or_else(error)?;
+        let (amount, unit) = parse_span(offset).ok_or_else(error)?;
+        Ok(DueSpec::In(amount, unit))
+    }
+}
+
+impl fmt::Display for DueSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DueSpec::On(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            DueSpec::Ambiguous {
+                first,
+                second,
+                year,
+            } => write!(f, "{:02}/{:02}/{}", first, second, year),
+            DueSpec::Today => write!(f, "today"),
+            DueSpec::Tomorrow => write!(f, "tomorrow"),
+            DueSpec::Next(weekday) => write!(f, "{}", weekday_name(*weekday)),
+            DueSpec::In(amount, unit) => {
+                let suffix = match unit {
+                    RecurrenceUnit::Day => "d",
+                    RecurrenceUnit::Week => "w",
+                    RecurrenceUnit::Month => "m",
+                    RecurrenceUnit::Year => "y",
+                };
+                write!(f, "+{}{}", amount, suffix)
+            }
+        }
+    }
+}
+
+/// A due date with an optional time of day, as given to `--due`.
+///
+/// Accepts any [`DueSpec`] followed by a time such as `17:00`, `5pm`, or
+/// `9:30am` (e.g. `2025-05-01 17:00`, `tomorrow 9am`), a time alone for
+/// today, or an offset in hours or minutes from now such as `+2h` or `+45min`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueAt {
+    On(DueSpec, Option<NaiveTime>),
+    /// This many minutes from now.
+    InMinutes(u32),
+}
+
+impl DueAt {
+    /// Settles an ambiguous date, see [`DueSpec::with_order`].
+    pub fn with_order(self, order: Option<DateOrder>) -> Result<Self, String> {
+        match self {
+            DueAt::On(spec, time) => spec.with_order(order).map(|spec| DueAt::On(spec, time)),
+            offset => Ok(offset),
+        }
+    }
+
+    /// Resolves to a date and time of day relative to `now`.
+    pub fn resolve(self, now: NaiveDateTime) -> Option<(NaiveDate, Option<NaiveTime>)> {
+        match self {
+            DueAt::On(spec, time) => Some((spec.resolve(now.date())?, time)),
+            DueAt::InMinutes(minutes) => {
+                let at = now.checked_add_signed(Duration::minutes(minutes.into()))?;
+                Some((at.date(), Some(at.time())))
+            }
+        }
+    }
+}
+
+impl fmt::Display for DueAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DueAt::On(spec, None) => write!(f, "{}", spec),
+            DueAt::On(spec, Some(time)) => write!(f, "{} {}", spec, time.format("%H:%M")),
+            DueAt::InMinutes(minutes) => write!(f, "+{}min", minutes),
+        }
+    }
+}
+
+impl FromStr for DueAt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_ascii_lowercase();
+        if let Some(offset) = input.strip_prefix('+') {
+            let minutes = offset
+                .strip_suffix("min")
+                .and_then(|n| n.parse::<u32>().ok())
+                .or_else(|| {
+                    offset
+                        .strip_suffix('h')
+                        .and_then(|n| n.parse::<u32>().ok()?.checked_mul(60))
+                });
+            if let Some(minutes) = minutes {
+                return Ok(DueAt::InMinutes(minutes));
+            }
+        }
+        if let Some(time) = parse_time_of_day(&input) {
+            return Ok(DueAt::On(DueSpec::Today, Some(time)));
+        }
+
+        let split = input
+            .split_once('t')
+            .filter(|(date, _)| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+            .or_else(|| input.rsplit_once(' '));
+        if let Some((date, time)) = split
+            && let Some(time) = parse_time_of_day(time)
+        {
+            let date = date.trim_end();
+            let date = date.strip_suffix(" at").unwrap_or(date);
+            if let Ok(spec) = date.parse() {
+                return Ok(DueAt::On(spec, Some(time)));
+            }
+        }
+        s.parse().map(|spec| DueAt::On(spec, None)).map_err(|_| {
+            format!(
+                "Due must be a date (YYYY-MM-DD, DD/MM/YYYY, 'today', 'tomorrow', a weekday, or '+3d'), optionally followed by a time like '17:00' or '5pm', or an offset like '+2h' or '+30min'. Failed to parse: '{}'",
+                s
+            )
+        })
+    }
+}
+
+/// Parses a time of day such as `17:00`, `17:00:30`, `5pm`, or `9:30am`.
+pub fn parse_time_of_day(input: &str) -> Option<NaiveTime> {
+    for format in ["%H:%M", "%H:%M:%S"] {
+        if let Ok(time) = NaiveTime::parse_from_str(input, format) {
+            return Some(time);
+        }
+    }
+    let (clock, evening) = match input.strip_suffix("pm") {
+        Some(clock) => (clock, true),
+        None => (input.strip_suffix("am")?, false),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hour % 12 + if evening { 12 } else { 0 }, minute, 0)
+}
+
+/// How far `snooze` pushes a task: by a span such as `3d`, or to a date in
+/// any of the [`DueSpec`] formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozeSpec {
+    /// This many units after the due date, or after today if that is later.
+    For(u32, RecurrenceUnit),
+    Until(DueSpec),
+}
+
+impl SnoozeSpec {
+    /// Settles an ambiguous date, see [`DueSpec::with_order`].
+    pub fn with_order(self, order: Option<DateOrder>) -> Result<Self, String> {
+        match self {
+            SnoozeSpec::Until(spec) => spec.with_order(order).map(SnoozeSpec::Until),
+            span => Ok(span),
+        }
+    }
+
+    /// Returns the new due date of a task currently due on `due`.
+    ///
+    /// Spans count from today for overdue tasks and tasks without a due date,
+    /// so snoozing always moves a task into the future.
+    pub fn resolve(self, due: Option<NaiveDate>, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            SnoozeSpec::For(amount, unit) => {
+                add_span(due.map_or(today, |d| d.max(today)), amount, unit)
+            }
+            SnoozeSpec::Until(spec) => spec.resolve(today),
+        }
+    }
+}
+
+impl FromStr for SnoozeSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_ascii_lowercase();
+        if let Some((amount, unit)) = parse_span(input.strip_prefix('+').unwrap_or(&input)) {
+            return Ok(SnoozeSpec::For(amount, unit));
+        }
+        s.parse().map(SnoozeSpec::Until).map_err(|_| {
+            format!(
+                "Expected a span like '3d' or '2w', or a date such as YYYY-MM-DD, 'tomorrow', or 'next monday'. Failed to parse: '{}'",
+                s
+            )
+        })
+    }
+}
+
+/// Parses `A/B/YYYY`, also separated by `.` or `-`, and `YYYY/MM/DD`.
+///
+/// Returns `None` if the input doesn't have that shape at all, and
+/// `Some(None)` if it does but no reading is a valid date.
+fn parse_numeric_date(input: &str) -> Option<Option<DueSpec>> {
+    let parts: Vec<&str> = input.split(['/', '.', '-']).collect();
+    let [a, b, c] = parts[..] else {
+        return None;
+    };
+    if [a, b, c]
+        .iter()
+        .any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let (a, b, c): (u32, u32, u32) = (a.parse().ok()?, b.parse().ok()?, c.parse().ok()?);
+
+    if parts[0].len() == 4 {
+        let year = i32::try_from(a).ok()?;
+        return Some(NaiveDate::from_ymd_opt(year, b, c).map(DueSpec::On));
+    }
+    if parts[2].len() != 4 {
+        return Some(None);
+    }
+    let year = i32::try_from(c).ok()?;
+    let spec = match (
+        NaiveDate::from_ymd_opt(year, b, a),
+        NaiveDate::from_ymd_opt(year, a, b),
+    ) {
+        (Some(dmy), Some(mdy)) if dmy == mdy => Some(DueSpec::On(dmy)),
+        (Some(_), Some(_)) => Some(DueSpec::Ambiguous {
+            first: a,
+            second: b,
+            year,
+        }),
+        (Some(date), None) | (None, Some(date)) => Some(DueSpec::On(date)),
+        (None, None) => None,
+    };
+    Some(spec)
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+impl Serialize for DueSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DueSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
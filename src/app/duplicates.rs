@@ -0,0 +1,42 @@
+//! Spotting tasks that are added twice, for `add`.
+//!
+//! Descriptions are compared after normalizing them: case, punctuation, and
+//! extra spaces don't count, so "Email Bob!" is "email bob". Beyond that, a
+//! description counts as a duplicate if a few characters' worth of typos
+//! turn it into the other, one for every five characters.
+
+use crate::app::{Task, edit_distance};
+
+/// How many characters of a normalized description one typo may take.
+const CHARS_PER_TYPO: usize = 5;
+
+/// Returns `description` lowercased, with punctuation dropped and words
+/// separated by one space.
+pub fn normalize_description(description: &str) -> String {
+    description
+        .split(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '\''))
+        .map(|word| word.replace('\'', "").to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns `true` if `a` and `b` describe the same task but for case,
+/// punctuation, and a typo or two.
+pub fn is_near_duplicate(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_description(a), normalize_description(b));
+    if a == b {
+        return !a.is_empty();
+    }
+    let allowed = a.chars().count().min(b.chars().count()) / CHARS_PER_TYPO;
+    allowed > 0 && edit_distance(&a, &b) <= allowed
+}
+
+/// Returns the pending tasks of `tasks` a new task described as
+/// `description` would duplicate.
+pub fn find_duplicates<'a>(description: &str, tasks: &'a [Task]) -> Vec<&'a Task> {
+    tasks
+        .iter()
+        .filter(|t| t.is_pending() && is_near_duplicate(description, &t.description))
+        .collect()
+}
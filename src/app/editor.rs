@@ -0,0 +1,239 @@
+//! Editing a task as text in `$EDITOR`, for `add --editor` and
+//! `edit --editor`.
+//!
+//! The task's fields are written to a temporary TOML file, which is opened
+//! in `$VISUAL`, `$EDITOR`, or `vi`. Once the editor exits, the file is read
+//! back as a [`TaskDraft`]; an emptied file cancels.
+
+use clap::ValueEnum;
+use log::debug;
+use serde::Deserialize;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::process::Command;
+use uuid::Uuid;
+
+use crate::app::{
+    AddArgs, AppError, BIN_NAME, DueAt, DueSpec, EditArgs, Priority, Result, Task, parse_url,
+};
+use crate::tr;
+
+/// The editor used when neither `VISUAL` nor `EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The fields of a task as they are edited.
+///
+/// Dates are kept as text in any format `--due` accepts, and an empty field
+/// means the task has none.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaskDraft {
+    pub description: String,
+    pub due: String,
+    pub start: String,
+    pub project: String,
+    pub priority: String,
+    pub tags: Vec<String>,
+    pub url: String,
+    pub assignee: String,
+    /// A note to add to the task.
+    pub note: String,
+}
+
+impl TaskDraft {
+    /// Takes the editable fields of `task`.
+    pub fn from_task(task: &Task) -> Self {
+        let due = task.due_date.map(|date| match task.due_time {
+            Some(time) => format!("{} {}", date, time.format("%H:%M")),
+            None => date.to_string(),
+        });
+        TaskDraft {
+            description: task.description.clone(),
+            due: due.unwrap_or_default(),
+            start: task.start_date.map(|d| d.to_string()).unwrap_or_default(),
+            project: task.project.clone().unwrap_or_default(),
+            priority: task.priority.map(|p| p.to_string()).unwrap_or_default(),
+            tags: task.tags.clone(),
+            url: task.url.clone().unwrap_or_default(),
+            assignee: task.assignee.clone().unwrap_or_default(),
+            note: String::new(),
+        }
+    }
+
+    /// Takes what `args` already fills in for a new task.
+    pub fn from_add_args(args: &AddArgs) -> Self {
+        TaskDraft {
+            description: args.description.clone(),
+            due: args.due.map(|d| d.to_string()).unwrap_or_default(),
+            start: args.start.map(|d| d.to_string()).unwrap_or_default(),
+            project: args.project.clone().unwrap_or_default(),
+            priority: args.priority.map(|p| p.to_string()).unwrap_or_default(),
+            tags: args.tags.clone(),
+            url: args.url.clone().unwrap_or_default(),
+            assignee: args.assignee.clone().unwrap_or_default(),
+            note: args.note.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Writes the draft as the TOML file to edit, under a comment saying what
+    /// is edited.
+    pub fn render(&self, heading: &str) -> String {
+        let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+        let tags: Vec<String> = self.tags.iter().map(|t| quote(t)).collect();
+        let mut text = String::new();
+        let _ = writeln!(text, "# {}", heading);
+        text.push_str(
+            "# Save and quit to apply; empty the file to cancel. Dates take any\n\
+             # format `--due` does, and empty fields are cleared.\n\n",
+        );
+        let _ = writeln!(text, "description = {}", quote(&self.description));
+        let _ = writeln!(text, "due = {}", quote(&self.due));
+        let _ = writeln!(text, "start = {}", quote(&self.start));
+        let _ = writeln!(text, "project = {}", quote(&self.project));
+        let _ = writeln!(
+            text,
+            "priority = {}  # low, medium, or high",
+            quote(&self.priority)
+        );
+        let _ = writeln!(text, "tags = [{}]", tags.join(", "));
+        let _ = writeln!(text, "url = {}", quote(&self.url));
+        let _ = writeln!(text, "assignee = {}", quote(&self.assignee));
+        text.push_str("\n# Added to the notes; use '''...''' for several lines.\n");
+        let _ = writeln!(text, "note = {}", quote(&self.note));
+        text
+    }
+
+    /// Reads an edited draft back, returning `None` if the file was emptied.
+    pub fn parse(text: &str) -> Result<Option<Self>> {
+        let has_content = text
+            .lines()
+            .any(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+        if !has_content {
+            return Ok(None);
+        }
+        let draft: TaskDraft =
+            toml::from_str(text).map_err(|e| AppError::InvalidArgument(e.to_string()))?;
+        if draft.description.trim().is_empty() {
+            return Err(AppError::InvalidArgument(tr!("error-empty-description")));
+        }
+        Ok(Some(draft))
+    }
+
+    /// Fills `args` in from the draft, for adding the task.
+    pub fn apply_to_add(&self, args: &mut AddArgs) -> Result<()> {
+        args.description = self.description.trim().to_string();
+        args.due = parse_field(&self.due)?;
+        args.start = parse_field(&self.start)?;
+        args.project = non_empty(&self.project);
+        args.priority = self.priority()?;
+        args.tags = self.tags.clone();
+        args.url = self.url()?;
+        args.assignee = non_empty(&self.assignee);
+        args.note = non_empty(&self.note);
+        Ok(())
+    }
+
+    /// Returns the changes turning `task` into the draft, and the note to
+    /// add, if any.
+    pub fn edit_args(&self, task: &Task) -> Result<(EditArgs, Option<String>)> {
+        let due: Option<DueAt> = parse_field(&self.due)?;
+        let start: Option<DueSpec> = parse_field(&self.start)?;
+        let project = non_empty(&self.project);
+        let priority = self.priority()?;
+        let url = self.url()?;
+        let assignee = non_empty(&self.assignee);
+        let changes = EditArgs {
+            description: Some(self.description.trim().to_string()),
+            no_due: due.is_none(),
+            due,
+            no_start: start.is_none(),
+            start,
+            no_project: project.is_none(),
+            project,
+            no_priority: priority.is_none(),
+            priority,
+            tags: self.tags.clone(),
+            untag: task
+                .tags
+                .iter()
+                .filter(|t| !self.tags.contains(t))
+                .cloned()
+                .collect(),
+            no_url: url.is_none(),
+            url,
+            unassign: assignee.is_none(),
+            assignee,
+            ..EditArgs::default()
+        };
+        Ok((changes, non_empty(&self.note)))
+    }
+
+    fn url(&self) -> Result<Option<String>> {
+        non_empty(&self.url)
+            .map(|url| parse_url(&url).map_err(AppError::InvalidArgument))
+            .transpose()
+    }
+
+    fn priority(&self) -> Result<Option<Priority>> {
+        let Some(priority) = non_empty(&self.priority) else {
+            return Ok(None);
+        };
+        Priority::from_str(&priority, true).map(Some).map_err(|_| {
+            AppError::InvalidArgument(tr!("error-unknown-priority", priority = priority))
+        })
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+fn parse_field<T: std::str::FromStr<Err = String>>(s: &str) -> Result<Option<T>> {
+    non_empty(s)
+        .map(|s| s.parse().map_err(AppError::InvalidArgument))
+        .transpose()
+}
+
+/// Returns the editor to run: `$VISUAL`, else `$EDITOR`, else `vi`.
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Opens `text` in the editor and returns it as saved. The editor command
+/// may carry arguments, e.g. `code --wait`.
+///
+/// # Returns
+///
+/// An `AppError::InvalidArgument` if the editor fails.
+pub fn edit_in_editor(text: &str) -> Result<String> {
+    let path = env::temp_dir().join(format!("{}-{}.toml", BIN_NAME, Uuid::new_v4()));
+    fs::write(&path, text)?;
+    let editor = editor_command();
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    debug!("Opening {} in {}.", path.display(), editor);
+    let status = Command::new(program).args(words).arg(&path).status();
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    let status = status.map_err(|e| {
+        AppError::InvalidArgument(tr!(
+            "error-could-not-run",
+            program = editor.as_str(),
+            error = e.to_string()
+        ))
+    })?;
+    if !status.success() {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-editor-failed",
+            editor = editor,
+            status = status.to_string()
+        )));
+    }
+    Ok(edited?)
+}
@@ -0,0 +1,146 @@
+//! Optional passphrase encryption of the data files.
+//!
+//! An encrypted file starts with [`MAGIC`], followed by a random salt and
+//! nonce and the ChaCha20-Poly1305 ciphertext of the contents. The key is
+//! derived from the passphrase in `TASK_PASSPHRASE` with Argon2id.
+//!
+//! Encryption is a property of the file: [`read_file`] decrypts whatever it
+//! finds, and an existing file keeps its form when rewritten. New files are
+//! encrypted when a passphrase is set. The `encrypt` and `decrypt` commands
+//! switch the data and trash files from one form to the other.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, Generate, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use log::debug;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::app::{AppError, Result};
+
+/// The environment variable holding the passphrase.
+pub const PASSPHRASE_ENV_VAR: &str = "TASK_PASSPHRASE";
+
+/// The bytes an encrypted file starts with.
+pub const MAGIC: &[u8] = b"tm-encrypted-v1\n";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Returns `true` if `data` is in the encrypted format.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Returns `true` if the file at `path` exists and is encrypted.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut header = [0; MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|()| is_encrypted(&header))
+}
+
+/// Decides whether a write to `path` should be encrypted: an existing file
+/// keeps its form, and a new one is encrypted if a passphrase is set.
+pub fn should_encrypt(path: &Path) -> bool {
+    if path.exists() {
+        is_encrypted_file(path)
+    } else {
+        env::var_os(PASSPHRASE_ENV_VAR).is_some_and(|p| !p.is_empty())
+    }
+}
+
+/// Reads the passphrase from `TASK_PASSPHRASE`.
+pub fn passphrase_from_env() -> Result<String> {
+    match env::var(PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase),
+        _ => Err(AppError::Encryption(format!(
+            "set {} to the passphrase of the data file",
+            PASSPHRASE_ENV_VAR
+        ))),
+    }
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase`.
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let salt = <[u8; SALT_LEN]>::generate();
+    let nonce = Nonce::generate();
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| AppError::Encryption("encryption failed".to_string()))?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypts data produced by [`encrypt`].
+///
+/// Fails if the passphrase is wrong or the data was tampered with.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or_else(|| AppError::Encryption("not an encrypted task file".to_string()))?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_from(nonce)
+        .map_err(|_| AppError::Encryption("not an encrypted task file".to_string()))?;
+    cipher(passphrase, salt)?
+        .decrypt(&nonce, ciphertext)
+        .map_err(|_| AppError::Encryption("wrong passphrase or corrupted file".to_string()))
+}
+
+/// Reads the text file at `path`, decrypting it if it is encrypted.
+pub fn read_file(path: &Path) -> Result<String> {
+    into_text(read_bytes(path)?)
+}
+
+/// Reads the file at `path` as bytes, decrypting it if it is encrypted.
+pub fn read_bytes(path: &Path) -> Result<Vec<u8>> {
+    debug!("Reading {}.", path.display());
+    decode_bytes(fs::read(path)?)
+}
+
+/// Turns file contents into text, decrypting them if they are encrypted.
+pub fn decode(data: Vec<u8>) -> Result<String> {
+    into_text(decode_bytes(data)?)
+}
+
+fn into_text(data: Vec<u8>) -> Result<String> {
+    String::from_utf8(data).map_err(|e| AppError::Encryption(e.to_string()))
+}
+
+/// Decrypts file contents if they are encrypted, and returns them as they
+/// are otherwise.
+pub fn decode_bytes(data: Vec<u8>) -> Result<Vec<u8>> {
+    if is_encrypted(&data) {
+        decrypt(&data, &passphrase_from_env()?)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Writes `contents` to `path`, encrypted if `encrypted` is set.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>, encrypted: bool) -> Result<()> {
+    let contents = contents.as_ref();
+    if encrypted {
+        fs::write(path, encrypt(contents, &passphrase_from_env()?)?)?;
+    } else {
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Encryption(e.to_string()))?;
+    Ok(ChaCha20Poly1305::new(&key))
+}
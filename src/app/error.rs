@@ -1,34 +1,308 @@
 //! Custom error types and a `Result` alias for the task manager application.
+//!
+//! Every error maps to the exit status the binary ends with, so scripts can
+//! tell outcomes apart without reading messages:
+//!
+//! | Status | Meaning |
+//! |--------|---------|
+//! | 0 | success |
+//! | 1 | the task wasn't found |
+//! | 2 | invalid arguments |
+//! | 3 | the data file or a file next to it couldn't be read or written |
+//! | 4 | the config file is invalid |
+//! | 5 | the data file was changed elsewhere in a conflicting way |
+//! | 6 | a hook, sync, notification, or other service failed |
+//! | 7 | anything else |
 
+use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::app::{Task, truncate};
+use crate::tr;
+
+/// The most tasks an error suggests instead of the one asked for.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// How wide descriptions in suggestions may get.
+const SUGGESTION_WIDTH: usize = 40;
+
+/// A task offered in an error in place of one that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub id: u32,
+    pub description: String,
+}
+
+impl Suggestion {
+    /// Offers `task`.
+    pub fn of(task: &Task) -> Self {
+        Suggestion {
+            id: task.id,
+            description: task.description.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: '{}'",
+            self.id,
+            truncate(&self.description, SUGGESTION_WIDTH)
+        )
+    }
+}
+
+/// Renders `suggestions` as a sentence following an error, such as
+/// " Did you mean 12: 'buy milk'?", or nothing if there are none.
+pub fn did_you_mean(suggestions: &[Suggestion]) -> String {
+    let shown: Vec<String> = suggestions.iter().map(Suggestion::to_string).collect();
+    let tasks = match shown.as_slice() {
+        [] => return String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => tr!(
+            "did-you-mean-list",
+            rest = rest.join(", "),
+            last = last.as_str()
+        ),
+    };
+    format!(" {}", tr!("did-you-mean", tasks = tasks))
+}
+
 /// Custom error types for the task manager.
 ///
 /// This enum encapsulates all possible errors that can occur within the application,
-/// providing specific variants for different failure modes.
+/// providing specific variants for different failure modes. Messages are in
+/// the active language.
 #[derive(Error, Debug)]
 pub enum AppError {
-    #[error("File system error: {0}")]
     Io(#[from] io::Error),
 
-    #[error("Failed to parse TOML data: {0}")]
     TomlDeserialize(#[from] toml::de::Error),
 
-    #[error("Failed to serialize data to TOML: {0}")]
+    /// A data file that isn't valid TOML; the error shows where.
+    DataFile {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
     TomlSerialize(#[from] toml::ser::Error),
 
-    #[error("Task with ID '{0}' not found.")]
-    TaskNotFound(u32),
+    MsgpackDecode(#[from] rmp_serde::decode::Error),
+
+    MsgpackEncode(#[from] rmp_serde::encode::Error),
+
+    Json(#[from] serde_json::Error),
+
+    Chart(String),
+
+    Notify(String),
+
+    Clipboard(String),
+
+    Encryption(String),
+
+    Audit(String),
+
+    Doctor(String),
+
+    EventLog(String),
+
+    Conflict(String),
+
+    Migration(String),
+
+    Hook(String),
+
+    Sync(String),
+
+    Search(String),
+
+    Todoist(String),
+
+    GitHub(String),
+
+    Jira(String),
+
+    Remote(String),
+
+    Config(String),
+
+    /// A change going past a limit under `[limits]`, with `--strict`.
+    Limit(String),
+
+    TaskNotFound {
+        id: u32,
+        /// Tasks with IDs close to the one asked for.
+        suggestions: Vec<Suggestion>,
+    },
 
-    #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
-    #[error("An unexpected error occurred: {0}")]
     Unexpected(String),
 }
 
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            AppError::Io(e) => tr!("error-io", error = e.to_string()),
+            AppError::TomlDeserialize(e) => tr!("error-toml-parse", error = e.to_string()),
+            AppError::DataFile { path, source } => tr!(
+                "error-data-file",
+                path = path.display().to_string(),
+                error = source.to_string()
+            ),
+            AppError::TomlSerialize(e) => tr!("error-toml-serialize", error = e.to_string()),
+            AppError::MsgpackDecode(e) => tr!("error-msgpack-decode", error = e.to_string()),
+            AppError::MsgpackEncode(e) => tr!("error-msgpack-encode", error = e.to_string()),
+            AppError::Json(e) => tr!("error-json", error = e.to_string()),
+            AppError::Chart(e) => tr!("error-chart", error = e.as_str()),
+            AppError::Notify(e) => tr!("error-notify", error = e.as_str()),
+            AppError::Clipboard(e) => tr!("error-clipboard", error = e.as_str()),
+            AppError::Encryption(e) => tr!("error-encryption", error = e.as_str()),
+            AppError::Audit(e) => tr!("error-audit", error = e.as_str()),
+            AppError::Doctor(e) => tr!("error-doctor", error = e.as_str()),
+            AppError::EventLog(e) => tr!("error-event-log", error = e.as_str()),
+            AppError::Conflict(e) => tr!("error-conflict", error = e.as_str()),
+            AppError::Migration(e) => tr!("error-migration", error = e.as_str()),
+            AppError::Hook(e) => tr!("error-hook", error = e.as_str()),
+            AppError::Sync(e) => tr!("error-sync", error = e.as_str()),
+            AppError::Search(e) => tr!("error-search", error = e.as_str()),
+            AppError::Todoist(e) => tr!("error-todoist", error = e.as_str()),
+            AppError::GitHub(e) => tr!("error-github", error = e.as_str()),
+            AppError::Jira(e) => tr!("error-jira", error = e.as_str()),
+            AppError::Remote(e) => tr!("error-remote", error = e.as_str()),
+            AppError::Config(e) => tr!("error-config", error = e.as_str()),
+            AppError::Limit(e) => tr!("error-limit", error = e.as_str()),
+            AppError::TaskNotFound { id, suggestions } => format!(
+                "{}{}",
+                tr!("error-task-not-found", id = *id),
+                did_you_mean(suggestions)
+            ),
+            AppError::InvalidArgument(e) => tr!("error-invalid-argument", error = e.as_str()),
+            AppError::Unexpected(e) => tr!("error-unexpected", error = e.as_str()),
+        };
+        f.write_str(&message)
+    }
+}
+
+/// The exit status of a successful command.
+pub const EXIT_SUCCESS: u8 = 0;
+/// The exit status when the task given doesn't exist.
+pub const EXIT_NOT_FOUND: u8 = 1;
+/// The exit status for invalid arguments; `clap` uses it for usage errors too.
+pub const EXIT_USAGE: u8 = 2;
+/// The exit status when the data file or a file next to it fails.
+pub const EXIT_STORAGE: u8 = 3;
+/// The exit status for an invalid config file.
+pub const EXIT_CONFIG: u8 = 4;
+/// The exit status when changes made elsewhere conflict.
+pub const EXIT_CONFLICT: u8 = 5;
+/// The exit status when a hook or another program or service fails.
+pub const EXIT_EXTERNAL: u8 = 6;
+/// The exit status for every other failure.
+pub const EXIT_OTHER: u8 = 7;
+
+impl AppError {
+    /// Returns the error for a task ID none of `tasks` has, suggesting the
+    /// ones whose IDs differ from it by a typo: a digit added, dropped,
+    /// changed, or two swapped.
+    pub fn task_not_found<'a>(id: u32, tasks: impl IntoIterator<Item = &'a Task>) -> Self {
+        let wanted = id.to_string();
+        let mut close: Vec<(usize, u32, bool, &Task)> = tasks
+            .into_iter()
+            .filter_map(|task| {
+                let distance = edit_distance(&wanted, &task.id.to_string());
+                (distance <= 1).then(|| (distance, task.id.abs_diff(id), !task.is_pending(), task))
+            })
+            .collect();
+        close.sort_by_key(|&(distance, gap, done, task)| (distance, done, gap, task.id));
+        AppError::TaskNotFound {
+            id,
+            suggestions: close
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|(.., task)| Suggestion::of(task))
+                .collect(),
+        }
+    }
+
+    /// Names `path` in a TOML parse error, which shows where in the file
+    /// it is but not which file.
+    pub fn in_file(self, path: &Path) -> Self {
+        match self {
+            AppError::TomlDeserialize(source) => AppError::DataFile {
+                path: path.to_path_buf(),
+                source,
+            },
+            e => e,
+        }
+    }
+
+    /// Returns the status the binary exits with for the error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AppError::TaskNotFound { .. } => EXIT_NOT_FOUND,
+            AppError::InvalidArgument(_) => EXIT_USAGE,
+            AppError::Io(_)
+            | AppError::TomlDeserialize(_)
+            | AppError::DataFile { .. }
+            | AppError::TomlSerialize(_)
+            | AppError::MsgpackDecode(_)
+            | AppError::MsgpackEncode(_)
+            | AppError::Json(_)
+            | AppError::Encryption(_)
+            | AppError::Audit(_)
+            | AppError::Doctor(_)
+            | AppError::EventLog(_)
+            | AppError::Migration(_)
+            | AppError::Search(_) => EXIT_STORAGE,
+            AppError::Config(_) => EXIT_CONFIG,
+            AppError::Conflict(_) => EXIT_CONFLICT,
+            AppError::Notify(_)
+            | AppError::Clipboard(_)
+            | AppError::Hook(_)
+            | AppError::Sync(_)
+            | AppError::Todoist(_)
+            | AppError::GitHub(_)
+            | AppError::Jira(_)
+            | AppError::Remote(_) => EXIT_EXTERNAL,
+            AppError::Chart(_) | AppError::Limit(_) | AppError::Unexpected(_) => EXIT_OTHER,
+        }
+    }
+}
+
 /// A convenient type alias for `Result` that uses `AppError` as the error type.
 ///
 /// This reduces boilerplate by allowing `Result<T>` instead of `Result<T, AppError>`.
 pub type Result<T> = std::result::Result<T, AppError>;
+
+/// Counts the characters to add, drop, change, or swap with their neighbour
+/// to turn `a` into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
@@ -0,0 +1,87 @@
+//! Escalating notifications for tasks that stay pending past their due date.
+//!
+//! Levels are configured in `config.toml` and fire in order of `after_hours`,
+//! each through its own channel:
+//!
+//! ```toml
+//! [[escalation]]
+//! after_hours = 0
+//! channel = "desktop"
+//!
+//! [[escalation]]
+//! after_hours = 24
+//! channel = "email"
+//! to = "me@example.com"
+//!
+//! [[escalation]]
+//! after_hours = 72
+//! channel = "webhook"
+//! url = "https://hooks.example.com/tasks"
+//! ```
+//!
+//! A task records how many levels have fired, so each level fires exactly once.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Channel, Task, clock};
+
+/// One step of the escalation chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationLevel {
+    /// Hours past the due time after which this level fires.
+    pub after_hours: u32,
+    #[serde(flatten)]
+    pub channel: Channel,
+}
+
+/// An escalation that is due to be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingEscalation {
+    pub task_id: u32,
+    /// The 0-based index of the level in the sorted chain.
+    pub level: usize,
+}
+
+/// Sorts the chain so levels fire in order of their delay.
+pub fn sorted_levels(levels: &[EscalationLevel]) -> Vec<EscalationLevel> {
+    let mut sorted = levels.to_vec();
+    sorted.sort_by_key(|level| level.after_hours);
+    sorted
+}
+
+/// Finds the escalations that should fire at `now`.
+///
+/// `levels` must already be sorted. Levels a task has already reached are
+/// skipped; if several levels came due since the last check, all of them
+/// are returned in order.
+pub fn pending_escalations(
+    tasks: &[Task],
+    levels: &[EscalationLevel],
+    now: DateTime<Utc>,
+) -> Vec<PendingEscalation> {
+    let mut pending = Vec::new();
+    for task in tasks.iter().filter(|t| t.is_pending()) {
+        let Some(due) = task.deadline().map(clock::from_local) else {
+            continue;
+        };
+        if now < due {
+            continue;
+        }
+        let hours_overdue = (now - due).num_hours();
+        for (level, step) in levels
+            .iter()
+            .enumerate()
+            .skip(task.escalations_sent as usize)
+        {
+            if hours_overdue < i64::from(step.after_hours) {
+                break;
+            }
+            pending.push(PendingEscalation {
+                task_id: task.id,
+                level,
+            });
+        }
+    }
+    pending
+}
@@ -0,0 +1,97 @@
+//! Effort estimates.
+//!
+//! Estimates are written like `90m`, `2h`, `1h30m`, or `1.5h` and stored as
+//! whole minutes.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// An effort estimate in whole minutes.
+///
+/// Serialized as the plain number of minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Estimate(u32);
+
+impl Estimate {
+    /// Creates an estimate of `minutes`.
+    pub fn from_minutes(minutes: u32) -> Self {
+        Estimate(minutes)
+    }
+
+    /// Returns the estimate in minutes.
+    pub fn minutes(self) -> u32 {
+        self.0
+    }
+}
+
+impl FromStr for Estimate {
+    type Err = String;
+
+    /// Parses an estimate such as `45m`, `2h`, `1h30m`, `1.5h`, or `1d` (8 hours).
+    /// A bare number is read as hours.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Estimate must look like '45m', '2h', '1h30m', or '1.5h'. Failed to parse: '{}'",
+                s
+            )
+        };
+        let input = s.trim().to_ascii_lowercase();
+        if input.is_empty() {
+            return Err(error());
+        }
+        if let Ok(hours) = input.parse::<f64>() {
+            return minutes_from_f64(hours * 60.0)
+                .map(Estimate)
+                .ok_or_else(error);
+        }
+
+        let mut total: f64 = 0.0;
+        let mut number = String::new();
+        for c in input.chars() {
+            match c {
+                '0'..='9' | '.' => number.push(c),
+                'd' | 'h' | 'm' => {
+                    let value: f64 = number.parse().map_err(|_| error())?;
+                    number.clear();
+                    total += match c {
+                        'd' => value * 8.0 * 60.0,
+                        'h' => value * 60.0,
+                        _ => value,
+                    };
+                }
+                ' ' => {}
+                _ => return Err(error()),
+            }
+        }
+        if !number.is_empty() {
+            return Err(error());
+        }
+        minutes_from_f64(total).map(Estimate).ok_or_else(error)
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_minutes(self.0))
+    }
+}
+
+/// Formats minutes as `1h 30m`, `2h`, or `45m`.
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+fn minutes_from_f64(minutes: f64) -> Option<u32> {
+    if minutes.is_finite() && minutes > 0.0 && minutes <= f64::from(u32::MAX) {
+        Some(minutes.round() as u32)
+    } else {
+        None
+    }
+}
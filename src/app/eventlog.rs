@@ -0,0 +1,403 @@
+//! Task storage as an append-only log of events.
+//!
+//! A data file ending in `.jsonl` holds one JSON event per line instead of
+//! the task list itself: a `snapshot` of all tasks, followed by the
+//! `task-added`, `task-completed`, `task-reopened`, `task-edited`, and
+//! `task-removed` events of every later save. Saving appends only the events
+//! for what changed, no matter how many tasks there are, and the file keeps
+//! the history of every task.
+//!
+//! Once more than [`COMPACT_AFTER_EVENTS`] events follow the last snapshot,
+//! the next save compacts the log into a single snapshot again. The events
+//! it replaces are appended to `{stem}.history.jsonl` next to it, so no
+//! history is lost.
+//!
+//! Encrypted logs are rewritten as a whole on every save, since they are
+//! encrypted in one piece.
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app::{AppError, Result, Task, TaskStatus, clock, encryption};
+
+/// How many events may follow the last snapshot before the log is compacted.
+pub const COMPACT_AFTER_EVENTS: usize = 1000;
+
+/// A change recorded in the event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum TaskEvent {
+    /// The whole task list, written when a log starts or is compacted.
+    Snapshot {
+        tasks: Vec<Task>,
+    },
+    TaskAdded {
+        task: Task,
+    },
+    TaskCompleted {
+        uuid: Uuid,
+        completed_at: Option<DateTime<Utc>>,
+    },
+    TaskReopened {
+        uuid: Uuid,
+    },
+    /// A task changed in any other way; carries its new state.
+    TaskEdited {
+        task: Task,
+    },
+    TaskRemoved {
+        uuid: Uuid,
+    },
+}
+
+/// One line of the event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// When the event was saved.
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: TaskEvent,
+}
+
+/// Returns `true` if the data file at `path` is stored as an event log.
+pub fn is_event_log(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+/// Returns `true` if `contents` read from a data file are an event log
+/// rather than a TOML task list.
+pub fn is_event_log_contents(contents: &str) -> bool {
+    contents.trim_start().starts_with('{')
+}
+
+/// Returns where the events compacted out of the log at `path` are kept.
+pub fn history_file_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    path.with_file_name(format!("{}.history.jsonl", stem))
+}
+
+/// The identity of a task, read from the log without the rest of it.
+#[derive(Debug, Deserialize)]
+struct TaskKey {
+    id: u32,
+    uuid: Uuid,
+}
+
+/// The events that change which tasks exist or what their IDs are.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum KeyEvent {
+    Snapshot {
+        tasks: Vec<TaskKey>,
+    },
+    TaskAdded {
+        task: TaskKey,
+    },
+    TaskEdited {
+        task: TaskKey,
+    },
+    TaskRemoved {
+        uuid: Uuid,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Parses every record of an event log.
+pub fn parse_events(contents: &str) -> Result<Vec<EventRecord>> {
+    parse_lines(contents)
+}
+
+/// Returns the IDs of the tasks an event log ends up with, without
+/// rebuilding the tasks themselves.
+pub fn read_event_log_ids(contents: &str) -> Result<Vec<u32>> {
+    let mut ids: HashMap<Uuid, u32> = HashMap::new();
+    for event in parse_lines::<KeyEvent>(contents)? {
+        match event {
+            KeyEvent::Snapshot { tasks } => {
+                ids = tasks.into_iter().map(|t| (t.uuid, t.id)).collect();
+            }
+            KeyEvent::TaskAdded { task } => {
+                ids.insert(task.uuid, task.id);
+            }
+            KeyEvent::TaskEdited { task } => {
+                if let Some(id) = ids.get_mut(&task.uuid) {
+                    *id = task.id;
+                }
+            }
+            KeyEvent::TaskRemoved { uuid } => {
+                ids.remove(&uuid);
+            }
+            KeyEvent::Other => {}
+        }
+    }
+    Ok(ids.into_values().collect())
+}
+
+fn parse_lines<T: DeserializeOwned>(contents: &str) -> Result<Vec<T>> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| AppError::EventLog(format!("event {} is malformed: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Rebuilds the task list by applying `records` in order.
+pub fn replay(records: &[EventRecord]) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    for record in records {
+        apply(&mut tasks, &record.event);
+    }
+    tasks
+}
+
+fn apply(tasks: &mut Vec<Task>, event: &TaskEvent) {
+    let position = |tasks: &[Task], uuid: Uuid| tasks.iter().position(|t| t.uuid == uuid);
+    match event {
+        TaskEvent::Snapshot { tasks: snapshot } => *tasks = snapshot.clone(),
+        TaskEvent::TaskAdded { task } => tasks.push(task.clone()),
+        TaskEvent::TaskCompleted { uuid, completed_at } => {
+            if let Some(i) = position(tasks, *uuid) {
+                tasks[i].status = TaskStatus::Done;
+                tasks[i].completed_at = *completed_at;
+            }
+        }
+        TaskEvent::TaskReopened { uuid } => {
+            if let Some(i) = position(tasks, *uuid) {
+                tasks[i].status = TaskStatus::Todo;
+                tasks[i].completed_at = None;
+                tasks[i].completed_by = None;
+            }
+        }
+        TaskEvent::TaskEdited { task } => {
+            if let Some(i) = position(tasks, task.uuid) {
+                tasks[i] = task.clone();
+            }
+        }
+        TaskEvent::TaskRemoved { uuid } => {
+            if let Some(i) = position(tasks, *uuid) {
+                tasks.remove(i);
+            }
+        }
+    }
+}
+
+/// Returns `true` if `a` and `b` serialize to the same thing.
+fn same<T: Serialize + ?Sized>(a: &T, b: &T) -> bool {
+    match (serde_json::to_vec(a), serde_json::to_vec(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns the events turning `before` into `after`.
+///
+/// Changes the events can't express, such as tasks changing places, are
+/// recorded as a snapshot of `after`.
+pub fn diff_events(before: &[Task], after: &[Task]) -> Vec<TaskEvent> {
+    let old_tasks: HashMap<Uuid, &Task> = before.iter().map(|t| (t.uuid, t)).collect();
+    let kept: HashSet<Uuid> = after.iter().map(|t| t.uuid).collect();
+    let mut events: Vec<TaskEvent> = before
+        .iter()
+        .filter(|old| !kept.contains(&old.uuid))
+        .map(|old| TaskEvent::TaskRemoved { uuid: old.uuid })
+        .collect();
+    for task in after {
+        let Some(old) = old_tasks.get(&task.uuid) else {
+            events.push(TaskEvent::TaskAdded { task: task.clone() });
+            continue;
+        };
+        if same(*old, task) {
+            continue;
+        }
+        let mut toggled = (*old).clone();
+        toggled.status = task.status;
+        toggled.completed_at = task.completed_at;
+        events.push(match (same(&toggled, task), task.status) {
+            (true, TaskStatus::Done) => TaskEvent::TaskCompleted {
+                uuid: task.uuid,
+                completed_at: task.completed_at,
+            },
+            (true, TaskStatus::Todo) if task.completed_at.is_none() => {
+                TaskEvent::TaskReopened { uuid: task.uuid }
+            }
+            _ => TaskEvent::TaskEdited { task: task.clone() },
+        });
+    }
+
+    let mut replayed = before.to_vec();
+    for event in &events {
+        apply(&mut replayed, event);
+    }
+    if same(replayed.as_slice(), after) {
+        events
+    } else {
+        vec![TaskEvent::Snapshot {
+            tasks: after.to_vec(),
+        }]
+    }
+}
+
+/// Saves `tasks` to the event log at `path` by appending the events that
+/// lead to them, compacting the log if it has grown long.
+///
+/// `encrypted` decides the form of the file as for TOML data files; a log
+/// changing form is rewritten.
+pub fn write_event_log(tasks: &[Task], path: &Path, encrypted: bool) -> Result<()> {
+    let existing = if path.exists() {
+        Some(encryption::read_file(path)?)
+    } else {
+        None
+    };
+    let records = existing
+        .as_deref()
+        .map(parse_events)
+        .transpose()?
+        .unwrap_or_default();
+    let events = if records.is_empty() {
+        vec![TaskEvent::Snapshot {
+            tasks: tasks.to_vec(),
+        }]
+    } else {
+        diff_events(&replay(&records), tasks)
+    };
+    let changes_form = existing.is_some() && encryption::is_encrypted_file(path) != encrypted;
+    if events.is_empty() && !changes_form {
+        debug!("No events to append to {}.", path.display());
+        return Ok(());
+    }
+
+    let now = clock::now();
+    let new: Vec<EventRecord> = events
+        .into_iter()
+        .map(|event| EventRecord { at: now, event })
+        .collect();
+    if events_since_snapshot(&records) + new.len() > COMPACT_AFTER_EVENTS {
+        let archived: Vec<EventRecord> = records.into_iter().chain(new).collect();
+        compact(path, archived, tasks, encrypted)?;
+        return Ok(());
+    }
+
+    append_records(path, existing, &new, encrypted)
+}
+
+/// Returns how many of `records` follow the last snapshot.
+pub fn events_since_snapshot(records: &[EventRecord]) -> usize {
+    records
+        .iter()
+        .rev()
+        .take_while(|r| !matches!(r.event, TaskEvent::Snapshot { .. }))
+        .count()
+}
+
+/// Compacts the event log at `path` into a single snapshot now, however
+/// short it is, as `maintenance --compact` does.
+///
+/// # Returns
+///
+/// The number of events moved to the history file; none if the log is
+/// already a lone snapshot.
+pub fn compact_event_log(path: &Path) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let records = parse_events(&encryption::read_file(path)?)?;
+    if records.len() <= 1 {
+        return Ok(0);
+    }
+    let tasks = replay(&records);
+    compact(path, records, &tasks, encryption::is_encrypted_file(path))
+}
+
+/// Moves `archived`, the whole log at `path`, to its history file and
+/// starts the log again with a snapshot of `tasks`.
+fn compact(
+    path: &Path,
+    archived: Vec<EventRecord>,
+    tasks: &[Task],
+    encrypted: bool,
+) -> Result<usize> {
+    let history_path = history_file_path(path);
+    let history = if history_path.exists() {
+        Some(encryption::read_file(&history_path)?)
+    } else {
+        None
+    };
+    append_records(&history_path, history, &archived, encrypted)?;
+    let snapshot = EventRecord {
+        at: clock::now(),
+        event: TaskEvent::Snapshot {
+            tasks: tasks.to_vec(),
+        },
+    };
+    append_records(path, None, &[snapshot], encrypted)?;
+    info!(
+        "Compacted {} and moved {} events to {}.",
+        path.display(),
+        archived.len(),
+        history_path.display()
+    );
+    Ok(archived.len())
+}
+
+/// Appends `events` to the plain event log at `path`, whose current
+/// contents are `existing`, without replaying it.
+///
+/// A log growing past [`COMPACT_AFTER_EVENTS`] this way is compacted by the
+/// next regular save.
+pub fn append_events(path: &Path, existing: &str, events: Vec<TaskEvent>) -> Result<()> {
+    let now = clock::now();
+    let records: Vec<EventRecord> = events
+        .into_iter()
+        .map(|event| EventRecord { at: now, event })
+        .collect();
+    append_records(path, Some(existing.to_string()), &records, false)
+}
+
+/// Writes `records` after `existing`, the current contents of the file at
+/// `path`, or starts the file with them if there are none.
+///
+/// Plain files that already hold `existing` are only appended to.
+fn append_records(
+    path: &Path,
+    existing: Option<String>,
+    records: &[EventRecord],
+    encrypted: bool,
+) -> Result<()> {
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+    match existing {
+        Some(contents)
+            if !encrypted && !encryption::is_encrypted_file(path) && contents.ends_with('\n') =>
+        {
+            let mut file = fs::OpenOptions::new().append(true).open(path)?;
+            file.write_all(lines.as_bytes())?;
+        }
+        Some(mut contents) => {
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&lines);
+            encryption::write_file(path, &contents, encrypted)?;
+        }
+        None => encryption::write_file(path, &lines, encrypted)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,579 @@
+//! Converts tasks into external formats for use in other applications.
+//!
+//! Each export format is rendered into a `String`, leaving it to the caller
+//! to decide whether it ends up on stdout or in a file.
+//!
+//! The `readme-section` format is a Markdown checklist meant to live between
+//! `<!-- tasks:start -->` and `<!-- tasks:end -->` markers in a README.
+//! [`update_readme_sections`] refreshes every such block of a document in
+//! place; a start marker may name its own project, as in
+//! `<!-- tasks:start project="Web site" -->`, so one file can hold a section
+//! per project.
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use crate::app::{
+    AppError, INBOX_LABEL, Priority, Result, Task, TaskList, clock, group_by_project, to_csv,
+    to_json,
+};
+
+/// The product identifier written into generated iCalendar files.
+const ICS_PRODUCT_ID: &str = "-//task_manager_command_line//EN";
+
+/// The maximum length of an iCalendar content line in octets, excluding the line break.
+const ICS_MAX_LINE_LEN: usize = 75;
+
+/// Opens a task section in a Markdown file; may be followed by `project=NAME`.
+const README_SECTION_START: &str = "<!-- tasks:start";
+
+/// Closes a task section in a Markdown file.
+const README_SECTION_END: &str = "<!-- tasks:end -->";
+
+/// The formats tasks can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// The native TOML data file layout.
+    Toml,
+    /// A JSON array of tasks with every field, for `import --from json`.
+    Json,
+    /// Comma-separated values for spreadsheets, for `import --from csv`.
+    Csv,
+    /// iCalendar file with one VTODO entry per task that has a due date.
+    Ics,
+    /// Markdown checklist with a section per project.
+    Markdown,
+    /// Create and update the tasks in Todoist instead of writing a file.
+    Todoist,
+    /// Markdown checklist for the marked task section of a README; with
+    /// `--output`, the sections of that file are updated in place.
+    ReadmeSection,
+}
+
+/// Options restricting which tasks end up in an export.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Skip tasks that are already completed or cancelled.
+    pub only_pending: bool,
+    /// Only include tasks due on or after this date.
+    pub from: Option<NaiveDate>,
+    /// Only include tasks due on or before this date.
+    pub to: Option<NaiveDate>,
+    /// Only include tasks in this project.
+    pub project: Option<String>,
+    /// Scramble all free text before exporting, see [`anonymize_tasks`].
+    pub anonymize: bool,
+}
+
+impl ExportOptions {
+    /// Returns `true` if the task passes the pending, project, and due-date
+    /// range filters.
+    ///
+    /// Tasks without a due date only match when no date range is set.
+    pub fn matches(&self, task: &Task) -> bool {
+        if self.only_pending && !task.is_pending() {
+            return false;
+        }
+        if self
+            .project
+            .as_ref()
+            .is_some_and(|project| task.project.as_ref() != Some(project))
+        {
+            return false;
+        }
+        match task.due_date {
+            Some(due) => {
+                self.from.is_none_or(|from| due >= from) && self.to.is_none_or(|to| due <= to)
+            }
+            None => self.from.is_none() && self.to.is_none(),
+        }
+    }
+}
+
+/// Exports tasks in the requested format.
+///
+/// # Arguments
+///
+/// * `tasks` - The tasks to export.
+/// * `format` - The output format.
+/// * `options` - Filters applied before exporting.
+///
+/// # Returns
+///
+/// A `Result` containing the exported document, or an `AppError` if serialization fails.
+pub fn export_tasks(
+    tasks: &[Task],
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<String> {
+    let mut selected: Vec<Task> = tasks
+        .iter()
+        .filter(|t| options.matches(t))
+        .cloned()
+        .collect();
+    if options.anonymize {
+        selected = anonymize_tasks(&selected);
+    }
+
+    match format {
+        ExportFormat::Toml => {
+            let task_list = TaskList::of(selected);
+            toml::to_string(&task_list).map_err(AppError::TomlSerialize)
+        }
+        ExportFormat::Json => to_json(&selected),
+        ExportFormat::Csv => Ok(to_csv(&selected)),
+        ExportFormat::Ics => Ok(to_ics(&selected.iter().collect::<Vec<_>>())),
+        ExportFormat::Markdown => Ok(to_markdown(&selected.iter().collect::<Vec<_>>())),
+        ExportFormat::Todoist => Err(AppError::InvalidArgument(
+            "Todoist exports are uploaded, not written out; see `export_to_todoist`".to_string(),
+        )),
+        ExportFormat::ReadmeSection => {
+            let mut lines = vec![match &options.project {
+                Some(project) => format!("{} project={:?} -->", README_SECTION_START, project),
+                None => format!("{} -->", README_SECTION_START),
+            }];
+            lines.extend(markdown_checklist(&selected.iter().collect::<Vec<_>>()));
+            lines.push(README_SECTION_END.to_string());
+            Ok(lines.join("\n") + "\n")
+        }
+    }
+}
+
+/// Returns copies of `tasks` with all personal free text scrambled.
+///
+/// Every free-text or personal field, from descriptions, notes, and checklist
+/// steps to tags, contexts, URLs, and the people on a shared list, is replaced
+/// character by character:
+/// letters become random letters of the same case and digits random digits,
+/// while whitespace, punctuation, and lengths are kept. IDs, dates, flags, and
+/// structure are untouched, so the result still reproduces layout-dependent bugs.
+/// Identical strings scramble identically within one call, keeping grouping intact.
+pub fn anonymize_tasks(tasks: &[Task]) -> Vec<Task> {
+    let salt = RandomState::new();
+    tasks
+        .iter()
+        .map(|task| {
+            let mut task = task.clone();
+            task.description = scramble(&task.description, &salt);
+            task.project = task.project.map(|p| scramble(&p, &salt));
+            task.tags = task.tags.iter().map(|t| scramble(t, &salt)).collect();
+            task.contexts = task.contexts.iter().map(|c| scramble(c, &salt)).collect();
+            for person in [
+                &mut task.created_by,
+                &mut task.completed_by,
+                &mut task.updated_by,
+                &mut task.assignee,
+            ] {
+                *person = person.take().map(|p| scramble(&p, &salt));
+            }
+            task.url = task.url.map(|u| scramble(&u, &salt));
+            task.waiting_on = task.waiting_on.map(|w| scramble(&w, &salt));
+            for note in &mut task.notes {
+                note.text = scramble(&note.text, &salt);
+            }
+            for annotation in &mut task.annotations {
+                annotation.text = scramble(&annotation.text, &salt);
+            }
+            for attachment in &mut task.attachments {
+                attachment.target = scramble(&attachment.target, &salt);
+            }
+            for item in &mut task.checklist {
+                item.text = scramble(&item.text, &salt);
+            }
+            task
+        })
+        .collect()
+}
+
+/// Scrambles letters and digits of `s`, deriving each replacement from the whole
+/// input, the character position, and the per-run `salt`.
+fn scramble(s: &str, salt: &RandomState) -> String {
+    s.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let mut hasher = salt.build_hasher();
+            s.hash(&mut hasher);
+            i.hash(&mut hasher);
+            let n = hasher.finish();
+            if c.is_ascii_digit() {
+                char::from(b'0' + (n % 10) as u8)
+            } else if c.is_ascii_uppercase() {
+                char::from(b'A' + (n % 26) as u8)
+            } else if c.is_alphabetic() {
+                char::from(b'a' + (n % 26) as u8)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Renders tasks as the lines of a Markdown checklist.
+///
+/// Completed tasks are checked and cancelled ones struck through.
+pub fn markdown_checklist(tasks: &[&Task]) -> Vec<String> {
+    if tasks.is_empty() {
+        return vec!["_No tasks._".to_string()];
+    }
+    tasks
+        .iter()
+        .map(|task| {
+            let mut line = if task.is_cancelled() {
+                format!("- [x] ~~{}~~", task.description)
+            } else {
+                format!(
+                    "- [{}] {}",
+                    if task.is_done() { "x" } else { " " },
+                    task.description
+                )
+            };
+            if let Some(due) = task.due_date.filter(|_| task.is_pending()) {
+                line.push_str(&format!(" (due {}", due.format("%Y-%m-%d")));
+                if let Some(time) = task.due_time {
+                    line.push_str(&format!(" {}", time.format("%H:%M")));
+                }
+                line.push(')');
+            }
+            line
+        })
+        .collect()
+}
+
+/// Renders tasks as a Markdown checklist under a heading per project.
+///
+/// Projects are sorted by name, with the inbox first.
+pub fn to_markdown(tasks: &[&Task]) -> String {
+    let sections: Vec<String> = group_by_project(tasks)
+        .into_iter()
+        .map(|(project, group)| {
+            let mut lines = vec![
+                format!("## {}", project.unwrap_or(INBOX_LABEL)),
+                String::new(),
+            ];
+            lines.extend(markdown_checklist(&group));
+            lines.join("\n") + "\n"
+        })
+        .collect();
+    if sections.is_empty() {
+        return markdown_checklist(&[]).join("\n") + "\n";
+    }
+    sections.join("\n")
+}
+
+/// Replaces the contents of every marked task section in `document`.
+///
+/// Each section lists the tasks passing `options`, restricted to the project
+/// named by its start marker if it has one.
+///
+/// # Returns
+///
+/// The updated document and the number of sections in it, or an
+/// `AppError` if it has no section or one is never closed.
+pub fn update_readme_sections(
+    document: &str,
+    tasks: &[Task],
+    options: &ExportOptions,
+) -> Result<(String, usize)> {
+    let tasks = if options.anonymize {
+        anonymize_tasks(tasks)
+    } else {
+        tasks.to_vec()
+    };
+    let newline = if document.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut updated = String::with_capacity(document.len());
+    let mut sections = 0;
+    let mut lines = document.split_inclusive('\n').enumerate();
+    while let Some((index, line)) = lines.next() {
+        updated.push_str(line);
+        let Some(project) = section_project(line) else {
+            continue;
+        };
+        if !line.ends_with('\n') {
+            updated.push_str(newline);
+        }
+
+        let mut section_options = options.clone();
+        if project.is_some() {
+            section_options.project = project;
+        }
+        let selected: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| section_options.matches(t))
+            .collect();
+        for item in markdown_checklist(&selected) {
+            updated.push_str(&item);
+            updated.push_str(newline);
+        }
+
+        let Some((_, end)) = lines.find(|(_, l)| l.trim() == README_SECTION_END) else {
+            return Err(AppError::InvalidArgument(format!(
+                "The task section starting on line {} has no closing `{}`.",
+                index + 1,
+                README_SECTION_END
+            )));
+        };
+        updated.push_str(end);
+        sections += 1;
+    }
+
+    if sections == 0 {
+        return Err(AppError::InvalidArgument(format!(
+            "No task section found; add a `{} -->` line and a `{}` line where the tasks should go.",
+            README_SECTION_START, README_SECTION_END
+        )));
+    }
+    Ok((updated, sections))
+}
+
+/// Parses a section start marker, returning the project it names, if any.
+///
+/// Returns `None` if `line` isn't a start marker.
+fn section_project(line: &str) -> Option<Option<String>> {
+    let attributes = line
+        .trim()
+        .strip_prefix(README_SECTION_START)?
+        .strip_suffix("-->")?;
+    if !attributes.is_empty() && !attributes.starts_with(' ') {
+        return None;
+    }
+    let project = attributes
+        .trim()
+        .strip_prefix("project=")
+        .map(|p| p.trim_matches('"').to_string())
+        .filter(|p| !p.is_empty());
+    Some(project)
+}
+
+/// Renders tasks with due dates as an iCalendar document of VTODO components.
+///
+/// Tasks without a due date are skipped, since calendar clients have nowhere to show them.
+/// Each VTODO's UID is the task's UUID, as with CalDAV sync, so it survives
+/// `renumber`.
+pub fn to_ics(tasks: &[&Task]) -> String {
+    let stamp = clock::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = Vec::new();
+    for task in tasks.iter().filter(|t| t.due_date.is_some()) {
+        let uid = task.uuid.to_string();
+        lines.push("BEGIN:VTODO".to_string());
+        lines.extend(vtodo_properties(task, &uid, &stamp));
+        lines.push("END:VTODO".to_string());
+    }
+    ics_document(lines)
+}
+
+/// Returns the properties of the VTODO component describing `task`.
+pub(crate) fn vtodo_properties(task: &Task, uid: &str, stamp: &str) -> Vec<String> {
+    let mut lines = vec![
+        format!("UID:{}", uid),
+        format!("DTSTAMP:{}", stamp),
+        format!("CREATED:{}", task.created_at.format("%Y%m%dT%H%M%SZ")),
+        format!("SUMMARY:{}", escape_ics_text(&task.description)),
+    ];
+    // A due time is written as floating local time, like the wall-clock
+    // time it was entered as.
+    match (task.due_date, task.due_time) {
+        (Some(due), Some(time)) => lines.push(format!(
+            "DUE:{}",
+            due.and_time(time).format("%Y%m%dT%H%M%S")
+        )),
+        (Some(due), None) => lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d"))),
+        (None, _) => {}
+    }
+    lines.push(format!(
+        "STATUS:{}",
+        if task.is_done() {
+            "COMPLETED"
+        } else if task.is_cancelled() {
+            "CANCELLED"
+        } else {
+            "NEEDS-ACTION"
+        }
+    ));
+    if let Some(priority) = task.priority {
+        lines.push(format!("PRIORITY:{}", ics_priority(priority)));
+    }
+    let categories: Vec<String> = task
+        .project
+        .iter()
+        .chain(&task.tags)
+        .map(|c| escape_ics_text(c))
+        .collect();
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+    lines
+}
+
+/// Wraps component lines in a VCALENDAR and folds them into a document.
+pub(crate) fn ics_document(components: Vec<String>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", ICS_PRODUCT_ID),
+    ];
+    lines.extend(components);
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_ics_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Maps a task priority onto the iCalendar 1 (highest) to 9 (lowest) scale.
+pub(crate) fn ics_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::High => 1,
+        Priority::Medium => 5,
+        Priority::Low => 9,
+    }
+}
+
+/// Escapes characters that have special meaning in iCalendar TEXT values.
+pub(crate) fn escape_ics_text(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line so that no physical line exceeds 75 octets.
+///
+/// Continuation lines start with a single space, as required by RFC 5545.
+/// Folding never splits a multi-byte UTF-8 character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut current_len = 0;
+    for c in line.chars() {
+        let char_len = c.len_utf8();
+        if current_len + char_len > ICS_MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            current_len = 1;
+        }
+        folded.push(c);
+        current_len += char_len;
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::app::{Annotation, Attachment, ChecklistItem, Note, TaskStatus};
+
+    #[test]
+    fn to_ics_uses_the_task_uuid_as_uid() {
+        let mut task = Task::new(
+            7,
+            "Pay rent".to_string(),
+            NaiveDate::from_ymd_opt(2026, 5, 1),
+        );
+        let uid = format!("UID:{}", task.uuid);
+        assert!(to_ics(&[&task]).lines().any(|line| line == uid));
+        task.id = 1;
+        assert!(to_ics(&[&task]).lines().any(|line| line == uid));
+    }
+
+    #[test]
+    fn anonymize_tasks_scrambles_every_string_field() {
+        let now = Utc::now();
+        // Spelled out field by field, so a field added to `Task` later has
+        // to be filled in here too.
+        let task = Task {
+            id: 1,
+            uuid: Uuid::new_v4(),
+            description: "Renew the passport".to_string(),
+            status: TaskStatus::Todo,
+            created_at: now,
+            due_date: None,
+            due_time: None,
+            project: Some("Travel".to_string()),
+            priority: None,
+            tags: vec!["paperwork".to_string()],
+            contexts: vec!["townhall".to_string()],
+            completed_at: None,
+            updated_at: None,
+            created_by: Some("alice.liddell".to_string()),
+            completed_by: Some("bob.marley".to_string()),
+            updated_by: Some("carol.danvers".to_string()),
+            notes: vec![Note {
+                created_at: now,
+                text: "Bring two photos".to_string(),
+            }],
+            annotations: vec![Annotation {
+                created_at: now,
+                text: "Called the office".to_string(),
+            }],
+            attachments: vec![Attachment {
+                target: "/home/alice/scan.pdf".to_string(),
+                added_at: now,
+            }],
+            checklist: vec![ChecklistItem {
+                text: "Fill in the form".to_string(),
+                done: false,
+            }],
+            assignee: Some("dave.bowman".to_string()),
+            url: Some("https://example.org/passport".to_string()),
+            parent: None,
+            progress: None,
+            goal: None,
+            waiting_on: Some("reply from the embassy".to_string()),
+            expires_on: None,
+            start_date: None,
+            recurrence: None,
+            estimate: None,
+            escalations_sent: 0,
+            remind_before_minutes: None,
+            reminded_on: None,
+            alerts: Vec::new(),
+            alerted_at: None,
+            intervals: Vec::new(),
+            snooze_count: 0,
+        };
+        let originals = [
+            "Renew the passport",
+            "Travel",
+            "paperwork",
+            "townhall",
+            "alice.liddell",
+            "bob.marley",
+            "carol.danvers",
+            "Bring two photos",
+            "Called the office",
+            "scan.pdf",
+            "Fill in the form",
+            "dave.bowman",
+            "example.org",
+            "embassy",
+        ];
+
+        let exported = serde_json::to_string(&anonymize_tasks(&[task])).unwrap();
+        for original in originals {
+            assert!(
+                !exported.contains(original),
+                "{:?} survived anonymizing: {}",
+                original,
+                exported
+            );
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Ad-hoc task filters such as `project:work tag:urgent`.
+//!
+//! A filter is a whitespace-separated list of terms, all of which must match:
+//!
+//! * `project:NAME` – the task belongs to the project
+//! * `tag:NAME` or `+NAME` – the task has the tag
+//! * `@NAME` – the task can be done in the context, e.g. `@office`
+//! * `priority:LEVEL` – the task has the priority (`low`, `medium`, `high`)
+//! * any other word – the description contains it, ignoring case
+//!
+//! Setting `TASK_FILTER` applies a filter for the rest of a shell session.
+
+use clap::ValueEnum;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app::{Priority, Task};
+
+/// The environment variable holding the session filter.
+pub const FILTER_ENV_VAR: &str = "TASK_FILTER";
+
+/// A single condition of a [`TaskFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterTerm {
+    Project(String),
+    Tag(String),
+    Context(String),
+    Priority(Priority),
+    Text(String),
+}
+
+impl FilterTerm {
+    /// Returns `true` if `task` satisfies this term.
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            FilterTerm::Project(name) => task.project.as_deref() == Some(name.as_str()),
+            FilterTerm::Tag(tag) => task.tags.iter().any(|t| t == tag),
+            FilterTerm::Context(context) => task.contexts.iter().any(|c| c == context),
+            FilterTerm::Priority(priority) => task.priority == Some(*priority),
+            FilterTerm::Text(word) => task
+                .description
+                .to_lowercase()
+                .contains(&word.to_lowercase()),
+        }
+    }
+}
+
+/// A conjunction of filter terms, remembering the text it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskFilter {
+    pub terms: Vec<FilterTerm>,
+    source: String,
+}
+
+impl TaskFilter {
+    /// Returns `true` if `task` satisfies every term.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.terms.iter().all(|term| term.matches(task))
+    }
+
+    /// Returns `true` if the filter has no terms and so matches everything.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+impl FromStr for FilterTerm {
+    type Err = String;
+
+    fn from_str(word: &str) -> Result<Self, Self::Err> {
+        if let Some(tag) = word.strip_prefix('+') {
+            return Ok(FilterTerm::Tag(tag.to_string()));
+        }
+        if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            return Ok(FilterTerm::Context(context.to_string()));
+        }
+        let Some((key, value)) = word.split_once(':') else {
+            return Ok(FilterTerm::Text(word.to_string()));
+        };
+        if value.is_empty() {
+            return Err(format!("Filter term '{}' is missing a value", word));
+        }
+        match key {
+            "project" => Ok(FilterTerm::Project(value.to_string())),
+            "tag" => Ok(FilterTerm::Tag(value.to_string())),
+            "priority" => Priority::from_str(value, true)
+                .map(FilterTerm::Priority)
+                .map_err(|_| format!("Unknown priority '{}' in filter", value)),
+            _ => Err(format!("Unknown filter key '{}'", key)),
+        }
+    }
+}
+
+impl FromStr for TaskFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = s
+            .split_whitespace()
+            .map(FilterTerm::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok(TaskFilter {
+            terms,
+            source: s.split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+    }
+}
+
+impl fmt::Display for TaskFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
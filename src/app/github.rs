@@ -0,0 +1,380 @@
+//! Working on GitHub issues as tasks through GitHub's REST API.
+//!
+//! `github pull --repo owner/name` copies the open issues of a repository
+//! assigned to the token's user into the task list, and `github close <id>`
+//! completes a task and closes its issue. A task is linked to its issue by
+//! its URL, so pulling again updates the tasks pulled before instead of
+//! adding them twice, and any task whose URL points at an issue can be
+//! closed. Issue labels become tags. Tasks whose issue was closed upstream
+//! are marked as completed on the next pull.
+//!
+//! The token is read from `GITHUB_TOKEN`, or from `token` under `[github]`
+//! in the config file. Talking to GitHub is only available when the crate is
+//! built with the `github` feature.
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+
+use crate::app::{AppError, ImportReport, Result, Task};
+
+/// The environment variable holding the GitHub token.
+pub const GITHUB_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+
+/// How to reach GitHub.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// A personal access token allowed to read and write issues.
+    pub token: Option<String>,
+    /// The API to talk to, e.g. a GitHub Enterprise server's; defaults to
+    /// github.com's.
+    pub api_url: Option<String>,
+}
+
+impl GitHubConfig {
+    /// Returns the token from `GITHUB_TOKEN`, or else from the config.
+    pub fn token(&self) -> Result<String> {
+        env::var(GITHUB_TOKEN_ENV_VAR)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .or_else(|| self.token.clone())
+            .ok_or_else(|| {
+                AppError::GitHub(format!(
+                    "no token; set {} or `token` under [github] in the config file",
+                    GITHUB_TOKEN_ENV_VAR
+                ))
+            })
+    }
+}
+
+/// A repository, written `owner/name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubRepo {
+    pub owner: String,
+    pub name: String,
+}
+
+impl fmt::Display for GitHubRepo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+/// Parses `owner/name`, for `--repo`.
+pub fn parse_github_repo(s: &str) -> std::result::Result<GitHubRepo, String> {
+    match s.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Ok(GitHubRepo {
+                owner: owner.to_string(),
+                name: name.trim_end_matches(".git").to_string(),
+            })
+        }
+        _ => Err(format!("expected `owner/name`, got '{}'", s)),
+    }
+}
+
+/// An issue a task is linked to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    pub repo: GitHubRepo,
+    pub number: u64,
+}
+
+impl IssueRef {
+    /// Reads the issue from a task's URL, e.g.
+    /// `https://github.com/owner/name/issues/12`.
+    pub fn from_url(url: &str) -> Option<Self> {
+        let mut segments = url.trim_end_matches('/').rsplit('/');
+        let number = segments.next()?.parse().ok()?;
+        if segments.next()? != "issues" {
+            return None;
+        }
+        let name = segments.next()?.to_string();
+        let owner = segments.next()?.to_string();
+        Some(IssueRef {
+            repo: GitHubRepo { owner, name },
+            number,
+        })
+    }
+
+    /// Returns the issue `task` is linked to, if any.
+    pub fn of(task: &Task) -> Option<Self> {
+        task.url.as_deref().and_then(Self::from_url)
+    }
+}
+
+impl fmt::Display for IssueRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.repo, self.number)
+    }
+}
+
+/// Copies the open issues of `repo` assigned to the token's user into
+/// `tasks`.
+///
+/// Issues pulled before are updated in place rather than added again, and
+/// pending tasks whose issue has been closed since are marked as completed.
+pub fn pull_github_issues(
+    tasks: &mut Vec<Task>,
+    repo: &GitHubRepo,
+    config: &GitHubConfig,
+) -> Result<ImportReport> {
+    api::pull(tasks, repo, config)
+}
+
+/// Closes the issue `task` is linked to, returning which one it was.
+pub fn close_github_issue(task: &Task, config: &GitHubConfig) -> Result<IssueRef> {
+    let issue = IssueRef::of(task).ok_or_else(|| {
+        AppError::InvalidArgument(format!(
+            "Task {} is not linked to a GitHub issue; its URL must point at one.",
+            task.id
+        ))
+    })?;
+    api::close(&issue, config)?;
+    Ok(issue)
+}
+
+#[cfg(feature = "github")]
+mod api {
+    use log::{debug, info};
+    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+    use std::collections::HashSet;
+    use ureq::Agent;
+    use ureq::http::Response;
+
+    use super::{GitHubConfig, GitHubRepo, IssueRef};
+    use crate::app::{AppError, ImportReport, Result, Task};
+
+    const DEFAULT_API_URL: &str = "https://api.github.com";
+
+    /// Issues asked for per page; fewer means the last page.
+    const PAGE_SIZE: usize = 100;
+
+    #[derive(Deserialize)]
+    struct User {
+        login: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Label {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Issue {
+        number: u64,
+        title: String,
+        html_url: String,
+        state: String,
+        #[serde(default)]
+        labels: Vec<Label>,
+        /// Set when the issue is a pull request, which the issues API lists
+        /// too.
+        pull_request: Option<serde_json::Value>,
+    }
+
+    impl Issue {
+        /// Copies the fields onto `task`, returning `true` if any changed.
+        fn apply(&self, task: &mut Task) -> bool {
+            let tags: Vec<String> = self.labels.iter().map(|l| l.name.clone()).collect();
+            let changed = task.description != self.title
+                || task.url.as_deref() != Some(self.html_url.as_str())
+                || task.tags != tags;
+            task.description = self.title.clone();
+            task.url = Some(self.html_url.clone());
+            task.tags = tags;
+            changed
+        }
+    }
+
+    struct Client {
+        agent: Agent,
+        base: String,
+        auth: String,
+    }
+
+    impl Client {
+        fn new(config: &GitHubConfig) -> Result<Self> {
+            let auth = format!("Bearer {}", config.token()?);
+            let agent = Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .new_agent();
+            let base = config
+                .api_url
+                .as_deref()
+                .unwrap_or(DEFAULT_API_URL)
+                .trim_end_matches('/')
+                .to_string();
+            Ok(Client { agent, base, auth })
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("{}/{}", self.base, path)
+        }
+
+        /// Turns transport errors and unsuccessful statuses into errors.
+        fn check(
+            url: &str,
+            response: std::result::Result<Response<ureq::Body>, ureq::Error>,
+        ) -> Result<Response<ureq::Body>> {
+            let response = response.map_err(|e| AppError::GitHub(format!("{}: {}", url, e)))?;
+            match response.status().as_u16() {
+                200..=299 => Ok(response),
+                401 => Err(AppError::GitHub(
+                    "the token was rejected; check that it hasn't expired".to_string(),
+                )),
+                403 | 404 => Err(AppError::GitHub(format!(
+                    "{} returned HTTP {}; check that the repository exists and the token may \
+                     access its issues",
+                    url,
+                    response.status().as_u16()
+                ))),
+                status => Err(AppError::GitHub(format!(
+                    "{} returned HTTP {}",
+                    url, status
+                ))),
+            }
+        }
+
+        fn read<T: DeserializeOwned>(url: &str, mut response: Response<ureq::Body>) -> Result<T> {
+            response
+                .body_mut()
+                .read_json()
+                .map_err(|e| AppError::GitHub(format!("{}: unexpected response: {}", url, e)))
+        }
+
+        fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+            let url = self.url(path);
+            let mut request = self
+                .agent
+                .get(&url)
+                .header("Authorization", &self.auth)
+                .header("Accept", "application/vnd.github+json");
+            for (name, value) in query {
+                request = request.query(*name, *value);
+            }
+            Self::read(&url, Self::check(&url, request.call())?)
+        }
+    }
+
+    pub(super) fn pull(
+        tasks: &mut Vec<Task>,
+        repo: &GitHubRepo,
+        config: &GitHubConfig,
+    ) -> Result<ImportReport> {
+        let client = Client::new(config)?;
+        let user: User = client.get("user", &[])?;
+        let path = format!("repos/{}/issues", repo);
+        let per_page = PAGE_SIZE.to_string();
+        let mut issues: Vec<Issue> = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let batch: Vec<Issue> = client.get(
+                &path,
+                &[
+                    ("assignee", &user.login),
+                    ("state", "open"),
+                    ("per_page", &per_page),
+                    ("page", &page),
+                ],
+            )?;
+            let last = batch.len() < PAGE_SIZE;
+            issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+            if last {
+                break;
+            }
+        }
+        debug!(
+            "Fetched {} open issues of {} assigned to {}.",
+            issues.len(),
+            repo,
+            user.login
+        );
+
+        let mut report = ImportReport::default();
+        let open: HashSet<u64> = issues.iter().map(|i| i.number).collect();
+        for task in tasks.iter_mut().filter(|t| t.is_pending()) {
+            let Some(issue) = IssueRef::of(task).filter(|i| i.repo == *repo) else {
+                continue;
+            };
+            if open.contains(&issue.number) {
+                continue;
+            }
+            // No longer listed: closed, or assigned to someone else.
+            let current: Issue = client.get(&format!("{}/{}", path, issue.number), &[])?;
+            if current.state == "closed" {
+                task.mark_completion(true);
+                report.completed += 1;
+            }
+        }
+
+        let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        for issue in &issues {
+            let linked = tasks.iter_mut().find(|t| {
+                IssueRef::of(t).is_some_and(|i| i.repo == *repo && i.number == issue.number)
+            });
+            match linked {
+                Some(task) => {
+                    if issue.apply(task) {
+                        report.updated += 1;
+                    }
+                }
+                None => {
+                    let mut task = Task::new(next_id, String::new(), None);
+                    next_id += 1;
+                    issue.apply(&mut task);
+                    tasks.push(task);
+                    report.added += 1;
+                }
+            }
+        }
+        info!(
+            "GitHub pull from {}: {} added, {} updated, {} completed.",
+            repo, report.added, report.updated, report.completed
+        );
+        Ok(report)
+    }
+
+    pub(super) fn close(issue: &IssueRef, config: &GitHubConfig) -> Result<()> {
+        let client = Client::new(config)?;
+        let url = client.url(&format!("repos/{}/issues/{}", issue.repo, issue.number));
+        let response = client
+            .agent
+            .patch(&url)
+            .header("Authorization", &client.auth)
+            .header("Accept", "application/vnd.github+json")
+            .send_json(json!({ "state": "closed", "state_reason": "completed" }));
+        Client::check(&url, response)?;
+        info!("Closed GitHub issue {}.", issue);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "github"))]
+mod api {
+    use super::{GitHubConfig, GitHubRepo, IssueRef};
+    use crate::app::{AppError, ImportReport, Result, Task};
+
+    fn unavailable() -> AppError {
+        AppError::GitHub(
+            "this build does not include GitHub support; rebuild with `--features github`"
+                .to_string(),
+        )
+    }
+
+    pub(super) fn pull(
+        _tasks: &mut Vec<Task>,
+        _repo: &GitHubRepo,
+        _config: &GitHubConfig,
+    ) -> Result<ImportReport> {
+        Err(unavailable())
+    }
+
+    pub(super) fn close(_issue: &IssueRef, _config: &GitHubConfig) -> Result<()> {
+        Err(unavailable())
+    }
+}
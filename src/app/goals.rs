@@ -0,0 +1,117 @@
+//! Goals that tasks work toward, for `goal` and `add --goal`.
+//!
+//! `goal add "Ship v1.0" --due 2025-09-01` defines a goal, `add --goal 2` or
+//! `edit --goal 2` links a task to it, and `goal list` shows how much of each
+//! goal is done: the share of its linked tasks that are, leaving out
+//! cancelled ones. Goals are kept next to the data file
+//! (`tasks.toml` → `tasks.goals.toml`):
+//!
+//! ```toml
+//! [[goal]]
+//! id = 2
+//! title = "Ship v1.0"
+//! due = "2025-09-01"
+//! ```
+
+use chrono::NaiveDate;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{AppError, Result, Task};
+
+/// A goal tasks can be linked to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    /// The ID `add --goal` links tasks by.
+    pub id: u32,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+}
+
+/// The on-disk layout of the goals file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GoalsFile {
+    #[serde(default)]
+    goal: Vec<Goal>,
+}
+
+/// How far along a goal is, from the tasks linked to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoalStatus {
+    /// The number of linked tasks, leaving out cancelled ones.
+    pub total: usize,
+    pub done: usize,
+}
+
+impl GoalStatus {
+    /// Counts the tasks of `tasks` linked to `goal`.
+    pub fn of(goal: &Goal, tasks: &[Task]) -> Self {
+        let linked: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.goal == Some(goal.id) && !t.is_cancelled())
+            .collect();
+        GoalStatus {
+            total: linked.len(),
+            done: linked.iter().filter(|t| t.is_done()).count(),
+        }
+    }
+
+    /// Returns the share of the linked tasks that are done, in percent, or
+    /// `None` if no tasks are linked.
+    pub fn percent(&self) -> Option<u8> {
+        (self.total > 0).then(|| (self.done * 100 / self.total) as u8)
+    }
+}
+
+/// Returns the goals file belonging to the data file at `tasks_path`.
+pub fn goals_file_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.goals.toml", stem))
+}
+
+/// Loads the goals at `path`, in the order they were added; none if the file
+/// doesn't exist.
+pub fn load_goals(path: &Path) -> Result<Vec<Goal>> {
+    if !path.exists() {
+        debug!("No goals file at {}.", path.display());
+        return Ok(Vec::new());
+    }
+    let file: GoalsFile =
+        toml::from_str(&fs::read_to_string(path)?).map_err(|e| AppError::from(e).in_file(path))?;
+    Ok(file.goal)
+}
+
+/// Saves `goals` to `path`.
+pub fn save_goals(goals: &[Goal], path: &Path) -> Result<()> {
+    let file = GoalsFile {
+        goal: goals.to_vec(),
+    };
+    fs::write(path, toml::to_string(&file)?)?;
+    info!("Saved {} goals to {}.", goals.len(), path.display());
+    Ok(())
+}
+
+/// Returns the goal with ID `id` among `goals`.
+///
+/// # Returns
+///
+/// An `AppError::InvalidArgument` if there is none.
+pub fn find_goal(goals: &[Goal], id: u32) -> Result<&Goal> {
+    goals.iter().find(|g| g.id == id).ok_or_else(|| {
+        AppError::InvalidArgument(format!(
+            "No goal with ID {}; `goal list` shows the goals there are.",
+            id
+        ))
+    })
+}
+
+/// Returns the ID for a new goal: one more than the highest in use.
+pub fn next_goal_id(goals: &[Goal]) -> u32 {
+    goals.iter().map(|g| g.id).max().unwrap_or(0) + 1
+}
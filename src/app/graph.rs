@@ -0,0 +1,152 @@
+//! The structure of the task list as a graph, for `graph`.
+//!
+//! Tasks are linked to their subtasks, made with `add --parent` or
+//! `edit --parent`. The graph prints as an indented tree in the terminal, or
+//! as Graphviz DOT to render with e.g. `tm graph --format dot | dot -Tsvg`,
+//! where edges point from a task to its subtasks and blocked tasks stand
+//! out.
+
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::app::{Task, TaskStatus};
+
+/// How `graph` prints the tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// An indented tree of each task's subtasks.
+    #[default]
+    Tree,
+    /// Graphviz DOT, for `dot` and other Graphviz tools.
+    Dot,
+}
+
+/// The tasks of the graph and how they are linked.
+#[derive(Debug)]
+pub struct TaskGraph<'a> {
+    /// The tasks shown, by ID.
+    pub tasks: Vec<&'a Task>,
+}
+
+impl<'a> TaskGraph<'a> {
+    /// Collects the tasks of `tasks` to show: pending ones, or with `all`
+    /// every task, and only those of `project` if given.
+    pub fn build(tasks: &'a [Task], all: bool, project: Option<&str>) -> Self {
+        let mut shown: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| all || t.is_pending())
+            .filter(|t| project.is_none_or(|p| t.project.as_deref() == Some(p)))
+            .collect();
+        shown.sort_by_key(|t| t.id);
+        TaskGraph { tasks: shown }
+    }
+
+    /// Returns the subtasks of the task with UUID `uuid` in the graph.
+    fn children(&self, uuid: Uuid) -> impl Iterator<Item = &'a Task> + '_ {
+        self.tasks
+            .iter()
+            .copied()
+            .filter(move |t| t.parent == Some(uuid))
+    }
+
+    /// Returns the tasks whose parent isn't in the graph.
+    fn roots(&self) -> impl Iterator<Item = &'a Task> + '_ {
+        let shown: HashSet<Uuid> = self.tasks.iter().map(|t| t.uuid).collect();
+        self.tasks
+            .iter()
+            .copied()
+            .filter(move |t| t.parent.is_none_or(|p| !shown.contains(&p)))
+    }
+
+    /// Renders the graph in `format`; with `ascii`, the tree is drawn with
+    /// ASCII characters only.
+    pub fn render(&self, format: GraphFormat, ascii: bool) -> String {
+        match format {
+            GraphFormat::Tree => self.tree(ascii),
+            GraphFormat::Dot => self.dot(),
+        }
+    }
+
+    fn tree(&self, ascii: bool) -> String {
+        let mut text = String::new();
+        let mut seen = HashSet::new();
+        for root in self.roots() {
+            self.branch(&mut text, root, "", None, ascii, &mut seen);
+        }
+        text
+    }
+
+    /// Writes `task` and its subtasks below `prefix`; `last` tells whether
+    /// it is its parent's last subtask, and is `None` for a root.
+    fn branch(
+        &self,
+        text: &mut String,
+        task: &Task,
+        prefix: &str,
+        last: Option<bool>,
+        ascii: bool,
+        seen: &mut HashSet<Uuid>,
+    ) {
+        let (tee, elbow, pipe) = if ascii {
+            ("|-- ", "`-- ", "|   ")
+        } else {
+            ("├── ", "└── ", "│   ")
+        };
+        let connector = match last {
+            None => "",
+            Some(true) => elbow,
+            Some(false) => tee,
+        };
+        let _ = write!(
+            text,
+            "{}{}{} {}",
+            prefix, connector, task.id, task.description
+        );
+        if task.status != TaskStatus::Todo {
+            let _ = write!(text, " [{}]", task.status.label());
+        }
+        text.push('\n');
+        // A parent chain looping back on itself is drawn once.
+        if !seen.insert(task.uuid) {
+            return;
+        }
+        let prefix = match last {
+            None => prefix.to_string(),
+            Some(true) => format!("{}    ", prefix),
+            Some(false) => format!("{}{}", prefix, pipe),
+        };
+        let children: Vec<&Task> = self.children(task.uuid).collect();
+        for (i, child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            self.branch(text, child, &prefix, Some(last), ascii, seen);
+        }
+    }
+
+    fn dot(&self) -> String {
+        let mut dot = String::from("digraph tasks {\n    rankdir=LR;\n    node [shape=box];\n");
+        for task in &self.tasks {
+            let label = escape_dot(&format!("{}: {}", task.id, task.description));
+            let style = match task.status {
+                TaskStatus::Blocked => ", style=filled, fillcolor=\"#f8d7da\"",
+                TaskStatus::InProgress => ", style=filled, fillcolor=\"#fff3cd\"",
+                TaskStatus::Done | TaskStatus::Cancelled => ", style=dashed, fontcolor=gray",
+                TaskStatus::Backlog | TaskStatus::Todo => "",
+            };
+            let _ = writeln!(dot, "    t{} [label=\"{}\"{}];", task.id, label, style);
+        }
+        for task in &self.tasks {
+            for child in self.children(task.uuid) {
+                let _ = writeln!(dot, "    t{} -> t{};", task.id, child.id);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escapes `s` for a double-quoted DOT string.
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
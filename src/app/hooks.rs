@@ -0,0 +1,141 @@
+//! Scripts run when tasks are added, completed, or removed.
+//!
+//! An executable named `on-add`, `on-complete`, or `on-remove` in the `hooks`
+//! directory next to the config file (e.g. `~/.config/task_manager/hooks/`)
+//! runs once for every task the command added, completed, or removed. It
+//! gets the task as JSON on standard input, and `TASK_HOOK_EVENT` names the
+//! event. Its output goes to the terminal.
+//!
+//! A hook exiting with a non-zero status vetoes the change: the command
+//! fails and nothing is saved.
+
+use log::{debug, info};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use uuid::Uuid;
+
+use crate::app::{AppError, Result, Task, config_file_path};
+
+/// The environment variable telling a hook which event it runs for.
+const EVENT_ENV_VAR: &str = "TASK_HOOK_EVENT";
+
+/// The changes hooks can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookEvent {
+    Add,
+    Complete,
+    Remove,
+}
+
+impl HookEvent {
+    const ALL: [HookEvent; 3] = [HookEvent::Add, HookEvent::Complete, HookEvent::Remove];
+
+    /// Returns the file name of the hook for the event.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            HookEvent::Add => "on-add",
+            HookEvent::Complete => "on-complete",
+            HookEvent::Remove => "on-remove",
+        }
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            HookEvent::Add => "add",
+            HookEvent::Complete => "complete",
+            HookEvent::Remove => "remove",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Returns the directory hooks are looked up in: `hooks` next to the config
+/// file.
+pub fn hooks_dir() -> Option<PathBuf> {
+    config_file_path().map(|path| path.with_file_name("hooks"))
+}
+
+/// The hooks that are installed.
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    paths: HashMap<HookEvent, PathBuf>,
+}
+
+impl Hooks {
+    /// Finds the hooks in `dir`, returning `None` if there are none.
+    pub fn find(dir: &Path) -> Option<Self> {
+        let paths: HashMap<HookEvent, PathBuf> = HookEvent::ALL
+            .into_iter()
+            .map(|event| (event, dir.join(event.file_name())))
+            .filter(|(_, path)| path.is_file())
+            .collect();
+        (!paths.is_empty()).then_some(Hooks { paths })
+    }
+
+    /// Runs the hooks for the tasks added, completed, and removed between
+    /// `before` and `after`, stopping at the first veto.
+    pub fn run(&self, before: &[Task], after: &[Task]) -> Result<()> {
+        for (event, task) in changes(before, after) {
+            if let Some(path) = self.paths.get(&event) {
+                run_hook(path, event, task)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the tasks added, completed, and removed between `before` and
+/// `after`, in that order.
+fn changes<'a>(before: &'a [Task], after: &'a [Task]) -> Vec<(HookEvent, &'a Task)> {
+    let old: HashMap<Uuid, &Task> = before.iter().map(|t| (t.uuid, t)).collect();
+    let kept: HashMap<Uuid, &Task> = after.iter().map(|t| (t.uuid, t)).collect();
+    let mut changes = Vec::new();
+    for task in after {
+        match old.get(&task.uuid) {
+            None => changes.push((HookEvent::Add, task)),
+            Some(previous) if !previous.is_done() && task.is_done() => {
+                changes.push((HookEvent::Complete, task));
+            }
+            Some(_) => {}
+        }
+    }
+    changes.sort_by_key(|(event, _)| *event == HookEvent::Complete);
+    changes.extend(
+        before
+            .iter()
+            .filter(|t| !kept.contains_key(&t.uuid))
+            .map(|t| (HookEvent::Remove, t)),
+    );
+    changes
+}
+
+fn run_hook(path: &Path, event: HookEvent, task: &Task) -> Result<()> {
+    debug!("Running {} for task ID {}.", path.display(), task.id);
+    let failed =
+        |e: std::io::Error| AppError::Hook(format!("could not run {}: {}", path.display(), e));
+    let mut child = Command::new(path)
+        .env(EVENT_ENV_VAR, event.to_string())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(failed)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook may exit without reading its input; that is its choice.
+        let _ = stdin.write_all(serde_json::to_string(task)?.as_bytes());
+    }
+    let status = child.wait().map_err(failed)?;
+    if !status.success() {
+        return Err(AppError::Hook(format!(
+            "{} rejected task ID {} ({}); nothing was saved",
+            event.file_name(),
+            task.id,
+            status
+        )));
+    }
+    info!("{} accepted task ID {}.", event.file_name(), task.id);
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! Translations of the messages the binary prints.
+//!
+//! Messages live in Fluent catalogs, one per language, compiled into the
+//! binary from `src/app/i18n/`. [`tr!`](crate::tr) looks a message up by its
+//! ID in the active language, falling back to English for messages a catalog
+//! lacks.
+//!
+//! The language is the one named by `language` in the config file, or else
+//! by the first of `TASK_LANG`, `LC_ALL`, `LC_MESSAGES`, and `LANG` that is
+//! set, so `LANG=de_DE.UTF-8` picks German. Anything unknown means English.
+//!
+//! Output meant for machines, such as JSON, CSV, and tab-separated lists,
+//! is never translated.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentArgs as MessageArgs;
+
+/// The environment variables naming the language, most specific first.
+const LANGUAGE_ENV_VARS: &[&str] = &["TASK_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// The languages there are catalogs for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Returns the language's tag, e.g. `de`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    fn catalog(self) -> &'static str {
+        match self {
+            Language::English => include_str!("i18n/en.ftl"),
+            Language::German => include_str!("i18n/de.ftl"),
+        }
+    }
+
+    /// Picks the language from the environment, English if none is set or
+    /// known.
+    pub fn detect() -> Self {
+        LANGUAGE_ENV_VARS
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Reads a language tag or POSIX locale such as `de`, `de-AT`, or
+    /// `de_DE.UTF-8`; `C` and `POSIX` mean English.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let primary = s
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        if primary == "c" || primary == "posix" {
+            return Ok(Language::English);
+        }
+        Language::ALL
+            .into_iter()
+            .find(|language| language.tag() == primary)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Language::ALL.iter().map(|l| l.tag()).collect();
+                format!("unknown language '{}'; expected {}", s, known.join(" or "))
+            })
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Language> for String {
+    fn from(language: Language) -> Self {
+        language.tag().to_string()
+    }
+}
+
+/// The language set with [`set_language`].
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// The catalogs, loaded on first use.
+static BUNDLES: OnceLock<Vec<(Language, FluentBundle<FluentResource>)>> = OnceLock::new();
+
+/// Sets the language messages are printed in for the rest of the process.
+///
+/// Only the first call has an effect; later calls are ignored.
+pub fn set_language(language: Language) {
+    debug!("Printing messages in {}.", language);
+    let _ = LANGUAGE.set(language);
+}
+
+/// Returns the language messages are printed in.
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_else(Language::detect)
+}
+
+fn bundles() -> &'static [(Language, FluentBundle<FluentResource>)] {
+    BUNDLES.get_or_init(|| {
+        Language::ALL
+            .into_iter()
+            .map(|language| {
+                let id: LanguageIdentifier = language.tag().parse().unwrap_or_default();
+                let mut bundle = FluentBundle::new_concurrent(vec![id]);
+                // The isolation marks around placeables would end up in terminals.
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(language.catalog().to_string())
+                    .unwrap_or_else(|(resource, errors)| {
+                        warn!("The {} catalog has errors: {:?}", language, errors);
+                        resource
+                    });
+                if let Err(errors) = bundle.add_resource(resource) {
+                    warn!("The {} catalog has duplicates: {:?}", language, errors);
+                }
+                (language, bundle)
+            })
+            .collect()
+    })
+}
+
+/// Returns the message `id` in the active language, filled in with `args`.
+///
+/// Use [`tr!`](crate::tr) rather than calling this directly. A message
+/// missing from every catalog comes out as its ID.
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    let active = language();
+    let bundles = bundles();
+    let ordered = bundles
+        .iter()
+        .filter(|(language, _)| *language == active)
+        .chain(
+            bundles
+                .iter()
+                .filter(|(language, _)| *language == Language::English),
+        );
+    for (language, bundle) in ordered {
+        let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            warn!("Message '{}' in {}: {:?}", id, language, errors);
+        }
+        return text.into_owned();
+    }
+    warn!("No message '{}' in any catalog.", id);
+    id.to_string()
+}
+
+/// Looks up a message in the active language, like `format!` with named
+/// arguments: `tr!("task-added", id = 3)`.
+///
+/// Arguments can be strings or numbers; numbers pick plural forms.
+#[macro_export]
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::app::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::app::i18n::MessageArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::app::i18n::message($id, Some(&args))
+    }};
+}
@@ -0,0 +1,619 @@
+# Von `tm` ausgegebene Meldungen, auf Deutsch.
+#
+# Fehlt hier eine Meldung aus en.ftl, wird sie auf Englisch ausgegeben.
+
+## Running commands
+
+error-no-dry-run = Dieser Befehl ändert mehr als die Aufgabendateien und kann daher nicht mit --dry-run ausgeführt werden.
+error-no-read-only = Dieser Befehl ändert mehr als die Aufgabendateien und kann daher nicht mit --read-only ausgeführt werden.
+nothing-added = Nichts hinzugefügt.
+daemon-running = Daemon läuft (Strg-C beendet ihn).
+warning-loading-fallback = Warnung: Aufgaben werden aus der Ausweichdatei { $fallback } geladen, weil { $path } beim letzten Speichern nicht geschrieben werden konnte.
+hint-lenient = Tipp: Mit --lenient werden die lesbaren Aufgaben geladen, `task doctor` findet den Schaden.
+task-annotated = Aufgabe { $id } kommentiert.
+task-duplicated = Aufgabe { $id } kopiert nach { $copies }
+task-merged = Aufgabe { $id } '{ $description }' in Aufgabe { $target } zusammengeführt
+error-read-only-changed = Nichts wurde gespeichert, weil --read-only angegeben wurde.
+error-no-remote = Dieser Befehl ändert mehr als die Aufgabendateien und kann daher nicht auf einer entfernten Datendatei laufen.
+error-remote-changed = Nichts wurde gespeichert, weil { $url } nur gelesen werden kann.
+error-saved-meanwhile = { $path } wurde inzwischen von einem anderen Prozess gespeichert, und { $reason }; nichts wurde gespeichert
+note-merged = Hinweis: { $path } wurde inzwischen von einem anderen Prozess gespeichert; seine Änderungen wurden übernommen.
+warning-saved-to-fallback = Warnung: { $path } konnte nicht geschrieben werden ({ $reason }). Die Änderungen wurden stattdessen in { $fallback } gespeichert.
+warning-fallback-until-writable = Sie werden von dort geladen, bis { $path } wieder beschreibbar ist.
+warning-skipped-unreadable = Warnung: { $count } unlesbare Aufgabe(n) in { $path } übersprungen:
+warning-remote-stale = Warnung: Zeige die früher geladene Kopie von { $url }, die veraltet sein kann ({ $reason }).
+warning-storage-not-fetched = Warnung: { $path } konnte nicht von { $url } aktualisiert werden ({ $reason }); die lokale Kopie wird verwendet.
+warning-storage-not-uploaded = Warnung: Ihre Änderungen wurden in { $path } gespeichert, aber nicht nach { $url } hochgeladen ({ $reason }). Führen Sie `sync` aus, um sie hochzuladen.
+session-interrupted = Unterbrochen; Ihre bisherigen Änderungen aus { $command } wurden in { $path } gesichert und beim nächsten Laden der Aufgaben übernommen.
+session-recovered = Die Änderungen der nach { $started } unterbrochenen Sitzung von { $command } wurden wiederhergestellt.
+warning-session-set-aside = Warnung: { $path } wurde geändert, nachdem eine unterbrochene Sitzung von { $command } begonnen hatte, daher wurden ihre Änderungen nicht übernommen; sie liegen in { $backup }.
+warning-limit = Warnung: { $breach }.
+limit-in-progress = { $count } Aufgaben sind in Arbeit, mehr als das Limit von { $limit }
+limit-due-per-day = { $count } Aufgaben sind am { $date } fällig, mehr als das Limit von { $limit }
+line-error = Zeile { $line }: { $error }
+original-backed-up = Die ursprüngliche Datei wurde nach { $path } gesichert.
+tasks-expired = { $count } Aufgabe(n) abgelaufen und abgebrochen:
+task-added = Aufgabe hinzugefügt: { $id }
+
+## Table columns
+
+column-assignee = Zuständig
+column-completed = Erledigt
+column-context = Kontext
+column-contexts = Kontexte
+column-created = Erstellt
+column-date = Datum
+column-description = Beschreibung
+column-due-after = Fällig nach
+column-due-date = Fälligkeit
+column-due-today = Heute fällig
+column-estimate = Schätzung
+column-goal = Ziel
+column-id = ID
+column-list = Liste
+column-overdue = Überfällig
+column-pending = Offen
+column-priority = Priorität
+column-progress = Fortschritt
+column-checklist = Checkliste
+column-project = Projekt
+column-query = Abfrage
+column-reminder = Erinnerung
+column-status = Status
+column-tags = Schlagwörter
+column-tasks-done = Erledigt
+column-template = Vorlage
+column-time = Zeit
+column-until = Bis
+column-total = Gesamt
+column-url = URL
+column-urgency = Dringlichkeit
+column-waiting-on = Wartet auf
+
+## Adding, picking, and editing
+
+no-tasks-to-add = Keine Aufgaben zum Hinzufügen.
+tasks-added = { $count } Aufgabe(n) hinzugefügt: { $ids }
+no-pending-to-pick = Keine offenen Aufgaben zur Auswahl.
+pick-prompt = auswählen>
+error-no-task-picked = Keine Aufgabe ausgewählt.
+error-no-pending-match = Keine offene Aufgabe passt zu '{ $text }'.
+error-several-pending-match = { $count } offene Aufgaben passen zu '{ $text }' ({ $ids }); gib stattdessen eine ID an.
+several-pending-match = { $count } offene Aufgaben passen zu '{ $text }':
+which-one = Welche? [1-{ $count }, leer zum Abbrechen]
+error-no-task-chosen = Keine Aufgabe gewählt.
+answer-with-number = Bitte mit einer Zahl von 1 bis { $count } antworten.
+task-unchanged = Aufgabe { $id } unverändert.
+task-updated = Aufgabe { $id } geändert: { $fields }.
+error-nothing-to-modify = Nichts zu ändern; gib --set, --add-tag oder --remove-tag an.
+no-tasks-match-query = Keine Aufgaben passen zur Abfrage.
+tasks-match = { $count } Aufgabe(n) passen:
+modify-needs-yes = Das ändert { $count } Aufgabe(n). Bestätige mit `task modify --yes`.
+confirm-modify = { $count } Aufgabe(n) ändern?
+nothing-changed = Nichts geändert.
+tasks-updated = { $updated } von { $count } Aufgabe(n) geändert.
+
+## Status and lists
+
+status-short = { $overdue } überfällig, { $due_today } heute fällig, { $pending } offen
+field-overdue = Überfällig
+field-due-today = Heute fällig
+field-pending = Offen
+no-tasks = Keine Aufgaben gefunden. Füge eine mit `task add <Beschreibung>` hinzu
+no-task-contexts = Keine offene Aufgabe hat einen Kontext. Gib einen mit `--context` oder `@kontext` in der Beschreibung an.
+header-project = Projekt: { $project }
+header-assignee = zuständig: { $assignee }
+header-filter = Filter: { $filter }
+header-query = Abfrage: { $query }
+header-shown = { $shown }/{ $count } Aufgaben angezeigt
+header-goal = Ziel: { $progress }
+no-tasks-match-filter = Keine Aufgaben passen zum Filter.
+no-tasks-in-project = Keine passenden Aufgaben in diesem Projekt.
+no-tasks-assigned = Keine passenden Aufgaben für { $assignee }.
+no-tasks-waiting = Keine Aufgaben warten auf ihr Startdatum.
+nothing-to-do-yet = Noch nichts zu tun; { $count } Aufgabe(n) beginnen später. `list --waiting` zeigt sie.
+all-tasks-completed = Alle Aufgaben erledigt! Gut gemacht. `list --all` zeigt sie.
+no-tasks-at-offset = Keine Aufgaben ab Position { $offset }; es passen nur { $count } Aufgaben.
+showing-range = Zeige { $first }-{ $last } von { $count } { $which ->
+        [pending] offenen Aufgaben
+       *[all] Aufgaben
+    }.
+late-minutes = { $minutes } Min. zu spät
+late-hours = { $hours } Std. zu spät
+late-days = { $days } Tg. zu spät
+marker-overdue = [ÜBERFÄLLIG]
+no-tasks-match-text = Keine Aufgaben passen zu '{ $text }'.
+
+## Statuses
+
+status-label = { $status ->
+        [backlog] BACKLOG
+        [todo] OFFEN
+        [in-progress] IN ARBEIT
+        [blocked] BLOCKIERT
+        [done] ERLEDIGT
+       *[cancelled] ABGEBROCHEN
+    }
+status-title = { $status ->
+        [backlog] Backlog
+        [todo] Offen
+        [in-progress] In Arbeit
+        [blocked] Blockiert
+        [done] Erledigt
+       *[cancelled] Abgebrochen
+    }
+
+## Templates, contexts, and lists
+
+template-saved = Vorlage '{ $name }' in { $path } gespeichert.
+error-no-template = Keine Vorlage namens '{ $name }'.
+template-removed = Vorlage '{ $name }' entfernt.
+no-templates = Keine Vorlagen gespeichert. Speichere eine mit `task template save <Name> <ID>`
+context-defined = Kontext '{ $name }' in { $path } festgelegt.
+error-no-context = Kein Kontext namens '{ $name }'. Lege ihn mit `task context define { $name } <Abfrage>` fest.
+context-active = Kontext '{ $name }' ist jetzt aktiv.
+no-context-active = Kein Kontext ist aktiv.
+context-deleted = Kontext '{ $name }' gelöscht.
+no-contexts = Keine Kontexte festgelegt. Lege einen mit `task context define <Name> <Abfrage>` fest
+no-lists = Keine Listen in { $dir }. Beginne eine mit `task --list <Name> add <Beschreibung>`
+error-already-in-list = Aufgabe { $id } ist schon in der Liste '{ $list }'.
+task-moved-to-list = Aufgabe { $id } als { $new_id } nach '{ $list }' verschoben.
+
+## Projects and completion
+
+error-no-tasks-in-project = Keine Aufgaben im Projekt '{ $name }' gefunden.
+field-project = Projekt
+field-completion = Fortschritt
+field-tasks = Aufgaben
+field-remaining = Verbleibend
+field-nearest-due = Nächste Fälligkeit
+project-completion = { $percent } % (nach Schätzungen gewichtet)
+project-tasks-done = { $done }/{ $total } erledigt
+project-unestimated = (+{ $count } ohne Schätzung)
+task-reopened = Aufgabe { $id } als offen markiert
+checklist-item-added = Punkt { $number } zur Checkliste von Aufgabe { $id } hinzugefügt.
+checklist-item-checked = „{ $text }“ abgehakt.
+checklist-item-unchecked = Haken bei „{ $text }“ entfernt.
+checklist-item-removed = „{ $text }“ von der Checkliste entfernt.
+task-completed = Aufgabe { $id } als erledigt markiert
+completion-banner = 🔥 { $streak } Tag(e) in Folge · +{ $points } Punkt(e), { $total } insgesamt
+completion-banner-ascii = { $streak } Tag(e) in Folge, +{ $points } Punkt(e), { $total } insgesamt
+next-occurrence-added = Nächste Wiederholung hinzugefügt: { $id }, fällig { $due }
+task-moved-to-status = Aufgabe { $id } nach { $status } verschoben
+nothing-to-do = Gerade gibt es nichts zu tun.
+random-pick = Wie wäre es mit Aufgabe { $id }: { $description }?
+done-log-empty = Am { $date } wurde nichts erledigt.
+
+## Timers and time sheets
+
+timer-stopped = Zeitmessung für Aufgabe { $id } gestoppt.
+timer-started = Zeitmessung für Aufgabe { $id } gestartet.
+timer-stopped-after = Zeitmessung für Aufgabe { $id } nach { $elapsed } gestoppt.
+no-timer-running = Keine Zeitmessung läuft.
+week-of = Woche vom { $date }
+no-time-tracked = Diese Woche wurde keine Zeit erfasst.
+weekday-mon = Mo
+weekday-tue = Di
+weekday-wed = Mi
+weekday-thu = Do
+weekday-fri = Fr
+weekday-sat = Sa
+weekday-sun = So
+
+## Recurrence, snoozing, notes, and attachments
+
+occurrence-skipped = Aufgabe { $id } übersprungen. Nächste Wiederholung fällig { $due }
+series-ended = Aufgabe { $id } war die letzte Wiederholung ihrer Serie und wurde abgebrochen.
+task-snoozed = Aufgabe { $id } zurückgestellt bis { $due } ({ $count }-mal zurückgestellt).
+error-editor-with-options = --editor übernimmt die Änderungen aus dem Editor; lass die anderen Optionen weg.
+task-heading = Aufgabe { $id }
+confirm-edit-again = Erneut bearbeiten?
+note-added = Notiz zu Aufgabe { $id } hinzugefügt.
+attached = { $target } an Aufgabe { $id } angehängt ({ $count } insgesamt).
+error-nothing-attached = Aufgabe { $id } hat keine URL und keine Anhänge.
+error-no-attachment = Aufgabe { $id } hat { $count } Anhang/Anhänge; Nummer { $number } gibt es nicht.
+opened = { $target } geöffnet.
+yanked-description = Beschreibung der Aufgabe ID { $id } in die Zwischenablage kopiert.
+yanked-url = URL der Aufgabe ID { $id } in die Zwischenablage kopiert.
+error-no-url = Aufgabe ID { $id } hat keine URL; setze eine mit `edit --url`.
+
+## Showing a task
+
+field-id = ID
+field-description = Beschreibung
+field-status = Status
+field-assignee = Zuständig
+field-url = URL
+field-progress = Fortschritt
+field-parent = Übergeordnet
+field-subtasks = Teilaufgaben
+field-goal = Ziel
+field-waiting-on = Wartet auf
+field-tags = Schlagwörter
+field-contexts = Kontexte
+field-priority = Priorität
+field-due-date = Fällig
+field-snoozed = Zurückgestellt
+field-estimate = Schätzung
+field-remind = Erinnerung
+field-reminders = Erinnerungen
+field-tracked = Erfasst
+field-recurrence = Wiederholung
+field-starts = Beginnt
+field-expires = Verfällt
+field-created = Erstellt
+field-completed = Erledigt
+field-changed = Geändert
+stamp-by = { $stamp } von { $user }
+snoozed-times = { $count }-mal
+before-due = { $offset } vor Fälligkeit
+tracked-running = { $total } (läuft)
+heading-attached = Angehängt:
+heading-checklist = Checkliste ({ $done }/{ $total }):
+heading-history = Verlauf:
+notes-none = Notizen:      keine
+heading-notes = Notizen:
+
+## Removing and the trash
+
+would-trash = Aufgabe { $id } würde in den Papierkorb verschoben.
+confirm-remove = Aufgabe { $id } '{ $description }' entfernen?
+nothing-removed = Nichts entfernt.
+task-trashed = Aufgabe { $id } in den Papierkorb verschoben. `task trash restore { $id }` macht das rückgängig.
+trash-empty = Der Papierkorb ist leer.
+column-removed = Entfernt
+column-purged-after = Gelöscht nach
+task-restored-as = Die ID { $id } ist vergeben; als Aufgabe { $new_id } wiederhergestellt.
+task-restored = Aufgabe { $id } wiederhergestellt.
+trash-already-empty = Der Papierkorb ist schon leer.
+would-delete-permanently = { $count } Aufgabe(n) würden endgültig gelöscht.
+empty-trash-needs-yes = Das löscht { $count } Aufgabe(n) endgültig. Bestätige mit `task trash empty --yes`.
+confirm-empty-trash = { $count } Aufgabe(n) endgültig löschen?
+nothing-deleted = Nichts gelöscht.
+deleted-permanently = { $count } Aufgabe(n) endgültig gelöscht.
+
+## Statistics and calendars
+
+stats-tasks = Aufgaben:   { $total } insgesamt, { $pending } offen, { $completed } erledigt, { $cancelled } abgebrochen
+stats-overdue = Überfällig: { $count }
+stats-weekly-goal = Wochenziel: { $progress }{ $met ->
+        [yes] {" "}(erreicht)
+       *[no] {""}
+    }
+stats-average = Durchschn. Zeit bis erledigt: { $time }
+stats-streak = Serie:      { $current } Tag(e) in Folge, längste { $longest }
+stats-points = Punkte:     { $today } heute, { $week } diese Woche, { $total } insgesamt
+stats-per-day = Erledigt pro Tag (letzte { $days } Tage):
+stats-per-week = Erledigt pro Woche (letzte { $weeks } Wochen):
+stats-by-project = Nach Projekt:
+stats-by-tag = Nach Schlagwort:
+none = keine
+stats-breakdown = { $pending } offen { $completed } erledigt
+error-no-agenda-recipient = Niemand, an den die Agenda gehen kann; gib --to an oder setze `to` unter [agenda] in der Konfigurationsdatei.
+agenda-nothing-due = Nichts ist überfällig oder heute fällig; keine E-Mail gesendet.
+agenda-emailed = Agenda an { $to } gesendet.
+calendar-weekdays = Mo    Di    Mi    Do    Fr    Sa    So
+due-this-month = { $count } Aufgabe(n) diesen Monat fällig. `calendar --agenda` listet sie auf.
+nothing-due-in-month = Im { $month } ist nichts fällig.
+
+## Burndown
+
+burndown-open = Offen:     { $sparkline }
+burndown-completed = Erledigt:  { $sparkline }
+column-week-of = Woche vom
+column-open = Offen
+
+## TODO files, reminders, and escalation
+
+watching = Beobachte { $path } (Strg-C beendet).
+todo-synced = { $kind ->
+        [added] Hinzugefügt
+        [completed] Erledigt
+        [reopened] Wieder geöffnet
+       *[renamed] Umbenannt
+    }: { $ids }
+nothing-to-remind = Keine Erinnerungen fällig.
+would-remind = Würde an Aufgabe { $id } erinnern: { $body } ({ $when }).
+reminded = An Aufgabe { $id } erinnert ({ $when }).
+reminders-set = Erinnerungen für Aufgabe { $id }: { $reminders }.
+reminders-cleared = Erinnerungen für Aufgabe { $id } entfernt.
+no-upcoming-reminders = Keine anstehenden Erinnerungen.
+no-escalation-levels = Keine Eskalationsstufen eingerichtet.
+escalation-title = Überfällige Aufgabe { $id }: { $description }
+escalation-body = Fällig { $due }, nach { $hours } Std. noch offen (Eskalationsstufe { $level }).
+would-notify = Würde zu Aufgabe { $id } über { $channel } benachrichtigen (Stufe { $level }).
+notified = Zu Aufgabe { $id } über { $channel } benachrichtigt (Stufe { $level }).
+
+## Syncing
+
+sync-committed = Lokale Änderungen eingecheckt.
+sync-pulled = Änderungen von { $branch } geholt.
+sync-merged = Änderungen von einem anderen Rechner zusammengeführt.
+sync-no-remote = Kein Sync-Remote eingerichtet; setze `remote` unter [sync] in der Konfigurationsdatei, um zu pushen.
+sync-pushed = Nach { $remote } gepusht ({ $branch }).
+sync-nothing-to-push = Nichts zu pushen.
+caldav-synced = Mit { $url } abgeglichen: { $pulled } lokale Änderungen ({ $removed } in den Papierkorb), { $pushed } hochgeladen, { $deleted } auf dem Server gelöscht.
+storage-synced = Mit { $url } abgeglichen: { $pulled } lokale Änderung(en).
+storage-uploaded = { $path } nach { $url } hochgeladen.
+error-interactive-no-terminal = --interactive braucht ein Terminal für Rückfragen.
+value-unset = (nicht gesetzt)
+conflict-heading = Aufgabe { $id } wurde auf beiden Seiten geändert: { $description }
+conflict-was = vorher
+conflict-local = lokal
+conflict-remote = remote
+conflict-prompt = [l]okal oder [r]emote behalten, oder [e]ditieren?
+conflict-new-value = Neuer Wert (JSON oder Text):
+conflict-invalid = Das ist kein gültiger Wert für { $field }.
+
+## Comparing and dry runs
+
+error-does-not-exist = { $path } gibt es nicht.
+no-differences = Keine Unterschiede.
+diff-added = Hinzugefügt ({ $count }):
+diff-removed = Entfernt ({ $count }):
+diff-changed = Geändert ({ $count }):
+dry-run-unchanged = Probelauf: { $path } bliebe unverändert.
+dry-run-write = Probelauf: { $path } würde geschrieben.
+
+## The audit journal
+
+journal-intact = Journal unversehrt: { $entries } Einträge, { $signed } mit gültiger Signatur.
+journal-entry-problem = Eintrag { $seq }: { $problem }
+error-journal-tampered = { $path } wurde manipuliert
+journal-empty = Das Journal ist leer.
+log-from-timestamps = Es wird kein Journal und kein Ereignisprotokoll geführt, daher wird nur gezeigt, wann Aufgaben angelegt und erledigt wurden.
+log-empty = In dieser Zeit ist mit den Aufgaben nichts geschehen.
+journal-signed = signiert
+error-no-key-directory = kein Konfigurationsverzeichnis für die Schlüsseldatei
+error-key-exists = { $path } gibt es schon; --force ersetzt sie
+key-written = Signaturschlüssel nach { $path } geschrieben.
+public-key = Öffentlicher Schlüssel: { $key }
+key-hint = Setze `sign = true` unter [audit], um neue Einträge zu signieren, und bewahre den öffentlichen Schlüssel zum Prüfen auf.
+
+## Encryption and checkups
+
+already-encrypted = { $path } ist schon { $action ->
+        [encrypted] verschlüsselt
+       *[decrypted] entschlüsselt
+    }.
+now-encrypted = { $path } ist jetzt { $action ->
+        [encrypted] verschlüsselt
+       *[decrypted] entschlüsselt
+    }.
+nothing-to-check = { $path } gibt es noch nicht; nichts zu prüfen.
+no-problems = Keine Probleme in { $path } gefunden.
+finding-fixed = behoben: { $repair }
+finding-fixable = --fix würde: { $repair }
+repaired = { $repaired } von { $count } Problem(en) behoben.
+doctor-fix-hint = `task doctor --fix` behebt { $count } davon
+error-problems-left = { $count } Problem(e) in { $path } übrig
+maintenance-data-file = Datendatei: { $path } ({ $size })
+maintenance-trash = Papierkorb: { $count } Aufgabe(n), { $expired } über die Aufbewahrungszeit ({ $size })
+maintenance-journal = Prüfprotokoll: { $count } Einträge ({ $size })
+maintenance-events = Ereignisprotokoll: { $count } Ereignis(se) seit dem letzten Schnappschuss, { $size } Verlauf
+maintenance-backups = Sicherungen: { $count } ({ $size })
+maintenance-total = Gesamt: { $size }
+maintenance-nothing = Nichts zu verdichten.
+maintenance-compacted = { $events } Ereignis(se) zusammengefasst, { $trash } Aufgabe(n) aus dem Papierkorb gelöscht und { $backups } Sicherung(en) entfernt; die Datendatei hat jetzt { $size } statt { $before }.
+error-event-log-format = { $path } ist ein Ereignisprotokoll und wird immer als JSON-Zeilen gespeichert.
+already-stored-as = { $path } ist schon als { $format } gespeichert.
+now-stored-as = { $path } ist jetzt als { $format } gespeichert.
+
+## Renumbering, triage, and reviews
+
+ids-sequential = Die IDs sind schon fortlaufend. Nichts umzunummerieren.
+would-renumber = Probelauf: { $count } Aufgabe(n) würden umnummeriert.
+renumbered = { $count } Aufgabe(n) umnummeriert.
+inbox-empty = Der Eingang ist leer. Nichts einzusortieren.
+inbox-count = { $count } Aufgabe(n) im Eingang.
+triage-project-prompt = Projekt (Enter überspringt, 'd' löscht, 'q' beendet):
+task-removed = Aufgabe { $id } entfernt.
+triage-due-prompt = Fälligkeit JJJJ-MM-TT, 'tomorrow', '+3d', ... (Enter für keine):
+triage-priority-prompt = Priorität low/medium/high (Enter für keine):
+priority-choices = Die Priorität muss low, medium oder high sein.
+triage-finished = Einsortieren beendet: { $organized } sortiert, { $deleted } gelöscht, { $remaining } im Eingang verblieben.
+nothing-to-review = Nichts zu überprüfen. Alles ist eingeplant und aktuell.
+review-count = { $count } Aufgabe(n) zu überprüfen.
+review-prompt = [c] erledigen, [r] verschieben, [d] löschen, [k] behalten oder [q] beenden?
+task-now-due = Aufgabe { $id } ist jetzt fällig am { $due }.
+review-finished = Überprüfung beendet: { $completed } erledigt, { $rescheduled } verschoben, { $deleted } gelöscht.
+no-stale-tasks = Keine offene Aufgabe ist seit über { $age } unverändert.
+stale-count = { $count } Aufgabe(n) seit über { $age } unverändert:
+stale-age = seit { $days } Tagen unverändert
+stale-bulk-prompt = [r] alle verschieben, [a] alle archivieren, [d] alle löschen, [o] einzeln durchgehen oder [q] beenden?
+stale-prompt = [r] verschieben, [a] archivieren, [d] löschen, [k] behalten oder [q] beenden?
+task-archived = Aufgabe { $id } als abgebrochen archiviert.
+stale-finished = Veraltete Aufgaben: { $rescheduled } verschoben, { $archived } archiviert, { $deleted } gelöscht.
+due-date-prompt = Neue Fälligkeit JJJJ-MM-TT, 'tomorrow', '+3d', ...:
+
+## Prompts
+
+confirm-choices = [j/N]
+confirm-yes = j ja
+
+## Exporting, importing, and clearing
+
+error-todoist-export-options = --output und --anonymize gelten nicht für Todoist-Exporte.
+todoist-exported = Nach Todoist exportiert: { $added } angelegt, { $updated } geändert, { $completed } geschlossen.
+sections-updated = { $count } Aufgabenabschnitt(e) in { $path } aktualisiert.
+tasks-exported = Aufgaben nach { $path } exportiert.
+error-todoist-import-file = Todoist-Importe kommen direkt von Todoist und brauchen keine Datei.
+error-taskwarrior-import-file = Gib die von `task export` geschriebene Datei an, z. B. `import --format taskwarrior export.json`.
+error-export-import-file = Gib die von `export` geschriebene Datei an, z. B. `import --from json tasks.json`.
+error-merge-source = --merge gilt nur für JSON- und CSV-Importe.
+imported = Aus { $source } importiert: { $added } hinzugefügt, { $updated } geändert.
+import-completed = { $count } in { $source } nicht mehr offene Aufgabe(n) als erledigt markiert.
+github-issue-closed = GitHub-Issue { $issue } geschlossen.
+not-preserved = Nicht übernommen: { $fields }.
+wrote = { $path } geschrieben
+scanned = { $files } Datei(en) durchsucht: { $added } Aufgabe(n) hinzugefügt, { $moved } in eine andere Zeile verschoben.
+would-clear = Alle { $count } Aufgaben würden in den Papierkorb verschoben.
+clear-needs-yes = Das entfernt ALLE Aufgaben. Bestätige mit `task clear --yes`.
+confirm-clear = ALLE { $count } Aufgaben entfernen?
+cleared = { $count } Aufgaben entfernt. Sie bleiben vorerst im Papierkorb.
+
+## Burndown, continued
+
+backlog-steady = Der Rückstand blieb gleich.
+backlog-shrank = Der Rückstand ist in { $weeks } Wochen um { $count } Aufgabe(n) geschrumpft.
+backlog-grew = Der Rückstand ist in { $weeks } Wochen um { $count } Aufgabe(n) gewachsen.
+forecast-pace = { $open } Aufgabe(n) offen. In den letzten { $weeks } Wochen kamen pro Woche { $added } hinzu und { $completed } wurden erledigt.
+forecast-clear-by = In diesem Tempo ist der Rückstand in der Woche vom { $week } abgearbeitet.
+forecast-not-shrinking = In diesem Tempo schrumpft der Rückstand nicht.
+forecast-empty = Der Rückstand ist leer.
+
+## CalDAV
+
+error-no-caldav = Kein CalDAV-Server eingerichtet; füge der Konfigurationsdatei einen Abschnitt [sync.caldav] hinzu.
+
+## Failures
+
+error = Fehler: { $error }
+error-io = Dateisystemfehler: { $error }
+error-toml-parse = TOML-Daten konnten nicht gelesen werden: { $error }
+error-data-file = TOML-Daten in { $path } konnten nicht gelesen werden: { $error }
+error-toml-serialize = Daten konnten nicht als TOML geschrieben werden: { $error }
+error-msgpack-decode = MessagePack-Daten konnten nicht gelesen werden: { $error }
+error-msgpack-encode = Daten konnten nicht als MessagePack geschrieben werden: { $error }
+error-json = Daten konnten nicht als JSON geschrieben werden: { $error }
+error-chart = Das Diagramm konnte nicht gezeichnet werden: { $error }
+error-notify = Die Benachrichtigung konnte nicht gesendet werden: { $error }
+error-clipboard = Fehler der Zwischenablage: { $error }
+error-encryption = Fehler bei der Verschlüsselung: { $error }
+error-audit = Fehler im Prüfprotokoll: { $error }
+error-doctor = Prüfung der Datendatei fehlgeschlagen: { $error }
+error-event-log = Fehler im Ereignisprotokoll: { $error }
+error-conflict = Widersprüchliche Änderungen: { $error }
+error-migration = Die Aufgabenliste konnte nicht aktualisiert werden: { $error }
+error-hook = Hook fehlgeschlagen: { $error }
+error-sync = Synchronisierung fehlgeschlagen: { $error }
+error-search = Fehler im Suchindex: { $error }
+error-todoist = Anfrage an Todoist fehlgeschlagen: { $error }
+error-github = Anfrage an GitHub fehlgeschlagen: { $error }
+error-jira = Anfrage an Jira fehlgeschlagen: { $error }
+error-remote = Die entfernte Datendatei konnte nicht geladen werden: { $error }
+error-config = Ungültige Konfigurationsdatei { $error }
+error-limit = Über einem Limit: { $error }; nichts wurde gespeichert
+error-task-not-found = Aufgabe mit ID '{ $id }' nicht gefunden.
+error-invalid-argument = Ungültiges Argument: { $error }
+error-unexpected = Ein unerwarteter Fehler ist aufgetreten: { $error }
+did-you-mean = Meintest du { $tasks }?
+did-you-mean-list = { $rest } oder { $last }
+
+## Invalid arguments
+
+error-no-command = Kein Befehl angegeben.
+error-unknown-command = Unbekannter Befehl '{ $name }', und im PATH gibt es kein { $plugin }.
+did-you-mean-command = Meintest du { $command }?
+error-could-not-run = { $program } konnte nicht ausgeführt werden: { $error }
+error-editor-failed = { $editor } endete mit { $status }; nichts wurde geändert
+error-empty-description = Die Beschreibung darf nicht leer sein.
+error-unknown-priority = Unbekannte Priorität '{ $priority }'; erwartet wird low, medium oder high.
+error-recurrence-no-occurrence = Die Wiederholung fällt auf keinen Tag am oder nach dem Fälligkeitsdatum.
+error-merge-into-itself = Aufgabe { $id } kann nicht mit sich selbst zusammengeführt werden.
+error-subtask-cycle = Aufgabe { $id } kann keine Unteraufgabe von Aufgabe { $parent } sein, die zu ihr gehört.
+error-progress-has-subtasks = Aufgabe { $id } hat Unteraufgaben; ihr Fortschritt ist der erledigte Anteil davon.
+error-due-out-of-range = Das Fälligkeitsdatum liegt außerhalb des gültigen Bereichs.
+error-not-pending = Aufgabe { $id } ist nicht offen.
+error-snooze-out-of-range = Das verschobene Fälligkeitsdatum liegt außerhalb des gültigen Bereichs.
+error-snooze-not-later = Aufgabe { $id } muss auf ein späteres Datum als { $date } verschoben werden.
+error-reminder-out-of-range = Die Erinnerung liegt außerhalb des gültigen Bereichs.
+error-no-due-to-remind-before = Aufgabe { $id } hat kein Fälligkeitsdatum, vor dem erinnert werden könnte.
+error-reminder-in-past = Die Erinnerung '{ $reminder }' liegt in der Vergangenheit.
+error-not-recurring = Aufgabe { $id } wiederholt sich nicht.
+error-no-checklist = Aufgabe { $id } hat keine Checkliste.
+error-no-checklist-item = Aufgabe { $id } hat { $count } Checklistenpunkt(e); einen { $number }. gibt es nicht.
+error-timer-running = Für Aufgabe { $id } läuft bereits ein Timer.
+error-snippet-empty = Der Ausschnitt ist leer; füge den Text ein, den `share` ausgegeben hat.
+error-snippet-no-description = Die erste Zeile des Ausschnitts muss die Beschreibung sein.
+error-snippet-bad-line = '{ $line }' ist nicht lesbar; Felder werden als `Name: Wert` geschrieben.
+error-snippet-unknown-field = Unbekanntes Feld '{ $field }' im Ausschnitt.
+error-csv-no-description = Die CSV-Datei hat keine Spalte 'description'.
+error-csv-invalid-field = Zeile { $row } der CSV-Datei hat einen ungültigen Wert für { $column }: '{ $value }'
+error-csv-unterminated-quote = Die CSV-Datei endet in einem Feld in Anführungszeichen.
+error-import-no-description = Die importierte Aufgabe { $uuid } hat keine Beschreibung.
+
+## Dates
+
+date-today = heute
+date-tomorrow = morgen
+date-yesterday = gestern
+date-in = { $unit ->
+        [day] in { $count } Tagen
+        [week] { $count ->
+            [one] in 1 Woche
+           *[other] in { $count } Wochen
+        }
+       *[month] { $count ->
+            [one] in 1 Monat
+           *[other] in { $count } Monaten
+        }
+    }
+date-ago = { $unit ->
+        [day] { $count ->
+            [one] vor 1 Tag
+           *[other] vor { $count } Tagen
+        }
+        [week] { $count ->
+            [one] vor 1 Woche
+           *[other] vor { $count } Wochen
+        }
+       *[month] { $count ->
+            [one] vor 1 Monat
+           *[other] vor { $count } Monaten
+        }
+    }
+date-overdue = { $unit ->
+        [day] { $count ->
+            [one] 1 Tag überfällig
+           *[other] { $count } Tage überfällig
+        }
+        [week] { $count ->
+            [one] 1 Woche überfällig
+           *[other] { $count } Wochen überfällig
+        }
+       *[month] { $count ->
+            [one] 1 Monat überfällig
+           *[other] { $count } Monate überfällig
+        }
+    }
+
+## Progress
+
+progress-set = Aufgabe { $id } ist zu { $percent } % erledigt.
+
+## Plan
+
+plan-nothing-due = Am { $date } ist nichts fällig.
+plan-heading = Plan für { $date }: { $planned } von { $capacity }
+plan-unestimated = { $count ->
+        [one] 1 Aufgabe hat keine Schätzung und wird nicht mitgezählt.
+       *[other] { $count } Aufgaben haben keine Schätzung und werden nicht mitgezählt.
+    }
+plan-overloaded = Warnung: Das sind { $over } mehr, als an einen Tag passen ({ $capacity }).
+
+## Goals
+
+goal-added = Ziel hinzugefügt: ID { $id }
+goal-removed = Ziel { $id } entfernt{ $count ->
+        [0] .
+        [one] ; 1 Aufgabe ist nicht mehr damit verknüpft.
+       *[other] ; { $count } Aufgaben sind nicht mehr damit verknüpft.
+    }
+no-goals = Noch keine Ziele. Füge eins mit `goal add "Version 1.0" --due 2025-09-01` hinzu.
+
+## Duplicates
+
+warning-duplicates = { $count ->
+        [one] Warnung: Eine offene Aufgabe lautet schon so ähnlich:
+       *[other] Warnung: { $count } offene Aufgaben lauten schon so ähnlich:
+    }
+confirm-add-duplicate = Trotzdem hinzufügen?
+error-duplicate = Aufgabe { $id } '{ $description }' lautet schon so ähnlich; nichts hinzugefügt.
+
+## Waiting
+
+task-waiting = Aufgabe { $id } wartet.
+task-waiting-until = Aufgabe { $id } wartet bis { $date }.
+task-not-waiting = Aufgabe { $id } wartet nicht mehr.
+no-followups = Nichts wartet auf jemanden.
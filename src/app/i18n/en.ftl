@@ -0,0 +1,620 @@
+# Messages printed by `tm`, in English.
+#
+# The other catalogs translate these messages under the same IDs; a message
+# missing there is printed in English. See src/app/i18n.rs.
+
+## Running commands
+
+error-no-dry-run = This command changes more than the task files, so it can't be run with --dry-run.
+error-no-read-only = This command changes more than the task files, so it can't be run with --read-only.
+nothing-added = Nothing added.
+daemon-running = Daemon running (press Ctrl-C to stop).
+warning-loading-fallback = Warning: loading tasks from fallback file { $fallback }, because the last save could not write { $path }.
+hint-lenient = Hint: run with --lenient to load the tasks that can be read, or `task doctor` to find the damage.
+task-annotated = Annotated task ID { $id }.
+task-duplicated = Task ID { $id } copied to ID { $copies }
+task-merged = Task ID { $id } '{ $description }' merged into ID { $target }
+error-read-only-changed = Nothing was saved, because --read-only was given.
+error-no-remote = This command changes more than the task files, so it can't be run on a remote data file.
+error-remote-changed = Nothing was saved, because { $url } is read only.
+error-saved-meanwhile = { $path } was saved by another process meanwhile, and { $reason }; nothing was saved
+note-merged = Note: { $path } was saved by another process meanwhile; its changes were merged.
+warning-saved-to-fallback = Warning: could not write { $path } ({ $reason }). Your changes were saved to { $fallback } instead.
+warning-fallback-until-writable = They are picked up from there until { $path } is writable again.
+warning-skipped-unreadable = Warning: skipped { $count } unreadable task(s) in { $path }:
+warning-remote-stale = Warning: showing the copy of { $url } fetched before, which may be out of date ({ $reason }).
+warning-storage-not-fetched = Warning: could not refresh { $path } from { $url } ({ $reason }); using the local copy.
+warning-storage-not-uploaded = Warning: your changes were saved to { $path } but not uploaded to { $url } ({ $reason }). Run `sync` to upload them.
+session-interrupted = Interrupted; your { $command } edits so far were saved to { $path } and will be applied the next time the tasks are loaded.
+session-recovered = Recovered the edits of the { $command } session interrupted after { $started }.
+warning-session-set-aside = Warning: { $path } changed after an interrupted { $command } session began, so its edits were not applied; they are kept in { $backup }.
+warning-limit = Warning: { $breach }.
+limit-in-progress = { $count } tasks are in progress, over the limit of { $limit }
+limit-due-per-day = { $count } tasks are due on { $date }, over the limit of { $limit }
+line-error = line { $line }: { $error }
+original-backed-up = The original file was backed up to { $path }.
+tasks-expired = { $count } task(s) expired and were cancelled:
+task-added = Task added: ID { $id }
+
+## Table columns
+
+column-assignee = Assignee
+column-completed = Completed
+column-context = Context
+column-contexts = Contexts
+column-created = Created
+column-date = Date
+column-description = Description
+column-due-after = Due After
+column-due-date = Due Date
+column-due-today = Due today
+column-estimate = Estimate
+column-goal = Goal
+column-id = ID
+column-list = List
+column-overdue = Overdue
+column-pending = Pending
+column-priority = Priority
+column-progress = Progress
+column-checklist = Checklist
+column-project = Project
+column-query = Query
+column-reminder = Reminder
+column-status = Status
+column-tags = Tags
+column-tasks-done = Done
+column-template = Template
+column-time = Time
+column-until = Until
+column-total = Total
+column-url = URL
+column-urgency = Urgency
+column-waiting-on = Waiting On
+
+## Adding, picking, and editing
+
+no-tasks-to-add = No tasks to add.
+tasks-added = { $count } task(s) added: IDs { $ids }
+no-pending-to-pick = No pending tasks to pick from.
+pick-prompt = pick> 
+error-no-task-picked = No task picked.
+error-no-pending-match = No pending task matches '{ $text }'.
+error-several-pending-match = { $count } pending tasks match '{ $text }' (IDs { $ids }); give an ID instead.
+several-pending-match = { $count } pending tasks match '{ $text }':
+which-one = Which one? [1-{ $count }, empty to cancel]
+error-no-task-chosen = No task chosen.
+answer-with-number = Please answer with a number from 1 to { $count }.
+task-unchanged = Task ID { $id } unchanged.
+task-updated = Task ID { $id } updated: { $fields }.
+error-nothing-to-modify = Nothing to change; give --set, --add-tag, or --remove-tag.
+no-tasks-match-query = No tasks match the query.
+tasks-match = { $count } task(s) match:
+modify-needs-yes = This changes { $count } task(s). Use `task modify --yes` to confirm.
+confirm-modify = Change { $count } task(s)?
+nothing-changed = Nothing changed.
+tasks-updated = Updated { $updated } of { $count } task(s).
+
+## Status and lists
+
+status-short = { $overdue } overdue, { $due_today } due today, { $pending } pending
+field-overdue = Overdue
+field-due-today = Due today
+field-pending = Pending
+no-tasks = No tasks found. Add one with `task add <description>`
+no-task-contexts = No pending task has a context. Give one with `--context` or `@context` in the description.
+header-project = project: { $project }
+header-assignee = assignee: { $assignee }
+header-filter = filter: { $filter }
+header-query = query: { $query }
+header-shown = { $shown }/{ $count } tasks shown
+header-goal = goal: { $progress }
+no-tasks-match-filter = No tasks match the filter.
+no-tasks-in-project = No matching tasks in this project.
+no-tasks-assigned = No matching tasks assigned to { $assignee }.
+no-tasks-waiting = No tasks are waiting for their start date.
+nothing-to-do-yet = Nothing to do yet; { $count } task(s) start later. Use `list --waiting` to see them.
+all-tasks-completed = All tasks completed! Good job. Use `list --all` to see them.
+no-tasks-at-offset = No tasks at offset { $offset }; there are only { $count } matching tasks.
+showing-range = Showing { $first }-{ $last } of { $count } { $which ->
+        [pending] pending tasks
+       *[all] tasks
+    }.
+late-minutes = { $minutes }min late
+late-hours = { $hours }h late
+late-days = { $days }d late
+marker-overdue = [OVERDUE]
+no-tasks-match-text = No tasks match '{ $text }'.
+
+## Statuses
+
+status-label = { $status ->
+        [backlog] BACKLOG
+        [todo] PENDING
+        [in-progress] IN PROGRESS
+        [blocked] BLOCKED
+        [done] DONE
+       *[cancelled] CANCELLED
+    }
+status-title = { $status ->
+        [backlog] Backlog
+        [todo] Todo
+        [in-progress] In progress
+        [blocked] Blocked
+        [done] Done
+       *[cancelled] Cancelled
+    }
+
+## Templates, contexts, and lists
+
+template-saved = Saved template '{ $name }' in { $path }.
+error-no-template = No template named '{ $name }'.
+template-removed = Removed template '{ $name }'.
+no-templates = No templates saved. Save one with `task template save <name> <id>`
+context-defined = Defined context '{ $name }' in { $path }.
+error-no-context = No context named '{ $name }'. Define it with `task context define { $name } <query>`.
+context-active = Context '{ $name }' is now active.
+no-context-active = No context is active.
+context-deleted = Deleted context '{ $name }'.
+no-contexts = No contexts defined. Define one with `task context define <name> <query>`
+no-lists = No lists in { $dir }. Start one with `task --list <name> add <description>`
+error-already-in-list = Task { $id } is already in the list '{ $list }'.
+task-moved-to-list = Task ID { $id } moved to '{ $list }' as ID { $new_id }.
+
+## Projects and completion
+
+error-no-tasks-in-project = No tasks found in project '{ $name }'.
+field-project = Project
+field-completion = Completion
+field-tasks = Tasks
+field-remaining = Remaining
+field-nearest-due = Nearest due
+project-completion = { $percent }% (weighted by estimates)
+project-tasks-done = { $done }/{ $total } done
+project-unestimated = (+{ $count } unestimated)
+task-reopened = Task ID { $id } marked as incomplete
+checklist-item-added = Added item { $number } to the checklist of task ID { $id }.
+checklist-item-checked = Checked off "{ $text }".
+checklist-item-unchecked = Unchecked "{ $text }".
+checklist-item-removed = Removed "{ $text }" from the checklist.
+task-completed = Task ID { $id } marked as completed
+completion-banner = 🔥 { $streak }-day streak · +{ $points } point(s), { $total } in all
+completion-banner-ascii = { $streak }-day streak, +{ $points } point(s), { $total } in all
+next-occurrence-added = Next occurrence added: ID { $id } due { $due }
+task-moved-to-status = Task ID { $id } moved to { $status }
+nothing-to-do = Nothing to do right now.
+random-pick = How about task { $id }: { $description }?
+done-log-empty = Nothing was completed on { $date }.
+
+## Timers and time sheets
+
+timer-stopped = Stopped the timer on task ID { $id }.
+timer-started = Started the timer on task ID { $id }.
+timer-stopped-after = Stopped the timer on task ID { $id } after { $elapsed }.
+no-timer-running = No timer is running.
+week-of = Week of { $date }
+no-time-tracked = No time tracked this week.
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+
+## Recurrence, snoozing, notes, and attachments
+
+occurrence-skipped = Task ID { $id } skipped. Next occurrence due { $due }
+series-ended = Task ID { $id } was the last occurrence of its series and has been cancelled.
+task-snoozed = Task ID { $id } snoozed until { $due } (snoozed { $count } time(s)).
+error-editor-with-options = --editor takes the changes from the editor; leave out the other options.
+task-heading = Task ID { $id }
+confirm-edit-again = Edit again?
+note-added = Note added to task ID { $id }.
+attached = Attached { $target } to task ID { $id } ({ $count } in all).
+error-nothing-attached = Task ID { $id } has no URL and nothing attached.
+error-no-attachment = Task ID { $id } has { $count } attachment(s); there is no { $number }.
+opened = Opened { $target }.
+yanked-description = Copied the description of task ID { $id } to the clipboard.
+yanked-url = Copied the URL of task ID { $id } to the clipboard.
+error-no-url = Task ID { $id } has no URL; set one with `edit --url`.
+
+## Showing a task
+
+field-id = ID
+field-description = Description
+field-status = Status
+field-assignee = Assignee
+field-url = URL
+field-progress = Progress
+field-parent = Parent
+field-subtasks = Subtasks
+field-goal = Goal
+field-waiting-on = Waiting on
+field-tags = Tags
+field-contexts = Contexts
+field-priority = Priority
+field-due-date = Due Date
+field-snoozed = Snoozed
+field-estimate = Estimate
+field-remind = Remind
+field-reminders = Reminders
+field-tracked = Tracked
+field-recurrence = Recurrence
+field-starts = Starts
+field-expires = Expires
+field-created = Created
+field-completed = Completed
+field-changed = Changed
+stamp-by = { $stamp } by { $user }
+snoozed-times = { $count } time(s)
+before-due = { $offset } before due
+tracked-running = { $total } (running)
+heading-attached = Attached:
+heading-checklist = Checklist ({ $done }/{ $total }):
+heading-history = History:
+notes-none = Notes:       none
+heading-notes = Notes:
+
+## Removing and the trash
+
+would-trash = Would move task ID { $id } to the trash.
+confirm-remove = Remove task { $id } '{ $description }'?
+nothing-removed = Nothing removed.
+task-trashed = Task ID { $id } moved to the trash. Use `task trash restore { $id }` to undo.
+trash-empty = The trash is empty.
+column-removed = Removed
+column-purged-after = Purged After
+task-restored-as = Task ID { $id } is taken; restored as task ID { $new_id }.
+task-restored = Task ID { $id } restored.
+trash-already-empty = The trash is already empty.
+would-delete-permanently = Would permanently delete { $count } task(s).
+empty-trash-needs-yes = This permanently deletes { $count } task(s). Use `task trash empty --yes` to confirm.
+confirm-empty-trash = Permanently delete { $count } task(s)?
+nothing-deleted = Nothing deleted.
+deleted-permanently = Permanently deleted { $count } task(s).
+
+## Statistics and calendars
+
+stats-tasks = Tasks:      { $total } total, { $pending } pending, { $completed } completed, { $cancelled } cancelled
+stats-overdue = Overdue:    { $count }
+stats-weekly-goal = Weekly goal: { $progress }{ $met ->
+        [yes] {" "}(met)
+       *[no] {""}
+    }
+stats-average = Avg. time to complete: { $time }
+stats-streak = Streak:     { $current } day(s) in a row, longest { $longest }
+stats-points = Points:     { $today } today, { $week } this week, { $total } in all
+stats-per-day = Completed per day (last { $days } days):
+stats-per-week = Completed per week (last { $weeks } weeks):
+stats-by-project = By project:
+stats-by-tag = By tag:
+none = none
+stats-breakdown = { $pending } pending { $completed } completed
+error-no-agenda-recipient = No one to email the agenda to; pass --to or set `to` under [agenda] in the config file.
+agenda-nothing-due = Nothing is overdue or due today; no email sent.
+agenda-emailed = Emailed the agenda to { $to }.
+calendar-weekdays = Mo    Tu    We    Th    Fr    Sa    Su
+due-this-month = { $count } task(s) due this month. Use `calendar --agenda` to list them.
+nothing-due-in-month = Nothing is due in { $month }.
+
+## Burndown
+
+burndown-open = Open:       { $sparkline }
+burndown-completed = Completed:  { $sparkline }
+column-week-of = Week of
+column-open = Open
+
+## TODO files, reminders, and escalation
+
+watching = Watching { $path } (press Ctrl-C to stop).
+todo-synced = { $kind ->
+        [added] Added
+        [completed] Completed
+        [reopened] Reopened
+       *[renamed] Renamed
+    } task(s): { $ids }
+nothing-to-remind = Nothing to remind about.
+would-remind = Would remind about task { $id }: { $body } ({ $when }).
+reminded = Reminded about task { $id } ({ $when }).
+reminders-set = Reminders for task { $id }: { $reminders }.
+reminders-cleared = Removed the reminders of task { $id }.
+no-upcoming-reminders = No reminders to come.
+no-escalation-levels = No escalation levels configured.
+escalation-title = Overdue task { $id }: { $description }
+escalation-body = Due { $due }, still pending after { $hours }h (escalation level { $level }).
+would-notify = Would notify task { $id } via { $channel } (level { $level }).
+notified = Notified task { $id } via { $channel } (level { $level }).
+
+## Syncing
+
+sync-committed = Committed local changes.
+sync-pulled = Pulled changes from { $branch }.
+sync-merged = Merged changes made on another machine.
+sync-no-remote = No sync remote configured; set `remote` under [sync] in the config file to push.
+sync-pushed = Pushed to { $remote } ({ $branch }).
+sync-nothing-to-push = Nothing to push.
+caldav-synced = Synced with { $url }: { $pulled } local changes ({ $removed } moved to the trash), { $pushed } uploaded, { $deleted } deleted on the server.
+storage-synced = Synced with { $url }: { $pulled } local change(s).
+storage-uploaded = Uploaded { $path } to { $url }.
+error-interactive-no-terminal = --interactive needs a terminal to ask on.
+value-unset = (unset)
+conflict-heading = Task { $id } was changed on both sides: { $description }
+conflict-was = was
+conflict-local = local
+conflict-remote = remote
+conflict-prompt = Keep [l]ocal, [r]emote, or [e]dit?
+conflict-new-value = New value (JSON or plain text):
+conflict-invalid = That is not a valid { $field }.
+
+## Comparing and dry runs
+
+error-does-not-exist = { $path } does not exist.
+no-differences = No differences.
+diff-added = Added ({ $count }):
+diff-removed = Removed ({ $count }):
+diff-changed = Changed ({ $count }):
+dry-run-unchanged = Dry run: { $path } would not change.
+dry-run-write = Dry run: would write { $path }.
+
+## The audit journal
+
+journal-intact = Journal intact: { $entries } entries, { $signed } with a valid signature.
+journal-entry-problem = Entry { $seq }: { $problem }
+error-journal-tampered = { $path } has been tampered with
+journal-empty = The journal is empty.
+log-from-timestamps = No journal or event log is kept, so only when tasks were added and completed is shown.
+log-empty = Nothing happened to tasks in that time.
+journal-signed = signed
+error-no-key-directory = no config directory for the key file
+error-key-exists = { $path } already exists; pass --force to replace it
+key-written = Wrote the signing key to { $path }.
+public-key = Public key: { $key }
+key-hint = Set `sign = true` under [audit] to sign new entries, and keep the public key to verify them.
+
+## Encryption and checkups
+
+already-encrypted = { $path } is already { $action ->
+        [encrypted] encrypted
+       *[decrypted] decrypted
+    }.
+now-encrypted = { $path } is now { $action ->
+        [encrypted] encrypted
+       *[decrypted] decrypted
+    }.
+nothing-to-check = { $path } does not exist yet; nothing to check.
+no-problems = No problems found in { $path }.
+finding-fixed = fixed: { $repair }
+finding-fixable = --fix would { $repair }
+repaired = Repaired { $repaired } of { $count } problem(s).
+doctor-fix-hint = `task doctor --fix` repairs { $count } of them
+error-problems-left = { $count } problem(s) left in { $path }
+maintenance-data-file = Data file: { $path } ({ $size })
+maintenance-trash = Trash: { $count } task(s), { $expired } past retention ({ $size })
+maintenance-journal = Audit journal: { $count } entries ({ $size })
+maintenance-events = Event log: { $count } event(s) since the last snapshot, { $size } of history
+maintenance-backups = Backups: { $count } ({ $size })
+maintenance-total = Total: { $size }
+maintenance-nothing = Nothing to compact.
+maintenance-compacted = Squashed { $events } event(s), purged { $trash } trashed task(s), and deleted { $backups } backup(s); the data file went from { $before } to { $size }.
+error-event-log-format = { $path } is an event log, which is always stored as JSON lines.
+already-stored-as = { $path } is already stored as { $format }.
+now-stored-as = { $path } is now stored as { $format }.
+
+## Renumbering, triage, and reviews
+
+ids-sequential = IDs are already sequential. Nothing to renumber.
+would-renumber = Dry run: { $count } task(s) would be renumbered.
+renumbered = Renumbered { $count } task(s).
+inbox-empty = Inbox is empty. Nothing to triage.
+inbox-count = { $count } task(s) in the inbox.
+triage-project-prompt = Project (enter to skip, 'd' to delete, 'q' to quit):
+task-removed = Task ID { $id } removed.
+triage-due-prompt = Due date YYYY-MM-DD, 'tomorrow', '+3d', ... (enter for none):
+triage-priority-prompt = Priority low/medium/high (enter for none):
+priority-choices = Priority must be one of: low, medium, high.
+triage-finished = Triage finished: { $organized } organized, { $deleted } deleted, { $remaining } left in the inbox.
+nothing-to-review = Nothing to review. Everything is scheduled and fresh.
+review-count = { $count } task(s) to review.
+review-prompt = [c]omplete, [r]eschedule, [d]elete, [k]eep, or [q]uit?
+task-now-due = Task ID { $id } is now due { $due }.
+review-finished = Review finished: { $completed } completed, { $rescheduled } rescheduled, { $deleted } deleted.
+no-stale-tasks = No pending task has gone unchanged for over { $age }.
+stale-count = { $count } task(s) unchanged for over { $age }:
+stale-age = unchanged for { $days } days
+stale-bulk-prompt = [r]eschedule all, [a]rchive all, [d]elete all, go through them [o]ne by one, or [q]uit?
+stale-prompt = [r]eschedule, [a]rchive, [d]elete, [k]eep, or [q]uit?
+task-archived = Task ID { $id } archived as cancelled.
+stale-finished = Stale tasks: { $rescheduled } rescheduled, { $archived } archived, { $deleted } deleted.
+due-date-prompt = New due date YYYY-MM-DD, 'tomorrow', '+3d', ...:
+
+## Prompts
+
+confirm-choices = [y/N]
+confirm-yes = y yes
+
+## Exporting, importing, and clearing
+
+error-todoist-export-options = --output and --anonymize don't apply to Todoist exports.
+todoist-exported = Exported to Todoist: { $added } created, { $updated } updated, { $completed } closed.
+sections-updated = Updated { $count } task section(s) in { $path }.
+tasks-exported = Tasks exported to { $path }.
+error-todoist-import-file = Todoist imports are fetched from Todoist and take no file.
+error-taskwarrior-import-file = Give the file written by `task export`, e.g. `import --format taskwarrior export.json`.
+error-export-import-file = Give the file written by `export`, e.g. `import --from json tasks.json`.
+error-merge-source = --merge only applies to JSON and CSV imports.
+imported = Imported from { $source }: { $added } added, { $updated } updated.
+import-completed = { $count } task(s) no longer open in { $source } marked as completed.
+github-issue-closed = Closed GitHub issue { $issue }.
+not-preserved = Not preserved: { $fields }.
+wrote = Wrote { $path }
+scanned = Scanned { $files } file(s): { $added } task(s) added, { $moved } moved to another line.
+would-clear = Would move all { $count } tasks to the trash.
+clear-needs-yes = This will remove ALL tasks. Use `task clear --yes` to confirm.
+confirm-clear = Remove ALL { $count } tasks?
+cleared = Cleared { $count } tasks. They stay in the trash for now.
+
+## Burndown, continued
+
+backlog-steady = The backlog held steady.
+backlog-shrank = The backlog shrank by { $count } task(s) over { $weeks } weeks.
+backlog-grew = The backlog grew by { $count } task(s) over { $weeks } weeks.
+forecast-pace = { $open } task(s) open. Over the last { $weeks } weeks, { $added } were added and { $completed } completed per week.
+forecast-clear-by = At this pace the backlog is cleared in the week of { $week }.
+forecast-not-shrinking = At this pace the backlog won't shrink.
+forecast-empty = The backlog is empty.
+
+## CalDAV
+
+error-no-caldav = No CalDAV server configured; add a [sync.caldav] section to the config file.
+
+## Failures
+
+error = Error: { $error }
+error-io = File system error: { $error }
+error-toml-parse = Failed to parse TOML data: { $error }
+error-data-file = Failed to parse TOML data in { $path }: { $error }
+error-toml-serialize = Failed to serialize data to TOML: { $error }
+error-msgpack-decode = Failed to parse MessagePack data: { $error }
+error-msgpack-encode = Failed to serialize data to MessagePack: { $error }
+error-json = Failed to serialize data to JSON: { $error }
+error-chart = Failed to render chart: { $error }
+error-notify = Failed to send notification: { $error }
+error-clipboard = Clipboard error: { $error }
+error-encryption = Encryption error: { $error }
+error-audit = Audit journal error: { $error }
+error-doctor = Data file check failed: { $error }
+error-event-log = Event log error: { $error }
+error-conflict = Conflicting changes: { $error }
+error-migration = Could not upgrade the task list: { $error }
+error-hook = Hook failed: { $error }
+error-sync = Sync failed: { $error }
+error-search = Search index error: { $error }
+error-todoist = Todoist request failed: { $error }
+error-github = GitHub request failed: { $error }
+error-jira = Jira request failed: { $error }
+error-remote = Could not fetch the remote data file: { $error }
+error-config = Invalid config file { $error }
+error-limit = Over a limit: { $error }; nothing was saved
+error-task-not-found = Task with ID '{ $id }' not found.
+error-invalid-argument = Invalid argument: { $error }
+error-unexpected = An unexpected error occurred: { $error }
+did-you-mean = Did you mean { $tasks }?
+did-you-mean-list = { $rest }, or { $last }
+
+## Invalid arguments
+
+error-no-command = No command given.
+error-unknown-command = Unknown command '{ $name }', and there is no { $plugin } on PATH.
+did-you-mean-command = Did you mean { $command }?
+error-could-not-run = Could not run { $program }: { $error }
+error-editor-failed = { $editor } exited with { $status }; nothing was changed
+error-empty-description = The description can't be empty.
+error-unknown-priority = Unknown priority '{ $priority }'; expected low, medium, or high.
+error-recurrence-no-occurrence = The recurrence has no occurrence on or after the due date.
+error-merge-into-itself = Can't merge task { $id } into itself.
+error-subtask-cycle = Task ID { $id } can't be a subtask of task ID { $parent }, which is part of it.
+error-progress-has-subtasks = Task ID { $id } has subtasks; its progress is the share of them that is done.
+error-due-out-of-range = The due date is out of range.
+error-not-pending = Task ID { $id } is not pending.
+error-snooze-out-of-range = The snoozed due date is out of range.
+error-snooze-not-later = Snoozing must move task ID { $id } to a later date than { $date }.
+error-reminder-out-of-range = The reminder is out of range.
+error-no-due-to-remind-before = Task { $id } has no due date to remind before.
+error-reminder-in-past = The reminder '{ $reminder }' is in the past.
+error-not-recurring = Task ID { $id } is not a recurring task.
+error-no-checklist = Task ID { $id } has no checklist.
+error-no-checklist-item = Task ID { $id } has { $count } checklist item(s); there is no { $number }.
+error-timer-running = A timer is already running on task ID { $id }.
+error-snippet-empty = The snippet is empty; paste the text `share` printed.
+error-snippet-no-description = The snippet's first line must be the description.
+error-snippet-bad-line = Can't read '{ $line }'; fields are written `Name: value`.
+error-snippet-unknown-field = Unknown field '{ $field }' in the snippet.
+error-csv-no-description = The CSV file has no 'description' column.
+error-csv-invalid-field = Row { $row } of the CSV file has an invalid { $column }: '{ $value }'
+error-csv-unterminated-quote = The CSV file ends inside a quoted field.
+error-import-no-description = The imported task { $uuid } has no description.
+
+## Dates
+
+date-today = today
+date-tomorrow = tomorrow
+date-yesterday = yesterday
+date-in = { $unit ->
+        [day] in { $count } days
+        [week] { $count ->
+            [one] in 1 week
+           *[other] in { $count } weeks
+        }
+       *[month] { $count ->
+            [one] in 1 month
+           *[other] in { $count } months
+        }
+    }
+date-ago = { $unit ->
+        [day] { $count ->
+            [one] 1 day ago
+           *[other] { $count } days ago
+        }
+        [week] { $count ->
+            [one] 1 week ago
+           *[other] { $count } weeks ago
+        }
+       *[month] { $count ->
+            [one] 1 month ago
+           *[other] { $count } months ago
+        }
+    }
+date-overdue = { $unit ->
+        [day] { $count ->
+            [one] 1 day overdue
+           *[other] { $count } days overdue
+        }
+        [week] { $count ->
+            [one] 1 week overdue
+           *[other] { $count } weeks overdue
+        }
+       *[month] { $count ->
+            [one] 1 month overdue
+           *[other] { $count } months overdue
+        }
+    }
+
+## Progress
+
+progress-set = Task ID { $id } is { $percent }% done.
+
+## Plan
+
+plan-nothing-due = Nothing is due on { $date }.
+plan-heading = Plan for { $date }: { $planned } of { $capacity }
+plan-unestimated = { $count ->
+        [one] 1 task has no estimate and isn't counted.
+       *[other] { $count } tasks have no estimate and aren't counted.
+    }
+plan-overloaded = Warning: that is { $over } more than fits in a day ({ $capacity }).
+
+## Goals
+
+goal-added = Goal added: ID { $id }
+goal-removed = Goal ID { $id } removed{ $count ->
+        [0] .
+        [one] ; 1 task was unlinked from it.
+       *[other] ; { $count } tasks were unlinked from it.
+    }
+no-goals = No goals yet. Add one with `goal add "Ship v1.0" --due 2025-09-01`.
+
+## Duplicates
+
+warning-duplicates = { $count ->
+        [one] Warning: a pending task already reads like this one:
+       *[other] Warning: { $count } pending tasks already read like this one:
+    }
+confirm-add-duplicate = Add it anyway?
+error-duplicate = Task { $id } '{ $description }' already reads like this one; nothing added.
+
+## Waiting
+
+task-waiting = Task { $id } is waiting.
+task-waiting-until = Task { $id } is waiting until { $date }.
+task-not-waiting = Task { $id } is no longer waiting.
+no-followups = Nothing is waiting on anyone.
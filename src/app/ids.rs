@@ -0,0 +1,127 @@
+//! Strategies for assigning IDs to new tasks.
+//!
+//! Sequential IDs are short and predictable but collide when two copies of a
+//! shared list add tasks independently. The random strategies trade
+//! predictability for a much lower chance of collisions after a sync.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::app::Task;
+
+/// The largest ID handed out by [`RandomShortIds`] while the space is sparse.
+const RANDOM_SHORT_MAX: u32 = 99_999;
+
+/// Assigns the ID of a newly created task.
+pub trait IdGenerator {
+    /// Returns an ID not in `used`.
+    fn next_unused_id(&mut self, used: &HashSet<u32>) -> u32;
+
+    /// Returns an ID not used by any task in `existing`.
+    fn next_id(&mut self, existing: &[Task]) -> u32 {
+        self.next_unused_id(&existing.iter().map(|t| t.id).collect())
+    }
+}
+
+/// Hands out the highest existing ID plus one.
+#[derive(Debug, Default)]
+pub struct SequentialIds;
+
+impl IdGenerator for SequentialIds {
+    fn next_unused_id(&mut self, used: &HashSet<u32>) -> u32 {
+        used.iter().max().copied().unwrap_or(0) + 1
+    }
+}
+
+/// Hands out random IDs of at most five digits.
+///
+/// Falls back to the full `u32` range once the short range gets crowded.
+#[derive(Debug, Default)]
+pub struct RandomShortIds;
+
+impl IdGenerator for RandomShortIds {
+    fn next_unused_id(&mut self, used: &HashSet<u32>) -> u32 {
+        let max = if used.len() < (RANDOM_SHORT_MAX / 2) as usize {
+            RANDOM_SHORT_MAX
+        } else {
+            u32::MAX
+        };
+        loop {
+            let id = fastrand::u32(1..=max);
+            if !used.contains(&id) {
+                return id;
+            }
+        }
+    }
+}
+
+/// Derives IDs from freshly generated v4 UUIDs.
+///
+/// The full UUID is stored on every task regardless of strategy; this
+/// strategy also makes the numeric ID span the whole `u32` range.
+#[derive(Debug, Default)]
+pub struct UuidIds;
+
+impl IdGenerator for UuidIds {
+    fn next_unused_id(&mut self, used: &HashSet<u32>) -> u32 {
+        loop {
+            let id = Uuid::new_v4().as_u128() as u32;
+            if id != 0 && !used.contains(&id) {
+                return id;
+            }
+        }
+    }
+}
+
+/// Reassigns sequential IDs starting at 1, keeping the current task order.
+///
+/// UUIDs are left untouched, so tasks stay identifiable across machines.
+///
+/// # Returns
+///
+/// The `(old, new)` pairs of every task whose ID changed.
+pub fn renumber(tasks: &mut [Task]) -> Vec<(u32, u32)> {
+    let mut changes = Vec::new();
+    for (new_id, task) in (1..).zip(tasks.iter_mut()) {
+        if task.id != new_id {
+            changes.push((task.id, new_id));
+            task.id = new_id;
+        }
+    }
+    changes
+}
+
+/// The ID strategies selectable in the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdStrategy {
+    #[default]
+    Sequential,
+    RandomShort,
+    Uuid,
+}
+
+impl IdStrategy {
+    /// Creates the generator implementing this strategy.
+    pub fn generator(self) -> Box<dyn IdGenerator> {
+        match self {
+            IdStrategy::Sequential => Box::new(SequentialIds),
+            IdStrategy::RandomShort => Box::new(RandomShortIds),
+            IdStrategy::Uuid => Box::new(UuidIds),
+        }
+    }
+}
+
+impl fmt::Display for IdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            IdStrategy::Sequential => "sequential",
+            IdStrategy::RandomShort => "random-short",
+            IdStrategy::Uuid => "uuid",
+        };
+        write!(f, "{}", label)
+    }
+}
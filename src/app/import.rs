@@ -0,0 +1,31 @@
+//! Importing tasks from other task managers.
+
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+
+/// Where tasks can be imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportSource {
+    /// The open tasks of a Todoist account.
+    Todoist,
+    /// A JSON file written by Taskwarrior's `task export`.
+    Taskwarrior,
+    /// A file written by `export --format json`, e.g. on another machine.
+    Json,
+    /// A file written by `export --format csv`, or a spreadsheet with the
+    /// same columns.
+    Csv,
+}
+
+/// What an import or an export to another service changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Tasks created.
+    pub added: usize,
+    /// Tasks brought up to date with the other side.
+    pub updated: usize,
+    /// Tasks marked as completed because the other side completed them.
+    pub completed: usize,
+    /// What couldn't be carried over, with the number of tasks affected.
+    pub dropped: BTreeMap<String, usize>,
+}
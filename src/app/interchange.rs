@@ -0,0 +1,310 @@
+//! Exchanging tasks with another copy of this program, e.g. on a second
+//! machine, as JSON or CSV, and merging them back in.
+//!
+//! `export --format json` writes every field of every task, and
+//! `export --format csv` the ones a spreadsheet can show. `import` reads
+//! either back and matches tasks by UUID, so importing the same file twice
+//! adds nothing. When a task exists on both sides, the [`MergeStrategy`]
+//! decides which one is kept; tasks only in the file are added under new
+//! IDs, and tasks only here are left alone.
+//!
+//! A CSV file carries these columns, in any order; only `description` is
+//! required:
+//!
+//! ```text
+//! uuid,id,description,status,priority,project,tags,contexts,due,created,completed
+//! ```
+//!
+//! Tags and contexts are separated by spaces, `due` is `YYYY-MM-DD` with an
+//! optional `HH:MM`, and `created` and `completed` are RFC 3339. Merging a
+//! task from CSV only changes these fields, keeping its notes and history.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use clap::ValueEnum;
+use uuid::Uuid;
+
+use crate::app::{AppError, ImportReport, Priority, Result, Task, TaskStatus, csv_field};
+use crate::tr;
+
+/// The columns of a CSV export, in order.
+pub const CSV_COLUMNS: [&str; 11] = [
+    "uuid",
+    "id",
+    "description",
+    "status",
+    "priority",
+    "project",
+    "tags",
+    "contexts",
+    "due",
+    "created",
+    "completed",
+];
+
+/// Which side wins when a task was changed both here and in the imported
+/// file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MergeStrategy {
+    /// The side with the latest recorded activity: being created or
+    /// completed, a note, annotation, attachment, or tracked time. Ties keep
+    /// the task here.
+    #[default]
+    Newest,
+    /// Keep the task here.
+    Ours,
+    /// Take the task from the file.
+    Theirs,
+}
+
+/// Tasks read from an exported file, and which fields they carry.
+#[derive(Debug, Clone)]
+pub struct Incoming {
+    pub tasks: Vec<Task>,
+    /// `true` if the tasks only carry the CSV columns, so merging them must
+    /// keep the other fields of the task here.
+    pub partial: bool,
+}
+
+/// Writes `tasks` as a JSON array with every field.
+pub fn to_json(tasks: &[Task]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(tasks)? + "\n")
+}
+
+/// Writes `tasks` as CSV with a header row of [`CSV_COLUMNS`].
+pub fn to_csv(tasks: &[Task]) -> String {
+    let mut lines = vec![CSV_COLUMNS.join(",")];
+    for task in tasks {
+        let due = task.due_date.map(|date| match task.due_time {
+            Some(time) => format!("{} {}", date, time.format("%H:%M")),
+            None => date.to_string(),
+        });
+        let fields = [
+            task.uuid.to_string(),
+            task.id.to_string(),
+            task.description.clone(),
+            task.status.to_string(),
+            task.priority.map(|p| p.to_string()).unwrap_or_default(),
+            task.project.clone().unwrap_or_default(),
+            task.tags.join(" "),
+            task.contexts.join(" "),
+            due.unwrap_or_default(),
+            task.created_at.to_rfc3339(),
+            task.completed_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        lines.push(fields.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Reads the tasks of a JSON export.
+pub fn parse_json_export(json: &str) -> Result<Incoming> {
+    Ok(Incoming {
+        tasks: serde_json::from_str(json)?,
+        partial: false,
+    })
+}
+
+/// Reads the tasks of a CSV export.
+///
+/// # Returns
+///
+/// An `AppError::InvalidArgument` naming the line of a row that can't be
+/// read, or if there is no `description` column.
+pub fn parse_csv_export(csv: &str) -> Result<Incoming> {
+    let mut rows = split_csv(csv)?.into_iter();
+    let header = rows.next().unwrap_or_default();
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let Some(description_column) = column("description") else {
+        return Err(AppError::InvalidArgument(tr!("error-csv-no-description")));
+    };
+    let columns: Vec<Option<usize>> = CSV_COLUMNS.iter().map(|name| column(name)).collect();
+    let mut tasks = Vec::new();
+    for (index, row) in rows.enumerate() {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let get = |name: &str| {
+            let position = CSV_COLUMNS.iter().position(|c| *c == name)?;
+            columns[position]
+                .and_then(|i| row.get(i))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let invalid = |name: &str, value: &str| {
+            AppError::InvalidArgument(tr!(
+                "error-csv-invalid-field",
+                row = index + 2,
+                column = name,
+                value = value
+            ))
+        };
+        let description = row
+            .get(description_column)
+            .map(|d| d.trim().to_string())
+            .unwrap_or_default();
+        let mut task = Task::new(0, description, None);
+        if let Some(value) = get("uuid") {
+            task.uuid = Uuid::parse_str(value).map_err(|_| invalid("uuid", value))?;
+        }
+        if let Some(value) = get("id") {
+            task.id = value.parse().map_err(|_| invalid("id", value))?;
+        }
+        if let Some(value) = get("status") {
+            task.status =
+                TaskStatus::from_str(value, true).map_err(|_| invalid("status", value))?;
+        }
+        if let Some(value) = get("priority") {
+            task.priority =
+                Some(Priority::from_str(value, true).map_err(|_| invalid("priority", value))?);
+        }
+        task.project = get("project").map(str::to_string);
+        task.tags = get("tags")
+            .map(|tags| tags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        task.contexts = get("contexts")
+            .map(|contexts| contexts.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        if let Some(value) = get("due") {
+            if let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M") {
+                task.due_date = Some(at.date());
+                task.due_time = Some(at.time());
+            } else {
+                task.due_date = Some(
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map_err(|_| invalid("due", value))?,
+                );
+            }
+        }
+        let timestamp = |name: &str| -> Result<Option<DateTime<Utc>>> {
+            get(name)
+                .map(|value| {
+                    DateTime::parse_from_rfc3339(value)
+                        .map(|at| at.with_timezone(&Utc))
+                        .map_err(|_| invalid(name, value))
+                })
+                .transpose()
+        };
+        if let Some(created) = timestamp("created")? {
+            task.created_at = created;
+        }
+        task.completed_at = timestamp("completed")?;
+        tasks.push(task);
+    }
+    Ok(Incoming {
+        tasks,
+        partial: true,
+    })
+}
+
+/// Merges the `incoming` tasks into `tasks` by UUID, settling tasks present
+/// on both sides with `strategy`.
+///
+/// Tasks new to the list keep their ID if it is free and get the next one
+/// otherwise.
+pub fn merge_import(
+    tasks: &mut Vec<Task>,
+    incoming: Incoming,
+    strategy: MergeStrategy,
+) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for theirs in incoming.tasks {
+        if theirs.description.trim().is_empty() {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-import-no-description",
+                uuid = theirs.uuid.to_string()
+            )));
+        }
+        let Some(ours) = tasks.iter_mut().find(|t| t.uuid == theirs.uuid) else {
+            let mut task = theirs;
+            if task.id == 0 || tasks.iter().any(|t| t.id == task.id) {
+                task.id = next_id;
+            }
+            next_id = next_id.max(task.id + 1);
+            tasks.push(task);
+            report.added += 1;
+            continue;
+        };
+        let take_theirs = match strategy {
+            MergeStrategy::Ours => false,
+            MergeStrategy::Theirs => true,
+            MergeStrategy::Newest => last_activity(&theirs) > last_activity(ours),
+        };
+        if !take_theirs {
+            continue;
+        }
+        let was_pending = ours.is_pending();
+        if incoming.partial {
+            ours.description = theirs.description;
+            ours.status = theirs.status;
+            ours.priority = theirs.priority;
+            ours.project = theirs.project;
+            ours.tags = theirs.tags;
+            ours.contexts = theirs.contexts;
+            ours.due_date = theirs.due_date;
+            ours.due_time = theirs.due_time;
+            ours.created_at = theirs.created_at;
+            ours.completed_at = theirs.completed_at;
+        } else {
+            let id = ours.id;
+            *ours = theirs;
+            ours.id = id;
+        }
+        if was_pending && !ours.is_pending() {
+            report.completed += 1;
+        }
+        report.updated += 1;
+    }
+    Ok(report)
+}
+
+/// Returns the last moment something was recorded about `task`.
+fn last_activity(task: &Task) -> DateTime<Utc> {
+    let notes = task.notes.iter().map(|n| n.created_at);
+    let annotations = task.annotations.iter().map(|a| a.created_at);
+    let attachments = task.attachments.iter().map(|a| a.added_at);
+    let intervals = task.intervals.iter().map(|i| i.end.unwrap_or(i.start));
+    notes
+        .chain(annotations)
+        .chain(attachments)
+        .chain(intervals)
+        .chain(task.completed_at)
+        .fold(task.created_at, DateTime::max)
+}
+
+/// Splits CSV text into rows of fields, honoring double quotes.
+fn split_csv(csv: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-csv-unterminated-quote"
+        )));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
@@ -0,0 +1,342 @@
+//! Mirroring the Jira issues assigned to you into the task list through
+//! Jira's REST API.
+//!
+//! `jira pull` adds a task for each unresolved issue assigned to the token's
+//! user and brings the tasks pulled before up to date: summary, status,
+//! priority, labels as tags, and due date. A task is linked to its issue by
+//! its URL, `<url>/browse/<KEY>`, so pulling again never adds an issue twice.
+//! Tasks whose issue has been resolved since are marked as completed.
+//!
+//! Jira statuses map to task statuses by their category (to do, in progress,
+//! done), or by name through `statuses` under `[jira]`, e.g.
+//! `statuses = { "Blocked" = "blocked" }`.
+//!
+//! The server and token are set under `[jira]` in the config file; the token
+//! can also come from `JIRA_API_TOKEN`. Jira Cloud takes the account's
+//! `email` with an API token, Jira Server and Data Center a personal access
+//! token alone. Talking to Jira is only available when the crate is built
+//! with the `jira` feature.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+
+use crate::app::{AppError, ImportReport, Result, Task, TaskStatus};
+
+/// The environment variable holding the Jira API token.
+pub const JIRA_TOKEN_ENV_VAR: &str = "JIRA_API_TOKEN";
+
+/// How to reach Jira.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraConfig {
+    /// The server, e.g. `https://example.atlassian.net`.
+    pub url: Option<String>,
+    /// The account's email, for Jira Cloud's basic authentication.
+    pub email: Option<String>,
+    /// An API token (Cloud) or personal access token (Server).
+    pub token: Option<String>,
+    /// Task statuses for Jira statuses by name, taking precedence over the
+    /// mapping by status category.
+    pub statuses: BTreeMap<String, TaskStatus>,
+}
+
+impl JiraConfig {
+    /// Returns the server without a trailing slash.
+    pub fn server(&self) -> Result<&str> {
+        self.url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| AppError::Jira("no server; set `url` under [jira]".to_string()))
+    }
+
+    /// Returns the token from `JIRA_API_TOKEN`, or else from the config.
+    pub fn token(&self) -> Result<String> {
+        env::var(JIRA_TOKEN_ENV_VAR)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .or_else(|| self.token.clone())
+            .ok_or_else(|| {
+                AppError::Jira(format!(
+                    "no API token; set {} or `token` under [jira] in the config file",
+                    JIRA_TOKEN_ENV_VAR
+                ))
+            })
+    }
+
+    /// Returns the key of the issue `task` was pulled from, if any.
+    pub fn issue_key(&self, task: &Task) -> Option<String> {
+        let prefix = format!("{}/browse/", self.server().ok()?);
+        let key = task.url.as_deref()?.strip_prefix(&prefix)?;
+        Some(key.trim_end_matches('/').to_string()).filter(|key| !key.contains('/'))
+    }
+}
+
+/// Mirrors the unresolved Jira issues assigned to the token's user into
+/// `tasks`.
+///
+/// Issues pulled before are updated in place rather than added again, and
+/// pending tasks whose issue is resolved or no longer assigned are brought
+/// up to date with it.
+pub fn pull_jira_issues(tasks: &mut Vec<Task>, config: &JiraConfig) -> Result<ImportReport> {
+    api::pull(tasks, config)
+}
+
+#[cfg(feature = "jira")]
+mod api {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use chrono::NaiveDate;
+    use log::{debug, info};
+    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+    use std::collections::HashSet;
+    use ureq::Agent;
+    use ureq::http::Response;
+
+    use super::JiraConfig;
+    use crate::app::{AppError, ImportReport, Priority, Result, Task, TaskStatus};
+
+    /// The unresolved issues assigned to whoever the token belongs to.
+    const ASSIGNED_JQL: &str =
+        "assignee = currentUser() AND statusCategory != Done ORDER BY created ASC";
+
+    const FIELDS: &str = "summary,status,priority,labels,duedate";
+
+    /// Issues asked for per page.
+    const PAGE_SIZE: usize = 100;
+
+    #[derive(Deserialize)]
+    struct SearchPage {
+        issues: Vec<Issue>,
+        total: usize,
+    }
+
+    #[derive(Deserialize)]
+    struct Issue {
+        key: String,
+        fields: Fields,
+    }
+
+    #[derive(Deserialize)]
+    struct Named {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Status {
+        name: String,
+        #[serde(rename = "statusCategory")]
+        category: StatusCategory,
+    }
+
+    #[derive(Deserialize)]
+    struct StatusCategory {
+        key: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Fields {
+        summary: String,
+        status: Status,
+        priority: Option<Named>,
+        #[serde(default)]
+        labels: Vec<String>,
+        duedate: Option<String>,
+    }
+
+    impl JiraConfig {
+        /// Returns the task status for a Jira status named `name` in the
+        /// category with key `category` (`new`, `indeterminate`, or `done`).
+        fn task_status(&self, name: &str, category: &str) -> TaskStatus {
+            if let Some(status) = self
+                .statuses
+                .iter()
+                .find(|(jira, _)| jira.eq_ignore_ascii_case(name))
+                .map(|(_, status)| *status)
+            {
+                return status;
+            }
+            match category {
+                "indeterminate" => TaskStatus::InProgress,
+                "done" => TaskStatus::Done,
+                _ => TaskStatus::Todo,
+            }
+        }
+    }
+
+    impl Issue {
+        fn task_priority(&self) -> Option<Priority> {
+            match self.fields.priority.as_ref()?.name.as_str() {
+                "Highest" | "Blocker" | "Critical" | "High" | "Major" => Some(Priority::High),
+                "Medium" => Some(Priority::Medium),
+                "Low" | "Lowest" | "Minor" | "Trivial" => Some(Priority::Low),
+                _ => None,
+            }
+        }
+
+        fn due_date(&self) -> Option<NaiveDate> {
+            let date = self.fields.duedate.as_deref()?;
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+        }
+
+        /// Copies the fields onto `task`, returning `true` if any changed.
+        fn apply(&self, server: &str, config: &JiraConfig, task: &mut Task) -> bool {
+            let status =
+                config.task_status(&self.fields.status.name, &self.fields.status.category.key);
+            let url = format!("{}/browse/{}", server, self.key);
+            let changed = task.description != self.fields.summary
+                || task.status != status
+                || task.priority != self.task_priority()
+                || task.due_date != self.due_date()
+                || task.tags != self.fields.labels
+                || task.url.as_deref() != Some(url.as_str());
+            task.description = self.fields.summary.clone();
+            task.set_status(status);
+            task.priority = self.task_priority();
+            task.set_due_date(self.due_date());
+            task.tags = self.fields.labels.clone();
+            task.url = Some(url);
+            changed
+        }
+    }
+
+    struct Client {
+        agent: Agent,
+        base: String,
+        auth: String,
+    }
+
+    impl Client {
+        fn new(config: &JiraConfig) -> Result<Self> {
+            let token = config.token()?;
+            let auth = match &config.email {
+                Some(email) => format!("Basic {}", BASE64.encode(format!("{}:{}", email, token))),
+                None => format!("Bearer {}", token),
+            };
+            let agent = Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .new_agent();
+            let base = format!("{}/rest/api/2", config.server()?);
+            Ok(Client { agent, base, auth })
+        }
+
+        fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+            let url = format!("{}/{}", self.base, path);
+            let mut request = self
+                .agent
+                .get(&url)
+                .header("Authorization", &self.auth)
+                .header("Accept", "application/json");
+            for (name, value) in query {
+                request = request.query(*name, *value);
+            }
+            let mut response: Response<ureq::Body> = request
+                .call()
+                .map_err(|e| AppError::Jira(format!("{}: {}", url, e)))?;
+            match response.status().as_u16() {
+                200..=299 => {}
+                401 | 403 => {
+                    return Err(AppError::Jira(
+                        "the token was rejected; check `email` and `token` under [jira]"
+                            .to_string(),
+                    ));
+                }
+                status => {
+                    return Err(AppError::Jira(format!("{} returned HTTP {}", url, status)));
+                }
+            }
+            response
+                .body_mut()
+                .read_json()
+                .map_err(|e| AppError::Jira(format!("{}: unexpected response: {}", url, e)))
+        }
+    }
+
+    pub(super) fn pull(tasks: &mut Vec<Task>, config: &JiraConfig) -> Result<ImportReport> {
+        let client = Client::new(config)?;
+        let server = config.server()?;
+        let max_results = PAGE_SIZE.to_string();
+        let mut issues: Vec<Issue> = Vec::new();
+        loop {
+            let start_at = issues.len().to_string();
+            let page: SearchPage = client.get(
+                "search",
+                &[
+                    ("jql", ASSIGNED_JQL),
+                    ("fields", FIELDS),
+                    ("startAt", &start_at),
+                    ("maxResults", &max_results),
+                ],
+            )?;
+            let empty = page.issues.is_empty();
+            issues.extend(page.issues);
+            if empty || issues.len() >= page.total {
+                break;
+            }
+        }
+        debug!("Fetched {} assigned Jira issues.", issues.len());
+
+        let mut report = ImportReport::default();
+        let open: HashSet<&str> = issues.iter().map(|i| i.key.as_str()).collect();
+        for task in tasks.iter_mut().filter(|t| t.is_pending()) {
+            let Some(key) = config.issue_key(task) else {
+                continue;
+            };
+            if open.contains(key.as_str()) {
+                continue;
+            }
+            // No longer listed: resolved, or assigned to someone else.
+            let current: Issue = client.get(&format!("issue/{}", key), &[("fields", FIELDS)])?;
+            let changed = current.apply(server, config, task);
+            if task.is_done() {
+                report.completed += 1;
+            } else if changed {
+                report.updated += 1;
+            }
+        }
+
+        let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        for issue in &issues {
+            let linked = tasks
+                .iter_mut()
+                .find(|t| config.issue_key(t).as_deref() == Some(issue.key.as_str()));
+            match linked {
+                Some(task) => {
+                    if issue.apply(server, config, task) {
+                        report.updated += 1;
+                    }
+                }
+                None => {
+                    let mut task = Task::new(next_id, String::new(), None);
+                    next_id += 1;
+                    issue.apply(server, config, &mut task);
+                    if task.status == TaskStatus::Done {
+                        continue;
+                    }
+                    tasks.push(task);
+                    report.added += 1;
+                }
+            }
+        }
+        info!(
+            "Jira pull: {} added, {} updated, {} completed.",
+            report.added, report.updated, report.completed
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(not(feature = "jira"))]
+mod api {
+    use super::JiraConfig;
+    use crate::app::{AppError, ImportReport, Result, Task};
+
+    pub(super) fn pull(_tasks: &mut Vec<Task>, _config: &JiraConfig) -> Result<ImportReport> {
+        Err(AppError::Jira(
+            "this build does not include Jira support; rebuild with `--features jira`".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,334 @@
+//! An append-only journal of changes to the task list, optionally signed.
+//!
+//! With `journal = true` under `[audit]`, every command that changes tasks
+//! appends an entry recording the command line, the resulting [`TaskDiff`],
+//! and, when the list is `shared`, who ran it. Entries form a hash chain: each one carries the SHA-256 of
+//! its predecessor, so editing or removing an entry breaks every hash after
+//! it. With `sign = true`, entries are also signed with an Ed25519 key, which
+//! proves the chain wasn't rebuilt by someone without the key.
+//!
+//! The signing key is a hex string read from `key_command` (for instance one
+//! looking it up in the system keyring with `secret-tool`) or from
+//! `key_file`, which `audit keygen` creates.
+//!
+//! ```toml
+//! [audit]
+//! journal = true
+//! sign = true
+//! key_command = "secret-tool lookup service tm-audit"
+//! public_key = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"
+//! ```
+//!
+//! Changes written by `watch-file`, `daemon`, and git `sync` are not journaled.
+
+use chacha20poly1305::aead::Generate;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app::{AppError, Result, TaskDiff, config, encryption};
+
+/// The hash the first entry points back to.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Settings for the audit journal.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Record changes in the journal.
+    pub journal: bool,
+    /// Sign journal entries.
+    pub sign: bool,
+    /// A command printing the hex signing key, e.g. from the system keyring.
+    pub key_command: Option<String>,
+    /// Where the hex signing key is stored when `key_command` is unset;
+    /// defaults to `audit.key` in the config directory.
+    pub key_file: Option<PathBuf>,
+    /// The hex public key entries are verified against; defaults to the one
+    /// belonging to the signing key.
+    pub public_key: Option<String>,
+}
+
+impl AuditConfig {
+    /// Returns where the signing key is stored.
+    pub fn key_file_path(&self) -> Option<PathBuf> {
+        self.key_file
+            .clone()
+            .or_else(|| config::config_dir().map(|dir| dir.join("audit.key")))
+    }
+
+    /// Reads the signing key from `key_command` or the key file.
+    pub fn signing_key(&self) -> Result<SigningKey> {
+        let hex_key = match &self.key_command {
+            Some(command) => {
+                let output = Command::new("sh")
+                    .args(["-c", command])
+                    .output()
+                    .map_err(|e| AppError::Audit(format!("could not run key_command: {}", e)))?;
+                if !output.status.success() {
+                    return Err(AppError::Audit(format!(
+                        "key_command failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )));
+                }
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            }
+            None => {
+                let path = self.key_file_path().ok_or_else(|| {
+                    AppError::Audit("no location for the signing key".to_string())
+                })?;
+                fs::read_to_string(&path).map_err(|e| {
+                    AppError::Audit(format!(
+                        "could not read the signing key {} ({}); create one with `audit keygen`",
+                        path.display(),
+                        e
+                    ))
+                })?
+            }
+        };
+        let bytes: [u8; 32] = decode_hex(hex_key.trim())?;
+        Ok(SigningKey::from_bytes(&bytes))
+    }
+
+    /// Returns the key entries are verified against.
+    pub fn verifying_key(&self) -> Result<VerifyingKey> {
+        match &self.public_key {
+            Some(key) => parse_public_key(key),
+            None => Ok(self.signing_key()?.verifying_key()),
+        }
+    }
+}
+
+/// Parses a hex-encoded Ed25519 public key.
+pub fn parse_public_key(key: &str) -> Result<VerifyingKey> {
+    VerifyingKey::from_bytes(&decode_hex(key.trim())?)
+        .map_err(|e| AppError::Audit(format!("invalid public key: {}", e)))
+}
+
+/// One recorded change to the task list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// The entry's position, starting at 1.
+    pub seq: u64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub at: DateTime<Utc>,
+    /// The command line that made the change.
+    pub command: String,
+    /// Who made the change, on a shared list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub changes: TaskDiff,
+    /// The hash of the previous entry.
+    pub prev_hash: String,
+    /// The SHA-256 of this entry's other fields.
+    pub hash: String,
+    /// The hex Ed25519 signature of `hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// The fields covered by an entry's hash, in a fixed order.
+#[derive(Serialize)]
+struct HashedFields<'a> {
+    seq: u64,
+    at: i64,
+    command: &'a str,
+    changes: &'a TaskDiff,
+    prev_hash: &'a str,
+    /// Left out when unset, so entries written before users were recorded
+    /// keep their hashes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<&'a str>,
+}
+
+impl JournalEntry {
+    fn compute_hash(&self) -> Result<String> {
+        let fields = HashedFields {
+            seq: self.seq,
+            at: self.at.timestamp(),
+            command: &self.command,
+            changes: &self.changes,
+            prev_hash: &self.prev_hash,
+            user: self.user.as_deref(),
+        };
+        Ok(hex::encode(Sha256::digest(serde_json::to_vec(&fields)?)))
+    }
+}
+
+/// Returns where the journal of the data file at `tasks_path` is kept.
+pub fn journal_file_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.journal.jsonl", stem))
+}
+
+/// Loads every journal entry at `path`; a missing file is an empty journal.
+pub fn load_journal(path: &Path) -> Result<Vec<JournalEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    encryption::read_file(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| AppError::Audit(format!("entry {} is malformed: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Appends an entry for `changes` made by `command` at `at`, and by `user`
+/// if known.
+///
+/// The entry is signed if the config asks for it.
+pub fn append_journal(
+    path: &Path,
+    command: &str,
+    user: Option<String>,
+    changes: TaskDiff,
+    at: DateTime<Utc>,
+    config: &AuditConfig,
+) -> Result<JournalEntry> {
+    let mut contents = if path.exists() {
+        encryption::read_file(path)?
+    } else {
+        String::new()
+    };
+    let last = contents
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(serde_json::from_str::<JournalEntry>)
+        .transpose()?;
+
+    let mut entry = JournalEntry {
+        seq: last.as_ref().map_or(1, |e| e.seq + 1),
+        at,
+        command: command.to_string(),
+        user,
+        changes,
+        prev_hash: last.map_or_else(|| GENESIS_HASH.to_string(), |e| e.hash),
+        hash: String::new(),
+        signature: None,
+    };
+    entry.hash = entry.compute_hash()?;
+    if config.sign {
+        let key = config.signing_key()?;
+        entry.signature = Some(hex::encode(key.sign(entry.hash.as_bytes()).to_bytes()));
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&serde_json::to_string(&entry)?);
+    contents.push('\n');
+    encryption::write_file(path, &contents, encryption::should_encrypt(path))?;
+    Ok(entry)
+}
+
+/// The outcome of checking a journal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalVerification {
+    pub entries: usize,
+    pub signed: usize,
+    /// Why the chain or a signature doesn't check out, by entry number.
+    pub problems: Vec<(u64, String)>,
+}
+
+impl JournalVerification {
+    /// Returns `true` if nothing was found to be tampered with.
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Checks the hash chain of `entries` and, with `key`, every signature.
+///
+/// With `require_signatures`, unsigned entries count as problems too.
+pub fn verify_journal(
+    entries: &[JournalEntry],
+    key: Option<&VerifyingKey>,
+    require_signatures: bool,
+) -> Result<JournalVerification> {
+    let mut result = JournalVerification {
+        entries: entries.len(),
+        ..JournalVerification::default()
+    };
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (expected_seq, entry) in (1..).zip(entries) {
+        let seq = entry.seq;
+        if seq != expected_seq {
+            result.problems.push((
+                expected_seq,
+                format!("expected entry {}, found {}", expected_seq, seq),
+            ));
+        }
+        if entry.prev_hash != prev_hash {
+            result
+                .problems
+                .push((seq, "does not follow the previous entry".to_string()));
+        }
+        if entry.compute_hash()? != entry.hash {
+            result
+                .problems
+                .push((seq, "contents do not match its hash".to_string()));
+        }
+        match (&entry.signature, key) {
+            (Some(signature), Some(key)) => {
+                let valid = decode_hex::<64>(signature)
+                    .map(|bytes| Signature::from_bytes(&bytes))
+                    .is_ok_and(|sig| key.verify_strict(entry.hash.as_bytes(), &sig).is_ok());
+                if valid {
+                    result.signed += 1;
+                } else {
+                    result.problems.push((seq, "invalid signature".to_string()));
+                }
+            }
+            (None, _) if require_signatures => {
+                result.problems.push((seq, "is not signed".to_string()));
+            }
+            _ => {}
+        }
+        prev_hash = entry.hash.clone();
+    }
+    Ok(result)
+}
+
+/// Creates a new signing key.
+pub fn generate_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&<[u8; 32]>::generate())
+}
+
+/// Writes `key` as hex to `path`, readable only by the owner.
+pub fn write_signing_key(path: &Path, key: &SigningKey) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", hex::encode(key.to_bytes()))?;
+    Ok(())
+}
+
+/// Returns the hex public key belonging to `key`.
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N]> {
+    let bytes = hex::decode(s).map_err(|e| AppError::Audit(format!("invalid hex key: {}", e)))?;
+    bytes
+        .try_into()
+        .map_err(|_| AppError::Audit(format!("expected {} bytes of hex", N)))
+}
@@ -0,0 +1,91 @@
+//! Soft limits on work in progress, as on a personal kanban board.
+//!
+//! Limits are set under `[limits]` in the config file. A command that takes
+//! the list past one, e.g. starting a fourth task with `in_progress = 3`,
+//! prints a warning; with `--strict`, or `strict = true`, it fails instead
+//! and nothing is saved. Lists already over a limit are only warned about
+//! when a command makes it worse.
+//!
+//! ```toml
+//! [limits]
+//! in_progress = 3
+//! due_per_day = 10
+//! strict = false
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::app::{Task, TaskStatus};
+
+/// Settings for the limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+    /// How many tasks may be in progress at once.
+    pub in_progress: Option<usize>,
+    /// How many pending tasks may be due on the same day.
+    pub due_per_day: Option<usize>,
+    /// Fail commands that go past a limit instead of warning.
+    pub strict: bool,
+}
+
+impl LimitsConfig {
+    /// Returns `true` if any limit is set.
+    pub fn is_set(&self) -> bool {
+        self.in_progress.is_some() || self.due_per_day.is_some()
+    }
+
+    /// Returns the limits `after` goes past that `before` didn't, or by less.
+    pub fn breaches(&self, before: &[Task], after: &[Task]) -> Vec<LimitBreach> {
+        let mut breaches = Vec::new();
+        if let Some(limit) = self.in_progress {
+            let count = in_progress(after);
+            if count > limit && count > in_progress(before) {
+                breaches.push(LimitBreach::InProgress { count, limit });
+            }
+        }
+        if let Some(limit) = self.due_per_day {
+            let earlier = due_per_day(before);
+            for (date, count) in due_per_day(after) {
+                if count > limit && count > earlier.get(&date).copied().unwrap_or(0) {
+                    breaches.push(LimitBreach::DuePerDay { date, count, limit });
+                }
+            }
+        }
+        breaches
+    }
+}
+
+/// A limit a command went past.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitBreach {
+    /// More tasks in progress than `in_progress` allows.
+    InProgress { count: usize, limit: usize },
+    /// More pending tasks due on `date` than `due_per_day` allows.
+    DuePerDay {
+        date: NaiveDate,
+        count: usize,
+        limit: usize,
+    },
+}
+
+fn in_progress(tasks: &[Task]) -> usize {
+    tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::InProgress)
+        .count()
+}
+
+fn due_per_day(tasks: &[Task]) -> BTreeMap<NaiveDate, usize> {
+    let mut counts = BTreeMap::new();
+    for date in tasks
+        .iter()
+        .filter(|t| t.is_pending())
+        .filter_map(|t| t.due_date)
+    {
+        *counts.entry(date).or_default() += 1;
+    }
+    counts
+}
@@ -0,0 +1,102 @@
+//! Named task lists, each kept in its own data file.
+//!
+//! `--list work` works on `work.toml` in the lists directory rather than the
+//! usual data file, and `default_list` in the config makes a list the one
+//! used without the flag. Everything derived from the data file, like the
+//! trash and the journal, is kept per list.
+//!
+//! ```toml
+//! default_list = "personal"
+//! lists_dir = "/home/me/lists"
+//! ```
+
+use log::info;
+use std::fs;
+use std::path::Path;
+
+use crate::app::{AppError, IdGenerator, Result, Task, storage};
+
+/// Checks that `name` can name a list: letters, digits, `-`, and `_`.
+pub fn parse_list_name(name: &str) -> std::result::Result<String, String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(format!(
+            "'{}' is not a valid list name; use letters, digits, '-', and '_'",
+            name
+        ))
+    }
+}
+
+/// Returns the names of the lists in `dir`, sorted.
+///
+/// Files kept next to a list, like its trash, aren't lists themselves.
+pub fn list_names(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str())
+            && parse_list_name(name).is_ok()
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Moves the task with ID `id` from `tasks` to the list in the data file at
+/// `target`, where it gets an ID from `ids`.
+///
+/// The target list is saved right away; `tasks` is left for the caller to
+/// save.
+///
+/// # Returns
+///
+/// The ID of the task in the target list.
+pub fn move_task(
+    tasks: &mut Vec<Task>,
+    id: u32,
+    target: &Path,
+    ids: &mut dyn IdGenerator,
+) -> Result<u32> {
+    let position = tasks
+        .iter()
+        .position(|t| t.id == id)
+        .ok_or_else(|| AppError::task_not_found(id, tasks.iter()))?;
+    let mut destination = storage::load_tasks_from(target)?;
+    if destination.iter().any(|t| t.uuid == tasks[position].uuid) {
+        return Err(AppError::InvalidArgument(format!(
+            "Task {} is already in {}.",
+            id,
+            target.display()
+        )));
+    }
+
+    let mut task = tasks[position].clone();
+    task.id = ids.next_id(&destination);
+    let new_id = task.id;
+    destination.push(task);
+    if let Some(dir) = target.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    storage::save_tasks_to(&destination, target)?;
+    tasks.remove(position);
+    info!(
+        "Moved task {} to {} as ID {}.",
+        id,
+        target.display(),
+        new_id
+    );
+    Ok(new_id)
+}
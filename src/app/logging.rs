@@ -0,0 +1,79 @@
+//! Diagnostic logging for `-v` and `--log-file`.
+//!
+//! Log messages and the phases timed with `info_span!` go through one
+//! `tracing` subscriber. Each `-v` shows more of this program's own
+//! messages; other crates only ever show warnings and errors, so `-vvv`
+//! stays readable. Without `-v`, `RUST_LOG` picks what is logged,
+//! defaulting to errors. Phases are logged when they end, with how long
+//! they took, e.g. `load: close time.busy=3.2ms`.
+
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::registry::Registry;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::app::{AppError, Profiler, Result};
+
+/// The crates whose messages `-v` shows: the library and the `tm` binary.
+const OWN_TARGETS: [&str; 2] = ["task_manager_command_line", "tm"];
+
+/// What to log, and where to.
+#[derive(Debug, Clone, Default)]
+pub struct LogSettings {
+    /// How many times `-v` was given.
+    pub verbosity: u8,
+    /// The file to append the log to instead of stderr.
+    pub log_file: Option<PathBuf>,
+}
+
+impl LogSettings {
+    /// Returns the filter for the log: this program's messages at the level
+    /// `-v` asks for, or what `RUST_LOG` says without it.
+    fn filter(&self) -> EnvFilter {
+        let level = match self.verbosity {
+            0 if self.log_file.is_none() => {
+                return EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| EnvFilter::new("error"));
+            }
+            0 | 2 => "debug",
+            1 => "info",
+            _ => "trace",
+        };
+        let directives: Vec<String> = OWN_TARGETS
+            .iter()
+            .map(|target| format!("{}={}", target, level))
+            .collect();
+        EnvFilter::new(format!("warn,{}", directives.join(",")))
+    }
+}
+
+/// Installs the global subscriber that writes the log as `settings` ask,
+/// and records phase timings into `profiler` if given.
+///
+/// # Returns
+///
+/// An `AppError::Io` if the log file can't be opened.
+pub fn install_logging(settings: &LogSettings, profiler: Option<&Profiler>) -> Result<()> {
+    let (writer, ansi) = match &settings.log_file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            (BoxMakeWriter::new(file), false)
+        }
+        None => (BoxMakeWriter::new(io::stderr), io::stderr().is_terminal()),
+    };
+    let log = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(settings.filter());
+    Registry::default()
+        .with(log)
+        .with(profiler.map(|p| p.layer()))
+        .try_init()
+        .map_err(|e| AppError::Unexpected(format!("failed to install logging: {}", e)))
+}
@@ -0,0 +1,178 @@
+//! Housekeeping for the files kept next to the data file.
+//!
+//! [`survey`] measures the data file and reports what has piled up beside
+//! it: trashed tasks, the audit journal, the history of an event log, and
+//! the backups taken of damaged data files. [`Survey::compact`] then cleans
+//! up in one pass: an event log is squashed into a single snapshot, trash
+//! entries past their retention are purged, and all but the newest backups
+//! are deleted.
+//!
+//! The audit journal is only measured, never shortened: its entries form a
+//! hash chain, and dropping any of them would look like tampering.
+
+use chrono::{DateTime, Utc};
+use log::info;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    Result, encryption, eventlog, journal_file_path, load_journal, load_trash, purge_trash,
+    trash_file_path, write_trash,
+};
+
+/// How many backups `maintenance --compact` keeps by default.
+pub const DEFAULT_KEEP_BACKUPS: usize = 3;
+
+/// What [`survey`] found next to the data file.
+#[derive(Debug, Default)]
+pub struct Survey {
+    /// The size of the data file in bytes.
+    pub data_bytes: u64,
+    /// The number of trashed tasks, and how many of them are past retention.
+    pub trashed: usize,
+    pub trash_expired: usize,
+    pub trash_bytes: u64,
+    /// The number of entries in the audit journal.
+    pub journal_entries: usize,
+    pub journal_bytes: u64,
+    /// For an event log, the events following its last snapshot.
+    pub events: Option<usize>,
+    /// The size of the event log's history file in bytes.
+    pub history_bytes: u64,
+    /// The backups of damaged data files, oldest first.
+    pub backups: Vec<PathBuf>,
+    pub backup_bytes: u64,
+}
+
+/// What [`Survey::compact`] cleaned up.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Compaction {
+    /// The events moved out of the event log into its history file.
+    pub events_squashed: usize,
+    pub trash_purged: usize,
+    pub backups_pruned: usize,
+}
+
+impl Survey {
+    /// Returns the bytes taken by the data file and everything beside it.
+    pub fn total_bytes(&self) -> u64 {
+        self.data_bytes
+            + self.trash_bytes
+            + self.journal_bytes
+            + self.history_bytes
+            + self.backup_bytes
+    }
+
+    /// Compacts the files beside the data file at `path`, purging trash
+    /// entries older than `retention_days` before `now` and keeping the
+    /// newest `keep_backups` backups.
+    pub fn compact(
+        &self,
+        path: &Path,
+        now: DateTime<Utc>,
+        retention_days: u32,
+        keep_backups: usize,
+    ) -> Result<Compaction> {
+        let mut compaction = Compaction::default();
+        if self.events.is_some_and(|events| events > 0) {
+            compaction.events_squashed = eventlog::compact_event_log(path)?;
+        }
+        if self.trash_expired > 0 {
+            let trash_path = trash_file_path(path);
+            let mut trash = load_trash(&trash_path)?;
+            compaction.trash_purged = purge_trash(&mut trash, now, retention_days);
+            write_trash(
+                &trash,
+                &trash_path,
+                encryption::is_encrypted_file(&trash_path),
+            )?;
+        }
+        let prune = self.backups.len().saturating_sub(keep_backups);
+        for backup in &self.backups[..prune] {
+            fs::remove_file(backup)?;
+            info!("Deleted the backup {}.", backup.display());
+        }
+        compaction.backups_pruned = prune;
+        Ok(compaction)
+    }
+}
+
+/// Measures the data file at `path` and the files beside it, counting trash
+/// entries older than `retention_days` before `now` as expired.
+pub fn survey(path: &Path, now: DateTime<Utc>, retention_days: u32) -> Result<Survey> {
+    let mut survey = Survey {
+        data_bytes: file_size(path),
+        ..Survey::default()
+    };
+
+    let trash_path = trash_file_path(path);
+    let mut trash = load_trash(&trash_path)?;
+    survey.trashed = trash.len();
+    survey.trash_expired = purge_trash(&mut trash, now, retention_days);
+    survey.trash_bytes = file_size(&trash_path);
+
+    let journal_path = journal_file_path(path);
+    survey.journal_entries = load_journal(&journal_path)?.len();
+    survey.journal_bytes = file_size(&journal_path);
+
+    if eventlog::is_event_log(path) {
+        let records = if path.exists() {
+            eventlog::parse_events(&encryption::read_file(path)?)?
+        } else {
+            Vec::new()
+        };
+        survey.events = Some(eventlog::events_since_snapshot(&records));
+        survey.history_bytes = file_size(&eventlog::history_file_path(path));
+    }
+
+    survey.backups = backups_of(path)?;
+    survey.backup_bytes = survey.backups.iter().map(|b| file_size(b)).sum();
+    Ok(survey)
+}
+
+/// Returns the backups taken of the data file at `path` when it couldn't be
+/// read, `{name}.corrupt-{time}.bak`, oldest first.
+fn backups_of(path: &Path) -> Result<Vec<PathBuf>> {
+    let Some(name) = path.file_name().map(|s| s.to_string_lossy().into_owned()) else {
+        return Ok(Vec::new());
+    };
+    let dir = match path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::from("."),
+    };
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let prefix = format!("{}.corrupt-", name);
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".bak"))
+        })
+        .collect();
+    // The time in the name sorts them by age.
+    backups.sort();
+    Ok(backups)
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |m| m.len())
+}
+
+/// Formats `bytes` for people, e.g. `12.3 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -0,0 +1,1186 @@
+//! The operations of the command-line interface as a library API.
+//!
+//! [`TaskManager`] works on a loaded task list and returns what each
+//! operation did instead of printing it, so the same logic can back other
+//! front ends and be checked without capturing output. Loading and saving
+//! stay with the caller.
+
+use chrono::{NaiveDate, NaiveTime};
+use log::{error, info};
+use std::collections::HashSet;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::app::{
+    AlertSpec, AppError, Attachment, ChecklistItem, Config, DateOrder, DueAt, DueSpec, Estimate,
+    IdGenerator, Priority, Query, Recurrence, Result, SnoozeSpec, SortKey, Task, TaskFilter,
+    TaskStatus, clock, sort_tasks, subtasks,
+};
+use crate::tr;
+
+/// A task to add with [`TaskManager::add`].
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub description: String,
+    pub due: Option<DueAt>,
+    pub project: Option<String>,
+    /// The priority; the configured default priority if `None`.
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub contexts: Vec<String>,
+    pub expires: Option<DueSpec>,
+    pub start: Option<DueSpec>,
+    pub estimate: Option<Estimate>,
+    /// Minutes before the due date to send a reminder.
+    pub remind_before: Option<u32>,
+    /// How the task repeats, with the end and exceptions of the series.
+    pub recurrence: Option<Recurrence>,
+    pub notes: Vec<String>,
+    pub url: Option<String>,
+    pub assignee: Option<String>,
+    /// The ID of the task the new one is a subtask of.
+    pub parent: Option<u32>,
+    pub goal: Option<u32>,
+}
+
+impl NewTask {
+    /// A task with nothing but a description.
+    pub fn new(description: impl Into<String>) -> Self {
+        NewTask {
+            description: description.into(),
+            ..NewTask::default()
+        }
+    }
+}
+
+/// Changes to make to a task with [`TaskManager::edit`].
+///
+/// Fields left at `None` keep their value, and `Some(None)` clears an
+/// optional one. Tags and contexts are removed before others are added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskChanges {
+    pub description: Option<String>,
+    pub due: Option<Option<DueAt>>,
+    pub start: Option<Option<DueSpec>>,
+    pub project: Option<Option<String>>,
+    pub priority: Option<Option<Priority>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub add_contexts: Vec<String>,
+    pub remove_contexts: Vec<String>,
+    pub url: Option<Option<String>>,
+    pub assignee: Option<Option<String>>,
+    /// The ID of the task to make this one a subtask of.
+    pub parent: Option<Option<u32>>,
+    pub goal: Option<Option<u32>>,
+}
+
+impl TaskChanges {
+    /// Returns `true` if no change was asked for.
+    pub fn is_empty(&self) -> bool {
+        *self == TaskChanges::default()
+    }
+}
+
+/// A field of a task that [`TaskManager::edit`] changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskField {
+    Description,
+    Due,
+    Start,
+    Project,
+    Priority,
+    Tags,
+    Contexts,
+    Url,
+    Assignee,
+    Parent,
+    Goal,
+}
+
+impl TaskField {
+    /// Returns the name of the field, as `edit` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            TaskField::Description => "description",
+            TaskField::Due => "due",
+            TaskField::Start => "start",
+            TaskField::Project => "project",
+            TaskField::Priority => "priority",
+            TaskField::Tags => "tags",
+            TaskField::Contexts => "contexts",
+            TaskField::Url => "url",
+            TaskField::Assignee => "assignee",
+            TaskField::Parent => "parent",
+            TaskField::Goal => "goal",
+        }
+    }
+}
+
+impl fmt::Display for TaskField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which tasks [`TaskManager::list`] returns, and in what order.
+///
+/// The default selects the pending tasks that aren't waiting, in the order
+/// of the list.
+#[derive(Debug, Clone, Default)]
+pub struct TaskSelection {
+    pub query: Option<Query>,
+    /// Includes completed and cancelled tasks, and waiting ones.
+    pub all: bool,
+    /// Selects only the tasks waiting on something or not started yet.
+    pub waiting: bool,
+    pub project: Option<String>,
+    pub assignee: Option<String>,
+    pub filter: Option<TaskFilter>,
+    /// Selects only tasks snoozed at least once, most snoozed first unless
+    /// `sort` is given.
+    pub snoozed: bool,
+    pub sort: Option<SortKey>,
+    pub reverse: bool,
+    /// How many of the selected tasks to skip.
+    pub offset: usize,
+    /// How many tasks to return at most.
+    pub limit: Option<usize>,
+}
+
+/// Which tasks [`TaskManager::count`] counts: those that pass every
+/// condition.
+#[derive(Debug, Clone, Default)]
+pub struct CountSelection {
+    pub query: Option<Query>,
+    /// Includes completed and cancelled tasks, and waiting ones.
+    pub all: bool,
+    pub overdue: bool,
+    pub due: Option<DueSpec>,
+    /// Tags the task must all have.
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+}
+
+/// A page of the task list, as selected by [`TaskManager::list`].
+#[derive(Debug)]
+pub struct ListPage<'a> {
+    /// The tasks on the page, in display order.
+    pub tasks: Vec<&'a Task>,
+    /// How many tasks matched before paging.
+    pub matching: usize,
+}
+
+/// The counts shown by the `status` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusSummary {
+    /// Pending tasks past their due date or time.
+    pub overdue: usize,
+    /// Pending tasks due later today.
+    pub due_today: usize,
+    /// Pending tasks that have started, as `list` shows them.
+    pub pending: usize,
+}
+
+/// What skipping an occurrence of a recurring task did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skipped {
+    /// The task is now due on the next occurrence.
+    Next(NaiveDate),
+    /// The skipped occurrence was the last one, so the task was cancelled.
+    Ended,
+}
+
+/// Adds, changes, and removes the tasks of a task list.
+pub struct TaskManager<'a> {
+    tasks: &'a mut Vec<Task>,
+    ids: Box<dyn IdGenerator>,
+    reserved_ids: Vec<u32>,
+    date_order: Option<DateOrder>,
+    default_priority: Option<Priority>,
+    /// Who changes are attributed to, on a shared list.
+    actor: Option<String>,
+}
+
+impl<'a> TaskManager<'a> {
+    /// Manages `tasks` with the ID strategy, date order, default priority,
+    /// and identity of `config`.
+    pub fn new(tasks: &'a mut Vec<Task>, config: &Config) -> Self {
+        TaskManager {
+            tasks,
+            ids: config.id_strategy.generator(),
+            reserved_ids: Vec::new(),
+            date_order: config.date_order,
+            default_priority: config.default_priority,
+            actor: config.actor(),
+        }
+    }
+
+    /// Also keeps new tasks off `ids`, the IDs of tasks of the list that
+    /// weren't loaded.
+    pub fn reserving(mut self, ids: Vec<u32>) -> Self {
+        self.reserved_ids = ids;
+        self
+    }
+
+    /// Returns an ID not used by any task, loaded or reserved.
+    fn next_id(&mut self) -> u32 {
+        let used: HashSet<u32> = self
+            .tasks
+            .iter()
+            .map(|t| t.id)
+            .chain(self.reserved_ids.iter().copied())
+            .collect();
+        self.ids.next_unused_id(&used)
+    }
+
+    /// Returns all tasks.
+    pub fn tasks(&self) -> &[Task] {
+        self.tasks
+    }
+
+    /// Returns the task with the given ID.
+    pub fn get(&self, id: u32) -> Result<&Task> {
+        self.tasks
+            .iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::task_not_found(id, self.tasks.iter()))
+    }
+
+    /// Returns the task to change, marking it as changed now, and by whom
+    /// on a shared list.
+    fn get_mut(&mut self, id: u32) -> Result<&mut Task> {
+        match self.tasks.iter().position(|t| t.id == id) {
+            Some(position) => {
+                let task = &mut self.tasks[position];
+                task.updated_at = Some(clock::now());
+                if self.actor.is_some() {
+                    task.updated_by = self.actor.clone();
+                }
+                Ok(task)
+            }
+            None => Err(AppError::task_not_found(id, self.tasks.iter())),
+        }
+    }
+
+    /// Resolves a due date given on the command line against today.
+    fn settle(&self, spec: Option<DueSpec>) -> Result<Option<NaiveDate>> {
+        let today = clock::today();
+        spec.map(|d| d.with_order(self.date_order))
+            .transpose()
+            .map_err(AppError::InvalidArgument)
+            .map(|d| d.and_then(|d| d.resolve(today)))
+    }
+
+    /// Resolves a due date and time given on the command line against now.
+    fn settle_due(&self, due: Option<DueAt>) -> Result<Option<(NaiveDate, Option<NaiveTime>)>> {
+        let now = clock::now_naive();
+        due.map(|d| d.with_order(self.date_order))
+            .transpose()
+            .map_err(AppError::InvalidArgument)
+            .map(|d| d.and_then(|d| d.resolve(now)))
+    }
+
+    /// Adds a task and returns it.
+    ///
+    /// Recurring tasks start on the first due date that isn't an exception.
+    pub fn add(&mut self, args: NewTask) -> Result<&Task> {
+        let due = self.settle_due(args.due)?;
+        let mut due_date = due.map(|(date, _)| date);
+        let recurrence = match args.recurrence {
+            Some(mut recurrence) => {
+                if let Some(date) = due_date {
+                    recurrence.anchor(date);
+                }
+                due_date = due_date.and_then(|d| recurrence.first_on_or_after(d));
+                if due_date.is_none() {
+                    return Err(AppError::InvalidArgument(tr!(
+                        "error-recurrence-no-occurrence"
+                    )));
+                }
+                Some(recurrence)
+            }
+            None => None,
+        };
+
+        let parent = args
+            .parent
+            .map(|id| self.get(id).map(|t| t.uuid))
+            .transpose()?;
+        let new_id = self.next_id();
+        let mut new_task = Task::new(new_id, args.description, due_date);
+        new_task.due_time = due.and_then(|(_, time)| time);
+        new_task.project = args.project;
+        new_task.priority = args.priority.or(self.default_priority);
+        new_task.expires_on = self.settle(args.expires)?;
+        new_task.start_date = self.settle(args.start)?;
+        new_task.tags = args.tags;
+        new_task.contexts = args.contexts;
+        new_task.estimate = args.estimate;
+        new_task.remind_before_minutes = args.remind_before;
+        new_task.recurrence = recurrence;
+        new_task.url = args.url;
+        new_task.assignee = args.assignee;
+        new_task.parent = parent;
+        new_task.goal = args.goal;
+        new_task.created_by = self.actor.clone();
+        for note in args.notes {
+            new_task.add_note(note);
+        }
+        info!("Adding new task: {:?}", new_task);
+        self.tasks.push(new_task);
+        Ok(&self.tasks[self.tasks.len() - 1])
+    }
+
+    /// Adds `count` copies of a task's description, tags, project, and
+    /// priority, due at `due` or when the original is. Returns the new IDs.
+    pub fn duplicate(&mut self, id: u32, due: Option<DueAt>, count: u32) -> Result<Vec<u32>> {
+        let original = self.get(id)?.clone();
+        let (due_date, due_time) = match self.settle_due(due)? {
+            Some((date, time)) => (Some(date), time),
+            None if due.is_some() => (None, None),
+            None => (original.due_date, original.due_time),
+        };
+        let mut ids = Vec::new();
+        for _ in 0..count {
+            let mut copy = Task::new(self.next_id(), original.description.clone(), due_date);
+            copy.due_time = due_time;
+            copy.project = original.project.clone();
+            copy.priority = original.priority;
+            copy.tags = original.tags.clone();
+            copy.contexts = original.contexts.clone();
+            copy.created_by = self.actor.clone();
+            ids.push(copy.id);
+            self.tasks.push(copy);
+        }
+        info!("Duplicated task ID {} as {:?}.", id, ids);
+        Ok(ids)
+    }
+
+    /// Returns the page of tasks `args` asks for: filtered, sorted, and
+    /// paged, but not grouped.
+    pub fn list(&self, args: &TaskSelection) -> Result<ListPage<'_>> {
+        let query = args
+            .query
+            .clone()
+            .map(|q| q.with_order(self.date_order))
+            .transpose()
+            .map_err(AppError::InvalidArgument)?;
+        let today = clock::today();
+        let mut selected: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|t| {
+                if args.waiting {
+                    t.is_waiting(today)
+                } else {
+                    args.all || (t.is_pending() && !t.is_waiting(today))
+                }
+            })
+            .filter(|t| {
+                args.project
+                    .as_ref()
+                    .is_none_or(|p| t.project.as_ref() == Some(p))
+            })
+            .filter(|t| {
+                args.assignee
+                    .as_ref()
+                    .is_none_or(|a| t.assignee.as_ref() == Some(a))
+            })
+            .filter(|t| args.filter.as_ref().is_none_or(|f| f.matches(t)))
+            .filter(|t| query.as_ref().is_none_or(|q| q.matches(t, today)))
+            .filter(|t| !args.snoozed || t.snooze_count > 0)
+            .collect();
+        if let Some(key) = args.sort {
+            sort_tasks(&mut selected, key, args.reverse);
+        } else if args.snoozed {
+            selected.sort_by_key(|t| std::cmp::Reverse(t.snooze_count));
+            if args.reverse {
+                selected.reverse();
+            }
+        } else if args.reverse {
+            selected.reverse();
+        }
+
+        let matching = selected.len();
+        let tasks = selected
+            .into_iter()
+            .skip(args.offset)
+            .take(args.limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(ListPage { tasks, matching })
+    }
+
+    /// Returns how many tasks satisfy every condition of `args`.
+    pub fn count(&self, args: &CountSelection) -> Result<usize> {
+        let due = self.settle(args.due)?;
+        let now = clock::now_naive();
+        let selection = TaskSelection {
+            query: args.query.clone(),
+            all: args.all,
+            project: args.project.clone(),
+            ..TaskSelection::default()
+        };
+        Ok(self
+            .list(&selection)?
+            .tasks
+            .into_iter()
+            .filter(|t| !args.overdue || t.is_overdue(now))
+            .filter(|t| due.is_none_or(|d| t.due_date == Some(d)))
+            .filter(|t| args.tags.iter().all(|tag| t.tags.contains(tag)))
+            .count())
+    }
+
+    /// Counts the overdue, due, and pending tasks as of now.
+    pub fn status(&self) -> StatusSummary {
+        let now = clock::now_naive();
+        let today = now.date();
+        let pending = || {
+            self.tasks
+                .iter()
+                .filter(move |t| t.is_pending() && !t.is_waiting(today))
+        };
+        StatusSummary {
+            overdue: pending().filter(|t| t.is_overdue(now)).count(),
+            due_today: pending()
+                .filter(|t| t.due_date == Some(today) && !t.is_overdue(now))
+                .count(),
+            pending: pending().count(),
+        }
+    }
+
+    /// Marks a task as completed.
+    ///
+    /// Completing a recurring task schedules its next occurrence, which is
+    /// returned.
+    pub fn complete(&mut self, id: u32) -> Result<Option<&Task>> {
+        let next_id = self.next_id();
+        let actor = self.actor.clone();
+        let task = self.get_mut(id).inspect_err(|_| {
+            error!("Attempted to complete non-existent task ID: {}", id);
+        })?;
+        if !task.is_done() {
+            task.completed_by = actor.clone();
+        }
+        task.mark_completion(true);
+        match task.spawn_next_occurrence(next_id) {
+            Some(mut next) => {
+                next.created_by = actor;
+                info!("Scheduled next occurrence: {:?}", next);
+                self.tasks.push(next);
+                Ok(self.tasks.last())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Marks a completed task as pending again.
+    pub fn reopen(&mut self, id: u32) -> Result<()> {
+        self.get_mut(id)
+            .inspect_err(|_| error!("Attempted to reopen non-existent task ID: {}", id))?
+            .mark_completion(false);
+        Ok(())
+    }
+
+    /// Moves a task to `status`. Moving it to done completes it as
+    /// [`complete`](Self::complete) does, and returns the next occurrence.
+    pub fn move_to(&mut self, id: u32, status: TaskStatus) -> Result<Option<&Task>> {
+        if status == TaskStatus::Done {
+            return self.complete(id);
+        }
+        self.get_mut(id)
+            .inspect_err(|_| error!("Attempted to move non-existent task ID: {}", id))?
+            .set_status(status);
+        Ok(None)
+    }
+
+    /// Takes a task out of the list and returns it.
+    pub fn remove(&mut self, id: u32) -> Result<Task> {
+        let Some(position) = self.tasks.iter().position(|t| t.id == id) else {
+            error!("Attempted to remove non-existent task ID: {}", id);
+            return Err(AppError::task_not_found(id, self.tasks.iter()));
+        };
+        Ok(self.tasks.remove(position))
+    }
+
+    /// Folds the task `source` into `target` and takes it out of the list,
+    /// returning it.
+    ///
+    /// The target gets the tags, notes, annotations, attachments, and
+    /// tracked time of both, the earlier due date and creation time, and the
+    /// higher priority. Fields only the source has set, like its project or
+    /// goal, carry over. Subtasks of the source become subtasks of the
+    /// target.
+    pub fn merge(&mut self, source: u32, target: u32) -> Result<Task> {
+        if source == target {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-merge-into-itself",
+                id = source
+            )));
+        }
+        self.get(target)?;
+        let source = self.remove(source)?;
+        let target_uuid = self.get(target)?.uuid;
+        for task in self.tasks.iter_mut() {
+            if task.parent == Some(source.uuid) {
+                task.parent = Some(target_uuid);
+            }
+        }
+
+        let task = self.get_mut(target)?;
+        if task.parent == Some(target_uuid) {
+            task.parent = source.parent;
+        }
+        for tag in &source.tags {
+            if !task.tags.contains(tag) {
+                task.tags.push(tag.clone());
+            }
+        }
+        for context in &source.contexts {
+            if !task.contexts.contains(context) {
+                task.contexts.push(context.clone());
+            }
+        }
+        let source_due = source.due_date.map(|date| (date, source.due_time));
+        let target_due = task.due_date.map(|date| (date, task.due_time));
+        if let Some((date, time)) = match (source_due, target_due) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        } {
+            task.due_date = Some(date);
+            task.due_time = time;
+        }
+        task.created_at = task.created_at.min(source.created_at);
+        task.priority = task.priority.max(source.priority);
+        task.notes.extend(source.notes.iter().cloned());
+        task.annotations.extend(source.annotations.iter().cloned());
+        task.annotations.sort_by_key(|a| a.created_at);
+        for attachment in &source.attachments {
+            if !task
+                .attachments
+                .iter()
+                .any(|a| a.target == attachment.target)
+            {
+                task.attachments.push(attachment.clone());
+            }
+        }
+        task.intervals.extend(source.intervals.iter().cloned());
+        task.intervals.sort_by_key(|i| i.start);
+        task.project = task.project.take().or_else(|| source.project.clone());
+        task.assignee = task.assignee.take().or_else(|| source.assignee.clone());
+        task.url = task.url.take().or_else(|| source.url.clone());
+        task.goal = task.goal.or(source.goal);
+        task.estimate = task.estimate.or(source.estimate);
+        task.start_date = task.start_date.or(source.start_date);
+        task.expires_on = task.expires_on.or(source.expires_on);
+        task.recurrence = task.recurrence.take().or_else(|| source.recurrence.clone());
+        task.annotate(format!(
+            "Merged task {} '{}' into this one.",
+            source.id, source.description
+        ));
+        info!("Merged task ID {} into task ID {}.", source.id, target);
+        Ok(source)
+    }
+
+    /// Makes `changes` to a task and returns the fields whose value changed.
+    pub fn edit(&mut self, id: u32, changes: TaskChanges) -> Result<Vec<TaskField>> {
+        let due = match changes.due {
+            Some(due) => Some(self.settle_due(due)?),
+            None => None,
+        };
+        let start_date = match changes.start {
+            Some(start) => Some(self.settle(start)?),
+            None => None,
+        };
+        let parent = match changes.parent {
+            Some(Some(parent)) => Some(Some(self.parent_for(id, parent)?)),
+            Some(None) => Some(None),
+            None => None,
+        };
+        let task = self.get_mut(id)?;
+        let before = task.clone();
+
+        if let Some(description) = changes.description {
+            task.description = description;
+        }
+        if let Some(due) = due {
+            task.due_date = due.map(|(date, _)| date);
+            task.due_time = due.and_then(|(_, time)| time);
+        }
+        if let Some(start_date) = start_date {
+            task.start_date = start_date;
+        }
+        if let Some(project) = changes.project {
+            task.project = project;
+        }
+        if let Some(priority) = changes.priority {
+            task.priority = priority;
+        }
+        if let Some(url) = changes.url {
+            task.url = url;
+        }
+        if let Some(assignee) = changes.assignee {
+            task.assignee = assignee;
+        }
+        if let Some(parent) = parent {
+            task.parent = parent;
+        }
+        if let Some(goal) = changes.goal {
+            task.goal = goal;
+        }
+        task.tags.retain(|t| !changes.remove_tags.contains(t));
+        for tag in changes.add_tags {
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
+        }
+        task.contexts
+            .retain(|c| !changes.remove_contexts.contains(c));
+        for context in changes.add_contexts {
+            if !task.contexts.contains(&context) {
+                task.contexts.push(context);
+            }
+        }
+
+        let changed: Vec<TaskField> = [
+            (
+                TaskField::Description,
+                task.description != before.description,
+            ),
+            (
+                TaskField::Due,
+                (task.due_date, task.due_time) != (before.due_date, before.due_time),
+            ),
+            (TaskField::Start, task.start_date != before.start_date),
+            (TaskField::Project, task.project != before.project),
+            (TaskField::Priority, task.priority != before.priority),
+            (TaskField::Tags, task.tags != before.tags),
+            (TaskField::Contexts, task.contexts != before.contexts),
+            (TaskField::Url, task.url != before.url),
+            (TaskField::Assignee, task.assignee != before.assignee),
+            (TaskField::Parent, task.parent != before.parent),
+            (TaskField::Goal, task.goal != before.goal),
+        ]
+        .into_iter()
+        .filter_map(|(field, differs)| differs.then_some(field))
+        .collect();
+        if !changed.is_empty() {
+            info!("Edited task ID {}: {:?}", id, changed);
+        }
+        Ok(changed)
+    }
+
+    /// Returns the UUID of task `parent`, checking that task `id` can become
+    /// its subtask: a task can't be a subtask of itself or of its own
+    /// subtasks.
+    fn parent_for(&self, id: u32, parent: u32) -> Result<Uuid> {
+        let child = self.get(id)?.uuid;
+        let parent_task = self.get(parent)?;
+        // Bounded, so a loop already in the file can't hang the walk.
+        let mut ancestors = std::iter::successors(Some(parent_task), |task| {
+            let uuid = task.parent?;
+            self.tasks.iter().find(|t| t.uuid == uuid)
+        })
+        .take(self.tasks.len());
+        if ancestors.any(|task| task.uuid == child) {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-subtask-cycle",
+                id = id,
+                parent = parent
+            )));
+        }
+        Ok(parent_task.uuid)
+    }
+
+    /// Records how far along a task is, in percent.
+    pub fn set_progress(&mut self, id: u32, percent: u8) -> Result<()> {
+        let task = self.get(id)?;
+        if subtasks(task, self.tasks).next().is_some() {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-progress-has-subtasks",
+                id = id
+            )));
+        }
+        self.get_mut(id)?.progress = Some(percent);
+        info!("Set the progress of task ID {} to {}%.", id, percent);
+        Ok(())
+    }
+
+    /// Gives a task a new due date and returns it, dropping the time of day
+    /// if the date changes. Reminders and escalations start over, since they
+    /// were about the old due date.
+    pub fn reschedule(&mut self, id: u32, due: DueSpec) -> Result<NaiveDate> {
+        let due = self
+            .settle(Some(due))?
+            .ok_or_else(|| AppError::InvalidArgument(tr!("error-due-out-of-range")))?;
+        let task = self.get_mut(id)?;
+        task.set_due_date(Some(due));
+        task.reminded_on = None;
+        task.escalations_sent = 0;
+        info!("Rescheduled task ID {} to {}.", id, due);
+        Ok(due)
+    }
+
+    /// Pushes the due date of a pending task back and returns the new one.
+    pub fn snooze(&mut self, id: u32, until: SnoozeSpec) -> Result<NaiveDate> {
+        let until = until
+            .with_order(self.date_order)
+            .map_err(AppError::InvalidArgument)?;
+        let today = clock::today();
+        let task = self.get_mut(id)?;
+        if !task.is_pending() {
+            return Err(AppError::InvalidArgument(tr!("error-not-pending", id = id)));
+        }
+        let due = until
+            .resolve(task.due_date, today)
+            .ok_or_else(|| AppError::InvalidArgument(tr!("error-snooze-out-of-range")))?;
+        if task.due_date.is_some_and(|current| due <= current) || due < today {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-snooze-not-later",
+                id = id,
+                date = task.due_date.map_or(today, |d| d.max(today)).to_string()
+            )));
+        }
+        task.snooze(due);
+        info!("Snoozed task ID {} until {}.", id, due);
+        Ok(due)
+    }
+
+    /// Records that a task waits on `what`, keeping it out of the default
+    /// list until `until` if given.
+    pub fn wait(&mut self, id: u32, what: String, until: Option<DueSpec>) -> Result<()> {
+        let until = self.settle(until)?;
+        let task = self.get_mut(id)?;
+        task.waiting_on = Some(what);
+        if until.is_some() {
+            task.start_date = until;
+        }
+        info!("Task ID {} is waiting until {:?}.", id, until);
+        Ok(())
+    }
+
+    /// Stops a task waiting: clears what it waits on, and a start date that
+    /// hasn't come yet so it shows up again right away.
+    pub fn stop_waiting(&mut self, id: u32) -> Result<()> {
+        let today = clock::today();
+        let task = self.get_mut(id)?;
+        task.waiting_on = None;
+        if task.start_date.is_some_and(|start| start > today) {
+            task.start_date = None;
+        }
+        info!("Task ID {} is no longer waiting.", id);
+        Ok(())
+    }
+
+    /// Adds reminders to a task, one for each of `alerts`.
+    ///
+    /// # Returns
+    ///
+    /// An `AppError::InvalidArgument` if a reminder is in the past, or is
+    /// before the due date of a task without one.
+    pub fn remind_at(&mut self, id: u32, alerts: Vec<AlertSpec>) -> Result<()> {
+        let now = clock::now();
+        let local_now = clock::now_naive();
+        let date_order = self.date_order;
+        let task = self.get_mut(id)?;
+        for spec in alerts {
+            let alert = spec
+                .with_order(date_order)
+                .map_err(AppError::InvalidArgument)?
+                .resolve(local_now)
+                .ok_or_else(|| AppError::InvalidArgument(tr!("error-reminder-out-of-range")))?;
+            match alert.time(task) {
+                None => {
+                    return Err(AppError::InvalidArgument(tr!(
+                        "error-no-due-to-remind-before",
+                        id = id
+                    )));
+                }
+                Some(at) if at <= now => {
+                    return Err(AppError::InvalidArgument(tr!(
+                        "error-reminder-in-past",
+                        reminder = alert.to_string()
+                    )));
+                }
+                Some(_) => {}
+            }
+            if !task.alerts.contains(&alert) {
+                task.alerts.push(alert);
+            }
+        }
+        info!("Task ID {} now has {} reminders.", id, task.alerts.len());
+        Ok(())
+    }
+
+    /// Removes the reminders set on a task with [`remind_at`](Self::remind_at).
+    pub fn clear_alerts(&mut self, id: u32) -> Result<()> {
+        let task = self.get_mut(id)?;
+        task.alerts.clear();
+        task.alerted_at = None;
+        info!("Cleared the reminders of task ID {}.", id);
+        Ok(())
+    }
+
+    /// Appends a note to a task.
+    pub fn note(&mut self, id: u32, text: String) -> Result<()> {
+        self.get_mut(id)?.add_note(text);
+        info!("Added note to task ID {}.", id);
+        Ok(())
+    }
+
+    /// Records an annotation in the history of a task.
+    pub fn annotate(&mut self, id: u32, text: String) -> Result<()> {
+        self.get_mut(id)?.annotate(text);
+        info!("Annotated task ID {}.", id);
+        Ok(())
+    }
+
+    /// Attaches `target`, a file path or URL, to a task, unless it is
+    /// attached already. Returns how many attachments the task has.
+    pub fn attach(&mut self, id: u32, target: String) -> Result<usize> {
+        let task = self.get_mut(id)?;
+        if !task.attachments.iter().any(|a| a.target == target) {
+            info!("Attached {} to task ID {}.", target, id);
+            task.attachments.push(Attachment {
+                target,
+                added_at: clock::now(),
+            });
+        }
+        Ok(task.attachments.len())
+    }
+
+    /// Appends an item to a task's checklist and returns its number.
+    pub fn check_add(&mut self, id: u32, text: String) -> Result<usize> {
+        let task = self.get_mut(id)?;
+        task.checklist.push(ChecklistItem { text, done: false });
+        info!("Added checklist item to task ID {}.", id);
+        Ok(task.checklist.len())
+    }
+
+    /// Checks or unchecks item `number` of a task's checklist.
+    pub fn check_mark(&mut self, id: u32, number: usize, done: bool) -> Result<&ChecklistItem> {
+        let item = checklist_item(self.get_mut(id)?, number)?;
+        item.done = done;
+        Ok(&*item)
+    }
+
+    /// Takes item `number` off a task's checklist and returns it.
+    pub fn check_remove(&mut self, id: u32, number: usize) -> Result<ChecklistItem> {
+        let task = self.get_mut(id)?;
+        checklist_item(task, number)?;
+        Ok(task.checklist.remove(number - 1))
+    }
+
+    /// Moves a recurring task to its next occurrence without completing it.
+    pub fn skip(&mut self, id: u32) -> Result<Skipped> {
+        let task = self.get_mut(id)?;
+        if task.recurrence.is_none() {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-not-recurring",
+                id = id
+            )));
+        }
+
+        match task.skip_occurrence() {
+            Some(next_due) => {
+                info!("Skipped task ID {} to {}.", id, next_due);
+                Ok(Skipped::Next(next_due))
+            }
+            None => {
+                task.recurrence = None;
+                task.cancel();
+                Ok(Skipped::Ended)
+            }
+        }
+    }
+}
+
+/// Returns item `number`, counted from 1, of the checklist of `task`.
+fn checklist_item(task: &mut Task, number: usize) -> Result<&mut ChecklistItem> {
+    let (id, count) = (task.id, task.checklist.len());
+    number
+        .checked_sub(1)
+        .and_then(|i| task.checklist.get_mut(i))
+        .ok_or_else(|| {
+            AppError::InvalidArgument(match count {
+                0 => tr!("error-no-checklist", id = id),
+                n => tr!(
+                    "error-no-checklist-item",
+                    id = id,
+                    count = n,
+                    number = number
+                ),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn due(s: &str) -> Option<DueAt> {
+        Some(s.parse().unwrap())
+    }
+
+    /// Adds a task for each description, returning the list.
+    fn tasks(descriptions: &[&str]) -> Vec<Task> {
+        let mut tasks = Vec::new();
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        for description in descriptions {
+            manager.add(NewTask::new(*description)).unwrap();
+        }
+        tasks
+    }
+
+    fn ids(page: &ListPage) -> Vec<u32> {
+        page.tasks.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn add_gives_each_task_an_unused_id() {
+        let mut tasks = tasks(&["one", "two", "three"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        manager.remove(2).unwrap();
+        let added = manager.add(NewTask::new("four")).unwrap().id;
+        assert!(![1, 3].contains(&added));
+    }
+
+    #[test]
+    fn add_keeps_off_reserved_ids() {
+        let mut tasks = Vec::new();
+        let mut manager = TaskManager::new(&mut tasks, &Config::default()).reserving(vec![1, 2]);
+        assert_eq!(manager.add(NewTask::new("new")).unwrap().id, 3);
+    }
+
+    #[test]
+    fn add_falls_back_to_the_default_priority() {
+        let config = Config {
+            default_priority: Some(Priority::Medium),
+            ..Config::default()
+        };
+        let mut tasks = Vec::new();
+        let mut manager = TaskManager::new(&mut tasks, &config);
+        let plain = manager.add(NewTask::new("plain")).unwrap().priority;
+        let urgent = NewTask {
+            priority: Some(Priority::High),
+            ..NewTask::new("urgent")
+        };
+        let urgent = manager.add(urgent).unwrap().priority;
+        assert_eq!(plain, Some(Priority::Medium));
+        assert_eq!(urgent, Some(Priority::High));
+    }
+
+    #[test]
+    fn add_starts_a_series_on_its_first_occurrence() {
+        let mut recurrence: Recurrence = "weekly".parse().unwrap();
+        recurrence.except = vec![date(2030, 1, 7)];
+        let new = NewTask {
+            due: due("2030-01-07"),
+            recurrence: Some(recurrence),
+            ..NewTask::new("standup")
+        };
+        let mut tasks = Vec::new();
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert_eq!(manager.add(new).unwrap().due_date, Some(date(2030, 1, 14)));
+    }
+
+    #[test]
+    fn add_rejects_an_unknown_parent() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        let new = NewTask {
+            parent: Some(7),
+            ..NewTask::new("orphan")
+        };
+        assert!(matches!(
+            manager.add(new),
+            Err(AppError::TaskNotFound { id: 7, .. })
+        ));
+        assert_eq!(manager.tasks().len(), 1);
+    }
+
+    #[test]
+    fn complete_marks_the_task_done() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert!(manager.complete(1).unwrap().is_none());
+        let task = manager.get(1).unwrap();
+        assert!(task.is_done());
+        assert!(task.completed_at.is_some());
+    }
+
+    #[test]
+    fn complete_schedules_the_next_occurrence() {
+        let new = NewTask {
+            due: due("2030-01-31"),
+            recurrence: Some("monthly".parse().unwrap()),
+            ..NewTask::new("rent")
+        };
+        let mut tasks = Vec::new();
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        manager.add(new).unwrap();
+        let next = manager.complete(1).unwrap().unwrap();
+        assert_eq!((next.id, next.due_date), (2, Some(date(2030, 2, 28))));
+        assert!(next.is_pending());
+    }
+
+    #[test]
+    fn complete_reports_a_missing_task() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert!(matches!(
+            manager.complete(2),
+            Err(AppError::TaskNotFound { id: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn remove_takes_the_task_out() {
+        let mut tasks = tasks(&["one", "two"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert_eq!(manager.remove(1).unwrap().description, "one");
+        assert_eq!(manager.tasks().len(), 1);
+        assert!(manager.remove(1).is_err());
+    }
+
+    #[test]
+    fn edit_returns_only_the_fields_that_changed() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        let changes = TaskChanges {
+            description: Some("one".to_string()),
+            project: Some(Some("home".to_string())),
+            add_tags: vec!["errand".to_string()],
+            ..TaskChanges::default()
+        };
+        let changed = manager.edit(1, changes).unwrap();
+        assert_eq!(changed, vec![TaskField::Project, TaskField::Tags]);
+        let task = manager.get(1).unwrap();
+        assert_eq!(task.project.as_deref(), Some("home"));
+        assert_eq!(task.tags, ["errand"]);
+    }
+
+    #[test]
+    fn edit_clears_fields_given_as_none() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        let set = TaskChanges {
+            due: Some(due("2030-05-01")),
+            priority: Some(Some(Priority::High)),
+            ..TaskChanges::default()
+        };
+        manager.edit(1, set).unwrap();
+        let clear = TaskChanges {
+            due: Some(None),
+            priority: Some(None),
+            ..TaskChanges::default()
+        };
+        let changed = manager.edit(1, clear).unwrap();
+        assert_eq!(changed, vec![TaskField::Due, TaskField::Priority]);
+        let task = manager.get(1).unwrap();
+        assert_eq!((task.due_date, task.priority), (None, None));
+        assert!(manager.edit(1, TaskChanges::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn edit_refuses_to_make_a_task_part_of_itself() {
+        let mut tasks = tasks(&["parent", "child"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        let under = |id| TaskChanges {
+            parent: Some(Some(id)),
+            ..TaskChanges::default()
+        };
+        assert_eq!(manager.edit(2, under(1)).unwrap(), vec![TaskField::Parent]);
+        assert!(manager.edit(1, under(2)).is_err());
+        assert!(manager.edit(1, under(1)).is_err());
+    }
+
+    #[test]
+    fn list_leaves_out_finished_tasks_unless_asked() {
+        let mut tasks = tasks(&["one", "two", "three"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        manager.complete(2).unwrap();
+        let pending = manager.list(&TaskSelection::default()).unwrap();
+        assert_eq!(ids(&pending), [1, 3]);
+        let all = TaskSelection {
+            all: true,
+            ..TaskSelection::default()
+        };
+        assert_eq!(ids(&manager.list(&all).unwrap()), [1, 2, 3]);
+    }
+
+    #[test]
+    fn list_filters_by_project_and_pages() {
+        let mut tasks = tasks(&["one", "two", "three", "four"]);
+        for task in tasks.iter_mut().filter(|t| t.id != 2) {
+            task.project = Some("work".to_string());
+        }
+        let manager = TaskManager::new(&mut tasks, &Config::default());
+        let selection = TaskSelection {
+            project: Some("work".to_string()),
+            reverse: true,
+            offset: 1,
+            limit: Some(1),
+            ..TaskSelection::default()
+        };
+        let page = manager.list(&selection).unwrap();
+        assert_eq!(ids(&page), [3]);
+        assert_eq!(page.matching, 3);
+    }
+
+    #[test]
+    fn count_needs_every_condition() {
+        let mut tasks = tasks(&["one", "two", "three"]);
+        tasks[0].due_date = Some(date(2000, 1, 1));
+        tasks[0].tags = vec!["a".to_string(), "b".to_string()];
+        tasks[1].due_date = Some(date(2000, 1, 1));
+        tasks[1].tags = vec!["a".to_string()];
+        let manager = TaskManager::new(&mut tasks, &Config::default());
+        let overdue = CountSelection {
+            overdue: true,
+            ..CountSelection::default()
+        };
+        assert_eq!(manager.count(&overdue).unwrap(), 2);
+        let tagged = CountSelection {
+            tags: vec!["a".to_string(), "b".to_string()],
+            ..overdue
+        };
+        assert_eq!(manager.count(&tagged).unwrap(), 1);
+    }
+
+    #[test]
+    fn status_counts_overdue_and_pending_tasks() {
+        let mut tasks = tasks(&["one", "two", "three"]);
+        tasks[0].due_date = Some(date(2000, 1, 1));
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        manager.complete(3).unwrap();
+        let status = manager.status();
+        assert_eq!((status.overdue, status.pending), (1, 2));
+    }
+
+    #[test]
+    fn merge_folds_the_source_into_the_target() {
+        let mut tasks = tasks(&["one", "two"]);
+        tasks[0].tags = vec!["a".to_string()];
+        tasks[0].priority = Some(Priority::High);
+        tasks[1].tags = vec!["b".to_string()];
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert!(manager.merge(1, 1).is_err());
+        assert_eq!(manager.merge(1, 2).unwrap().id, 1);
+        let target = manager.get(2).unwrap();
+        assert_eq!(target.tags, ["b", "a"]);
+        assert_eq!(target.priority, Some(Priority::High));
+        assert_eq!(manager.tasks().len(), 1);
+    }
+
+    #[test]
+    fn skip_needs_a_recurring_task() {
+        let mut tasks = tasks(&["one"]);
+        let mut manager = TaskManager::new(&mut tasks, &Config::default());
+        assert!(matches!(manager.skip(1), Err(AppError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,204 @@
+//! `mcp`: a Model Context Protocol server, so AI assistants can manage the
+//! task list through a standard interface.
+//!
+//! The server speaks JSON-RPC over standard input and output, like `rpc`, and
+//! offers four tools: `list_tasks`, `add_task`, `complete_task`, and
+//! `search_tasks`. Each runs the command of the same name against the data
+//! file, so hooks, the journal, and every other part of saving apply as
+//! usual. A client is set up with the command alone, e.g.:
+//!
+//! ```json
+//! { "mcpServers": { "tasks": { "command": "tm", "args": ["mcp"] } } }
+//! ```
+
+use serde_json::{Value, json};
+
+use crate::app::{BIN_NAME, INVALID_PARAMS, JsonSink, METHOD_NOT_FOUND, RpcError, RpcRequest};
+
+/// The protocol revision offered to clients that don't ask for one.
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Answers one MCP request, running the command line a tool call stands for
+/// through `run`.
+///
+/// A failed command is reported in the tool result, for the assistant to
+/// read; a malformed call is an error of the request.
+pub fn answer_mcp(
+    request: &RpcRequest,
+    run: impl FnOnce(&[String]) -> std::result::Result<JsonSink, RpcError>,
+) -> std::result::Result<Value, RpcError> {
+    match request.method.as_str() {
+        "initialize" => {
+            let version = request
+                .params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(MCP_PROTOCOL_VERSION);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": BIN_NAME, "version": env!("CARGO_PKG_VERSION") },
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let name = request
+                .params
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing tool name"))?;
+            let arguments = request.params.get("arguments").unwrap_or(&Value::Null);
+            let args = tool_command(name, arguments)?;
+            Ok(match run(&args) {
+                Ok(sink) => tool_result(&output_text(&sink), false),
+                Err(error) => tool_result(&error.message, true),
+            })
+        }
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method `{}`", method),
+        )),
+    }
+}
+
+/// Describes the tools with JSON schemas of their arguments.
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_tasks",
+            "description": "List tasks as JSON, pending ones unless `all` is set.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "A query such as `project:web and due < 2025-06-01`.",
+                    },
+                    "project": { "type": "string" },
+                    "all": { "type": "boolean", "description": "Include completed tasks." },
+                },
+            },
+        },
+        {
+            "name": "add_task",
+            "description": "Add a task, returning its ID.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "description": { "type": "string" },
+                    "due": {
+                        "type": "string",
+                        "description": "A date such as `2025-06-01`, `tomorrow`, or `friday 17:00`.",
+                    },
+                    "project": { "type": "string" },
+                    "priority": { "type": "string", "enum": ["low", "medium", "high"] },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["description"],
+            },
+        },
+        {
+            "name": "complete_task",
+            "description": "Mark the task with the given ID as done.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "integer", "minimum": 1 } },
+                "required": ["id"],
+            },
+        },
+        {
+            "name": "search_tasks",
+            "description": "Find tasks, completed ones included, whose description, notes, project, or tags contain every word of the query.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+                "required": ["query"],
+            },
+        },
+    ])
+}
+
+/// Returns the command line the call of tool `name` with `arguments` runs.
+fn tool_command(name: &str, arguments: &Value) -> std::result::Result<Vec<String>, RpcError> {
+    let text = |key: &str| arguments.get(key).and_then(Value::as_str);
+    let required = |key: &str| {
+        text(key).ok_or_else(|| {
+            RpcError::new(INVALID_PARAMS, format!("{} needs a `{}` string", name, key))
+        })
+    };
+    let mut args = vec![
+        name.trim_end_matches("_tasks")
+            .trim_end_matches("_task")
+            .to_string(),
+    ];
+    match name {
+        "list_tasks" => {
+            if arguments.get("all").and_then(Value::as_bool) == Some(true) {
+                args.push("--all".to_string());
+            }
+            if let Some(project) = text("project") {
+                args.extend(["--project".to_string(), project.to_string()]);
+            }
+            if let Some(query) = text("query") {
+                args.extend(["--".to_string(), query.to_string()]);
+            }
+        }
+        "add_task" => {
+            let description = required("description")?;
+            for key in ["due", "project", "priority"] {
+                if let Some(value) = text(key) {
+                    args.extend([format!("--{}", key), value.to_string()]);
+                }
+            }
+            for tag in arguments
+                .get("tags")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                args.extend(["--tag".to_string(), tag.to_string()]);
+            }
+            args.extend([
+                "--literal".to_string(),
+                "--".to_string(),
+                description.to_string(),
+            ]);
+        }
+        "complete_task" => {
+            let id = arguments.get("id").and_then(Value::as_u64).ok_or_else(|| {
+                RpcError::new(INVALID_PARAMS, "complete_task needs an integer `id`")
+            })?;
+            args.push(id.to_string());
+        }
+        "search_tasks" => {
+            args.push("--".to_string());
+            args.extend(required("query")?.split_whitespace().map(str::to_string));
+        }
+        _ => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("unknown tool `{}`", name),
+            ));
+        }
+    }
+    Ok(args)
+}
+
+/// Returns what a command printed: its values as JSON if it produced any,
+/// its lines otherwise.
+fn output_text(sink: &JsonSink) -> String {
+    match sink.values.as_slice() {
+        [] => sink.lines.join("\n"),
+        [value] => serde_json::to_string_pretty(value).unwrap_or_default(),
+        values => serde_json::to_string_pretty(values).unwrap_or_default(),
+    }
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
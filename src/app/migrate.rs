@@ -0,0 +1,137 @@
+//! Upgrades task lists written by older versions to the current layout.
+//!
+//! Every task list records the [`SCHEMA_VERSION`] it was written with; files
+//! from before versioning count as version 0. A list that is older, or that
+//! no longer reads as a [`TaskList`], is read again as a plain document and
+//! passed through the [`MIGRATIONS`] between its version and the current
+//! one, so renamed and newly required fields are settled before the tasks
+//! are built. The next save writes the upgraded layout.
+//!
+//! Lists written by a newer version are refused rather than guessed at.
+//! Event logs store each task on its own and are read as they are.
+
+use log::info;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::app::{AppError, Result, TaskList, TaskStatus, clock};
+
+/// The layout version written by this build.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A step upgrading a task list document from one version to the next.
+pub type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// The steps from every older version, `MIGRATIONS[n]` upgrading version
+/// `n` to `n + 1`.
+pub const MIGRATIONS: &[Migration] = &[store_identity, store_status];
+
+/// Settles a task list read as `typed`, migrating it from `document`, the
+/// same contents read as a plain document, if it is outdated or unreadable.
+///
+/// `document` is only read when needed.
+pub fn upgrade(
+    typed: Result<TaskList>,
+    document: impl FnOnce() -> Result<Value>,
+) -> Result<TaskList> {
+    match &typed {
+        Ok(list) if list.version == SCHEMA_VERSION => return typed,
+        Ok(list) if list.version > SCHEMA_VERSION => return Err(too_new(list.version)),
+        _ => {}
+    }
+    let mut document = match (document(), typed) {
+        (Ok(Value::Object(document)), Ok(_)) => document,
+        (Ok(Value::Object(document)), Err(error)) => {
+            // A current list that doesn't read has nothing to migrate.
+            if version_of(&document)? >= SCHEMA_VERSION {
+                return Err(error);
+            }
+            document
+        }
+        // Lists that only read as tasks are migrated from those.
+        (_, Ok(list)) => match serde_json::to_value(list)? {
+            Value::Object(document) => document,
+            _ => unreachable!("a task list serializes to a map"),
+        },
+        (_, Err(error)) => return Err(error),
+    };
+    let version = version_of(&document)?;
+    if version > SCHEMA_VERSION {
+        return Err(too_new(version));
+    }
+
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(&mut document)?;
+        document.insert("version".to_string(), Value::from(step + 1));
+    }
+    let list: TaskList = serde_json::from_value(Value::Object(document)).map_err(|e| {
+        AppError::Migration(format!(
+            "the task list doesn't fit version {}: {}",
+            SCHEMA_VERSION, e
+        ))
+    })?;
+    if version < SCHEMA_VERSION {
+        info!(
+            "Upgraded the task list from version {} to {}.",
+            version, SCHEMA_VERSION
+        );
+    }
+    Ok(list)
+}
+
+/// Returns the version recorded in `document`, 0 if there is none.
+fn version_of(document: &Map<String, Value>) -> Result<u32> {
+    match document.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| AppError::Migration(format!("invalid version {}", version))),
+    }
+}
+
+fn too_new(version: u32) -> AppError {
+    AppError::Migration(format!(
+        "the task list has version {}, but this build only reads up to {}; upgrade tm",
+        version, SCHEMA_VERSION
+    ))
+}
+
+/// Returns the task tables of `document`.
+fn tasks_mut(document: &mut Map<String, Value>) -> impl Iterator<Item = &mut Map<String, Value>> {
+    document
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+/// Version 1: stores the UUID and creation time of tasks from before they
+/// were recorded, which would otherwise be made up anew on every load.
+fn store_identity(document: &mut Map<String, Value>) -> Result<()> {
+    let now = clock::now().timestamp();
+    for task in tasks_mut(document) {
+        task.entry("uuid")
+            .or_insert_with(|| Value::from(Uuid::new_v4().to_string()));
+        task.entry("created_at").or_insert_with(|| Value::from(now));
+    }
+    Ok(())
+}
+
+/// Version 2: replaces the `completed` and `cancelled` flags with a
+/// `status`.
+fn store_status(document: &mut Map<String, Value>) -> Result<()> {
+    for task in tasks_mut(document) {
+        let completed = task.remove("completed").and_then(|v| v.as_bool());
+        let cancelled = task.remove("cancelled").and_then(|v| v.as_bool());
+        let status = match (completed, cancelled) {
+            (Some(true), _) => TaskStatus::Done,
+            (_, Some(true)) => TaskStatus::Cancelled,
+            _ => TaskStatus::Todo,
+        };
+        task.entry("status")
+            .or_insert_with(|| Value::from(status.to_string()));
+    }
+    Ok(())
+}
@@ -1,11 +1,182 @@
 //! The core application logic and modules for the task manager.
 
+pub mod agenda;
+pub mod aliases;
+#[cfg(feature = "async")]
+pub mod async_storage;
+pub mod attachments;
+pub mod cache;
+pub mod calendar;
+pub mod changelog;
+pub mod charts;
 pub mod cli;
+pub mod clipboard;
+pub mod clock;
+#[cfg(feature = "native")]
+pub mod commands;
+pub mod compare;
+pub mod completions;
+pub mod config;
+pub mod context;
+#[cfg(feature = "native")]
+pub mod daemon;
+pub mod dashboard;
+pub mod doctor;
+pub mod donelog;
+pub mod due;
+pub mod duplicates;
+pub mod editor;
+pub mod encryption;
 pub mod error;
+pub mod escalation;
+pub mod estimate;
+pub mod eventlog;
+pub mod export;
+pub mod filter;
+pub mod github;
+pub mod goals;
+pub mod graph;
+pub mod hooks;
+pub mod i18n;
+pub mod ids;
+pub mod import;
+pub mod interchange;
+pub mod jira;
+pub mod journal;
+pub mod limits;
+pub mod lists;
+pub mod logging;
+pub mod maintenance;
+pub mod manager;
+pub mod mcp;
+pub mod migrate;
 pub mod models;
+pub mod notify;
+pub mod output;
+#[cfg(feature = "native")]
+pub mod picker;
+pub mod plan;
+pub mod plugins;
+pub mod profile;
+pub mod progress;
+pub mod projects;
+pub mod query;
+pub mod quickadd;
+pub mod recurrence;
+pub mod remind;
+#[cfg(feature = "native")]
+pub mod remote;
+pub mod render;
+pub mod review;
+pub mod rollover;
+pub mod rpc;
+pub mod scan;
+pub mod search;
+pub mod session;
+pub mod snippet;
+pub mod sort;
+pub mod standup;
+pub mod stats;
 pub mod storage;
+pub mod store;
+pub mod sync;
+pub mod task_contexts;
+pub mod taskwarrior;
+pub mod templates;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod timetrack;
+pub mod todo_file;
+pub mod todoist;
+pub mod trash;
+pub mod urgency;
 
+pub use agenda::*;
+pub use aliases::*;
+#[cfg(feature = "async")]
+pub use async_storage::*;
+pub use attachments::*;
+pub use cache::*;
+pub use calendar::*;
+pub use changelog::*;
+pub use charts::*;
 pub use cli::*;
+pub use clipboard::*;
+#[cfg(feature = "native")]
+pub use commands::*;
+pub use compare::*;
+pub use completions::*;
+pub use config::*;
+pub use context::*;
+#[cfg(feature = "native")]
+pub use daemon::*;
+pub use dashboard::*;
+pub use doctor::*;
+pub use donelog::*;
+pub use due::*;
+pub use duplicates::*;
+pub use editor::*;
+pub use encryption::*;
 pub use error::*;
+pub use escalation::*;
+pub use estimate::*;
+pub use eventlog::*;
+pub use export::*;
+pub use filter::*;
+pub use github::*;
+pub use goals::*;
+pub use graph::*;
+pub use hooks::*;
+pub use i18n::*;
+pub use ids::*;
+pub use import::*;
+pub use interchange::*;
+pub use jira::*;
+pub use journal::*;
+pub use limits::*;
+pub use lists::*;
+pub use logging::*;
+pub use maintenance::*;
+pub use manager::*;
+pub use mcp::*;
+pub use migrate::*;
 pub use models::*;
+pub use notify::*;
+pub use output::*;
+#[cfg(feature = "native")]
+pub use picker::*;
+pub use plan::*;
+pub use plugins::*;
+pub use profile::*;
+pub use progress::*;
+pub use projects::*;
+pub use query::*;
+pub use quickadd::*;
+pub use recurrence::*;
+pub use remind::*;
+#[cfg(feature = "native")]
+pub use remote::*;
+pub use render::*;
+pub use review::*;
+pub use rollover::*;
+pub use rpc::*;
+pub use scan::*;
+pub use search::*;
+pub use session::*;
+pub use snippet::*;
+pub use sort::*;
+pub use standup::*;
+pub use stats::*;
 pub use storage::*;
+pub use store::*;
+pub use sync::*;
+pub use task_contexts::*;
+pub use taskwarrior::*;
+pub use templates::*;
+#[cfg(feature = "test-support")]
+pub use test_support::*;
+pub use timetrack::*;
+pub use todo_file::*;
+pub use todoist::*;
+pub use trash::*;
+pub use urgency::*;
@@ -1,27 +1,307 @@
 //! Defines the data structures for the task manager, primarily the `Task` struct.
 
-use chrono::{DateTime, NaiveDate, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use clap::ValueEnum;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::app::{Alert, Estimate, Recurrence, SCHEMA_VERSION, WorkInterval, clock};
+use crate::tr;
+
+/// The priority level of a task.
+///
+/// Variants are ordered from lowest to highest so priorities can be compared directly.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// Returns the marker shown for the priority in `--ascii` mode, `[P1]`
+    /// for high.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Priority::High => "[P1]",
+            Priority::Medium => "[P2]",
+            Priority::Low => "[P3]",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Where a task stands, as the columns of `board` show it.
+///
+/// Backlog, todo, in-progress, and blocked tasks are all pending; done and
+/// cancelled ones are finished.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskStatus {
+    /// Captured, but not planned yet.
+    Backlog,
+    /// Planned, but not started.
+    #[default]
+    Todo,
+    InProgress,
+    /// Started, but waiting on something.
+    Blocked,
+    Done,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// Every status, in the order of the board columns.
+    pub const ALL: [TaskStatus; 6] = [
+        TaskStatus::Backlog,
+        TaskStatus::Todo,
+        TaskStatus::InProgress,
+        TaskStatus::Blocked,
+        TaskStatus::Done,
+        TaskStatus::Cancelled,
+    ];
+
+    /// Returns `true` for the statuses of tasks that are still to be done.
+    pub fn is_pending(self) -> bool {
+        !matches!(self, TaskStatus::Done | TaskStatus::Cancelled)
+    }
+
+    /// Returns the heading of the board column, in the active language.
+    pub fn title(self) -> String {
+        tr!("status-title", status = self.to_string())
+    }
+
+    /// Returns the label listings show, e.g. `IN PROGRESS`, in the active
+    /// language.
+    pub fn label(self) -> String {
+        tr!("status-label", status = self.to_string())
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            TaskStatus::Backlog => "backlog",
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in-progress",
+            TaskStatus::Blocked => "blocked",
+            TaskStatus::Done => "done",
+            TaskStatus::Cancelled => "cancelled",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Also reads the `completed` flag stored before there were statuses, as
+/// `done` or `todo`.
+impl<'de> Deserialize<'de> for TaskStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StatusVisitor;
+
+        impl Visitor<'_> for StatusVisitor {
+            type Value = TaskStatus;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a task status or a completion flag")
+            }
+
+            fn visit_bool<E: de::Error>(self, completed: bool) -> Result<TaskStatus, E> {
+                Ok(if completed {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::Todo
+                })
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<TaskStatus, E> {
+                TaskStatus::ALL
+                    .into_iter()
+                    .find(|status| status.to_string() == name)
+                    .ok_or_else(|| E::custom(format!("unknown task status '{}'", name)))
+            }
+        }
+
+        deserializer.deserialize_any(StatusVisitor)
+    }
+}
+
+/// A timestamped free-form note attached to a task.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Note {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// A timestamped entry in the history of a task, recorded with `annotate`.
+///
+/// Unlike notes, which hold details about the task itself, annotations log
+/// what happened with it, like "called vendor".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// A file or link attached to a task with `attach`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// An absolute file path, or a URL.
+    pub target: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub added_at: DateTime<Utc>,
+}
+
+/// An entry of a task's checklist, for steps too small to be subtasks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
 
 /// Represents a single task in the task manager.
 ///
-/// Each task has a unique ID, a description, a completion status,
-/// the timestamp when it was created, and an optional due date.
+/// Each task has a unique ID, a description, a [`TaskStatus`],
+/// the timestamp when it was created, an optional due date, and optional
+/// project and priority. Tasks without a project live in the inbox.
+/// Longer details are kept as a list of timestamped notes.
+///
+/// Besides the short numeric ID shown to users, every task carries a UUID that
+/// stays stable across renumbering and identifies it between machines.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Task {
     pub id: u32,
+    #[serde(default = "Uuid::new_v4")]
+    pub uuid: Uuid,
     pub description: String,
-    pub completed: bool,
-    #[serde(with = "chrono::serde::ts_seconds")] // Serialize as Unix timestamp
+    #[serde(default, alias = "completed")]
+    pub status: TaskStatus,
+    // Serialize as Unix timestamp; files written before timestamps existed get the load time.
+    #[serde(default = "clock::now", with = "chrono::serde::ts_seconds")]
     pub created_at: DateTime<Utc>,
     pub due_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<NaiveTime>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Where the task can be done, in the GTD sense, e.g. `phone` or
+    /// `office`; written `@phone`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<String>,
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// When a command last changed the task; unset until one does.
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Who added the task, on a shared list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    /// Who completed the task, on a shared list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_by: Option<String>,
+    /// Who last changed the task, on a shared list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Steps ticked off with `check done`, numbered from 1.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+    /// Who the task is for, when a list is shared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// The page the task is about, e.g. a pull request, opened by `open`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The task this one is a subtask of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Uuid>,
+    /// How far along the task is, in percent; for a task with subtasks, the
+    /// share of them that is done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u8>,
+    /// The ID of the goal the task works toward.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<u32>,
+    /// What the task is blocked on outside of the list, e.g. "reply from
+    /// legal"; set with `wait`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_on: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_on: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    #[serde(
+        default,
+        rename = "estimate_minutes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub estimate: Option<Estimate>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub escalations_sent: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind_before_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminded_on: Option<NaiveDate>,
+    /// The reminders set with `remind <id> --at`, besides the one for the
+    /// due date.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<Alert>,
+    /// When the last of [`alerts`](Task::alerts) went off.
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub alerted_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<WorkInterval>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub snooze_count: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
 }
 
 impl Task {
     /// Creates a new `Task` instance.
     ///
     /// The `id` should be unique and usually generated by the task manager logic.
-    /// `created_at` is set to the current UTC timestamp.
+    /// `created_at` is set to the current UTC timestamp (see [`clock::now`]).
     ///
     /// # Arguments
     ///
@@ -35,34 +315,266 @@ impl Task {
     pub fn new(id: u32, description: String, due_date: Option<NaiveDate>) -> Self {
         Task {
             id,
+            uuid: Uuid::new_v4(),
             description,
-            completed: false,
-            created_at: Utc::now(),
+            status: TaskStatus::Todo,
+            created_at: clock::now(),
             due_date,
+            due_time: None,
+            project: None,
+            priority: None,
+            tags: Vec::new(),
+            contexts: Vec::new(),
+            completed_at: None,
+            updated_at: None,
+            created_by: None,
+            completed_by: None,
+            updated_by: None,
+            notes: Vec::new(),
+            annotations: Vec::new(),
+            attachments: Vec::new(),
+            checklist: Vec::new(),
+            assignee: None,
+            url: None,
+            parent: None,
+            progress: None,
+            goal: None,
+            waiting_on: None,
+            expires_on: None,
+            start_date: None,
+            recurrence: None,
+            estimate: None,
+            escalations_sent: 0,
+            remind_before_minutes: None,
+            reminded_on: None,
+            alerts: Vec::new(),
+            alerted_at: None,
+            intervals: Vec::new(),
+            snooze_count: 0,
         }
     }
 
+    /// Returns when the task was last changed, or created if it never was.
+    pub fn last_modified(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or(self.created_at)
+    }
+
+    /// Returns how many checklist items are checked and how many there are,
+    /// or `None` without a checklist.
+    pub fn checklist_progress(&self) -> Option<(usize, usize)> {
+        (!self.checklist.is_empty()).then(|| {
+            let done = self.checklist.iter().filter(|item| item.done).count();
+            (done, self.checklist.len())
+        })
+    }
+
+    /// Returns `true` if the task is neither completed nor cancelled.
+    pub fn is_pending(&self) -> bool {
+        self.status.is_pending()
+    }
+
+    /// Returns `true` if the task is completed.
+    pub fn is_done(&self) -> bool {
+        self.status == TaskStatus::Done
+    }
+
+    /// Returns `true` if the task is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.status == TaskStatus::Cancelled
+    }
+
+    /// Returns the status label of machine-readable listings, which are
+    /// never translated; [`TaskStatus::label`] is the one shown to people.
+    pub fn status_label(&self) -> &'static str {
+        match self.status {
+            TaskStatus::Backlog => "BACKLOG",
+            TaskStatus::Todo => "PENDING",
+            TaskStatus::InProgress => "IN PROGRESS",
+            TaskStatus::Blocked => "BLOCKED",
+            TaskStatus::Done => "DONE",
+            TaskStatus::Cancelled => "CANCELLED",
+        }
+    }
+
+    /// Returns `true` if the task is pending and has not been assigned a project yet.
+    ///
+    /// Quickly captured tasks land in the inbox until they are triaged.
+    pub fn is_in_inbox(&self) -> bool {
+        self.is_pending() && self.project.is_none()
+    }
+
+    /// Returns `true` if the task is pending but doesn't start before a later day.
+    ///
+    /// Such tasks stay out of the default list until their start date.
+    pub fn is_waiting(&self, today: NaiveDate) -> bool {
+        self.is_pending() && self.start_date.is_some_and(|start| start > today)
+    }
+
+    /// Returns `true` if the task is pending and its expiration date lies before `today`.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.is_pending() && self.expires_on.is_some_and(|expires| expires < today)
+    }
+
+    /// Creates the next occurrence of a recurring task under the ID `next_id`.
+    ///
+    /// The recurrence rule moves to the new task, so a completed occurrence
+    /// never spawns twice.
+    ///
+    /// # Returns
+    ///
+    /// The new task, or `None` if the task doesn't recur or its series has ended.
+    pub fn spawn_next_occurrence(&mut self, next_id: u32) -> Option<Task> {
+        let recurrence = self.recurrence.take()?;
+        let due = self.due_date?;
+        let next_due = recurrence.next_after(due)?;
+
+        let mut next = Task::new(next_id, self.description.clone(), Some(next_due));
+        next.due_time = self.due_time;
+        next.project = self.project.clone();
+        next.priority = self.priority;
+        next.tags = self.tags.clone();
+        next.contexts = self.contexts.clone();
+        next.estimate = self.estimate;
+        next.remind_before_minutes = self.remind_before_minutes;
+        next.alerts = self
+            .alerts
+            .iter()
+            .copied()
+            .filter(|a| matches!(a, Alert::BeforeDue(_)))
+            .collect();
+        next.recurrence = Some(recurrence.advanced(due));
+        Some(next)
+    }
+
+    /// Moves a recurring task to its next occurrence without completing it.
+    ///
+    /// # Returns
+    ///
+    /// The new due date, or `None` if the task doesn't recur or its series has
+    /// ended, in which case the task is left unchanged.
+    pub fn skip_occurrence(&mut self) -> Option<NaiveDate> {
+        let recurrence = self.recurrence.as_ref()?;
+        let due = self.due_date?;
+        let next_due = recurrence.next_after(due)?;
+        self.recurrence = Some(recurrence.advanced(due));
+        self.due_date = Some(next_due);
+        Some(next_due)
+    }
+
+    /// Pushes the due date back to `until` and counts the snooze.
+    ///
+    /// Reminders and escalations start over, since they were about the old
+    /// due date.
+    pub fn snooze(&mut self, until: NaiveDate) {
+        self.due_date = Some(until);
+        self.snooze_count += 1;
+        self.reminded_on = None;
+        self.escalations_sent = 0;
+    }
+
+    /// Cancels the task, taking it out of the pending list without completing it.
+    pub fn cancel(&mut self) {
+        self.set_status(TaskStatus::Cancelled);
+    }
+
     /// Sets the completion status of the task.
     ///
+    /// Completing a task records the completion time; marking it incomplete
+    /// clears it and puts it back in the todo column. Either way, a cancelled
+    /// task becomes active again.
+    ///
     /// # Arguments
     ///
     /// * `status` - A boolean indicating whether the task is completed (`true`)
-    ///              or not completed (`false`).
+    ///   or not completed (`false`).
     pub fn mark_completion(&mut self, status: bool) {
-        self.completed = status;
+        self.set_status(if status {
+            TaskStatus::Done
+        } else {
+            TaskStatus::Todo
+        });
+    }
+
+    /// Moves the task to `status`, recording the completion time when it
+    /// becomes done and clearing it, and who completed it, when it stops
+    /// being done.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if status == TaskStatus::Done && !self.is_done() {
+            self.completed_at = Some(clock::now());
+        } else if status != TaskStatus::Done {
+            self.completed_at = None;
+            self.completed_by = None;
+        }
+        self.status = status;
+    }
+
+    /// Appends a note to the task, stamped with the current UTC time.
+    pub fn add_note(&mut self, text: String) {
+        self.notes.push(Note {
+            created_at: clock::now(),
+            text,
+        });
+    }
+
+    /// Records an annotation on the task, stamped with the current UTC time.
+    pub fn annotate(&mut self, text: String) {
+        self.annotations.push(Annotation {
+            created_at: clock::now(),
+            text,
+        });
+    }
+
+    /// Returns the moment the task becomes overdue: its due time, or the end
+    /// of its due date if it has no time.
+    pub fn deadline(&self) -> Option<NaiveDateTime> {
+        let date = self.due_date?;
+        match self.due_time {
+            Some(time) => Some(date.and_time(time)),
+            None => Some(
+                date.checked_add_days(Days::new(1))?
+                    .and_time(NaiveTime::MIN),
+            ),
+        }
+    }
+
+    /// Returns `true` if the task is pending and its deadline has passed at `now`.
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        self.is_pending() && self.deadline().is_some_and(|deadline| deadline <= now)
+    }
+
+    /// Sets the due date, dropping the time of day if the date changes.
+    ///
+    /// For sources that only know dates, so a synced date doesn't pick up
+    /// the time of the one it replaces.
+    pub fn set_due_date(&mut self, date: Option<NaiveDate>) {
+        if self.due_date != date {
+            self.due_time = None;
+        }
+        self.due_date = date;
     }
 }
 
 /// A wrapper struct for serializing and deserializing a list of tasks to TOML.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskList {
+    /// The layout version the list was written with; see [`migrate`](crate::app::migrate).
+    #[serde(default)]
+    pub version: u32,
     pub tasks: Vec<Task>,
 }
 
 impl TaskList {
     /// Creates a new empty `TaskList`.
     pub fn new() -> Self {
-        TaskList { tasks: Vec::new() }
+        TaskList::of(Vec::new())
+    }
+
+    /// Creates a `TaskList` of `tasks` in the current layout.
+    pub fn of(tasks: Vec<Task>) -> Self {
+        TaskList {
+            version: SCHEMA_VERSION,
+            tasks,
+        }
     }
 }
 
@@ -73,3 +585,20 @@ impl Default for TaskList {
         Self::new()
     }
 }
+
+/// A borrowed [`TaskList`], for writing tasks without copying them first.
+#[derive(Debug, Serialize)]
+pub struct TaskListRef<'a> {
+    pub version: u32,
+    pub tasks: &'a [Task],
+}
+
+impl<'a> TaskListRef<'a> {
+    /// Borrows `tasks` as a list in the current layout.
+    pub fn of(tasks: &'a [Task]) -> Self {
+        TaskListRef {
+            version: SCHEMA_VERSION,
+            tasks,
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Delivery of notifications through external channels.
+//!
+//! Desktop notifications go through the desktop's notification service, email is handed to the
+//! local `sendmail`, webhooks receive a JSON `POST`, and pushes to a phone go through an
+//! [ntfy](https://ntfy.sh) topic or a [Gotify](https://gotify.net) server. [`send_email`] can also use an SMTP
+//! server configured under `[smtp]`. Sending needs the `native` feature; without it only the
+//! settings are there, for the config file to be read.
+
+use serde::{Deserialize, Serialize};
+
+/// A way of delivering a notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "channel", rename_all = "lowercase")]
+pub enum Channel {
+    /// A desktop notification.
+    Desktop,
+    /// An email sent through `sendmail`.
+    Email { to: String },
+    /// A JSON `POST` with `title` and `body` fields.
+    Webhook { url: String },
+    /// A push to an ntfy topic, e.g. one the ntfy app on a phone subscribes
+    /// to.
+    Ntfy {
+        topic: String,
+        /// Defaults to `https://ntfy.sh`.
+        server: Option<String>,
+        /// An access token, for topics that need one.
+        token: Option<String>,
+    },
+    /// A push to a Gotify server, with an application's token.
+    Gotify {
+        url: String,
+        token: String,
+        /// Gotify's priority, from 0 to 10; defaults to the application's.
+        priority: Option<u8>,
+    },
+}
+
+impl Channel {
+    /// A short name for log and status messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Channel::Desktop => "desktop",
+            Channel::Email { .. } => "email",
+            Channel::Webhook { .. } => "webhook",
+            Channel::Ntfy { .. } => "ntfy",
+            Channel::Gotify { .. } => "gotify",
+        }
+    }
+}
+
+/// The SMTP server email is sent through; unset `host` means `sendmail`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub host: Option<String>,
+    /// Defaults to 587; 465 uses implicit TLS instead of STARTTLS.
+    pub port: Option<u16>,
+    /// The login; unset means the server is used without authentication.
+    pub username: Option<String>,
+    /// The environment variable holding the password; defaults to
+    /// `TASK_SMTP_PASSWORD`. The password itself is never read from the file.
+    pub password_env: Option<String>,
+    /// The sender address; defaults to `username`.
+    pub from: Option<String>,
+}
+
+#[cfg(feature = "native")]
+mod deliver {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+    use log::{debug, info};
+    use notify_rust::Notification;
+    use std::env;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    use super::{Channel, SmtpConfig};
+    use crate::app::{AppError, BIN_NAME, Result};
+
+    /// The environment variable the SMTP password is read from by default.
+    const SMTP_PASSWORD_ENV_VAR: &str = "TASK_SMTP_PASSWORD";
+
+    /// The ntfy server used when a channel doesn't name one.
+    const DEFAULT_NTFY_SERVER: &str = "https://ntfy.sh";
+
+    /// The port used when `[smtp]` doesn't give one: submission with STARTTLS.
+    const DEFAULT_SMTP_PORT: u16 = 587;
+
+    /// The port mail is submitted on over implicit TLS rather than STARTTLS.
+    const IMPLICIT_TLS_PORT: u16 = 465;
+
+    impl Channel {
+        /// Delivers a notification with the given title and body.
+        ///
+        /// # Returns
+        ///
+        /// An `AppError::Notify` if the channel rejected the notification.
+        pub fn send(&self, title: &str, body: &str) -> Result<()> {
+            debug!("Sending {} notification: {}", self.name(), title);
+            match self {
+                Channel::Desktop => {
+                    Notification::new()
+                        .appname(BIN_NAME)
+                        .summary(title)
+                        .body(body)
+                        .show()
+                        .map_err(|e| AppError::Notify(format!("desktop: {}", e)))?;
+                    Ok(())
+                }
+                Channel::Email { to } => {
+                    let message = format!("To: {}\nSubject: {}\n\n{}\n", to, title, body);
+                    run(Command::new("sendmail").arg("-t"), &message)
+                }
+                Channel::Webhook { url } => {
+                    ureq::post(url)
+                        .send_json(serde_json::json!({ "title": title, "body": body }))
+                        .map_err(|e| AppError::Notify(format!("webhook {}: {}", url, e)))?;
+                    Ok(())
+                }
+                Channel::Ntfy {
+                    topic,
+                    server,
+                    token,
+                } => {
+                    // Publishing as JSON keeps titles that aren't ASCII intact.
+                    let server = server.as_deref().unwrap_or(DEFAULT_NTFY_SERVER);
+                    let mut request = ureq::post(server.trim_end_matches('/'));
+                    if let Some(token) = token {
+                        request = request.header("Authorization", &format!("Bearer {}", token));
+                    }
+                    request
+                        .send_json(serde_json::json!({
+                            "topic": topic,
+                            "title": title,
+                            "message": body,
+                        }))
+                        .map_err(|e| AppError::Notify(format!("ntfy {}: {}", server, e)))?;
+                    Ok(())
+                }
+                Channel::Gotify {
+                    url,
+                    token,
+                    priority,
+                } => {
+                    let mut message = serde_json::json!({ "title": title, "message": body });
+                    if let Some(priority) = priority {
+                        message["priority"] = serde_json::json!(priority);
+                    }
+                    ureq::post(&format!("{}/message", url.trim_end_matches('/')))
+                        .header("X-Gotify-Key", token)
+                        .send_json(message)
+                        .map_err(|e| AppError::Notify(format!("gotify {}: {}", url, e)))?;
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Emails `body` to `to`, through the SMTP server in `smtp` if one is set and
+    /// the local `sendmail` otherwise.
+    ///
+    /// # Returns
+    ///
+    /// An `AppError::Notify` if the settings are incomplete or the mail was
+    /// rejected.
+    pub fn send_email(smtp: &SmtpConfig, to: &str, subject: &str, body: &str) -> Result<()> {
+        let Some(host) = &smtp.host else {
+            return Channel::Email { to: to.to_string() }.send(subject, body);
+        };
+        let smtp_error =
+            |e: &dyn std::fmt::Display| AppError::Notify(format!("smtp {}: {}", host, e));
+        let from = smtp
+            .from
+            .as_ref()
+            .or(smtp.username.as_ref())
+            .ok_or_else(|| {
+                AppError::Notify(
+                    "set `from` or `username` under [smtp] in the config file".to_string(),
+                )
+            })?;
+        let message = Message::builder()
+            .from(from.parse().map_err(|e| smtp_error(&e))?)
+            .to(to.parse().map_err(|e| smtp_error(&e))?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .map_err(|e| smtp_error(&e))?;
+
+        let port = smtp.port.unwrap_or(DEFAULT_SMTP_PORT);
+        let relay = if port == IMPLICIT_TLS_PORT {
+            SmtpTransport::relay(host)
+        } else {
+            SmtpTransport::starttls_relay(host)
+        };
+        let mut transport = relay.map_err(|e| smtp_error(&e))?.port(port);
+        if let Some(username) = &smtp.username {
+            let var = smtp
+                .password_env
+                .as_deref()
+                .unwrap_or(SMTP_PASSWORD_ENV_VAR);
+            let password = env::var(var)
+                .map_err(|_| AppError::Notify(format!("no SMTP password; set {} to it", var)))?;
+            transport = transport.credentials(Credentials::new(username.clone(), password));
+        }
+        transport
+            .build()
+            .send(&message)
+            .map_err(|e| smtp_error(&e))?;
+        info!("Sent \"{}\" to {} through {}.", subject, to, host);
+        Ok(())
+    }
+
+    /// Runs `command`, feeding it `input` on stdin, and checks its exit status.
+    fn run(command: &mut Command, input: &str) -> Result<()> {
+        let program = command.get_program().to_string_lossy().into_owned();
+        let notify_error = |e: std::io::Error| AppError::Notify(format!("{}: {}", program, e));
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(notify_error)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes()).map_err(notify_error)?;
+        }
+        let status = child.wait().map_err(notify_error)?;
+        if !status.success() {
+            return Err(AppError::Notify(format!(
+                "{} exited with {}",
+                program, status
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "native")]
+pub use deliver::*;
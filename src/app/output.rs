@@ -0,0 +1,111 @@
+//! Destinations for command output.
+//!
+//! Command handlers write through an [`OutputSink`] instead of printing
+//! directly, so the same logic can print to a terminal, fill a buffer, or
+//! be collected as JSON by other front ends.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// Receives the output of a command.
+pub trait OutputSink {
+    /// Writes one line of human-readable output.
+    fn line(&mut self, line: &str);
+
+    /// Writes preformatted text, such as an exported document, as is.
+    ///
+    /// Sinks that work in lines drop a single trailing newline.
+    fn text(&mut self, text: &str) {
+        self.line(text.strip_suffix('\n').unwrap_or(text));
+    }
+
+    /// Emits a structured value, such as a task shown with `--json`.
+    ///
+    /// Text sinks print it as pretty JSON.
+    fn value(&mut self, value: &Value) {
+        let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+        self.line(&text);
+    }
+}
+
+/// Writes a formatted line to an [`OutputSink`], like `println!`.
+#[macro_export]
+macro_rules! outln {
+    ($out:expr) => {
+        $out.line("")
+    };
+    ($out:expr, $($arg:tt)*) => {
+        $out.line(&format!($($arg)*))
+    };
+}
+
+/// Prints to standard output.
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn line(&mut self, line: &str) {
+        // A closed pipe (e.g. `tm list | head`) is not worth failing a command over.
+        let _ = writeln!(io::stdout(), "{}", line);
+    }
+
+    fn text(&mut self, text: &str) {
+        let _ = io::stdout().write_all(text.as_bytes());
+    }
+}
+
+/// Drops everything, for `--quiet`.
+#[derive(Debug, Default)]
+pub struct NullSink;
+
+impl OutputSink for NullSink {
+    fn line(&mut self, _line: &str) {}
+
+    fn text(&mut self, _text: &str) {}
+
+    fn value(&mut self, _value: &Value) {}
+}
+
+/// Collects lines in memory.
+#[derive(Debug, Default)]
+pub struct BufferSink {
+    pub lines: Vec<String>,
+}
+
+impl BufferSink {
+    /// Returns the collected output as one newline-terminated string.
+    pub fn contents(&self) -> String {
+        self.lines.iter().map(|l| format!("{}\n", l)).collect()
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn line(&mut self, line: &str) {
+        self.lines.push(line.to_string());
+    }
+}
+
+/// Collects text lines and structured values separately, for JSON front ends.
+#[derive(Debug, Default, Serialize)]
+pub struct JsonSink {
+    pub lines: Vec<String>,
+    pub values: Vec<Value>,
+}
+
+impl JsonSink {
+    /// Serializes everything collected as `{"lines": [...], "values": [...]}`.
+    pub fn to_json(&self) -> crate::app::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl OutputSink for JsonSink {
+    fn line(&mut self, line: &str) {
+        self.lines.push(line.to_string());
+    }
+
+    fn value(&mut self, value: &Value) {
+        self.values.push(value.clone());
+    }
+}
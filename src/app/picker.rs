@@ -0,0 +1,190 @@
+//! An inline fuzzy finder over task descriptions, for `pick`.
+//!
+//! Typing narrows the list to the tasks whose description contains the typed
+//! characters in order, best matches first; words separated by spaces must
+//! all match. Up and Down (or Ctrl-P and Ctrl-N) move the selection, Enter
+//! picks the selected task, and Esc or Ctrl-C cancels. The finder is drawn on
+//! standard error below the cursor, so standard output stays free for the
+//! result.
+
+use crossterm::cursor::{MoveToColumn, MoveUp, RestorePosition, SavePosition};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use std::io::{self, IsTerminal, Write};
+
+use crate::app::{AppError, Result, Task, display_width, truncate};
+
+/// How many matches are shown at once.
+const VISIBLE_MATCHES: usize = 10;
+
+/// Scores how well `pattern` matches `text`, or `None` if some word of the
+/// pattern isn't a subsequence of the text. Case is ignored.
+///
+/// Matched characters score most when they follow each other or start a
+/// word; gaps between them cost a little.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut total = 0;
+    for word in pattern.split_whitespace() {
+        let mut score = 0;
+        let mut last: Option<usize> = None;
+        let mut from = 0;
+        for c in word.to_lowercase().chars() {
+            let at = from + text[from..].iter().position(|&t| t == c)?;
+            score += 16;
+            if at == 0 || !text[at - 1].is_alphanumeric() {
+                score += 8;
+            }
+            match last {
+                Some(prev) if prev + 1 == at => score += 8,
+                Some(prev) => score -= (at - prev - 1) as i64,
+                None => {}
+            }
+            last = Some(at);
+            from = at + 1;
+        }
+        total += score;
+    }
+    Some(total)
+}
+
+/// Returns the tasks of `tasks` matching `pattern`, best first; ties keep
+/// their order.
+pub fn fuzzy_matches<'a>(pattern: &str, tasks: &[&'a Task]) -> Vec<&'a Task> {
+    let mut scored: Vec<(i64, &Task)> = tasks
+        .iter()
+        .filter_map(|task| fuzzy_score(pattern, &task.description).map(|score| (score, *task)))
+        .collect();
+    scored.sort_by_key(|(score, _)| -score);
+    scored.into_iter().map(|(_, task)| task).collect()
+}
+
+/// Puts the terminal back when the finder is done, even on errors.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Lets the user pick one of `tasks`, showing `prompt` before what they
+/// type. Returns `None` if they cancel.
+///
+/// # Returns
+///
+/// An `AppError::InvalidArgument` if there is no terminal to draw on.
+pub fn pick_task<'a>(tasks: &[&'a Task], prompt: &str) -> Result<Option<&'a Task>> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(AppError::InvalidArgument(
+            "pick needs a terminal to draw on.".to_string(),
+        ));
+    }
+    let height = tasks.len().min(VISIBLE_MATCHES) + 1;
+    let mut screen = io::stderr();
+    let _raw = RawMode::enable()?;
+    // Make room below the cursor first, so drawing never scrolls the screen
+    // out from under the saved position.
+    execute!(
+        screen,
+        Print("\n".repeat(height)),
+        MoveUp(height as u16),
+        MoveToColumn(0),
+        SavePosition
+    )?;
+
+    let mut query = String::new();
+    let mut selected = 0;
+    let picked = loop {
+        let matches = fuzzy_matches(&query, tasks);
+        selected = selected.min(matches.len().saturating_sub(1));
+        draw(&mut screen, prompt, &query, &matches, selected, tasks.len())?;
+
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Enter => break matches.get(selected).copied(),
+            KeyCode::Esc => break None,
+            KeyCode::Char('c' | 'g') if ctrl => break None,
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p' | 'k') if ctrl => selected = selected.saturating_sub(1),
+            KeyCode::Down => selected += 1,
+            KeyCode::Char('n' | 'j') if ctrl => selected += 1,
+            KeyCode::Char('u') if ctrl => query.clear(),
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    };
+    execute!(screen, RestorePosition, Clear(ClearType::FromCursorDown))?;
+    Ok(picked)
+}
+
+fn draw(
+    screen: &mut impl Write,
+    prompt: &str,
+    query: &str,
+    matches: &[&Task],
+    selected: usize,
+    total: usize,
+) -> Result<()> {
+    let width = match terminal::size() {
+        Ok((columns, _)) if columns > 0 => columns as usize,
+        _ => 80,
+    };
+    let first = selected.saturating_sub(VISIBLE_MATCHES - 1);
+    queue!(screen, RestorePosition, Clear(ClearType::FromCursorDown))?;
+    for (i, task) in matches.iter().enumerate().skip(first).take(VISIBLE_MATCHES) {
+        let line = truncate(
+            &format!("[{}] {}", task.id, task.description),
+            width.saturating_sub(3),
+        );
+        queue!(screen, Print("\r\n"))?;
+        if i == selected {
+            queue!(
+                screen,
+                SetAttribute(Attribute::Reverse),
+                Print(format!("> {}", line)),
+                SetAttribute(Attribute::Reset)
+            )?;
+        } else {
+            queue!(screen, Print(format!("  {}", line)))?;
+        }
+    }
+    queue!(
+        screen,
+        RestorePosition,
+        Print(format!(
+            "{}{}  ({}/{})",
+            prompt,
+            query,
+            matches.len(),
+            total
+        )),
+        MoveToColumn((display_width(prompt) + display_width(query)) as u16)
+    )?;
+    screen.flush()?;
+    Ok(())
+}
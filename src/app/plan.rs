@@ -0,0 +1,83 @@
+//! The workload of a day, for `plan`.
+//!
+//! `plan --date tomorrow` lists the pending tasks due that day with their
+//! estimates and adds the estimates up, warning when they come to more than
+//! fits in a day. How much fits is set under `[plan]` in the config file:
+//!
+//! ```toml
+//! [plan]
+//! capacity_hours = 6
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::app::Task;
+
+/// How many hours of work fit in a day unless the config file says otherwise.
+pub const DEFAULT_CAPACITY_HOURS: f64 = 8.0;
+
+/// Settings for `plan`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanConfig {
+    /// How many hours of work fit in a day.
+    pub capacity_hours: f64,
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        PlanConfig {
+            capacity_hours: DEFAULT_CAPACITY_HOURS,
+        }
+    }
+}
+
+impl PlanConfig {
+    /// Returns how many minutes of work fit in a day.
+    pub fn capacity_minutes(&self) -> u32 {
+        (self.capacity_hours * 60.0).round() as u32
+    }
+}
+
+/// The pending tasks due on one day and the work they add up to.
+#[derive(Debug)]
+pub struct DayPlan<'a> {
+    pub date: NaiveDate,
+    /// The tasks due that day, in the order they fall due.
+    pub tasks: Vec<&'a Task>,
+    /// The summed estimates of the tasks, in minutes.
+    pub planned_minutes: u32,
+    /// The number of tasks without an estimate.
+    pub unestimated: usize,
+}
+
+impl<'a> DayPlan<'a> {
+    /// Collects the pending tasks of `tasks` due on `date`. Tasks that
+    /// haven't started by then are left out.
+    pub fn build(tasks: &'a [Task], date: NaiveDate) -> Self {
+        let mut due: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.is_pending() && t.due_date == Some(date) && !t.is_waiting(date))
+            .collect();
+        due.sort_by_key(|t| (t.due_time.is_none(), t.due_time, t.id));
+        DayPlan {
+            date,
+            planned_minutes: due
+                .iter()
+                .filter_map(|t| t.estimate)
+                .map(|e| e.minutes())
+                .sum(),
+            unestimated: due.iter().filter(|t| t.estimate.is_none()).count(),
+            tasks: due,
+        }
+    }
+
+    /// Returns how many minutes the plan goes over `capacity_minutes`, if it
+    /// does.
+    pub fn overload(&self, capacity_minutes: u32) -> Option<u32> {
+        self.planned_minutes
+            .checked_sub(capacity_minutes)
+            .filter(|&over| over > 0)
+    }
+}
@@ -0,0 +1,129 @@
+//! External commands: `task foo` runs an executable named `task-foo` found
+//! on `PATH`, the way `git` and `cargo` do, so commands can be added
+//! without changing this crate.
+//!
+//! The plugin gets the words after the command name as arguments, and
+//! learns what the command would have worked on from the environment:
+//!
+//! * `TASK_FILE`: the data file, as `--file`, `--list`, or the config file
+//!   chose it
+//! * `TASK_CONFIG`: the config file
+//! * `TASK_CONTEXT_QUERY`: the query of the active context, if any
+//! * `TASK_BIN`: this program, for running its commands
+//!
+//! `TASK_FILTER` is passed on as it was set. Since `TASK_FILE` is where
+//! this program looks for the data file too, commands run through
+//! `TASK_BIN` work on the same one. The plugin's exit status becomes the
+//! command's.
+
+use log::debug;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use crate::app::{AppError, Config, Result, build_cli, config_file_path, edit_distance};
+use crate::tr;
+
+/// What the executables of external commands are named, before the command.
+pub const PLUGIN_PREFIX: &str = "task-";
+
+/// How many characters a name may be off by to be suggested for another.
+const MAX_TYPO_DISTANCE: usize = 2;
+
+/// Returns the executable on `PATH` that runs the external command `name`.
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}{}", PLUGIN_PREFIX, name, env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Returns the names of the external commands on `PATH`, sorted.
+pub fn plugin_names() -> Vec<String> {
+    let Some(path) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = env::split_paths(&path)
+        .filter_map(|dir| dir.read_dir().ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let name = file_name
+                .strip_prefix(PLUGIN_PREFIX)?
+                .strip_suffix(env::consts::EXE_SUFFIX)?;
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Runs the external command named by the first of `args` with the rest as
+/// its arguments, working on the data file at `tasks_path`.
+///
+/// # Returns
+///
+/// How the plugin exited, or an `AppError::InvalidArgument` naming the
+/// commands closest to the one asked for if there is no such plugin.
+pub fn run_plugin(args: &[OsString], tasks_path: &Path, config: &Config) -> Result<ExitStatus> {
+    let Some((name, rest)) = args.split_first() else {
+        return Err(AppError::InvalidArgument(tr!("error-no-command")));
+    };
+    let name = name.to_string_lossy();
+    let Some(path) = find_plugin(&name) else {
+        return Err(unknown_command(&name));
+    };
+    debug!("Running {} for the '{}' command.", path.display(), name);
+    let mut command = Command::new(&path);
+    command.args(rest).env("TASK_FILE", tasks_path);
+    if let Some(config_path) = config_file_path() {
+        command.env("TASK_CONFIG", config_path);
+    }
+    if let Some(query) = config
+        .context
+        .as_ref()
+        .and_then(|name| config.contexts.get(name))
+    {
+        command.env("TASK_CONTEXT_QUERY", query);
+    }
+    if let Ok(exe) = env::current_exe() {
+        command.env("TASK_BIN", exe);
+    }
+    command.status().map_err(|e| {
+        AppError::InvalidArgument(tr!(
+            "error-could-not-run",
+            program = path.display().to_string(),
+            error = e.to_string()
+        ))
+    })
+}
+
+/// Returns the error for a command that is neither built in nor a plugin,
+/// suggesting the ones spelled closest to it.
+fn unknown_command(name: &str) -> AppError {
+    let built_in = build_cli()
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .map(|c| c.get_name().to_string())
+        .collect::<Vec<_>>();
+    let mut close: Vec<(usize, String)> = built_in
+        .into_iter()
+        .chain(plugin_names())
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= MAX_TYPO_DISTANCE)
+        .collect();
+    close.sort();
+    let mut message = tr!(
+        "error-unknown-command",
+        name = name,
+        plugin = format!("{}{}", PLUGIN_PREFIX, name)
+    );
+    if let Some((_, closest)) = close.first() {
+        message.push(' ');
+        message.push_str(&tr!("did-you-mean-command", command = closest.as_str()));
+    }
+    AppError::InvalidArgument(message)
+}
@@ -0,0 +1,119 @@
+//! Phase timings for the `--profile` flag.
+//!
+//! Phases are `tracing` spans. [`Profiler::layer`] measures how long each
+//! span was open, so timing a phase only takes wrapping it in
+//! `info_span!("name").in_scope(..)`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::Subscriber;
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// How long one phase took.
+#[derive(Debug, Clone)]
+pub struct PhaseTiming {
+    pub name: String,
+    /// How many enclosing phases this one ran inside.
+    pub depth: usize,
+    pub started: Instant,
+    pub elapsed: Duration,
+}
+
+/// Collects phase timings for the rest of the process.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    timings: Arc<Mutex<Vec<PhaseTiming>>>,
+    started: Instant,
+}
+
+impl Profiler {
+    /// Starts collecting timings. `started` is when the process began, used
+    /// for the total.
+    ///
+    /// Nothing is recorded until [`layer`](Profiler::layer) is part of the
+    /// global subscriber; see
+    /// [`install_logging`](crate::app::install_logging).
+    pub fn new(started: Instant) -> Self {
+        Profiler {
+            timings: Arc::new(Mutex::new(Vec::new())),
+            started,
+        }
+    }
+
+    /// Returns the `tracing` layer that records span timings into this
+    /// profiler.
+    pub fn layer<S>(&self) -> impl Layer<S> + use<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        TimingLayer {
+            timings: Arc::clone(&self.timings),
+        }
+    }
+
+    /// Records a phase measured outside of a span, e.g. before installation.
+    pub fn record(&self, name: &str, started: Instant, elapsed: Duration) {
+        self.lock().push(PhaseTiming {
+            name: name.to_string(),
+            depth: 0,
+            started,
+            elapsed,
+        });
+    }
+
+    /// Returns the recorded phases in the order they started.
+    pub fn timings(&self) -> Vec<PhaseTiming> {
+        let mut timings = self.lock().clone();
+        timings.sort_by_key(|t| t.started);
+        timings
+    }
+
+    /// Returns the time since the process started.
+    pub fn total(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<PhaseTiming>> {
+        // A panic while holding the lock can't leave the list half-updated.
+        self.timings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The start of a span, stored in its extensions.
+struct SpanStart(Instant);
+
+struct TimingLayer {
+    timings: Arc<Mutex<Vec<PhaseTiming>>>,
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(started) = span.extensions().get::<SpanStart>().map(|s| s.0) else {
+            return;
+        };
+        let timing = PhaseTiming {
+            name: span.name().to_string(),
+            depth: span.scope().skip(1).count(),
+            started,
+            elapsed: started.elapsed(),
+        };
+        self.timings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(timing);
+    }
+}
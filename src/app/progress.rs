@@ -0,0 +1,59 @@
+//! How far along tasks are.
+//!
+//! `progress 3 60` records that task 3 is 60% done. A task with subtasks,
+//! made with `add --parent` or `edit --parent`, gets its progress from them
+//! instead: the share of its subtasks that are done, leaving out cancelled
+//! ones. It is worked out again whenever the task list is loaded and before
+//! it is saved.
+
+use log::debug;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::app::Task;
+
+/// How many characters the bar of [`progress_bar`] is long.
+const BAR_LENGTH: usize = 10;
+
+/// Sets the progress of every task with subtasks to the share of them that
+/// is done.
+pub fn roll_up_progress(tasks: &mut [Task]) {
+    let mut counts: HashMap<Uuid, (u32, u32)> = HashMap::new();
+    for task in tasks.iter().filter(|t| !t.is_cancelled()) {
+        if let Some(parent) = task.parent {
+            let (done, total) = counts.entry(parent).or_default();
+            *done += u32::from(task.is_done());
+            *total += 1;
+        }
+    }
+    for task in tasks.iter_mut() {
+        let Some(&(done, total)) = counts.get(&task.uuid) else {
+            continue;
+        };
+        let progress = Some((done * 100 / total) as u8);
+        if task.progress != progress {
+            debug!(
+                "Task ID {} has {} of {} subtasks done.",
+                task.id, done, total
+            );
+            task.progress = progress;
+        }
+    }
+}
+
+/// Returns the subtasks of `task` among `tasks`.
+pub fn subtasks<'a>(task: &Task, tasks: &'a [Task]) -> impl Iterator<Item = &'a Task> {
+    let uuid = task.uuid;
+    tasks.iter().filter(move |t| t.parent == Some(uuid))
+}
+
+/// Draws `percent` as a bar, e.g. `[######    ] 60%`.
+pub fn progress_bar(percent: u8) -> String {
+    let filled = usize::from(percent.min(100)) * BAR_LENGTH / 100;
+    format!(
+        "[{}{}] {}%",
+        "#".repeat(filled),
+        " ".repeat(BAR_LENGTH - filled),
+        percent
+    )
+}
@@ -0,0 +1,154 @@
+//! Grouping and summarizing tasks by project.
+//!
+//! Tasks without a project belong to the inbox, which is reported under the
+//! `None` project name.
+
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use crate::app::{Estimate, Task};
+
+/// The label shown for tasks that don't belong to any project.
+pub const INBOX_LABEL: &str = "(inbox)";
+
+/// Task counts for a single project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSummary {
+    /// The project name, or `None` for the inbox.
+    pub name: Option<String>,
+    /// The number of tasks that are neither completed nor cancelled.
+    pub pending: usize,
+    /// The number of tasks in the project, in any state.
+    pub total: usize,
+}
+
+/// Summarizes task counts per project, sorted by project name with the inbox first.
+pub fn project_summaries(tasks: &[Task]) -> Vec<ProjectSummary> {
+    let mut counts: BTreeMap<Option<&str>, (usize, usize)> = BTreeMap::new();
+    for task in tasks {
+        let entry = counts.entry(task.project.as_deref()).or_default();
+        if task.is_pending() {
+            entry.0 += 1;
+        }
+        entry.1 += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(name, (pending, total))| ProjectSummary {
+            name: name.map(str::to_string),
+            pending,
+            total,
+        })
+        .collect()
+}
+
+/// Groups tasks by project, keeping their relative order within each group.
+///
+/// Groups are sorted by project name, with the inbox (`None`) first.
+pub fn group_by_project<'a>(tasks: &[&'a Task]) -> Vec<(Option<&'a str>, Vec<&'a Task>)> {
+    let mut groups: BTreeMap<Option<&'a str>, Vec<&'a Task>> = BTreeMap::new();
+    for task in tasks {
+        groups
+            .entry(task.project.as_deref())
+            .or_default()
+            .push(task);
+    }
+    groups.into_iter().collect()
+}
+
+/// Progress of a single project, weighted by effort estimates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectStatus {
+    pub name: String,
+    /// The number of tasks counted, excluding cancelled ones.
+    pub total: usize,
+    pub completed: usize,
+    /// Completion as a fraction between 0 and 1, weighted by estimates.
+    pub weighted_completion: f64,
+    /// The summed estimates of pending tasks, in minutes.
+    pub remaining_minutes: u32,
+    /// The number of pending tasks without an estimate.
+    pub unestimated_pending: usize,
+    /// The earliest due date among pending tasks.
+    pub nearest_due: Option<NaiveDate>,
+}
+
+/// Computes the effort-weighted status of the project called `name`.
+///
+/// Each task weighs as much as its estimate. Tasks without an estimate weigh
+/// the average estimate of the project's estimated tasks, or count equally
+/// when nothing is estimated. Cancelled tasks are left out entirely.
+///
+/// # Returns
+///
+/// The status, or `None` if no task belongs to the project.
+pub fn project_status(tasks: &[Task], name: &str) -> Option<ProjectStatus> {
+    let members: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.project.as_deref() == Some(name) && !t.is_cancelled())
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+
+    let estimates: Vec<u32> = members
+        .iter()
+        .filter_map(|t| t.estimate.map(Estimate::minutes))
+        .collect();
+    let fallback_weight = if estimates.is_empty() {
+        1.0
+    } else {
+        estimates.iter().map(|&m| f64::from(m)).sum::<f64>() / estimates.len() as f64
+    };
+    let weight = |t: &Task| {
+        t.estimate
+            .map_or(fallback_weight, |e| f64::from(e.minutes()))
+    };
+
+    let total_weight: f64 = members.iter().map(|t| weight(t)).sum();
+    // Summing no floats gives -0.0, which prints as "-0%".
+    let done_weight = members
+        .iter()
+        .filter(|t| t.is_done())
+        .map(|t| weight(t))
+        .fold(0.0, |sum, w| sum + w);
+    let pending: Vec<&&Task> = members.iter().filter(|t| t.is_pending()).collect();
+
+    Some(ProjectStatus {
+        name: name.to_string(),
+        total: members.len(),
+        completed: members.iter().filter(|t| t.is_done()).count(),
+        weighted_completion: if total_weight > 0.0 {
+            done_weight / total_weight
+        } else {
+            0.0
+        },
+        remaining_minutes: pending
+            .iter()
+            .filter_map(|t| t.estimate.map(Estimate::minutes))
+            .sum(),
+        unestimated_pending: pending.iter().filter(|t| t.estimate.is_none()).count(),
+        nearest_due: pending.iter().filter_map(|t| t.due_date).min(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u32, project: &str) -> Task {
+        let mut task = Task::new(id, format!("Task {}", id), None);
+        task.project = Some(project.to_string());
+        task
+    }
+
+    #[test]
+    fn project_status_without_completed_tasks_is_zero_percent() {
+        let tasks = vec![task(1, "garden"), task(2, "garden")];
+        let status = project_status(&tasks, "garden").unwrap();
+        assert_eq!(status.completed, 0);
+        assert!(status.weighted_completion.is_sign_positive());
+        assert_eq!(format!("{:.0}", status.weighted_completion * 100.0), "0");
+    }
+}
@@ -0,0 +1,516 @@
+//! A query language for selecting tasks, as in
+//! `list "due < 2025-06-01 and priority >= high and not tag:waiting"`.
+//!
+//! A query combines conditions with `and`, `or`, and `not`, grouped with
+//! parentheses. `not` binds tightest and `or` loosest; conditions written
+//! next to each other without an operator must all hold. A condition is
+//! either a [`FilterTerm`] such as `project:work`, `+urgent`, `@office`, or a
+//! bare word, or a field compared with `<`, `<=`, `>`, `>=`, `=`, or `!=`:
+//!
+//! * `due`, `start`, `created`, `completed` – a date in any format `--due`
+//!   accepts, such as `2025-06-01`, `today`, `next monday`, or `+1w`
+//! * `priority` – `low`, `medium`, or `high`; higher priorities are greater
+//! * `id`, `snoozed` – a number; `snoozed` counts how often a task was snoozed
+//! * `project` – a project name, compared with `=` or `!=` only
+//! * `status` – `pending`, `waiting`, `completed`, or `cancelled`, compared
+//!   with `=` or `!=` only
+//!
+//! Values with spaces go in double quotes. Every field but `id`, `snoozed`,
+//! and `status` can also be compared with `none`: `due = none` matches tasks
+//! without a due date. A task lacking a field never satisfies `<`, `<=`,
+//! `>`, or `>=` on it.
+//!
+//! Queries only narrow what a command looks at: `list` still leaves out
+//! completed and cancelled tasks unless `--all` is given.
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app::{DateOrder, DueSpec, FilterTerm, Priority, Task, clock};
+
+/// How a field is compared with a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    /// Returns `true` if `left` relates to `right` this way. Missing values
+    /// are only ever equal to each other.
+    fn holds<T: Ord>(self, left: Option<T>, right: Option<T>) -> bool {
+        let ordering = match (self, left, right) {
+            (Comparison::Equal, left, right) => return left == right,
+            (Comparison::NotEqual, left, right) => return left != right,
+            (_, Some(left), Some(right)) => left.cmp(&right),
+            _ => return false,
+        };
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+            Comparison::Equal | Comparison::NotEqual => unreachable!(),
+        }
+    }
+}
+
+/// The fields a query can compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Due,
+    Start,
+    Created,
+    Completed,
+    Priority,
+    Id,
+    Snoozed,
+    Project,
+    Status,
+}
+
+impl QueryField {
+    const NAMES: &[(&str, QueryField)] = &[
+        ("due", QueryField::Due),
+        ("start", QueryField::Start),
+        ("created", QueryField::Created),
+        ("completed", QueryField::Completed),
+        ("priority", QueryField::Priority),
+        ("id", QueryField::Id),
+        ("snoozed", QueryField::Snoozed),
+        ("project", QueryField::Project),
+        ("status", QueryField::Status),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, field)| *field == self)
+            .map_or("?", |(name, _)| name)
+    }
+
+    fn parse(name: &str) -> Result<Self, String> {
+        let lower = name.to_ascii_lowercase();
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| *n == lower)
+            .map(|(_, field)| *field)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::NAMES.iter().map(|(n, _)| *n).collect();
+                format!(
+                    "Unknown field '{}' in query; expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The status of a task, as named in queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QueryStatus {
+    /// Pending and already started.
+    Pending,
+    /// Pending, with a start date that hasn't come yet.
+    Waiting,
+    Completed,
+    Cancelled,
+}
+
+impl QueryStatus {
+    fn of(task: &Task, today: NaiveDate) -> Self {
+        if task.is_done() {
+            QueryStatus::Completed
+        } else if task.is_cancelled() {
+            QueryStatus::Cancelled
+        } else if task.is_waiting(today) {
+            QueryStatus::Waiting
+        } else {
+            QueryStatus::Pending
+        }
+    }
+}
+
+/// The value a field is compared with; `None` stands for `none`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Date(Option<DueSpec>),
+    Priority(Option<Priority>),
+    Number(u32),
+    Name(Option<String>),
+    Status(QueryStatus),
+}
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryExpr {
+    Term(FilterTerm),
+    Compare(QueryField, Comparison, Operand),
+    Not(Box<QueryExpr>),
+    /// Matches if every expression does; an empty list matches everything.
+    All(Vec<QueryExpr>),
+    Any(Vec<QueryExpr>),
+}
+
+impl QueryExpr {
+    fn matches(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            QueryExpr::Term(term) => term.matches(task),
+            QueryExpr::Compare(field, comparison, operand) => {
+                compare(task, *field, *comparison, operand, today)
+            }
+            QueryExpr::Not(expr) => !expr.matches(task, today),
+            QueryExpr::All(exprs) => exprs.iter().all(|e| e.matches(task, today)),
+            QueryExpr::Any(exprs) => exprs.iter().any(|e| e.matches(task, today)),
+        }
+    }
+
+    fn with_order(self, order: Option<DateOrder>) -> Result<Self, String> {
+        let settle = |exprs: Vec<QueryExpr>| {
+            exprs
+                .into_iter()
+                .map(|e| e.with_order(order))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            QueryExpr::Compare(field, comparison, Operand::Date(Some(spec))) => QueryExpr::Compare(
+                field,
+                comparison,
+                Operand::Date(Some(spec.with_order(order)?)),
+            ),
+            QueryExpr::Not(expr) => QueryExpr::Not(Box::new(expr.with_order(order)?)),
+            QueryExpr::All(exprs) => QueryExpr::All(settle(exprs)?),
+            QueryExpr::Any(exprs) => QueryExpr::Any(settle(exprs)?),
+            other => other,
+        })
+    }
+}
+
+/// Compares a field of `task` with `operand`, resolving relative dates
+/// against `today`.
+fn compare(
+    task: &Task,
+    field: QueryField,
+    comparison: Comparison,
+    operand: &Operand,
+    today: NaiveDate,
+) -> bool {
+    match (field, operand) {
+        (
+            QueryField::Due | QueryField::Start | QueryField::Created | QueryField::Completed,
+            Operand::Date(spec),
+        ) => {
+            let value = match field {
+                QueryField::Due => task.due_date,
+                QueryField::Start => task.start_date,
+                QueryField::Created => Some(clock::local_date(task.created_at)),
+                _ => task.completed_at.map(clock::local_date),
+            };
+            let date = match spec {
+                Some(spec) => match spec.resolve(today) {
+                    Some(date) => Some(date),
+                    None => return false,
+                },
+                None => None,
+            };
+            comparison.holds(value, date)
+        }
+        (QueryField::Priority, Operand::Priority(priority)) => {
+            comparison.holds(task.priority, *priority)
+        }
+        (QueryField::Id, Operand::Number(n)) => comparison.holds(Some(task.id), Some(*n)),
+        (QueryField::Snoozed, Operand::Number(n)) => {
+            comparison.holds(Some(task.snooze_count), Some(*n))
+        }
+        (QueryField::Project, Operand::Name(name)) => {
+            comparison.holds(task.project.as_ref(), name.as_ref())
+        }
+        (QueryField::Status, Operand::Status(status)) => {
+            (QueryStatus::of(task, today) == *status) == (comparison == Comparison::Equal)
+        }
+        _ => false,
+    }
+}
+
+/// A query, remembering the text it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub expr: QueryExpr,
+    source: String,
+}
+
+impl Query {
+    /// Returns `true` if `task` satisfies the query, with relative dates
+    /// counted from `today`.
+    pub fn matches(&self, task: &Task, today: NaiveDate) -> bool {
+        self.expr.matches(task, today)
+    }
+
+    /// Returns a query matching the tasks both `self` and `other` match.
+    pub fn and(self, other: Query) -> Query {
+        Query {
+            source: format!("({}) and ({})", self.source, other.source),
+            expr: QueryExpr::All(vec![self.expr, other.expr]),
+        }
+    }
+
+    /// Settles ambiguous numeric dates in the query using `order`, as
+    /// [`DueSpec::with_order`] does.
+    pub fn with_order(mut self, order: Option<DateOrder>) -> Result<Self, String> {
+        self.expr = self.expr.with_order(order)?;
+        Ok(self)
+    }
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        let expr = parser.any()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {} in query", token));
+        }
+        Ok(Query {
+            expr,
+            source: s.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Compare(Comparison),
+    Word { text: String, quoted: bool },
+}
+
+impl Token {
+    /// Returns `true` for the unquoted word `keyword`, ignoring case.
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word { text, quoted: false } if text.eq_ignore_ascii_case(keyword))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::Compare(_) => write!(f, "comparison"),
+            Token::Word { text, .. } => write!(f, "'{}'", text),
+        }
+    }
+}
+
+/// Splits a query into words, parentheses, and comparison operators.
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '<' | '>' | '=' => {
+                chars.next();
+                let or_equal = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Compare(match (c, or_equal) {
+                    ('<', false) => Comparison::Less,
+                    ('<', true) => Comparison::LessOrEqual,
+                    ('>', false) => Comparison::Greater,
+                    ('>', true) => Comparison::GreaterOrEqual,
+                    _ => Comparison::Equal,
+                }));
+            }
+            '"' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Word { text, quoted: true });
+            }
+            _ => {
+                let mut text = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()<>=\"".contains(c) {
+                        break;
+                    }
+                    chars.next();
+                    if c == '!' && chars.next_if_eq(&'=').is_some() {
+                        if !text.is_empty() {
+                            tokens.push(Token::Word {
+                                text: std::mem::take(&mut text),
+                                quoted: false,
+                            });
+                        }
+                        tokens.push(Token::Compare(Comparison::NotEqual));
+                        continue;
+                    }
+                    text.push(c);
+                }
+                if !text.is_empty() {
+                    tokens.push(Token::Word {
+                        text,
+                        quoted: false,
+                    });
+                }
+            }
+        }
+    }
+    if !s.matches('"').count().is_multiple_of(2) {
+        return Err("Unterminated quote in query".to_string());
+    }
+    Ok(tokens)
+}
+
+/// A recursive-descent parser over the tokens of a query.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Parses alternatives separated by `or`.
+    fn any(&mut self) -> Result<QueryExpr, String> {
+        let mut alternatives = vec![self.all()?];
+        while self.peek().is_some_and(|t| t.is_keyword("or")) {
+            self.next();
+            alternatives.push(self.all()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            QueryExpr::Any(alternatives)
+        })
+    }
+
+    /// Parses conditions joined by `and` or written side by side.
+    fn all(&mut self) -> Result<QueryExpr, String> {
+        let mut conditions = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::Close) => break,
+                Some(t) if t.is_keyword("or") => break,
+                Some(t) if t.is_keyword("and") => {
+                    self.next();
+                    conditions.push(self.condition()?);
+                }
+                Some(_) => conditions.push(self.condition()?),
+            }
+        }
+        Ok(if conditions.len() == 1 {
+            conditions.remove(0)
+        } else {
+            QueryExpr::All(conditions)
+        })
+    }
+
+    /// Parses a negation, a parenthesized query, a comparison, or a term.
+    fn condition(&mut self) -> Result<QueryExpr, String> {
+        match self.next() {
+            Some(t) if t.is_keyword("not") => Ok(QueryExpr::Not(Box::new(self.condition()?))),
+            Some(Token::Open) => {
+                let expr = self.any()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Missing ')' in query".to_string()),
+                }
+            }
+            Some(Token::Word { text, quoted }) => {
+                if let Some(Token::Compare(comparison)) = self.peek().cloned() {
+                    self.next();
+                    let field = QueryField::parse(&text)?;
+                    return self.comparison(field, comparison);
+                }
+                if quoted {
+                    Ok(QueryExpr::Term(FilterTerm::Text(text)))
+                } else {
+                    text.parse().map(QueryExpr::Term)
+                }
+            }
+            Some(token) => Err(format!("Unexpected {} in query", token)),
+            None => Err("Query ends where a condition was expected".to_string()),
+        }
+    }
+
+    /// Parses the value `field` is compared with.
+    fn comparison(
+        &mut self,
+        field: QueryField,
+        comparison: Comparison,
+    ) -> Result<QueryExpr, String> {
+        let Some(Token::Word { mut text, .. }) = self.next() else {
+            return Err("Missing value after comparison in query".to_string());
+        };
+        if text.eq_ignore_ascii_case("next")
+            && let Some(Token::Word { text: weekday, .. }) = self.peek().cloned()
+        {
+            self.next();
+            text = format!("next {}", weekday);
+        }
+        let none = text.eq_ignore_ascii_case("none");
+        let equality_only = matches!(comparison, Comparison::Equal | Comparison::NotEqual);
+
+        let operand = match field {
+            QueryField::Due | QueryField::Start | QueryField::Created | QueryField::Completed => {
+                Operand::Date(if none { None } else { Some(text.parse()?) })
+            }
+            QueryField::Priority => Operand::Priority(if none {
+                None
+            } else {
+                Some(
+                    Priority::from_str(&text, true)
+                        .map_err(|_| format!("Unknown priority '{}' in query", text))?,
+                )
+            }),
+            QueryField::Id | QueryField::Snoozed => Operand::Number(
+                text.parse()
+                    .map_err(|_| format!("Expected a number in query, found '{}'", text))?,
+            ),
+            QueryField::Project if equality_only => {
+                Operand::Name(if none { None } else { Some(text) })
+            }
+            QueryField::Status if equality_only => Operand::Status(
+                QueryStatus::from_str(&text, true)
+                    .map_err(|_| format!("Unknown status '{}' in query", text))?,
+            ),
+            QueryField::Project | QueryField::Status => {
+                return Err(format!(
+                    "'{}' can only be compared with = or != in a query",
+                    field.name()
+                ));
+            }
+        };
+        Ok(QueryExpr::Compare(field, comparison, operand))
+    }
+}
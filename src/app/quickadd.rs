@@ -0,0 +1,271 @@
+//! Quick-add syntax: fields written inline in the description given to `add`.
+//!
+//! `add "pay rent by tomorrow !high #finance @home"` adds "pay rent" due
+//! tomorrow, with high priority, in project `finance`, to do `@home`:
+//!
+//! * a due date in any format `--due` accepts, e.g. `friday`, `next monday
+//!   9am`, `2025-06-01`, or `+3d`; the last one in the description wins, and
+//!   a word like `by` or `at` right before it goes with it
+//! * `!low`, `!medium`, or `!high` for the priority
+//! * `#project`, starting with a letter, so `PR #123` stays as it is
+//! * `@context`, which can be repeated
+//!
+//! Options given to `add` win over what is written inline; with `--due`, dates
+//! stay in the description. `--literal` turns the syntax off.
+
+use chrono::Weekday;
+use clap::ValueEnum;
+
+use crate::app::{AddArgs, AppError, DueAt, Priority, Result};
+
+/// Words that introduce a due date and are dropped along with it.
+const DUE_CONNECTORS: &[&str] = &["at", "by", "due", "on"];
+
+/// The most words a due date can take, as in `next monday 9am`.
+const MAX_DUE_WORDS: usize = 3;
+
+/// What quick-add syntax found in a description.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickAdd {
+    /// The description with the inline fields taken out.
+    pub description: String,
+    pub due: Option<DueAt>,
+    pub priority: Option<Priority>,
+    pub project: Option<String>,
+    pub contexts: Vec<String>,
+}
+
+impl QuickAdd {
+    /// Reads the inline fields out of `input`. Without `with_due`, e.g.
+    /// because `--due` was given, dates are left in the description.
+    pub fn parse(input: &str, with_due: bool) -> Self {
+        let mut quick = QuickAdd::default();
+        let mut words: Vec<&str> = Vec::new();
+        for word in input.split_whitespace() {
+            if let Some(priority) = word
+                .strip_prefix('!')
+                .and_then(|p| Priority::from_str(p, true).ok())
+            {
+                quick.priority = Some(priority);
+            } else if let Some(project) = word
+                .strip_prefix('#')
+                .filter(|p| p.starts_with(|c: char| c.is_alphabetic()))
+            {
+                quick.project = Some(project.to_string());
+            } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                if !quick.contexts.iter().any(|c| c == context) {
+                    quick.contexts.push(context.to_string());
+                }
+            } else {
+                words.push(word);
+            }
+        }
+
+        if let Some((start, end, due)) = find_due(&words).filter(|_| with_due) {
+            quick.due = Some(due);
+            let start = match start.checked_sub(1) {
+                Some(before) if DUE_CONNECTORS.contains(&words[before].to_lowercase().as_str()) => {
+                    before
+                }
+                _ => start,
+            };
+            words.drain(start..end);
+        }
+        quick.description = words.join(" ");
+        quick
+    }
+
+    /// Reads the quick-add syntax in the description of `args` into its
+    /// fields, unless `--literal` was given.
+    pub fn read_into(args: &mut AddArgs) -> Result<()> {
+        if args.literal || args.description.is_empty() {
+            return Ok(());
+        }
+        QuickAdd::parse(&args.description, args.due.is_none()).apply(args)
+    }
+
+    /// Fills in what `args` leaves unset and replaces its description.
+    ///
+    /// # Returns
+    ///
+    /// An `AppError::InvalidArgument` if nothing is left of the description.
+    pub fn apply(self, args: &mut AddArgs) -> Result<()> {
+        if self.description.is_empty() {
+            return Err(AppError::InvalidArgument(format!(
+                "Nothing is left of '{}' to describe the task; pass --literal to keep it as is.",
+                args.description
+            )));
+        }
+        args.description = self.description;
+        args.due = args.due.or(self.due);
+        args.priority = args.priority.or(self.priority);
+        args.project = args.project.take().or(self.project);
+        for context in self.contexts {
+            if !args.contexts.contains(&context) {
+                args.contexts.push(context);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the last run of words that reads as a due date, preferring longer
+/// runs, and returns where it starts and ends.
+fn find_due(words: &[&str]) -> Option<(usize, usize, DueAt)> {
+    for end in (1..=words.len()).rev() {
+        for len in (1..=MAX_DUE_WORDS.min(end)).rev() {
+            let start = end - len;
+            let phrase = words[start..end].join(" ");
+            if len == 1 && is_ambiguous_word(&phrase) {
+                continue;
+            }
+            if let Ok(due) = phrase.parse::<DueAt>() {
+                return Some((start, end, due));
+            }
+        }
+    }
+    None
+}
+
+/// Returns `true` for words that read as dates but are more likely meant as
+/// words, like `sun` or `sat`; the full weekday names still count.
+fn is_ambiguous_word(word: &str) -> bool {
+    word.len() == 3 && word.parse::<Weekday>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Cli, Commands};
+    use clap::Parser;
+
+    fn due(s: &str) -> Option<DueAt> {
+        Some(s.parse().unwrap())
+    }
+
+    fn add_args(args: &[&str]) -> AddArgs {
+        let cli = Cli::try_parse_from(["tm", "add"].iter().chain(args)).unwrap();
+        match cli.command {
+            Commands::Add(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn plain_descriptions_are_left_alone() {
+        let quick = QuickAdd::parse("water the plants", true);
+        assert_eq!(
+            quick,
+            QuickAdd {
+                description: "water the plants".to_string(),
+                ..QuickAdd::default()
+            }
+        );
+    }
+
+    #[test]
+    fn reads_every_field() {
+        let quick = QuickAdd::parse("pay rent by tomorrow !high #finance @home", true);
+        assert_eq!(
+            quick,
+            QuickAdd {
+                description: "pay rent".to_string(),
+                due: due("tomorrow"),
+                priority: Some(Priority::High),
+                project: Some("finance".to_string()),
+                contexts: vec!["home".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn reads_fields_in_the_middle() {
+        let quick = QuickAdd::parse("call !low mom @phone about the #family trip", true);
+        assert_eq!(quick.description, "call mom about the trip");
+        assert_eq!(quick.priority, Some(Priority::Low));
+        assert_eq!(quick.project.as_deref(), Some("family"));
+        assert_eq!(quick.contexts, ["phone"]);
+    }
+
+    #[test]
+    fn takes_a_due_date_out_of_the_middle() {
+        let quick = QuickAdd::parse("meet on friday with Sam", true);
+        assert_eq!(quick.description, "meet with Sam");
+        assert_eq!(quick.due, due("friday"));
+    }
+
+    #[test]
+    fn takes_dates_of_several_words() {
+        let quick = QuickAdd::parse("standup next monday 9am", true);
+        assert_eq!(quick.description, "standup");
+        assert_eq!(quick.due, due("next monday 9am"));
+    }
+
+    #[test]
+    fn the_last_date_wins() {
+        let quick = QuickAdd::parse("move monday to friday", true);
+        assert_eq!(quick.description, "move monday to");
+        assert_eq!(quick.due, due("friday"));
+    }
+
+    #[test]
+    fn dates_stay_without_with_due() {
+        let quick = QuickAdd::parse("pay rent by tomorrow !high", false);
+        assert_eq!(quick.description, "pay rent by tomorrow");
+        assert_eq!(quick.due, None);
+        assert_eq!(quick.priority, Some(Priority::High));
+    }
+
+    #[test]
+    fn malformed_tokens_stay_in_the_description() {
+        let input = "fix PR #123 !urgent for @ and # ! on sat";
+        let quick = QuickAdd::parse(input, true);
+        assert_eq!(
+            quick,
+            QuickAdd {
+                description: input.to_string(),
+                ..QuickAdd::default()
+            }
+        );
+    }
+
+    #[test]
+    fn repeated_contexts_count_once() {
+        let quick = QuickAdd::parse("buy stamps @town @errands @town", true);
+        assert_eq!(quick.contexts, ["town", "errands"]);
+    }
+
+    #[test]
+    fn options_win_over_inline_fields() {
+        let mut args = add_args(&[
+            "pay rent tomorrow !high #finance @home @phone",
+            "--priority",
+            "low",
+            "--context",
+            "home",
+        ]);
+        QuickAdd::read_into(&mut args).unwrap();
+        assert_eq!(args.description, "pay rent");
+        assert_eq!(args.due, due("tomorrow"));
+        assert_eq!(args.priority, Some(Priority::Low));
+        assert_eq!(args.project.as_deref(), Some("finance"));
+        assert_eq!(args.contexts, ["home", "phone"]);
+    }
+
+    #[test]
+    fn literal_keeps_the_description() {
+        let mut args = add_args(&["email Bob !high tomorrow", "--literal"]);
+        QuickAdd::read_into(&mut args).unwrap();
+        assert_eq!(args.description, "email Bob !high tomorrow");
+        assert_eq!((args.due, args.priority), (None, None));
+    }
+
+    #[test]
+    fn nothing_left_to_describe_is_an_error() {
+        let mut args = add_args(&["!high #finance tomorrow"]);
+        assert!(matches!(
+            QuickAdd::read_into(&mut args),
+            Err(AppError::InvalidArgument(_))
+        ));
+    }
+}
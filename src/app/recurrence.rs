@@ -0,0 +1,442 @@
+//! Recurrence rules for repeating tasks.
+//!
+//! A rule advances a task's due date by a fixed interval. Individual dates can
+//! be excluded (e.g. holidays), and a series can end on a date or after a
+//! number of occurrences.
+//!
+//! Monthly and yearly series keep the day of the month they started on, so a
+//! series due on Jan 31 falls on Feb 28 and then on Mar 31 again, and one due
+//! on Feb 29 comes back to it in leap years.
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The unit of a recurrence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl RecurrenceUnit {
+    fn name(self) -> &'static str {
+        match self {
+            RecurrenceUnit::Day => "day",
+            RecurrenceUnit::Week => "week",
+            RecurrenceUnit::Month => "month",
+            RecurrenceUnit::Year => "year",
+        }
+    }
+}
+
+/// How and for how long a task repeats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recurrence {
+    /// The number of units between occurrences.
+    pub every: u32,
+    pub unit: RecurrenceUnit,
+    /// Dates that never become an occurrence; the series moves past them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub except: Vec<NaiveDate>,
+    /// The last date an occurrence may fall on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    /// The total number of occurrences in the series.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// The 1-based index of the current occurrence.
+    #[serde(default = "first_occurrence")]
+    pub occurrence: u32,
+    /// For monthly and yearly rules, the day of the month the series falls
+    /// on; months too short for it get their last day instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day: Option<u32>,
+}
+
+fn first_occurrence() -> u32 {
+    1
+}
+
+impl Recurrence {
+    /// Creates an open-ended rule repeating every `every` `unit`s.
+    pub fn new(every: u32, unit: RecurrenceUnit) -> Self {
+        Recurrence {
+            every,
+            unit,
+            except: Vec::new(),
+            until: None,
+            count: None,
+            occurrence: first_occurrence(),
+            day: None,
+        }
+    }
+
+    /// Returns the date one interval after `date`, ignoring exceptions and limits.
+    ///
+    /// Monthly and yearly steps land on the series' day of the month, or the
+    /// last day of shorter months.
+    pub fn step(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self.unit {
+            RecurrenceUnit::Day => date.checked_add_days(Days::new(self.every.into())),
+            RecurrenceUnit::Week => date.checked_add_days(Days::new(u64::from(self.every) * 7)),
+            RecurrenceUnit::Month => add_months_on_day(date, self.every, self.day_for(date)),
+            RecurrenceUnit::Year => add_months_on_day(date, self.every * 12, self.day_for(date)),
+        }
+    }
+
+    /// Returns the day of the month the series falls on, judging from an
+    /// occurrence due on `date`.
+    ///
+    /// The recorded day only counts while `date` is that day, or the last day
+    /// of a month too short for it; a due date moved by hand starts over from
+    /// its own day.
+    fn day_for(&self, date: NaiveDate) -> u32 {
+        match self.day {
+            Some(day) if day.min(days_in_month(date)) == date.day() => day,
+            _ => date.day(),
+        }
+    }
+
+    /// Records the day of the month of an occurrence due on `date`, for
+    /// monthly and yearly rules.
+    pub fn anchor(&mut self, date: NaiveDate) {
+        if matches!(self.unit, RecurrenceUnit::Month | RecurrenceUnit::Year) {
+            self.day = Some(self.day_for(date));
+        }
+    }
+
+    /// Returns the first date on or after `date` that isn't an exception,
+    /// or `None` if that would fall after `until`.
+    pub fn first_on_or_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let mut candidate = date;
+        while self.except.contains(&candidate) {
+            candidate = self.step(candidate)?;
+        }
+        self.within_until(candidate)
+    }
+
+    /// Returns the occurrence following the one due on `current`.
+    ///
+    /// Exception dates are skipped over. Returns `None` once the series has
+    /// reached its occurrence count or would run past its `until` date.
+    pub fn next_after(&self, current: NaiveDate) -> Option<NaiveDate> {
+        if self.count.is_some_and(|count| self.occurrence >= count) {
+            return None;
+        }
+        let mut candidate = self.step(current)?;
+        while self.except.contains(&candidate) {
+            candidate = self.step(candidate)?;
+        }
+        self.within_until(candidate)
+    }
+
+    /// Returns the rule for the occurrence after the one due on `current`.
+    pub fn advanced(&self, current: NaiveDate) -> Self {
+        let mut next = Recurrence {
+            occurrence: self.occurrence + 1,
+            ..self.clone()
+        };
+        next.anchor(current);
+        next
+    }
+
+    fn within_until(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self.until {
+            Some(until) if date > until => None,
+            _ => Some(date),
+        }
+    }
+}
+
+/// Returns the number of days in the month of `date`.
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).expect("day 1 exists");
+    first
+        .checked_add_months(Months::new(1))
+        .map_or(31, |next| (next - first).num_days() as u32)
+}
+
+/// Returns the date `months` months after `date`, on `day` or the last day
+/// of a shorter month.
+fn add_months_on_day(date: NaiveDate, months: u32, day: u32) -> Option<NaiveDate> {
+    let month = date
+        .with_day(1)
+        .expect("day 1 exists")
+        .checked_add_months(Months::new(months))?;
+    month.with_day(day.min(days_in_month(month)))
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Parses a rule such as `daily`, `weekly`, `monthly`, `yearly`,
+    /// `3d`, `2w`, `1m`, `1y`, or `every 2 weeks`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Recurrence must look like 'daily', 'weekly', 'monthly', 'yearly', '2w', or 'every 3 days'. Failed to parse: '{}'",
+                s
+            )
+        };
+        let normalized = s.trim().to_ascii_lowercase();
+
+        let (every, unit) = match normalized.as_str() {
+            "daily" => (1, "d"),
+            "weekly" => (1, "w"),
+            "monthly" => (1, "m"),
+            "yearly" | "annually" => (1, "y"),
+            other => {
+                let rest = other.strip_prefix("every").unwrap_or(other).trim();
+                let digits_end = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let (number, unit) = rest.split_at(digits_end);
+                let every = if number.is_empty() {
+                    1
+                } else {
+                    number.parse().map_err(|_| error())?
+                };
+                (every, unit.trim())
+            }
+        };
+
+        let unit = match unit.trim_end_matches('s') {
+            "d" | "day" => RecurrenceUnit::Day,
+            "w" | "week" => RecurrenceUnit::Week,
+            "m" | "month" => RecurrenceUnit::Month,
+            "y" | "year" => RecurrenceUnit::Year,
+            _ => return Err(error()),
+        };
+        if every == 0 {
+            return Err(error());
+        }
+        Ok(Recurrence::new(every, unit))
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.every == 1 {
+            write!(f, "every {}", self.unit.name())?;
+        } else {
+            write!(f, "every {} {}s", self.every, self.unit.name())?;
+        }
+
+        let mut details = Vec::new();
+        if let Some(count) = self.count {
+            details.push(format!("occurrence {} of {}", self.occurrence, count));
+        }
+        if let Some(until) = self.until {
+            details.push(format!("until {}", until.format("%Y-%m-%d")));
+        }
+        if !self.except.is_empty() {
+            let dates: Vec<String> = self
+                .except
+                .iter()
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .collect();
+            details.push(format!("except {}", dates.join(", ")));
+        }
+        if !details.is_empty() {
+            write!(f, " ({})", details.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// Walks the series from `start` the way completing each occurrence
+    /// does, returning up to `limit` due dates.
+    fn series(mut rule: Recurrence, start: NaiveDate, limit: usize) -> Vec<NaiveDate> {
+        rule.anchor(start);
+        let mut due = rule.first_on_or_after(start);
+        let mut dates = Vec::new();
+        while let Some(current) = due {
+            dates.push(current);
+            if dates.len() == limit {
+                break;
+            }
+            due = rule.next_after(current);
+            rule = rule.advanced(current);
+        }
+        dates
+    }
+
+    #[test]
+    fn monthly_series_returns_to_month_end_after_short_months() {
+        let rule = Recurrence::new(1, RecurrenceUnit::Month);
+        assert_eq!(
+            series(rule, date(2026, 1, 31), 5),
+            [
+                date(2026, 1, 31),
+                date(2026, 2, 28),
+                date(2026, 3, 31),
+                date(2026, 4, 30),
+                date(2026, 5, 31),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_series_on_the_30th_keeps_it_after_february() {
+        let rule = Recurrence::new(1, RecurrenceUnit::Month);
+        assert_eq!(
+            series(rule, date(2028, 1, 30), 3),
+            [date(2028, 1, 30), date(2028, 2, 29), date(2028, 3, 30)]
+        );
+    }
+
+    #[test]
+    fn every_other_month_crosses_the_year_end() {
+        let rule = Recurrence::new(2, RecurrenceUnit::Month);
+        assert_eq!(
+            series(rule, date(2026, 10, 31), 3),
+            [date(2026, 10, 31), date(2026, 12, 31), date(2027, 2, 28)]
+        );
+    }
+
+    #[test]
+    fn monthly_series_without_a_recorded_day_anchors_on_first_advance() {
+        // Rules stored before the day was recorded.
+        let rule = Recurrence::new(1, RecurrenceUnit::Month);
+        let next = rule.next_after(date(2026, 1, 31)).unwrap();
+        assert_eq!(next, date(2026, 2, 28));
+        let rule = rule.advanced(date(2026, 1, 31));
+        assert_eq!(rule.next_after(next), Some(date(2026, 3, 31)));
+    }
+
+    #[test]
+    fn moved_due_date_starts_the_series_over_from_its_day() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Month);
+        rule.anchor(date(2026, 1, 31));
+        // Rescheduled by hand to the 15th.
+        assert_eq!(rule.next_after(date(2026, 3, 15)), Some(date(2026, 4, 15)));
+        let rule = rule.advanced(date(2026, 3, 15));
+        assert_eq!(rule.day, Some(15));
+    }
+
+    #[test]
+    fn yearly_series_on_leap_day_returns_to_it_in_leap_years() {
+        let rule = Recurrence::new(1, RecurrenceUnit::Year);
+        assert_eq!(
+            series(rule, date(2024, 2, 29), 5),
+            [
+                date(2024, 2, 29),
+                date(2025, 2, 28),
+                date(2026, 2, 28),
+                date(2027, 2, 28),
+                date(2028, 2, 29),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_four_years_from_leap_day_stays_on_it() {
+        let rule = Recurrence::new(4, RecurrenceUnit::Year);
+        assert_eq!(
+            series(rule, date(2024, 2, 29), 3),
+            [date(2024, 2, 29), date(2028, 2, 29), date(2032, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn daily_and_weekly_steps_cross_month_and_year_ends() {
+        let daily = Recurrence::new(1, RecurrenceUnit::Day);
+        assert_eq!(daily.step(date(2026, 12, 31)), Some(date(2027, 1, 1)));
+        assert_eq!(daily.step(date(2028, 2, 28)), Some(date(2028, 2, 29)));
+        let weekly = Recurrence::new(2, RecurrenceUnit::Week);
+        assert_eq!(weekly.step(date(2026, 12, 24)), Some(date(2027, 1, 7)));
+    }
+
+    #[test]
+    fn exceptions_are_skipped() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Week);
+        rule.except = vec![date(2026, 12, 24), date(2026, 12, 31)];
+        assert_eq!(
+            series(rule, date(2026, 12, 17), 3),
+            [date(2026, 12, 17), date(2027, 1, 7), date(2027, 1, 14)]
+        );
+    }
+
+    #[test]
+    fn excepted_start_moves_to_the_next_occurrence() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Month);
+        rule.except = vec![date(2026, 1, 31)];
+        rule.anchor(date(2026, 1, 31));
+        assert_eq!(
+            rule.first_on_or_after(date(2026, 1, 31)),
+            Some(date(2026, 2, 28))
+        );
+    }
+
+    #[test]
+    fn excepted_short_month_keeps_the_day() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Month);
+        rule.except = vec![date(2026, 2, 28)];
+        assert_eq!(
+            series(rule, date(2026, 1, 31), 2),
+            [date(2026, 1, 31), date(2026, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn count_ends_the_series() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Day);
+        rule.count = Some(3);
+        assert_eq!(
+            series(rule, date(2026, 10, 30), 10),
+            [date(2026, 10, 30), date(2026, 10, 31), date(2026, 11, 1)]
+        );
+    }
+
+    #[test]
+    fn until_ends_the_series_inclusively() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Week);
+        rule.until = Some(date(2026, 11, 12));
+        assert_eq!(
+            series(rule, date(2026, 10, 29), 10),
+            [date(2026, 10, 29), date(2026, 11, 5), date(2026, 11, 12)]
+        );
+    }
+
+    #[test]
+    fn until_before_the_first_date_leaves_no_occurrence() {
+        let mut rule = Recurrence::new(1, RecurrenceUnit::Month);
+        rule.until = Some(date(2026, 1, 30));
+        assert_eq!(rule.first_on_or_after(date(2026, 1, 31)), None);
+    }
+
+    #[test]
+    fn parses_rules() {
+        let parse = |s: &str| s.parse::<Recurrence>().map(|r| (r.every, r.unit));
+        assert_eq!(parse("daily"), Ok((1, RecurrenceUnit::Day)));
+        assert_eq!(parse("Yearly"), Ok((1, RecurrenceUnit::Year)));
+        assert_eq!(parse("2w"), Ok((2, RecurrenceUnit::Week)));
+        assert_eq!(parse("every 3 months"), Ok((3, RecurrenceUnit::Month)));
+        assert!(parse("0d").is_err());
+        assert!(parse("every fortnight").is_err());
+    }
+
+    #[test]
+    fn displays_limits() {
+        let mut rule = Recurrence::new(2, RecurrenceUnit::Week);
+        rule.count = Some(5);
+        rule.until = Some(date(2026, 12, 31));
+        assert_eq!(
+            rule.to_string(),
+            "every 2 weeks (occurrence 1 of 5; until 2026-12-31)"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Reminders for tasks that are due soon, due today, or overdue.
+//!
+//! A pending task with a due date becomes due for a reminder at its due time,
+//! or the start of its due date if it has none, or earlier by the task's
+//! `remind_before` offset. Reminders
+//! fire at most once per task per day, so `remind` can run from cron or a
+//! systemd timer as often as desired.
+//!
+//! A task can also have [`Alert`]s, set with `remind <id> --at`: reminders at
+//! a given time, like `2025-06-01 09:00`, or before the due date, like
+//! `1h before due`. Each alert goes off once.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::app::{Config, DateOrder, DueAt, Task, clock, format_minutes};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// Parses a reminder offset such as `30m`, `3h`, `2d`, `1w`, or `1d12h` into minutes.
+pub fn parse_offset(s: &str) -> Result<u32, String> {
+    let error = || {
+        format!(
+            "Offset must look like '30m', '3h', '2d', or '1w'. Failed to parse: '{}'",
+            s
+        )
+    };
+    let input = s.trim().to_ascii_lowercase();
+    let mut total: u32 = 0;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'm' => 1,
+            'h' => 60,
+            'd' => MINUTES_PER_DAY,
+            'w' => MINUTES_PER_WEEK,
+            _ => return Err(error()),
+        };
+        let value: u32 = number.parse().map_err(|_| error())?;
+        number.clear();
+        total = value
+            .checked_mul(unit)
+            .and_then(|minutes| total.checked_add(minutes))
+            .ok_or_else(error)?;
+    }
+    if !number.is_empty() || total == 0 {
+        return Err(error());
+    }
+    Ok(total)
+}
+
+/// Formats an offset in minutes as `1w`, `2d`, or `3h 30m`.
+pub fn format_offset(minutes: u32) -> String {
+    if minutes.is_multiple_of(MINUTES_PER_WEEK) {
+        format!("{}w", minutes / MINUTES_PER_WEEK)
+    } else if minutes.is_multiple_of(MINUTES_PER_DAY) {
+        format!("{}d", minutes / MINUTES_PER_DAY)
+    } else {
+        format_minutes(minutes)
+    }
+}
+
+/// The suffix of an alert that goes off before the due date.
+const BEFORE_DUE: &str = " before due";
+
+/// A reminder set on a task with `remind <id> --at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alert {
+    /// At this local date and time.
+    At(NaiveDateTime),
+    /// This many minutes before the task is due.
+    BeforeDue(u32),
+}
+
+impl Alert {
+    /// Returns when the alert goes off for `task`, or `None` if it counts
+    /// from a due date the task doesn't have.
+    pub fn time(self, task: &Task) -> Option<DateTime<Utc>> {
+        match self {
+            Alert::At(at) => Some(clock::from_local(at)),
+            Alert::BeforeDue(minutes) => Some(due_time(task)? - Duration::minutes(minutes.into())),
+        }
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::At(at) => write!(f, "{}", at.format("%Y-%m-%d %H:%M")),
+            Alert::BeforeDue(minutes) => write!(f, "{}{}", format_offset(*minutes), BEFORE_DUE),
+        }
+    }
+}
+
+impl FromStr for Alert {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(offset) = s.trim().strip_suffix(BEFORE_DUE) {
+            return parse_offset(&offset.replace(' ', "")).map(Alert::BeforeDue);
+        }
+        NaiveDateTime::parse_from_str(s.trim(), "%Y-%m-%d %H:%M")
+            .map(Alert::At)
+            .map_err(|_| format!("Invalid reminder: '{}'", s))
+    }
+}
+
+impl Serialize for Alert {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Alert {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An alert as given to `remind --at`: a date and time in any of the
+/// [`DueAt`] formats, such as `2025-06-01 09:00` or `tomorrow 9am`, or an
+/// offset before the due date, such as `1h before due`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertSpec {
+    At(DueAt),
+    BeforeDue(u32),
+}
+
+impl AlertSpec {
+    /// Settles an ambiguous date, see [`DueSpec::with_order`].
+    ///
+    /// [`DueSpec::with_order`]: crate::app::DueSpec::with_order
+    pub fn with_order(self, order: Option<DateOrder>) -> Result<Self, String> {
+        match self {
+            AlertSpec::At(at) => at.with_order(order).map(AlertSpec::At),
+            offset => Ok(offset),
+        }
+    }
+
+    /// Resolves to an alert relative to `now`; a date without a time of day
+    /// means its start.
+    pub fn resolve(self, now: NaiveDateTime) -> Option<Alert> {
+        match self {
+            AlertSpec::At(at) => {
+                let (date, time) = at.resolve(now)?;
+                Some(Alert::At(date.and_time(time.unwrap_or(NaiveTime::MIN))))
+            }
+            AlertSpec::BeforeDue(minutes) => Some(Alert::BeforeDue(minutes)),
+        }
+    }
+}
+
+impl FromStr for AlertSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_ascii_lowercase();
+        let parsed = match input.strip_suffix(BEFORE_DUE) {
+            Some(offset) => parse_offset(&offset.replace(' ', ""))
+                .ok()
+                .map(AlertSpec::BeforeDue),
+            None => input.parse().ok().map(AlertSpec::At),
+        };
+        parsed.ok_or_else(|| {
+            format!(
+                "A reminder must be a date and time such as '2025-06-01 09:00' or 'tomorrow 9am', or an offset such as '1h before due'. Failed to parse: '{}'",
+                s
+            )
+        })
+    }
+}
+
+/// Returns when `task` is due, at the start of its due date if it has no
+/// time of day.
+fn due_time(task: &Task) -> Option<DateTime<Utc>> {
+    Some(clock::from_local(
+        task.due_date?
+            .and_time(task.due_time.unwrap_or(NaiveTime::MIN)),
+    ))
+}
+
+/// Returns when `task` becomes due for a reminder, or `None` without a due date.
+pub fn reminder_time(task: &Task) -> Option<DateTime<Utc>> {
+    Some(due_time(task)? - Duration::minutes(task.remind_before_minutes.unwrap_or(0).into()))
+}
+
+/// Returns every time `task` is reminded about: for its due date and at each
+/// of its alerts.
+fn reminder_times(task: &Task) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+    reminder_time(task)
+        .into_iter()
+        .chain(task.alerts.iter().filter_map(|alert| alert.time(task)))
+}
+
+/// Returns `true` if one of the alerts of `task` went off by `now` and
+/// wasn't delivered yet.
+fn alert_due(task: &Task, now: DateTime<Utc>) -> bool {
+    task.alerts
+        .iter()
+        .filter_map(|alert| alert.time(task))
+        .any(|at| at <= now && task.alerted_at.is_none_or(|last| at > last))
+}
+
+/// Returns the IDs of pending tasks that should be reminded about at `now`,
+/// skipping tasks already reminded about on the same day unless one of their
+/// alerts went off since.
+pub fn due_reminders(tasks: &[Task], now: DateTime<Utc>) -> Vec<u32> {
+    let today: NaiveDate = clock::local_date(now);
+    tasks
+        .iter()
+        .filter(|t| t.is_pending())
+        .filter(|t| {
+            (t.reminded_on != Some(today) && reminder_time(t).is_some_and(|at| at <= now))
+                || alert_due(t, now)
+        })
+        .map(|t| t.id)
+        .collect()
+}
+
+/// Records on `task` that it was reminded about at `now`.
+pub fn mark_reminded(task: &mut Task, now: DateTime<Utc>) {
+    if reminder_time(task).is_some_and(|at| at <= now) {
+        task.reminded_on = Some(clock::local_date(now));
+    }
+    if !task.alerts.is_empty() {
+        task.alerted_at = Some(now);
+    }
+}
+
+/// Returns the reminders still to come after `now` for pending tasks,
+/// soonest first: the one for each due date and every alert.
+pub fn upcoming_reminders(tasks: &[Task], now: DateTime<Utc>) -> Vec<(DateTime<Utc>, &Task)> {
+    let mut upcoming: Vec<(DateTime<Utc>, &Task)> = tasks
+        .iter()
+        .filter(|t| t.is_pending())
+        .flat_map(|task| {
+            reminder_times(task)
+                .filter(|&at| at > now)
+                .map(move |at| (at, task))
+        })
+        .collect();
+    upcoming.sort_by_key(|(at, task)| (*at, task.id));
+    upcoming.dedup_by_key(|(at, task)| (*at, task.id));
+    upcoming
+}
+
+/// A reminder ready to be shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    pub task_id: u32,
+    /// When the task is due relative to today, e.g. `due today`.
+    pub when: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// Builds the reminders due at `now`, formatting dates with `config`.
+pub fn build_reminders(tasks: &[Task], now: DateTime<Utc>, config: &Config) -> Vec<Reminder> {
+    let today = clock::local_date(now);
+    let due = due_reminders(tasks, now);
+    tasks
+        .iter()
+        .filter(|t| due.contains(&t.id))
+        .map(|task| {
+            let Some(date) = task.due_date else {
+                return Reminder {
+                    task_id: task.id,
+                    title: format!("Task {}", task.id),
+                    body: task.description.clone(),
+                    when: "reminder".to_string(),
+                };
+            };
+            let when = if date < today {
+                format!("overdue since {}", config.format_date(date))
+            } else if date == today {
+                "due today".to_string()
+            } else {
+                format!("due {}", config.format_date(date))
+            };
+            let when = match task.due_time {
+                Some(time) if date >= today => format!("{} at {}", when, time.format("%H:%M")),
+                _ => when,
+            };
+            Reminder {
+                task_id: task.id,
+                title: format!("Task {} {}", task.id, when),
+                body: task.description.clone(),
+                when,
+            }
+        })
+        .collect()
+}
+
+/// Returns the next moment after `now` at which a reminder may become due:
+/// the earliest upcoming reminder time, or the next midnight, when tasks
+/// reminded about today become due again.
+pub fn next_reminder_check(tasks: &[Task], now: DateTime<Utc>) -> DateTime<Utc> {
+    let next_midnight = (clock::local_date(now) + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .map(clock::from_local)
+        .unwrap_or(now);
+    tasks
+        .iter()
+        .filter(|t| t.is_pending())
+        .flat_map(reminder_times)
+        .filter(|&at| at > now)
+        .fold(next_midnight, DateTime::min)
+}
@@ -0,0 +1,119 @@
+//! Data files published over HTTP(S), read with `--file https://...`.
+//!
+//! A team can put its backlog on any web server and everyone reads it with
+//! the usual commands, e.g. `task --file https://example.com/team.toml
+//! list`. The file is read only: commands that would change tasks fail.
+//!
+//! The last copy fetched is kept in the cache directory along with its
+//! `ETag`, and sent back in `If-None-Match`, so an unchanged file isn't
+//! downloaded again. When the server can't be reached, the cached copy is
+//! used with a warning.
+
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ureq::Agent;
+
+use crate::app::{AppError, CONFIG_DIR_NAME, Result};
+
+/// The largest remote data file read, in bytes.
+const MAX_REMOTE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Returns `true` if `path` is an `http://` or `https://` URL rather than a
+/// file.
+pub fn is_remote(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|s| s.starts_with("https://") || s.starts_with("http://"))
+}
+
+/// Returns where the copy of the data file at `url` is cached.
+pub fn remote_cache_path(url: &str) -> PathBuf {
+    let name = hex::encode(&Sha256::digest(url.as_bytes())[..16]);
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(CONFIG_DIR_NAME)
+        .join("remote")
+        .join(format!("{}.toml", name))
+}
+
+/// The cached copy of a remote data file.
+#[derive(Debug, Clone)]
+pub struct RemoteCopy {
+    pub path: PathBuf,
+    /// Why the copy may be out of date, if the server couldn't be asked.
+    pub stale_because: Option<String>,
+}
+
+/// Fetches the data file at `url` into the cache unless the cached copy is
+/// still current.
+///
+/// # Returns
+///
+/// An `AppError::Remote` if the file can't be fetched and was never cached.
+pub fn fetch_remote(url: &str) -> Result<RemoteCopy> {
+    let path = remote_cache_path(url);
+    let etag_path = path.with_extension("etag");
+    let cached = path.exists();
+    let etag = fs::read_to_string(&etag_path).ok().filter(|_| cached);
+
+    let agent = Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .new_agent();
+    let mut request = agent.get(url);
+    if let Some(etag) = &etag {
+        request = request.header("If-None-Match", etag.trim());
+    }
+    let fetched = request
+        .call()
+        .map_err(|e| e.to_string())
+        .and_then(|response| match response.status().as_u16() {
+            304 => Ok(None),
+            200..=299 => Ok(Some(response)),
+            status => Err(format!("{} returned HTTP {}", url, status)),
+        });
+    let mut response = match fetched {
+        Ok(Some(response)) => response,
+        Ok(None) => {
+            debug!("{} is unchanged since it was cached.", url);
+            return Ok(RemoteCopy {
+                path,
+                stale_because: None,
+            });
+        }
+        Err(reason) if cached => {
+            return Ok(RemoteCopy {
+                path,
+                stale_because: Some(reason),
+            });
+        }
+        Err(reason) => return Err(AppError::Remote(reason)),
+    };
+
+    let etag = response
+        .headers()
+        .get("ETag")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let contents = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_REMOTE_BYTES)
+        .read_to_vec()
+        .map_err(|e| AppError::Remote(format!("{}: {}", url, e)))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, contents)?;
+    match etag {
+        Some(etag) => fs::write(&etag_path, etag)?,
+        None if etag_path.exists() => fs::remove_file(&etag_path)?,
+        None => {}
+    }
+    debug!("Fetched {} into {}.", url, path.display());
+    Ok(RemoteCopy {
+        path,
+        stale_because: None,
+    })
+}
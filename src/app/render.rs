@@ -0,0 +1,584 @@
+//! Formatting of tabular command output.
+//!
+//! Handlers that list things build a [`Table`] and leave its layout to a
+//! [`Renderer`], picked with the global `--output-format` flag or the
+//! `output_format` config setting. `table` draws the aligned, colored columns
+//! meant for reading; `plain` writes tab-separated values for shell
+//! pipelines; `json` and `csv` are for other programs. The raw value of each
+//! cell is what the last three emit, so nothing is truncated there.
+//!
+//! Widths are measured in terminal columns, so wide characters such as CJK
+//! and emoji count twice. On a terminal, the description column of a table
+//! grows or shrinks so the table fits the window.
+
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::app::OutputSink;
+use crate::tr;
+
+/// The width of the description column in the task table.
+pub const DESCRIPTION_WIDTH: usize = 28;
+
+/// The narrowest a column is squeezed to fit the terminal.
+const MIN_FLEXIBLE_WIDTH: usize = 12;
+
+/// Due dates this many days away or fewer are counted in days, later ones
+/// in weeks.
+const MAX_DAYS_SHOWN: u64 = 13;
+
+/// Due dates this many weeks away or fewer are counted in weeks, later ones
+/// in months.
+const MAX_WEEKS_SHOWN: u64 = 7;
+
+/// How lists of tasks and projects are printed.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    /// Aligned columns under a header.
+    #[default]
+    Table,
+    /// Tab-separated values without a header.
+    Plain,
+    /// An array of objects keyed by column.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl RenderFormat {
+    /// Returns `true` for the formats people read, which may be surrounded
+    /// by headings and summaries; the others carry nothing but the table.
+    pub fn for_humans(self) -> bool {
+        self == RenderFormat::Table
+    }
+
+    /// Returns the renderer for this format. `color` only affects `table`.
+    pub fn renderer(self, color: bool) -> Box<dyn Renderer> {
+        match self {
+            RenderFormat::Table => Box::new(TableRenderer {
+                color,
+                width: terminal_width(),
+            }),
+            RenderFormat::Plain => Box::new(PlainRenderer),
+            RenderFormat::Json => Box::new(JsonRenderer),
+            RenderFormat::Csv => Box::new(CsvRenderer),
+        }
+    }
+}
+
+/// Terminal colors for table cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Green => "32",
+            Color::Yellow => "33",
+            Color::Dim => "2",
+        }
+    }
+}
+
+/// Wraps `text` in the ANSI code of `color` when `enabled`.
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Shortens `s` to the first line and at most `max` characters for table display.
+///
+/// An ellipsis marks text that was cut off.
+pub fn truncate(s: &str, max: usize) -> String {
+    let first_line = s.lines().next().unwrap_or("");
+    let shortened = first_line != s;
+    if !shortened && display_width(first_line) <= max {
+        return first_line.to_string();
+    }
+
+    let keep = max.saturating_sub(3);
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in first_line.chars() {
+        width += c.width().unwrap_or(0);
+        if width > keep {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push_str("...");
+    truncated
+}
+
+/// How [`relative_date`] words a date before today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Past {
+    /// "yesterday", "3 days ago".
+    Ago,
+    /// "1 day overdue", "3 days overdue"; for due dates.
+    Overdue,
+}
+
+/// Describes `date` by how far it is from `today`: "today", "tomorrow",
+/// "in 3 days", "in 4 months", and before today as `past` words it.
+///
+/// Days are counted up to two weeks, then weeks up to two months, then
+/// whole calendar months, so June 30 is a month after May 31.
+pub fn relative_date(date: NaiveDate, today: NaiveDate, past: Past) -> String {
+    let days = (date - today).num_days();
+    let span = days.unsigned_abs();
+    let (unit, count) = if span <= MAX_DAYS_SHOWN {
+        ("day", span)
+    } else if span / 7 <= MAX_WEEKS_SHOWN {
+        ("week", span / 7)
+    } else {
+        ("month", months_between(date.min(today), date.max(today)))
+    };
+    match (days, past) {
+        (0, _) => tr!("date-today"),
+        (1, _) => tr!("date-tomorrow"),
+        (-1, Past::Ago) => tr!("date-yesterday"),
+        (2.., _) => tr!("date-in", count = count, unit = unit),
+        (_, Past::Ago) => tr!("date-ago", count = count, unit = unit),
+        (_, Past::Overdue) => tr!("date-overdue", count = count, unit = unit),
+    }
+}
+
+/// Returns how many whole calendar months lie between `from` and the later
+/// `to`; a month ends on the same day of the month, or on the last day of
+/// a shorter month.
+fn months_between(from: NaiveDate, to: NaiveDate) -> u64 {
+    let months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    let month_end = to.succ_opt().is_none_or(|next| next.month() != to.month());
+    let short = to.day() < from.day() && !month_end;
+    (months - i32::from(short)).max(0) as u64
+}
+
+/// Returns how many terminal columns `s` takes up.
+pub fn display_width(s: &str) -> usize {
+    s.width()
+}
+
+/// Left-aligns `s` in `width` terminal columns, like `{:<width$}` does for
+/// text whose characters are all one column wide.
+pub fn pad(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+/// Returns how wide the terminal standard output goes to is, or `None` if
+/// it isn't one.
+#[cfg(feature = "native")]
+pub fn terminal_width() -> Option<usize> {
+    use std::io::{self, IsTerminal};
+
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|&columns| columns > 0)
+}
+
+/// Without a terminal to ask, tables keep their natural width.
+#[cfg(not(feature = "native"))]
+pub fn terminal_width() -> Option<usize> {
+    None
+}
+
+/// Draws `values` as a line of block characters, the highest as a full block;
+/// with `ascii`, as a line of ASCII characters growing from `_` to `#`.
+pub fn sparkline(values: &[usize], ascii: bool) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const ASCII_BLOCKS: [char; 8] = ['_', '.', ':', '-', '=', '+', '*', '#'];
+    let blocks = if ascii { ASCII_BLOCKS } else { BLOCKS };
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&v| blocks[v * (blocks.len() - 1) / max])
+        .collect()
+}
+
+/// A column of a [`Table`].
+#[derive(Debug, Clone)]
+pub struct Column {
+    /// The name of the column in JSON and CSV.
+    pub key: &'static str,
+    /// The heading of the column in `table`.
+    pub title: String,
+    /// How wide the column is drawn in `table`; longer text is truncated.
+    pub width: usize,
+}
+
+/// One value of a [`Table`].
+#[derive(Debug, Clone)]
+pub struct Cell {
+    /// What `table` shows.
+    pub text: String,
+    /// What the other formats emit.
+    pub value: Value,
+    /// How `table` colors the cell.
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    /// A cell showing `value` as is.
+    pub fn new(value: impl Into<Value>) -> Self {
+        let value = value.into();
+        let text = match &value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        Cell {
+            text,
+            value,
+            color: None,
+        }
+    }
+
+    /// A cell without a value, shown as `placeholder` in `table`.
+    pub fn missing(placeholder: &str) -> Self {
+        Cell {
+            text: placeholder.to_string(),
+            value: Value::Null,
+            color: None,
+        }
+    }
+
+    /// Shows the cell as `text` in `table` instead of its value.
+    pub fn shown_as(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Colors the cell in `table`.
+    pub fn colored(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the value as text for `plain` and `csv`; lists are joined
+    /// with commas.
+    fn raw(&self) -> String {
+        raw_text(&self.value)
+    }
+}
+
+fn raw_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(raw_text).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Rows of values under named columns.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Cell>>,
+    /// The column that grows and shrinks with the terminal in `table`.
+    pub flexible: Option<usize>,
+}
+
+impl Table {
+    /// Starts an empty table with the given columns. The description
+    /// column, if there is one, adapts to the terminal width.
+    pub fn new(columns: Vec<Column>) -> Self {
+        let flexible = columns.iter().position(|c| c.key == "description");
+        Table {
+            columns,
+            rows: Vec::new(),
+            flexible,
+        }
+    }
+
+    /// Appends a row with one cell per column.
+    pub fn push(&mut self, row: Vec<Cell>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+}
+
+/// Writes a [`Table`] to an [`OutputSink`] in some format.
+pub trait Renderer {
+    fn table(&self, out: &mut dyn OutputSink, table: &Table);
+}
+
+/// Draws aligned columns with a header and a rule below it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableRenderer {
+    pub color: bool,
+    /// How wide the terminal is; `None` keeps the columns as wide as given.
+    pub width: Option<usize>,
+}
+
+impl TableRenderer {
+    /// Returns the widths to draw the columns of `table` at: the flexible
+    /// column takes as much of the terminal as its longest cell needs, and
+    /// gives up room when the table would be wider than the terminal.
+    fn widths(&self, table: &Table) -> Vec<usize> {
+        let mut widths: Vec<usize> = table.columns.iter().map(|c| c.width).collect();
+        let (Some(terminal), Some(flexible)) = (self.width, table.flexible) else {
+            return widths;
+        };
+        let others: usize = widths
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != flexible)
+            .map(|(_, width)| width + 1)
+            .sum();
+        let longest = table
+            .rows
+            .iter()
+            .filter_map(|row| row.get(flexible))
+            .map(|cell| display_width(cell.text.lines().next().unwrap_or("")))
+            .chain([display_width(&table.columns[flexible].title)])
+            .max()
+            .unwrap_or(0);
+        widths[flexible] = longest
+            .min(terminal.saturating_sub(others))
+            .max(MIN_FLEXIBLE_WIDTH.min(widths[flexible]));
+        widths
+    }
+}
+
+impl Renderer for TableRenderer {
+    fn table(&self, out: &mut dyn OutputSink, table: &Table) {
+        let widths = self.widths(table);
+        let last = table.columns.len().saturating_sub(1);
+        let align = |i: usize, text: &str, width: usize| {
+            if i == last {
+                text.to_string()
+            } else {
+                pad(text, width)
+            }
+        };
+
+        let header: Vec<String> = table
+            .columns
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (c, &width))| align(i, &c.title, width))
+            .collect();
+        out.line(&header.join(" "));
+        let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        out.line(&rule.join(" "));
+
+        for row in &table.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, &width))| {
+                    let text = align(i, &truncate(&cell.text, width), width);
+                    match cell.color {
+                        Some(color) => paint(&text, color, self.color),
+                        None => text,
+                    }
+                })
+                .collect();
+            out.line(&cells.join(" "));
+        }
+    }
+}
+
+/// Writes one line of tab-separated values per row.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn table(&self, out: &mut dyn OutputSink, table: &Table) {
+        for row in &table.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| cell.raw().replace(['\t', '\n'], " "))
+                .collect();
+            out.line(&cells.join("\t"));
+        }
+    }
+}
+
+/// Emits the rows as an array of JSON objects.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn table(&self, out: &mut dyn OutputSink, table: &Table) {
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = table
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| (column.key.to_string(), cell.value.clone()))
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        out.value(&Value::Array(rows));
+    }
+}
+
+/// Writes RFC 4180 comma-separated values with the column keys as header.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvRenderer;
+
+/// Quotes a CSV field if it contains a separator, quote, or line break.
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl Renderer for CsvRenderer {
+    fn table(&self, out: &mut dyn OutputSink, table: &Table) {
+        let header: Vec<&str> = table.columns.iter().map(|c| c.key).collect();
+        out.line(&header.join(","));
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|cell| csv_field(&cell.raw())).collect();
+            out.line(&cells.join(","));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// The day the tests take as today.
+    fn today() -> NaiveDate {
+        date(2024, 5, 15)
+    }
+
+    #[test]
+    fn relative_date_names_the_nearest_days() {
+        assert_eq!(relative_date(today(), today(), Past::Ago), "today");
+        assert_eq!(
+            relative_date(date(2024, 5, 16), today(), Past::Ago),
+            "tomorrow"
+        );
+        assert_eq!(
+            relative_date(date(2024, 5, 14), today(), Past::Ago),
+            "yesterday"
+        );
+        assert_eq!(
+            relative_date(date(2024, 5, 18), today(), Past::Ago),
+            "in 3 days"
+        );
+        assert_eq!(
+            relative_date(date(2024, 5, 5), today(), Past::Ago),
+            "10 days ago"
+        );
+    }
+
+    #[test]
+    fn relative_date_counts_days_then_weeks_then_months() {
+        let due = |date| relative_date(date, today(), Past::Overdue);
+        assert_eq!(due(date(2024, 5, 14)), "1 day overdue");
+        assert_eq!(due(date(2024, 5, 28)), "in 13 days");
+        assert_eq!(due(date(2024, 5, 29)), "in 2 weeks");
+        assert_eq!(due(date(2024, 4, 1)), "6 weeks overdue");
+        assert_eq!(due(date(2024, 9, 20)), "in 4 months");
+        assert_eq!(
+            relative_date(date(2024, 1, 10), today(), Past::Ago),
+            "4 months ago"
+        );
+    }
+
+    #[test]
+    fn relative_date_crosses_month_ends() {
+        let end_of_april = date(2024, 4, 30);
+        assert_eq!(
+            relative_date(date(2024, 5, 1), end_of_april, Past::Overdue),
+            "tomorrow"
+        );
+        assert_eq!(
+            relative_date(end_of_april, date(2024, 5, 1), Past::Ago),
+            "yesterday"
+        );
+        assert_eq!(
+            relative_date(end_of_april, date(2024, 5, 1), Past::Overdue),
+            "1 day overdue"
+        );
+        // A month from May 31 ends on the last day of June.
+        assert_eq!(
+            relative_date(date(2024, 8, 30), date(2024, 5, 31), Past::Overdue),
+            "in 2 months"
+        );
+        assert_eq!(
+            relative_date(date(2024, 8, 31), date(2024, 5, 31), Past::Overdue),
+            "in 3 months"
+        );
+    }
+
+    #[test]
+    fn relative_date_crosses_the_new_year() {
+        let new_years_eve = date(2024, 12, 31);
+        assert_eq!(
+            relative_date(date(2025, 1, 1), new_years_eve, Past::Ago),
+            "tomorrow"
+        );
+        assert_eq!(
+            relative_date(new_years_eve, date(2025, 1, 1), Past::Ago),
+            "yesterday"
+        );
+        assert_eq!(
+            relative_date(date(2025, 1, 3), new_years_eve, Past::Ago),
+            "in 3 days"
+        );
+        assert_eq!(
+            relative_date(date(2025, 3, 31), new_years_eve, Past::Overdue),
+            "in 3 months"
+        );
+    }
+
+    #[test]
+    fn relative_date_counts_the_leap_day() {
+        let leap_day = date(2024, 2, 29);
+        assert_eq!(
+            relative_date(date(2024, 3, 1), leap_day, Past::Ago),
+            "tomorrow"
+        );
+        assert_eq!(
+            relative_date(date(2024, 2, 28), leap_day, Past::Ago),
+            "yesterday"
+        );
+        assert_eq!(
+            relative_date(date(2024, 3, 1), date(2024, 2, 27), Past::Ago),
+            "in 3 days"
+        );
+        assert_eq!(
+            relative_date(date(2023, 3, 1), date(2023, 2, 27), Past::Ago),
+            "in 2 days"
+        );
+        assert_eq!(
+            relative_date(date(2025, 2, 28), leap_day, Past::Overdue),
+            "in 12 months"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Picks the tasks to go through in a GTD-style weekly review.
+//!
+//! `review` asks about every pending task that is overdue, stale, or has no
+//! due date, in that order. A task is stale once it has been around for
+//! longer than the review's threshold without being done.
+//!
+//! `stale` looks at when tasks were last changed instead, to find the ones
+//! nobody has touched in a long time.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+
+use crate::app::{Task, clock};
+
+/// How many days a pending task may sit before `review` calls it stale.
+pub const DEFAULT_STALE_AFTER_DAYS: u32 = 30;
+
+/// Why a task comes up in the review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewReason {
+    Overdue,
+    /// Added more than the given number of days ago.
+    Stale(i64),
+    Undated,
+}
+
+impl fmt::Display for ReviewReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewReason::Overdue => write!(f, "overdue"),
+            ReviewReason::Stale(days) => write!(f, "added {} days ago", days),
+            ReviewReason::Undated => write!(f, "no due date"),
+        }
+    }
+}
+
+/// Returns the IDs of the tasks to review at `now`, overdue ones first, then
+/// stale ones, then those without a due date, each with the first reason
+/// that applies to it.
+///
+/// Tasks waiting for their start date aren't reviewed yet.
+pub fn review_queue(
+    tasks: &[Task],
+    now: DateTime<Utc>,
+    stale_after_days: u32,
+) -> Vec<(u32, ReviewReason)> {
+    let local_now = clock::local(now);
+    let mut queue: Vec<(u32, ReviewReason)> = tasks
+        .iter()
+        .filter(|t| t.is_pending() && !t.is_waiting(local_now.date()))
+        .filter_map(|task| {
+            let age = (now - task.created_at).num_days();
+            let reason = if task.is_overdue(local_now) {
+                ReviewReason::Overdue
+            } else if age > i64::from(stale_after_days) {
+                ReviewReason::Stale(age)
+            } else if task.due_date.is_none() {
+                ReviewReason::Undated
+            } else {
+                return None;
+            };
+            Some((task.id, reason))
+        })
+        .collect();
+    queue.sort_by_key(|&(id, reason)| (rank(reason), id));
+    queue
+}
+
+fn rank(reason: ReviewReason) -> u8 {
+    match reason {
+        ReviewReason::Overdue => 0,
+        ReviewReason::Stale(_) => 1,
+        ReviewReason::Undated => 2,
+    }
+}
+
+/// Returns the pending tasks no command has changed for longer than
+/// `older_than` at `now`, least recently changed first.
+///
+/// Tasks waiting for their start date aren't stale yet.
+pub fn stale_tasks(tasks: &[Task], now: DateTime<Utc>, older_than: Duration) -> Vec<&Task> {
+    let today = clock::local_date(now);
+    let mut stale: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.is_pending() && !t.is_waiting(today))
+        .filter(|t| now - t.last_modified() > older_than)
+        .collect();
+    stale.sort_by_key(|t| (t.last_modified(), t.id));
+    stale
+}
@@ -0,0 +1,36 @@
+//! Date-driven transitions applied every time the task list is loaded.
+//!
+//! The results are reported to the user in a short banner before the
+//! command's own output, so changes never happen silently.
+
+use chrono::NaiveDate;
+use log::info;
+
+use crate::app::Task;
+
+/// What changed during a rollover.
+#[derive(Debug, Default)]
+pub struct RolloverReport {
+    /// IDs of tasks cancelled because their expiration date passed.
+    pub expired: Vec<u32>,
+}
+
+impl RolloverReport {
+    /// Returns `true` if the rollover didn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.expired.is_empty()
+    }
+}
+
+/// Applies all date-driven transitions to `tasks` as of `today`.
+///
+/// Currently this cancels pending tasks whose expiration date has passed.
+pub fn rollover(tasks: &mut [Task], today: NaiveDate) -> RolloverReport {
+    let mut report = RolloverReport::default();
+    for task in tasks.iter_mut().filter(|t| t.is_expired(today)) {
+        task.cancel();
+        info!("Task ID {} expired and was cancelled.", task.id);
+        report.expired.push(task.id);
+    }
+    report
+}
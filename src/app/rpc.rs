@@ -0,0 +1,162 @@
+//! `rpc`: a JSON-RPC 2.0 API over standard input and output, so editor
+//! plugins can drive the task list from one long-running process.
+//!
+//! Every line of input is a request naming a command as `method`, with its
+//! arguments as `params`, and gets one line of output in return:
+//!
+//! ```text
+//! --> {"jsonrpc": "2.0", "id": 1, "method": "add", "params": ["Buy milk", "--due", "tomorrow"]}
+//! <-- {"jsonrpc":"2.0","id":1,"result":{"lines":["Task added: ID 4"],"values":[]}}
+//! --> {"jsonrpc": "2.0", "id": 2, "method": "list", "params": ["--all"]}
+//! <-- {"jsonrpc":"2.0","id":2,"result":{"lines":[],"values":[[{"id":4,"description":"Buy milk", ...}]]}}
+//! ```
+//!
+//! A result holds the command's output: its messages as `lines`, and tables
+//! and tasks as `values`, in the shape `--output-format json` prints them.
+//! Requests without an `id` are notifications and get no response.
+//!
+//! [`serve_rpc`] only speaks the protocol; `mcp` answers it with a method set
+//! of its own.
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+use crate::app::{Result, interrupted};
+
+/// The input line wasn't JSON.
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON wasn't a request.
+pub const INVALID_REQUEST: i64 = -32600;
+/// No command has the name, or it can't run over `rpc`.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// The command's arguments were rejected.
+pub const INVALID_PARAMS: i64 = -32602;
+/// The command ran and failed.
+pub const COMMAND_FAILED: i64 = -32000;
+
+/// Commands that can't run over `rpc`: they read standard input themselves,
+/// write straight to standard output, or never return.
+pub const NOT_OVER_RPC: &[&str] = &[
+    "completions",
+    "daemon",
+    "mcp",
+    "pick",
+    "review",
+    "rpc",
+    "triage",
+    "watch-file",
+];
+
+/// A call of one command.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    /// Echoed back in the response; absent for notifications.
+    #[serde(default)]
+    pub id: Option<Value>,
+    /// The command to run, as typed on the command line.
+    pub method: String,
+    /// For `rpc`, the command's arguments as typed on the command line.
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    /// Returns `params` as command-line arguments: a list of strings, or
+    /// nothing.
+    pub fn args(&self) -> std::result::Result<Vec<String>, RpcError> {
+        match &self.params {
+            Value::Null => Ok(Vec::new()),
+            params => serde_json::from_value(params.clone())
+                .map_err(|_| RpcError::new(INVALID_PARAMS, "params must be a list of strings")),
+        }
+    }
+}
+
+/// Why a request failed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse<T> {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+/// Answers the requests read from `input` on `output` until `input` ends or
+/// a signal arrives during a request, running each through `call`. Returns
+/// how many requests were answered.
+pub fn serve_rpc<T: Serialize>(
+    input: impl BufRead,
+    mut output: impl Write,
+    mut call: impl FnMut(&RpcRequest) -> std::result::Result<T, RpcError>,
+) -> Result<usize> {
+    let mut answered = 0;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, outcome) = match serde_json::from_str::<Value>(&line) {
+            Err(e) => (
+                Some(Value::Null),
+                Err(RpcError::new(PARSE_ERROR, e.to_string())),
+            ),
+            Ok(value) => {
+                let id = value.get("id").cloned();
+                match serde_json::from_value::<RpcRequest>(value) {
+                    Err(e) => (
+                        Some(id.unwrap_or(Value::Null)),
+                        Err(RpcError::new(INVALID_REQUEST, e.to_string())),
+                    ),
+                    Ok(request) => {
+                        debug!("RPC call: {} {}", request.method, request.params);
+                        (request.id.clone(), call(&request))
+                    }
+                }
+            }
+        };
+        let Some(id) = id else {
+            if let Err(error) = outcome {
+                warn!("RPC notification failed: {}", error.message);
+            }
+            continue;
+        };
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        let response = RpcResponse {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        };
+        writeln!(output, "{}", serde_json::to_string(&response)?)?;
+        output.flush()?;
+        answered += 1;
+        if interrupted() {
+            info!("RPC stopped by a signal after {} responses.", answered);
+            return Ok(answered);
+        }
+    }
+    info!("RPC input closed after {} responses.", answered);
+    Ok(answered)
+}
@@ -0,0 +1,296 @@
+//! Turning `TODO` and `FIXME` comments in source code into tasks.
+//!
+//! `scan` reads the files under a directory, or the files git tracks when it
+//! is inside a repository, and adds a task for each comment it hasn't seen
+//! before. Comments are recognized by a hash of their file and text rather
+//! than their line, so code moving around doesn't add them again; the hashes
+//! are kept in a state file next to the data file. A task is tagged with the
+//! kind of comment and its `file:line`, which is kept up to date on later
+//! scans while the task is open.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use uuid::Uuid;
+
+use crate::app::{Result, Task, encryption};
+
+/// The words that mark a comment as code debt.
+const MARKERS: &[&str] = &["TODO", "FIXME"];
+
+/// What a comment starts with in the languages worth scanning; a marker only
+/// counts right after one of these.
+const COMMENT_LEADERS: &[&str] = &["//", "//!", "#", "/*", "*", "--", ";", "<!--", "%"];
+
+/// Directories that hold generated or third-party code, skipped when the
+/// files aren't listed by git.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Files larger than this are assumed not to be source code.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A `TODO` or `FIXME` comment found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeComment {
+    /// `TODO` or `FIXME`.
+    pub marker: &'static str,
+    /// The file, relative to the scanned directory.
+    pub path: String,
+    /// The line, counted from 1.
+    pub line: usize,
+    /// What the comment says after the marker.
+    pub text: String,
+}
+
+impl CodeComment {
+    /// Returns `file:line`, the tag that points at the comment.
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+
+    /// Returns the hash the comment is recognized by across scans.
+    fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.marker.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.text.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    fn description(&self) -> String {
+        if self.text.is_empty() {
+            format!("{} in {}", self.marker, self.path)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// What a scan changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Files read.
+    pub files: usize,
+    /// Tasks created for comments not seen before.
+    pub added: usize,
+    /// Open tasks whose comment moved to another line.
+    pub moved: usize,
+}
+
+/// A comment scanned before and the task made for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScannedComment {
+    hash: String,
+    uuid: Uuid,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScanState {
+    #[serde(default)]
+    comments: Vec<ScannedComment>,
+}
+
+/// Returns where the comments scanned into the data file at `tasks_path`
+/// are remembered.
+pub fn scan_state_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.scan.toml", stem))
+}
+
+fn load_state(tasks_path: &Path) -> Result<ScanState> {
+    let path = scan_state_path(tasks_path);
+    if !path.exists() {
+        return Ok(ScanState::default());
+    }
+    Ok(toml::from_str(&encryption::read_file(&path)?)?)
+}
+
+fn save_state(state: &ScanState, tasks_path: &Path) -> Result<()> {
+    encryption::write_file(
+        &scan_state_path(tasks_path),
+        &toml::to_string(state)?,
+        encryption::should_encrypt(tasks_path),
+    )
+}
+
+/// Adds a task to `tasks` for each comment under `root` not scanned before.
+///
+/// With `save` unset, e.g. for `--dry-run`, the state file is left alone so
+/// a later scan adds the same tasks.
+pub fn scan_into(
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    root: &Path,
+    save: bool,
+) -> Result<ScanReport> {
+    let mut state = load_state(tasks_path)?;
+    let files = source_files(root)?;
+    let mut report = ScanReport {
+        files: files.len(),
+        ..ScanReport::default()
+    };
+    let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for file in files {
+        let Ok(contents) = fs::read_to_string(root.join(&file)) else {
+            continue;
+        };
+        for comment in find_comments(&file, &contents) {
+            let hash = comment.hash();
+            let location = comment.location();
+            if let Some(known) = state.comments.iter().find(|c| c.hash == hash) {
+                let task = tasks
+                    .iter_mut()
+                    .find(|t| t.uuid == known.uuid && t.is_pending());
+                if let Some(task) = task
+                    && !task.tags.contains(&location)
+                {
+                    let prefix = format!("{}:", comment.path);
+                    task.tags.retain(|tag| !tag.starts_with(&prefix));
+                    task.tags.push(location);
+                    report.moved += 1;
+                }
+                continue;
+            }
+            let mut task = Task::new(next_id, comment.description(), None);
+            next_id += 1;
+            task.tags = vec![comment.marker.to_lowercase(), location];
+            state.comments.push(ScannedComment {
+                hash,
+                uuid: task.uuid,
+            });
+            tasks.push(task);
+            report.added += 1;
+        }
+    }
+    if save {
+        save_state(&state, tasks_path)?;
+    }
+    Ok(report)
+}
+
+/// Lists the files under `root` relative to it: the ones git tracks or
+/// would track, or else every file outside hidden and build directories.
+fn source_files(root: &Path) -> Result<Vec<String>> {
+    let listed = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .output();
+    if let Ok(output) = listed
+        && output.status.success()
+    {
+        let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        files.sort();
+        files.dedup();
+        files.retain(|f| is_small_file(&root.join(f)));
+        return Ok(files);
+    }
+    let mut files = Vec::new();
+    walk(root, root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                walk(root, &path, files)?;
+            }
+        } else if file_type.is_file() && is_small_file(&path) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn is_small_file(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_SIZE)
+}
+
+/// Finds the `TODO` and `FIXME` comments in the contents of the file at
+/// `path`.
+pub fn find_comments(path: &str, contents: &str) -> Vec<CodeComment> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (marker, text) = parse_comment(line)?;
+            Some(CodeComment {
+                marker,
+                path: path.to_string(),
+                line: index + 1,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Returns the marker and the text after it when `line` has a `TODO` or
+/// `FIXME` comment, e.g. `// TODO(ana): handle errors` gives "handle
+/// errors".
+fn parse_comment(line: &str) -> Option<(&'static str, String)> {
+    for marker in MARKERS {
+        let mut from = 0;
+        while let Some(found) = line[from..].find(marker) {
+            let start = from + found;
+            let end = start + marker.len();
+            from = end;
+            let before = &line[..start];
+            let after = &line[end..];
+            let bounded = !before.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                && !after.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+            let in_comment = COMMENT_LEADERS
+                .iter()
+                .any(|l| before.trim_end().ends_with(l));
+            if bounded && in_comment {
+                return Some((marker, comment_text(after)));
+            }
+        }
+    }
+    None
+}
+
+/// Strips the author, the separator, and any comment closer from what
+/// follows a marker.
+fn comment_text(after: &str) -> String {
+    let mut text = after;
+    if let Some(rest) = text.strip_prefix('(')
+        && let Some(close) = rest.find(')')
+    {
+        text = &rest[close + 1..];
+    }
+    let text = text.trim_start_matches([':', '-', ' ', '\t']);
+    let text = text
+        .trim_end()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_end();
+    text.to_string()
+}
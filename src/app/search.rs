@@ -0,0 +1,264 @@
+//! Full-text search over descriptions, notes, projects, and tags, and
+//! optionally annotations.
+//!
+//! `search` goes through a [`SearchBackend`]. The default, [`LinearSearch`],
+//! scans every task and matches each word of the query as a case-insensitive
+//! substring. Built with the `search-index` feature, [`open_search_backend`]
+//! hands out a tantivy index kept in `{stem}.index` next to the data file
+//! instead, which answers without reading every task. It matches whole words
+//! and ranks results by relevance. The index is created by the first search,
+//! refreshed whenever tasks are saved, and checked against the tasks before
+//! each search, so edits made elsewhere are picked up too.
+//!
+//! Encrypted data files are never indexed, since the index would hold their
+//! text in the clear.
+
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app::{Result, Task, encryption};
+
+/// Finds tasks by the words in them.
+pub trait SearchBackend {
+    /// Brings the backend up to date with `tasks`.
+    fn update(&mut self, tasks: &[Task]) -> Result<()>;
+
+    /// Returns the UUIDs of the tasks matching every word of `query`, best
+    /// match first. Annotations are only looked at with `annotations`.
+    fn search(&self, tasks: &[Task], query: &str, annotations: bool) -> Result<Vec<Uuid>>;
+}
+
+/// Searches by scanning every task.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearSearch;
+
+impl SearchBackend for LinearSearch {
+    fn update(&mut self, _tasks: &[Task]) -> Result<()> {
+        Ok(())
+    }
+
+    fn search(&self, tasks: &[Task], query: &str, annotations: bool) -> Result<Vec<Uuid>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        Ok(tasks
+            .iter()
+            .filter(|task| {
+                let text = searchable_text(task, annotations).to_lowercase();
+                words.iter().all(|word| text.contains(word.as_str()))
+            })
+            .map(|task| task.uuid)
+            .collect())
+    }
+}
+
+/// Returns the text of a task that search looks at, with its annotations
+/// if `annotations` is set.
+fn searchable_text(task: &Task, annotations: bool) -> String {
+    let mut text = task.description.clone();
+    for part in task
+        .project
+        .iter()
+        .chain(&task.tags)
+        .chain(task.notes.iter().map(|n| &n.text))
+        .chain(
+            task.annotations
+                .iter()
+                .filter(|_| annotations)
+                .map(|a| &a.text),
+        )
+    {
+        text.push('\n');
+        text.push_str(part);
+    }
+    text
+}
+
+/// Returns where the search index of the data file at `tasks_path` is kept.
+pub fn search_index_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.index", stem))
+}
+
+/// Returns the backend `search` uses for the data file at `tasks_path`.
+///
+/// That is the full-text index when the crate is built with `search-index`
+/// and the data file isn't encrypted, and [`LinearSearch`] otherwise.
+pub fn open_search_backend(tasks_path: &Path) -> Result<Box<dyn SearchBackend>> {
+    if !encryption::should_encrypt(tasks_path)
+        && let Some(index) = index::open(&search_index_path(tasks_path))?
+    {
+        return Ok(index);
+    }
+    Ok(Box::new(LinearSearch))
+}
+
+/// Refreshes the search index of the data file at `tasks_path`, if it has one.
+pub fn refresh_search_index(tasks: &[Task], tasks_path: &Path) -> Result<()> {
+    if !search_index_path(tasks_path).exists() || encryption::should_encrypt(tasks_path) {
+        return Ok(());
+    }
+    open_search_backend(tasks_path)?.update(tasks)
+}
+
+#[cfg(feature = "search-index")]
+mod index {
+    use log::debug;
+    use sha2::{Digest, Sha256};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tantivy::collector::TopDocs;
+    use tantivy::directory::MmapDirectory;
+    use tantivy::query::QueryParser;
+    use tantivy::schema::{Field, STORED, STRING, Schema, TEXT, Value};
+    use tantivy::{Index, IndexWriter, TantivyDocument, TantivyError, doc};
+    use uuid::Uuid;
+
+    use super::SearchBackend;
+    use crate::app::{AppError, Result, Task, storage};
+
+    /// The memory the index writer may use before flushing to disk.
+    const WRITER_MEMORY: usize = 20_000_000;
+
+    /// Names the file recording which tasks the index describes.
+    const FINGERPRINT_FILE: &str = "fingerprint";
+
+    fn err(e: impl std::fmt::Display) -> AppError {
+        AppError::Search(e.to_string())
+    }
+
+    struct Fields {
+        uuid: Field,
+        description: Field,
+        notes: Field,
+        annotations: Field,
+        project: Field,
+        tags: Field,
+    }
+
+    pub(super) fn open(dir: &Path) -> Result<Option<Box<dyn SearchBackend>>> {
+        Ok(Some(Box::new(TaskIndex::open(dir)?)))
+    }
+
+    /// A tantivy index of the task list.
+    struct TaskIndex {
+        dir: PathBuf,
+        index: Index,
+        fields: Fields,
+    }
+
+    impl TaskIndex {
+        fn open(dir: &Path) -> Result<Self> {
+            let mut schema = Schema::builder();
+            let fields = Fields {
+                uuid: schema.add_text_field("uuid", STRING | STORED),
+                description: schema.add_text_field("description", TEXT),
+                notes: schema.add_text_field("notes", TEXT),
+                annotations: schema.add_text_field("annotations", TEXT),
+                project: schema.add_text_field("project", TEXT),
+                tags: schema.add_text_field("tags", TEXT),
+            };
+            let schema = schema.build();
+            fs::create_dir_all(dir)?;
+            let index =
+                match Index::open_or_create(MmapDirectory::open(dir).map_err(err)?, schema.clone())
+                {
+                    Ok(index) => index,
+                    // An index written by an older version lacks some fields; it
+                    // only caches the tasks, so start it over.
+                    Err(TantivyError::SchemaError(e)) => {
+                        debug!("Rebuilding {}: {}", dir.display(), e);
+                        fs::remove_dir_all(dir)?;
+                        fs::create_dir_all(dir)?;
+                        Index::create_in_dir(dir, schema).map_err(err)?
+                    }
+                    Err(e) => return Err(err(e)),
+                };
+            Ok(TaskIndex {
+                dir: dir.to_path_buf(),
+                index,
+                fields,
+            })
+        }
+    }
+
+    impl SearchBackend for TaskIndex {
+        fn update(&mut self, tasks: &[Task]) -> Result<()> {
+            let fingerprint = hex::encode(Sha256::digest(storage::serialize_tasks(tasks)?));
+            let fingerprint_path = self.dir.join(FINGERPRINT_FILE);
+            if fs::read_to_string(&fingerprint_path).is_ok_and(|f| f == fingerprint) {
+                return Ok(());
+            }
+
+            let mut writer: IndexWriter = self
+                .index
+                .writer_with_num_threads(1, WRITER_MEMORY)
+                .map_err(err)?;
+            writer.delete_all_documents().map_err(err)?;
+            let fields = &self.fields;
+            for task in tasks {
+                let notes: Vec<&str> = task.notes.iter().map(|n| n.text.as_str()).collect();
+                let annotations: Vec<&str> =
+                    task.annotations.iter().map(|a| a.text.as_str()).collect();
+                writer
+                    .add_document(doc!(
+                        fields.uuid => task.uuid.to_string(),
+                        fields.description => task.description.as_str(),
+                        fields.notes => notes.join("\n"),
+                        fields.annotations => annotations.join("\n"),
+                        fields.project => task.project.as_deref().unwrap_or(""),
+                        fields.tags => task.tags.join(" "),
+                    ))
+                    .map_err(err)?;
+            }
+            writer.commit().map_err(err)?;
+            fs::write(&fingerprint_path, fingerprint)?;
+            debug!("Indexed {} tasks in {}.", tasks.len(), self.dir.display());
+            Ok(())
+        }
+
+        fn search(&self, tasks: &[Task], query: &str, annotations: bool) -> Result<Vec<Uuid>> {
+            let fields = &self.fields;
+            let mut searched = vec![
+                fields.description,
+                fields.notes,
+                fields.project,
+                fields.tags,
+            ];
+            if annotations {
+                searched.push(fields.annotations);
+            }
+            let mut parser = QueryParser::for_index(&self.index, searched);
+            parser.set_conjunction_by_default();
+            let (query, _) = parser.parse_query_lenient(query);
+
+            let searcher = self.index.reader().map_err(err)?.searcher();
+            let top = TopDocs::with_limit(tasks.len().max(1)).order_by_score();
+            let mut uuids = Vec::new();
+            for (_, address) in searcher.search(&query, &top).map_err(err)? {
+                let document: TantivyDocument = searcher.doc(address).map_err(err)?;
+                if let Some(uuid) = document
+                    .get_first(fields.uuid)
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| Uuid::parse_str(s).ok())
+                {
+                    uuids.push(uuid);
+                }
+            }
+            Ok(uuids)
+        }
+    }
+}
+
+#[cfg(not(feature = "search-index"))]
+mod index {
+    use std::path::Path;
+
+    use super::SearchBackend;
+    use crate::app::Result;
+
+    pub(super) fn open(_dir: &Path) -> Result<Option<Box<dyn SearchBackend>>> {
+        Ok(None)
+    }
+}
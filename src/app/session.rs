@@ -0,0 +1,322 @@
+//! Keeps the edits of interactive sessions, `triage` and `review`, and of
+//! the `rpc` and `mcp` servers from being lost when they are interrupted.
+//!
+//! `triage` and `review` hold their edits in memory until the last question
+//! is answered. While one runs, the edits so far are written to
+//! `{stem}.session` next to the data file every `autosave_interval` seconds,
+//! and SIGINT or SIGTERM writes them out before the process exits. The next
+//! command that loads the data file replays a session file left behind by a
+//! crash or a signal: if the data file is still as it was when the session
+//! began, the session's tasks replace it; if something else changed it
+//! since, the session file is set aside as `{stem}.session.bak` rather than
+//! undoing those changes.
+//!
+//! The servers save after every request, so a signal arriving in the middle
+//! of one only stops them once it has been answered.
+
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    Result, Task, clock, encryption, file_hash, move_to_trash, storage, trash_file_path,
+};
+#[cfg(feature = "native")]
+use crate::tr;
+
+/// How often an interactive session's edits are written out by default, in
+/// seconds.
+pub const DEFAULT_AUTOSAVE_INTERVAL: u64 = 30;
+
+/// The exit status after an interrupt, as shells report SIGINT.
+#[cfg(feature = "native")]
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// Returns where the edits of an interactive session on the data file at
+/// `tasks_path` are kept until it ends.
+pub fn session_file_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.session", stem))
+}
+
+/// The edits of an interactive session, as the session file stores them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// The command running the session, e.g. `triage`.
+    pub command: String,
+    pub started: DateTime<Utc>,
+    /// The SHA-256 of the data file when the session began; empty if there
+    /// was none.
+    base: String,
+    /// Every task as the session left it.
+    pub tasks: Vec<Task>,
+    /// The tasks the session removed, bound for the trash.
+    #[serde(default)]
+    pub trashed: Vec<Task>,
+}
+
+/// What the process is doing when a signal arrives.
+enum Activity {
+    /// Nothing to finish; the signal ends the process.
+    Idle,
+    /// A server request is running; the server stops once it is answered.
+    Busy,
+    /// An interactive session is running; its edits, if it made any, are
+    /// written out first.
+    Session {
+        #[cfg(feature = "native")]
+        path: PathBuf,
+        snapshot: SessionSnapshot,
+        #[cfg(feature = "native")]
+        encrypted: bool,
+        edited: bool,
+    },
+}
+
+static ACTIVITY: Mutex<Activity> = Mutex::new(Activity::Idle);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static HANDLER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Installs SIGINT/SIGTERM handlers that leave according to what is running;
+/// see [`Activity`]. Installing them again does nothing.
+pub fn install_interrupt_handler() -> Result<()> {
+    if HANDLER_INSTALLED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    #[cfg(feature = "native")]
+    ctrlc::set_handler(on_interrupt).map_err(|e| {
+        crate::app::AppError::Unexpected(format!("failed to install signal handler: {}", e))
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "native")]
+fn on_interrupt() {
+    let Ok(activity) = ACTIVITY.lock() else {
+        std::process::exit(EXIT_INTERRUPTED);
+    };
+    match &*activity {
+        Activity::Idle => std::process::exit(EXIT_INTERRUPTED),
+        Activity::Busy => {
+            info!("Interrupted; stopping once the current request is answered.");
+            INTERRUPTED.store(true, Ordering::SeqCst);
+        }
+        Activity::Session {
+            path,
+            snapshot,
+            encrypted,
+            edited: true,
+        } => {
+            match write_snapshot(snapshot, path, *encrypted) {
+                Ok(()) => eprintln!(
+                    "{}",
+                    tr!(
+                        "session-interrupted",
+                        command = snapshot.command.as_str(),
+                        path = path.display().to_string()
+                    )
+                ),
+                Err(e) => error!("Could not save the {} session: {}", snapshot.command, e),
+            }
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        Activity::Session { .. } => std::process::exit(EXIT_INTERRUPTED),
+    }
+}
+
+/// Returns `true` if a signal asked a server to stop.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Marks a server request as running until dropped, so a signal lets it
+/// finish.
+#[derive(Debug)]
+pub struct RequestGuard(());
+
+impl RequestGuard {
+    pub fn new() -> Self {
+        set_activity(Activity::Busy);
+        RequestGuard(())
+    }
+}
+
+impl Default for RequestGuard {
+    fn default() -> Self {
+        RequestGuard::new()
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        set_activity(Activity::Idle);
+    }
+}
+
+fn set_activity(activity: Activity) {
+    if let Ok(mut current) = ACTIVITY.lock() {
+        *current = activity;
+    }
+}
+
+/// An interactive session editing the data file.
+///
+/// Dropping it stops the signal handlers from writing its edits; the session
+/// file stays until [`clear_session`] is called after the data file was
+/// saved.
+#[derive(Debug)]
+pub struct Session {
+    path: PathBuf,
+    encrypted: bool,
+    interval: Duration,
+    last_written: Instant,
+}
+
+impl Session {
+    /// Begins a session of `command` on `tasks`, loaded from the data file at
+    /// `tasks_path`, whose edits are written out every `interval` seconds.
+    pub fn begin(command: &str, tasks: &[Task], tasks_path: &Path, interval: u64) -> Result<Self> {
+        install_interrupt_handler()?;
+        let base = match fs::read(tasks_path) {
+            Ok(contents) => file_hash(&contents),
+            Err(_) => String::new(),
+        };
+        let path = session_file_path(tasks_path);
+        let encrypted = encryption::should_encrypt(tasks_path);
+        debug!(
+            "Session of {} began; edits go to {}.",
+            command,
+            path.display()
+        );
+        set_activity(Activity::Session {
+            #[cfg(feature = "native")]
+            path: path.clone(),
+            snapshot: SessionSnapshot {
+                command: command.to_string(),
+                started: clock::now(),
+                base,
+                tasks: tasks.to_vec(),
+                trashed: Vec::new(),
+            },
+            #[cfg(feature = "native")]
+            encrypted,
+            edited: false,
+        });
+        Ok(Session {
+            path,
+            encrypted,
+            interval: Duration::from_secs(interval),
+            last_written: Instant::now(),
+        })
+    }
+
+    /// Records the session's edits so far: every task in `tasks`, and the
+    /// removed ones in `trashed`. They are written out if the autosave
+    /// interval has passed.
+    ///
+    /// Autosaving is a safety net, so failing to write is logged rather than
+    /// reported.
+    pub fn record(&mut self, tasks: &[Task], trashed: &[Task]) {
+        let Ok(mut activity) = ACTIVITY.lock() else {
+            return;
+        };
+        let Activity::Session {
+            snapshot, edited, ..
+        } = &mut *activity
+        else {
+            return;
+        };
+        *edited = true;
+        snapshot.tasks = tasks.to_vec();
+        snapshot.trashed = trashed.to_vec();
+        if self.last_written.elapsed() < self.interval {
+            return;
+        }
+        match write_snapshot(snapshot, &self.path, self.encrypted) {
+            Ok(()) => debug!("Autosaved the session to {}.", self.path.display()),
+            Err(e) => error!("Could not autosave the session: {}", e),
+        }
+        self.last_written = Instant::now();
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        set_activity(Activity::Idle);
+    }
+}
+
+fn write_snapshot(snapshot: &SessionSnapshot, path: &Path, encrypted: bool) -> Result<()> {
+    encryption::write_file(path, serde_json::to_string(snapshot)?, encrypted)
+}
+
+/// Deletes the session file of the data file at `tasks_path`, once the
+/// session's edits are saved to it.
+pub fn clear_session(tasks_path: &Path) -> Result<()> {
+    let path = session_file_path(tasks_path);
+    if path.exists() {
+        fs::remove_file(&path)?;
+        debug!("Removed the session file {}.", path.display());
+    }
+    Ok(())
+}
+
+/// What [`recover_session`] did with a session file left behind.
+#[derive(Debug)]
+pub enum SessionRecovery {
+    /// The session's edits were saved to the data file.
+    Replayed(SessionSnapshot),
+    /// The data file changed after the session began, so the session file
+    /// was moved to `backup` instead.
+    SetAside {
+        snapshot: SessionSnapshot,
+        backup: PathBuf,
+    },
+}
+
+/// Replays the session file an interrupted session left next to the data
+/// file at `tasks_path`, if there is one; see the module documentation.
+pub fn recover_session(tasks_path: &Path) -> Result<Option<SessionRecovery>> {
+    let path = session_file_path(tasks_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let snapshot: SessionSnapshot = serde_json::from_str(&encryption::read_file(&path)?)?;
+    let current = match fs::read(tasks_path) {
+        Ok(contents) => file_hash(&contents),
+        Err(_) => String::new(),
+    };
+    if current != snapshot.base {
+        let backup = path.with_extension("session.bak");
+        fs::rename(&path, &backup)?;
+        warn!(
+            "{} changed after the {} session began; set its edits aside in {}.",
+            tasks_path.display(),
+            snapshot.command,
+            backup.display()
+        );
+        return Ok(Some(SessionRecovery::SetAside { snapshot, backup }));
+    }
+    storage::save_tasks_to(&snapshot.tasks, tasks_path)?;
+    if !snapshot.trashed.is_empty() {
+        move_to_trash(
+            snapshot.trashed.clone(),
+            &trash_file_path(tasks_path),
+            clock::now(),
+        )?;
+    }
+    fs::remove_file(&path)?;
+    info!(
+        "Replayed the {} session from {}.",
+        snapshot.command, snapshot.started
+    );
+    Ok(Some(SessionRecovery::Replayed(snapshot)))
+}
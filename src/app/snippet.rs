@@ -0,0 +1,211 @@
+//! A single task as a short block of text to paste into a chat or an email,
+//! and back.
+//!
+//! `share` prints the description on the first line and one `Field: value`
+//! line for each field that is set; the Markdown form bolds the description
+//! and makes the fields a list. `paste` reads either form, so a task shared
+//! from one machine can be added on another:
+//!
+//! ```text
+//! **Renew passport**
+//! - Due: 2025-07-01 09:00
+//! - Priority: high
+//! - Project: admin
+//! - Tags: errands, travel
+//! - Link: <https://example.com/passport>
+//! - Note: Bring two photos.
+//! ```
+//!
+//! A note running over several lines continues on indented lines.
+
+use clap::ValueEnum;
+use std::fmt::Write;
+
+use crate::app::{AppError, DueAt, NewTask, Priority, Result, Task, parse_url};
+use crate::tr;
+
+/// How `share` prints a task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SnippetFormat {
+    /// A bold description and a list of fields, for chats that render
+    /// Markdown.
+    #[default]
+    Markdown,
+    /// Plain lines, for anywhere else.
+    Plain,
+}
+
+/// Renders `task` as a snippet in `format`.
+pub fn render_snippet(task: &Task, format: SnippetFormat) -> String {
+    let (bullet, indent) = match format {
+        SnippetFormat::Markdown => ("- ", "    "),
+        SnippetFormat::Plain => ("", "  "),
+    };
+    let mut text = String::new();
+    let _ = match format {
+        SnippetFormat::Markdown => writeln!(text, "**{}**", task.description),
+        SnippetFormat::Plain => writeln!(text, "{}", task.description),
+    };
+    let mut field = |name: &str, value: String| {
+        let mut lines = value.lines();
+        let _ = writeln!(
+            text,
+            "{}{}: {}",
+            bullet,
+            name,
+            lines.next().unwrap_or_default()
+        );
+        for line in lines {
+            let _ = writeln!(text, "{}{}", indent, line);
+        }
+    };
+    if let Some(date) = task.due_date {
+        field(
+            "Due",
+            match task.due_time {
+                Some(time) => format!("{} {}", date, time.format("%H:%M")),
+                None => date.to_string(),
+            },
+        );
+    }
+    if let Some(priority) = task.priority {
+        field("Priority", priority.to_string());
+    }
+    if let Some(project) = &task.project {
+        field("Project", project.clone());
+    }
+    if !task.tags.is_empty() {
+        field("Tags", task.tags.join(", "));
+    }
+    if !task.contexts.is_empty() {
+        let contexts: Vec<String> = task.contexts.iter().map(|c| format!("@{}", c)).collect();
+        field("Contexts", contexts.join(", "));
+    }
+    if let Some(url) = &task.url {
+        field(
+            "Link",
+            match format {
+                SnippetFormat::Markdown => format!("<{}>", url),
+                SnippetFormat::Plain => url.clone(),
+            },
+        );
+    }
+    for note in &task.notes {
+        field("Note", note.text.clone());
+    }
+    text
+}
+
+/// A task read back from a snippet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snippet {
+    pub description: String,
+    pub due: Option<DueAt>,
+    pub priority: Option<Priority>,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub contexts: Vec<String>,
+    pub url: Option<String>,
+    pub notes: Vec<String>,
+}
+
+impl Snippet {
+    /// Reads a snippet printed by `share`, in either format.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let first = lines
+            .next()
+            .ok_or_else(|| AppError::InvalidArgument(tr!("error-snippet-empty")))?;
+        let description = first
+            .trim()
+            .trim_start_matches("- [ ] ")
+            .trim_start_matches('#')
+            .trim()
+            .trim_matches('*')
+            .trim()
+            .to_string();
+        if description.is_empty() {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-snippet-no-description"
+            )));
+        }
+        let mut snippet = Snippet {
+            description,
+            ..Snippet::default()
+        };
+        for line in lines {
+            match snippet.notes.last_mut() {
+                // A note running over several lines.
+                Some(note) if line.starts_with([' ', '\t']) => {
+                    note.push('\n');
+                    note.push_str(line.trim());
+                }
+                _ => snippet.read_field(line)?,
+            }
+        }
+        Ok(snippet)
+    }
+
+    /// Reads a `Name: value` line.
+    fn read_field(&mut self, line: &str) -> Result<()> {
+        let line = line.trim();
+        let Some((name, value)) = line.strip_prefix("- ").unwrap_or(line).split_once(':') else {
+            return Err(AppError::InvalidArgument(tr!(
+                "error-snippet-bad-line",
+                line = line
+            )));
+        };
+        let value = value.trim();
+        let list = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "due" => self.due = Some(value.parse().map_err(AppError::InvalidArgument)?),
+            "priority" => {
+                self.priority = Some(Priority::from_str(value, true).map_err(|_| {
+                    AppError::InvalidArgument(tr!("error-unknown-priority", priority = value))
+                })?)
+            }
+            "project" => self.project = Some(value.to_string()),
+            "tags" => self.tags = list(value),
+            "contexts" => {
+                self.contexts = list(value)
+                    .into_iter()
+                    .map(|c| c.trim_start_matches('@').to_string())
+                    .collect()
+            }
+            "link" | "url" => {
+                let url = value.trim_start_matches('<').trim_end_matches('>');
+                self.url = Some(parse_url(url).map_err(AppError::InvalidArgument)?);
+            }
+            "note" => self.notes.push(value.to_string()),
+            _ => {
+                return Err(AppError::InvalidArgument(tr!(
+                    "error-snippet-unknown-field",
+                    field = name.trim()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Snippet> for NewTask {
+    fn from(snippet: Snippet) -> Self {
+        NewTask {
+            due: snippet.due,
+            priority: snippet.priority,
+            project: snippet.project,
+            tags: snippet.tags,
+            contexts: snippet.contexts,
+            url: snippet.url,
+            notes: snippet.notes,
+            ..NewTask::new(snippet.description)
+        }
+    }
+}
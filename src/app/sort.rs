@@ -0,0 +1,54 @@
+//! Ordering of task lists.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+
+use crate::app::Task;
+
+/// The fields a task list can be sorted by.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Earliest due date first; tasks without a due date last.
+    Due,
+    /// Highest priority first; tasks without a priority last.
+    Priority,
+    /// Oldest first.
+    Created,
+    /// Lowest ID first.
+    Id,
+    /// Alphabetically by description, ignoring case.
+    Alpha,
+}
+
+/// Sorts `tasks` by `key`, breaking ties by ID.
+///
+/// The sort is stable. With `reverse`, the complete order is flipped,
+/// including where tasks missing the sorted field end up.
+pub fn sort_tasks(tasks: &mut [&Task], key: SortKey, reverse: bool) {
+    tasks.sort_by(|a, b| {
+        let ordering = compare(a, b, key).then(a.id.cmp(&b.id));
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn compare(a: &Task, b: &Task, key: SortKey) -> Ordering {
+    match key {
+        SortKey::Due => {
+            (a.deadline().is_none(), a.deadline()).cmp(&(b.deadline().is_none(), b.deadline()))
+        }
+        SortKey::Priority => (a.priority.is_none(), a.priority.map(Reverse))
+            .cmp(&(b.priority.is_none(), b.priority.map(Reverse))),
+        SortKey::Created => a.created_at.cmp(&b.created_at),
+        SortKey::Id => Ordering::Equal,
+        SortKey::Alpha => a
+            .description
+            .to_lowercase()
+            .cmp(&b.description.to_lowercase()),
+    }
+}
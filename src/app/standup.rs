@@ -0,0 +1,145 @@
+//! The report `standup` prints for a daily standup meeting: what was
+//! completed on the last working day, what is planned for today, and what is
+//! blocked.
+//!
+//! On a Monday, "yesterday" reaches back to Friday, so work done over the
+//! weekend counts too.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
+use std::fmt::Write;
+
+use crate::app::{Task, TaskStatus, clock};
+
+/// How `standup` prints its report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StandupFormat {
+    /// Plain text for the terminal.
+    #[default]
+    Text,
+    /// Markdown, e.g. for a wiki page or an issue comment.
+    Markdown,
+    /// Slack's message formatting, to paste into a channel.
+    Slack,
+}
+
+/// The tasks a standup report covers.
+#[derive(Debug)]
+pub struct Standup<'a> {
+    pub date: NaiveDate,
+    /// The first day counted as "yesterday".
+    pub since: NaiveDate,
+    /// Tasks completed since `since`, in the order they were completed.
+    pub completed: Vec<&'a Task>,
+    /// Tasks in progress, overdue, or due today; in-progress ones first.
+    pub planned: Vec<&'a Task>,
+    /// Blocked tasks and those waiting on someone else.
+    pub blockers: Vec<&'a Task>,
+}
+
+impl<'a> Standup<'a> {
+    /// Collects the report for `tasks` at `now`.
+    pub fn build(tasks: &'a [Task], now: NaiveDateTime) -> Self {
+        let date = now.date();
+        let since = previous_workday(date);
+        let mut completed: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter(|t| {
+                t.completed_at
+                    .map(clock::local_date)
+                    .is_some_and(|day| since <= day && day < date)
+            })
+            .collect();
+        completed.sort_by_key(|t| (t.completed_at, t.id));
+
+        let mut planned = Vec::new();
+        let mut blockers = Vec::new();
+        for task in tasks.iter().filter(|t| t.is_pending()) {
+            if task.status == TaskStatus::Blocked || task.waiting_on.is_some() {
+                blockers.push(task);
+            } else if task.status == TaskStatus::InProgress
+                || (!task.is_waiting(date) && (task.is_overdue(now) || task.due_date == Some(date)))
+            {
+                planned.push(task);
+            }
+        }
+        planned.sort_by_key(|t| (t.status != TaskStatus::InProgress, t.deadline(), t.id));
+        blockers.sort_by_key(|t| t.id);
+        Standup {
+            date,
+            since,
+            completed,
+            planned,
+            blockers,
+        }
+    }
+
+    /// Renders the report in `format`.
+    pub fn render(&self, format: StandupFormat) -> String {
+        let sections = [
+            ("Yesterday I completed", &self.completed),
+            ("Today I plan to", &self.planned),
+            ("Blockers", &self.blockers),
+        ];
+        let mut report = String::new();
+        for (heading, tasks) in sections {
+            if !report.is_empty() {
+                report.push('\n');
+            }
+            let _ = match format {
+                StandupFormat::Text => writeln!(report, "{}:", heading),
+                StandupFormat::Markdown => writeln!(report, "### {}\n", heading),
+                StandupFormat::Slack => writeln!(report, "*{}*", heading),
+            };
+            if tasks.is_empty() {
+                let _ = writeln!(report, "{}nothing", bullet(format));
+            }
+            for task in tasks.iter() {
+                let _ = write!(report, "{}{}", bullet(format), task.description);
+                if format == StandupFormat::Text {
+                    let _ = write!(report, " [{}]", task.id);
+                }
+                if let Some(note) = self.note(task) {
+                    let _ = write!(report, " ({})", note);
+                }
+                report.push('\n');
+            }
+        }
+        report
+    }
+
+    /// Says why a planned or blocked task is listed.
+    fn note(&self, task: &Task) -> Option<String> {
+        if task.is_done() {
+            return None;
+        }
+        if let Some(waiting_on) = &task.waiting_on {
+            return Some(format!("waiting on {}", waiting_on));
+        }
+        match task.status {
+            TaskStatus::Blocked => Some("blocked".to_string()),
+            TaskStatus::InProgress => Some("in progress".to_string()),
+            _ if task.due_date == Some(self.date) => Some("due today".to_string()),
+            _ if task.due_date.is_some() => Some("overdue".to_string()),
+            _ => None,
+        }
+    }
+}
+
+fn bullet(format: StandupFormat) -> &'static str {
+    match format {
+        StandupFormat::Text => "  - ",
+        StandupFormat::Markdown => "- ",
+        StandupFormat::Slack => "• ",
+    }
+}
+
+/// Returns the working day before `date`: the Friday before a weekend or a
+/// Monday, the day before otherwise.
+fn previous_workday(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Mon => date - Duration::days(3),
+        Weekday::Sun => date - Duration::days(2),
+        _ => date - Duration::days(1),
+    }
+}
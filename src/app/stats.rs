@@ -0,0 +1,488 @@
+//! Productivity statistics over the task list.
+//!
+//! [`Stats::build`] computes counts, completion throughput, and breakdowns by
+//! project and tag. The result can be printed as text, serialized as JSON,
+//! or turned into a [`Chart`] of daily completions. [`GoalProgress`] tracks
+//! the configured weekly completion goal, [`Streaks`] the days in a row with
+//! a completion and the points earned, [`Burndown`] how the number of open
+//! tasks develops week by week, and [`Forecast`] where it is heading.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::app::{Chart, ChartKind, ChartSeries, INBOX_LABEL, Priority, Task, clock};
+
+/// The number of days covered by the daily completion counts.
+pub const STATS_DAYS: i64 = 14;
+
+/// The number of weeks covered by the weekly completion counts.
+pub const STATS_WEEKS: i64 = 8;
+
+/// The number of past weeks `forecast` takes the pace from by default.
+pub const FORECAST_HISTORY_WEEKS: u32 = 4;
+
+/// The number of tasks completed in one period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeriodCount {
+    /// The first day of the period.
+    pub start: NaiveDate,
+    pub completed: usize,
+}
+
+/// Task counts for one project or tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Breakdown {
+    pub name: String,
+    pub pending: usize,
+    pub completed: usize,
+}
+
+/// Progress toward the weekly completion goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GoalProgress {
+    /// The Monday the week started on.
+    pub week_start: NaiveDate,
+    pub completed: usize,
+    pub goal: u32,
+}
+
+impl GoalProgress {
+    /// Counts the tasks completed in the ISO week containing `now`.
+    pub fn this_week(tasks: &[Task], goal: u32, now: DateTime<Utc>) -> Self {
+        let start = week_start(clock::local_date(now));
+        GoalProgress {
+            week_start: start,
+            completed: tasks
+                .iter()
+                .filter(|t| t.is_done())
+                .filter_map(|t| t.completed_at)
+                .filter(|at| week_start(clock::local_date(*at)) == start)
+                .count(),
+            goal,
+        }
+    }
+
+    /// Returns `true` once the goal is met.
+    pub fn is_met(&self) -> bool {
+        self.completed >= self.goal as usize
+    }
+}
+
+impl fmt::Display for GoalProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} this week", self.completed, self.goal)
+    }
+}
+
+/// Completion streaks and points, shown by `stats --fun`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Streaks {
+    /// Days in a row with a completion, up to today. A streak holds until
+    /// today ends without one.
+    pub current: u32,
+    pub longest: u32,
+    pub points_today: u32,
+    pub points_this_week: u32,
+    pub points_total: u32,
+}
+
+impl Streaks {
+    /// Computes the streaks and points of `tasks` as seen at `now`.
+    pub fn build(tasks: &[Task], now: DateTime<Utc>) -> Self {
+        let today = clock::local_date(now);
+        let this_week = week_start(today);
+        let completed: Vec<(NaiveDate, u32)> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter_map(|t| {
+                t.completed_at
+                    .map(|at| (clock::local_date(at), completion_points(t)))
+            })
+            .collect();
+        let days: BTreeSet<NaiveDate> = completed.iter().map(|(day, _)| *day).collect();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for &day in &days {
+            run = match previous {
+                Some(p) if day - p == Duration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            previous = Some(day);
+        }
+        let mut day = today;
+        if !days.contains(&day) {
+            day -= Duration::days(1);
+        }
+        let mut current = 0;
+        while days.contains(&day) {
+            current += 1;
+            day -= Duration::days(1);
+        }
+
+        let points = |keep: &dyn Fn(NaiveDate) -> bool| {
+            completed
+                .iter()
+                .filter(|(day, _)| keep(*day))
+                .map(|(_, points)| points)
+                .sum()
+        };
+        Streaks {
+            current,
+            longest,
+            points_today: points(&|day| day == today),
+            points_this_week: points(&|day| week_start(day) == this_week),
+            points_total: points(&|_| true),
+        }
+    }
+}
+
+/// Returns the points completing `task` earns: 3 for high priority, 2 for
+/// medium, and 1 otherwise.
+pub fn completion_points(task: &Task) -> u32 {
+    match task.priority {
+        Some(Priority::High) => 3,
+        Some(Priority::Medium) => 2,
+        Some(Priority::Low) | None => 1,
+    }
+}
+
+/// A productivity report for the task list.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub generated_at: DateTime<Utc>,
+    pub total: usize,
+    pub pending: usize,
+    pub completed: usize,
+    pub cancelled: usize,
+    pub overdue: usize,
+    /// The mean time from creation to completion, in hours.
+    pub average_completion_hours: Option<f64>,
+    /// Completions per day, oldest first, ending today.
+    pub completed_per_day: Vec<PeriodCount>,
+    /// Completions per ISO week (starting Monday), oldest first, ending this week.
+    pub completed_per_week: Vec<PeriodCount>,
+    pub by_project: Vec<Breakdown>,
+    pub by_tag: Vec<Breakdown>,
+    /// Progress toward the configured weekly goal, if one is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekly_goal: Option<GoalProgress>,
+    /// Completion streaks and points, with `--fun`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaks: Option<Streaks>,
+}
+
+impl Stats {
+    /// Computes statistics for `tasks` as seen at `now`.
+    pub fn build(tasks: &[Task], now: DateTime<Utc>) -> Self {
+        let today = clock::local_date(now);
+        let completion_dates: Vec<NaiveDate> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter_map(|t| t.completed_at)
+            .map(clock::local_date)
+            .collect();
+
+        let completed_per_day = (0..STATS_DAYS)
+            .rev()
+            .map(|offset| {
+                let start = today - Duration::days(offset);
+                PeriodCount {
+                    start,
+                    completed: completion_dates.iter().filter(|&&d| d == start).count(),
+                }
+            })
+            .collect();
+
+        let this_week = week_start(today);
+        let completed_per_week = (0..STATS_WEEKS)
+            .rev()
+            .map(|offset| {
+                let start = this_week - Duration::weeks(offset);
+                PeriodCount {
+                    start,
+                    completed: completion_dates
+                        .iter()
+                        .filter(|&&d| week_start(d) == start)
+                        .count(),
+                }
+            })
+            .collect();
+
+        let durations: Vec<f64> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter_map(|t| t.completed_at.map(|at| at - t.created_at))
+            .map(|d| d.num_seconds().max(0) as f64 / 3600.0)
+            .collect();
+        let average_completion_hours = if durations.is_empty() {
+            None
+        } else {
+            Some(durations.iter().sum::<f64>() / durations.len() as f64)
+        };
+
+        let mut by_project: BTreeMap<String, Breakdown> = BTreeMap::new();
+        let mut by_tag: BTreeMap<String, Breakdown> = BTreeMap::new();
+        for task in tasks.iter().filter(|t| !t.is_cancelled()) {
+            let project = task.project.as_deref().unwrap_or(INBOX_LABEL);
+            count_into(&mut by_project, project, task);
+            for tag in &task.tags {
+                count_into(&mut by_tag, tag, task);
+            }
+        }
+
+        Stats {
+            generated_at: now,
+            total: tasks.len(),
+            pending: tasks.iter().filter(|t| t.is_pending()).count(),
+            completed: tasks.iter().filter(|t| t.is_done()).count(),
+            cancelled: tasks.iter().filter(|t| t.is_cancelled()).count(),
+            overdue: tasks
+                .iter()
+                .filter(|t| t.is_overdue(clock::local(now)))
+                .count(),
+            average_completion_hours,
+            completed_per_day,
+            completed_per_week,
+            by_project: by_project.into_values().collect(),
+            by_tag: by_tag.into_values().collect(),
+            weekly_goal: None,
+            streaks: None,
+        }
+    }
+
+    /// Adds progress toward a weekly goal of `goal` completions.
+    pub fn with_weekly_goal(mut self, tasks: &[Task], goal: Option<u32>) -> Self {
+        self.weekly_goal = goal.map(|goal| GoalProgress::this_week(tasks, goal, self.generated_at));
+        self
+    }
+
+    /// Adds completion streaks and points.
+    pub fn with_streaks(mut self, tasks: &[Task]) -> Self {
+        self.streaks = Some(Streaks::build(tasks, self.generated_at));
+        self
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    pub fn to_json(&self) -> crate::app::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Describes the daily completion counts as a bar chart.
+    pub fn chart(&self) -> Chart {
+        Chart {
+            title: "Tasks completed per day".to_string(),
+            kind: ChartKind::Bar,
+            labels: self
+                .completed_per_day
+                .iter()
+                .map(|p| p.start.format("%m-%d").to_string())
+                .collect(),
+            series: vec![ChartSeries {
+                name: "completed".to_string(),
+                values: self
+                    .completed_per_day
+                    .iter()
+                    .map(|p| p.completed as f64)
+                    .collect(),
+            }],
+        }
+    }
+}
+
+/// The open and completed tasks of one week.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BurndownWeek {
+    /// The Monday the week started on.
+    pub start: NaiveDate,
+    /// The tasks open at the end of the week, or now for the current week.
+    pub open: usize,
+    /// The tasks completed during the week.
+    pub completed: usize,
+}
+
+/// Open tasks against completions over the last weeks, read from the
+/// creation and completion times of the tasks.
+///
+/// Cancelled tasks are left out, since it isn't recorded when they were
+/// cancelled.
+#[derive(Debug, Clone, Serialize)]
+pub struct Burndown {
+    /// Oldest first, ending with the current week.
+    pub weeks: Vec<BurndownWeek>,
+}
+
+impl Burndown {
+    /// Computes the last `weeks` weeks of `tasks` as seen at `now`.
+    pub fn build(tasks: &[Task], weeks: u32, now: DateTime<Utc>) -> Self {
+        let this_week = week_start(clock::local_date(now));
+        let counted: Vec<(NaiveDate, Option<NaiveDate>)> = tasks
+            .iter()
+            .filter(|t| !t.is_cancelled())
+            .map(|t| {
+                let done = t.completed_at.filter(|_| t.is_done());
+                (clock::local_date(t.created_at), done.map(clock::local_date))
+            })
+            .collect();
+        let weeks = (0..i64::from(weeks.max(1)))
+            .rev()
+            .map(|offset| {
+                let start = this_week - Duration::weeks(offset);
+                let end = start + Duration::weeks(1);
+                BurndownWeek {
+                    start,
+                    open: counted
+                        .iter()
+                        .filter(|(created, done)| *created < end && done.is_none_or(|d| d >= end))
+                        .count(),
+                    completed: counted
+                        .iter()
+                        .filter(|(_, done)| done.is_some_and(|d| start <= d && d < end))
+                        .count(),
+                }
+            })
+            .collect();
+        Burndown { weeks }
+    }
+
+    /// Returns how many more tasks are open now than at the end of the
+    /// first week; negative if the backlog shrank.
+    pub fn change(&self) -> i64 {
+        match (self.weeks.first(), self.weeks.last()) {
+            (Some(first), Some(last)) => last.open as i64 - first.open as i64,
+            _ => 0,
+        }
+    }
+
+    /// Describes the open and completed counts as a line chart.
+    pub fn chart(&self) -> Chart {
+        let series = |name: &str, value: fn(&BurndownWeek) -> usize| ChartSeries {
+            name: name.to_string(),
+            values: self.weeks.iter().map(|w| value(w) as f64).collect(),
+        };
+        Chart {
+            title: "Open and completed tasks per week".to_string(),
+            kind: ChartKind::Line,
+            labels: self
+                .weeks
+                .iter()
+                .map(|w| w.start.format("%m-%d").to_string())
+                .collect(),
+            series: vec![
+                series("open", |w| w.open),
+                series("completed", |w| w.completed),
+            ],
+        }
+    }
+}
+
+/// The projected open tasks of one week ahead.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForecastWeek {
+    /// The Monday the week starts on.
+    pub start: NaiveDate,
+    /// The tasks expected to be open as the week begins; those open now for
+    /// the current week.
+    pub open: usize,
+}
+
+/// Where the backlog is heading if tasks keep being added and completed at
+/// the pace of the last weeks.
+#[derive(Debug, Clone, Serialize)]
+pub struct Forecast {
+    /// The tasks open now.
+    pub open: usize,
+    /// The tasks added and completed per week, on average over the weeks
+    /// looked back on.
+    pub added_per_week: f64,
+    pub completed_per_week: f64,
+    /// The weeks ahead, starting with the current one.
+    pub weeks: Vec<ForecastWeek>,
+    /// The Monday of the week the backlog is expected to be cleared, if it
+    /// shrinks at all.
+    pub clear_by: Option<NaiveDate>,
+}
+
+impl Forecast {
+    /// Projects `tasks` `ahead` weeks past `now`, at the pace of the last
+    /// `history` weeks.
+    pub fn build(tasks: &[Task], history: u32, ahead: u32, now: DateTime<Utc>) -> Self {
+        let history = history.max(1);
+        let burndown = Burndown::build(tasks, history, now);
+        let this_week = week_start(clock::local_date(now));
+        let since = this_week - Duration::weeks(i64::from(history) - 1);
+        let added = tasks
+            .iter()
+            .filter(|t| !t.is_cancelled())
+            .filter(|t| clock::local_date(t.created_at) >= since)
+            .count();
+        let completed: usize = burndown.weeks.iter().map(|w| w.completed).sum();
+        let open = tasks.iter().filter(|t| t.is_pending()).count();
+
+        let added_per_week = added as f64 / f64::from(history);
+        let completed_per_week = completed as f64 / f64::from(history);
+        let shrink = completed_per_week - added_per_week;
+        let projected =
+            |weeks: i64| (open as f64 - shrink * weeks as f64).round().max(0.0) as usize;
+        let weeks = (0..i64::from(ahead.max(1)))
+            .map(|offset| ForecastWeek {
+                start: this_week + Duration::weeks(offset),
+                open: projected(offset),
+            })
+            .collect();
+        let clear_by = if open == 0 {
+            Some(this_week)
+        } else {
+            (shrink > 0.0).then(|| {
+                let needed = (open as f64 / shrink).ceil() as i64;
+                this_week + Duration::weeks(needed.max(1) - 1)
+            })
+        };
+        Forecast {
+            open,
+            added_per_week,
+            completed_per_week,
+            weeks,
+            clear_by,
+        }
+    }
+
+    /// Describes the projected open tasks as a line chart.
+    pub fn chart(&self) -> Chart {
+        Chart {
+            title: "Projected open tasks per week".to_string(),
+            kind: ChartKind::Line,
+            labels: self
+                .weeks
+                .iter()
+                .map(|w| w.start.format("%m-%d").to_string())
+                .collect(),
+            series: vec![ChartSeries {
+                name: "open".to_string(),
+                values: self.weeks.iter().map(|w| w.open as f64).collect(),
+            }],
+        }
+    }
+}
+
+/// Returns the Monday of the week containing `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+fn count_into(map: &mut BTreeMap<String, Breakdown>, name: &str, task: &Task) {
+    let entry = map.entry(name.to_string()).or_insert_with(|| Breakdown {
+        name: name.to_string(),
+        pending: 0,
+        completed: 0,
+    });
+    if task.is_done() {
+        entry.completed += 1;
+    } else {
+        entry.pending += 1;
+    }
+}
@@ -1,57 +1,360 @@
 //! Handles loading and saving tasks to a TOML file.
 //!
 //! This module manages the persistence layer of the task manager,
-//! ensuring tasks are saved between application runs.
+//! ensuring tasks are saved between application runs. Data files ending in
+//! `.jsonl` are kept as an append-only log of events instead; see
+//! [`eventlog`].
+//!
+//! A data file can also hold its task list as MessagePack, which is smaller
+//! and much faster to read and write than TOML for large lists. Like
+//! encryption, the format is a property of the file: loading reads either,
+//! and an existing file keeps its format when saved. New files use
+//! `storage_format` from the config, and `convert-storage` switches a file
+//! from one format to the other.
 
 #![allow(unused_imports)]
-use log::{debug, error, info};
-use std::{fs, path::PathBuf};
+use clap::ValueEnum;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
 
-use crate::app::{AppError, Result, Task, TaskList};
+use crate::app::{
+    AppError, Result, Task, TaskList, TaskListRef, clock, encryption, eventlog, migrate,
+};
 
 /// The default name for the task data file.
 const TASKS_FILE_NAME: &str = "tasks.toml";
 
-/// Determines the path where the tasks file should be stored.
+/// The bytes a data file in the MessagePack format starts with.
+pub const MSGPACK_MAGIC: &[u8] = b"tm-msgpack-v1\n";
+
+/// About how many bytes a task takes up in MessagePack, to size the buffer
+/// a list is encoded into up front.
+const MSGPACK_BYTES_PER_TASK: usize = 256;
+
+/// The format new data files are written in; see [`set_default_format`].
+static DEFAULT_FORMAT: OnceLock<StorageFormat> = OnceLock::new();
+
+/// How the task list is encoded in a data file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageFormat {
+    /// Human-readable TOML.
+    #[default]
+    Toml,
+    /// Compact binary MessagePack.
+    Msgpack,
+}
+
+impl StorageFormat {
+    /// Returns the format of `contents` read from a data file.
+    pub fn of(contents: &[u8]) -> Self {
+        if contents.starts_with(MSGPACK_MAGIC) {
+            StorageFormat::Msgpack
+        } else {
+            StorageFormat::Toml
+        }
+    }
+}
+
+impl fmt::Display for StorageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            StorageFormat::Toml => "toml",
+            StorageFormat::Msgpack => "msgpack",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Sets the format new data files are written in, for the rest of the
+/// process. Only the first call has an effect.
+pub fn set_default_format(format: StorageFormat) {
+    let _ = DEFAULT_FORMAT.set(format);
+}
+
+/// Returns the format new data files are written in.
+pub fn default_format() -> StorageFormat {
+    DEFAULT_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Returns the format a save to `path` uses: an existing file keeps its
+/// format, and a new one gets the default.
+pub fn stored_format(path: &Path) -> Result<StorageFormat> {
+    if !path.exists() {
+        return Ok(default_format());
+    }
+    if encryption::is_encrypted_file(path) {
+        return Ok(StorageFormat::of(&encryption::read_bytes(path)?));
+    }
+    let mut header = Vec::with_capacity(MSGPACK_MAGIC.len());
+    fs::File::open(path)?
+        .take(MSGPACK_MAGIC.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(StorageFormat::of(&header))
+}
+
+/// Determines the default path where the tasks file should be stored.
 ///
-/// For simplicity, it currently places the file in the current working directory.
-/// In a real application, you might use a configuration directory (e.g., `dirs-next` crate).
-fn get_tasks_file_path() -> PathBuf {
+/// The file lives in the current working directory unless the config file
+/// or `--file` points elsewhere.
+pub fn default_tasks_file_path() -> PathBuf {
     PathBuf::from(TASKS_FILE_NAME)
 }
 
-/// Loads tasks from the tasks file.
+/// Loads tasks from the default tasks file.
 ///
 /// # Returns
 ///
 /// A `Result` containing a `Vec<Task>` by unpacking from `TaskList` on success, or an `AppError` on failure.
 pub fn load_tasks() -> Result<Vec<Task>> {
-    let path = get_tasks_file_path();
+    load_tasks_from(&default_tasks_file_path())
+}
+
+/// Loads tasks from the tasks file at `path`, decrypting it if needed.
+///
+/// A missing file yields an empty list.
+pub fn load_tasks_from(path: &Path) -> Result<Vec<Task>> {
+    let Some(contents) = read_tasks_file(path)? else {
+        return Ok(Vec::new());
+    };
+    let tasks = parse_tasks(&contents).map_err(|e| e.in_file(path))?;
+    info!(
+        "Successfully loaded {} tasks from {}.",
+        tasks.len(),
+        path.display()
+    );
+    Ok(tasks)
+}
+
+/// Reads the tasks file at `path`, decrypting it if needed.
+///
+/// Returns `None` if the file doesn't exist.
+pub fn read_tasks_file(path: &Path) -> Result<Option<Vec<u8>>> {
     debug!("Attempting to load tasks from: {}", path.display());
 
     if !path.exists() {
-        info!(
-            "Tasks file not found at {}. Returning empty list.",
-            path.display()
-        );
-        return Ok(Vec::new());
+        info!("Tasks file not found at {}.", path.display());
+        return Ok(None);
     }
 
-    let contents = fs::read_to_string(&path)?;
+    let contents = encryption::read_bytes(path)?;
     debug!("Successfully read contents from {}.", path.display());
+    Ok(Some(contents))
+}
 
-    // Deserialize into the wrapper struct
-    let task_list: TaskList =
-        toml::from_str(&contents).map_err(|e| AppError::TomlDeserialize(e))?;
-    info!(
-        "Successfully loaded {} tasks from {}.",
-        task_list.tasks.len(),
-        path.display()
-    );
+/// Parses the contents of a tasks file: a TOML or MessagePack task list, or
+/// an event log. An empty file holds no tasks.
+pub fn parse_tasks(contents: &[u8]) -> Result<Vec<Task>> {
+    if let Some(data) = contents.strip_prefix(MSGPACK_MAGIC) {
+        let task_list = migrate::upgrade(read_msgpack(data), || read_msgpack(data))?;
+        return Ok(task_list.tasks);
+    }
+    let contents = as_text(contents)?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    if eventlog::is_event_log_contents(contents) {
+        return Ok(eventlog::replay(&eventlog::parse_events(contents)?));
+    }
+    // Deserialize into the wrapper struct, upgrading older layouts
+    let task_list = migrate::upgrade(
+        toml::from_str(contents).map_err(AppError::TomlDeserialize),
+        || toml::from_str(contents).map_err(AppError::TomlDeserialize),
+    )?;
     Ok(task_list.tasks)
 }
 
-/// Saves the given tasks to the tasks file.
+/// Reads `T` from MessagePack data written by [`encode_tasks`].
+///
+/// Lists written before UUIDs were stored as text are read too.
+fn read_msgpack<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T> {
+    let mut deserializer = rmp_serde::Deserializer::from_read_ref(data).with_human_readable();
+    T::deserialize(&mut deserializer).or_else(|e| rmp_serde::from_slice(data).map_err(|_| e.into()))
+}
+
+fn as_text(contents: &[u8]) -> Result<&str> {
+    std::str::from_utf8(contents).map_err(|e| {
+        AppError::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("tasks file is not valid UTF-8: {}", e),
+        ))
+    })
+}
+
+/// A `[[tasks]]` section of a TOML data file that can't be read.
+#[derive(Debug, Clone)]
+pub struct BadSection {
+    /// The line the section starts on.
+    pub line: usize,
+    pub text: String,
+    pub error: String,
+}
+
+/// Splits a TOML task list that doesn't parse as a whole into its
+/// `[[tasks]]` sections, returning the tasks of the readable ones and the
+/// other sections.
+///
+/// Returns `None` if `contents` aren't a TOML task list.
+pub fn parse_sections(contents: &[u8]) -> Option<(Vec<Task>, Vec<BadSection>)> {
+    if StorageFormat::of(contents) != StorageFormat::Toml {
+        return None;
+    }
+    let text = std::str::from_utf8(contents).ok()?;
+    if eventlog::is_event_log_contents(text) {
+        return None;
+    }
+    let mut sections: Vec<(usize, String)> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim() == "[[tasks]]" {
+            sections.push((i + 1, String::new()));
+        }
+        if let Some((_, section)) = sections.last_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+    if sections.is_empty() {
+        return None;
+    }
+
+    let mut tasks = Vec::new();
+    let mut bad = Vec::new();
+    for (line, section) in sections {
+        match toml::from_str::<TaskList>(&section) {
+            Ok(list) => tasks.extend(list.tasks),
+            Err(e) => bad.push(BadSection {
+                line,
+                text: section,
+                error: e.message().to_string(),
+            }),
+        }
+    }
+    Some((tasks, bad))
+}
+
+/// What [`recover_tasks`] could load of a damaged data file.
+#[derive(Debug)]
+pub struct Recovery {
+    pub tasks: Vec<Task>,
+    /// The sections left out.
+    pub skipped: Vec<BadSection>,
+    /// Where the file was copied to before anything was left out.
+    pub backup: PathBuf,
+}
+
+/// Loads the readable tasks of the data file at `path`, whose contents
+/// `contents` don't parse as a whole, after backing the file up next to it.
+///
+/// Fails with `error`, the reason the contents didn't parse, if they aren't
+/// a TOML task list that can be split into tasks.
+pub fn recover_tasks(path: &Path, contents: &[u8], error: AppError) -> Result<Recovery> {
+    let Some((tasks, skipped)) = parse_sections(contents) else {
+        return Err(error);
+    };
+    let name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| TASKS_FILE_NAME.to_string());
+    let backup = path.with_file_name(format!(
+        "{}.corrupt-{}.bak",
+        name,
+        clock::now().format("%Y%m%dT%H%M%S")
+    ));
+    fs::copy(path, &backup)?;
+    warn!(
+        "Skipped {} unreadable sections of {}; backed it up to {}.",
+        skipped.len(),
+        path.display(),
+        backup.display()
+    );
+    Ok(Recovery {
+        tasks,
+        skipped,
+        backup,
+    })
+}
+
+/// The IDs of a task list, read without the rest of the tasks.
+#[derive(Deserialize)]
+struct TaskIdList {
+    #[serde(default)]
+    tasks: Vec<TaskId>,
+}
+
+#[derive(Deserialize)]
+struct TaskId {
+    id: u32,
+}
+
+/// Reads the IDs of the tasks in the contents of a tasks file, skipping
+/// everything else about them.
+pub fn read_task_ids(contents: &[u8]) -> Result<Vec<u32>> {
+    let list: TaskIdList = match contents.strip_prefix(MSGPACK_MAGIC) {
+        Some(data) => rmp_serde::from_slice(data)?,
+        None => {
+            let contents = as_text(contents)?;
+            if eventlog::is_event_log_contents(contents) {
+                return eventlog::read_event_log_ids(contents);
+            }
+            toml::from_str(contents).map_err(AppError::TomlDeserialize)?
+        }
+    };
+    Ok(list.tasks.into_iter().map(|t| t.id).collect())
+}
+
+/// Returns `true` if [`append_tasks_to`] can add to the tasks file at `path`
+/// holding `existing`, the tasks with the IDs `ids`.
+///
+/// That takes an event log or a TOML file already holding a task, since an
+/// empty TOML list is stored as `tasks = []`, which further tasks can't
+/// follow. MessagePack files are always rewritten whole.
+pub fn can_append_to(path: &Path, existing: &[u8], ids: &[u32]) -> bool {
+    match StorageFormat::of(existing) {
+        StorageFormat::Msgpack => false,
+        StorageFormat::Toml => {
+            !ids.is_empty()
+                || eventlog::is_event_log(path)
+                || as_text(existing).is_ok_and(eventlog::is_event_log_contents)
+        }
+    }
+}
+
+/// Adds `tasks` to the end of the plain tasks file at `path`, whose current
+/// contents are `existing`, without rewriting the tasks already in it.
+///
+/// See [`can_append_to`] for the files this works on.
+pub fn append_tasks_to(tasks: &[Task], path: &Path, existing: &[u8]) -> Result<()> {
+    let text = as_text(existing)?;
+    if eventlog::is_event_log_contents(text) || eventlog::is_event_log(path) {
+        let events = tasks
+            .iter()
+            .map(|task| eventlog::TaskEvent::TaskAdded { task: task.clone() })
+            .collect();
+        eventlog::append_events(path, text, events)?;
+    } else {
+        let mut addition = String::from(if text.ends_with('\n') { "\n" } else { "\n\n" });
+        // Only the task tables; the file has its header already, and one
+        // repeated after a task would be read as a field of it.
+        let serialized = serialize_tasks(tasks)?;
+        addition.push_str(
+            serialized
+                .find("[[tasks]]")
+                .map_or(serialized.as_str(), |start| &serialized[start..]),
+        );
+        let mut file = fs::OpenOptions::new().append(true).open(path)?;
+        file.write_all(addition.as_bytes())?;
+    }
+    info!("Appended {} tasks to {}.", tasks.len(), path.display());
+    Ok(())
+}
+
+/// Saves the given tasks to the default tasks file.
 ///
 /// Overwrites the existing file if it exists.
 ///
@@ -63,22 +366,204 @@ pub fn load_tasks() -> Result<Vec<Task>> {
 ///
 /// A `Result` by wrapping in `TaskList` indicating success or an `AppError` on failure.
 pub fn save_tasks(tasks: &[Task]) -> Result<()> {
-    let path = get_tasks_file_path();
+    save_tasks_to(tasks, &default_tasks_file_path())
+}
+
+/// Saves the given tasks to the tasks file at `path`, overwriting it.
+///
+/// An existing file keeps its form; see [`encryption::should_encrypt`].
+pub fn save_tasks_to(tasks: &[Task], path: &Path) -> Result<()> {
+    write_tasks_to(tasks, path, encryption::should_encrypt(path))
+}
+
+/// Saves the given tasks to `path`, encrypted if `encrypted` is set.
+///
+/// An existing file keeps its format; see [`stored_format`].
+pub fn write_tasks_to(tasks: &[Task], path: &Path, encrypted: bool) -> Result<()> {
+    write_tasks_as(tasks, path, encrypted, stored_format(path)?)
+}
+
+/// Saves the given tasks to `path` in `format`, encrypted if `encrypted` is
+/// set. Event logs are written as such whatever `format` says.
+pub fn write_tasks_as(
+    tasks: &[Task],
+    path: &Path,
+    encrypted: bool,
+    format: StorageFormat,
+) -> Result<()> {
     debug!(
         "Attempting to save {} tasks to: {}",
         tasks.len(),
         path.display()
     );
 
-    // Wrap the tasks slice into a TaskList struct for serialization
-    let task_list = TaskList {
-        tasks: tasks.to_vec(),
+    if eventlog::is_event_log(path) {
+        eventlog::write_event_log(tasks, path, encrypted)?;
+    } else {
+        let contents = encode_tasks(tasks, format)?;
+        encryption::write_file(path, &contents, encrypted)?;
+    }
+    info!("Successfully saved tasks to {}.", path.display());
+
+    Ok(())
+}
+
+/// Renders `tasks` the way a data file in `format` stores them.
+pub fn encode_tasks(tasks: &[Task], format: StorageFormat) -> Result<Vec<u8>> {
+    match format {
+        StorageFormat::Toml => Ok(serialize_tasks(tasks)?.into_bytes()),
+        StorageFormat::Msgpack => {
+            // Human-readable, so UUIDs are text and the list reads as a plain
+            // document when it needs migrating.
+            let mut contents =
+                Vec::with_capacity(MSGPACK_MAGIC.len() + tasks.len() * MSGPACK_BYTES_PER_TASK);
+            contents.extend_from_slice(MSGPACK_MAGIC);
+            let mut serializer = rmp_serde::Serializer::new(&mut contents)
+                .with_struct_map()
+                .with_human_readable();
+            TaskListRef::of(tasks).serialize(&mut serializer)?;
+            Ok(contents)
+        }
+    }
+}
+
+/// Renders `tasks` the way a TOML tasks file stores them.
+pub fn serialize_tasks(tasks: &[Task]) -> Result<String> {
+    toml::to_string(&TaskListRef::of(tasks)).map_err(AppError::TomlSerialize)
+}
+
+/// Returns `true` if `contents` read from a tasks file already hold exactly
+/// `tasks`, so there is nothing to save.
+///
+/// A TOML file written by hand or another tool counts as up to date when it
+/// holds the same values in another layout, so comments and formatting
+/// alone never make it be rewritten. Fields filled in on load, like missing
+/// UUIDs, do.
+pub fn is_up_to_date(contents: &[u8], tasks: &[Task]) -> Result<bool> {
+    let format = StorageFormat::of(contents);
+    if format == StorageFormat::Toml && as_text(contents).is_ok_and(eventlog::is_event_log_contents)
+    {
+        return Ok(eventlog::diff_events(&parse_tasks(contents)?, tasks).is_empty());
+    }
+    let encoded = encode_tasks(tasks, format)?;
+    if contents == encoded {
+        return Ok(true);
+    }
+    if format != StorageFormat::Toml {
+        return Ok(false);
+    }
+    let stored = as_text(contents)
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok());
+    Ok(stored.is_some() && stored == as_text(&encoded)?.parse::<toml::Table>().ok())
+}
+
+/// Returns `true` if `err` means the file can't be written where it is,
+/// such as on a read-only or full filesystem, or without permission.
+pub fn is_unwritable(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::ReadOnlyFilesystem
+            | io::ErrorKind::StorageFull
+            | io::ErrorKind::PermissionDenied
+    )
+}
+
+/// Returns `true` if the file at `path` exists but can't be opened for
+/// writing, e.g. on a read-only mount or without permission.
+pub fn is_read_only(path: &Path) -> bool {
+    fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .is_err_and(|e| is_unwritable(&e))
+}
+
+/// Where a save ended up.
+#[derive(Debug)]
+pub enum SaveOutcome {
+    /// The tasks were written to the requested file.
+    Saved,
+    /// The requested file couldn't be written, so the tasks went to `path`.
+    FellBack { path: PathBuf, reason: io::Error },
+}
+
+/// Saves `tasks` to `path`, or to `fallback` if `path` isn't writable.
+///
+/// Only failures recognized by [`is_unwritable`] trigger the fallback; other
+/// errors, and failures to write the fallback itself, are returned.
+/// A successful save to `path` removes a stale fallback file. The fallback is
+/// written in the same form and format as `path`.
+pub fn save_tasks_with_fallback(
+    tasks: &[Task],
+    path: &Path,
+    fallback: Option<&Path>,
+) -> Result<SaveOutcome> {
+    let encrypted = encryption::should_encrypt(path);
+    let format = stored_format(path)?;
+    let reason = match write_tasks_as(tasks, path, encrypted, format) {
+        Ok(()) => {
+            if let Some(fallback) = fallback.filter(|f| f.exists()) {
+                fs::remove_file(fallback)?;
+                info!("Removed fallback file {}.", fallback.display());
+            }
+            return Ok(SaveOutcome::Saved);
+        }
+        Err(AppError::Io(e)) if fallback.is_some() && is_unwritable(&e) => e,
+        Err(e) => return Err(e),
     };
 
-    let contents = toml::to_string(&task_list).map_err(|e| AppError::TomlSerialize(e))?;
+    let fallback = fallback.unwrap_or(path);
+    warn!(
+        "Could not write {} ({}). Falling back to {}.",
+        path.display(),
+        reason,
+        fallback.display()
+    );
+    if let Some(dir) = fallback.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    write_tasks_as(tasks, fallback, encrypted, format)?;
+    Ok(SaveOutcome::FellBack {
+        path: fallback.to_path_buf(),
+        reason,
+    })
+}
 
-    fs::write(&path, contents)?;
-    info!("Successfully saved tasks to {}.", path.display());
+/// Returns when the file at `path` was last modified, if it exists.
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
 
-    Ok(())
+/// Reads the tasks file at `path` again if another process saved it since
+/// it was loaded as `loaded`, last modified at `modified`.
+///
+/// Returns the contents it holds now, or `None` if it is unchanged.
+pub fn changed_since_load(
+    path: &Path,
+    loaded: Option<&[u8]>,
+    modified: Option<SystemTime>,
+) -> Result<Option<Vec<u8>>> {
+    if modified_time(path) == modified {
+        return Ok(None);
+    }
+    let current = read_tasks_file(path)?;
+    if current.as_deref() == loaded {
+        return Ok(None);
+    }
+    debug!("{} changed since it was loaded.", path.display());
+    Ok(Some(current.unwrap_or_default()))
+}
+
+/// Picks the file to load: `fallback` if it was written after `path`
+/// (i.e. the last save fell back), otherwise `path`.
+pub fn resolve_load_path(path: &Path, fallback: Option<&Path>) -> PathBuf {
+    match fallback {
+        Some(fallback) => match (modified_time(path), modified_time(fallback)) {
+            (_, None) => path.to_path_buf(),
+            (None, Some(_)) => fallback.to_path_buf(),
+            (Some(primary), Some(newer)) if newer > primary => fallback.to_path_buf(),
+            _ => path.to_path_buf(),
+        },
+        None => path.to_path_buf(),
+    }
 }
@@ -0,0 +1,136 @@
+//! Where a task list is kept, for hosts that don't read it from a file.
+//!
+//! The task logic itself never touches the file system: [`TaskManager`]
+//! works on a loaded `Vec<Task>`, and [`parse_tasks`] and [`encode_tasks`]
+//! turn the stored bytes into tasks and back. A [`TaskStore`] says where
+//! those bytes live. The command line keeps them in the data file through
+//! [`FileStore`], and [`MemoryStore`] keeps them in memory for tests and
+//! embedding; a browser built for wasm32 (without the default `native`
+//! feature) can keep them in local storage instead:
+//!
+//! ```ignore
+//! struct LocalStorage(web_sys::Storage);
+//!
+//! impl TaskStore for LocalStorage {
+//!     fn read(&self) -> Result<Option<Vec<u8>>> { /* getItem("tasks") */ }
+//!     fn write(&mut self, contents: &[u8]) -> Result<()> { /* setItem */ }
+//! }
+//!
+//! let mut tasks = store.load()?;
+//! TaskManager::new(&mut tasks, &config).complete(3)?;
+//! store.save(&tasks)?;
+//! ```
+//!
+//! [`TaskManager`]: crate::app::TaskManager
+
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    Result, StorageFormat, Task, default_format, encode_tasks, encryption, load_tasks_from,
+    parse_tasks, read_tasks_file, save_tasks_to,
+};
+
+/// Somewhere the bytes of a task list can be read from and written to.
+///
+/// Hosts implement [`read`](TaskStore::read) and
+/// [`write`](TaskStore::write); loading and saving tasks come with them.
+pub trait TaskStore {
+    /// Returns what was stored last, or `None` if nothing was stored yet.
+    fn read(&self) -> Result<Option<Vec<u8>>>;
+
+    /// Replaces what is stored with `contents`.
+    fn write(&mut self, contents: &[u8]) -> Result<()>;
+
+    /// Loads the stored tasks; none if nothing was stored yet.
+    fn load(&self) -> Result<Vec<Task>> {
+        match self.read()? {
+            Some(contents) => parse_tasks(&contents),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Stores `tasks`, in the format of what was stored before if anything
+    /// was, and in the default format otherwise.
+    fn save(&mut self, tasks: &[Task]) -> Result<()> {
+        let format = match self.read()? {
+            Some(contents) => StorageFormat::of(&contents),
+            None => default_format(),
+        };
+        self.write(&encode_tasks(tasks, format)?)
+    }
+}
+
+/// The data file at a path, encrypted and kept as an event log as the file
+/// already is.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Creates a store for the data file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStore { path: path.into() }
+    }
+
+    /// Returns the path of the data file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TaskStore for FileStore {
+    fn read(&self) -> Result<Option<Vec<u8>>> {
+        read_tasks_file(&self.path)
+    }
+
+    fn write(&mut self, contents: &[u8]) -> Result<()> {
+        let encrypted = encryption::should_encrypt(&self.path);
+        encryption::write_file(&self.path, contents, encrypted)
+    }
+
+    fn load(&self) -> Result<Vec<Task>> {
+        load_tasks_from(&self.path)
+    }
+
+    fn save(&mut self, tasks: &[Task]) -> Result<()> {
+        save_tasks_to(tasks, &self.path)
+    }
+}
+
+/// A task list kept in memory, for tests and embedding: nothing touches the
+/// file system or depends on the current directory.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    contents: Option<Vec<u8>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    /// Creates a store holding `tasks`, encoded in the default format.
+    pub fn with_tasks(tasks: &[Task]) -> Result<Self> {
+        let mut store = MemoryStore::new();
+        store.save(tasks)?;
+        Ok(store)
+    }
+
+    /// Returns what was stored last, as a data file would hold it.
+    pub fn contents(&self) -> Option<&[u8]> {
+        self.contents.as_deref()
+    }
+}
+
+impl TaskStore for MemoryStore {
+    fn read(&self) -> Result<Option<Vec<u8>>> {
+        Ok(self.contents.clone())
+    }
+
+    fn write(&mut self, contents: &[u8]) -> Result<()> {
+        self.contents = Some(contents.to_vec());
+        Ok(())
+    }
+}
@@ -0,0 +1,627 @@
+//! Two-way sync with a CalDAV task list, such as one on Nextcloud or Radicale.
+//!
+//! Every task is stored on the server as a VTODO whose UID is the task's UUID.
+//! Items created by other clients keep their own UID; the mapping is kept
+//! in a state file next to the data file, together with the task list as of
+//! the last sync. That copy is the common ancestor [`merge_tasks`] needs to
+//! tell edits from deletions on either side.
+//!
+//! Only what a VTODO can hold travels to the server: the description, due
+//! date, status, priority, project, and tags. Notes, recurrence, and other
+//! fields stay local.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use log::{debug, info};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ureq::http::{Request, Response};
+use ureq::{Agent, Body};
+use uuid::Uuid;
+
+use crate::app::export::{escape_ics_text, ics_document, vtodo_properties};
+use crate::app::{
+    AppError, CaldavConfig, ConflictResolver, NewestWins, Priority, Result, Task, TaskDiff, clock,
+    encryption, merge_tasks, run_password_command,
+};
+
+/// The body of the REPORT request listing every VTODO with its ETag.
+const CALENDAR_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+impl CaldavConfig {
+    /// Returns the password from the config or from `password_command`.
+    fn resolve_password(&self) -> Result<Option<String>> {
+        if let Some(password) = &self.password {
+            return Ok(Some(password.clone()));
+        }
+        match &self.password_command {
+            Some(command) => run_password_command(command).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// What a CalDAV sync changed.
+#[derive(Debug, Default, Clone)]
+pub struct CaldavReport {
+    /// Tasks added, changed, or removed locally.
+    pub pulled: usize,
+    /// Items created or updated on the server.
+    pub pushed: usize,
+    /// Items deleted from the server.
+    pub deleted: usize,
+    /// Tasks that disappeared from the server and were removed locally.
+    pub removed: Vec<Task>,
+}
+
+/// A VTODO on the server as of the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncedItem {
+    uuid: Uuid,
+    uid: String,
+    href: String,
+    etag: Option<String>,
+}
+
+/// What the last sync left behind.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CaldavState {
+    #[serde(default)]
+    items: Vec<SyncedItem>,
+    #[serde(default)]
+    tasks: Vec<Task>,
+}
+
+/// Returns where the CalDAV sync state of the data file at `tasks_path` is kept.
+pub fn caldav_state_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.caldav.toml", stem))
+}
+
+/// Syncs `tasks` with the CalDAV task list, updating them in place.
+///
+/// A field changed on both sides is settled by `resolver`. Without one it
+/// keeps the local value if the data file was modified after the newest item
+/// on the server, and the server's otherwise.
+pub fn sync_caldav(
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    config: &CaldavConfig,
+    resolver: Option<&mut dyn ConflictResolver>,
+) -> Result<CaldavReport> {
+    let state_path = caldav_state_path(tasks_path);
+    let state: CaldavState = if state_path.exists() {
+        toml::from_str(&encryption::read_file(&state_path)?)?
+    } else {
+        CaldavState::default()
+    };
+    let client = Client::new(config)?;
+    let remote = client.list()?;
+    debug!("Fetched {} items from {}.", remote.len(), config.url);
+
+    // Map every server item onto a task UUID, keeping the local-only fields.
+    let known: HashMap<&str, Uuid> = state
+        .items
+        .iter()
+        .map(|i| (i.uid.as_str(), i.uuid))
+        .collect();
+    let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    let mut theirs = Vec::new();
+    let mut items: HashMap<Uuid, (SyncedItem, Todo)> = HashMap::new();
+    let mut newest_remote = None;
+    for (href, etag, data) in remote {
+        let Some(todo) = Todo::parse(&data) else {
+            debug!("Skipping {}: no VTODO.", href);
+            continue;
+        };
+        let uuid = known
+            .get(todo.uid.as_str())
+            .copied()
+            .or_else(|| Uuid::parse_str(&todo.uid).ok())
+            .unwrap_or_else(Uuid::new_v4);
+        let mut task = match tasks.iter().chain(&state.tasks).find(|t| t.uuid == uuid) {
+            Some(task) => task.clone(),
+            None => {
+                let mut task = Task::new(next_id, String::new(), None);
+                next_id += 1;
+                task.uuid = uuid;
+                task
+            }
+        };
+        todo.apply(&mut task);
+        newest_remote = newest_remote.max(todo.last_modified);
+        theirs.push(task);
+        let item = SyncedItem {
+            uuid,
+            uid: todo.uid.clone(),
+            href,
+            etag,
+        };
+        items.insert(uuid, (item, todo));
+    }
+
+    let local_modified: Option<DateTime<Utc>> = fs::metadata(tasks_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(Into::into);
+    let mut newest = NewestWins {
+        ours_newer: match (local_modified, newest_remote) {
+            (Some(local), Some(remote)) => local >= remote,
+            _ => true,
+        },
+    };
+    let merged = merge_tasks(
+        &state.tasks,
+        tasks,
+        &theirs,
+        resolver.unwrap_or(&mut newest),
+    )?;
+
+    let diff = TaskDiff::between(tasks, &merged)?;
+    let mut report = CaldavReport {
+        pulled: diff.added.len() + diff.removed.len() + diff.changed.len(),
+        removed: diff.removed,
+        ..CaldavReport::default()
+    };
+
+    let mut synced = Vec::new();
+    for task in &merged {
+        let wanted = Todo::from_task(task);
+        match items.remove(&task.uuid) {
+            Some((item, todo)) if todo.same_content(&wanted) => synced.push(item),
+            Some((mut item, _)) => {
+                item.etag = client.put(&item.href, &item.uid, task, item.etag.as_deref())?;
+                report.pushed += 1;
+                synced.push(item);
+            }
+            None => {
+                let uid = task.uuid.to_string();
+                let href = format!("{}.ics", uid);
+                let etag = client.put(&href, &uid, task, None)?;
+                report.pushed += 1;
+                synced.push(SyncedItem {
+                    uuid: task.uuid,
+                    uid,
+                    href,
+                    etag,
+                });
+            }
+        }
+    }
+    for (item, _) in items.into_values() {
+        client.delete(&item.href, item.etag.as_deref())?;
+        report.deleted += 1;
+    }
+
+    let state = CaldavState {
+        items: synced,
+        tasks: merged.clone(),
+    };
+    encryption::write_file(
+        &state_path,
+        &toml::to_string(&state)?,
+        encryption::should_encrypt(tasks_path),
+    )?;
+    *tasks = merged;
+    info!(
+        "CalDAV sync: {} pulled, {} pushed, {} deleted.",
+        report.pulled, report.pushed, report.deleted
+    );
+    Ok(report)
+}
+
+/// The status values of a VTODO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TodoStatus {
+    NeedsAction,
+    Completed,
+    Cancelled,
+}
+
+/// The fields of a VTODO that map onto a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Todo {
+    uid: String,
+    summary: String,
+    due: Option<NaiveDate>,
+    status: TodoStatus,
+    priority: Option<Priority>,
+    project: Option<String>,
+    tags: Vec<String>,
+    completed_at: Option<DateTime<Utc>>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+impl Todo {
+    fn from_task(task: &Task) -> Self {
+        Todo {
+            uid: task.uuid.to_string(),
+            summary: task.description.clone(),
+            due: task.due_date,
+            status: if task.is_done() {
+                TodoStatus::Completed
+            } else if task.is_cancelled() {
+                TodoStatus::Cancelled
+            } else {
+                TodoStatus::NeedsAction
+            },
+            priority: task.priority,
+            project: task.project.clone(),
+            tags: task.tags.clone(),
+            completed_at: task.completed_at,
+            last_modified: None,
+        }
+    }
+
+    /// Returns `true` if both describe the task the same way, ignoring timestamps.
+    fn same_content(&self, other: &Todo) -> bool {
+        (
+            &self.summary,
+            self.due,
+            self.status,
+            self.priority,
+            &self.project,
+            &self.tags,
+        ) == (
+            &other.summary,
+            other.due,
+            other.status,
+            other.priority,
+            &other.project,
+            &other.tags,
+        )
+    }
+
+    /// Copies the VTODO's fields onto `task`.
+    fn apply(&self, task: &mut Task) {
+        task.description = self.summary.clone();
+        task.set_due_date(self.due);
+        task.priority = self.priority;
+        task.project = self.project.clone();
+        task.tags = self.tags.clone();
+        match self.status {
+            TodoStatus::Completed if !task.is_done() => {
+                task.mark_completion(true);
+                task.completed_at = self.completed_at.or(task.completed_at);
+            }
+            TodoStatus::Cancelled if !task.is_cancelled() => {
+                task.mark_completion(false);
+                task.cancel();
+            }
+            TodoStatus::NeedsAction if !task.is_pending() => task.mark_completion(false),
+            _ => {}
+        }
+    }
+
+    /// Parses the first VTODO of an iCalendar document.
+    fn parse(data: &str) -> Option<Self> {
+        let unfolded = data.replace("\r\n ", "").replace("\r\n\t", "");
+        let unfolded = unfolded.replace("\n ", "").replace("\n\t", "");
+        let mut lines = unfolded.lines().map(|l| l.trim_end_matches('\r'));
+        lines.find(|l| l.eq_ignore_ascii_case("BEGIN:VTODO"))?;
+
+        let mut todo = Todo {
+            uid: String::new(),
+            summary: String::new(),
+            due: None,
+            status: TodoStatus::NeedsAction,
+            priority: None,
+            project: None,
+            tags: Vec::new(),
+            completed_at: None,
+            last_modified: None,
+        };
+        let mut categories = Vec::new();
+        for line in lines {
+            if line.eq_ignore_ascii_case("END:VTODO") {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let name = name.split(';').next().unwrap_or("").to_ascii_uppercase();
+            match name.as_str() {
+                "UID" => todo.uid = value.to_string(),
+                "SUMMARY" => todo.summary = unescape_ics_text(value),
+                "DUE" => todo.due = parse_ics_date(value),
+                "STATUS" => {
+                    todo.status = match value.to_ascii_uppercase().as_str() {
+                        "COMPLETED" => TodoStatus::Completed,
+                        "CANCELLED" => TodoStatus::Cancelled,
+                        _ => TodoStatus::NeedsAction,
+                    }
+                }
+                "PRIORITY" => {
+                    todo.priority = match value.trim().parse::<u8>() {
+                        Ok(1..=4) => Some(Priority::High),
+                        Ok(5) => Some(Priority::Medium),
+                        Ok(6..=9) => Some(Priority::Low),
+                        _ => None,
+                    }
+                }
+                "CATEGORIES" => categories.extend(split_ics_list(value)),
+                "X-TM-PROJECT" => todo.project = Some(unescape_ics_text(value)),
+                "COMPLETED" => todo.completed_at = parse_ics_datetime(value),
+                "LAST-MODIFIED" => todo.last_modified = parse_ics_datetime(value),
+                _ => {}
+            }
+        }
+        // The project is listed first among the categories; see `vtodo_properties`.
+        if let Some(project) = &todo.project
+            && categories.first() == Some(project)
+        {
+            categories.remove(0);
+        }
+        todo.tags = categories;
+        (!todo.uid.is_empty()).then_some(todo)
+    }
+}
+
+fn unescape_ics_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Splits a comma-separated list value, honoring escaped commas.
+fn split_ics_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => items.push(std::mem::take(&mut current)),
+            _ => {
+                escaped = c == '\\' && !escaped;
+                current.push(c);
+            }
+        }
+    }
+    items.push(current);
+    items
+        .iter()
+        .map(|item| unescape_ics_text(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    let date = value.get(..8)?;
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+fn parse_ics_datetime(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// A minimal WebDAV client for one calendar collection.
+struct Client {
+    agent: Agent,
+    base: String,
+    auth: Option<String>,
+}
+
+impl Client {
+    fn new(config: &CaldavConfig) -> Result<Self> {
+        if config.url.is_empty() {
+            return Err(AppError::Sync(
+                "set `url` under [sync.caldav] in the config file".to_string(),
+            ));
+        }
+        let auth = match &config.username {
+            Some(user) => {
+                let password = config.resolve_password()?.unwrap_or_default();
+                Some(format!(
+                    "Basic {}",
+                    BASE64.encode(format!("{}:{}", user, password))
+                ))
+            }
+            None => None,
+        };
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .allow_non_standard_methods(true)
+            .build()
+            .new_agent();
+        let mut base = config.url.clone();
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        Ok(Client { agent, base, auth })
+    }
+
+    /// Resolves an href from the server, or a file name, against the collection URL.
+    fn url(&self, href: &str) -> String {
+        if href.contains("://") {
+            href.to_string()
+        } else if href.starts_with('/') {
+            let origin_end = self
+                .base
+                .find("://")
+                .and_then(|scheme| self.base[scheme + 3..].find('/').map(|i| i + scheme + 3))
+                .unwrap_or(self.base.len());
+            format!("{}{}", &self.base[..origin_end], href)
+        } else {
+            format!("{}{}", self.base, href)
+        }
+    }
+
+    fn send(&self, request: ureq::http::request::Builder, body: String) -> Result<Response<Body>> {
+        let request = match &self.auth {
+            Some(auth) => request.header("Authorization", auth),
+            None => request,
+        };
+        let request: Request<String> = request
+            .body(body)
+            .map_err(|e| AppError::Sync(e.to_string()))?;
+        let uri = request.uri().to_string();
+        let response = self
+            .agent
+            .run(request)
+            .map_err(|e| AppError::Sync(format!("{}: {}", uri, e)))?;
+        match response.status().as_u16() {
+            200..=299 => Ok(response),
+            412 => Err(AppError::Sync(format!(
+                "{} changed on the server during the sync; run sync again",
+                uri
+            ))),
+            status => Err(AppError::Sync(format!("{} returned HTTP {}", uri, status))),
+        }
+    }
+
+    /// Lists the collection's VTODOs as `(href, etag, calendar data)`.
+    fn list(&self) -> Result<Vec<(String, Option<String>, String)>> {
+        let request = Request::builder()
+            .method("REPORT")
+            .uri(&self.base)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8");
+        let mut response = self.send(request, CALENDAR_QUERY.to_string())?;
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| AppError::Sync(e.to_string()))?;
+        parse_multistatus(&body)
+    }
+
+    /// Creates or replaces the item at `href`, returning its new ETag.
+    ///
+    /// With `etag`, the request only succeeds if the item is unchanged since.
+    fn put(
+        &self,
+        href: &str,
+        uid: &str,
+        task: &Task,
+        etag: Option<&str>,
+    ) -> Result<Option<String>> {
+        let stamp = clock::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec!["BEGIN:VTODO".to_string()];
+        lines.extend(vtodo_properties(task, uid, &stamp));
+        if let Some(project) = &task.project {
+            lines.push(format!("X-TM-PROJECT:{}", escape_ics_text(project)));
+        }
+        if let Some(at) = task.completed_at {
+            lines.push(format!("COMPLETED:{}", at.format("%Y%m%dT%H%M%SZ")));
+        }
+        lines.push(format!("LAST-MODIFIED:{}", stamp));
+        lines.push("END:VTODO".to_string());
+
+        let request = Request::builder()
+            .method("PUT")
+            .uri(self.url(href))
+            .header("Content-Type", "text/calendar; charset=utf-8");
+        let request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        let response = self.send(request, ics_document(lines))?;
+        Ok(response
+            .headers()
+            .get("ETag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string))
+    }
+
+    fn delete(&self, href: &str, etag: Option<&str>) -> Result<()> {
+        let request = Request::builder().method("DELETE").uri(self.url(href));
+        let request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request,
+        };
+        self.send(request, String::new()).map(drop)
+    }
+}
+
+/// Extracts `(href, etag, calendar data)` from a WebDAV multistatus response.
+fn parse_multistatus(xml: &str) -> Result<Vec<(String, Option<String>, String)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let (mut href, mut etag, mut data) = (String::new(), String::new(), String::new());
+    let mut field: Option<String> = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| AppError::Sync(format!("invalid response from server: {}", e)))?;
+        match event {
+            Event::Start(start) => {
+                let name = start.local_name().as_ref().to_string();
+                match name.as_str() {
+                    "response" => {
+                        href.clear();
+                        etag.clear();
+                        data.clear();
+                    }
+                    "href" | "getetag" | "calendar-data" => field = Some(name),
+                    _ => {}
+                }
+            }
+            Event::End(end) => {
+                let name = end.local_name();
+                if name.as_ref() == "response" && !data.is_empty() {
+                    let etag = (!etag.is_empty()).then(|| etag.trim().to_string());
+                    entries.push((href.trim().to_string(), etag, std::mem::take(&mut data)));
+                } else if field.as_deref() == Some(name.as_ref()) {
+                    field = None;
+                }
+            }
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) => {
+                let text = match &event {
+                    Event::Text(text) => text.xml10_content().into_owned(),
+                    Event::CData(cdata) => cdata.xml10_content().into_owned(),
+                    Event::GeneralRef(reference) => resolve_entity(reference),
+                    _ => unreachable!(),
+                };
+                match field.as_deref() {
+                    Some("href") => href.push_str(&text),
+                    Some("getetag") => etag.push_str(&text),
+                    Some("calendar-data") => data.push_str(&text),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+fn resolve_entity(reference: &quick_xml::events::BytesRef) -> String {
+    if let Ok(Some(c)) = reference.resolve_char_ref() {
+        return c.to_string();
+    }
+    match reference.xml10_content().as_ref() {
+        "lt" => "<",
+        "gt" => ">",
+        "amp" => "&",
+        "quot" => "\"",
+        "apos" => "'",
+        _ => "",
+    }
+    .to_string()
+}
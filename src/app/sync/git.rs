@@ -0,0 +1,258 @@
+//! Syncs the data file through a git repository.
+//!
+//! The directory holding the data file becomes a git repository of its own.
+//! `sync` commits local changes, fetches the configured remote, and pushes
+//! the result back. When both sides changed since they last met, the task
+//! lists are merged by UUID against their common ancestor: changes from
+//! either side are kept, and a field changed on both sides takes the value
+//! from the side that was edited last. Commits are dated with the data file's
+//! modification time so "last" means the last edit, not the last sync.
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app::{
+    AppError, ConflictResolver, NewestWins, Result, SyncConfig, Task, clock, encryption,
+    merge_tasks, storage,
+};
+
+/// The name of the remote `sync` manages in the repository.
+const REMOTE_NAME: &str = "origin";
+
+/// What a sync did.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Local changes were committed.
+    pub committed: bool,
+    /// Remote changes were applied without a merge.
+    pub fast_forwarded: bool,
+    /// Both sides had changed and were merged.
+    pub merged: bool,
+    /// The result was pushed to the remote.
+    pub pushed: bool,
+}
+
+/// Runs `sync` for the data file at `tasks_path`.
+///
+/// Conflicting edits are settled by `resolver`, or in favor of the more
+/// recent commit without one.
+pub fn sync_tasks(
+    tasks_path: &Path,
+    config: &SyncConfig,
+    resolver: Option<&mut dyn ConflictResolver>,
+) -> Result<SyncReport> {
+    let repo = Repo::open(tasks_path)?;
+    let mut report = SyncReport {
+        committed: repo.commit_data_file()?,
+        ..SyncReport::default()
+    };
+
+    let Some(url) = &config.remote else {
+        info!("No sync remote configured; committed locally only.");
+        return Ok(report);
+    };
+    repo.set_remote(url)?;
+    if repo
+        .git(&["ls-remote", "--heads", REMOTE_NAME, &config.branch])?
+        .is_empty()
+    {
+        debug!("Remote branch {} does not exist yet.", config.branch);
+    } else {
+        repo.git(&["fetch", REMOTE_NAME, &config.branch])?;
+        match repo.head()? {
+            None => {
+                repo.git(&["checkout", "-B", &config.branch, "FETCH_HEAD"])?;
+                report.fast_forwarded = true;
+            }
+            Some(head) => {
+                let remote = repo.git(&["rev-parse", "FETCH_HEAD"])?;
+                let base = repo.git(&["merge-base", &head, &remote])?;
+                if base == remote {
+                    debug!("Remote has no new commits.");
+                } else if base == head {
+                    repo.git(&["merge", "--ff-only", "FETCH_HEAD"])?;
+                    report.fast_forwarded = true;
+                } else {
+                    repo.merge(&base, &head, &remote, resolver)?;
+                    report.merged = true;
+                }
+            }
+        }
+    }
+
+    if repo.head()?.is_some() {
+        let refspec = format!("HEAD:refs/heads/{}", config.branch);
+        repo.git(&["push", REMOTE_NAME, &refspec])?;
+        report.pushed = true;
+    }
+    Ok(report)
+}
+
+/// The git repository holding the data file.
+struct Repo {
+    dir: PathBuf,
+    /// The data file's name inside `dir`.
+    file: String,
+}
+
+impl Repo {
+    /// Opens the repository in the data file's directory, creating it if needed.
+    fn open(tasks_path: &Path) -> Result<Self> {
+        let dir = match tasks_path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            Some(dir) => dir.to_path_buf(),
+            None => PathBuf::from("."),
+        };
+        let dir = fs::canonicalize(&dir)?;
+        let file = tasks_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| AppError::Sync(format!("{} is not a file", tasks_path.display())))?;
+        let repo = Repo { dir, file };
+
+        match repo.git(&["rev-parse", "--show-toplevel"]) {
+            Ok(top) if fs::canonicalize(&top)? == repo.dir => {}
+            Ok(top) => {
+                return Err(AppError::Sync(format!(
+                    "{} is inside the git repository {}; move the data file to a directory of its own",
+                    repo.dir.display(),
+                    top
+                )));
+            }
+            Err(_) => {
+                repo.git(&["init", "--quiet"])?;
+                info!("Initialized a git repository in {}.", repo.dir.display());
+            }
+        }
+        Ok(repo)
+    }
+
+    /// Runs git in the repository and returns its trimmed output.
+    fn git(&self, args: &[&str]) -> Result<String> {
+        self.git_with_date(args, None)
+    }
+
+    fn git_with_date(&self, args: &[&str], date: Option<DateTime<Utc>>) -> Result<String> {
+        debug!("Running git {}", args.join(" "));
+        let mut command = Command::new("git");
+        command.arg("-C").arg(&self.dir).args(args);
+        if let Some(date) = date {
+            let date = date.to_rfc3339();
+            command
+                .env("GIT_AUTHOR_DATE", &date)
+                .env("GIT_COMMITTER_DATE", &date);
+        }
+        let output = command
+            .output()
+            .map_err(|e| AppError::Sync(format!("could not run git: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Sync(format!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Returns the current commit, or `None` before the first one.
+    fn head(&self) -> Result<Option<String>> {
+        Ok(self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).ok())
+    }
+
+    /// Points the managed remote at `url`.
+    fn set_remote(&self, url: &str) -> Result<()> {
+        match self.git(&["remote", "get-url", REMOTE_NAME]) {
+            Ok(current) if current == url => Ok(()),
+            Ok(_) => self.git(&["remote", "set-url", REMOTE_NAME, url]).map(drop),
+            Err(_) => self.git(&["remote", "add", REMOTE_NAME, url]).map(drop),
+        }
+    }
+
+    /// Commits the data file if it changed, dated with its modification time.
+    fn commit_data_file(&self) -> Result<bool> {
+        let path = self.dir.join(&self.file);
+        if !path.exists() {
+            return Ok(false);
+        }
+        self.git(&["add", "--", &self.file])?;
+        if self
+            .git(&["diff", "--cached", "--quiet", "--", &self.file])
+            .is_ok()
+            && self.head()?.is_some()
+        {
+            return Ok(false);
+        }
+        let modified = fs::metadata(&path)?.modified()?;
+        let message = format!("Update tasks ({})", clock::now().format("%Y-%m-%d %H:%M"));
+        self.git_with_date(
+            &["commit", "--quiet", "-m", &message, "--", &self.file],
+            Some(modified.into()),
+        )?;
+        Ok(true)
+    }
+
+    /// Loads the task list as of commit `rev`.
+    fn tasks_at(&self, rev: &str) -> Result<Vec<Task>> {
+        let spec = format!("{}:{}", rev, self.file);
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(["show", &spec])
+            .output()
+            .map_err(|e| AppError::Sync(format!("could not run git: {}", e)))?;
+        if !output.status.success() {
+            // The file didn't exist yet at that commit.
+            return Ok(Vec::new());
+        }
+        storage::parse_tasks(&encryption::decode_bytes(output.stdout)?)
+    }
+
+    /// Merges commit `theirs` into `ours`, merging the task lists by UUID.
+    fn merge(
+        &self,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+        resolver: Option<&mut dyn ConflictResolver>,
+    ) -> Result<()> {
+        let commit_time = |rev: &str| -> Result<i64> {
+            self.git(&["log", "-1", "--format=%ct", rev])?
+                .parse()
+                .map_err(|_| AppError::Sync(format!("no commit time for {}", rev)))
+        };
+        let mut newest = NewestWins {
+            ours_newer: commit_time(ours)? >= commit_time(theirs)?,
+        };
+        let merged = merge_tasks(
+            &self.tasks_at(base)?,
+            &self.tasks_at(ours)?,
+            &self.tasks_at(theirs)?,
+            resolver.unwrap_or(&mut newest),
+        )?;
+
+        // Conflicts in the data file are expected; it is overwritten below.
+        let _ = self.git(&["merge", "--no-commit", "--no-ff", theirs]);
+        storage::save_tasks_to(&merged, &self.dir.join(&self.file))?;
+        self.git(&["add", "--", &self.file])?;
+        if !self
+            .git(&["diff", "--name-only", "--diff-filter=U"])?
+            .is_empty()
+        {
+            let _ = self.git(&["merge", "--abort"]);
+            return Err(AppError::Sync(
+                "files other than the data file conflict; resolve the merge by hand".to_string(),
+            ));
+        }
+        self.git(&[
+            "commit",
+            "--quiet",
+            "-m",
+            "Merge tasks from another machine",
+        ])?;
+        info!("Merged {} tasks.", merged.len());
+        Ok(())
+    }
+}
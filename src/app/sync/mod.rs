@@ -0,0 +1,300 @@
+//! Synchronization of the task list with other machines and services.
+//!
+//! [`sync_tasks`] syncs the data file through a git repository,
+//! [`sync_caldav`] with a CalDAV task list, and [`sync_remote_storage`] with
+//! a copy kept on S3-compatible or WebDAV storage. Both reconcile concurrent edits
+//! with [`merge_tasks`], which matches tasks by UUID. Fields changed on both
+//! sides are settled by a [`ConflictResolver`]: [`NewestWins`] by default, or
+//! one asking the user with `sync --interactive`.
+//!
+//! ```toml
+//! [sync]
+//! remote = "git@example.com:me/tasks.git"
+//! branch = "main"
+//!
+//! [sync.caldav]
+//! url = "https://cloud.example.com/remote.php/dav/calendars/me/tasks/"
+//! username = "me"
+//! password_command = "secret-tool lookup service tm-caldav"
+//!
+//! [sync.storage]
+//! kind = "s3"
+//! url = "https://my-bucket.s3.eu-central-1.amazonaws.com/tasks.toml"
+//! region = "eu-central-1"
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "native")]
+use std::process::Command;
+use uuid::Uuid;
+
+use crate::app::{AppError, Result, Task};
+
+#[cfg(feature = "native")]
+mod caldav;
+mod git;
+mod remote_storage;
+
+#[cfg(feature = "native")]
+pub use caldav::*;
+pub use git::*;
+pub use remote_storage::*;
+
+/// Settings for the `sync` command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// The URL of the remote repository; unset means local commits only.
+    pub remote: Option<String>,
+    /// The branch to push to and pull from.
+    pub branch: String,
+    /// The CalDAV task list used by `sync --caldav`.
+    pub caldav: Option<CaldavConfig>,
+    /// Remote storage the data file is kept on, instead of a git remote.
+    pub storage: Option<RemoteStorageConfig>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig {
+            remote: None,
+            branch: "main".to_string(),
+            caldav: None,
+            storage: None,
+        }
+    }
+}
+
+/// Where the CalDAV task list lives and how to log in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaldavConfig {
+    /// The URL of the task list (calendar collection).
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// A command printing the password, e.g. to read it from the system keyring
+    /// with `secret-tool`. Used when `password` is unset.
+    pub password_command: Option<String>,
+}
+
+/// Runs `command` with the shell and returns the first line it prints, for
+/// settings like `password_command` that read a secret from the keyring.
+#[cfg(feature = "native")]
+pub(crate) fn run_password_command(command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .map_err(|e| AppError::Sync(format!("could not run password_command: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::Sync(format!(
+            "password_command failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or("").to_string())
+}
+
+/// A field both sides changed to different values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub field: String,
+    /// The value both sides started from; `None` if the field was unset.
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// A task both sides edited in conflicting ways.
+#[derive(Debug)]
+pub struct TaskConflict<'a> {
+    pub ours: &'a Task,
+    pub theirs: &'a Task,
+    pub fields: Vec<FieldConflict>,
+}
+
+impl TaskConflict<'_> {
+    /// Returns `true` if `value` can be stored in `field` of the task.
+    pub fn accepts(&self, field: &str, value: &Value) -> bool {
+        let Ok(Value::Object(mut map)) = serde_json::to_value(self.ours) else {
+            return false;
+        };
+        map.insert(field.to_string(), value.clone());
+        serde_json::from_value::<Task>(Value::Object(map)).is_ok()
+    }
+}
+
+/// How a conflicting field is settled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// A value entered by hand.
+    Value(Value),
+}
+
+/// Decides conflicting fields during a merge.
+pub trait ConflictResolver {
+    /// Settles each of `conflict.fields`, returning one resolution per field
+    /// in the same order.
+    fn resolve(&mut self, conflict: &TaskConflict) -> Result<Vec<Resolution>>;
+}
+
+/// Settles every conflict in favor of the side edited most recently.
+#[derive(Debug, Clone, Copy)]
+pub struct NewestWins {
+    pub ours_newer: bool,
+}
+
+impl ConflictResolver for NewestWins {
+    fn resolve(&mut self, conflict: &TaskConflict) -> Result<Vec<Resolution>> {
+        let pick = if self.ours_newer {
+            Resolution::Ours
+        } else {
+            Resolution::Theirs
+        };
+        Ok(vec![pick; conflict.fields.len()])
+    }
+}
+
+/// Refuses to settle conflicts, failing the merge instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RejectConflicts;
+
+impl ConflictResolver for RejectConflicts {
+    fn resolve(&mut self, conflict: &TaskConflict) -> Result<Vec<Resolution>> {
+        let fields: Vec<&str> = conflict.fields.iter().map(|f| f.field.as_str()).collect();
+        Err(AppError::Conflict(format!(
+            "task {} was also changed elsewhere ({})",
+            conflict.ours.id,
+            fields.join(", ")
+        )))
+    }
+}
+
+/// Merges two versions of a task list that both descend from `base`.
+///
+/// Tasks are matched by UUID. A task deleted on one side stays deleted
+/// unless the other side changed it. Fields changed on both sides are settled
+/// by `resolver`. Tasks only in `theirs` whose ID is taken get the next free
+/// ID.
+pub fn merge_tasks(
+    base: &[Task],
+    ours: &[Task],
+    theirs: &[Task],
+    resolver: &mut dyn ConflictResolver,
+) -> Result<Vec<Task>> {
+    let base: HashMap<Uuid, &Task> = base.iter().map(|t| (t.uuid, t)).collect();
+    let their_tasks: HashMap<Uuid, &Task> = theirs.iter().map(|t| (t.uuid, t)).collect();
+    let our_uuids: HashSet<Uuid> = ours.iter().map(|t| t.uuid).collect();
+
+    let mut merged = Vec::new();
+    for task in ours {
+        match (base.get(&task.uuid), their_tasks.get(&task.uuid)) {
+            (Some(original), Some(other)) => {
+                merged.push(merge_task(original, task, other, resolver)?);
+            }
+            (None, Some(other)) => merged.push(merge_task(task, task, other, resolver)?),
+            // Deleted on their side; keep it only if we changed it since.
+            (Some(original), None) if same(original, task)? => {}
+            _ => merged.push(task.clone()),
+        }
+    }
+
+    let mut taken: HashSet<u32> = merged.iter().map(|t| t.id).collect();
+    let mut next_id = taken.iter().max().map_or(1, |id| id + 1);
+    for task in theirs.iter().filter(|t| !our_uuids.contains(&t.uuid)) {
+        // Deleted on our side; keep it only if they changed it since.
+        if let Some(original) = base.get(&task.uuid)
+            && same(original, task)?
+        {
+            continue;
+        }
+        let mut task = task.clone();
+        if !taken.insert(task.id) {
+            task.id = next_id;
+            taken.insert(next_id);
+        }
+        next_id = next_id.max(task.id + 1);
+        merged.push(task);
+    }
+    Ok(merged)
+}
+
+fn same(a: &Task, b: &Task) -> Result<bool> {
+    Ok(serde_json::to_value(a)? == serde_json::to_value(b)?)
+}
+
+fn merge_task(
+    base: &Task,
+    ours: &Task,
+    theirs: &Task,
+    resolver: &mut dyn ConflictResolver,
+) -> Result<Task> {
+    let as_map = |task: &Task| -> Result<Map<String, Value>> {
+        match serde_json::to_value(task)? {
+            Value::Object(map) => Ok(map),
+            _ => Ok(Map::new()),
+        }
+    };
+    let (base, ours_map, theirs_map) = (as_map(base)?, as_map(ours)?, as_map(theirs)?);
+
+    // When both sides changed the task, the later change is the last one.
+    let at = |map: &Map<String, Value>| map.get("updated_at").and_then(Value::as_i64);
+    let ours_later = at(&ours_map) >= at(&theirs_map);
+
+    let mut names: Vec<&String> = ours_map.keys().collect();
+    names.extend(theirs_map.keys().filter(|k| !ours_map.contains_key(*k)));
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    for name in names {
+        let original = base.get(name);
+        let (mine, other) = (ours_map.get(name), theirs_map.get(name));
+        let value = if mine == other || other == original {
+            mine
+        } else if mine == original {
+            other
+        } else if name == "updated_at" || name == "updated_by" {
+            if ours_later { mine } else { other }
+        } else {
+            conflicts.push(FieldConflict {
+                field: name.clone(),
+                base: original.cloned(),
+                ours: mine.cloned(),
+                theirs: other.cloned(),
+            });
+            continue;
+        };
+        if let Some(value) = value {
+            merged.insert(name.clone(), value.clone());
+        }
+    }
+
+    if !conflicts.is_empty() {
+        let conflict = TaskConflict {
+            ours,
+            theirs,
+            fields: conflicts,
+        };
+        let resolutions = resolver.resolve(&conflict)?;
+        for (field, resolution) in conflict.fields.into_iter().zip(resolutions) {
+            let value = match resolution {
+                Resolution::Ours => field.ours,
+                Resolution::Theirs => field.theirs,
+                Resolution::Value(value) => Some(value),
+            };
+            if let Some(value) = value {
+                merged.insert(field.field, value);
+            }
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|e| {
+        AppError::Sync(format!(
+            "the merged version of task {} is invalid: {}",
+            ours.id, e
+        ))
+    })
+}
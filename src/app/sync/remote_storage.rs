@@ -0,0 +1,522 @@
+//! Keeps the data file on S3-compatible or WebDAV storage.
+//!
+//! With `[sync.storage]` in the config file, the local data file is a cached
+//! copy of one kept on the server. Each command first asks the server for a
+//! newer version, sending the ETag of the copy it has in `If-None-Match`, and
+//! uploads the file again after changing it. Uploads carry `If-Match`, so a
+//! version someone else uploaded meanwhile is never overwritten: the upload
+//! fails and `sync` merges both versions by UUID.
+//!
+//! The ETag and the task list as of the last exchange with the server are
+//! kept in a state file next to the data file. That copy is the common
+//! ancestor [`merge_tasks`] needs, and tells whether local changes are still
+//! waiting to be uploaded, in which case the server's version isn't fetched
+//! over them.
+//!
+//! The file is stored on the server as it is stored locally, so an encrypted
+//! data file stays encrypted there. Talking to the server is only available
+//! when the crate is built with the `remote` feature.
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    ConflictResolver, NewestWins, Result, Task, TaskDiff, encryption, merge_tasks, storage,
+};
+
+/// The environment variables S3 credentials are read from before the config.
+pub const ACCESS_KEY_ENV_VAR: &str = "AWS_ACCESS_KEY_ID";
+pub const SECRET_KEY_ENV_VAR: &str = "AWS_SECRET_ACCESS_KEY";
+
+/// The kind of server the data file is kept on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteStorageKind {
+    /// An S3-compatible object store, such as AWS S3, MinIO, or R2.
+    #[default]
+    S3,
+    /// A WebDAV server, such as Nextcloud or Apache with mod_dav.
+    Webdav,
+}
+
+/// Where the data file is kept and how to log in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteStorageConfig {
+    pub kind: RemoteStorageKind,
+    /// The URL of the file itself, e.g.
+    /// `https://my-bucket.s3.eu-central-1.amazonaws.com/tasks.toml`.
+    pub url: String,
+    /// The S3 region the bucket is in; defaults to `us-east-1`.
+    pub region: Option<String>,
+    /// The S3 access key; `AWS_ACCESS_KEY_ID` takes precedence.
+    pub access_key_id: Option<String>,
+    /// The S3 secret key; `AWS_SECRET_ACCESS_KEY` takes precedence.
+    pub secret_access_key: Option<String>,
+    /// The WebDAV user name.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// A command printing the WebDAV password, used when `password` is unset.
+    pub password_command: Option<String>,
+}
+
+/// What the server had to offer before a command ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullOutcome {
+    /// A newer version was fetched into the data file.
+    Fetched,
+    /// The local copy is the latest.
+    Unchanged,
+    /// Nothing is stored on the server yet.
+    Missing,
+    /// Local changes haven't been uploaded yet, so nothing was fetched.
+    Unpushed,
+}
+
+/// What `sync` did with remote storage.
+#[derive(Debug, Default, Clone)]
+pub struct RemoteStorageReport {
+    /// Tasks added, changed, or removed locally.
+    pub pulled: usize,
+    /// The data file was uploaded.
+    pub pushed: bool,
+}
+
+/// The server's version as of the last exchange with it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StorageState {
+    etag: Option<String>,
+    #[serde(default)]
+    tasks: Vec<Task>,
+}
+
+/// Returns where the remote storage state of the data file at `tasks_path`
+/// is kept.
+pub fn remote_storage_state_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.storage.toml", stem))
+}
+
+fn load_state(tasks_path: &Path) -> Result<StorageState> {
+    let path = remote_storage_state_path(tasks_path);
+    if !path.exists() {
+        return Ok(StorageState::default());
+    }
+    Ok(toml::from_str(&encryption::read_file(&path)?)?)
+}
+
+fn save_state(state: &StorageState, tasks_path: &Path) -> Result<()> {
+    encryption::write_file(
+        &remote_storage_state_path(tasks_path),
+        &toml::to_string(state)?,
+        encryption::should_encrypt(tasks_path),
+    )
+}
+
+fn same_tasks(a: &[Task], b: &[Task]) -> Result<bool> {
+    Ok(storage::serialize_tasks(a)? == storage::serialize_tasks(b)?)
+}
+
+/// A version of the data file downloaded from the server.
+#[derive(Debug)]
+struct Download {
+    contents: Vec<u8>,
+    etag: Option<String>,
+    modified: Option<DateTime<Utc>>,
+}
+
+/// The server's answer to a download.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "remote"), allow(dead_code))]
+enum Fetched {
+    NotModified,
+    Missing,
+    Body(Download),
+}
+
+/// Fetches the server's version into the data file at `tasks_path` if it
+/// changed since the last exchange and there are no local changes waiting
+/// to be uploaded.
+pub fn pull_remote_storage(tasks_path: &Path, config: &RemoteStorageConfig) -> Result<PullOutcome> {
+    let mut state = load_state(tasks_path)?;
+    if tasks_path.exists() && !same_tasks(&storage::load_tasks_from(tasks_path)?, &state.tasks)? {
+        debug!(
+            "Local changes to {} are not uploaded yet.",
+            tasks_path.display()
+        );
+        return Ok(PullOutcome::Unpushed);
+    }
+    let etag = state.etag.as_deref().filter(|_| tasks_path.exists());
+    let download = match client::get(config, etag)? {
+        Fetched::NotModified => return Ok(PullOutcome::Unchanged),
+        Fetched::Missing => return Ok(PullOutcome::Missing),
+        Fetched::Body(download) => download,
+    };
+    if let Some(dir) = tasks_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(tasks_path, &download.contents)?;
+    state.tasks = storage::load_tasks_from(tasks_path)?;
+    state.etag = download.etag;
+    save_state(&state, tasks_path)?;
+    info!("Fetched {} into {}.", config.url, tasks_path.display());
+    Ok(PullOutcome::Fetched)
+}
+
+/// Uploads the data file at `tasks_path` if it changed since the last
+/// exchange with the server.
+///
+/// # Returns
+///
+/// Whether the file was uploaded, or an `AppError::Conflict` if someone else
+/// uploaded a version meanwhile.
+pub fn push_remote_storage(tasks_path: &Path, config: &RemoteStorageConfig) -> Result<bool> {
+    let mut state = load_state(tasks_path)?;
+    let tasks = storage::load_tasks_from(tasks_path)?;
+    if state.etag.is_some() && same_tasks(&tasks, &state.tasks)? {
+        return Ok(false);
+    }
+    state.etag = client::put(config, &fs::read(tasks_path)?, state.etag.as_deref())?;
+    state.tasks = tasks;
+    save_state(&state, tasks_path)?;
+    info!("Uploaded {} to {}.", tasks_path.display(), config.url);
+    Ok(true)
+}
+
+/// Fetches the server's version whatever the local copy is, merges it with
+/// local changes by UUID, and uploads the result if it differs.
+///
+/// A field changed on both sides is settled by `resolver`. Without one it
+/// keeps the local value if the data file was modified after the server's
+/// version.
+pub fn sync_remote_storage(
+    tasks_path: &Path,
+    config: &RemoteStorageConfig,
+    resolver: Option<&mut dyn ConflictResolver>,
+) -> Result<RemoteStorageReport> {
+    let mut state = load_state(tasks_path)?;
+    let tasks = storage::load_tasks_from(tasks_path)?;
+    let mut report = RemoteStorageReport::default();
+
+    let (merged, etag) = match client::get(config, None)? {
+        Fetched::Body(download) => {
+            let theirs = storage::parse_tasks(&encryption::decode_bytes(download.contents)?)?;
+            let local_modified: Option<DateTime<Utc>> =
+                storage::modified_time(tasks_path).map(Into::into);
+            let mut newest = NewestWins {
+                ours_newer: match (local_modified, download.modified) {
+                    (Some(local), Some(remote)) => local >= remote,
+                    _ => true,
+                },
+            };
+            let merged = merge_tasks(
+                &state.tasks,
+                &tasks,
+                &theirs,
+                resolver.unwrap_or(&mut newest),
+            )?;
+            report.pushed = !same_tasks(&merged, &theirs)?;
+            (merged, download.etag)
+        }
+        // Nothing was uploaded yet, or the file was removed from the server.
+        Fetched::Missing | Fetched::NotModified => {
+            report.pushed = tasks_path.exists();
+            (tasks.clone(), None)
+        }
+    };
+
+    let diff = TaskDiff::between(&tasks, &merged)?;
+    report.pulled = diff.added.len() + diff.removed.len() + diff.changed.len();
+    if report.pulled > 0 {
+        storage::save_tasks_to(&merged, tasks_path)?;
+    }
+    state.etag = if report.pushed {
+        client::put(config, &fs::read(tasks_path)?, etag.as_deref())?
+    } else {
+        etag
+    };
+    state.tasks = merged;
+    save_state(&state, tasks_path)?;
+    info!(
+        "Remote storage sync: {} pulled, {}.",
+        report.pulled,
+        if report.pushed {
+            "uploaded"
+        } else {
+            "nothing to upload"
+        }
+    );
+    Ok(report)
+}
+
+#[cfg(feature = "remote")]
+mod client {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use chrono::DateTime;
+    use log::debug;
+    use sha2::{Digest, Sha256};
+    use std::env;
+    use ureq::http::{Request, Response};
+    use ureq::{Agent, Body};
+
+    use super::{
+        ACCESS_KEY_ENV_VAR, Download, Fetched, RemoteStorageConfig, RemoteStorageKind,
+        SECRET_KEY_ENV_VAR,
+    };
+    use crate::app::{AppError, Result, clock, run_password_command};
+
+    /// The largest data file downloaded, in bytes.
+    const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+    /// The S3 region used when the config names none.
+    const DEFAULT_REGION: &str = "us-east-1";
+
+    /// Downloads the file, or learns that the copy with `etag` is current.
+    pub(super) fn get(config: &RemoteStorageConfig, etag: Option<&str>) -> Result<Fetched> {
+        let request = Request::builder().method("GET").uri(&config.url);
+        let request = match etag {
+            Some(etag) => request.header("If-None-Match", etag),
+            None => request,
+        };
+        let mut response = send(config, request, Vec::new())?;
+        match response.status().as_u16() {
+            304 => return Ok(Fetched::NotModified),
+            404 => return Ok(Fetched::Missing),
+            200..=299 => {}
+            status => return Err(status_error(config, status)),
+        }
+        let modified = header(&response, "Last-Modified")
+            .and_then(|value| DateTime::parse_from_rfc2822(&value).ok())
+            .map(Into::into);
+        let etag = header(&response, "ETag");
+        let contents = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_DOWNLOAD_BYTES)
+            .read_to_vec()
+            .map_err(|e| AppError::Sync(format!("{}: {}", config.url, e)))?;
+        Ok(Fetched::Body(Download {
+            contents,
+            etag,
+            modified,
+        }))
+    }
+
+    /// Uploads `contents`, returning the new ETag.
+    ///
+    /// With `etag`, the upload only succeeds if the server's version is
+    /// still that one; without it, only if there is none yet.
+    pub(super) fn put(
+        config: &RemoteStorageConfig,
+        contents: &[u8],
+        etag: Option<&str>,
+    ) -> Result<Option<String>> {
+        let request = Request::builder().method("PUT").uri(&config.url);
+        let request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        let response = send(config, request, contents.to_vec())?;
+        match response.status().as_u16() {
+            200..=299 => {}
+            412 => {
+                return Err(AppError::Conflict(format!(
+                    "{} was changed by someone else since it was fetched",
+                    config.url
+                )));
+            }
+            status => return Err(status_error(config, status)),
+        }
+        if let Some(etag) = header(&response, "ETag") {
+            return Ok(Some(etag));
+        }
+        // Some WebDAV servers don't say; ask for it.
+        debug!("No ETag in the upload response; asking with HEAD.");
+        let response = send(
+            config,
+            Request::builder().method("HEAD").uri(&config.url),
+            Vec::new(),
+        )?;
+        Ok(header(&response, "ETag"))
+    }
+
+    fn header(response: &Response<Body>, name: &str) -> Option<String> {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+
+    fn status_error(config: &RemoteStorageConfig, status: u16) -> AppError {
+        AppError::Sync(format!("{} returned HTTP {}", config.url, status))
+    }
+
+    /// Signs `request` for the configured server and sends it.
+    fn send(
+        config: &RemoteStorageConfig,
+        request: ureq::http::request::Builder,
+        body: Vec<u8>,
+    ) -> Result<Response<Body>> {
+        if config.url.is_empty() {
+            return Err(AppError::Sync(
+                "set `url` under [sync.storage] in the config file".to_string(),
+            ));
+        }
+        let request = match config.kind {
+            RemoteStorageKind::S3 => sign_s3(config, request, &body)?,
+            RemoteStorageKind::Webdav => match &config.username {
+                Some(user) => {
+                    let password = match (&config.password, &config.password_command) {
+                        (Some(password), _) => password.clone(),
+                        (None, Some(command)) => run_password_command(command)?,
+                        (None, None) => String::new(),
+                    };
+                    request.header(
+                        "Authorization",
+                        format!("Basic {}", BASE64.encode(format!("{}:{}", user, password))),
+                    )
+                }
+                None => request,
+            },
+        };
+        let request: Request<Vec<u8>> = request
+            .body(body)
+            .map_err(|e| AppError::Sync(e.to_string()))?;
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .new_agent();
+        agent
+            .run(request)
+            .map_err(|e| AppError::Sync(format!("{}: {}", config.url, e)))
+    }
+
+    /// Adds the headers of an AWS Signature Version 4 to `request`.
+    fn sign_s3(
+        config: &RemoteStorageConfig,
+        request: ureq::http::request::Builder,
+        body: &[u8],
+    ) -> Result<ureq::http::request::Builder> {
+        let secret = |var: &str, configured: &Option<String>, key: &str| {
+            env::var(var)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .or_else(|| configured.clone())
+                .ok_or_else(|| {
+                    AppError::Sync(format!(
+                        "no S3 credentials; set {} or `{}` under [sync.storage] in the config file",
+                        var, key
+                    ))
+                })
+        };
+        let access_key = secret(ACCESS_KEY_ENV_VAR, &config.access_key_id, "access_key_id")?;
+        let secret_key = secret(
+            SECRET_KEY_ENV_VAR,
+            &config.secret_access_key,
+            "secret_access_key",
+        )?;
+        let region = config.region.as_deref().unwrap_or(DEFAULT_REGION);
+        let method = request
+            .method_ref()
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+
+        let rest = config
+            .url
+            .split_once("://")
+            .map_or(config.url.as_str(), |(_, rest)| rest);
+        let (host, path_and_query) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+        let path = if path.is_empty() { "/" } else { path };
+
+        let now = clock::now();
+        let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(body));
+        let scope = format!("{}/{}/s3/aws4_request", date, region);
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method,
+            path,
+            query,
+            host,
+            payload_hash,
+            stamp,
+            "host;x-amz-content-sha256;x-amz-date",
+            payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            stamp,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let mut key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+        for part in [region, "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+        Ok(request
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", stamp)
+            .header(
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                    access_key, scope, signature
+                ),
+            ))
+    }
+
+    /// HMAC-SHA256 as in RFC 2104.
+    fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut block = [0u8; 64];
+        if key.len() > block.len() {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = Sha256::new();
+        inner.update(block.map(|b| b ^ 0x36));
+        inner.update(data);
+        let mut outer = Sha256::new();
+        outer.update(block.map(|b| b ^ 0x5c));
+        outer.update(inner.finalize());
+        outer.finalize().to_vec()
+    }
+}
+
+#[cfg(not(feature = "remote"))]
+mod client {
+    use super::{Fetched, RemoteStorageConfig};
+    use crate::app::{AppError, Result};
+
+    fn unavailable() -> AppError {
+        AppError::Sync(
+            "this build does not include remote storage; rebuild with `--features remote`"
+                .to_string(),
+        )
+    }
+
+    pub(super) fn get(_config: &RemoteStorageConfig, _etag: Option<&str>) -> Result<Fetched> {
+        Err(unavailable())
+    }
+
+    pub(super) fn put(
+        _config: &RemoteStorageConfig,
+        _contents: &[u8],
+        _etag: Option<&str>,
+    ) -> Result<Option<String>> {
+        Err(unavailable())
+    }
+}
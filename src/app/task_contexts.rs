@@ -0,0 +1,54 @@
+//! GTD contexts: where a task can be done, such as `@phone` or `@office`.
+//!
+//! Tags say what a task is about; contexts say what it takes to do it, so
+//! `list @office` pulls up only what can be done at the office. A task gets
+//! them from `add --context office` or `@office` in its description. They
+//! have nothing to do with the saved queries of the `context` command.
+
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+
+use crate::app::Task;
+
+/// Checks that `name` can name a context, dropping a leading `@`.
+pub fn parse_task_context(name: &str) -> Result<String, String> {
+    let name = name.strip_prefix('@').unwrap_or(name);
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(format!(
+            "'{}' is not a valid context; use a single word such as 'phone'",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// How many pending tasks can be done in a context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextSummary {
+    pub name: String,
+    pub pending: usize,
+    /// How many of the pending tasks are overdue.
+    pub overdue: usize,
+}
+
+/// Summarizes the pending tasks per context, sorted by context name.
+pub fn context_summaries(tasks: &[Task], now: NaiveDateTime) -> Vec<ContextSummary> {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for task in tasks.iter().filter(|t| t.is_pending()) {
+        for context in &task.contexts {
+            let entry = counts.entry(context).or_default();
+            entry.0 += 1;
+            if task.is_overdue(now) {
+                entry.1 += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(name, (pending, overdue))| ContextSummary {
+            name: name.to_string(),
+            pending,
+            overdue,
+        })
+        .collect()
+}
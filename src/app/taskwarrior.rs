@@ -0,0 +1,196 @@
+//! Importing tasks exported by Taskwarrior with `task export`.
+//!
+//! Taskwarrior's status, description, project, tags, priority, due date and
+//! time, `wait` and `until` dates, annotations, and UUIDs all have a counterpart here;
+//! `wait` becomes the start date, `until` the expiration date, and
+//! annotations become notes. Recurrence is kept if the rule is one
+//! [`Recurrence`] understands. Anything else, such as `scheduled`, `depends`,
+//! or user-defined attributes, is dropped and listed in the [`ImportReport`].
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::app::{
+    AppError, ImportReport, Note, Priority, Recurrence, Result, Task, TaskStatus, clock,
+};
+
+/// Attributes that are mapped, or that only matter inside Taskwarrior.
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "id",
+    "uuid",
+    "description",
+    "status",
+    "entry",
+    "modified",
+    "end",
+    "due",
+    "until",
+    "wait",
+    "priority",
+    "project",
+    "tags",
+    "annotations",
+    "recur",
+    "urgency",
+    "mask",
+    "imask",
+    "parent",
+];
+
+#[derive(Deserialize)]
+struct Annotation {
+    entry: String,
+    description: String,
+}
+
+/// Parses the output of `task export`, either a JSON array or one JSON
+/// object per line as older versions write it.
+fn parse_export(json: &str) -> Result<Vec<Map<String, Value>>> {
+    let trimmed = json.trim_start();
+    if trimmed.starts_with('[') {
+        return Ok(serde_json::from_str(trimmed)?);
+    }
+    trimmed
+        .lines()
+        .map(|line| line.trim().trim_end_matches(','))
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str(line).map_err(AppError::Json))
+        .collect()
+}
+
+/// Parses a Taskwarrior timestamp such as `20251014T093000Z`.
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value.as_str()?, "%Y%m%dT%H%M%SZ").ok()?;
+    Some(naive.and_utc())
+}
+
+/// Reads a Taskwarrior timestamp as a date.
+///
+/// Dates entered in Taskwarrior are stored as local midnight in UTC, so the
+/// date in the configured time zone is the one that was meant.
+fn parse_date(value: &Value) -> Option<NaiveDate> {
+    parse_timestamp(value).map(clock::local_date)
+}
+
+/// Imports the tasks of a Taskwarrior export into `tasks`.
+///
+/// Tasks whose UUID is already known are updated in place; the others get
+/// new IDs. Recurrence templates are skipped, since each of their instances
+/// is exported as a task of its own.
+pub fn import_taskwarrior(tasks: &mut Vec<Task>, json: &str) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for item in parse_export(json)? {
+        let get = |name: &str| item.get(name).filter(|v| !v.is_null());
+        // Waiting tasks are pending ones with a `wait` date.
+        let status = get("status").and_then(Value::as_str).unwrap_or("pending");
+        if status == "recurring" {
+            *report
+                .dropped
+                .entry("recurrence template".to_string())
+                .or_default() += 1;
+            continue;
+        }
+        let Some(description) = get("description").and_then(Value::as_str) else {
+            return Err(AppError::InvalidArgument(
+                "A Taskwarrior task has no description.".to_string(),
+            ));
+        };
+
+        let uuid = get("uuid")
+            .and_then(Value::as_str)
+            .and_then(|u| Uuid::parse_str(u).ok());
+        let existing = uuid.and_then(|uuid| tasks.iter().position(|t| t.uuid == uuid));
+        let mut task = match existing {
+            Some(index) => tasks[index].clone(),
+            None => {
+                let mut task = Task::new(next_id, String::new(), None);
+                next_id += 1;
+                if let Some(uuid) = uuid {
+                    task.uuid = uuid;
+                }
+                task
+            }
+        };
+
+        task.description = description.to_string();
+        if let Some(entry) = get("entry").and_then(parse_timestamp) {
+            task.created_at = entry;
+        }
+        let due = get("due").and_then(parse_timestamp).map(clock::local);
+        task.due_date = due.map(|at| at.date());
+        task.due_time = due
+            .map(|at| at.time())
+            .filter(|time| *time != NaiveTime::MIN);
+        task.expires_on = get("until").and_then(parse_date);
+        task.start_date = get("wait").and_then(parse_date);
+        task.project = get("project").and_then(Value::as_str).map(str::to_string);
+        task.priority = match get("priority").and_then(Value::as_str) {
+            Some("H") => Some(Priority::High),
+            Some("M") => Some(Priority::Medium),
+            Some("L") => Some(Priority::Low),
+            _ => None,
+        };
+        task.tags = get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(annotations) = get("annotations") {
+            let annotations: Vec<Annotation> = serde_json::from_value(annotations.clone())?;
+            task.notes = annotations
+                .into_iter()
+                .map(|a| Note {
+                    created_at: parse_timestamp(&Value::String(a.entry)).unwrap_or(task.created_at),
+                    text: a.description,
+                })
+                .collect();
+        }
+        task.recurrence = match get("recur").and_then(Value::as_str) {
+            Some(rule) => match rule.parse::<Recurrence>() {
+                Ok(recurrence) => Some(recurrence),
+                Err(_) => {
+                    *report.dropped.entry(format!("recur:{}", rule)).or_default() += 1;
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let end = get("end").and_then(parse_timestamp);
+        task.status = match status {
+            "completed" => TaskStatus::Done,
+            "deleted" => TaskStatus::Cancelled,
+            _ => TaskStatus::Todo,
+        };
+        task.completed_at = if task.is_done() {
+            end.or(task.completed_at)
+        } else {
+            None
+        };
+
+        for name in item.keys() {
+            if !KNOWN_ATTRIBUTES.contains(&name.as_str()) {
+                *report.dropped.entry(name.clone()).or_default() += 1;
+            }
+        }
+
+        match existing {
+            Some(index) => {
+                tasks[index] = task;
+                report.updated += 1;
+            }
+            None => {
+                tasks.push(task);
+                report.added += 1;
+            }
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,118 @@
+//! Task templates, pre-filling `add --template <name>`.
+//!
+//! `template save sprint-bug 12` remembers the project, priority, and tags of
+//! task 12, and how many days after it was added it was due. Templates are
+//! kept in `templates.toml` next to the config file:
+//!
+//! ```toml
+//! [sprint-bug]
+//! project = "web"
+//! priority = "high"
+//! tags = ["bug", "sprint"]
+//! due_after_days = 3
+//! ```
+
+use chrono::Duration;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::app::{AddArgs, AppError, Priority, Result, Task, clock, config_file_path};
+
+/// The name of the file templates are kept in.
+const TEMPLATES_FILE_NAME: &str = "templates.toml";
+
+/// What a template fills in for a new task.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Template {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// How many days after it is added the task is due.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_after_days: Option<u32>,
+}
+
+impl Template {
+    /// Takes the fields of `task` worth repeating, with its due date relative
+    /// to the day it was added.
+    pub fn from_task(task: &Task) -> Self {
+        let added = clock::local_date(task.created_at);
+        Template {
+            project: task.project.clone(),
+            priority: task.priority,
+            tags: task.tags.clone(),
+            due_after_days: task
+                .due_date
+                .map(|due| (due - added).num_days().max(0) as u32),
+        }
+    }
+
+    /// Fills in what `args` leaves unset; tags are added to the given ones.
+    pub fn apply(&self, args: &mut AddArgs) -> Result<()> {
+        args.project = args.project.take().or_else(|| self.project.clone());
+        args.priority = args.priority.or(self.priority);
+        for tag in &self.tags {
+            if !args.tags.contains(tag) {
+                args.tags.push(tag.clone());
+            }
+        }
+        if let (None, Some(days)) = (&args.due, self.due_after_days) {
+            let due = clock::today() + Duration::days(days.into());
+            args.due = Some(due.to_string().parse().map_err(AppError::InvalidArgument)?);
+        }
+        Ok(())
+    }
+}
+
+/// Returns where templates are kept: next to the config file.
+pub fn templates_file_path() -> Option<PathBuf> {
+    config_file_path().map(|path| path.with_file_name(TEMPLATES_FILE_NAME))
+}
+
+/// Loads the templates by name; none if the file doesn't exist.
+pub fn load_templates() -> Result<BTreeMap<String, Template>> {
+    let Some(path) = templates_file_path().filter(|p| p.exists()) else {
+        return Ok(BTreeMap::new());
+    };
+    toml::from_str(&fs::read_to_string(&path)?)
+        .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))
+}
+
+/// Writes `templates` to the templates file, returning its path.
+pub fn save_templates(templates: &BTreeMap<String, Template>) -> Result<PathBuf> {
+    let path = templates_file_path()
+        .ok_or_else(|| AppError::Config("no config directory available".to_string()))?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, toml::to_string(templates)?)?;
+    info!("Saved {} templates to {}.", templates.len(), path.display());
+    Ok(path)
+}
+
+/// Returns the template `name`, or an error naming the ones there are.
+pub fn find_template(name: &str) -> Result<Template> {
+    let mut templates = load_templates()?;
+    templates.remove(name).ok_or_else(|| {
+        let known: Vec<&str> = templates.keys().map(String::as_str).collect();
+        AppError::InvalidArgument(if known.is_empty() {
+            format!(
+                "No template named '{}'. Save one with `task template save {} <id>`.",
+                name, name
+            )
+        } else {
+            format!(
+                "No template named '{}'; there are {}.",
+                name,
+                known.join(", ")
+            )
+        })
+    })
+}
@@ -0,0 +1,197 @@
+//! Helpers for testing code built on this crate; only available with the
+//! `test-support` feature.
+//!
+//! [`arb_task`] and [`arb_tasks`] are proptest strategies generating tasks,
+//! for checking that a task list survives being stored:
+//!
+//! ```ignore
+//! proptest! {
+//!     #[test]
+//!     fn tasks_survive_storage(tasks in arb_tasks(50)) {
+//!         assert_round_trips(&tasks);
+//!     }
+//! }
+//! ```
+//!
+//! [`render_table`] draws a table the same way on every machine, whatever
+//! the terminal, and [`assert_golden`] compares output with a file checked in
+//! next to the test. Setting `UPDATE_GOLDEN=1` writes the files instead, for
+//! when the output changed on purpose. Pin the clock with
+//! [`clock::set_now_override`] first when the output shows dates.
+//!
+//! [`clock::set_now_override`]: crate::app::clock::set_now_override
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use proptest::prelude::*;
+use std::env;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::app::{
+    BufferSink, Priority, RenderFormat, Renderer, Result, StorageFormat, Table, TableRenderer,
+    Task, TaskStatus, encode_tasks, parse_tasks,
+};
+
+/// The environment variable that makes [`assert_golden`] write the expected
+/// output instead of checking it.
+pub const UPDATE_GOLDEN_ENV_VAR: &str = "UPDATE_GOLDEN";
+
+/// The encodings a task list is checked to survive by [`assert_round_trips`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Toml,
+    Msgpack,
+    Json,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 3] = [Encoding::Toml, Encoding::Msgpack, Encoding::Json];
+}
+
+/// Generates dates up to ten years either side of 2026.
+pub fn arb_date() -> impl Strategy<Value = NaiveDate> {
+    let epoch = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    (-3650i64..3650).prop_map(move |days| epoch + chrono::Duration::days(days))
+}
+
+/// Generates a single task with an ID between 1 and 9999.
+///
+/// Descriptions, projects, and tags may hold any printable characters,
+/// including ones TOML has to escape.
+pub fn arb_task() -> impl Strategy<Value = Task> {
+    let status = prop::sample::select(TaskStatus::value_variants());
+    let priority = prop::option::of(prop::sample::select(Priority::value_variants()));
+    (
+        (1u32..10_000, any::<u128>(), "\\PC{1,40}", status, priority),
+        (
+            prop::option::of(arb_date()),
+            prop::option::of("[a-z][a-z0-9-]{0,11}"),
+            prop::collection::vec("[a-z][a-z0-9_]{0,9}", 0..4),
+            0i64..2_000_000_000,
+            prop::option::of(0u8..=100),
+        ),
+    )
+        .prop_map(
+            |(
+                (id, uuid, description, status, priority),
+                (due, project, tags, created, progress),
+            )| {
+                let mut task = Task::new(id, description, due);
+                task.uuid = Uuid::from_u128(uuid);
+                task.status = status;
+                task.priority = priority;
+                task.project = project;
+                task.tags = tags;
+                task.created_at = DateTime::<Utc>::from_timestamp(created, 0).unwrap();
+                task.progress = progress;
+                task
+            },
+        )
+}
+
+/// Generates a task list of up to `max_len` tasks, numbered from 1 like a
+/// data file.
+pub fn arb_tasks(max_len: usize) -> impl Strategy<Value = Vec<Task>> {
+    prop::collection::vec(arb_task(), 0..=max_len).prop_map(|mut tasks| {
+        for (i, task) in tasks.iter_mut().enumerate() {
+            task.id = i as u32 + 1;
+        }
+        tasks
+    })
+}
+
+/// Stores `tasks` in `encoding` and reads them back.
+pub fn round_trip(tasks: &[Task], encoding: Encoding) -> Result<Vec<Task>> {
+    match encoding {
+        Encoding::Toml => parse_tasks(&encode_tasks(tasks, StorageFormat::Toml)?),
+        Encoding::Msgpack => parse_tasks(&encode_tasks(tasks, StorageFormat::Msgpack)?),
+        Encoding::Json => Ok(serde_json::from_str(&serde_json::to_string(tasks)?)?),
+    }
+}
+
+/// Checks that `tasks` come back unchanged from every [`Encoding`].
+///
+/// # Panics
+///
+/// If an encoding fails or changes a task, naming the encoding and the
+/// first task that differs.
+pub fn assert_round_trips(tasks: &[Task]) {
+    for encoding in Encoding::ALL {
+        let back = round_trip(tasks, encoding)
+            .unwrap_or_else(|e| panic!("{:?} round trip failed: {}", encoding, e));
+        assert_eq!(
+            back.len(),
+            tasks.len(),
+            "{:?} round trip changed the number of tasks",
+            encoding
+        );
+        for (before, after) in tasks.iter().zip(&back) {
+            let before = serde_json::to_value(before).unwrap();
+            let after = serde_json::to_value(after).unwrap();
+            assert_eq!(after, before, "{:?} round trip changed a task", encoding);
+        }
+    }
+}
+
+/// Renders `table` in `format` without color, at the widths its columns
+/// give, so the result doesn't depend on the terminal.
+pub fn render_table(table: &Table, format: RenderFormat) -> String {
+    let mut out = BufferSink::default();
+    match format {
+        RenderFormat::Table => TableRenderer {
+            color: false,
+            width: None,
+        }
+        .table(&mut out, table),
+        other => other.renderer(false).table(&mut out, table),
+    }
+    out.contents()
+}
+
+/// Checks `actual` against the golden file at `path`, or writes it there if
+/// `UPDATE_GOLDEN` is set.
+///
+/// # Panics
+///
+/// If the output differs, showing the first line that does, or if the file
+/// doesn't exist.
+pub fn assert_golden(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    if env::var_os(UPDATE_GOLDEN_ENV_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(path, actual)
+            .unwrap_or_else(|e| panic!("could not write {}: {}", path.display(), e));
+        return;
+    }
+    if !path.exists() {
+        panic!(
+            "the golden file {} is missing; set {}=1 to write it",
+            path.display(),
+            UPDATE_GOLDEN_ENV_VAR
+        );
+    }
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("could not read {}: {}", path.display(), e));
+    if expected == actual {
+        return;
+    }
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let line = (0..expected_lines.len().max(actual_lines.len()))
+        .find(|&i| expected_lines.get(i) != actual_lines.get(i))
+        .unwrap_or(expected_lines.len());
+    let want = expected_lines.get(line).copied().unwrap_or("");
+    let got = actual_lines.get(line).copied().unwrap_or("");
+    panic!(
+        "output differs from {} at line {}:\n  expected: {}\n  actual:   {}\nSet {}=1 to update it.",
+        path.display(),
+        line + 1,
+        want,
+        got,
+        UPDATE_GOLDEN_ENV_VAR
+    );
+}
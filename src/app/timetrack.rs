@@ -0,0 +1,177 @@
+//! Time tracking with per-task start/stop timers.
+//!
+//! Each tracked stretch of work is a [`WorkInterval`] stored on its task. At
+//! most one timer runs at a time: starting a timer stops the running one.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{AppError, Result, Task, clock};
+use crate::tr;
+
+/// A stretch of time spent on a task; `end` is `None` while the timer runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkInterval {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub start: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl WorkInterval {
+    /// Returns the part of the interval that falls between `from` and `to`,
+    /// counting a running interval as lasting until `now`.
+    pub fn overlap(&self, from: DateTime<Utc>, to: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or(now).min(to);
+        (end - start).max(Duration::zero())
+    }
+}
+
+/// Returns the task whose timer is running, if any.
+pub fn running_task(tasks: &[Task]) -> Option<&Task> {
+    tasks
+        .iter()
+        .find(|t| t.intervals.last().is_some_and(|i| i.end.is_none()))
+}
+
+/// Starts a timer on task `id` at `now`, stopping any other running timer.
+///
+/// # Returns
+///
+/// The ID of the task whose timer was stopped, if one was running.
+pub fn start_timer(tasks: &mut [Task], id: u32, now: DateTime<Utc>) -> Result<Option<u32>> {
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::task_not_found(id, tasks.iter()))?;
+    if !task.is_pending() {
+        return Err(AppError::InvalidArgument(tr!("error-not-pending", id = id)));
+    }
+    if running_task(tasks).is_some_and(|t| t.id == id) {
+        return Err(AppError::InvalidArgument(tr!(
+            "error-timer-running",
+            id = id
+        )));
+    }
+
+    let stopped = stop_timer(tasks, now).map(|(stopped, _)| stopped);
+    if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+        task.intervals.push(WorkInterval {
+            start: now,
+            end: None,
+        });
+    }
+    Ok(stopped)
+}
+
+/// Stops the running timer at `now`.
+///
+/// # Returns
+///
+/// The task ID and the length of the stopped interval, or `None` if no
+/// timer was running.
+pub fn stop_timer(tasks: &mut [Task], now: DateTime<Utc>) -> Option<(u32, Duration)> {
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.intervals.last().is_some_and(|i| i.end.is_none()))?;
+    let interval = task.intervals.last_mut()?;
+    let end = now.max(interval.start);
+    interval.end = Some(end);
+    Some((task.id, end - interval.start))
+}
+
+/// Returns the total time tracked on `task`, including a running timer up to `now`.
+pub fn tracked_time(task: &Task, now: DateTime<Utc>) -> Duration {
+    task.intervals
+        .iter()
+        .map(|i| i.overlap(DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC, now))
+        .sum()
+}
+
+/// Formats a duration as `1h 05m`, or `12m` below an hour.
+pub fn format_elapsed(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Time tracked on one task, per day of a week.
+#[derive(Debug, Clone)]
+pub struct TimesheetRow {
+    pub task_id: u32,
+    pub description: String,
+    /// Monday through Sunday.
+    pub days: [Duration; 7],
+}
+
+impl TimesheetRow {
+    /// Returns the time tracked over the whole week.
+    pub fn total(&self) -> Duration {
+        self.days.iter().copied().sum()
+    }
+}
+
+/// Tracked time for the week starting on `monday`.
+#[derive(Debug, Clone)]
+pub struct Timesheet {
+    pub monday: NaiveDate,
+    pub rows: Vec<TimesheetRow>,
+}
+
+impl Timesheet {
+    /// Builds the timesheet for the week containing `date`, as seen at `now`.
+    ///
+    /// Days run from midnight to midnight in the configured time zone. Tasks without tracked time
+    /// that week are left out.
+    pub fn for_week(tasks: &[Task], date: NaiveDate, now: DateTime<Utc>) -> Self {
+        let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+        let day_start = |offset: i64| {
+            (monday + Duration::days(offset))
+                .and_hms_opt(0, 0, 0)
+                .map(clock::from_local)
+                .unwrap_or(now)
+        };
+
+        let rows = tasks
+            .iter()
+            .filter_map(|task| {
+                let mut days = [Duration::zero(); 7];
+                for (offset, day) in (0..).zip(days.iter_mut()) {
+                    let (from, to) = (day_start(offset), day_start(offset + 1));
+                    *day = task
+                        .intervals
+                        .iter()
+                        .map(|i| i.overlap(from, to, now))
+                        .sum();
+                }
+                let row = TimesheetRow {
+                    task_id: task.id,
+                    description: task.description.clone(),
+                    days,
+                };
+                (row.total() > Duration::zero()).then_some(row)
+            })
+            .collect();
+
+        Timesheet { monday, rows }
+    }
+
+    /// Returns the time tracked per day across all tasks.
+    pub fn day_totals(&self) -> [Duration; 7] {
+        let mut totals = [Duration::zero(); 7];
+        for row in &self.rows {
+            for (total, day) in totals.iter_mut().zip(row.days) {
+                *total += day;
+            }
+        }
+        totals
+    }
+}
@@ -0,0 +1,227 @@
+//! Two-way sync between the task store and a plain-text TODO file.
+//!
+//! The file uses Markdown checkboxes, one task per line:
+//!
+//! ```text
+//! # Groceries
+//! - [ ] buy milk <!-- tm:3 -->
+//! - [x] call the plumber <!-- tm:4 -->
+//! - [ ] a line typed in an editor
+//! ```
+//!
+//! Lines without a `<!-- tm:ID -->` marker become new tasks; checking or
+//! unchecking a box completes or reopens the task, and editing the text
+//! renames it. When writing back, markers are added, lines of removed tasks
+//! are dropped, and pending tasks missing from the file are appended. All
+//! other lines are kept as they are. Deleting a line does not delete its task.
+//!
+//! A copy of the last written contents is kept next to the file (see
+//! [`snapshot_path`]). Only lines that differ from that copy are applied, so
+//! changes made with other commands in the meantime aren't undone.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::app::{IdGenerator, Task};
+
+/// The prefix of the marker linking a line to a task.
+const MARKER_PREFIX: &str = "<!-- tm:";
+const MARKER_SUFFIX: &str = "-->";
+
+/// A line of a TODO file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TodoLine {
+    /// A checkbox item, optionally linked to a task.
+    Item {
+        /// The list marker and indentation, e.g. `"- "` or `"  * "`.
+        bullet: String,
+        checked: bool,
+        description: String,
+        id: Option<u32>,
+    },
+    /// Any other line, kept verbatim.
+    Text(String),
+}
+
+/// What a sync changed in the store.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TodoSyncReport {
+    pub added: Vec<u32>,
+    pub completed: Vec<u32>,
+    pub reopened: Vec<u32>,
+    pub renamed: Vec<u32>,
+}
+
+impl TodoSyncReport {
+    /// Returns `true` if the store was left unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.completed.is_empty()
+            && self.reopened.is_empty()
+            && self.renamed.is_empty()
+    }
+}
+
+/// Parses the lines of a TODO file.
+pub fn parse_todo_file(text: &str) -> Vec<TodoLine> {
+    text.lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> TodoLine {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+    else {
+        return TodoLine::Text(line.to_string());
+    };
+    let checked = match rest.get(..3) {
+        Some("[ ]") => false,
+        Some("[x]") | Some("[X]") => true,
+        _ => return TodoLine::Text(line.to_string()),
+    };
+
+    let mut description = rest[3..].trim().to_string();
+    let mut id = None;
+    if let Some(start) = description.rfind(MARKER_PREFIX)
+        && let Some(value) = description[start + MARKER_PREFIX.len()..]
+            .trim_end()
+            .strip_suffix(MARKER_SUFFIX)
+        && let Ok(parsed) = value.trim().parse()
+    {
+        id = Some(parsed);
+        description.truncate(start);
+        description = description.trim_end().to_string();
+    }
+    if description.is_empty() {
+        return TodoLine::Text(line.to_string());
+    }
+
+    TodoLine::Item {
+        bullet: format!("{}{}", indent, &trimmed[..2]),
+        checked,
+        description,
+        id,
+    }
+}
+
+/// Returns where the last written contents of the TODO file at `path` are kept.
+pub fn snapshot_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.sync", name))
+}
+
+/// Applies the file's lines to `tasks`, creating tasks for new lines and
+/// linking those lines to the new IDs.
+///
+/// Linked lines identical to their counterpart in `previous`, the lines as
+/// last written, are left alone.
+pub fn apply_todo_lines(
+    tasks: &mut Vec<Task>,
+    lines: &mut [TodoLine],
+    previous: &[TodoLine],
+    ids: &mut dyn IdGenerator,
+) -> TodoSyncReport {
+    let mut report = TodoSyncReport::default();
+    for line in lines.iter_mut() {
+        if id_of(line).is_some() && previous.contains(line) {
+            continue;
+        }
+        let TodoLine::Item {
+            checked,
+            description,
+            id,
+            ..
+        } = line
+        else {
+            continue;
+        };
+
+        match id.and_then(|id| tasks.iter_mut().find(|t| t.id == id)) {
+            Some(task) => {
+                if task.description != *description {
+                    task.description = description.clone();
+                    report.renamed.push(task.id);
+                }
+                if task.is_done() != *checked {
+                    task.mark_completion(*checked);
+                    if *checked {
+                        report.completed.push(task.id);
+                    } else {
+                        report.reopened.push(task.id);
+                    }
+                }
+            }
+            // The linked task was removed from the store; `render_todo_file` drops the line.
+            None if id.is_some() => {}
+            None => {
+                let new_id = ids.next_id(tasks);
+                let mut task = Task::new(new_id, description.clone(), None);
+                if *checked {
+                    task.mark_completion(true);
+                }
+                tasks.push(task);
+                *id = Some(new_id);
+                report.added.push(new_id);
+            }
+        }
+    }
+    report
+}
+
+/// Renders the file contents reflecting the current state of `tasks`.
+///
+/// Existing lines keep their position; pending tasks not yet in the file are
+/// appended at the end.
+pub fn render_todo_file(tasks: &[Task], lines: &[TodoLine]) -> String {
+    let mut out = String::new();
+    let mut listed = HashSet::new();
+    for line in lines {
+        match line {
+            TodoLine::Text(text) => {
+                out.push_str(text);
+                out.push('\n');
+            }
+            TodoLine::Item { bullet, id, .. } => {
+                let Some(task) = id.and_then(|id| tasks.iter().find(|t| t.id == id)) else {
+                    continue;
+                };
+                if task.is_cancelled() {
+                    continue;
+                }
+                listed.insert(task.id);
+                out.push_str(&format_item(bullet, task));
+            }
+        }
+    }
+    for task in tasks
+        .iter()
+        .filter(|t| t.is_pending() && !listed.contains(&t.id))
+    {
+        out.push_str(&format_item("- ", task));
+    }
+    out
+}
+
+fn id_of(line: &TodoLine) -> Option<u32> {
+    match line {
+        TodoLine::Item { id, .. } => *id,
+        TodoLine::Text(_) => None,
+    }
+}
+
+fn format_item(bullet: &str, task: &Task) -> String {
+    format!(
+        "{}[{}] {} {}{} {}\n",
+        bullet,
+        if task.is_done() { 'x' } else { ' ' },
+        task.description,
+        MARKER_PREFIX,
+        task.id,
+        MARKER_SUFFIX
+    )
+}
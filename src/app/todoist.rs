@@ -0,0 +1,543 @@
+//! Importing from and exporting to Todoist through its REST API.
+//!
+//! `import --from todoist` copies the open tasks of the account into the task
+//! list, and `export --format todoist` creates or updates them in Todoist.
+//! Which task is which is remembered in a state file next to the data file,
+//! so both can be run again and again to mirror the list without duplicates.
+//!
+//! Projects are matched by name, with Todoist's inbox standing for no
+//! project. Todoist's priorities p1 to p3 become high, medium, and low, labels
+//! become tags, and due dates keep only the date. A Todoist task description
+//! becomes a note on import. Tasks no longer open in Todoist are marked as
+//! completed on import, and tasks completed here are closed on export.
+//!
+//! The API token is read from `TODOIST_API_TOKEN`, or from `token` under
+//! `[todoist]` in the config file. Talking to Todoist is only available when
+//! the crate is built with the `todoist` feature.
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app::{AppError, ImportReport, Result, Task, encryption};
+
+/// The environment variable holding the Todoist API token.
+pub const TOKEN_ENV_VAR: &str = "TODOIST_API_TOKEN";
+
+/// How to reach Todoist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TodoistConfig {
+    /// The API token, found under Settings > Integrations > Developer.
+    pub token: Option<String>,
+    /// The API to talk to; defaults to Todoist's own.
+    pub api_url: Option<String>,
+}
+
+impl TodoistConfig {
+    /// Returns the token from `TODOIST_API_TOKEN`, or else from the config.
+    pub fn token(&self) -> Result<String> {
+        env::var(TOKEN_ENV_VAR)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .or_else(|| self.token.clone())
+            .ok_or_else(|| {
+                AppError::Todoist(format!(
+                    "no API token; set {} or `token` under [todoist] in the config file",
+                    TOKEN_ENV_VAR
+                ))
+            })
+    }
+}
+
+/// A task linked to its counterpart in Todoist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LinkedTask {
+    uuid: Uuid,
+    /// The Todoist task ID.
+    id: String,
+    /// The Todoist task is known to be closed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    closed: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TodoistState {
+    #[serde(default)]
+    items: Vec<LinkedTask>,
+}
+
+/// Returns where the Todoist links of the data file at `tasks_path` are kept.
+pub fn todoist_state_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.todoist.toml", stem))
+}
+
+fn load_state(tasks_path: &Path) -> Result<TodoistState> {
+    let path = todoist_state_path(tasks_path);
+    if !path.exists() {
+        return Ok(TodoistState::default());
+    }
+    Ok(toml::from_str(&encryption::read_file(&path)?)?)
+}
+
+fn save_state(state: &TodoistState, tasks_path: &Path) -> Result<()> {
+    encryption::write_file(
+        &todoist_state_path(tasks_path),
+        &toml::to_string(state)?,
+        encryption::should_encrypt(tasks_path),
+    )
+}
+
+/// Imports the open Todoist tasks into `tasks`.
+///
+/// Tasks imported before are updated in place rather than added again.
+pub fn import_from_todoist(
+    tasks: &mut Vec<Task>,
+    tasks_path: &Path,
+    config: &TodoistConfig,
+) -> Result<ImportReport> {
+    let mut state = load_state(tasks_path)?;
+    let report = api::import(tasks, &mut state, config)?;
+    state
+        .items
+        .retain(|link| tasks.iter().any(|t| t.uuid == link.uuid));
+    save_state(&state, tasks_path)?;
+    Ok(report)
+}
+
+/// Creates or updates `tasks` in Todoist.
+///
+/// Pending tasks not in Todoist yet are created, ones exported or imported
+/// before are updated, and those completed or cancelled since are closed.
+pub fn export_to_todoist(
+    tasks: &[Task],
+    tasks_path: &Path,
+    config: &TodoistConfig,
+) -> Result<ImportReport> {
+    let mut state = load_state(tasks_path)?;
+    let report = api::export(tasks, &mut state, config)?;
+    save_state(&state, tasks_path)?;
+    Ok(report)
+}
+
+#[cfg(feature = "todoist")]
+mod api {
+    use chrono::NaiveDate;
+    use log::{debug, info};
+    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+    use std::collections::{HashMap, HashSet};
+    use ureq::Agent;
+    use ureq::http::Response;
+
+    use super::{LinkedTask, TodoistConfig, TodoistState};
+    use crate::app::{AppError, ImportReport, Priority, Result, Task};
+
+    const DEFAULT_API_URL: &str = "https://api.todoist.com/api/v1";
+
+    /// One page of a listing; `next_cursor` fetches the next.
+    #[derive(Deserialize)]
+    struct Page<T> {
+        results: Vec<T>,
+        next_cursor: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Project {
+        id: String,
+        name: String,
+        #[serde(default)]
+        inbox_project: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct Due {
+        /// `YYYY-MM-DD`, followed by a time for tasks due at a specific time.
+        date: String,
+    }
+
+    #[derive(Deserialize)]
+    struct RemoteTask {
+        id: String,
+        content: String,
+        #[serde(default)]
+        description: String,
+        project_id: String,
+        /// From 1 (normal) to 4 (urgent, shown as p1).
+        priority: u8,
+        due: Option<Due>,
+        #[serde(default)]
+        labels: Vec<String>,
+    }
+
+    impl RemoteTask {
+        fn due_date(&self) -> Option<NaiveDate> {
+            let date = self.due.as_ref()?.date.get(..10)?;
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+        }
+
+        fn task_priority(&self) -> Option<Priority> {
+            match self.priority {
+                4 => Some(Priority::High),
+                3 => Some(Priority::Medium),
+                2 => Some(Priority::Low),
+                _ => None,
+            }
+        }
+
+        /// Copies the fields onto `task`, returning `true` if any changed.
+        fn apply(&self, project: Option<&String>, task: &mut Task) -> bool {
+            let before = (
+                task.description.clone(),
+                task.project.clone(),
+                task.priority,
+                task.due_date,
+                task.tags.clone(),
+            );
+            task.description = self.content.clone();
+            task.project = project.cloned();
+            task.priority = self.task_priority();
+            task.set_due_date(self.due_date());
+            task.tags = self.labels.clone();
+            before
+                != (
+                    task.description.clone(),
+                    task.project.clone(),
+                    task.priority,
+                    task.due_date,
+                    task.tags.clone(),
+                )
+        }
+
+        /// Returns `true` if `task` says the same as this task, ignoring the project.
+        fn matches(&self, task: &Task) -> bool {
+            self.content == task.description
+                && self.task_priority() == task.priority
+                && self.due_date() == task.due_date
+                && self.labels == task.tags
+        }
+    }
+
+    fn todoist_priority(priority: Option<Priority>) -> u8 {
+        match priority {
+            Some(Priority::High) => 4,
+            Some(Priority::Medium) => 3,
+            Some(Priority::Low) => 2,
+            None => 1,
+        }
+    }
+
+    struct Client {
+        agent: Agent,
+        base: String,
+        auth: String,
+    }
+
+    impl Client {
+        fn new(config: &TodoistConfig) -> Result<Self> {
+            let auth = format!("Bearer {}", config.token()?);
+            let agent = Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .new_agent();
+            let base = config
+                .api_url
+                .as_deref()
+                .unwrap_or(DEFAULT_API_URL)
+                .trim_end_matches('/')
+                .to_string();
+            Ok(Client { agent, base, auth })
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("{}/{}", self.base, path)
+        }
+
+        /// Turns transport errors and unsuccessful statuses into errors.
+        ///
+        /// A 404 is returned as `None`.
+        fn check(
+            url: &str,
+            response: std::result::Result<Response<ureq::Body>, ureq::Error>,
+        ) -> Result<Option<Response<ureq::Body>>> {
+            let response = response.map_err(|e| AppError::Todoist(format!("{}: {}", url, e)))?;
+            match response.status().as_u16() {
+                200..=299 => Ok(Some(response)),
+                404 => Ok(None),
+                401 | 403 => Err(AppError::Todoist(
+                    "the API token was rejected; check it in Todoist's settings".to_string(),
+                )),
+                status => Err(AppError::Todoist(format!(
+                    "{} returned HTTP {}",
+                    url, status
+                ))),
+            }
+        }
+
+        fn read<T: DeserializeOwned>(url: &str, mut response: Response<ureq::Body>) -> Result<T> {
+            response
+                .body_mut()
+                .read_json()
+                .map_err(|e| AppError::Todoist(format!("{}: unexpected response: {}", url, e)))
+        }
+
+        /// Fetches every page of the listing at `path`.
+        fn list<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>> {
+            let url = self.url(path);
+            let mut items = Vec::new();
+            let mut cursor: Option<String> = None;
+            loop {
+                let mut request = self.agent.get(&url).header("Authorization", &self.auth);
+                if let Some(cursor) = &cursor {
+                    request = request.query("cursor", cursor);
+                }
+                let response = Self::check(&url, request.call())?
+                    .ok_or_else(|| AppError::Todoist(format!("{} returned HTTP 404", url)))?;
+                let page: Page<T> = Self::read(&url, response)?;
+                items.extend(page.results);
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => return Ok(items),
+                }
+            }
+        }
+
+        /// Posts `body` to `path`, returning the response unless it was a 404.
+        fn post(&self, path: &str, body: &Value) -> Result<Option<Response<ureq::Body>>> {
+            let url = self.url(path);
+            let response = self
+                .agent
+                .post(&url)
+                .header("Authorization", &self.auth)
+                .send_json(body);
+            Self::check(&url, response)
+        }
+
+        fn create<T: DeserializeOwned>(&self, path: &str, body: &Value) -> Result<T> {
+            let url = self.url(path);
+            let response = self
+                .post(path, body)?
+                .ok_or_else(|| AppError::Todoist(format!("{} returned HTTP 404", url)))?;
+            Self::read(&url, response)
+        }
+    }
+
+    /// The fields of a Todoist task describing `task`, except its project.
+    fn task_body(task: &Task) -> Value {
+        let mut body = json!({
+            "content": task.description,
+            "priority": todoist_priority(task.priority),
+            "labels": task.tags,
+        });
+        match task.due_date {
+            Some(due) => body["due_date"] = json!(due.format("%Y-%m-%d").to_string()),
+            None => body["due_string"] = json!("no date"),
+        }
+        body
+    }
+
+    pub(super) fn import(
+        tasks: &mut Vec<Task>,
+        state: &mut TodoistState,
+        config: &TodoistConfig,
+    ) -> Result<ImportReport> {
+        let client = Client::new(config)?;
+        let projects: HashMap<String, String> = client
+            .list::<Project>("projects")?
+            .into_iter()
+            .filter(|p| !p.inbox_project)
+            .map(|p| (p.id, p.name))
+            .collect();
+        let remote: Vec<RemoteTask> = client.list("tasks")?;
+        debug!("Fetched {} open tasks from Todoist.", remote.len());
+
+        let mut report = ImportReport::default();
+        let mut next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        let open: HashSet<&str> = remote.iter().map(|t| t.id.as_str()).collect();
+        for link in &mut state.items {
+            if open.contains(link.id.as_str()) {
+                link.closed = false;
+            } else if let Some(task) = tasks.iter_mut().find(|t| t.uuid == link.uuid) {
+                link.closed = true;
+                if task.is_pending() {
+                    task.mark_completion(true);
+                    report.completed += 1;
+                }
+            }
+        }
+
+        for item in &remote {
+            let project = projects.get(&item.project_id);
+            let linked = state
+                .items
+                .iter()
+                .find(|link| link.id == item.id)
+                .map(|link| link.uuid);
+            match linked.and_then(|uuid| tasks.iter_mut().find(|t| t.uuid == uuid)) {
+                Some(task) => {
+                    if item.apply(project, task) {
+                        report.updated += 1;
+                    }
+                }
+                None => {
+                    let mut task = Task::new(next_id, String::new(), None);
+                    next_id += 1;
+                    item.apply(project, &mut task);
+                    if !item.description.is_empty() {
+                        task.add_note(item.description.clone());
+                    }
+                    state.items.retain(|link| link.id != item.id);
+                    state.items.push(LinkedTask {
+                        uuid: task.uuid,
+                        id: item.id.clone(),
+                        closed: false,
+                    });
+                    tasks.push(task);
+                    report.added += 1;
+                }
+            }
+        }
+        info!(
+            "Todoist import: {} added, {} updated, {} completed.",
+            report.added, report.updated, report.completed
+        );
+        Ok(report)
+    }
+
+    pub(super) fn export(
+        tasks: &[Task],
+        state: &mut TodoistState,
+        config: &TodoistConfig,
+    ) -> Result<ImportReport> {
+        let client = Client::new(config)?;
+        let mut projects: HashMap<String, String> = HashMap::new();
+        let mut inbox = None;
+        for project in client.list::<Project>("projects")? {
+            if project.inbox_project {
+                inbox = Some(project.id);
+            } else {
+                projects.insert(project.name, project.id);
+            }
+        }
+        let remote: HashMap<String, RemoteTask> = client
+            .list::<RemoteTask>("tasks")?
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+
+        let mut report = ImportReport::default();
+        for task in tasks {
+            let link = state.items.iter_mut().find(|link| link.uuid == task.uuid);
+            if !task.is_pending() {
+                if let Some(link) = link.filter(|link| !link.closed) {
+                    // Already gone from Todoist if it's not open there.
+                    if remote.contains_key(&link.id) {
+                        client.post(&format!("tasks/{}/close", link.id), &json!({}))?;
+                        report.completed += 1;
+                    }
+                    link.closed = true;
+                }
+                continue;
+            }
+
+            let project_id = match &task.project {
+                Some(name) => match projects.get(name) {
+                    Some(id) => Some(id.clone()),
+                    None => {
+                        let project: Project =
+                            client.create("projects", &json!({ "name": name }))?;
+                        info!("Created Todoist project '{}'.", name);
+                        projects.insert(name.clone(), project.id.clone());
+                        Some(project.id)
+                    }
+                },
+                None => inbox.clone(),
+            };
+
+            match link {
+                Some(link) => {
+                    let Some(current) = remote.get(&link.id) else {
+                        debug!(
+                            "Task {} is closed or deleted in Todoist; not exporting it.",
+                            task.id
+                        );
+                        continue;
+                    };
+                    let mut changed = false;
+                    if !current.matches(task) {
+                        client.post(&format!("tasks/{}", link.id), &task_body(task))?;
+                        changed = true;
+                    }
+                    if let Some(project_id) = project_id.filter(|id| *id != current.project_id) {
+                        client.post(
+                            &format!("tasks/{}/move", link.id),
+                            &json!({ "project_id": project_id }),
+                        )?;
+                        changed = true;
+                    }
+                    if changed {
+                        report.updated += 1;
+                    }
+                }
+                None => {
+                    let mut body = task_body(task);
+                    if task.due_date.is_none()
+                        && let Some(fields) = body.as_object_mut()
+                    {
+                        fields.remove("due_string");
+                    }
+                    if let Some(project_id) = project_id {
+                        body["project_id"] = json!(project_id);
+                    }
+                    let created: RemoteTask = client.create("tasks", &body)?;
+                    state.items.push(LinkedTask {
+                        uuid: task.uuid,
+                        id: created.id,
+                        closed: false,
+                    });
+                    report.added += 1;
+                }
+            }
+        }
+        info!(
+            "Todoist export: {} added, {} updated, {} closed.",
+            report.added, report.updated, report.completed
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(not(feature = "todoist"))]
+mod api {
+    use super::{TodoistConfig, TodoistState};
+    use crate::app::{AppError, ImportReport, Result, Task};
+
+    fn unavailable() -> AppError {
+        AppError::Todoist(
+            "this build does not include Todoist support; rebuild with `--features todoist`"
+                .to_string(),
+        )
+    }
+
+    pub(super) fn import(
+        _tasks: &mut Vec<Task>,
+        _state: &mut TodoistState,
+        _config: &TodoistConfig,
+    ) -> Result<ImportReport> {
+        Err(unavailable())
+    }
+
+    pub(super) fn export(
+        _tasks: &[Task],
+        _state: &mut TodoistState,
+        _config: &TodoistConfig,
+    ) -> Result<ImportReport> {
+        Err(unavailable())
+    }
+}
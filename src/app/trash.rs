@@ -0,0 +1,110 @@
+//! Soft-deleted tasks, kept for a while so they can be restored.
+//!
+//! Removed tasks move to a trash file next to the data file
+//! (`tasks.toml` → `tasks.trash.toml`). Entries older than the configured
+//! retention are purged on startup.
+
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app::{AppError, Result, Task, encryption};
+
+/// How long trashed tasks are kept by default, in days.
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// A removed task and when it was removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedTask {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub deleted_at: DateTime<Utc>,
+    pub task: Task,
+}
+
+/// The on-disk layout of the trash file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrashFile {
+    #[serde(default)]
+    trash: Vec<TrashedTask>,
+}
+
+/// Returns the trash file belonging to the data file at `tasks_path`.
+pub fn trash_file_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tasks".to_string());
+    tasks_path.with_file_name(format!("{}.trash.toml", stem))
+}
+
+/// Loads the trash at `path`; a missing file is an empty trash.
+pub fn load_trash(path: &Path) -> Result<Vec<TrashedTask>> {
+    if !path.exists() {
+        debug!("No trash file at {}.", path.display());
+        return Ok(Vec::new());
+    }
+    let contents = encryption::read_file(path)?;
+    let file: TrashFile = toml::from_str(&contents).map_err(|e| AppError::from(e).in_file(path))?;
+    Ok(file.trash)
+}
+
+/// Takes the tasks with the given UUIDs out of the trash at `path` again,
+/// for removals that were undone before being saved.
+pub fn take_back_from_trash(path: &Path, uuids: &HashSet<Uuid>) -> Result<()> {
+    let mut trash = load_trash(path)?;
+    let before = trash.len();
+    trash.retain(|entry| !uuids.contains(&entry.task.uuid));
+    if trash.len() != before {
+        save_trash(&trash, path)?;
+    }
+    Ok(())
+}
+
+/// Saves the trash to `path`, removing the file once the trash is empty.
+///
+/// An existing file keeps its form; see [`encryption::should_encrypt`].
+pub fn save_trash(trash: &[TrashedTask], path: &Path) -> Result<()> {
+    write_trash(trash, path, encryption::should_encrypt(path))
+}
+
+/// Saves the trash to `path`, encrypted if `encrypted` is set.
+pub fn write_trash(trash: &[TrashedTask], path: &Path, encrypted: bool) -> Result<()> {
+    if trash.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    let file = TrashFile {
+        trash: trash.to_vec(),
+    };
+    encryption::write_file(path, &toml::to_string(&file)?, encrypted)?;
+    info!("Saved {} trashed tasks to {}.", trash.len(), path.display());
+    Ok(())
+}
+
+/// Moves `tasks` into the trash file at `path`, stamped with `now`.
+pub fn move_to_trash(tasks: Vec<Task>, path: &Path, now: DateTime<Utc>) -> Result<()> {
+    let mut trash = load_trash(path)?;
+    trash.extend(tasks.into_iter().map(|task| TrashedTask {
+        deleted_at: now,
+        task,
+    }));
+    save_trash(&trash, path)
+}
+
+/// Drops entries removed more than `retention_days` before `now`.
+///
+/// # Returns
+///
+/// The number of entries purged.
+pub fn purge_trash(trash: &mut Vec<TrashedTask>, now: DateTime<Utc>, retention_days: u32) -> usize {
+    let cutoff = now - Duration::days(retention_days.into());
+    let before = trash.len();
+    trash.retain(|entry| entry.deleted_at > cutoff);
+    before - trash.len()
+}
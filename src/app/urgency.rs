@@ -0,0 +1,145 @@
+//! Urgency scores, ranking the pending tasks `next` suggests working on and
+//! weighting the draw of `random`.
+//!
+//! As in Taskwarrior, a task's urgency is a sum of weighted terms: how close
+//! its deadline is, its priority, its age, and its status. The weights are
+//! read from `[urgency]` in the config file; a weight of 0 turns a term off.
+//!
+//! ```toml
+//! [urgency]
+//! due = 12.0
+//! priority_high = 6.0
+//! priority_medium = 3.9
+//! priority_low = 1.8
+//! age = 2.0
+//! in_progress = 4.0
+//! blocked = -5.0
+//! ```
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use crate::app::{Priority, Task, TaskStatus};
+
+/// How many days old a task must be for the age term to reach its full weight.
+const MAX_AGE_DAYS: f64 = 365.0;
+
+/// The weights of the terms of the urgency score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrgencyConfig {
+    /// Given in full a week after the deadline, falling to a fifth of it two
+    /// weeks before; tasks without a due date get nothing.
+    pub due: f64,
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    /// Given in full to tasks a year old or older, in part to younger ones.
+    pub age: f64,
+    /// Given to tasks in progress, to finish what was started.
+    pub in_progress: f64,
+    /// Given to blocked tasks; negative to push them down.
+    pub blocked: f64,
+}
+
+impl Default for UrgencyConfig {
+    fn default() -> Self {
+        UrgencyConfig {
+            due: 12.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            age: 2.0,
+            in_progress: 4.0,
+            blocked: -5.0,
+        }
+    }
+}
+
+impl UrgencyConfig {
+    /// Returns the urgency of `task` at `now`.
+    pub fn score(&self, task: &Task, now: NaiveDateTime) -> f64 {
+        let priority = match task.priority {
+            Some(Priority::High) => self.priority_high,
+            Some(Priority::Medium) => self.priority_medium,
+            Some(Priority::Low) => self.priority_low,
+            None => 0.0,
+        };
+        let status = match task.status {
+            TaskStatus::InProgress => self.in_progress,
+            TaskStatus::Blocked => self.blocked,
+            _ => 0.0,
+        };
+        let age_days = (now - task.created_at.naive_utc()).num_seconds() as f64 / 86_400.0;
+        let age = (age_days / MAX_AGE_DAYS).clamp(0.0, 1.0);
+        self.due * due_factor(task, now) + priority + self.age * age + status
+    }
+
+    /// Returns the pending tasks of `tasks` that have started, most urgent
+    /// first, with their scores. Ties are broken by ID.
+    pub fn rank<'a>(&self, tasks: &'a [Task], now: NaiveDateTime) -> Vec<(&'a Task, f64)> {
+        let mut ranked: Vec<(&Task, f64)> = tasks
+            .iter()
+            .filter(|t| t.is_pending() && !t.is_waiting(now.date()))
+            .map(|t| (t, self.score(t, now)))
+            .collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .partial_cmp(a_score)
+                .unwrap_or(Ordering::Equal)
+                .then(a.id.cmp(&b.id))
+        });
+        ranked
+    }
+
+    /// Picks one of the `tasks` that can be worked on now at random, for
+    /// `random`. The more urgent a task, the likelier it is picked: each is
+    /// weighted by how far its score lies above the lowest one, plus one, so
+    /// the least urgent still has a chance. Blocked tasks, tasks waiting on
+    /// something, and tasks that haven't started are left out.
+    pub fn pick<'a>(
+        &self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+        now: NaiveDateTime,
+    ) -> Option<&'a Task> {
+        let scored: Vec<(&Task, f64)> = tasks
+            .into_iter()
+            .filter(|t| {
+                t.is_pending()
+                    && t.status != TaskStatus::Blocked
+                    && t.waiting_on.is_none()
+                    && !t.is_waiting(now.date())
+            })
+            .map(|t| (t, self.score(t, now)))
+            .collect();
+        let lowest = scored
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f64::INFINITY, f64::min);
+        let weights: Vec<f64> = scored.iter().map(|(_, s)| s - lowest + 1.0).collect();
+        let mut target = fastrand::f64() * weights.iter().sum::<f64>();
+        for ((task, _), weight) in scored.iter().zip(&weights) {
+            if target < *weight {
+                return Some(task);
+            }
+            target -= weight;
+        }
+        scored.last().map(|(task, _)| *task)
+    }
+}
+
+/// Returns how much of the due weight `task` gets at `now`, between 0 and 1.
+fn due_factor(task: &Task, now: NaiveDateTime) -> f64 {
+    let Some(deadline) = task.deadline() else {
+        return 0.0;
+    };
+    let days_overdue = (now - deadline).num_seconds() as f64 / 86_400.0;
+    if days_overdue >= 7.0 {
+        1.0
+    } else if days_overdue >= -14.0 {
+        (days_overdue + 14.0) * 0.8 / 21.0 + 0.2
+    } else {
+        0.2
+    }
+}
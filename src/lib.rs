@@ -2,7 +2,10 @@
 //!
 //! This library provides the core logic and data structures for managing tasks,
 //! including adding, listing, completing, and removing tasks, with data persistence
-//! to a TOML file.
+//! to a TOML file. [`TaskManager`] carries out the operations of the command
+//! line on a loaded task list. Without the default `native` feature the
+//! library builds for wasm32, and the host keeps the data through a
+//! [`TaskStore`].
 
 pub mod app;
 
@@ -0,0 +1,113 @@
+//! Checks how `list` lays out tasks against the golden files in
+//! `tests/golden`. Run with `UPDATE_GOLDEN=1` after changing the layout on
+//! purpose.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use task_manager_command_line::{
+    Config, ListColumn, Priority, RenderFormat, TableStyle, Task, TaskStatus, assert_golden,
+    render_table, task_table,
+};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// The moment the list is drawn at.
+fn now() -> NaiveDateTime {
+    date(2026, 3, 10).and_hms_opt(9, 0, 0).unwrap()
+}
+
+fn golden(name: &str) -> String {
+    format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// A list with something of everything the table shows.
+fn tasks() -> Vec<Task> {
+    let mut overdue = Task::new(1, "File the tax return".to_string(), Some(date(2026, 3, 2)));
+    overdue.priority = Some(Priority::High);
+    overdue.project = Some("home".to_string());
+
+    let mut tonight = Task::new(2, "Call Sam".to_string(), Some(date(2026, 3, 10)));
+    tonight.due_time = NaiveTime::from_hms_opt(18, 30, 0);
+    tonight.status = TaskStatus::InProgress;
+    tonight.tags = vec!["phone".to_string(), "family".to_string()];
+
+    let mut later = Task::new(
+        3,
+        "Write the quarterly report for the board meeting".to_string(),
+        Some(date(2026, 4, 2)),
+    );
+    later.priority = Some(Priority::Medium);
+    later.project = Some("work".to_string());
+    later.progress = Some(40);
+
+    let undated = Task::new(4, "Learn 日本語".to_string(), None);
+
+    let mut done = Task::new(5, "Renew passport".to_string(), Some(date(2026, 3, 11)));
+    done.status = TaskStatus::Done;
+
+    let mut done_late = Task::new(
+        6,
+        "Return the library books".to_string(),
+        Some(date(2026, 2, 20)),
+    );
+    done_late.status = TaskStatus::Done;
+    vec![overdue, tonight, later, undated, done, done_late]
+}
+
+fn render(style: TableStyle, format: RenderFormat) -> String {
+    let tasks = tasks();
+    let tasks: Vec<&Task> = tasks.iter().collect();
+    render_table(
+        &task_table(&tasks, style, now(), &Config::default()),
+        format,
+    )
+}
+
+#[test]
+fn list_shows_due_dates_relative_to_now() {
+    assert_golden(
+        golden("list.txt"),
+        &render(TableStyle::default(), RenderFormat::Table),
+    );
+}
+
+#[test]
+fn list_absolute_shows_the_dates() {
+    let style = TableStyle {
+        absolute: Some(true),
+        ..TableStyle::default()
+    };
+    assert_golden(
+        golden("list-absolute.txt"),
+        &render(style, RenderFormat::Table),
+    );
+}
+
+#[test]
+fn list_columns_picks_what_is_shown() {
+    let columns = [
+        ListColumn::Id,
+        ListColumn::Description,
+        ListColumn::Priority,
+        ListColumn::Project,
+        ListColumn::Tags,
+        ListColumn::Progress,
+    ];
+    let style = TableStyle {
+        columns: &columns,
+        ..TableStyle::default()
+    };
+    assert_golden(
+        golden("list-columns.txt"),
+        &render(style, RenderFormat::Table),
+    );
+}
+
+#[test]
+fn list_as_json_keeps_the_raw_values() {
+    assert_golden(
+        golden("list.json"),
+        &render(TableStyle::default(), RenderFormat::Json),
+    );
+}
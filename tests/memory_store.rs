@@ -0,0 +1,205 @@
+//! Drives [`TaskManager`] against a [`MemoryStore`] the way a host
+//! embedding the crate would: load, change, save, and load again.
+
+use chrono::NaiveDate;
+use task_manager_command_line::{
+    Config, MemoryStore, NewTask, Priority, Recurrence, StorageFormat, Task, TaskChanges,
+    TaskField, TaskManager, TaskSelection, TaskStore, encode_tasks,
+};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Loads the tasks of `store`, runs `change` on them, and saves them back.
+fn update<T>(store: &mut MemoryStore, change: impl FnOnce(&mut TaskManager) -> T) -> T {
+    let mut tasks = store.load().unwrap();
+    let result = change(&mut TaskManager::new(&mut tasks, &Config::default()));
+    store.save(&tasks).unwrap();
+    result
+}
+
+/// A store holding a task for each description.
+fn store_with(descriptions: &[&str]) -> MemoryStore {
+    let mut store = MemoryStore::new();
+    update(&mut store, |manager| {
+        for description in descriptions {
+            manager.add(NewTask::new(*description)).unwrap();
+        }
+    });
+    store
+}
+
+fn descriptions(tasks: &[Task]) -> Vec<&str> {
+    tasks.iter().map(|t| t.description.as_str()).collect()
+}
+
+#[test]
+fn an_empty_store_has_no_tasks() {
+    let store = MemoryStore::new();
+    assert!(store.contents().is_none());
+    assert!(store.load().unwrap().is_empty());
+}
+
+#[test]
+fn added_tasks_are_stored() {
+    let mut store = MemoryStore::new();
+    let id = update(&mut store, |manager| {
+        let new = NewTask {
+            priority: Some(Priority::High),
+            tags: vec!["errand".to_string()],
+            ..NewTask::new("buy milk")
+        };
+        manager.add(new).unwrap().id
+    });
+    let tasks = store.load().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, id);
+    assert_eq!(tasks[0].description, "buy milk");
+    assert_eq!(tasks[0].priority, Some(Priority::High));
+    assert_eq!(tasks[0].tags, ["errand"]);
+}
+
+#[test]
+fn added_tasks_get_new_ids_after_a_reload() {
+    let mut store = store_with(&["one", "two"]);
+    let id = update(&mut store, |manager| {
+        manager.add(NewTask::new("three")).unwrap().id
+    });
+    assert_eq!(id, 3);
+    assert_eq!(
+        descriptions(&store.load().unwrap()),
+        ["one", "two", "three"]
+    );
+}
+
+#[test]
+fn completed_tasks_stay_completed() {
+    let mut store = store_with(&["one", "two"]);
+    let next = update(&mut store, |manager| manager.complete(1).unwrap().is_some());
+    assert!(!next);
+    let tasks = store.load().unwrap();
+    assert!(tasks[0].is_done());
+    assert!(tasks[0].completed_at.is_some());
+    assert!(tasks[1].is_pending());
+}
+
+#[test]
+fn completing_a_series_stores_its_next_occurrence() {
+    let mut store = MemoryStore::new();
+    update(&mut store, |manager| {
+        let new = NewTask {
+            due: Some("2030-01-31".parse().unwrap()),
+            recurrence: Some("monthly".parse::<Recurrence>().unwrap()),
+            ..NewTask::new("pay rent")
+        };
+        manager.add(new).unwrap();
+    });
+    update(&mut store, |manager| {
+        manager.complete(1).unwrap().map(|t| t.id)
+    });
+    let next = update(&mut store, |manager| {
+        manager.complete(2).unwrap().map(|t| t.id)
+    });
+    assert_eq!(next, Some(3));
+    let tasks = store.load().unwrap();
+    let dues: Vec<_> = tasks.iter().map(|t| t.due_date).collect();
+    assert_eq!(
+        dues,
+        [
+            Some(date(2030, 1, 31)),
+            Some(date(2030, 2, 28)),
+            Some(date(2030, 3, 31))
+        ]
+    );
+}
+
+#[test]
+fn removed_tasks_are_gone() {
+    let mut store = store_with(&["one", "two", "three"]);
+    let removed = update(&mut store, |manager| manager.remove(2).unwrap());
+    assert_eq!(removed.description, "two");
+    assert_eq!(descriptions(&store.load().unwrap()), ["one", "three"]);
+    let missing = update(&mut store, |manager| manager.remove(2).is_err());
+    assert!(missing);
+}
+
+#[test]
+fn edits_are_stored() {
+    let mut store = store_with(&["one"]);
+    let changed = update(&mut store, |manager| {
+        let changes = TaskChanges {
+            description: Some("the first".to_string()),
+            project: Some(Some("home".to_string())),
+            due: Some(Some("2030-06-01".parse().unwrap())),
+            ..TaskChanges::default()
+        };
+        manager.edit(1, changes).unwrap()
+    });
+    assert_eq!(
+        changed,
+        [TaskField::Description, TaskField::Due, TaskField::Project]
+    );
+    let task = &store.load().unwrap()[0];
+    assert_eq!(task.description, "the first");
+    assert_eq!(task.project.as_deref(), Some("home"));
+    assert_eq!(task.due_date, Some(date(2030, 6, 1)));
+
+    update(&mut store, |manager| {
+        let clear = TaskChanges {
+            project: Some(None),
+            ..TaskChanges::default()
+        };
+        manager.edit(1, clear).unwrap()
+    });
+    assert_eq!(store.load().unwrap()[0].project, None);
+}
+
+#[test]
+fn list_selects_from_the_stored_tasks() {
+    let mut store = store_with(&["one", "two", "three", "four"]);
+    update(&mut store, |manager| {
+        manager.complete(2).unwrap();
+        let work = TaskChanges {
+            project: Some(Some("work".to_string())),
+            ..TaskChanges::default()
+        };
+        manager.edit(3, work.clone()).unwrap();
+        manager.edit(4, work).unwrap();
+    });
+
+    let mut tasks = store.load().unwrap();
+    let manager = TaskManager::new(&mut tasks, &Config::default());
+    let listed = |selection: &TaskSelection| -> Vec<u32> {
+        let page = manager.list(selection).unwrap();
+        page.tasks.iter().map(|t| t.id).collect()
+    };
+    assert_eq!(listed(&TaskSelection::default()), [1, 3, 4]);
+    let all = TaskSelection {
+        all: true,
+        ..TaskSelection::default()
+    };
+    assert_eq!(listed(&all), [1, 2, 3, 4]);
+    let work = TaskSelection {
+        project: Some("work".to_string()),
+        limit: Some(1),
+        ..TaskSelection::default()
+    };
+    assert_eq!(listed(&work), [3]);
+    assert_eq!(manager.list(&work).unwrap().matching, 2);
+}
+
+#[test]
+fn saving_keeps_the_stored_format() {
+    let tasks = vec![Task::new(1, "one".to_string(), None)];
+    let mut store = MemoryStore::new();
+    store
+        .write(&encode_tasks(&tasks, StorageFormat::Msgpack).unwrap())
+        .unwrap();
+    update(&mut store, |manager| {
+        manager.add(NewTask::new("two")).unwrap();
+    });
+    let contents = store.contents().unwrap();
+    assert_eq!(StorageFormat::of(contents), StorageFormat::Msgpack);
+    assert_eq!(descriptions(&store.load().unwrap()), ["one", "two"]);
+}
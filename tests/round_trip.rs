@@ -0,0 +1,35 @@
+//! Checks that generated task lists survive every storage encoding.
+
+use proptest::prelude::*;
+use task_manager_command_line::{Encoding, Task, arb_tasks, assert_round_trips, round_trip};
+
+/// Stores `tasks` in `encoding`, reads them back, and compares them field
+/// by field.
+fn check(tasks: &[Task], encoding: Encoding) {
+    let back = round_trip(tasks, encoding).unwrap();
+    let before = serde_json::to_value(tasks).unwrap();
+    let after = serde_json::to_value(&back).unwrap();
+    assert_eq!(after, before);
+}
+
+proptest! {
+    #[test]
+    fn toml_keeps_every_task(tasks in arb_tasks(20)) {
+        check(&tasks, Encoding::Toml);
+    }
+
+    #[test]
+    fn json_keeps_every_task(tasks in arb_tasks(20)) {
+        check(&tasks, Encoding::Json);
+    }
+
+    #[test]
+    fn msgpack_keeps_every_task(tasks in arb_tasks(20)) {
+        check(&tasks, Encoding::Msgpack);
+    }
+
+    #[test]
+    fn every_encoding_keeps_every_task(tasks in arb_tasks(20)) {
+        assert_round_trips(&tasks);
+    }
+}
@@ -0,0 +1,68 @@
+//! Runs `triage` on a data file of its own, answering the prompts on stdin.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use task_manager_command_line::{load_trash, parse_tasks, trash_file_path};
+use uuid::Uuid;
+
+/// A directory holding the data and config files of one test.
+fn scratch_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!("tm-triage-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs `tm` with `args` on the data file at `tasks_path`, feeding `input`
+/// to stdin.
+fn tm(tasks_path: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tm"))
+        .args(args)
+        .env("TASK_FILE", tasks_path)
+        .env("TASK_CONFIG", tasks_path.with_file_name("config.toml"))
+        .env("TASK_LANG", "en")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "tm {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn deleting_during_triage_moves_the_task_to_the_trash() {
+    let dir = scratch_dir();
+    let tasks_path = dir.join("tasks.toml");
+    tm(&tasks_path, &["add", "Sort the receipts"], "");
+    tm(&tasks_path, &["add", "Book the dentist"], "");
+
+    let output = tm(&tasks_path, &["triage"], "d\nhealth\n\n\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("moved to the trash"), "{}", stdout);
+
+    let tasks = parse_tasks(&fs::read(&tasks_path).unwrap()).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].description, "Book the dentist");
+    assert_eq!(tasks[0].project.as_deref(), Some("health"));
+
+    let trash = load_trash(&trash_file_path(&tasks_path)).unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].task.description, "Sort the receipts");
+
+    fs::remove_dir_all(dir).unwrap();
+}